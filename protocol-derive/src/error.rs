@@ -2,6 +2,7 @@ use syn::Error as SynError;
 
 /// Possible errors while deriving.
 #[derive(Debug)]
+#[allow(dead_code)]
 pub(crate) enum DeriveInputParserError {
     /// Derive attribute must be placed on a structure or enum.
     UnsupportedData,
@@ -9,10 +10,15 @@ pub(crate) enum DeriveInputParserError {
     UnnamedDataFields,
     /// Possible errors while parsing attributes.
     AttributeError { attribute_error: AttributeError },
+    /// Variant discriminant must be a non-negative integer literal.
+    UnsupportedDiscriminant,
+    /// Variant discriminant doesn't fit into discriminant type, e.g. 300 is written as u8.
+    DiscriminantOutOfRange { discriminant: u64, max: u64 },
 }
 
 /// Possible errors while parsing attributes.
 #[derive(Debug)]
+#[allow(dead_code)]
 pub(crate) enum AttributeError {
     /// Failed to parse field meta due incorrect syntax.
     BadAttributeSyntax { syn_error: SynError },
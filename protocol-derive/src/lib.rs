@@ -1,8 +1,13 @@
 extern crate proc_macro;
 
-use crate::parse::{parse_derive_input, DeriveInputParseResult};
+use crate::parse::{parse_derive_input, DeriveInputParseResult, MessageInput};
+use crate::render::builder::render_struct_builder;
 use crate::render::decoder::{render_enum_decoder, render_struct_decoder};
 use crate::render::encoder::{render_enum_encoder, render_struct_encoder};
+use crate::render::msg::render_msg;
+use crate::render::packet_variants::render_packet_variants;
+use crate::render::validate::render_validate;
+use crate::render::visit_messages::render_visit_messages;
 use proc_macro::TokenStream;
 use syn::parse_macro_input;
 use syn::DeriveInput;
@@ -17,12 +22,17 @@ pub fn derive_encoder(tokens: TokenStream) -> TokenStream {
     let derive_parse_result = parse_derive_input(&input).expect("Failed to parse derive input");
 
     TokenStream::from(match derive_parse_result {
-        DeriveInputParseResult::Struct { name, fields } => render_struct_encoder(name, &fields),
+        DeriveInputParseResult::Struct {
+            name,
+            generics,
+            fields,
+        } => render_struct_encoder(name, generics, &fields),
         DeriveInputParseResult::Enum {
             name,
+            generics,
             discriminant_type,
             variants,
-        } => render_enum_encoder(name, &discriminant_type, &variants),
+        } => render_enum_encoder(name, generics, &discriminant_type, &variants),
     })
 }
 
@@ -32,11 +42,67 @@ pub fn derive_decoder(tokens: TokenStream) -> TokenStream {
     let derive_parse_result = parse_derive_input(&input).expect("Failed to parse derive input");
 
     TokenStream::from(match derive_parse_result {
-        DeriveInputParseResult::Struct { name, fields } => render_struct_decoder(name, &fields),
+        DeriveInputParseResult::Struct {
+            name,
+            generics,
+            fields,
+        } => render_struct_decoder(name, generics, &fields),
         DeriveInputParseResult::Enum {
             name,
+            generics,
             discriminant_type,
             variants,
-        } => render_enum_decoder(name, &discriminant_type, &variants),
+        } => render_enum_decoder(name, generics, &discriminant_type, &variants),
     })
 }
+
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive_builder(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+    let derive_parse_result = parse_derive_input(&input).expect("Failed to parse derive input");
+
+    TokenStream::from(match derive_parse_result {
+        DeriveInputParseResult::Struct {
+            name,
+            generics,
+            fields,
+        } => {
+            if !generics.params.is_empty() {
+                panic!("Builder can't be derived for generic structs");
+            }
+
+            render_struct_builder(name, &fields)
+        }
+        DeriveInputParseResult::Enum { .. } => panic!("Builder can be derived only for structs"),
+    })
+}
+
+#[proc_macro_derive(VisitMessages, attributes(visit))]
+pub fn derive_visit_messages(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+
+    TokenStream::from(render_visit_messages(&input))
+}
+
+#[proc_macro_derive(PacketVariants)]
+pub fn derive_packet_variants(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+
+    TokenStream::from(render_packet_variants(&input))
+}
+
+#[proc_macro_derive(Validate, attributes(data_type, validate))]
+pub fn derive_validate(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+
+    TokenStream::from(render_validate(&input))
+}
+
+/// Builds chat message from format string with legacy formatting codes, see
+/// `minecraft_protocol::msg`.
+#[proc_macro]
+pub fn msg(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as MessageInput);
+
+    TokenStream::from(render_msg(&input).unwrap_or_else(|e| e.to_compile_error()))
+}
@@ -1,9 +1,11 @@
 use crate::error::{AttributeError, DeriveInputParserError};
 use proc_macro2::Ident;
 use std::iter::FromIterator;
+use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::{
-    Attribute, Data, DeriveInput, ExprLit, Field, Fields, FieldsNamed, Lit, Meta, NestedMeta, Type,
+    Attribute, Data, DeriveInput, ExprLit, Field, Fields, FieldsNamed, Generics, Lit, LitStr, Meta,
+    NestedMeta, Type,
 };
 use syn::{Error as SynError, Variant};
 use syn::{Expr, Token};
@@ -11,17 +13,20 @@ use syn::{Expr, Token};
 pub(crate) enum DeriveInputParseResult<'a> {
     Struct {
         name: &'a Ident,
+        generics: &'a Generics,
         fields: Vec<FieldData<'a>>,
     },
     Enum {
         name: &'a Ident,
+        generics: &'a Generics,
         discriminant_type: DiscriminantType,
         variants: Vec<VariantData<'a>>,
     },
 }
 
 pub(crate) struct VariantData<'a> {
-    pub(crate) discriminant: usize,
+    /// Discriminant is checked to fit into discriminant type, width doesn't depend on target.
+    pub(crate) discriminant: u64,
     pub(crate) name: &'a Ident,
     pub(crate) fields: Vec<FieldData<'a>>,
 }
@@ -30,6 +35,8 @@ pub(crate) struct FieldData<'a> {
     pub(crate) name: &'a Ident,
     pub(crate) ty: &'a Type,
     pub(crate) attribute: AttributeData,
+    /// Builder uses default value if field is not set.
+    pub(crate) builder_default: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -40,10 +47,33 @@ pub(crate) enum AttributeData {
     Empty,
 }
 
+/// Field invariants checked by derived `Validate`.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub(crate) struct ValidateData {
+    /// Max string length in UTF-16 code units, taken from `data_type(max_length)` unless set.
+    pub(crate) max_length: Option<usize>,
+    pub(crate) min: Option<i64>,
+    pub(crate) max: Option<i64>,
+    pub(crate) max_items: Option<usize>,
+    /// Field implements `Validate` itself.
+    pub(crate) nested: bool,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub(crate) enum DiscriminantType {
     UnsignedByte,
     VarInt,
+    Int,
+}
+
+impl DiscriminantType {
+    /// Returns max discriminant which can be written.
+    fn max(&self) -> u64 {
+        match self {
+            DiscriminantType::UnsignedByte => u8::MAX as u64,
+            DiscriminantType::VarInt | DiscriminantType::Int => i32::MAX as u64,
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -53,26 +83,42 @@ pub(crate) enum BitfieldPosition {
     End,
 }
 
+type AttributeParser = fn(&NestedMeta) -> Result<AttributeData, AttributeError>;
+
 pub(crate) fn parse_derive_input(
     input: &DeriveInput,
-) -> Result<DeriveInputParseResult, DeriveInputParserError> {
+) -> Result<DeriveInputParseResult<'_>, DeriveInputParserError> {
     let name = &input.ident;
+    let generics = &input.generics;
 
     match &input.data {
         Data::Struct(data_struct) => match &data_struct.fields {
             Fields::Named(named_fields) => {
                 let fields = parse_fields(named_fields)?;
 
-                Ok(DeriveInputParseResult::Struct { name, fields })
+                Ok(DeriveInputParseResult::Struct {
+                    name,
+                    generics,
+                    fields,
+                })
             }
             _ => Err(DeriveInputParserError::UnnamedDataFields),
         },
         Data::Enum(data_enum) => {
             let variants = parse_variants(&data_enum.variants)?;
             let discriminant_type = parse_discriminant_type(&input.attrs)?;
+            let max = discriminant_type.max();
+
+            if let Some(variant) = variants.iter().find(|v| v.discriminant > max) {
+                return Err(DeriveInputParserError::DiscriminantOutOfRange {
+                    discriminant: variant.discriminant,
+                    max,
+                });
+            }
 
             Ok(DeriveInputParseResult::Enum {
                 name,
+                generics,
                 discriminant_type,
                 variants,
             })
@@ -82,20 +128,22 @@ pub(crate) fn parse_derive_input(
 }
 
 fn parse_discriminant_type(
-    attributes: &Vec<Attribute>,
+    attributes: &[Attribute],
 ) -> Result<DiscriminantType, DeriveInputParserError> {
     let nested_metas = parse_attributes_nested_metas(attributes)?;
     let attribute = parse_attribute(nested_metas, None, 0)?;
 
     match attribute {
         AttributeData::With { module } if module == "var_int" => Ok(DiscriminantType::VarInt),
+        AttributeData::With { module } if module == "i32" => Ok(DiscriminantType::Int),
+        AttributeData::With { .. } => Err(AttributeError::UnsupportedAttribute.into()),
         _ => Ok(DiscriminantType::UnsignedByte),
     }
 }
 
 fn parse_variants(
     variants: &Punctuated<Variant, Token![,]>,
-) -> Result<Vec<VariantData>, DeriveInputParserError> {
+) -> Result<Vec<VariantData<'_>>, DeriveInputParserError> {
     variants
         .iter()
         .enumerate()
@@ -103,8 +151,8 @@ fn parse_variants(
         .collect()
 }
 
-fn parse_variant(idx: usize, variant: &Variant) -> Result<VariantData, DeriveInputParserError> {
-    let discriminant = parse_variant_discriminant(variant).unwrap_or(idx);
+fn parse_variant(idx: usize, variant: &Variant) -> Result<VariantData<'_>, DeriveInputParserError> {
+    let discriminant = parse_variant_discriminant(variant)?.unwrap_or(idx as u64);
     let name = &variant.ident;
 
     let fields = match &variant.fields {
@@ -120,20 +168,23 @@ fn parse_variant(idx: usize, variant: &Variant) -> Result<VariantData, DeriveInp
     })
 }
 
-fn parse_variant_discriminant(variant: &Variant) -> Option<usize> {
-    variant
-        .discriminant
-        .as_ref()
-        .and_then(|(_, expr)| match expr {
+fn parse_variant_discriminant(variant: &Variant) -> Result<Option<u64>, DeriveInputParserError> {
+    match &variant.discriminant {
+        Some((_, expr)) => match expr {
             Expr::Lit(ExprLit {
                 lit: Lit::Int(lit_int),
                 ..
-            }) => lit_int.base10_parse().ok(),
-            _ => None,
-        })
+            }) => match lit_int.base10_parse() {
+                Ok(discriminant) => Ok(Some(discriminant)),
+                Err(_) => Err(DeriveInputParserError::UnsupportedDiscriminant),
+            },
+            _ => Err(DeriveInputParserError::UnsupportedDiscriminant),
+        },
+        None => Ok(None),
+    }
 }
 
-fn parse_fields(named_fields: &FieldsNamed) -> Result<Vec<FieldData>, DeriveInputParserError> {
+fn parse_fields(named_fields: &FieldsNamed) -> Result<Vec<FieldData<'_>>, DeriveInputParserError> {
     let mut fields_data = Vec::new();
     let mut current_bitfield_idx = 0;
 
@@ -156,10 +207,13 @@ fn parse_fields(named_fields: &FieldsNamed) -> Result<Vec<FieldData>, DeriveInpu
             _ => current_bitfield_idx = 0,
         }
 
+        let builder_default = parse_builder_default(&field.attrs)?;
+
         fields_data.push(FieldData {
             name,
             ty,
             attribute,
+            builder_default,
         })
     }
 
@@ -167,7 +221,7 @@ fn parse_fields(named_fields: &FieldsNamed) -> Result<Vec<FieldData>, DeriveInpu
 }
 
 fn parse_attributes_nested_metas(
-    attributes: &Vec<Attribute>,
+    attributes: &[Attribute],
 ) -> Result<Vec<NestedMeta>, DeriveInputParserError> {
     let parsed_metas = attributes
         .iter()
@@ -186,12 +240,94 @@ fn parse_attributes_nested_metas(
     Ok(nested_metas.into_iter().flatten().collect())
 }
 
+fn parse_builder_default(attributes: &[Attribute]) -> Result<bool, DeriveInputParserError> {
+    let parsed_metas = attributes
+        .iter()
+        .filter(|a| a.path.is_ident("builder"))
+        .map(|a| a.parse_meta())
+        .collect::<Result<Vec<Meta>, SynError>>()?;
+
+    let mut builder_default = false;
+
+    for meta in parsed_metas {
+        let meta_list = match meta {
+            Meta::List(meta_list) => meta_list,
+            _ => return Err(AttributeError::UnsupportedAttribute.into()),
+        };
+
+        for nested_meta in meta_list.nested.iter() {
+            match nested_meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("default") => {
+                    builder_default = true
+                }
+                _ => return Err(AttributeError::UnsupportedAttribute.into()),
+            }
+        }
+    }
+
+    Ok(builder_default)
+}
+
+pub(crate) fn parse_validate_data(field: &Field) -> Result<ValidateData, DeriveInputParserError> {
+    let mut validate_data = ValidateData::default();
+
+    for nested_meta in parse_attributes_nested_metas(&field.attrs)?.iter() {
+        if let AttributeData::MaxLength { length } = get_max_length_attribute(nested_meta)? {
+            validate_data.max_length = Some(length);
+        }
+    }
+
+    let parsed_metas = field
+        .attrs
+        .iter()
+        .filter(|a| a.path.is_ident("validate"))
+        .map(|a| a.parse_meta())
+        .collect::<Result<Vec<Meta>, SynError>>()?;
+
+    for meta in parsed_metas {
+        let meta_list = match meta {
+            Meta::List(meta_list) => meta_list,
+            _ => return Err(AttributeError::UnsupportedAttribute.into()),
+        };
+
+        for nested_meta in meta_list.nested.iter() {
+            match nested_meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("nested") => {
+                    validate_data.nested = true
+                }
+                NestedMeta::Meta(Meta::NameValue(named_meta)) => {
+                    let lit_int = match &named_meta.lit {
+                        Lit::Int(lit_int) => lit_int,
+                        _ => return Err(AttributeError::AttributeWrongValueType.into()),
+                    };
+                    let path = &named_meta.path;
+
+                    if path.is_ident("max_length") {
+                        validate_data.max_length = Some(lit_int.base10_parse()?);
+                    } else if path.is_ident("min") {
+                        validate_data.min = Some(lit_int.base10_parse()?);
+                    } else if path.is_ident("max") {
+                        validate_data.max = Some(lit_int.base10_parse()?);
+                    } else if path.is_ident("max_items") {
+                        validate_data.max_items = Some(lit_int.base10_parse()?);
+                    } else {
+                        return Err(AttributeError::UnsupportedAttribute.into());
+                    }
+                }
+                _ => return Err(AttributeError::UnsupportedAttribute.into()),
+            }
+        }
+    }
+
+    Ok(validate_data)
+}
+
 fn parse_attribute(
     nested_metas: Vec<NestedMeta>,
     next_nested_metas_opt: Option<Vec<NestedMeta>>,
     current_bitfield_idx: u8,
 ) -> Result<AttributeData, DeriveInputParserError> {
-    let simple_attribute_parsers: Vec<fn(&NestedMeta) -> Result<AttributeData, AttributeError>> =
+    let simple_attribute_parsers: Vec<AttributeParser> =
         vec![get_module_attribute, get_max_length_attribute];
 
     for nested_meta in nested_metas.iter() {
@@ -265,14 +401,12 @@ fn calc_bitfield_position(
     current_bitfield_idx: u8,
     next_nested_metas_opt: &Option<Vec<NestedMeta>>,
 ) -> BitfieldPosition {
-    fn next_has_bitfield_attribute(next_nested_metas: &Vec<NestedMeta>) -> bool {
-        next_nested_metas
-            .iter()
-            .any(|nested_meta| is_bitfield_attribute(nested_meta))
+    fn next_has_bitfield_attribute(next_nested_metas: &[NestedMeta]) -> bool {
+        next_nested_metas.iter().any(is_bitfield_attribute)
     }
 
     match next_nested_metas_opt {
-        Some(next_nested_metas) if (next_has_bitfield_attribute(&next_nested_metas)) => {
+        Some(next_nested_metas) if (next_has_bitfield_attribute(next_nested_metas)) => {
             if current_bitfield_idx == 0 {
                 BitfieldPosition::Start
             } else {
@@ -289,3 +423,196 @@ fn is_bitfield_attribute(nested_meta: &NestedMeta) -> bool {
         _ => false,
     }
 }
+
+/// Input of `msg!`: format string with color codes followed by arguments.
+pub(crate) struct MessageInput {
+    pub(crate) format: LitStr,
+    pub(crate) args: Vec<Expr>,
+}
+
+impl Parse for MessageInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let format = input.parse()?;
+        let mut args = Vec::new();
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+
+            if input.is_empty() {
+                break;
+            }
+
+            args.push(input.parse()?);
+        }
+
+        Ok(MessageInput { format, args })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum SegmentColor {
+    /// Name of `Color` variant.
+    Named(&'static str),
+    /// Hex color in `#RRGGBB` format.
+    Hex(String),
+}
+
+/// Formatting of message segment, set by legacy codes before its text.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub(crate) struct SegmentStyle {
+    pub(crate) color: Option<SegmentColor>,
+    pub(crate) bold: bool,
+    pub(crate) italic: bool,
+    pub(crate) underlined: bool,
+    pub(crate) strikethrough: bool,
+    pub(crate) obfuscated: bool,
+}
+
+/// Text of `msg!` with the same formatting.
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct MessageSegment {
+    pub(crate) style: SegmentStyle,
+    /// Format string of segment text, as accepted by `format!`.
+    pub(crate) format: String,
+    /// Number of arguments interpolated into segment text.
+    pub(crate) args: usize,
+}
+
+const NAMED_COLOR_CODES: [(char, &str); 16] = [
+    ('0', "Black"),
+    ('1', "DarkBlue"),
+    ('2', "DarkGreen"),
+    ('3', "DarkAqua"),
+    ('4', "DarkRed"),
+    ('5', "DarkPurple"),
+    ('6', "Gold"),
+    ('7', "Gray"),
+    ('8', "DarkGray"),
+    ('9', "Blue"),
+    ('a', "Green"),
+    ('b', "Aqua"),
+    ('c', "Red"),
+    ('d', "LightPurple"),
+    ('e', "Yellow"),
+    ('f', "White"),
+];
+
+/// Splits `msg!` format string into segments at legacy formatting codes.
+///
+/// Color codes and `&r` reset formatting, as in vanilla. Segments without text are dropped.
+pub(crate) fn parse_message_format(format: &LitStr) -> Result<Vec<MessageSegment>, SynError> {
+    let error = |message: String| SynError::new(format.span(), message);
+    let value = format.value();
+    let mut chars = value.chars().peekable();
+
+    let mut segments = Vec::new();
+    let mut current = MessageSegment {
+        style: SegmentStyle::default(),
+        format: String::new(),
+        args: 0,
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '&' | '§' => {
+                let code = chars
+                    .next()
+                    .ok_or_else(|| error(format!("missing formatting code after `{}`", c)))?;
+
+                if code == c {
+                    current.format.push(c);
+                    continue;
+                }
+
+                let mut style = current.style.clone();
+
+                match code.to_ascii_lowercase() {
+                    'k' => style.obfuscated = true,
+                    'l' => style.bold = true,
+                    'm' => style.strikethrough = true,
+                    'n' => style.underlined = true,
+                    'o' => style.italic = true,
+                    'r' => style = SegmentStyle::default(),
+                    '#' => {
+                        let digits: String = chars.by_ref().take(6).collect();
+
+                        if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                            return Err(error(format!(
+                                "invalid hex color `{}#{}`, expected `{}#RRGGBB`",
+                                c, digits, c
+                            )));
+                        }
+
+                        style = SegmentStyle {
+                            color: Some(SegmentColor::Hex(format!("#{}", digits))),
+                            ..SegmentStyle::default()
+                        };
+                    }
+                    lower => {
+                        let (_, name) = NAMED_COLOR_CODES
+                            .iter()
+                            .find(|(code, _)| *code == lower)
+                            .ok_or_else(|| {
+                            error(format!("unknown formatting code `{}{}`", c, code))
+                        })?;
+
+                        style = SegmentStyle {
+                            color: Some(SegmentColor::Named(name)),
+                            ..SegmentStyle::default()
+                        };
+                    }
+                }
+
+                if current.format.is_empty() {
+                    current.style = style;
+                } else {
+                    let next = MessageSegment {
+                        style,
+                        format: String::new(),
+                        args: 0,
+                    };
+                    segments.push(std::mem::replace(&mut current, next));
+                }
+            }
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                current.format.push_str("{{");
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                current.format.push_str("}}");
+            }
+            '{' => {
+                let mut placeholder = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err(error(String::from("unclosed `{` in format string"))),
+                    }
+                }
+
+                if !placeholder.is_empty() && !placeholder.starts_with(':') {
+                    return Err(error(format!(
+                        "unsupported placeholder `{{{}}}`, only `{{}}` and `{{:spec}}` are allowed",
+                        placeholder
+                    )));
+                }
+
+                current.format.push('{');
+                current.format.push_str(&placeholder);
+                current.format.push('}');
+                current.args += 1;
+            }
+            '}' => return Err(error(String::from("unmatched `}` in format string"))),
+            c => current.format.push(c),
+        }
+    }
+
+    if !current.format.is_empty() || segments.is_empty() {
+        segments.push(current);
+    }
+
+    Ok(segments)
+}
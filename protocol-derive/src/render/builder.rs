@@ -0,0 +1,117 @@
+use crate::parse::FieldData;
+use proc_macro2::Ident;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+
+pub(crate) fn render_struct_builder(name: &Ident, fields: &Vec<FieldData>) -> TokenStream2 {
+    let builder_name = format_ident!("{}Builder", name);
+    let builder_doc = format!("Builder for [`{}`].", name);
+    let builder_fields = render_builder_fields(fields);
+    let setters = render_setters(fields);
+    let build_fields = render_build_fields(fields);
+    let default = render_default(name, fields);
+
+    quote! {
+        #[doc = #builder_doc]
+        #[derive(Debug, Default)]
+        pub struct #builder_name {
+            #builder_fields
+        }
+
+        #[automatically_derived]
+        impl #name {
+            pub fn builder() -> #builder_name {
+                #builder_name::default()
+            }
+        }
+
+        #[automatically_derived]
+        impl #builder_name {
+            #setters
+
+            /// Returns `BuildError::MissingField` if required field is not set.
+            pub fn build(self) -> Result<#name, ::minecraft_protocol::error::BuildError> {
+                Ok(#name {
+                    #build_fields
+                })
+            }
+        }
+
+        #default
+    }
+}
+
+fn render_builder_fields(fields: &Vec<FieldData>) -> TokenStream2 {
+    fields
+        .iter()
+        .map(|f| {
+            let name = f.name;
+            let ty = f.ty;
+
+            quote!(#name: Option<#ty>,)
+        })
+        .collect()
+}
+
+fn render_setters(fields: &Vec<FieldData>) -> TokenStream2 {
+    fields
+        .iter()
+        .map(|f| {
+            let name = f.name;
+            let ty = f.ty;
+
+            quote! {
+                pub fn #name(mut self, #name: #ty) -> Self {
+                    self.#name = Some(#name);
+                    self
+                }
+            }
+        })
+        .collect()
+}
+
+fn render_build_fields(fields: &Vec<FieldData>) -> TokenStream2 {
+    fields
+        .iter()
+        .map(|f| {
+            let name = f.name;
+
+            if f.builder_default {
+                quote!(#name: self.#name.unwrap_or_default(),)
+            } else {
+                let field = name.to_string();
+
+                quote! {
+                    #name: self.#name.ok_or(::minecraft_protocol::error::BuildError::MissingField { field: #field })?,
+                }
+            }
+        })
+        .collect()
+}
+
+/// Renders `Default` if every field has default value.
+fn render_default(name: &Ident, fields: &Vec<FieldData>) -> TokenStream2 {
+    if !fields.iter().all(|f| f.builder_default) {
+        return quote!();
+    }
+
+    let default_fields: TokenStream2 = fields
+        .iter()
+        .map(|f| {
+            let name = f.name;
+
+            quote!(#name: Default::default(),)
+        })
+        .collect();
+
+    quote! {
+        #[automatically_derived]
+        impl Default for #name {
+            fn default() -> Self {
+                #name {
+                    #default_fields
+                }
+            }
+        }
+    }
+}
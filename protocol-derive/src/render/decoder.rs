@@ -1,22 +1,30 @@
 use crate::parse::{AttributeData, BitfieldPosition, DiscriminantType, FieldData, VariantData};
+use crate::render::bound_type_params;
 use proc_macro2::TokenStream as TokenStream2;
 use proc_macro2::{Ident, Span};
 use quote::quote;
-use syn::Type;
+use std::convert::TryFrom;
+use syn::{parse_quote, Generics, Type};
 
-pub(crate) fn render_struct_decoder(name: &Ident, fields: &Vec<FieldData>) -> TokenStream2 {
+pub(crate) fn render_struct_decoder(
+    name: &Ident,
+    generics: &Generics,
+    fields: &Vec<FieldData>,
+) -> TokenStream2 {
     let field_names_joined_comma = render_field_names_joined_comma(fields);
     let render_fields = render_fields(fields);
+    let generics = decoder_generics(generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     quote! {
         #[automatically_derived]
-        impl crate::decoder::Decoder for #name {
+        impl #impl_generics ::minecraft_protocol::decoder::Decoder for #name #ty_generics #where_clause {
             type Output = Self;
 
-            fn decode<R: std::io::Read>(reader: &mut R) -> Result<Self::Output, crate::error::DecodeError> {
+            fn decode<R: std::io::Read>(reader: &mut R) -> Result<Self::Output, ::minecraft_protocol::error::DecodeError> {
                 #render_fields
 
-                Ok(#name {
+                Ok(Self {
                     #field_names_joined_comma
                 })
             }
@@ -26,29 +34,40 @@ pub(crate) fn render_struct_decoder(name: &Ident, fields: &Vec<FieldData>) -> To
 
 pub(crate) fn render_enum_decoder(
     name: &Ident,
+    generics: &Generics,
     discriminant_type: &DiscriminantType,
     variants: &Vec<VariantData>,
 ) -> TokenStream2 {
     let render_variants = render_variants(discriminant_type, variants);
     let render_discriminant_type = render_discriminant_type(discriminant_type);
+    let generics = decoder_generics(generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     quote! {
         #[automatically_derived]
-        impl crate::decoder::Decoder for #name {
+        impl #impl_generics ::minecraft_protocol::decoder::Decoder for #name #ty_generics #where_clause {
             type Output = Self;
 
-            fn decode<R: std::io::Read>(reader: &mut R) -> Result<Self::Output, crate::error::DecodeError> {
+            fn decode<R: std::io::Read>(reader: &mut R) -> Result<Self::Output, ::minecraft_protocol::error::DecodeError> {
                 let type_id = #render_discriminant_type;
 
                 match type_id {
                     #render_variants
-                    _ => Err(DecodeError::UnknownEnumType { type_id: type_id as usize, }),
+                    _ => Err(::minecraft_protocol::error::DecodeError::UnknownEnumType { type_id: type_id as u32 as usize, }),
                 }
             }
         }
     }
 }
 
+/// Type parameters are bound by `Decoder` which decodes into parameter type itself.
+fn decoder_generics(generics: &Generics) -> Generics {
+    bound_type_params(
+        generics,
+        |ident| parse_quote!(::minecraft_protocol::decoder::Decoder<Output = #ident>),
+    )
+}
+
 fn render_variants(
     discriminant_type: &DiscriminantType,
     variants: &Vec<VariantData>,
@@ -104,22 +123,25 @@ fn render_struct_variant(
 fn render_discriminant_type(discriminant_type: &DiscriminantType) -> TokenStream2 {
     match discriminant_type {
         DiscriminantType::UnsignedByte => {
-            quote!(reader.read_u8()?;)
+            quote!(<u8 as ::minecraft_protocol::decoder::Decoder>::decode(reader)?;)
         }
         DiscriminantType::VarInt => {
-            quote!(reader.read_var_i32()?;)
+            quote!(::minecraft_protocol::decoder::DecoderReadExt::read_var_i32(reader)?;)
+        }
+        DiscriminantType::Int => {
+            quote!(<i32 as ::minecraft_protocol::decoder::Decoder>::decode(reader)?;)
         }
     }
 }
 
-fn render_discriminant(discriminant_type: &DiscriminantType, discriminant: usize) -> TokenStream2 {
+fn render_discriminant(discriminant_type: &DiscriminantType, discriminant: u64) -> TokenStream2 {
     match discriminant_type {
         DiscriminantType::UnsignedByte => {
-            let u8 = discriminant as u8;
+            let u8 = u8::try_from(discriminant).expect("Discriminant is checked by parser");
             quote!(#u8)
         }
-        DiscriminantType::VarInt => {
-            let i32 = discriminant as i32;
+        DiscriminantType::VarInt | DiscriminantType::Int => {
+            let i32 = i32::try_from(discriminant).expect("Discriminant is checked by parser");
             quote!(#i32)
         }
     }
@@ -147,7 +169,7 @@ fn render_field(field: &FieldData) -> TokenStream2 {
 
 fn render_simple_field(name: &Ident, ty: &Type) -> TokenStream2 {
     quote! {
-        let #name = <#ty as crate::decoder::Decoder>::decode(reader)?;
+        let #name = <#ty as ::minecraft_protocol::decoder::Decoder>::decode(reader)?;
     }
 }
 
@@ -155,13 +177,13 @@ fn render_with_field(name: &Ident, module: &str) -> TokenStream2 {
     let module_ident = Ident::new(module, Span::call_site());
 
     quote! {
-        let #name = crate::decoder::#module_ident::decode(reader)?;
+        let #name = ::minecraft_protocol::decoder::#module_ident::decode(reader)?;
     }
 }
 
 fn render_max_length_field(name: &Ident, max_length: u16) -> TokenStream2 {
     quote! {
-        let #name = crate::decoder::DecoderReadExt::read_string(reader, #max_length)?;
+        let #name = ::minecraft_protocol::decoder::DecoderReadExt::read_string(reader, #max_length)?;
     }
 }
 
@@ -175,7 +197,7 @@ fn render_bitfield(name: &Ident, idx: u8, position: &BitfieldPosition) -> TokenS
     match position {
         BitfieldPosition::Start => {
             quote! {
-              let flags = reader.read_u8()?;
+              let flags = <u8 as ::minecraft_protocol::decoder::Decoder>::decode(reader)?;
 
               #render_mask
             }
@@ -1,15 +1,24 @@
 use crate::parse::{AttributeData, BitfieldPosition, DiscriminantType, FieldData, VariantData};
+use crate::render::bound_type_params;
 use proc_macro2::TokenStream as TokenStream2;
 use proc_macro2::{Ident, Span};
 use quote::quote;
+use std::convert::TryFrom;
+use syn::{parse_quote, Generics};
 
-pub(crate) fn render_struct_encoder(name: &Ident, fields: &Vec<FieldData>) -> TokenStream2 {
+pub(crate) fn render_struct_encoder(
+    name: &Ident,
+    generics: &Generics,
+    fields: &Vec<FieldData>,
+) -> TokenStream2 {
     let render_fields = render_fields(fields, true);
+    let generics = encoder_generics(generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     quote! {
         #[automatically_derived]
-        impl crate::encoder::Encoder for #name {
-            fn encode<W: std::io::Write>(&self, writer: &mut W) -> Result<(), crate::error::EncodeError> {
+        impl #impl_generics ::minecraft_protocol::encoder::Encoder for #name #ty_generics #where_clause {
+            fn encode<W: std::io::Write>(&self, writer: &mut W) -> Result<(), ::minecraft_protocol::error::EncodeError> {
                 #render_fields
 
                 Ok(())
@@ -18,17 +27,27 @@ pub(crate) fn render_struct_encoder(name: &Ident, fields: &Vec<FieldData>) -> To
     }
 }
 
+/// Type parameters are bound by `Encoder`, lifetimes are kept as they are.
+fn encoder_generics(generics: &Generics) -> Generics {
+    bound_type_params(generics, |_| {
+        parse_quote!(::minecraft_protocol::encoder::Encoder)
+    })
+}
+
 pub(crate) fn render_enum_encoder(
     name: &Ident,
+    generics: &Generics,
     discriminant_type: &DiscriminantType,
     variants: &Vec<VariantData>,
 ) -> TokenStream2 {
     let render_variants = render_variants(discriminant_type, variants);
+    let generics = encoder_generics(generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     quote! {
         #[automatically_derived]
-        impl crate::encoder::Encoder for #name {
-            fn encode<W: std::io::Write>(&self, writer: &mut W) -> Result<(), crate::error::EncodeError> {
+        impl #impl_generics ::minecraft_protocol::encoder::Encoder for #name #ty_generics #where_clause {
+            fn encode<W: std::io::Write>(&self, writer: &mut W) -> Result<(), ::minecraft_protocol::error::EncodeError> {
                 match self {
                     #render_variants
                 }
@@ -98,18 +117,23 @@ fn render_struct_variant(
 
 fn render_discriminant_type(
     discriminant_type: &DiscriminantType,
-    discriminant: usize,
+    discriminant: u64,
 ) -> TokenStream2 {
     match discriminant_type {
         DiscriminantType::UnsignedByte => {
-            let u8 = discriminant as u8;
+            let u8 = u8::try_from(discriminant).expect("Discriminant is checked by parser");
 
-            quote!(writer.write_u8(#u8)?;)
+            quote!(::minecraft_protocol::encoder::Encoder::encode(&#u8, writer)?;)
         }
         DiscriminantType::VarInt => {
-            let var_i32 = discriminant as i32;
+            let var_i32 = i32::try_from(discriminant).expect("Discriminant is checked by parser");
+
+            quote!(::minecraft_protocol::encoder::EncoderWriteExt::write_var_i32(writer, #var_i32)?;)
+        }
+        DiscriminantType::Int => {
+            let i32 = i32::try_from(discriminant).expect("Discriminant is checked by parser");
 
-            quote!(writer.write_var_i32(#var_i32)?;)
+            quote!(::minecraft_protocol::encoder::Encoder::encode(&#i32, writer)?;)
         }
     }
 }
@@ -142,17 +166,21 @@ fn render_simple_field(name: &Ident, with_self: bool) -> TokenStream2 {
 fn render_with_field(name: &Ident, module: &str, with_self: bool) -> TokenStream2 {
     let module_ident = Ident::new(module, Span::call_site());
     let final_name = get_field_final_name(name, with_self);
+    let field = name.to_string();
 
     quote! {
-        crate::encoder::#module_ident::encode(#final_name, writer)?;
+        ::minecraft_protocol::encoder::#module_ident::encode(#final_name, writer)
+            .map_err(|e| e.with_field(#field))?;
     }
 }
 
 fn render_max_length_field(name: &Ident, max_length: u16, with_self: bool) -> TokenStream2 {
     let final_name = get_field_final_name(name, with_self);
+    let field = name.to_string();
 
     quote! {
-        crate::encoder::EncoderWriteExt::write_string(writer, #final_name, #max_length)?;
+        ::minecraft_protocol::encoder::EncoderWriteExt::write_string(writer, #final_name, #max_length)
+            .map_err(|e| e.with_field(#field))?;
     }
 }
 
@@ -167,7 +195,7 @@ fn render_bitfield(name: &Ident, idx: u8, position: &BitfieldPosition) -> TokenS
 
     match position {
         BitfieldPosition::Start => quote!(
-            let mut flags = 0;
+            let mut flags: u8 = 0;
 
             #render_mask
         ),
@@ -176,7 +204,7 @@ fn render_bitfield(name: &Ident, idx: u8, position: &BitfieldPosition) -> TokenS
             quote! {
                 #render_mask
 
-                writer.write_u8(flags)?;
+                ::minecraft_protocol::encoder::Encoder::encode(&flags, writer)?;
             }
         }
     }
@@ -1,2 +1,25 @@
+use proc_macro2::Ident;
+use syn::{Generics, TypeParamBound};
+
+pub(crate) mod builder;
 pub(crate) mod decoder;
 pub(crate) mod encoder;
+pub(crate) mod msg;
+pub(crate) mod packet_variants;
+pub(crate) mod validate;
+pub(crate) mod visit_messages;
+
+/// Returns generics with bound added to every type parameter, bound is built from parameter name.
+pub(crate) fn bound_type_params<F>(generics: &Generics, bound: F) -> Generics
+where
+    F: Fn(&Ident) -> TypeParamBound,
+{
+    let mut generics = generics.clone();
+
+    for type_param in generics.type_params_mut() {
+        let bound = bound(&type_param.ident);
+        type_param.bounds.push(bound);
+    }
+
+    generics
+}
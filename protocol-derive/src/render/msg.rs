@@ -0,0 +1,95 @@
+use crate::parse::{parse_message_format, MessageInput, MessageSegment, SegmentColor};
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::Error as SynError;
+
+pub(crate) fn render_msg(input: &MessageInput) -> Result<TokenStream2, SynError> {
+    let segments = parse_message_format(&input.format)?;
+    let placeholders: usize = segments.iter().map(|s| s.args).sum();
+
+    if placeholders != input.args.len() {
+        return Err(SynError::new(
+            input.format.span(),
+            format!(
+                "format string has {} placeholders but {} arguments were given",
+                placeholders,
+                input.args.len()
+            ),
+        ));
+    }
+
+    let mut args = input.args.iter();
+    let mut segments = segments.iter().map(|segment| {
+        let segment_args = args.by_ref().take(segment.args);
+
+        render_segment(segment, segment_args.collect())
+    });
+
+    let (first_payload, first_style) = segments.next().expect("Message has at least one segment");
+
+    // Children inherit formatting of parent, so segments of styled message are placed under
+    // empty root, as vanilla does for legacy text.
+    let builder = match segments.len() {
+        0 => quote! {
+            ::minecraft_protocol::data::chat::MessageBuilder::builder(#first_payload) #first_style
+        },
+        _ => {
+            let rest = segments.map(|(payload, style)| quote!(.then(#payload) #style));
+
+            quote! {
+                ::minecraft_protocol::data::chat::MessageBuilder::builder(
+                    ::minecraft_protocol::data::chat::Payload::text("")
+                )
+                .then(#first_payload) #first_style
+                #(#rest)*
+            }
+        }
+    };
+
+    Ok(quote!(#builder.build()))
+}
+
+/// Returns payload and builder calls for style of segment.
+fn render_segment(segment: &MessageSegment, args: Vec<&syn::Expr>) -> (TokenStream2, TokenStream2) {
+    let format = &segment.format;
+    let payload = if args.is_empty() {
+        let text = format.replace("{{", "{").replace("}}", "}");
+
+        quote!(::minecraft_protocol::data::chat::Payload::text(#text))
+    } else {
+        quote! {
+            ::minecraft_protocol::data::chat::Payload::text(&::std::format!(#format, #(#args),*))
+        }
+    };
+
+    let style = &segment.style;
+    let mut calls = TokenStream2::new();
+
+    match &style.color {
+        Some(SegmentColor::Named(name)) => {
+            let variant = Ident::new(name, Span::call_site());
+
+            calls.extend(quote!(.color(::minecraft_protocol::data::chat::Color::#variant)));
+        }
+        Some(SegmentColor::Hex(hex)) => calls.extend(quote! {
+            .color(::minecraft_protocol::data::chat::Color::Hex(::std::string::String::from(#hex)))
+        }),
+        None => {}
+    }
+
+    let flags = [
+        (style.bold, "bold"),
+        (style.italic, "italic"),
+        (style.underlined, "underlined"),
+        (style.strikethrough, "strikethrough"),
+        (style.obfuscated, "obfuscated"),
+    ];
+
+    for (_, method) in flags.iter().filter(|(enabled, _)| *enabled) {
+        let method = Ident::new(method, Span::call_site());
+
+        calls.extend(quote!(.#method(true)));
+    }
+
+    (payload, calls)
+}
@@ -0,0 +1,45 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+pub(crate) fn render_packet_variants(input: &DeriveInput) -> TokenStream2 {
+    let name = &input.ident;
+
+    let data_enum = match &input.data {
+        Data::Enum(data_enum) => data_enum,
+        _ => panic!("PacketVariants can be derived only for enums"),
+    };
+
+    data_enum
+        .variants
+        .iter()
+        .filter_map(|v| match &v.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let variant = &v.ident;
+                let ty = &fields.unnamed[0].ty;
+
+                Some(quote! {
+                    #[automatically_derived]
+                    impl ::minecraft_protocol::handler::PacketVariant<#name> for #ty {
+                        fn from_packet(packet: &#name) -> Option<&Self> {
+                            #[allow(unreachable_patterns)]
+                            match packet {
+                                #name::#variant(value) => Some(value),
+                                _ => None,
+                            }
+                        }
+
+                        fn try_from_packet(packet: #name) -> Result<Self, #name> {
+                            #[allow(unreachable_patterns)]
+                            match packet {
+                                #name::#variant(value) => Ok(value),
+                                packet => Err(packet),
+                            }
+                        }
+                    }
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
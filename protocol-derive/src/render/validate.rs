@@ -0,0 +1,169 @@
+use crate::parse::{parse_validate_data, ValidateData};
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Index, Member};
+
+pub(crate) fn render_validate(input: &DeriveInput) -> TokenStream2 {
+    let name = &input.ident;
+
+    let body = match &input.data {
+        Data::Struct(data_struct) => render_struct_body(&data_struct.fields),
+        Data::Enum(data_enum) => {
+            let arms: Vec<TokenStream2> = data_enum
+                .variants
+                .iter()
+                .filter_map(|v| render_variant_arm(name, &v.ident, &v.fields))
+                .collect();
+
+            if arms.is_empty() {
+                TokenStream2::new()
+            } else if arms.len() < data_enum.variants.len() {
+                quote! {
+                    match self {
+                        #(#arms)*
+                        _ => {}
+                    }
+                }
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+        Data::Union(_) => panic!("Validate can't be derived for unions"),
+    };
+
+    let body = if body.is_empty() {
+        quote!(let _ = (path, violations);)
+    } else {
+        body
+    };
+
+    quote! {
+        #[automatically_derived]
+        impl ::minecraft_protocol::validate::Validate for #name {
+            fn validate_into(
+                &self,
+                path: &str,
+                violations: &mut Vec<::minecraft_protocol::validate::Violation>,
+            ) {
+                #body
+            }
+        }
+    }
+}
+
+fn render_struct_body(fields: &Fields) -> TokenStream2 {
+    checked_fields(fields)
+        .map(|(member, _, validate_data)| {
+            render_checks(&member, quote!(&self.#member), &validate_data)
+        })
+        .collect()
+}
+
+fn render_variant_arm(name: &Ident, variant: &Ident, fields: &Fields) -> Option<TokenStream2> {
+    let (bindings, checks): (Vec<_>, Vec<_>) = checked_fields(fields)
+        .map(|(member, binding, validate_data)| {
+            let pattern = match member {
+                Member::Named(_) => quote!(#binding,),
+                Member::Unnamed(_) => quote!(#member: #binding,),
+            };
+            let checks = render_checks(&member, quote!(#binding), &validate_data);
+
+            (pattern, checks)
+        })
+        .unzip();
+
+    if checks.is_empty() {
+        return None;
+    }
+
+    Some(quote! {
+        #name::#variant { #(#bindings)* .. } => {
+            #(#checks)*
+        }
+    })
+}
+
+/// Returns fields with invariants. Unnamed fields, as in packet enums, are always validated as
+/// nested under the same path.
+fn checked_fields(fields: &Fields) -> impl Iterator<Item = (Member, Ident, ValidateData)> + '_ {
+    fields.iter().enumerate().filter_map(|(idx, f)| {
+        let validate_data = parse_validate_data(f).expect("Failed to parse validate attributes");
+
+        let (member, binding, validate_data) = match &f.ident {
+            Some(ident) => (Member::Named(ident.clone()), ident.clone(), validate_data),
+            None => (
+                Member::Unnamed(Index::from(idx)),
+                format_ident!("field_{}", idx),
+                ValidateData {
+                    nested: true,
+                    ..validate_data
+                },
+            ),
+        };
+
+        if validate_data == ValidateData::default() {
+            None
+        } else {
+            Some((member, binding, validate_data))
+        }
+    })
+}
+
+fn render_checks(
+    member: &Member,
+    value: TokenStream2,
+    validate_data: &ValidateData,
+) -> TokenStream2 {
+    let field = match member {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(_) => String::new(),
+    };
+    let mut checks = TokenStream2::new();
+
+    if let Some(max_length) = validate_data.max_length {
+        checks.extend(quote! {
+            {
+                use ::minecraft_protocol::validate::ValidateLength as _;
+                ::minecraft_protocol::validate::check_length(violations, path, #field, (#value).validate_length(), #max_length);
+            }
+        });
+    }
+
+    if validate_data.min.is_some() || validate_data.max.is_some() {
+        let min = render_bound(validate_data.min);
+        let max = render_bound(validate_data.max);
+
+        checks.extend(quote! {
+            ::minecraft_protocol::validate::check_range(violations, path, #field, *(#value) as i64, #min, #max);
+        });
+    }
+
+    if let Some(max_items) = validate_data.max_items {
+        checks.extend(quote! {
+            ::minecraft_protocol::validate::check_items(violations, path, #field, (#value).len(), #max_items);
+        });
+    }
+
+    if validate_data.nested {
+        checks.extend(quote! {
+            ::minecraft_protocol::validate::Validate::validate_into(
+                #value,
+                &::minecraft_protocol::validate::field_path(path, #field),
+                violations,
+            );
+        });
+    }
+
+    checks
+}
+
+fn render_bound(bound: Option<i64>) -> TokenStream2 {
+    match bound {
+        Some(bound) => quote!(Some(#bound)),
+        None => quote!(None),
+    }
+}
@@ -0,0 +1,103 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Data, DeriveInput, Field, Fields, Index, Member};
+
+pub(crate) fn render_visit_messages(input: &DeriveInput) -> TokenStream2 {
+    let name = &input.ident;
+
+    let body = match &input.data {
+        Data::Struct(data_struct) => render_struct_body(&data_struct.fields),
+        Data::Enum(data_enum) => {
+            let arms: TokenStream2 = data_enum
+                .variants
+                .iter()
+                .map(|v| render_variant_arm(name, &v.ident, &v.fields))
+                .collect();
+
+            quote! {
+                match self {
+                    #arms
+                }
+            }
+        }
+        Data::Union(_) => panic!("VisitMessages can't be derived for unions"),
+    };
+
+    quote! {
+        #[automatically_derived]
+        impl ::minecraft_protocol::data::chat::VisitMessages for #name {
+            fn visit_messages(
+                &mut self,
+                visitor: &mut dyn FnMut(&mut ::minecraft_protocol::data::chat::Message),
+            ) {
+                #body
+            }
+        }
+    }
+}
+
+fn render_struct_body(fields: &Fields) -> TokenStream2 {
+    let visits: TokenStream2 = visited_fields(fields)
+        .map(|(member, _)| {
+            quote! {
+                ::minecraft_protocol::data::chat::VisitMessages::visit_messages(&mut self.#member, visitor);
+            }
+        })
+        .collect();
+
+    if visits.is_empty() {
+        quote!(let _ = visitor;)
+    } else {
+        visits
+    }
+}
+
+fn render_variant_arm(name: &Ident, variant: &Ident, fields: &Fields) -> TokenStream2 {
+    let (bindings, visits): (Vec<_>, Vec<_>) = visited_fields(fields)
+        .map(|(member, binding)| {
+            let pattern = match member {
+                Member::Named(_) => quote!(#binding,),
+                Member::Unnamed(_) => quote!(#member: #binding,),
+            };
+            let visit = quote! {
+                ::minecraft_protocol::data::chat::VisitMessages::visit_messages(#binding, visitor);
+            };
+
+            (pattern, visit)
+        })
+        .unzip();
+
+    quote! {
+        #name::#variant { #(#bindings)* .. } => {
+            #(#visits)*
+        }
+    }
+}
+
+/// Returns fields with `Message` in their type or marked with `#[visit]`.
+fn visited_fields(fields: &Fields) -> impl Iterator<Item = (Member, Ident)> + '_ {
+    fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| is_visited(f))
+        .map(|(idx, f)| match &f.ident {
+            Some(ident) => (Member::Named(ident.clone()), ident.clone()),
+            None => (
+                Member::Unnamed(Index::from(idx)),
+                format_ident!("field_{}", idx),
+            ),
+        })
+}
+
+fn is_visited(field: &Field) -> bool {
+    field.attrs.iter().any(|a| a.path.is_ident("visit"))
+        || contains_message(field.ty.to_token_stream())
+}
+
+fn contains_message(tokens: TokenStream2) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "Message",
+        TokenTree::Group(group) => contains_message(group.stream()),
+        _ => false,
+    })
+}
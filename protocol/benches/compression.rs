@@ -0,0 +1,94 @@
+//! Compares compression overrides of `PacketCodec` for chunk data and chat packets.
+//!
+//! Encoded frame lengths are printed before measuring, so CPU time can be weighed against
+//! bandwidth. Run with `cargo bench --bench compression`.
+use criterion::{criterion_group, criterion_main, Criterion};
+use minecraft_protocol::codec::{FlushPolicy, PacketCodec};
+use minecraft_protocol::compression::CompressionOverride;
+use minecraft_protocol::data::chat::Message;
+use minecraft_protocol::data::game::MessagePosition;
+use minecraft_protocol::packet::{Packet, RawPacket};
+use minecraft_protocol::version::v1_14_4::game::{
+    ChunkData, ClientBoundChatMessage, GameClientBoundPacket,
+};
+use nbt::CompoundTag;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::io::{self, BufWriter};
+
+/// Vanilla default threshold.
+const THRESHOLD: usize = 256;
+
+const OVERRIDES: [(&str, Option<CompressionOverride>); 5] = [
+    ("threshold", None),
+    ("skip", Some(CompressionOverride::Skip)),
+    ("level_1", Some(CompressionOverride::Level(1))),
+    ("level_6", Some(CompressionOverride::Level(6))),
+    ("level_9", Some(CompressionOverride::Level(9))),
+];
+
+/// Chunk sections with block states from small palette, compressible like real chunks.
+fn chunk_data() -> GameClientBoundPacket {
+    let mut rng = StdRng::seed_from_u64(1);
+    let data = (0..64 * 1024).map(|_| rng.gen_range(0..16)).collect();
+
+    let mut heights = CompoundTag::named("");
+    heights.insert_i64_vec("MOTION_BLOCKING", vec![0; 36]);
+
+    ChunkData::new(12, -4, true, 0xFF, heights, data, vec![])
+}
+
+fn chat_message() -> GameClientBoundPacket {
+    let message = Message::from_str(&"Welcome to the server! ".repeat(16));
+
+    ClientBoundChatMessage::new(message, MessagePosition::Chat)
+}
+
+fn codec<S>(
+    stream: S,
+    id: i32,
+    compression_override: Option<CompressionOverride>,
+) -> PacketCodec<S> {
+    let mut codec = PacketCodec::new(stream).with_flush_policy(FlushPolicy::Manual);
+    codec.set_compression_threshold(Some(THRESHOLD));
+    codec.set_compression_override(id, compression_override);
+
+    codec
+}
+
+fn bench_packet(c: &mut Criterion, name: &str, packet: &GameClientBoundPacket) {
+    let raw_packet = RawPacket::from_packet(packet).unwrap();
+    let mut group = c.benchmark_group(name);
+
+    for (override_name, compression_override) in OVERRIDES {
+        let mut sized = codec(Vec::new(), raw_packet.id, compression_override);
+        sized.write_raw_packet(&raw_packet).unwrap();
+        println!(
+            "{}/{}: {} of {} bytes",
+            name,
+            override_name,
+            sized.get_ref().len(),
+            packet.encoded_len().unwrap()
+        );
+
+        let stream = BufWriter::new(io::sink());
+        let mut codec = codec(stream, raw_packet.id, compression_override);
+
+        group.bench_function(override_name, |b| {
+            b.iter(|| codec.write_raw_packet(&raw_packet).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_chunk_data(c: &mut Criterion) {
+    bench_packet(c, "chunk_data", &chunk_data());
+}
+
+fn bench_chat_message(c: &mut Criterion) {
+    bench_packet(c, "chat_message", &chat_message());
+}
+
+criterion_group!(benches, bench_chunk_data, bench_chat_message);
+criterion_main!(benches);
@@ -0,0 +1,108 @@
+//! Compares decoding buffers through `Read` adapter with decoding them directly.
+//!
+//! Run with `cargo bench --features bytes`.
+use bytes::{Buf, Bytes, BytesMut};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use minecraft_protocol::buf::{decode_buf, decode_frame, DecoderBufExt, EncoderBufMutExt};
+use minecraft_protocol::decoder::{Decoder, DecoderReadExt};
+use minecraft_protocol::encoder::Encoder;
+use minecraft_protocol::packet::RawPacket;
+use minecraft_protocol::version::v1_14_4::game::ChunkData;
+use nbt::CompoundTag;
+use std::sync::Arc;
+
+/// Number of var ints decoded per iteration.
+const VAR_INT_COUNT: i32 = 1024;
+
+fn var_ints() -> Bytes {
+    let mut buf = BytesMut::new();
+
+    for value in 0..VAR_INT_COUNT {
+        buf.put_var_i32(value * 4099).unwrap();
+    }
+
+    buf.freeze()
+}
+
+fn chunk_data() -> Bytes {
+    let mut heights = CompoundTag::named("");
+    heights.insert_i64_vec("MOTION_BLOCKING", vec![0; 36]);
+
+    let chunk_data = ChunkData {
+        x: 12,
+        z: -4,
+        full: true,
+        primary_mask: 0xFF,
+        heights: Arc::new(heights),
+        data: vec![7; 64 * 1024],
+        tiles: Vec::new(),
+    };
+
+    let mut vec = Vec::new();
+    chunk_data.encode(&mut vec).unwrap();
+
+    Bytes::from(vec)
+}
+
+fn bench_var_int(c: &mut Criterion) {
+    let bytes = var_ints();
+    let mut group = c.benchmark_group("var_int");
+
+    group.bench_function("read", |b| {
+        b.iter(|| {
+            let mut reader = bytes.clone().reader();
+
+            for _ in 0..VAR_INT_COUNT {
+                black_box(reader.read_var_i32().unwrap());
+            }
+        })
+    });
+
+    group.bench_function("buf", |b| {
+        b.iter(|| {
+            let mut buf = bytes.clone();
+
+            for _ in 0..VAR_INT_COUNT {
+                black_box(buf.get_var_i32().unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_chunk_data(c: &mut Criterion) {
+    let bytes = chunk_data();
+    let mut frame = Vec::new();
+    RawPacket::new(0x21, bytes.to_vec())
+        .encode(&mut frame)
+        .unwrap();
+
+    let mut group = c.benchmark_group("chunk_data");
+
+    group.bench_function("read", |b| {
+        b.iter(|| {
+            let mut reader = bytes.clone().reader();
+            black_box(ChunkData::decode(&mut reader).unwrap())
+        })
+    });
+
+    group.bench_function("buf", |b| {
+        b.iter(|| {
+            let mut buf = bytes.clone();
+            black_box(decode_buf::<ChunkData, _>(&mut buf).unwrap())
+        })
+    });
+
+    group.bench_function("frame", |b| {
+        b.iter(|| {
+            let mut buf = BytesMut::from(&frame[..]);
+            black_box(decode_frame(&mut buf).unwrap())
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_var_int, bench_chunk_data);
+criterion_main!(benches);
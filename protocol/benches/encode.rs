@@ -0,0 +1,50 @@
+//! Compares encode strategies of `PacketCodec` for small and huge packets.
+//!
+//! Run with `cargo bench --bench encode`.
+use criterion::{criterion_group, criterion_main, Criterion};
+use minecraft_protocol::codec::{EncodeStrategy, FlushPolicy, PacketCodec};
+use minecraft_protocol::version::v1_14_4::game::{
+    ChunkData, ClientBoundKeepAlive, GameClientBoundPacket,
+};
+use nbt::CompoundTag;
+use std::io::{self, BufWriter};
+
+const STRATEGIES: [(&str, EncodeStrategy); 3] = [
+    ("allocate", EncodeStrategy::Allocate),
+    ("buffered", EncodeStrategy::Buffered),
+    ("two_pass", EncodeStrategy::TwoPass),
+];
+
+fn chunk_data() -> GameClientBoundPacket {
+    let mut heights = CompoundTag::named("");
+    heights.insert_i64_vec("MOTION_BLOCKING", vec![0; 36]);
+
+    ChunkData::new(12, -4, true, 0xFF, heights, vec![7; 64 * 1024], vec![])
+}
+
+fn bench_packet(c: &mut Criterion, name: &str, packet: &GameClientBoundPacket) {
+    let mut group = c.benchmark_group(name);
+
+    for (strategy_name, encode_strategy) in STRATEGIES {
+        let mut codec = PacketCodec::new(BufWriter::new(io::sink()))
+            .with_flush_policy(FlushPolicy::Manual)
+            .with_encode_strategy(encode_strategy);
+
+        group.bench_function(strategy_name, |b| {
+            b.iter(|| codec.write_packet(packet).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_keep_alive(c: &mut Criterion) {
+    bench_packet(c, "keep_alive", &ClientBoundKeepAlive::new(42));
+}
+
+fn bench_chunk_data(c: &mut Criterion) {
+    bench_packet(c, "chunk_data", &chunk_data());
+}
+
+criterion_group!(benches, bench_keep_alive, bench_chunk_data);
+criterion_main!(benches);
@@ -0,0 +1,121 @@
+//! Bot which joins offline-mode 1.14.4 server and logs chat messages it receives.
+//!
+//! Bot answers keep-alives and login plugin requests, so it stays connected until the server
+//! kicks it. Servers in online mode ask for encryption, which bot doesn't support.
+//!
+//! ```text
+//! cargo run --example chat_bot -- 127.0.0.1:25565 ChatLogger
+//! ```
+use minecraft_protocol::connection::Connection;
+use minecraft_protocol::driver::{ProtocolState, Side};
+use minecraft_protocol::handler::PacketHandlers;
+use minecraft_protocol::handshake::ServerAddress;
+use minecraft_protocol::login_plugin::LoginPluginResponder;
+use minecraft_protocol::version::v1_14_4::game::*;
+use minecraft_protocol::version::v1_14_4::handshake::NextState;
+use minecraft_protocol::version::v1_14_4::login::*;
+use minecraft_protocol::version::ProtocolVersion;
+use std::env;
+use std::net::TcpStream;
+use std::process;
+
+/// State shared by packet handlers.
+#[derive(Default)]
+struct Bot {
+    /// Ids of keep-alives which must be answered.
+    keep_alive_ids: Vec<u64>,
+    /// Reason of disconnect, set when server kicks bot.
+    disconnect_reason: Option<String>,
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let address = args.next().unwrap_or_else(|| String::from("127.0.0.1"));
+    let address = ServerAddress::parse(&address).expect("Invalid server address");
+    let username = args.next().unwrap_or_else(|| String::from("ChatLogger"));
+
+    let stream = TcpStream::connect((address.host(), address.port())).expect("Failed to connect");
+    stream.set_nodelay(true).expect("Failed to set nodelay");
+
+    let mut connection = Connection::new(stream, Side::Client);
+
+    login(&mut connection, &address, &username);
+    log_chat(&mut connection);
+}
+
+fn login(connection: &mut Connection<TcpStream>, address: &ServerAddress, username: &str) {
+    let protocol_version = ProtocolVersion::V1_14_4.number();
+
+    connection
+        .write_packet(&address.handshake(protocol_version, NextState::Login))
+        .expect("Failed to send handshake");
+    connection
+        .write_packet(&LoginStart::new(username.to_owned()))
+        .expect("Failed to send login start");
+
+    // Bot doesn't know any login plugin channel, every request is answered as not understood.
+    let mut responder = LoginPluginResponder::new();
+
+    // Compression is enabled by connection when `SetCompression` is read.
+    while connection.state() == ProtocolState::Login {
+        match connection
+            .read_packet()
+            .expect("Failed to read login packet")
+        {
+            LoginClientBoundPacket::LoginSuccess(login_success) => {
+                println!("Logged in as {}", login_success.username)
+            }
+            LoginClientBoundPacket::LoginPluginRequest(request) => connection
+                .write_packet(&responder.respond(&request, &mut ()))
+                .expect("Failed to send login plugin response"),
+            LoginClientBoundPacket::LoginDisconnect(login_disconnect) => {
+                eprintln!(
+                    "Disconnected: {}",
+                    login_disconnect.reason.to_json().unwrap()
+                );
+                process::exit(1);
+            }
+            LoginClientBoundPacket::EncryptionRequest(_) => {
+                eprintln!("Server is in online mode, encryption is not supported");
+                process::exit(1);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn log_chat(connection: &mut Connection<TcpStream>) {
+    let mut handlers = PacketHandlers::new();
+
+    handlers
+        .on(|chat_message: &ClientBoundChatMessage, _: &mut Bot| {
+            if chat_message.position != MessagePosition::HotBar {
+                println!("{}", chat_message.message.to_json().unwrap());
+            }
+        })
+        .on(|keep_alive: &ClientBoundKeepAlive, bot: &mut Bot| {
+            bot.keep_alive_ids.push(keep_alive.id)
+        })
+        .on(|game_disconnect: &GameDisconnect, bot: &mut Bot| {
+            bot.disconnect_reason = game_disconnect.reason.to_json().ok()
+        });
+
+    let mut bot = Bot::default();
+
+    loop {
+        connection
+            .read_and_dispatch(&mut handlers, &mut bot)
+            .expect("Failed to read game packet");
+
+        for id in bot.keep_alive_ids.drain(..) {
+            connection
+                .write_packet(&ServerBoundKeepAlive::new(id))
+                .expect("Failed to answer keep alive");
+        }
+
+        if let Some(reason) = bot.disconnect_reason.take() {
+            println!("Disconnected: {}", reason);
+            return;
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Minimal offline-mode 1.14.4 server which echoes chat messages back to the sender.
+//!
+//! Answers server list ping, logs any player in without authentication and sends every chat
+//! message back. Server sends no world, so vanilla client stays on the terrain screen, but bots
+//! like `chat_bot` example can talk to it.
+//!
+//! ```text
+//! cargo run --example echo_server -- 127.0.0.1:25565
+//! ```
+use minecraft_protocol::connection::{Connection, StatePacket};
+use minecraft_protocol::data::chat::Message;
+use minecraft_protocol::data::server_status::{OnlinePlayers, ServerStatus, ServerVersion};
+use minecraft_protocol::driver::{ProtocolState, Side};
+use minecraft_protocol::error::{DecodeError, EncodeError};
+use minecraft_protocol::keep_alive::KEEP_ALIVE_INTERVAL;
+use minecraft_protocol::version::v1_14_4::game::*;
+use minecraft_protocol::version::v1_14_4::login::*;
+use minecraft_protocol::version::v1_14_4::status::*;
+use minecraft_protocol::version::v1_14_4::ServerBoundPacket;
+use minecraft_protocol::version::ProtocolVersion;
+use std::env;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Read timeout of sockets, keep-alives are sent between reads.
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+enum ConnectionError {
+    Decode(DecodeError),
+    Encode(EncodeError),
+}
+
+impl From<DecodeError> for ConnectionError {
+    fn from(decode_error: DecodeError) -> Self {
+        ConnectionError::Decode(decode_error)
+    }
+}
+
+impl From<EncodeError> for ConnectionError {
+    fn from(encode_error: EncodeError) -> Self {
+        ConnectionError::Encode(encode_error)
+    }
+}
+
+impl From<std::io::Error> for ConnectionError {
+    fn from(io_error: std::io::Error) -> Self {
+        ConnectionError::Decode(io_error.into())
+    }
+}
+
+fn main() {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("127.0.0.1:25565"));
+    let listener = TcpListener::bind(&addr).expect("Failed to bind");
+
+    println!("Listening on {}", addr);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(io_error) => {
+                eprintln!("Failed to accept connection: {}", io_error);
+                continue;
+            }
+        };
+
+        thread::spawn(move || {
+            let peer_addr = stream.peer_addr().ok();
+
+            match handle_connection(stream) {
+                Ok(()) => {}
+                Err(ConnectionError::Decode(decode_error)) => {
+                    eprintln!("Connection {:?} closed: {:?}", peer_addr, decode_error)
+                }
+                Err(ConnectionError::Encode(encode_error)) => {
+                    eprintln!("Failed to write to {:?}: {:?}", peer_addr, encode_error)
+                }
+            }
+        });
+    }
+}
+
+fn handle_connection(stream: TcpStream) -> Result<(), ConnectionError> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_nodelay(true)?;
+
+    let mut connection = Connection::new(stream, Side::Server);
+
+    loop {
+        match connection.read_state_packet()? {
+            ServerBoundPacket::Handshake(_) => {}
+            ServerBoundPacket::Status(StatusServerBoundPacket::StatusRequest) => {
+                connection.write_packet(&StatusResponse::new(server_status()))?;
+            }
+            ServerBoundPacket::Status(StatusServerBoundPacket::PingRequest(ping_request)) => {
+                connection.write_packet(&PingResponse::new(ping_request.time))?;
+
+                return Ok(());
+            }
+            ServerBoundPacket::Login(LoginServerBoundPacket::LoginStart(login_start)) => {
+                let username = login_start.name;
+
+                connection.write_packet(&LoginSuccess::new(Uuid::new_v4(), username.clone()))?;
+                connection.write_packet(&JoinGame::new(
+                    1,
+                    GameMode::Creative,
+                    0,
+                    20,
+                    String::from("flat"),
+                    8,
+                    false,
+                ))?;
+
+                println!("{} joined", username);
+
+                return play(&mut connection, &username);
+            }
+            packet => {
+                eprintln!("Unexpected packet {}", packet.name());
+
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn play(connection: &mut Connection<TcpStream>, username: &str) -> Result<(), ConnectionError> {
+    debug_assert_eq!(connection.state(), ProtocolState::Game);
+
+    let mut last_keep_alive = Instant::now();
+    let mut keep_alive_id = 0;
+
+    loop {
+        if last_keep_alive.elapsed() >= KEEP_ALIVE_INTERVAL {
+            keep_alive_id += 1;
+            last_keep_alive = Instant::now();
+            connection.write_packet(&ClientBoundKeepAlive::new(keep_alive_id))?;
+        }
+
+        let packet = match connection.read_packet_timeout(READ_TIMEOUT) {
+            Ok(packet) => packet,
+            Err(DecodeError::IOError { io_error }) if io_error.kind() == ErrorKind::TimedOut => {
+                continue;
+            }
+            Err(decode_error) => return Err(decode_error.into()),
+        };
+
+        if let GameServerBoundPacket::ServerBoundChatMessage(chat_message) = packet {
+            println!("<{}> {}", username, chat_message.message);
+
+            let echo = Message::from_str(&format!("<{}> {}", username, chat_message.message));
+            connection.write_packet(&ClientBoundChatMessage::new(echo, MessagePosition::Chat))?;
+        }
+    }
+}
+
+fn server_status() -> ServerStatus {
+    let version = ProtocolVersion::V1_14_4;
+
+    ServerStatus {
+        version: ServerVersion {
+            name: String::from(version.name()),
+            protocol: version.number() as u32,
+        },
+        players: OnlinePlayers {
+            max: 20,
+            online: 0,
+            sample: Vec::new(),
+        },
+        description: Message::from_str("Echo server"),
+        favicon: None,
+        enforces_secure_chat: None,
+    }
+}
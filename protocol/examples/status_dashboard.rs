@@ -0,0 +1,67 @@
+//! Dashboard which pings a list of servers in parallel and prints their status.
+//!
+//! Servers are pinged like vanilla server list does, so servers which don't answer ping request
+//! are still shown with status latency.
+//!
+//! ```text
+//! cargo run --example status_dashboard -- 127.0.0.1 mc.example.com:25566
+//! ```
+use minecraft_protocol::error::PingError;
+use minecraft_protocol::handshake::ServerAddress;
+use minecraft_protocol::ping::{ping_tcp, PingConfig, ServerPing};
+use std::env;
+use std::thread;
+
+fn main() {
+    let addresses: Vec<String> = env::args().skip(1).collect();
+
+    if addresses.is_empty() {
+        eprintln!("Usage: status_dashboard <address>...");
+        return;
+    }
+
+    let config = PingConfig::default();
+    let pings: Vec<_> = addresses
+        .into_iter()
+        .map(|address| thread::spawn(move || (ping(&address, &config), address)))
+        .collect();
+
+    println!(
+        "{:<32} {:<24} {:>9} {:>9}",
+        "SERVER", "VERSION", "PLAYERS", "PING"
+    );
+
+    for handle in pings {
+        let (server_ping, address) = handle.join().unwrap();
+
+        match server_ping {
+            Ok(server_ping) => {
+                let status = &server_ping.status;
+                let players = format!("{}/{}", status.players.online, status.players.max);
+                let latency = server_ping
+                    .latency
+                    .as_ref()
+                    .unwrap_or(&server_ping.status_latency);
+
+                println!(
+                    "{:<32} {:<24} {:>9} {:>7}ms",
+                    address,
+                    status.version.name,
+                    players,
+                    latency.as_millis()
+                );
+            }
+            Err(error) => println!("{:<32} {}", address, error),
+        }
+    }
+}
+
+fn ping(address: &str, config: &PingConfig) -> Result<ServerPing, String> {
+    let address = ServerAddress::parse(address).map_err(|e| format!("invalid address {:?}", e))?;
+
+    ping_tcp(address.host(), address.port(), config).map_err(|ping_error| match ping_error {
+        PingError::ConnectionClosed => String::from("connection closed"),
+        PingError::Timeout => String::from("timed out"),
+        ping_error => format!("{:?}", ping_error),
+    })
+}
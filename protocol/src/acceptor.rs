@@ -0,0 +1,537 @@
+//! Detection of the protocol spoken by a newly accepted connection.
+//!
+//! Minecraft servers receive not only modern handshakes but also legacy server list pings from
+//! old clients and, quite often, misdirected HTTP requests. First bytes of the stream are peeked
+//! without consuming them, so the handler receives the stream untouched.
+//!
+//! Multi-protocol listeners and honeypots can use `report`, which also parses the handshake to tell
+//! which protocol version the client speaks.
+//!
+//! Servers behind a load balancer can use `accept_proxied`, which reads PROXY protocol header
+//! first and passes the real client address to the handler.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use minecraft_protocol::acceptor::{self, ConnectionHandler};
+//! use std::net::{TcpListener, TcpStream};
+//! use std::time::Duration;
+//!
+//! struct Handler;
+//!
+//! impl ConnectionHandler for Handler {
+//!     fn handle_handshake(&mut self, stream: TcpStream) {
+//!         // Read handshake packet.
+//!     }
+//!
+//!     fn handle_legacy_ping(&mut self, stream: TcpStream) {
+//!         // Respond with legacy kick packet.
+//!     }
+//!
+//!     fn handle_http(&mut self, stream: TcpStream) {
+//!         // Respond with HTTP error.
+//!     }
+//! }
+//!
+//! let listener = TcpListener::bind("127.0.0.1:25565").unwrap();
+//! let mut handler = Handler;
+//!
+//! for stream in listener.incoming() {
+//!     acceptor::accept(stream.unwrap(), &mut handler, Duration::from_secs(5)).unwrap();
+//! }
+//! ```
+#[cfg(feature = "v1_14_4")]
+use crate::decoder::Decoder;
+use crate::decoder::DecoderReadExt;
+use crate::error::DecodeError;
+use crate::proxy_protocol::ProxyHeader;
+#[cfg(feature = "v1_14_4")]
+use crate::version::v1_14_4::handshake::Handshake;
+use std::io::{self, Cursor, ErrorKind};
+use std::net::TcpStream;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Enough bytes to tell apart every detected protocol.
+const PEEK_LENGTH: usize = 8;
+
+/// Delay between peeks while waiting for more bytes.
+const PEEK_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Handshake packet length with empty server address.
+const HANDSHAKE_MIN_LENGTH: i32 = 6;
+
+/// Handshake packet length with the longest server address decoder accepts.
+#[cfg(feature = "v1_14_4")]
+const HANDSHAKE_MAX_LENGTH: i32 = 266;
+
+const HTTP_METHODS: [&[u8]; 9] = [
+    b"GET ",
+    b"HEAD ",
+    b"POST ",
+    b"PUT ",
+    b"DELETE ",
+    b"CONNECT ",
+    b"OPTIONS ",
+    b"TRACE ",
+    b"PATCH ",
+];
+
+/// Protocol detected from the first bytes sent by the peer.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DetectedProtocol {
+    /// Length prefixed handshake packet used since 1.7.
+    Handshake,
+    /// Server list ping used before 1.7, starting with `0xFE` byte.
+    LegacyPing,
+    /// HTTP request.
+    Http,
+    /// Bytes don't match any known protocol.
+    Unknown,
+}
+
+/// Detected protocol together with what could be parsed from the first bytes.
+#[cfg(feature = "v1_14_4")]
+#[derive(Debug)]
+pub enum ProtocolReport {
+    Handshake {
+        /// Protocol version declared by the client.
+        protocol_version: i32,
+        /// Parsed handshake, `None` if packet is longer than vanilla allows or malformed.
+        handshake: Option<Handshake>,
+        /// Length of the handshake packet including length prefix.
+        packet_length: usize,
+    },
+    LegacyPing,
+    Http,
+    Unknown,
+}
+
+/// Handles connections after their protocol is detected.
+pub trait ConnectionHandler {
+    fn handle_handshake(&mut self, stream: TcpStream);
+
+    fn handle_legacy_ping(&mut self, stream: TcpStream);
+
+    fn handle_http(&mut self, stream: TcpStream);
+
+    /// Connection is dropped by default.
+    fn handle_unknown(&mut self, _stream: TcpStream) {}
+
+    /// Receives PROXY protocol header before the stream is passed to other methods. Called only
+    /// by `accept_proxied`.
+    fn handle_proxy_header(&mut self, _proxy_header: &ProxyHeader) {}
+}
+
+/// Detects protocol from the first bytes of the stream.
+///
+/// Returns `None` if more bytes are required to make a decision.
+pub fn detect(bytes: &[u8]) -> Option<DetectedProtocol> {
+    if *bytes.first()? == 0xFE {
+        return Some(DetectedProtocol::LegacyPing);
+    }
+
+    let mut incomplete = false;
+
+    for method in HTTP_METHODS.iter() {
+        if bytes.starts_with(method) {
+            return Some(DetectedProtocol::Http);
+        }
+
+        if method.starts_with(bytes) {
+            incomplete = true;
+        }
+    }
+
+    let mut cursor = Cursor::new(bytes);
+
+    match cursor.read_var_i32() {
+        Ok(length) if length >= HANDSHAKE_MIN_LENGTH => {
+            match bytes.get(cursor.position() as usize) {
+                Some(0x00) => return Some(DetectedProtocol::Handshake),
+                Some(_) => {}
+                None => incomplete = true,
+            }
+        }
+        Err(DecodeError::IOError { io_error }) if io_error.kind() == ErrorKind::UnexpectedEof => {
+            incomplete = true;
+        }
+        _ => {}
+    }
+
+    if incomplete {
+        None
+    } else {
+        Some(DetectedProtocol::Unknown)
+    }
+}
+
+/// Detects protocol like `detect` and parses the handshake.
+///
+/// Returns `None` if more bytes are required, whole handshake packet is needed unless it's too long
+/// to be parsed. Bytes after the handshake packet are never looked at. Malformed protocol version
+/// is reported as `ProtocolReport::Unknown`.
+#[cfg(feature = "v1_14_4")]
+pub fn report(bytes: &[u8]) -> Option<ProtocolReport> {
+    match detect(bytes)? {
+        DetectedProtocol::Handshake => {}
+        DetectedProtocol::LegacyPing => return Some(ProtocolReport::LegacyPing),
+        DetectedProtocol::Http => return Some(ProtocolReport::Http),
+        DetectedProtocol::Unknown => return Some(ProtocolReport::Unknown),
+    }
+
+    let mut cursor = Cursor::new(bytes);
+    let length = cursor.read_var_i32().ok()?;
+    let packet_length = cursor.position() as usize + length as usize;
+
+    cursor.set_position(cursor.position() + 1);
+
+    let protocol_version = match cursor.read_var_i32() {
+        Ok(protocol_version) => protocol_version,
+        Err(DecodeError::IOError { io_error }) if io_error.kind() == ErrorKind::UnexpectedEof => {
+            return None;
+        }
+        Err(_) => return Some(ProtocolReport::Unknown),
+    };
+
+    let handshake = if length > HANDSHAKE_MAX_LENGTH {
+        None
+    } else {
+        let packet = bytes.get(..packet_length)?;
+        let mut cursor = Cursor::new(&packet[packet_length - length as usize + 1..]);
+
+        Handshake::decode(&mut cursor)
+            .ok()
+            .filter(|_| cursor.position() as usize == cursor.get_ref().len())
+    };
+
+    Some(ProtocolReport::Handshake {
+        protocol_version,
+        handshake,
+        packet_length,
+    })
+}
+
+/// Peeks the first bytes of the stream until protocol is detected.
+///
+/// Bytes are not consumed. Returns `TimedOut` error if peer doesn't send enough bytes in time
+/// and `UnexpectedEof` error if peer closes connection before that.
+pub fn detect_stream(stream: &TcpStream, timeout: Duration) -> io::Result<DetectedProtocol> {
+    let previous_timeout = stream.read_timeout()?;
+    let result = peek_until(stream, timeout, &mut [0; PEEK_LENGTH], detect);
+
+    stream.set_read_timeout(previous_timeout)?;
+
+    result
+}
+
+/// Peeks the first bytes of the stream until `report` can be made.
+///
+/// Bytes are not consumed, errors are the same as `detect_stream` returns.
+#[cfg(feature = "v1_14_4")]
+pub fn report_stream(stream: &TcpStream, timeout: Duration) -> io::Result<ProtocolReport> {
+    // Length prefix of the longest handshake takes 2 bytes.
+    let mut buf = [0; HANDSHAKE_MAX_LENGTH as usize + 2];
+
+    let previous_timeout = stream.read_timeout()?;
+    let result = peek_until(stream, timeout, &mut buf, report);
+
+    stream.set_read_timeout(previous_timeout)?;
+
+    result
+}
+
+/// Detects stream protocol and passes stream to the matching handler method.
+pub fn accept<H: ConnectionHandler>(
+    stream: TcpStream,
+    handler: &mut H,
+    timeout: Duration,
+) -> io::Result<()> {
+    match detect_stream(&stream, timeout)? {
+        DetectedProtocol::Handshake => handler.handle_handshake(stream),
+        DetectedProtocol::LegacyPing => handler.handle_legacy_ping(stream),
+        DetectedProtocol::Http => handler.handle_http(stream),
+        DetectedProtocol::Unknown => handler.handle_unknown(stream),
+    }
+
+    Ok(())
+}
+
+/// Reads PROXY protocol header and then works like `accept`.
+///
+/// Header is required, so this must be used only if server is reachable through proxy alone.
+/// Returns `InvalidData` error if header is missing or malformed.
+pub fn accept_proxied<H: ConnectionHandler>(
+    stream: TcpStream,
+    handler: &mut H,
+    timeout: Duration,
+) -> io::Result<()> {
+    let deadline = Instant::now() + timeout;
+    let previous_timeout = stream.read_timeout()?;
+
+    stream.set_read_timeout(Some(timeout))?;
+    let result = ProxyHeader::read(&mut &stream);
+    stream.set_read_timeout(previous_timeout)?;
+
+    let proxy_header = match result {
+        Ok(proxy_header) => proxy_header,
+        Err(e) if e.kind() == ErrorKind::WouldBlock => return Err(ErrorKind::TimedOut.into()),
+        Err(e) => return Err(e),
+    };
+
+    handler.handle_proxy_header(&proxy_header);
+
+    accept(
+        stream,
+        handler,
+        deadline.saturating_duration_since(Instant::now()),
+    )
+}
+
+fn peek_until<T>(
+    stream: &TcpStream,
+    timeout: Duration,
+    buf: &mut [u8],
+    detect: fn(&[u8]) -> Option<T>,
+) -> io::Result<T> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        if remaining == Duration::from_secs(0) {
+            return Err(ErrorKind::TimedOut.into());
+        }
+
+        stream.set_read_timeout(Some(remaining))?;
+
+        let length = match stream.peek(buf) {
+            Ok(length) => length,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => return Err(ErrorKind::TimedOut.into()),
+            Err(e) => return Err(e),
+        };
+
+        if length == 0 {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+
+        if let Some(value) = detect(&buf[..length]) {
+            return Ok(value);
+        }
+
+        thread::sleep(PEEK_INTERVAL.min(remaining));
+    }
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::acceptor::*;
+    use crate::encoder::Encoder;
+    use crate::version::v1_14_4::handshake::{Handshake, NextState};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_detect_handshake() {
+        let handshake = Handshake {
+            protocol_version: 498,
+            server_addr: String::from("localhost"),
+            server_port: 25565,
+            next_state: NextState::Status,
+        };
+
+        let mut bytes = Vec::new();
+        handshake.encode(&mut bytes).unwrap();
+
+        let mut packet = vec![bytes.len() as u8 + 1, 0x00];
+        packet.extend_from_slice(&bytes);
+
+        assert_eq!(detect(&packet), Some(DetectedProtocol::Handshake));
+        assert_eq!(detect(&packet[..1]), None);
+    }
+
+    #[test]
+    fn test_detect_legacy_ping() {
+        assert_eq!(detect(&[0xFE, 0x01]), Some(DetectedProtocol::LegacyPing));
+    }
+
+    #[test]
+    fn test_detect_http() {
+        assert_eq!(detect(b"GET / HTTP/1.1"), Some(DetectedProtocol::Http));
+        assert_eq!(detect(b"OPTIO"), None);
+    }
+
+    #[test]
+    fn test_detect_unknown() {
+        assert_eq!(detect(b"\x16\x03\x01"), Some(DetectedProtocol::Unknown));
+        assert_eq!(detect(&[0x02, 0x00]), Some(DetectedProtocol::Unknown));
+    }
+
+    #[test]
+    fn test_report_handshake() {
+        let handshake = Handshake {
+            protocol_version: 754,
+            server_addr: String::from("localhost"),
+            server_port: 25565,
+            next_state: NextState::Login,
+        };
+
+        let mut bytes = Vec::new();
+        handshake.encode(&mut bytes).unwrap();
+
+        let mut packet = vec![bytes.len() as u8 + 1, 0x00];
+        packet.extend_from_slice(&bytes);
+        packet.push(0xFF);
+
+        assert!(report(&packet[..3]).is_none());
+        assert!(report(&packet[..packet.len() - 2]).is_none());
+
+        match report(&packet).unwrap() {
+            ProtocolReport::Handshake {
+                protocol_version,
+                handshake,
+                packet_length,
+            } => {
+                assert_eq!(protocol_version, 754);
+                assert_eq!(packet_length, packet.len() - 1);
+
+                let handshake = handshake.expect("Expected parsed handshake");
+                assert_eq!(handshake.server_addr, "localhost");
+                assert_eq!(handshake.next_state, NextState::Login);
+            }
+            report => panic!("Expected handshake but got `{:?}`", report),
+        }
+    }
+
+    #[test]
+    fn test_report_oversized_handshake() {
+        // Length of 1000 bytes, packet id and protocol version 47.
+        let packet = [0xE8, 0x07, 0x00, 0x2F];
+
+        match report(&packet).unwrap() {
+            ProtocolReport::Handshake {
+                protocol_version,
+                handshake,
+                packet_length,
+            } => {
+                assert_eq!(protocol_version, 47);
+                assert!(handshake.is_none());
+                assert_eq!(packet_length, 1002);
+            }
+            report => panic!("Expected handshake but got `{:?}`", report),
+        }
+    }
+
+    #[test]
+    fn test_report_malformed_protocol_version() {
+        // Protocol version var int doesn't end after 5 bytes.
+        let packet = [0x10, 0x00, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80];
+
+        assert!(report(&packet[..4]).is_none());
+        assert!(matches!(report(&packet), Some(ProtocolReport::Unknown)));
+    }
+
+    #[test]
+    fn test_report_legacy_ping() {
+        assert!(matches!(
+            report(&[0xFE, 0x01]),
+            Some(ProtocolReport::LegacyPing)
+        ));
+    }
+
+    struct Handler {
+        http: Option<TcpStream>,
+        proxy_header: Option<ProxyHeader>,
+    }
+
+    impl ConnectionHandler for Handler {
+        fn handle_handshake(&mut self, _stream: TcpStream) {
+            panic!("Expected HTTP connection");
+        }
+
+        fn handle_legacy_ping(&mut self, _stream: TcpStream) {
+            panic!("Expected HTTP connection");
+        }
+
+        fn handle_http(&mut self, stream: TcpStream) {
+            self.http = Some(stream);
+        }
+
+        fn handle_proxy_header(&mut self, proxy_header: &ProxyHeader) {
+            self.proxy_header = Some(*proxy_header);
+        }
+    }
+
+    #[test]
+    fn test_accept_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+        client.write_all(b"GE").unwrap();
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            client.write_all(b"T / HTTP/1.1\r\n").unwrap();
+            client
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut handler = Handler {
+            http: None,
+            proxy_header: None,
+        };
+
+        accept(stream, &mut handler, Duration::from_secs(5)).unwrap();
+        let _client = writer.join().unwrap();
+
+        let mut stream = handler.http.expect("Expected HTTP connection");
+        let mut buf = [0; 5];
+        stream.read_exact(&mut buf).unwrap();
+
+        assert_eq!(&buf, b"GET /");
+    }
+
+    #[test]
+    fn test_accept_proxied() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+        client
+            .write_all(b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 25565\r\nGET / HTTP/1.1\r\n")
+            .unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut handler = Handler {
+            http: None,
+            proxy_header: None,
+        };
+
+        accept_proxied(stream, &mut handler, Duration::from_secs(5)).unwrap();
+
+        assert_eq!(
+            handler.proxy_header,
+            Some(ProxyHeader::Proxied {
+                source: "192.168.0.1:56324".parse().unwrap(),
+                destination: "192.168.0.11:25565".parse().unwrap(),
+            })
+        );
+
+        let mut stream = handler.http.expect("Expected HTTP connection");
+        let mut buf = [0; 5];
+        stream.read_exact(&mut buf).unwrap();
+
+        assert_eq!(&buf, b"GET /");
+    }
+
+    #[test]
+    fn test_detect_stream_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let _client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+
+        let error = detect_stream(&stream, Duration::from_millis(20))
+            .expect_err("Expected error because peer didn't send any bytes");
+
+        assert_eq!(error.kind(), ErrorKind::TimedOut);
+        assert_eq!(stream.read_timeout().unwrap(), None);
+    }
+}
@@ -0,0 +1,921 @@
+//! Packet framing over async streams of tokio.
+//!
+//! `AsyncDecoder` and `AsyncEncoder` read and write whole packet frames on `AsyncRead` and
+//! `AsyncWrite` streams, e.g. `tokio::net::TcpStream`, without blocking the runtime. Only the frame
+//! is read asynchronously, packet data is decoded from memory with sync `Decoder` once the frame
+//! is complete.
+//!
+//! `AsyncPacketCodec` is async equivalent of `PacketCodec`, it follows compression threshold and
+//! max packet length of the connection. Codec keeps received bytes in its own buffer, so
+//! `AsyncPacketCodec::read_raw_packet` is cancel safe: future dropped in the middle of a frame,
+//! e.g. by `tokio::select!` or `read_packet_timeout`, loses nothing and the next read continues
+//! the frame. `AsyncDecoder` reads straight from the stream and is not cancel safe.
+//!
+//! `AsyncPacketCodec::into_split` splits codec into halves for reading and writing in separate
+//! tasks, which share negotiated settings like halves of `split` module.
+//!
+//! Reader ending before or inside a frame is reported as `DecodeError::Incomplete` as in `packet`
+//! module.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::async_io::{AsyncDecoder, AsyncEncoder};
+//! use minecraft_protocol::version::v1_14_4::game::{ClientBoundKeepAlive, GameClientBoundPacket};
+//!
+//! # let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+//! # runtime.block_on(async {
+//! let mut stream = Vec::new();
+//! ClientBoundKeepAlive::new(42)
+//!     .encode_async(&mut stream)
+//!     .await
+//!     .unwrap();
+//!
+//! let mut reader = stream.as_slice();
+//! let packet = GameClientBoundPacket::decode_async(&mut reader).await.unwrap();
+//!
+//! assert!(matches!(packet, GameClientBoundPacket::ClientBoundKeepAlive(_)));
+//! # });
+//! # }
+//! ```
+use crate::codec::FlushPolicy;
+use crate::compression::CompressedRawPacket;
+use crate::decoder::DecoderReadExt;
+use crate::encoder::{var_i32_size, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use crate::limits::{self, DecodeLimits};
+use crate::packet::{Packet, RawPacket, PACKET_MAX_LENGTH};
+use crate::split::SharedState;
+use std::future::{poll_fn, Future};
+use std::io::{self, ErrorKind};
+use std::pin::Pin;
+use std::task::{ready, Poll};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Var int takes at most 5 bytes.
+const VAR_INT_MAX_BYTES: usize = 5;
+
+/// Max count of bytes requested from stream by one read.
+const READ_BUFFER_LENGTH: usize = 4096;
+
+/// Packet frame which can be read from async stream.
+pub trait AsyncDecoder: Sized {
+    fn decode_async<R>(reader: &mut R) -> impl Future<Output = Result<Self, DecodeError>> + Send
+    where
+        R: AsyncRead + Unpin + Send;
+}
+
+/// Packet frame which can be written to async stream.
+pub trait AsyncEncoder {
+    fn encode_async<W>(
+        &self,
+        writer: &mut W,
+    ) -> impl Future<Output = Result<(), EncodeError>> + Send
+    where
+        W: AsyncWrite + Unpin + Send;
+}
+
+impl AsyncDecoder for RawPacket {
+    /// Reads length prefixed frame, see `RawPacket::decode`.
+    async fn decode_async<R>(reader: &mut R) -> Result<Self, DecodeError>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        RawPacket::from_body(read_frame(reader, PACKET_MAX_LENGTH).await?)
+    }
+}
+
+impl<P: Packet> AsyncDecoder for P {
+    async fn decode_async<R>(reader: &mut R) -> Result<Self, DecodeError>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        RawPacket::decode_async(reader).await?.decode_packet()
+    }
+}
+
+impl AsyncEncoder for RawPacket {
+    /// Writes length prefixed frame.
+    async fn encode_async<W>(&self, writer: &mut W) -> Result<(), EncodeError>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        let mut frame = self.header()?;
+        frame.extend_from_slice(&self.data);
+
+        Ok(write_all(writer, &frame).await?)
+    }
+}
+
+impl<P: Packet + Sync> AsyncEncoder for P {
+    async fn encode_async<W>(&self, writer: &mut W) -> Result<(), EncodeError>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        RawPacket::from_packet(self)?.encode_async(writer).await
+    }
+}
+
+/// Packet codec for async connection streams.
+#[derive(Debug)]
+pub struct AsyncPacketCodec<S> {
+    stream: S,
+    compression_threshold: Option<usize>,
+    max_packet_length: usize,
+    flush_policy: FlushPolicy,
+    decode_limits: Option<DecodeLimits>,
+    read_buffer: Vec<u8>,
+}
+
+impl<S> AsyncPacketCodec<S> {
+    pub fn new(stream: S) -> Self {
+        AsyncPacketCodec {
+            stream,
+            compression_threshold: None,
+            max_packet_length: PACKET_MAX_LENGTH,
+            flush_policy: FlushPolicy::default(),
+            decode_limits: None,
+            read_buffer: Vec::new(),
+        }
+    }
+
+    /// Codec flushes only after packets, bundles are not supported.
+    pub fn with_flush_policy(mut self, flush_policy: FlushPolicy) -> Self {
+        self.flush_policy = flush_policy;
+        self
+    }
+
+    pub fn flush_policy(&self) -> FlushPolicy {
+        self.flush_policy
+    }
+
+    pub fn set_flush_policy(&mut self, flush_policy: FlushPolicy) {
+        self.flush_policy = flush_policy;
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.compression_threshold
+    }
+
+    pub fn set_compression_threshold(&mut self, compression_threshold: Option<usize>) {
+        self.compression_threshold = compression_threshold;
+    }
+
+    pub fn with_max_packet_length(mut self, max_packet_length: usize) -> Self {
+        self.max_packet_length = max_packet_length;
+        self
+    }
+
+    pub fn max_packet_length(&self) -> usize {
+        self.max_packet_length
+    }
+
+    pub fn set_max_packet_length(&mut self, max_packet_length: usize) {
+        self.max_packet_length = max_packet_length;
+    }
+
+    /// Decodes packets with limits instead of limits of the thread, see `limits` module.
+    pub fn with_decode_limits(mut self, decode_limits: DecodeLimits) -> Self {
+        self.decode_limits = Some(decode_limits);
+        self
+    }
+
+    /// Returns limits set for codec, `None` if packets are decoded with limits of the thread.
+    pub fn decode_limits(&self) -> Option<DecodeLimits> {
+        self.decode_limits
+    }
+
+    pub fn set_decode_limits(&mut self, decode_limits: Option<DecodeLimits>) {
+        self.decode_limits = decode_limits;
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Returns stream, bytes received but not read as packets yet are lost.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    /// Applies shared settings of split halves.
+    fn apply(&mut self, state: &SharedState) {
+        self.compression_threshold = state.compression_threshold();
+        self.max_packet_length = state.max_packet_length();
+    }
+
+    /// Removes first frame from read buffer and returns its body, `None` if frame is not
+    /// received completely.
+    fn take_frame(&mut self) -> Result<Option<Vec<u8>>, DecodeError> {
+        let mut reader = self.read_buffer.as_slice();
+
+        let length = match reader.read_var_i32() {
+            Ok(length) => length as u32 as usize,
+            Err(DecodeError::IOError { io_error })
+                if io_error.kind() == ErrorKind::UnexpectedEof =>
+            {
+                return Ok(None);
+            }
+            Err(decode_error) => return Err(decode_error),
+        };
+
+        if length > self.max_packet_length {
+            return Err(DecodeError::PacketTooLong {
+                length,
+                max_length: self.max_packet_length,
+            });
+        }
+
+        let prefix_length = self.read_buffer.len() - reader.len();
+        let frame_length = prefix_length + length;
+
+        if self.read_buffer.len() < frame_length {
+            return Ok(None);
+        }
+
+        let body = self.read_buffer[prefix_length..frame_length].to_vec();
+        self.read_buffer.drain(..frame_length);
+
+        Ok(Some(body))
+    }
+
+    /// Returns error for stream ended with read buffer content.
+    fn incomplete(&self) -> DecodeError {
+        let mut reader = self.read_buffer.as_slice();
+
+        let needed = match reader.read_var_i32() {
+            Ok(length) => {
+                let prefix_length = self.read_buffer.len() - reader.len();
+                Some(prefix_length + length as u32 as usize - self.read_buffer.len())
+            }
+            Err(_) => None,
+        };
+
+        DecodeError::Incomplete { needed }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncPacketCodec<S> {
+    /// Returns `DecodeError::PacketTooLong` if frame is longer than max packet length.
+    ///
+    /// Cancel safe, bytes of the frame received before future is dropped stay buffered.
+    pub async fn read_raw_packet(&mut self) -> Result<RawPacket, DecodeError> {
+        let body = loop {
+            if let Some(body) = self.take_frame()? {
+                break body;
+            }
+
+            if read_buf(&mut self.stream, &mut self.read_buffer).await? == 0 {
+                return Err(self.incomplete());
+            }
+        };
+
+        match self.compression_threshold {
+            Some(threshold) => {
+                CompressedRawPacket::from_body(body, threshold).map(|p| p.into_inner())
+            }
+            None => RawPacket::from_body(body),
+        }
+    }
+
+    /// Reads frame, giving up with `ErrorKind::TimedOut` error once deadline passes.
+    ///
+    /// Frame already buffered is returned even if deadline passed. Bytes of the frame received
+    /// before deadline stay buffered, so the next read continues the frame.
+    pub async fn read_raw_packet_deadline(
+        &mut self,
+        deadline: Instant,
+    ) -> Result<RawPacket, DecodeError> {
+        let deadline = tokio::time::Instant::from_std(deadline);
+
+        match tokio::time::timeout_at(deadline, self.read_raw_packet()).await {
+            Ok(result) => result,
+            Err(_) => Err(io::Error::from(ErrorKind::TimedOut).into()),
+        }
+    }
+
+    /// Reads frame waiting at most `timeout`. See `read_raw_packet_deadline`.
+    pub async fn read_raw_packet_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<RawPacket, DecodeError> {
+        self.read_raw_packet_deadline(Instant::now() + timeout)
+            .await
+    }
+
+    pub async fn read_packet<P: Packet>(&mut self) -> Result<P, DecodeError> {
+        let raw_packet = self.read_raw_packet().await?;
+
+        limits::with_optional_limits(self.decode_limits, || raw_packet.decode_packet())
+    }
+
+    pub async fn read_packet_deadline<P: Packet>(
+        &mut self,
+        deadline: Instant,
+    ) -> Result<P, DecodeError> {
+        let raw_packet = self.read_raw_packet_deadline(deadline).await?;
+
+        limits::with_optional_limits(self.decode_limits, || raw_packet.decode_packet())
+    }
+
+    pub async fn read_packet_timeout<P: Packet>(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<P, DecodeError> {
+        let raw_packet = self.read_raw_packet_timeout(timeout).await?;
+
+        limits::with_optional_limits(self.decode_limits, || raw_packet.decode_packet())
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncPacketCodec<S> {
+    /// Returns `EncodeError::PacketTooLong` if frame is longer than max packet length.
+    pub async fn write_raw_packet(&mut self, raw_packet: &RawPacket) -> Result<(), EncodeError> {
+        let frame = match self.compression_threshold {
+            Some(threshold) => {
+                let body = CompressedRawPacket::from(raw_packet.clone()).encode_body(threshold)?;
+                self.check_length(body.len())?;
+
+                let mut frame = Vec::with_capacity(var_i32_size(body.len() as i32) + body.len());
+                frame.write_var_i32(body.len() as i32)?;
+                frame.extend_from_slice(&body);
+
+                frame
+            }
+            None => {
+                self.check_length(var_i32_size(raw_packet.id) + raw_packet.data.len())?;
+
+                let mut frame = raw_packet.header()?;
+                frame.extend_from_slice(&raw_packet.data);
+
+                frame
+            }
+        };
+
+        write_all(&mut self.stream, &frame).await?;
+
+        if self.flush_policy != FlushPolicy::Manual {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn write_packet<P: Packet>(&mut self, packet: &P) -> Result<(), EncodeError> {
+        self.write_raw_packet(&RawPacket::from_packet(packet)?)
+            .await
+    }
+
+    pub async fn flush(&mut self) -> Result<(), EncodeError> {
+        Ok(poll_fn(|cx| Pin::new(&mut self.stream).poll_flush(cx)).await?)
+    }
+
+    fn check_length(&self, length: usize) -> Result<(), EncodeError> {
+        if length > self.max_packet_length {
+            return Err(EncodeError::PacketTooLong {
+                length,
+                max_length: self.max_packet_length,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite> AsyncPacketCodec<S> {
+    /// Splits codec into halves over read and write handles of its stream.
+    ///
+    /// Flush policy stays with writing half, decode limits and received bytes go to reading half.
+    pub fn into_split(self) -> AsyncHalves<S> {
+        let state = SharedState::new(self.compression_threshold, self.max_packet_length);
+        let (reader, writer) = tokio::io::split(self.stream);
+
+        let read_codec = AsyncPacketCodec {
+            stream: reader,
+            compression_threshold: self.compression_threshold,
+            max_packet_length: self.max_packet_length,
+            flush_policy: FlushPolicy::default(),
+            decode_limits: self.decode_limits,
+            read_buffer: self.read_buffer,
+        };
+        let write_codec = AsyncPacketCodec {
+            stream: writer,
+            compression_threshold: self.compression_threshold,
+            max_packet_length: self.max_packet_length,
+            flush_policy: self.flush_policy,
+            decode_limits: None,
+            read_buffer: Vec::new(),
+        };
+
+        let read_half = AsyncReadHalf {
+            codec: read_codec,
+            state: state.clone(),
+        };
+        let write_half = AsyncWriteHalf {
+            codec: write_codec,
+            state,
+        };
+
+        (read_half, write_half)
+    }
+}
+
+/// Halves of async codec over stream `S`.
+pub type AsyncHalves<S> = (
+    AsyncReadHalf<tokio::io::ReadHalf<S>>,
+    AsyncWriteHalf<tokio::io::WriteHalf<S>>,
+);
+
+/// Reading half of split async codec.
+#[derive(Debug)]
+pub struct AsyncReadHalf<R> {
+    codec: AsyncPacketCodec<R>,
+    state: SharedState,
+}
+
+impl<R> AsyncReadHalf<R> {
+    pub fn state(&self) -> &SharedState {
+        &self.state
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.state.compression_threshold()
+    }
+
+    /// Changes compression of both halves, see `SharedState::set_compression_threshold`.
+    pub fn set_compression_threshold(&self, compression_threshold: Option<usize>) {
+        self.state.set_compression_threshold(compression_threshold);
+    }
+
+    pub fn get_ref(&self) -> &R {
+        self.codec.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        self.codec.get_mut()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.codec.into_inner()
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncReadHalf<R> {
+    /// Cancel safe, see `AsyncPacketCodec::read_raw_packet`.
+    pub async fn read_raw_packet(&mut self) -> Result<RawPacket, DecodeError> {
+        self.codec.apply(&self.state);
+        self.codec.read_raw_packet().await
+    }
+
+    pub async fn read_packet<P: Packet>(&mut self) -> Result<P, DecodeError> {
+        self.codec.apply(&self.state);
+        self.codec.read_packet().await
+    }
+
+    pub async fn read_packet_deadline<P: Packet>(
+        &mut self,
+        deadline: Instant,
+    ) -> Result<P, DecodeError> {
+        self.codec.apply(&self.state);
+        self.codec.read_packet_deadline(deadline).await
+    }
+
+    pub async fn read_packet_timeout<P: Packet>(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<P, DecodeError> {
+        self.codec.apply(&self.state);
+        self.codec.read_packet_timeout(timeout).await
+    }
+}
+
+/// Writing half of split async codec.
+#[derive(Debug)]
+pub struct AsyncWriteHalf<W> {
+    codec: AsyncPacketCodec<W>,
+    state: SharedState,
+}
+
+impl<W> AsyncWriteHalf<W> {
+    pub fn state(&self) -> &SharedState {
+        &self.state
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.state.compression_threshold()
+    }
+
+    /// Changes compression of both halves, see `SharedState::set_compression_threshold`.
+    pub fn set_compression_threshold(&self, compression_threshold: Option<usize>) {
+        self.state.set_compression_threshold(compression_threshold);
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.codec.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        self.codec.get_mut()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.codec.into_inner()
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWriteHalf<W> {
+    /// Returns `EncodeError::PacketTooLong` if frame is longer than max packet length.
+    pub async fn write_raw_packet(&mut self, raw_packet: &RawPacket) -> Result<(), EncodeError> {
+        self.codec.apply(&self.state);
+        self.codec.write_raw_packet(raw_packet).await
+    }
+
+    pub async fn write_packet<P: Packet>(&mut self, packet: &P) -> Result<(), EncodeError> {
+        self.codec.apply(&self.state);
+        self.codec.write_packet(packet).await
+    }
+
+    pub async fn flush(&mut self) -> Result<(), EncodeError> {
+        self.codec.flush().await
+    }
+}
+
+/// Reads length prefixed frame and returns its body, see `packet::read_frame`.
+async fn read_frame<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    max_length: usize,
+) -> Result<Vec<u8>, DecodeError> {
+    let mut prefix = [0; VAR_INT_MAX_BYTES];
+    let mut prefix_length = 0;
+
+    loop {
+        if prefix_length == VAR_INT_MAX_BYTES {
+            return Err(DecodeError::VarIntTooLong {
+                max_bytes: VAR_INT_MAX_BYTES,
+            });
+        }
+
+        let byte = &mut prefix[prefix_length..prefix_length + 1];
+
+        if read_exact(reader, byte).await? == 0 {
+            return Err(DecodeError::Incomplete { needed: None });
+        }
+
+        prefix_length += 1;
+
+        if byte[0] & 0b10000000 == 0 {
+            break;
+        }
+    }
+
+    let length = (&prefix[..prefix_length]).read_var_i32()? as u32 as usize;
+
+    if length > max_length {
+        return Err(DecodeError::PacketTooLong { length, max_length });
+    }
+
+    // Buffer grows as body arrives, declared length alone doesn't allocate.
+    let mut buf = Vec::new();
+
+    while buf.len() < length {
+        let start = buf.len();
+        let end = start + (length - start).min(READ_BUFFER_LENGTH);
+        buf.resize(end, 0);
+
+        let read = read_exact(reader, &mut buf[start..]).await?;
+
+        if start + read < end {
+            return Err(DecodeError::Incomplete {
+                needed: Some(length - start - read),
+            });
+        }
+    }
+
+    Ok(buf)
+}
+
+/// Appends at most `READ_BUFFER_LENGTH` bytes read from reader to buffer and returns their count,
+/// which is zero only if reader has ended.
+///
+/// Cancel safe, bytes are appended in the same poll they are read.
+async fn read_buf<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut Vec<u8>) -> io::Result<usize> {
+    let mut chunk = [0; READ_BUFFER_LENGTH];
+
+    let read = poll_fn(|cx| {
+        let mut read_buf = ReadBuf::new(&mut chunk);
+        ready!(Pin::new(&mut *reader).poll_read(cx, &mut read_buf))?;
+
+        Poll::Ready(Ok::<_, io::Error>(read_buf.filled().len()))
+    })
+    .await?;
+
+    buf.extend_from_slice(&chunk[..read]);
+
+    Ok(read)
+}
+
+/// Fills buffer and returns count of bytes read, which is less than buffer length only if reader
+/// has ended.
+async fn read_exact<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+
+    while filled < buf.len() {
+        let read = poll_fn(|cx| {
+            let mut read_buf = ReadBuf::new(&mut buf[filled..]);
+            ready!(Pin::new(&mut *reader).poll_read(cx, &mut read_buf))?;
+
+            Poll::Ready(Ok::<_, io::Error>(read_buf.filled().len()))
+        })
+        .await?;
+
+        if read == 0 {
+            break;
+        }
+
+        filled += read;
+    }
+
+    Ok(filled)
+}
+
+async fn write_all<W: AsyncWrite + Unpin>(writer: &mut W, mut buf: &[u8]) -> io::Result<()> {
+    while !buf.is_empty() {
+        let written = poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, buf)).await?;
+
+        if written == 0 {
+            return Err(io::Error::from(ErrorKind::WriteZero));
+        }
+
+        buf = &buf[written..];
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::async_io::*;
+    use crate::codec::PacketCodec;
+    use crate::version::v1_14_4::game::{
+        ClientBoundKeepAlive, GameServerBoundPacket, ServerBoundChatMessage,
+    };
+    use crate::version::v1_14_4::login::{LoginClientBoundPacket, SetCompression};
+    use std::sync::Arc;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::Notify;
+
+    const THRESHOLD: usize = 64;
+
+    /// Reader returning one byte per read, as slow connection does.
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl AsyncRead for ByteReader<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            if let Some((byte, rest)) = self.0.split_first() {
+                buf.put_slice(&[*byte]);
+                self.0 = rest;
+            }
+
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn chat_message() -> GameServerBoundPacket {
+        ServerBoundChatMessage::new("a".repeat(200))
+    }
+
+    #[tokio::test]
+    async fn test_frames_match_sync_codec() {
+        let mut sync_codec = PacketCodec::new(Vec::new());
+        sync_codec.write_packet(&chat_message()).unwrap();
+
+        let mut stream = Vec::new();
+        chat_message().encode_async(&mut stream).await.unwrap();
+
+        assert_eq!(stream, sync_codec.into_inner());
+    }
+
+    #[tokio::test]
+    async fn test_read_packet_split_into_bytes() {
+        let mut stream = Vec::new();
+        RawPacket::from_packet(&ClientBoundKeepAlive::new(42))
+            .unwrap()
+            .encode_async(&mut stream)
+            .await
+            .unwrap();
+
+        let mut reader = ByteReader(&stream);
+        let raw_packet = RawPacket::decode_async(&mut reader).await.unwrap();
+
+        assert_eq!(
+            raw_packet,
+            RawPacket::from_packet(&ClientBoundKeepAlive::new(42)).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_frame() {
+        let mut stream = Vec::new();
+        RawPacket::new(0x01, vec![0; 8])
+            .encode_async(&mut stream)
+            .await
+            .unwrap();
+        stream.truncate(6);
+
+        let decode_error = RawPacket::decode_async(&mut stream.as_slice())
+            .await
+            .expect_err("Expected error `Incomplete` because frame is truncated");
+
+        match decode_error {
+            DecodeError::Incomplete { needed } => assert_eq!(needed, Some(4)),
+            _ => panic!("Expected `Incomplete` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_codec_compression_roundtrip() {
+        let mut codec = AsyncPacketCodec::new(Vec::new());
+        codec.set_compression_threshold(Some(64));
+        codec.write_packet(&chat_message()).await.unwrap();
+
+        let mut sync_codec = PacketCodec::new(Vec::new());
+        sync_codec.set_compression_threshold(Some(64));
+        sync_codec.write_packet(&chat_message()).unwrap();
+
+        let stream = codec.into_inner();
+        assert_eq!(stream, sync_codec.into_inner());
+
+        let mut codec = AsyncPacketCodec::new(stream.as_slice());
+        codec.set_compression_threshold(Some(64));
+        let packet: GameServerBoundPacket = codec.read_packet().await.unwrap();
+
+        assert!(matches!(
+            packet,
+            GameServerBoundPacket::ServerBoundChatMessage(message) if message.message == "a".repeat(200)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_cancelled_mid_frame() {
+        let mut frame = Vec::new();
+        chat_message().encode_async(&mut frame).await.unwrap();
+
+        let (mut client, server) = tokio::io::duplex(1024);
+        let mut codec = AsyncPacketCodec::new(server);
+
+        write_all(&mut client, &frame[..100]).await.unwrap();
+
+        let decode_error = codec
+            .read_packet_timeout::<GameServerBoundPacket>(Duration::from_millis(10))
+            .await
+            .expect_err("Expected error `IOError` because frame is not received in time");
+
+        match decode_error {
+            DecodeError::IOError { io_error } => assert_eq!(io_error.kind(), ErrorKind::TimedOut),
+            _ => panic!("Expected `IOError` but got `{:?}`", decode_error),
+        }
+
+        // Read dropped in the middle of the frame keeps received bytes.
+        write_all(&mut client, &frame[100..]).await.unwrap();
+
+        let packet: GameServerBoundPacket = codec
+            .read_packet_deadline(Instant::now() + Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            packet,
+            GameServerBoundPacket::ServerBoundChatMessage(message) if message.message == "a".repeat(200)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_codec_incomplete_frame() {
+        let mut stream = Vec::new();
+        RawPacket::new(0x01, vec![0; 8])
+            .encode_async(&mut stream)
+            .await
+            .unwrap();
+        stream.truncate(6);
+
+        let mut codec = AsyncPacketCodec::new(stream.as_slice());
+        let decode_error = codec
+            .read_raw_packet()
+            .await
+            .expect_err("Expected error `Incomplete` because frame is truncated");
+
+        match decode_error {
+            DecodeError::Incomplete { needed } => assert_eq!(needed, Some(4)),
+            _ => panic!("Expected `Incomplete` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_codec_max_packet_length() {
+        let mut codec = AsyncPacketCodec::new(Vec::new()).with_max_packet_length(8);
+        let encode_error = codec
+            .write_packet(&ClientBoundKeepAlive::new(1))
+            .await
+            .expect_err("Expected error `PacketTooLong` because frame is longer than 8 bytes");
+
+        match encode_error {
+            EncodeError::PacketTooLong { length, max_length } => {
+                assert_eq!(length, 9);
+                assert_eq!(max_length, 8);
+            }
+            _ => panic!("Expected `PacketTooLong` but got `{:?}`", encode_error),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_halves_in_tasks_share_compression() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+
+        // Server enables compression, then echoes packet, which it reads only if client
+        // compresses it too.
+        let server = tokio::spawn(async move {
+            let mut codec = AsyncPacketCodec::new(server);
+
+            codec
+                .write_packet(&SetCompression::new(THRESHOLD as i32))
+                .await
+                .unwrap();
+            codec.set_compression_threshold(Some(THRESHOLD));
+
+            let raw_packet = codec.read_raw_packet().await.unwrap();
+            codec.write_raw_packet(&raw_packet).await.unwrap();
+
+            raw_packet
+        });
+
+        let (mut read_half, mut write_half) = AsyncPacketCodec::new(client).into_split();
+        let negotiated = Arc::new(Notify::new());
+        let reader_negotiated = negotiated.clone();
+
+        let reader = tokio::spawn(async move {
+            match read_half
+                .read_packet::<LoginClientBoundPacket>()
+                .await
+                .unwrap()
+            {
+                LoginClientBoundPacket::SetCompression(set_compression) => {
+                    read_half.set_compression_threshold(Some(set_compression.threshold as usize))
+                }
+                packet => panic!("Expected `SetCompression` but got `{:?}`", packet),
+            }
+
+            reader_negotiated.notify_one();
+
+            read_half.read_raw_packet().await.unwrap()
+        });
+
+        // Writer task waits for compression negotiated by reading task.
+        let writer = tokio::spawn(async move {
+            negotiated.notified().await;
+            assert_eq!(write_half.compression_threshold(), Some(THRESHOLD));
+
+            write_half.write_packet(&chat_message()).await
+        });
+
+        writer.await.unwrap().unwrap();
+
+        let expected = RawPacket::from_packet(&chat_message()).unwrap();
+        assert_eq!(reader.await.unwrap(), expected);
+        assert_eq!(server.await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_halves_max_packet_length_shared() {
+        let (client, _server) = tokio::io::duplex(1024);
+        let (read_half, mut write_half) = AsyncPacketCodec::new(client).into_split();
+
+        read_half.state().set_max_packet_length(8);
+        let encode_error = write_half
+            .write_packet(&ClientBoundKeepAlive::new(1))
+            .await
+            .expect_err("Expected error `PacketTooLong` because max length is shared");
+
+        match encode_error {
+            EncodeError::PacketTooLong { length, max_length } => {
+                assert_eq!(length, 9);
+                assert_eq!(max_length, 8);
+            }
+            _ => panic!("Expected `PacketTooLong` but got `{:?}`", encode_error),
+        }
+    }
+}
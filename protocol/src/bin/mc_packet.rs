@@ -0,0 +1,340 @@
+//! Command-line tool for decoding and encoding Minecraft packets.
+//!
+//! Packet bytes are the packet id var int followed by the packet data, without the length
+//! prefix. They are read from stdin as hex (or base64) and printed as JSON, or read from stdin as
+//! JSON and printed as hex (or base64).
+//!
+//! ```text
+//! $ echo "20 00 00 00 00 00 03 ac 4a" | mc-packet decode --version 1.14.4 --state game --bound client
+//! {
+//!   "ClientBoundKeepAlive": {
+//!     "id": 240714
+//!   }
+//! }
+//!
+//! $ echo '{"ClientBoundKeepAlive":{"id":240714}}' | mc-packet encode --version 1.14.4 --state game --bound client
+//! 20000000000003ac4a
+//! ```
+use base64::Engine;
+use minecraft_protocol::decoder::DecoderReadExt;
+use minecraft_protocol::encoder::EncoderWriteExt;
+use minecraft_protocol::error::DecodeError;
+use minecraft_protocol::version::v1_14_4::game::{GameClientBoundPacket, GameServerBoundPacket};
+use minecraft_protocol::version::v1_14_4::handshake::HandshakeServerBoundPacket;
+use minecraft_protocol::version::v1_14_4::login::{LoginClientBoundPacket, LoginServerBoundPacket};
+use minecraft_protocol::version::v1_14_4::status::{
+    StatusClientBoundPacket, StatusServerBoundPacket,
+};
+use serde::Serialize;
+use std::io::{Cursor, Read};
+use std::process;
+
+const USAGE: &str = "\
+Usage: mc-packet <decode|encode> --version <VERSION> --state <STATE> --bound <BOUND> [--base64]
+
+Commands:
+    decode    Read packet bytes from stdin and print the packet as JSON
+    encode    Read packet JSON from stdin and print the packet bytes
+
+Options:
+    --version <VERSION>    Protocol version name or number (supported: 1.14.4/498)
+    --state <STATE>        Protocol state: handshake, status, login or game
+    --bound <BOUND>        Packet direction: server or client
+    --base64               Use base64 instead of hex for packet bytes";
+
+#[derive(Debug, Eq, PartialEq)]
+enum Command {
+    Decode,
+    Encode,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum State {
+    Handshake,
+    Status,
+    Login,
+    Game,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Bound {
+    Server,
+    Client,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Format {
+    Hex,
+    Base64,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Options {
+    command: Command,
+    state: State,
+    bound: Bound,
+    format: Format,
+}
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let mut input = String::new();
+
+    if let Err(io_error) = std::io::stdin().read_to_string(&mut input) {
+        eprintln!("error: failed to read stdin: {}", io_error);
+        process::exit(1);
+    }
+
+    match run(&options, &input) {
+        Ok(output) => println!("{}", output),
+        Err(message) => {
+            eprintln!("error: {}", message);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let command = match args.next().as_deref() {
+        Some("decode") => Command::Decode,
+        Some("encode") => Command::Encode,
+        Some(command) => return Err(format!("unknown command `{}`", command)),
+        None => return Err(String::from("missing command")),
+    };
+
+    let mut version = None;
+    let mut state = None;
+    let mut bound = None;
+    let mut format = Format::Hex;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--version" => version = Some(next_value(&mut args, &arg)?),
+            "--state" => state = Some(next_value(&mut args, &arg)?),
+            "--bound" => bound = Some(next_value(&mut args, &arg)?),
+            "--base64" => format = Format::Base64,
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        }
+    }
+
+    match version.as_deref() {
+        Some("1.14.4") | Some("498") => {}
+        Some(version) => return Err(format!("unsupported protocol version `{}`", version)),
+        None => return Err(String::from("missing `--version`")),
+    }
+
+    let state = match state.as_deref() {
+        Some("handshake") => State::Handshake,
+        Some("status") => State::Status,
+        Some("login") => State::Login,
+        Some("game") => State::Game,
+        Some(state) => return Err(format!("unknown state `{}`", state)),
+        None => return Err(String::from("missing `--state`")),
+    };
+
+    let bound = match bound.as_deref() {
+        Some("server") => Bound::Server,
+        Some("client") => Bound::Client,
+        Some(bound) => return Err(format!("unknown bound `{}`", bound)),
+        None => return Err(String::from("missing `--bound`")),
+    };
+
+    Ok(Options {
+        command,
+        state,
+        bound,
+        format,
+    })
+}
+
+fn next_value<I: Iterator<Item = String>>(args: &mut I, name: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for `{}`", name))
+}
+
+fn run(options: &Options, input: &str) -> Result<String, String> {
+    match options.command {
+        Command::Decode => {
+            let bytes = parse_bytes(input, &options.format)?;
+
+            decode(&options.state, &options.bound, &bytes)
+        }
+        Command::Encode => {
+            let bytes = encode(&options.state, &options.bound, input)?;
+
+            Ok(format_bytes(&bytes, &options.format))
+        }
+    }
+}
+
+fn parse_bytes(input: &str, format: &Format) -> Result<Vec<u8>, String> {
+    let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+
+    match format {
+        Format::Hex => hex::decode(input).map_err(|e| format!("invalid hex input: {}", e)),
+        Format::Base64 => base64::engine::general_purpose::STANDARD
+            .decode(input)
+            .map_err(|e| format!("invalid base64 input: {}", e)),
+    }
+}
+
+fn format_bytes(bytes: &[u8], format: &Format) -> String {
+    match format {
+        Format::Hex => hex::encode(bytes),
+        Format::Base64 => base64::engine::general_purpose::STANDARD.encode(bytes),
+    }
+}
+
+fn decode(state: &State, bound: &Bound, bytes: &[u8]) -> Result<String, String> {
+    let mut reader = Cursor::new(bytes);
+    let type_id = reader
+        .read_var_i32()
+        .map_err(|e| format!("failed to read packet id: {:?}", e))?;
+
+    if !(0..=0xFF).contains(&type_id) {
+        return Err(format!("packet id {} is out of range", type_id));
+    }
+
+    let type_id = type_id as u8;
+    let reader = &mut reader;
+
+    let json = match (state, bound) {
+        (State::Handshake, Bound::Server) => {
+            to_json(HandshakeServerBoundPacket::decode(type_id, reader))?
+        }
+        (State::Handshake, Bound::Client) => {
+            return Err(String::from("there are no client-bound handshake packets"))
+        }
+        (State::Status, Bound::Server) => {
+            to_json(StatusServerBoundPacket::decode(type_id, reader))?
+        }
+        (State::Status, Bound::Client) => {
+            to_json(StatusClientBoundPacket::decode(type_id, reader))?
+        }
+        (State::Login, Bound::Server) => to_json(LoginServerBoundPacket::decode(type_id, reader))?,
+        (State::Login, Bound::Client) => to_json(LoginClientBoundPacket::decode(type_id, reader))?,
+        (State::Game, Bound::Server) => to_json(GameServerBoundPacket::decode(type_id, reader))?,
+        (State::Game, Bound::Client) => to_json(GameClientBoundPacket::decode(type_id, reader))?,
+    };
+
+    let trailing = bytes.len() - reader.position() as usize;
+
+    if trailing > 0 {
+        return Err(format!("packet has {} trailing bytes", trailing));
+    }
+
+    Ok(json)
+}
+
+fn to_json<P: Serialize>(result: Result<P, DecodeError>) -> Result<String, String> {
+    let packet = result.map_err(|e| format!("failed to decode packet: {:?}", e))?;
+
+    serde_json::to_string_pretty(&packet).map_err(|e| format!("failed to write json: {}", e))
+}
+
+macro_rules! encode_packet (
+    ($ty: ty, $json: expr) => ({
+        let packet: $ty = serde_json::from_str($json)
+            .map_err(|e| format!("invalid packet json: {}", e))?;
+
+        let mut vec = Vec::new();
+        vec.write_var_i32(packet.get_type_id() as i32)
+            .and_then(|_| packet.encode(&mut vec))
+            .map_err(|e| format!("failed to encode packet: {:?}", e))?;
+
+        Ok(vec)
+    });
+);
+
+fn encode(state: &State, bound: &Bound, json: &str) -> Result<Vec<u8>, String> {
+    match (state, bound) {
+        (State::Handshake, Bound::Server) => encode_packet!(HandshakeServerBoundPacket, json),
+        (State::Handshake, Bound::Client) => {
+            Err(String::from("there are no client-bound handshake packets"))
+        }
+        (State::Status, Bound::Server) => encode_packet!(StatusServerBoundPacket, json),
+        (State::Status, Bound::Client) => encode_packet!(StatusClientBoundPacket, json),
+        (State::Login, Bound::Server) => encode_packet!(LoginServerBoundPacket, json),
+        (State::Login, Bound::Client) => encode_packet!(LoginClientBoundPacket, json),
+        (State::Game, Bound::Server) => encode_packet!(GameServerBoundPacket, json),
+        (State::Game, Bound::Client) => encode_packet!(GameClientBoundPacket, json),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args(
+            "decode --version 498 --state game --bound client --base64",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            options,
+            Options {
+                command: Command::Decode,
+                state: State::Game,
+                bound: Bound::Client,
+                format: Format::Base64,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_args_unsupported_version() {
+        let error = parse_args(args("decode --version 1.8 --state game --bound client"))
+            .expect_err("Expected error because version is not supported");
+
+        assert_eq!(error, "unsupported protocol version `1.8`");
+    }
+
+    #[test]
+    fn test_decode_keep_alive() {
+        let bytes = parse_bytes("20 00 00 00 00 00 03 ac 4a\n", &Format::Hex).unwrap();
+        let json = decode(&State::Game, &Bound::Client, &bytes).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!({"ClientBoundKeepAlive": {"id": 240714}})
+        );
+    }
+
+    #[test]
+    fn test_decode_trailing_bytes() {
+        let bytes = parse_bytes("20 00 00 00 00 00 03 ac 4a ff", &Format::Hex).unwrap();
+        let error = decode(&State::Game, &Bound::Client, &bytes)
+            .expect_err("Expected error because packet has trailing bytes");
+
+        assert_eq!(error, "packet has 1 trailing bytes");
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let json = r#"{"LoginStart":{"name":"Username"}}"#;
+        let bytes = encode(&State::Login, &Bound::Server, json).unwrap();
+        let base64 = format_bytes(&bytes, &Format::Base64);
+
+        let decoded_bytes = parse_bytes(&base64, &Format::Base64).unwrap();
+        let decoded_json = decode(&State::Login, &Bound::Server, &decoded_bytes).unwrap();
+
+        assert_eq!(bytes[0], 0x00);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&decoded_json).unwrap(),
+            serde_json::from_str::<serde_json::Value>(json).unwrap()
+        );
+    }
+}
@@ -0,0 +1,530 @@
+//! Interactive tool for crafting and sending packets to a live server.
+//!
+//! Logs in to an offline mode server with protocol 1.14.4 and prints packets received from the
+//! server as JSON. Lines typed at the prompt are sent as server-bound packets of the current
+//! state, in the same JSON form `mc-packet` uses. Keep-alives are answered automatically.
+//!
+//! ```text
+//! $ mc-repl localhost --name Username --record session
+//! < {"LoginSuccess":{"uuid":"...","username":"Username"}}
+//! < {"JoinGame":{...}}
+//! :hide ChunkData
+//! {"ServerBoundChatMessage":{"message":"Hello"}}
+//! > {"ServerBoundChatMessage":{"message":"Hello"}}
+//! ```
+//!
+//! Session is recorded in the format of `pcap-import`, one file with packet data per packet.
+use minecraft_protocol::connection::Connection;
+use minecraft_protocol::driver::{ProtocolState, Side};
+use minecraft_protocol::error::DecodeError;
+use minecraft_protocol::packet::{Packet, RawPacket};
+use minecraft_protocol::version::v1_14_4::game::{
+    GameClientBoundPacket, GameServerBoundPacket, ServerBoundKeepAlive,
+};
+use minecraft_protocol::version::v1_14_4::handshake::{Handshake, NextState};
+use minecraft_protocol::version::v1_14_4::login::{
+    LoginClientBoundPacket, LoginServerBoundPacket, LoginStart,
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::fs;
+use std::io::{self, BufRead, ErrorKind};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+const USAGE: &str = "\
+Usage: mc-repl <HOST> [--port <PORT>] [--name <NAME>] [--record <DIR>]
+
+Options:
+    --port <PORT>     Server port (default: 25565)
+    --name <NAME>     Player name (default: Player)
+    --record <DIR>    Directory where sent and received packets are written";
+
+const COMMANDS: &str = "\
+Commands:
+    <JSON>            Send server-bound packet of the current state
+    :hide <PACKET>    Stop printing received packets with name
+    :show <PACKET>    Print received packets with name again
+    :filters          List hidden packets
+    :help             Print this help
+    :quit             Disconnect";
+
+const PROTOCOL_VERSION: i32 = 498;
+
+const DEFAULT_PORT: u16 = 25565;
+
+const DEFAULT_NAME: &str = "Player";
+
+/// Time between checks of the prompt while waiting for packets.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Eq, PartialEq)]
+struct Options {
+    host: String,
+    port: u16,
+    name: String,
+    record: Option<PathBuf>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Command {
+    Send(String),
+    Hide(String),
+    Show(String),
+    Filters,
+    Help,
+    Quit,
+}
+
+/// Names of received packets which are not printed.
+#[derive(Debug)]
+struct Filter {
+    hidden: BTreeSet<String>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        let mut hidden = BTreeSet::new();
+        hidden.insert(String::from("ClientBoundKeepAlive"));
+
+        Filter { hidden }
+    }
+}
+
+impl Filter {
+    fn is_shown(&self, name: &str) -> bool {
+        !self.hidden.contains(name)
+    }
+}
+
+/// Writes packets to directory in the format of `pcap-import`.
+#[derive(Debug)]
+struct Recorder {
+    dir: PathBuf,
+    index: usize,
+}
+
+impl Recorder {
+    fn new(dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+
+        Ok(Recorder { dir, index: 0 })
+    }
+
+    fn record(
+        &mut self,
+        side: Side,
+        state: ProtocolState,
+        raw_packet: &RawPacket,
+    ) -> io::Result<()> {
+        let file_name = packet_file_name(self.index, side, state, raw_packet.id);
+        self.index += 1;
+
+        fs::write(self.dir.join(file_name), &raw_packet.data)
+    }
+}
+
+/// Returns file name of packet sent by side, named like `pcap-import` names them.
+fn packet_file_name(index: usize, side: Side, state: ProtocolState, id: i32) -> String {
+    // Packets sent by client are server-bound.
+    let bound = match side {
+        Side::Client => "server",
+        Side::Server => "client",
+    };
+    let state = match state {
+        ProtocolState::Handshake => "handshake",
+        ProtocolState::Status => "status",
+        ProtocolState::Login => "login",
+        ProtocolState::Game => "game",
+    };
+
+    format!("{:04}_{}_{}_0x{:02x}.dat", index, bound, state, id)
+}
+
+struct Session {
+    connection: Connection<TcpStream>,
+    filter: Filter,
+    recorder: Option<Recorder>,
+}
+
+impl Session {
+    fn send(&mut self, raw_packet: RawPacket) -> Result<(), String> {
+        let state = self.connection.state();
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder
+                .record(Side::Client, state, &raw_packet)
+                .map_err(|e| format!("failed to record packet: {}", e))?;
+        }
+
+        self.connection
+            .write_raw_packet(raw_packet)
+            .map_err(|e| format!("failed to send packet: {:?}", e))
+    }
+
+    fn send_packet<P: Packet>(&mut self, packet: &P) -> Result<(), String> {
+        let raw_packet = RawPacket::from_packet(packet)
+            .map_err(|e| format!("failed to encode packet: {:?}", e))?;
+
+        self.send(raw_packet)
+    }
+
+    /// Reads packet if one arrives in time, returns `false` once server closes connection.
+    fn receive(&mut self) -> Result<bool, String> {
+        // Packet is received in the state before reading it, reading applies its transition.
+        let state = self.connection.state();
+
+        let raw_packet = match self.connection.read_raw_packet_timeout(POLL_INTERVAL) {
+            Ok(raw_packet) => raw_packet,
+            Err(DecodeError::IOError { io_error }) if io_error.kind() == ErrorKind::TimedOut => {
+                return Ok(true)
+            }
+            Err(DecodeError::IOError { io_error })
+                if io_error.kind() == ErrorKind::UnexpectedEof =>
+            {
+                println!("connection closed by server");
+                return Ok(false);
+            }
+            Err(decode_error) => return Err(format!("failed to read packet: {:?}", decode_error)),
+        };
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder
+                .record(Side::Server, state, &raw_packet)
+                .map_err(|e| format!("failed to record packet: {}", e))?;
+        }
+
+        match state {
+            ProtocolState::Login => {
+                let packet = raw_packet.decode_packet::<LoginClientBoundPacket>();
+                self.print_received(&raw_packet, packet.map(|p| (p.name(), to_json(&p))));
+            }
+            ProtocolState::Game => {
+                let packet = raw_packet.decode_packet::<GameClientBoundPacket>();
+                self.print_received(&raw_packet, packet.as_ref().map(|p| (p.name(), to_json(p))));
+
+                if let Ok(GameClientBoundPacket::ClientBoundKeepAlive(keep_alive)) = packet {
+                    self.send_packet(&ServerBoundKeepAlive::new(keep_alive.id))?;
+                }
+            }
+            _ => {
+                let packet = Err(match u8::try_from(raw_packet.id) {
+                    Ok(type_id) => DecodeError::UnknownPacketType { type_id },
+                    Err(_) => DecodeError::PacketIdOutOfRange { id: raw_packet.id },
+                });
+                self.print_received(&raw_packet, packet);
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn print_received<E: std::fmt::Debug>(
+        &self,
+        raw_packet: &RawPacket,
+        packet: Result<(&'static str, String), E>,
+    ) {
+        match packet {
+            Ok((name, json)) if self.filter.is_shown(name) => println!("< {}", json),
+            Ok(_) => {}
+            Err(decode_error) => println!(
+                "< 0x{:02x} ({} bytes) not decoded: {:?}",
+                raw_packet.id,
+                raw_packet.data.len(),
+                decode_error
+            ),
+        }
+    }
+
+    /// Runs command, returns `false` when session should end.
+    fn execute(&mut self, command: Command) -> Result<bool, String> {
+        match command {
+            Command::Send(json) => match encode(self.connection.state(), &json) {
+                Ok(raw_packet) => {
+                    self.send(raw_packet)?;
+                    println!("> {}", json);
+                }
+                Err(message) => println!("error: {}", message),
+            },
+            Command::Hide(name) => {
+                self.filter.hidden.insert(name);
+            }
+            Command::Show(name) => {
+                self.filter.hidden.remove(&name);
+            }
+            Command::Filters => {
+                for name in &self.filter.hidden {
+                    println!("{}", name);
+                }
+            }
+            Command::Help => println!("{}", COMMANDS),
+            Command::Quit => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(message) = run(options) {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut host = None;
+    let mut port = DEFAULT_PORT;
+    let mut name = String::from(DEFAULT_NAME);
+    let mut record = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => {
+                let value = next_value(&mut args, &arg)?;
+                port = value
+                    .parse()
+                    .map_err(|_| format!("invalid port `{}`", value))?;
+            }
+            "--name" => name = next_value(&mut args, &arg)?,
+            "--record" => record = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            _ if arg.starts_with("--") => return Err(format!("unknown argument `{}`", arg)),
+            _ if host.is_none() => host = Some(arg),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    let host = host.ok_or_else(|| String::from("missing host"))?;
+
+    Ok(Options {
+        host,
+        port,
+        name,
+        record,
+    })
+}
+
+fn next_value<I: Iterator<Item = String>>(args: &mut I, name: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for `{}`", name))
+}
+
+/// Parses prompt line, empty lines are ignored.
+fn parse_command(line: &str) -> Result<Option<Command>, String> {
+    let line = line.trim();
+
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    if !line.starts_with(':') {
+        return Ok(Some(Command::Send(String::from(line))));
+    }
+
+    let mut words = line[1..].split_whitespace();
+    let command = words.next().unwrap_or_default();
+    let argument = words.next().map(String::from);
+
+    let command = match (command, argument) {
+        ("hide", Some(name)) => Command::Hide(name),
+        ("show", Some(name)) => Command::Show(name),
+        ("hide", None) | ("show", None) => return Err(String::from("missing packet name")),
+        ("filters", _) => Command::Filters,
+        ("help", _) => Command::Help,
+        ("quit", _) => Command::Quit,
+        (command, _) => return Err(format!("unknown command `:{}`", command)),
+    };
+
+    Ok(Some(command))
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let stream = TcpStream::connect((options.host.as_str(), options.port))
+        .map_err(|e| format!("failed to connect: {}", e))?;
+    stream
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .map_err(|e| format!("failed to set read timeout: {}", e))?;
+
+    let recorder = match options.record {
+        Some(dir) => {
+            Some(Recorder::new(dir).map_err(|e| format!("failed to create directory: {}", e))?)
+        }
+        None => None,
+    };
+
+    let mut session = Session {
+        connection: Connection::new(stream, Side::Client),
+        filter: Filter::default(),
+        recorder,
+    };
+
+    session.send_packet(&Handshake::new(
+        PROTOCOL_VERSION,
+        options.host.clone(),
+        options.port,
+        NextState::Login,
+    ))?;
+    session.send_packet(&LoginStart::new(options.name))?;
+
+    let lines = read_lines();
+
+    loop {
+        match lines.try_recv() {
+            Ok(line) => match parse_command(&line) {
+                Ok(Some(command)) => {
+                    if !session.execute(command)? {
+                        return Ok(());
+                    }
+                }
+                Ok(None) => {}
+                Err(message) => println!("error: {}", message),
+            },
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => return Ok(()),
+        }
+
+        if !session.receive()? {
+            return Ok(());
+        }
+    }
+}
+
+/// Reads stdin lines on a separate thread, so packets are printed while prompt waits.
+fn read_lines() -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    receiver
+}
+
+fn to_json<P: Serialize>(packet: &P) -> String {
+    serde_json::to_string(packet).unwrap_or_else(|e| format!("failed to write json: {}", e))
+}
+
+macro_rules! encode_packet (
+    ($ty: ty, $json: expr) => ({
+        let packet: $ty = serde_json::from_str($json)
+            .map_err(|e| format!("invalid packet json: {}", e))?;
+
+        RawPacket::from_packet(&packet).map_err(|e| format!("failed to encode packet: {:?}", e))
+    });
+);
+
+fn encode(state: ProtocolState, json: &str) -> Result<RawPacket, String> {
+    match state {
+        ProtocolState::Login => encode_packet!(LoginServerBoundPacket, json),
+        ProtocolState::Game => encode_packet!(GameServerBoundPacket, json),
+        _ => Err(String::from(
+            "packets can be sent only in login and game state",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args("localhost --port 25566 --record session")).unwrap();
+
+        assert_eq!(
+            options,
+            Options {
+                host: String::from("localhost"),
+                port: 25566,
+                name: String::from(DEFAULT_NAME),
+                record: Some(PathBuf::from("session")),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_args_missing_host() {
+        let error = parse_args(args("--name Username"))
+            .expect_err("Expected error because host is missing");
+
+        assert_eq!(error, "missing host");
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("  "), Ok(None));
+        assert_eq!(
+            parse_command(":hide ChunkData"),
+            Ok(Some(Command::Hide(String::from("ChunkData"))))
+        );
+        assert_eq!(
+            parse_command(r#"{"ServerBoundKeepAlive":{"id":1}}"#),
+            Ok(Some(Command::Send(String::from(
+                r#"{"ServerBoundKeepAlive":{"id":1}}"#
+            ))))
+        );
+        assert_eq!(
+            parse_command(":show"),
+            Err(String::from("missing packet name"))
+        );
+        assert_eq!(
+            parse_command(":exit"),
+            Err(String::from("unknown command `:exit`"))
+        );
+    }
+
+    #[test]
+    fn test_filter_hides_keep_alive() {
+        let filter = Filter::default();
+
+        assert!(!filter.is_shown("ClientBoundKeepAlive"));
+        assert!(filter.is_shown("ChunkData"));
+    }
+
+    #[test]
+    fn test_encode_by_state() {
+        let json = r#"{"ServerBoundKeepAlive":{"id":240714}}"#;
+        let raw_packet = encode(ProtocolState::Game, json).unwrap();
+
+        assert_eq!(raw_packet.id, 0x0F);
+        assert_eq!(raw_packet.data, [0, 0, 0, 0, 0, 3, 0xac, 0x4a]);
+
+        let error = encode(ProtocolState::Login, json)
+            .expect_err("Expected error because keep alive is not a login packet");
+
+        assert!(error.starts_with("invalid packet json"));
+    }
+
+    #[test]
+    fn test_packet_file_name() {
+        assert_eq!(
+            packet_file_name(3, Side::Client, ProtocolState::Login, 0x00),
+            "0003_server_login_0x00.dat"
+        );
+        assert_eq!(
+            packet_file_name(12, Side::Server, ProtocolState::Game, 0x21),
+            "0012_client_game_0x21.dat"
+        );
+    }
+}
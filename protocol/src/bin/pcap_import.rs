@@ -0,0 +1,941 @@
+//! Tool for turning captured Minecraft sessions into packet fixtures.
+//!
+//! Reads pcap or pcapng file saved by Wireshark or tcpdump, reassembles TCP stream of the first
+//! connection to the server port and splits both directions into packets. Every packet is written
+//! to the output directory as a separate file with packet data without id, the same format as
+//! fixtures in `test/packet`. Compression is followed from `SetCompression` packet and encrypted
+//! sessions are decrypted when shared secret is known.
+//!
+//! ```text
+//! $ pcap-import session.pcapng --out capture --secret 000102030405060708090a0b0c0d0e0f
+//! capture/0000_server_handshake_0x00.dat
+//! capture/0001_server_login_0x00.dat
+//! capture/0002_client_login_0x01.dat
+//! ...
+//! ```
+//!
+//! States are tracked like in 1.14.4, every packet after `LoginSuccess` is in game state.
+use aes::Aes128;
+use byteorder::{BigEndian, ReadBytesExt};
+use cfb8::cipher::generic_array::GenericArray;
+use cfb8::cipher::{BlockDecryptMut, KeyIvInit};
+use minecraft_protocol::compression::CompressedRawPacket;
+use minecraft_protocol::decoder::DecoderReadExt;
+use minecraft_protocol::error::DecodeError;
+use minecraft_protocol::packet::RawPacket;
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::fmt;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::PathBuf;
+use std::process;
+
+const USAGE: &str = "\
+Usage: pcap-import <FILE> --out <DIR> [--port <PORT>] [--secret <HEX>]
+
+Options:
+    --out <DIR>        Directory where packet files are written
+    --port <PORT>      Server port (default: 25565)
+    --secret <HEX>     Shared secret of encrypted session as 16 hex bytes";
+
+const DEFAULT_PORT: u16 = 25565;
+
+const SERVER_ADDRESS_MAX_LENGTH: u16 = 255;
+
+const PCAPNG_SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+type Decryptor = cfb8::Decryptor<Aes128>;
+
+#[derive(Debug, Eq, PartialEq)]
+struct Options {
+    input: PathBuf,
+    out: PathBuf,
+    port: u16,
+    secret: Option<[u8; 16]>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Bound {
+    Server,
+    Client,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum State {
+    Handshake,
+    Status,
+    Login,
+    Game,
+}
+
+/// Packet extracted from the capture.
+#[derive(Debug, Eq, PartialEq)]
+struct CapturedPacket {
+    bound: Bound,
+    state: State,
+    raw_packet: RawPacket,
+}
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(message) = run(&options) {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut input = None;
+    let mut out = None;
+    let mut port = DEFAULT_PORT;
+    let mut secret = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--port" => {
+                let value = next_value(&mut args, &arg)?;
+                port = value
+                    .parse()
+                    .map_err(|_| format!("invalid port `{}`", value))?;
+            }
+            "--secret" => secret = Some(parse_secret(&next_value(&mut args, &arg)?)?),
+            _ if arg.starts_with("--") => return Err(format!("unknown argument `{}`", arg)),
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    Ok(Options {
+        input: input.ok_or_else(|| String::from("missing capture file"))?,
+        out: out.ok_or_else(|| String::from("missing `--out`"))?,
+        port,
+        secret,
+    })
+}
+
+fn next_value<I: Iterator<Item = String>>(args: &mut I, name: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for `{}`", name))
+}
+
+fn parse_secret(value: &str) -> Result<[u8; 16], String> {
+    let bytes = hex::decode(value).map_err(|e| format!("invalid secret: {}", e))?;
+
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("secret must be 16 bytes, got {}", bytes.len()))
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let capture = fs::read(&options.input)
+        .map_err(|e| format!("failed to read `{}`: {}", options.input.display(), e))?;
+    let packets = import(&capture, options.port, options.secret)?;
+
+    fs::create_dir_all(&options.out)
+        .map_err(|e| format!("failed to create `{}`: {}", options.out.display(), e))?;
+
+    for (index, packet) in packets.iter().enumerate() {
+        let path = options.out.join(packet.file_name(index));
+
+        fs::write(&path, &packet.raw_packet.data)
+            .map_err(|e| format!("failed to write `{}`: {}", path.display(), e))?;
+
+        println!("{}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Extracts packets of the first session with given server port.
+fn import(
+    capture: &[u8],
+    port: u16,
+    secret: Option<[u8; 16]>,
+) -> Result<Vec<CapturedPacket>, String> {
+    let mut session: Option<Session> = None;
+
+    for (link_type, frame) in read_capture(capture)? {
+        let segment = match parse_link(link_type, frame).and_then(parse_ip) {
+            Some(segment) => segment,
+            None => continue,
+        };
+
+        let session = match session {
+            Some(ref mut session) => session,
+            None if segment.destination.port() == port => {
+                session.insert(Session::new(segment.source, segment.destination, secret))
+            }
+            None if segment.source.port() == port => {
+                session.insert(Session::new(segment.destination, segment.source, secret))
+            }
+            None => continue,
+        };
+
+        session.receive(&segment)?;
+    }
+
+    match session {
+        Some(session) => Ok(session.packets),
+        None => Err(format!("no connection to port {} in capture", port)),
+    }
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bound::Server => write!(f, "server"),
+            Bound::Client => write!(f, "client"),
+        }
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            State::Handshake => write!(f, "handshake"),
+            State::Status => write!(f, "status"),
+            State::Login => write!(f, "login"),
+            State::Game => write!(f, "game"),
+        }
+    }
+}
+
+impl CapturedPacket {
+    fn file_name(&self, index: usize) -> String {
+        format!(
+            "{:04}_{}_{}_0x{:02x}.dat",
+            index, self.bound, self.state, self.raw_packet.id
+        )
+    }
+}
+
+/// Minecraft connection between client and server.
+struct Session {
+    client: SocketAddr,
+    server: SocketAddr,
+    secret: Option<[u8; 16]>,
+    /// Packets sent by client.
+    server_bound: Direction,
+    /// Packets sent by server.
+    client_bound: Direction,
+    packets: Vec<CapturedPacket>,
+}
+
+impl Session {
+    fn new(client: SocketAddr, server: SocketAddr, secret: Option<[u8; 16]>) -> Self {
+        Session {
+            client,
+            server,
+            secret,
+            server_bound: Direction::default(),
+            client_bound: Direction::default(),
+            packets: Vec::new(),
+        }
+    }
+
+    fn receive(&mut self, segment: &Segment) -> Result<(), String> {
+        let bound = if segment.source == self.client && segment.destination == self.server {
+            Bound::Server
+        } else if segment.source == self.server && segment.destination == self.client {
+            Bound::Client
+        } else {
+            return Ok(());
+        };
+
+        let direction = self.direction(bound);
+        let data = direction
+            .stream
+            .push(segment.seq, segment.syn, segment.payload);
+        direction.append(data);
+
+        loop {
+            let direction = self.direction(bound);
+            let state = direction.state;
+
+            let raw_packet = match direction.read_packet() {
+                Ok(Some(raw_packet)) => raw_packet,
+                Ok(None) => return Ok(()),
+                Err(decode_error) => {
+                    return Err(format!(
+                        "failed to read {} bound {} packet: {:?}",
+                        bound, state, decode_error
+                    ))
+                }
+            };
+
+            self.handle(bound, state, &raw_packet)?;
+            self.packets.push(CapturedPacket {
+                bound,
+                state,
+                raw_packet,
+            });
+        }
+    }
+
+    fn direction(&mut self, bound: Bound) -> &mut Direction {
+        match bound {
+            Bound::Server => &mut self.server_bound,
+            Bound::Client => &mut self.client_bound,
+        }
+    }
+
+    /// Follows packets which change state, compression or encryption.
+    fn handle(&mut self, bound: Bound, state: State, raw_packet: &RawPacket) -> Result<(), String> {
+        match (bound, state, raw_packet.id) {
+            (Bound::Server, State::Handshake, 0x00) => {
+                let state = match read_next_state(&raw_packet.data) {
+                    Ok(1) => State::Status,
+                    Ok(_) => State::Login,
+                    Err(decode_error) => {
+                        return Err(format!("failed to read handshake: {:?}", decode_error))
+                    }
+                };
+
+                self.server_bound.state = state;
+                self.client_bound.state = state;
+            }
+            // Encryption request and response. Each side encrypts everything it sends next.
+            (_, State::Login, 0x01) => {
+                let secret = self
+                    .secret
+                    .ok_or_else(|| String::from("session is encrypted, `--secret` is required"))?;
+
+                self.direction(bound).enable_encryption(&secret);
+            }
+            (Bound::Client, State::Login, 0x02) => {
+                self.server_bound.state = State::Game;
+                self.client_bound.state = State::Game;
+            }
+            (Bound::Client, State::Login, 0x03) => {
+                let threshold = raw_packet
+                    .data
+                    .as_slice()
+                    .read_var_i32()
+                    .map_err(|e| format!("failed to read compression threshold: {:?}", e))?;
+                let threshold = Some(threshold).filter(|t| *t >= 0).map(|t| t as usize);
+
+                self.server_bound.compression_threshold = threshold;
+                self.client_bound.compression_threshold = threshold;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+fn read_next_state(mut data: &[u8]) -> Result<i32, DecodeError> {
+    data.read_var_i32()?;
+    data.read_string(SERVER_ADDRESS_MAX_LENGTH)?;
+    data.read_u16::<BigEndian>()?;
+
+    data.read_var_i32()
+}
+
+/// One direction of the connection.
+struct Direction {
+    stream: TcpStream,
+    /// Decrypted data which doesn't form a complete frame yet.
+    buf: Vec<u8>,
+    decryptor: Option<Decryptor>,
+    state: State,
+    compression_threshold: Option<usize>,
+}
+
+impl Default for Direction {
+    fn default() -> Self {
+        Direction {
+            stream: TcpStream::default(),
+            buf: Vec::new(),
+            decryptor: None,
+            state: State::Handshake,
+            compression_threshold: None,
+        }
+    }
+}
+
+impl Direction {
+    fn append(&mut self, mut data: Vec<u8>) {
+        if let Some(decryptor) = &mut self.decryptor {
+            decrypt(decryptor, &mut data);
+        }
+
+        self.buf.extend_from_slice(&data);
+    }
+
+    fn enable_encryption(&mut self, secret: &[u8; 16]) {
+        let mut decryptor = Decryptor::new(secret.into(), secret.into());
+        decrypt(&mut decryptor, &mut self.buf);
+
+        self.decryptor = Some(decryptor);
+    }
+
+    fn read_packet(&mut self) -> Result<Option<RawPacket>, DecodeError> {
+        let mut reader = self.buf.as_slice();
+
+        let result = match self.compression_threshold {
+            Some(threshold) => {
+                CompressedRawPacket::decode(&mut reader, threshold).map(|p| p.into_inner())
+            }
+            None => RawPacket::decode(&mut reader),
+        };
+
+        match result {
+            Ok(raw_packet) => {
+                let consumed = self.buf.len() - reader.len();
+                self.buf.drain(..consumed);
+
+                Ok(Some(raw_packet))
+            }
+            Err(DecodeError::Incomplete { .. }) => Ok(None),
+            Err(decode_error) => Err(decode_error),
+        }
+    }
+}
+
+fn decrypt(decryptor: &mut Decryptor, data: &mut [u8]) {
+    for byte in data {
+        decryptor.decrypt_block_mut(GenericArray::from_mut_slice(std::slice::from_mut(byte)));
+    }
+}
+
+/// TCP stream reassembled from segments which may be duplicated or out of order.
+#[derive(Default)]
+struct TcpStream {
+    /// Sequence number of the first data byte.
+    initial_seq: Option<u32>,
+    /// Number of bytes already returned.
+    position: u64,
+    /// Segments by their offset from the initial sequence number.
+    pending: BTreeMap<u64, Vec<u8>>,
+}
+
+impl TcpStream {
+    /// Adds segment and returns data which became contiguous.
+    fn push(&mut self, seq: u32, syn: bool, payload: &[u8]) -> Vec<u8> {
+        // SYN takes one sequence number before the first data byte.
+        let seq = if syn { seq.wrapping_add(1) } else { seq };
+        let initial_seq = *self.initial_seq.get_or_insert(seq);
+        let offset = seq.wrapping_sub(initial_seq) as u64;
+
+        if !payload.is_empty() {
+            let pending = self.pending.entry(offset).or_default();
+
+            if pending.len() < payload.len() {
+                *pending = payload.to_vec();
+            }
+        }
+
+        let mut data = Vec::new();
+
+        while let Some(entry) = self.pending.first_entry() {
+            let offset = *entry.key();
+
+            if offset > self.position {
+                break;
+            }
+
+            let payload = entry.remove();
+            let end = offset + payload.len() as u64;
+
+            if end > self.position {
+                data.extend_from_slice(&payload[(self.position - offset) as usize..]);
+                self.position = end;
+            }
+        }
+
+        data
+    }
+}
+
+/// Reads link type and data of every captured frame.
+fn read_capture(capture: &[u8]) -> Result<Vec<(u32, &[u8])>, String> {
+    let magic = bytes(capture, 0, 4)?;
+
+    match magic {
+        [0xD4, 0xC3, 0xB2, 0xA1] | [0x4D, 0x3C, 0xB2, 0xA1] => read_pcap(capture, Endian::Little),
+        [0xA1, 0xB2, 0xC3, 0xD4] | [0xA1, 0xB2, 0x3C, 0x4D] => read_pcap(capture, Endian::Big),
+        _ if Endian::Little.u32(magic) == PCAPNG_SECTION_HEADER => read_pcapng(capture),
+        _ => Err(String::from("unknown capture format")),
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Endian {
+    Little,
+    Big,
+}
+
+impl Endian {
+    fn u16(self, bytes: &[u8]) -> u16 {
+        let bytes = [bytes[0], bytes[1]];
+
+        match self {
+            Endian::Little => u16::from_le_bytes(bytes),
+            Endian::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    fn u32(self, bytes: &[u8]) -> u32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+
+        match self {
+            Endian::Little => u32::from_le_bytes(bytes),
+            Endian::Big => u32::from_be_bytes(bytes),
+        }
+    }
+}
+
+fn bytes(data: &[u8], start: usize, length: usize) -> Result<&[u8], String> {
+    data.get(start..start + length)
+        .ok_or_else(|| String::from("capture is truncated"))
+}
+
+fn read_pcap(capture: &[u8], endian: Endian) -> Result<Vec<(u32, &[u8])>, String> {
+    let link_type = endian.u32(bytes(capture, 20, 4)?);
+    let mut frames = Vec::new();
+    let mut position = 24;
+
+    while position < capture.len() {
+        let header = bytes(capture, position, 16)?;
+        let length = endian.u32(&header[8..12]) as usize;
+
+        frames.push((link_type, bytes(capture, position + 16, length)?));
+        position += 16 + length;
+    }
+
+    Ok(frames)
+}
+
+fn read_pcapng(capture: &[u8]) -> Result<Vec<(u32, &[u8])>, String> {
+    let mut endian = Endian::Little;
+    let mut link_types = Vec::new();
+    let mut frames = Vec::new();
+    let mut position = 0;
+
+    while position < capture.len() {
+        let header = bytes(capture, position, 12)?;
+
+        if Endian::Little.u32(header) == PCAPNG_SECTION_HEADER {
+            endian = if Endian::Little.u32(&header[8..]) == PCAPNG_BYTE_ORDER_MAGIC {
+                Endian::Little
+            } else {
+                Endian::Big
+            };
+            link_types.clear();
+        }
+
+        let block_type = endian.u32(header);
+        let block_length = endian.u32(&header[4..]) as usize;
+
+        if block_length < 12 {
+            return Err(String::from("invalid pcapng block length"));
+        }
+
+        let body = bytes(capture, position + 8, block_length - 12)?;
+
+        match block_type {
+            // Interface description.
+            1 => link_types.push(endian.u16(bytes(body, 0, 2)?) as u32),
+            // Enhanced packet.
+            6 => {
+                let interface = endian.u32(bytes(body, 0, 4)?) as usize;
+                let length = endian.u32(bytes(body, 12, 4)?) as usize;
+                let link_type = *link_types
+                    .get(interface)
+                    .ok_or_else(|| String::from("packet of unknown interface"))?;
+
+                frames.push((link_type, bytes(body, 20, length)?));
+            }
+            // Simple packet.
+            3 => {
+                let length = (endian.u32(bytes(body, 0, 4)?) as usize).min(body.len() - 4);
+                let link_type = *link_types
+                    .first()
+                    .ok_or_else(|| String::from("packet of unknown interface"))?;
+
+                frames.push((link_type, bytes(body, 4, length)?));
+            }
+            _ => {}
+        }
+
+        position += block_length;
+    }
+
+    Ok(frames)
+}
+
+/// Returns IP packet carried by link layer frame.
+fn parse_link(link_type: u32, frame: &[u8]) -> Option<&[u8]> {
+    match link_type {
+        // BSD loopback.
+        0 => frame.get(4..),
+        // Ethernet.
+        1 => {
+            let mut position = 12;
+
+            // Skip VLAN tags.
+            while matches!(
+                frame.get(position..position + 2)?,
+                [0x81, 0x00] | [0x88, 0xA8]
+            ) {
+                position += 4;
+            }
+
+            frame.get(position + 2..)
+        }
+        // Raw IP.
+        101 => Some(frame),
+        // Linux cooked capture.
+        113 => frame.get(16..),
+        // Linux cooked capture v2.
+        276 => frame.get(20..),
+        _ => None,
+    }
+}
+
+/// TCP segment with its endpoints.
+struct Segment<'a> {
+    source: SocketAddr,
+    destination: SocketAddr,
+    seq: u32,
+    syn: bool,
+    payload: &'a [u8],
+}
+
+fn parse_ip(packet: &[u8]) -> Option<Segment<'_>> {
+    let (source, destination, tcp) = match packet.first()? >> 4 {
+        4 => {
+            let header_length = ((packet[0] & 0x0F) as usize) * 4;
+            let total_length = u16::from_be_bytes([*packet.get(2)?, *packet.get(3)?]) as usize;
+            let fragment = u16::from_be_bytes([*packet.get(6)?, *packet.get(7)?]);
+
+            // Fragmented packets are not reassembled.
+            if *packet.get(9)? != 6 || fragment & 0x3FFF != 0 {
+                return None;
+            }
+
+            let source: [u8; 4] = packet.get(12..16)?.try_into().ok()?;
+            let destination: [u8; 4] = packet.get(16..20)?.try_into().ok()?;
+            let tcp = packet.get(header_length..total_length.min(packet.len()))?;
+
+            (
+                IpAddr::from(Ipv4Addr::from(source)),
+                IpAddr::from(Ipv4Addr::from(destination)),
+                tcp,
+            )
+        }
+        6 => {
+            let payload_length = u16::from_be_bytes([*packet.get(4)?, *packet.get(5)?]) as usize;
+
+            // Extension headers are not supported.
+            if *packet.get(6)? != 6 {
+                return None;
+            }
+
+            let source: [u8; 16] = packet.get(8..24)?.try_into().ok()?;
+            let destination: [u8; 16] = packet.get(24..40)?.try_into().ok()?;
+            let tcp = packet.get(40..(40 + payload_length).min(packet.len()))?;
+
+            (
+                IpAddr::from(Ipv6Addr::from(source)),
+                IpAddr::from(Ipv6Addr::from(destination)),
+                tcp,
+            )
+        }
+        _ => return None,
+    };
+
+    let header = tcp.get(..20)?;
+    let data_offset = ((header[12] >> 4) as usize) * 4;
+
+    Some(Segment {
+        source: SocketAddr::new(source, u16::from_be_bytes([header[0], header[1]])),
+        destination: SocketAddr::new(destination, u16::from_be_bytes([header[2], header[3]])),
+        seq: u32::from_be_bytes([header[4], header[5], header[6], header[7]]),
+        syn: header[13] & 0x02 != 0,
+        payload: tcp.get(data_offset..)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfb8::cipher::BlockEncryptMut;
+    use minecraft_protocol::encoder::EncoderWriteExt;
+
+    const CLIENT: [u8; 4] = [10, 0, 0, 2];
+    const SERVER: [u8; 4] = [10, 0, 0, 1];
+    const CLIENT_PORT: u16 = 50000;
+
+    const SECRET: [u8; 16] = [7; 16];
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(String::from)
+    }
+
+    /// Builds Ethernet frame with IPv4 TCP segment.
+    fn tcp_frame(bound: Bound, seq: u32, syn: bool, payload: &[u8]) -> Vec<u8> {
+        let (source, destination, source_port, destination_port) = match bound {
+            Bound::Server => (CLIENT, SERVER, CLIENT_PORT, DEFAULT_PORT),
+            Bound::Client => (SERVER, CLIENT, DEFAULT_PORT, CLIENT_PORT),
+        };
+
+        let mut frame = vec![0; 12];
+        frame.extend_from_slice(&[0x08, 0x00]);
+
+        frame.extend_from_slice(&[0x45, 0]);
+        frame.extend_from_slice(&((40 + payload.len()) as u16).to_be_bytes());
+        frame.extend_from_slice(&[0, 0, 0x40, 0, 64, 6, 0, 0]);
+        frame.extend_from_slice(&source);
+        frame.extend_from_slice(&destination);
+
+        frame.extend_from_slice(&source_port.to_be_bytes());
+        frame.extend_from_slice(&destination_port.to_be_bytes());
+        frame.extend_from_slice(&seq.to_be_bytes());
+        frame.extend_from_slice(&[0, 0, 0, 0, 0x50, if syn { 0x02 } else { 0x18 }]);
+        frame.extend_from_slice(&[0; 6]);
+        frame.extend_from_slice(payload);
+
+        frame
+    }
+
+    fn pcap(frames: &[Vec<u8>]) -> Vec<u8> {
+        let mut capture = vec![0xD4, 0xC3, 0xB2, 0xA1, 2, 0, 4, 0];
+        capture.extend_from_slice(&[0; 8]);
+        capture.extend_from_slice(&65535u32.to_le_bytes());
+        capture.extend_from_slice(&1u32.to_le_bytes());
+
+        for frame in frames {
+            capture.extend_from_slice(&[0; 8]);
+            capture.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            capture.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            capture.extend_from_slice(frame);
+        }
+
+        capture
+    }
+
+    fn pcapng(frames: &[Vec<u8>]) -> Vec<u8> {
+        fn block(capture: &mut Vec<u8>, block_type: u32, body: &[u8]) {
+            let length = (12 + body.len()) as u32;
+
+            capture.extend_from_slice(&block_type.to_be_bytes());
+            capture.extend_from_slice(&length.to_be_bytes());
+            capture.extend_from_slice(body);
+            capture.extend_from_slice(&length.to_be_bytes());
+        }
+
+        let mut capture = Vec::new();
+
+        let mut section_header = PCAPNG_BYTE_ORDER_MAGIC.to_be_bytes().to_vec();
+        section_header.extend_from_slice(&[0, 1, 0, 0]);
+        section_header.extend_from_slice(&[0xFF; 8]);
+        block(&mut capture, PCAPNG_SECTION_HEADER, &section_header);
+        block(&mut capture, 1, &[0, 1, 0, 0, 0, 0, 0, 0]);
+
+        for frame in frames {
+            let mut body = vec![0; 12];
+            body.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+            body.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+            body.extend_from_slice(frame);
+            body.resize(body.len().div_ceil(4) * 4, 0);
+
+            block(&mut capture, 6, &body);
+        }
+
+        capture
+    }
+
+    fn frame(id: i32, data: &[u8]) -> Vec<u8> {
+        let mut vec = Vec::new();
+        RawPacket::new(id, data.to_vec()).encode(&mut vec).unwrap();
+
+        vec
+    }
+
+    fn compressed_frame(id: i32, data: &[u8]) -> Vec<u8> {
+        let mut vec = Vec::new();
+        CompressedRawPacket::from(RawPacket::new(id, data.to_vec()))
+            .encode(&mut vec, 64)
+            .unwrap();
+
+        vec
+    }
+
+    fn handshake() -> Vec<u8> {
+        let mut data = Vec::new();
+        data.write_var_i32(498).unwrap();
+        data.write_string("localhost", SERVER_ADDRESS_MAX_LENGTH)
+            .unwrap();
+        data.extend_from_slice(&DEFAULT_PORT.to_be_bytes());
+        data.write_var_i32(2).unwrap();
+
+        frame(0x00, &data)
+    }
+
+    fn encrypt(mut data: Vec<u8>) -> Vec<u8> {
+        let mut encryptor = cfb8::Encryptor::<Aes128>::new(&SECRET.into(), &SECRET.into());
+
+        for byte in data.iter_mut() {
+            encryptor.encrypt_block_mut(GenericArray::from_mut_slice(std::slice::from_mut(byte)));
+        }
+
+        data
+    }
+
+    fn summary(packets: &[CapturedPacket]) -> Vec<(Bound, State, i32)> {
+        packets
+            .iter()
+            .map(|p| (p.bound, p.state, p.raw_packet.id))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args(
+            "session.pcap --out capture --port 25566 --secret 000102030405060708090a0b0c0d0e0f",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            options,
+            Options {
+                input: PathBuf::from("session.pcap"),
+                out: PathBuf::from("capture"),
+                port: 25566,
+                secret: Some([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_args_short_secret() {
+        let error = parse_args(args("session.pcap --out capture --secret 0001"))
+            .expect_err("Expected error because secret is too short");
+
+        assert_eq!(error, "secret must be 16 bytes, got 2");
+    }
+
+    #[test]
+    fn test_import_compressed_session() {
+        let mut server_bound = handshake();
+        server_bound.extend(frame(0x00, b"\x08Username"));
+        let chat_message = compressed_frame(0x03, b"\x05hello");
+
+        let mut client_bound = frame(0x03, &[64]);
+        client_bound.extend(compressed_frame(0x02, &[0; 46]));
+        client_bound.extend(compressed_frame(0x20, &[0; 8]));
+        client_bound.extend(compressed_frame(0x0E, &[0; 100]));
+
+        let split = client_bound.len() - 20;
+        let server_bound_seq = 1001 + server_bound.len() as u32;
+
+        let frames = [
+            tcp_frame(Bound::Server, 1000, true, &[]),
+            tcp_frame(Bound::Client, 5000, true, &[]),
+            tcp_frame(Bound::Server, 1001, false, &server_bound[..10]),
+            tcp_frame(Bound::Server, 1011, false, &server_bound[10..]),
+            // Out of order and retransmitted segments.
+            tcp_frame(
+                Bound::Client,
+                5001 + split as u32,
+                false,
+                &client_bound[split..],
+            ),
+            tcp_frame(Bound::Client, 5001, false, &client_bound[..split]),
+            tcp_frame(Bound::Client, 5001, false, &client_bound[..split]),
+            tcp_frame(Bound::Server, server_bound_seq, false, &chat_message),
+        ];
+
+        let packets = import(&pcap(&frames), DEFAULT_PORT, None).unwrap();
+
+        assert_eq!(
+            summary(&packets),
+            vec![
+                (Bound::Server, State::Handshake, 0x00),
+                (Bound::Server, State::Login, 0x00),
+                (Bound::Client, State::Login, 0x03),
+                (Bound::Client, State::Login, 0x02),
+                (Bound::Client, State::Game, 0x20),
+                (Bound::Client, State::Game, 0x0E),
+                (Bound::Server, State::Game, 0x03),
+            ]
+        );
+        assert_eq!(packets[6].raw_packet.data, b"\x05hello");
+        assert_eq!(packets[5].raw_packet.data, vec![0; 100]);
+        assert_eq!(packets[4].file_name(4), "0004_client_game_0x20.dat");
+    }
+
+    #[test]
+    fn test_import_encrypted_session() {
+        let mut server_bound = handshake();
+        server_bound.extend(frame(0x00, b"\x08Username"));
+        server_bound.extend(frame(0x01, &[0; 10]));
+        let chat_message = encrypt(frame(0x03, b"\x05hello"));
+
+        let encryption_request = frame(0x01, &[0; 10]);
+        let mut client_bound = frame(0x02, &[0; 46]);
+        client_bound.extend(frame(0x20, &[0; 8]));
+        let client_bound = encrypt(client_bound);
+
+        let frames = [
+            tcp_frame(Bound::Server, 1000, false, &server_bound[..30]),
+            tcp_frame(Bound::Client, 5000, false, &encryption_request),
+            tcp_frame(Bound::Server, 1030, false, &server_bound[30..]),
+            tcp_frame(
+                Bound::Client,
+                5000 + encryption_request.len() as u32,
+                false,
+                &client_bound,
+            ),
+            tcp_frame(
+                Bound::Server,
+                1000 + server_bound.len() as u32,
+                false,
+                &chat_message,
+            ),
+        ];
+
+        let packets = import(&pcapng(&frames), DEFAULT_PORT, Some(SECRET)).unwrap();
+
+        assert_eq!(
+            summary(&packets),
+            vec![
+                (Bound::Server, State::Handshake, 0x00),
+                (Bound::Server, State::Login, 0x00),
+                (Bound::Client, State::Login, 0x01),
+                (Bound::Server, State::Login, 0x01),
+                (Bound::Client, State::Login, 0x02),
+                (Bound::Client, State::Game, 0x20),
+                (Bound::Server, State::Game, 0x03),
+            ]
+        );
+        assert_eq!(packets[6].raw_packet.data, b"\x05hello");
+
+        let error = import(&pcapng(&frames), DEFAULT_PORT, None)
+            .expect_err("Expected error because session is encrypted");
+
+        assert_eq!(error, "session is encrypted, `--secret` is required");
+    }
+
+    #[test]
+    fn test_import_no_connection() {
+        let frames = [tcp_frame(Bound::Server, 1000, true, &[])];
+
+        let error = import(&pcap(&frames), 25566, None)
+            .expect_err("Expected error because there is no connection to port");
+
+        assert_eq!(error, "no connection to port 25566 in capture");
+    }
+}
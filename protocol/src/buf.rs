@@ -0,0 +1,341 @@
+//! Decoding from `bytes::Buf` and encoding into `bytes::BufMut`.
+//!
+//! Network code usually keeps received bytes in `BytesMut`. Types can be decoded from it without
+//! wrapping it in `Read`: contiguous buffers are decoded as slices and advanced only after
+//! successful decoding, so incomplete data stays buffered until more bytes arrive.
+//!
+//! `DecoderBufExt` and `EncoderBufMutExt` read and write var ints, strings and byte arrays
+//! directly on buffer chunks, which avoids per byte `Read` calls on hot paths.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::BytesMut;
+//! use minecraft_protocol::buf::{decode_frame, EncoderBufMutExt};
+//! use minecraft_protocol::error::DecodeError;
+//!
+//! let mut buf = BytesMut::new();
+//! buf.put_var_i32(3).unwrap();
+//! buf.put_var_i32(0x20).unwrap();
+//!
+//! // Frame is incomplete, nothing is consumed.
+//! assert!(matches!(decode_frame(&mut buf), Err(DecodeError::Incomplete { .. })));
+//! assert_eq!(buf.len(), 2);
+//!
+//! buf.extend_from_slice(&[1, 2]);
+//!
+//! let raw_packet = decode_frame(&mut buf).unwrap();
+//! assert_eq!(raw_packet.data, [1, 2]);
+//! assert!(buf.is_empty());
+//! ```
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::RawPacket;
+use bytes::{Buf, BufMut, BytesMut};
+use std::convert::TryFrom;
+use std::io::{self, ErrorKind};
+
+/// Decodes value from buffer.
+///
+/// Contiguous buffer is advanced only if value is decoded successfully. Buffer consisting of
+/// several chunks is read through `Read` adapter and may be partially consumed on error.
+pub fn decode_buf<D: Decoder, B: Buf>(buf: &mut B) -> Result<D::Output, DecodeError> {
+    let chunk = buf.chunk();
+
+    if chunk.len() == buf.remaining() {
+        let mut reader = chunk;
+        let output = D::decode(&mut reader)?;
+        let consumed = chunk.len() - reader.len();
+
+        buf.advance(consumed);
+
+        Ok(output)
+    } else {
+        D::decode(&mut buf.reader())
+    }
+}
+
+/// Encodes value at the end of buffer.
+pub fn encode_buf<E: Encoder, B: BufMut>(value: &E, buf: &mut B) -> Result<(), EncodeError> {
+    value.encode(&mut buf.writer())
+}
+
+/// Splits length prefixed frame from the start of buffer.
+///
+/// Returns `DecodeError::Incomplete` without consuming anything if frame isn't received
+/// completely.
+pub fn decode_frame(buf: &mut BytesMut) -> Result<RawPacket, DecodeError> {
+    let mut reader = &buf[..];
+    let raw_packet = RawPacket::decode(&mut reader)?;
+    let consumed = buf.len() - reader.len();
+
+    buf.advance(consumed);
+
+    Ok(raw_packet)
+}
+
+/// Trait adds helper methods for `Buf` to read protocol data.
+pub trait DecoderBufExt {
+    fn get_var_i32(&mut self) -> Result<i32, DecodeError>;
+
+    fn get_var_i64(&mut self) -> Result<i64, DecodeError>;
+
+    fn get_string(&mut self, max_length: u16) -> Result<String, DecodeError>;
+
+    fn get_byte_array(&mut self) -> Result<Vec<u8>, DecodeError>;
+}
+
+macro_rules! get_signed_var_int (
+    ($type: ident, $name: ident, $max_bytes: expr) => (
+        fn $name(&mut self) -> Result<$type, DecodeError> {
+            let mut bytes = 0;
+            let mut output = 0;
+
+            loop {
+                if !self.has_remaining() {
+                    return Err(unexpected_eof());
+                }
+
+                if bytes == $max_bytes {
+                    return Err(DecodeError::VarIntTooLong { max_bytes: $max_bytes })
+                }
+
+                let byte = self.get_u8();
+                let value = (byte & 0b01111111) as $type;
+
+                output |= value << 7 * bytes;
+                bytes += 1;
+
+                if (byte & 0b10000000) == 0 {
+                    break;
+                }
+            }
+
+            Ok(output)
+        }
+   );
+);
+
+impl<B: Buf> DecoderBufExt for B {
+    get_signed_var_int!(i32, get_var_i32, 5);
+    get_signed_var_int!(i64, get_var_i64, 10);
+
+    fn get_string(&mut self, max_length: u16) -> Result<String, DecodeError> {
+        let length = self.get_var_i32()?;
+        let length =
+            u32::try_from(length).map_err(|_| DecodeError::NegativeLength { length })? as usize;
+
+        if length > crate::string_max_bytes(max_length) {
+            return Err(DecodeError::StringTooLong { length, max_length });
+        }
+
+        let value = String::from_utf8(take(self, length)?)?;
+
+        match crate::utf16_length_over(&value, max_length) {
+            Some(length) => Err(DecodeError::StringTooLong { length, max_length }),
+            None => Ok(value),
+        }
+    }
+
+    fn get_byte_array(&mut self) -> Result<Vec<u8>, DecodeError> {
+        let length = self.get_var_i32()?;
+        let length = u32::try_from(length).map_err(|_| DecodeError::NegativeLength { length })?;
+
+        take(self, length as usize)
+    }
+}
+
+/// Trait adds helper methods for `BufMut` to write protocol data.
+pub trait EncoderBufMutExt {
+    fn put_var_i32(&mut self, value: i32) -> Result<(), EncodeError>;
+
+    fn put_var_i64(&mut self, value: i64) -> Result<(), EncodeError>;
+
+    fn put_string(&mut self, value: &str, max_length: u16) -> Result<(), EncodeError>;
+
+    fn put_byte_array(&mut self, value: &[u8]) -> Result<(), EncodeError>;
+}
+
+macro_rules! put_signed_var_int (
+    ($type: ident, $unsigned: ident, $name: ident) => (
+        fn $name(&mut self, value: $type) -> Result<(), EncodeError> {
+            let mut value = value as $unsigned;
+
+            loop {
+                if !self.has_remaining_mut() {
+                    return Err(EncodeError::IOError { io_error: ErrorKind::WriteZero.into() });
+                }
+
+                if value & !0b01111111 == 0 {
+                    self.put_u8(value as u8);
+                    return Ok(());
+                }
+
+                self.put_u8((value as u8 & 0b01111111) | 0b10000000);
+                value >>= 7;
+            }
+        }
+   );
+);
+
+impl<B: BufMut> EncoderBufMutExt for B {
+    put_signed_var_int!(i32, u32, put_var_i32);
+    put_signed_var_int!(i64, u64, put_var_i64);
+
+    fn put_string(&mut self, value: &str, max_length: u16) -> Result<(), EncodeError> {
+        if let Some(length) = crate::utf16_length_over(value, max_length) {
+            return Err(EncodeError::StringTooLong {
+                field: None,
+                length,
+                max_length,
+            });
+        }
+
+        self.put_byte_array(value.as_bytes())
+    }
+
+    fn put_byte_array(&mut self, value: &[u8]) -> Result<(), EncodeError> {
+        let length = i32::try_from(value.len()).map_err(|_| EncodeError::LengthOutOfRange {
+            length: value.len(),
+        })?;
+        self.put_var_i32(length)?;
+
+        if self.remaining_mut() < value.len() {
+            return Err(EncodeError::IOError {
+                io_error: ErrorKind::WriteZero.into(),
+            });
+        }
+
+        self.put_slice(value);
+
+        Ok(())
+    }
+}
+
+fn unexpected_eof() -> DecodeError {
+    io::Error::from(ErrorKind::UnexpectedEof).into()
+}
+
+/// Copies bytes checking length first, as `Buf` methods panic on missing data.
+fn take<B: Buf>(buf: &mut B, length: usize) -> Result<Vec<u8>, DecodeError> {
+    if buf.remaining() < length {
+        return Err(unexpected_eof());
+    }
+
+    let mut bytes = vec![0; length];
+    buf.copy_to_slice(&mut bytes);
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buf::*;
+    use crate::decoder::DecoderReadExt;
+    use crate::encoder::EncoderWriteExt;
+    use bytes::Bytes;
+
+    #[test]
+    fn test_var_int_matches_write_ext() {
+        for value in [0, 1, 127, 128, 25565, i32::MAX] {
+            let mut vec = Vec::new();
+            vec.write_var_i32(value).unwrap();
+
+            let mut buf = BytesMut::new();
+            buf.put_var_i32(value).unwrap();
+            assert_eq!(&buf[..], &vec[..]);
+
+            assert_eq!(buf.get_var_i32().unwrap(), value);
+            assert!(buf.is_empty());
+        }
+
+        let mut buf = BytesMut::new();
+        buf.put_var_i64(i64::MIN).unwrap();
+
+        assert_eq!(buf.as_ref().read_var_i64().unwrap(), i64::MIN);
+    }
+
+    #[test]
+    fn test_negative_var_int() {
+        let mut buf = BytesMut::new();
+        buf.put_var_i32(-1).unwrap();
+
+        assert_eq!(&buf[..], &[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+        assert_eq!(buf.get_var_i32().unwrap(), -1);
+    }
+
+    #[test]
+    fn test_var_int_too_long() {
+        let mut buf = Bytes::from_static(&[0xFF; 6]);
+
+        let decode_error = buf
+            .get_var_i32()
+            .expect_err("Expected error `VarIntTooLong` because var int has 6 bytes");
+
+        match decode_error {
+            DecodeError::VarIntTooLong { max_bytes } => assert_eq!(max_bytes, 5),
+            _ => panic!("Expected `VarIntTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_string_round_trip() {
+        let mut buf = BytesMut::new();
+        buf.put_string("Привет", 16).unwrap();
+
+        assert_eq!(buf.get_string(16).unwrap(), "Привет");
+    }
+
+    #[test]
+    fn test_string_negative_length() {
+        let mut buf = Bytes::from_static(&[0xff, 0xff, 0xff, 0xff, 0x0f, b'a']);
+
+        let decode_error = buf
+            .get_string(16)
+            .expect_err("Expected error `NegativeLength` because length prefix is -1");
+
+        match decode_error {
+            DecodeError::NegativeLength { length } => assert_eq!(length, -1),
+            _ => panic!("Expected `NegativeLength` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_string_missing_bytes() {
+        let mut buf = Bytes::from_static(&[5, b'a']);
+
+        let decode_error = buf
+            .get_string(16)
+            .expect_err("Expected error `IOError` because string has only 1 of 5 bytes");
+
+        match decode_error {
+            DecodeError::IOError { io_error } => {
+                assert_eq!(io_error.kind(), ErrorKind::UnexpectedEof)
+            }
+            _ => panic!("Expected `IOError` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_decode_buf_chained() {
+        let mut vec = Vec::new();
+        vec.write_string("minecraft:brand", 32).unwrap();
+
+        let (first, second) = vec.split_at(4);
+        let mut buf = Bytes::copy_from_slice(first).chain(Bytes::copy_from_slice(second));
+
+        let mut encoded = BytesMut::new();
+        encode_buf(&decode_buf::<String, _>(&mut buf).unwrap(), &mut encoded).unwrap();
+
+        assert_eq!(&encoded[..], &vec[..]);
+    }
+
+    #[test]
+    fn test_decode_buf_error_keeps_bytes() {
+        let mut buf = Bytes::from_static(&[5, b'a']);
+
+        assert!(decode_buf::<String, _>(&mut buf).is_err());
+        assert_eq!(buf.len(), 2);
+    }
+}
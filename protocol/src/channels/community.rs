@@ -0,0 +1,402 @@
+//! Plugin channels of popular proxies and mod loaders.
+//!
+//! `lookup` tells which project uses a channel, so tools can label traffic they don't parse.
+//! Payloads are typed only where their format is stable: channel registration, Velocity modern
+//! forwarding and BungeeCord plugin messaging.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::channels::community::{self, BungeeCordMessage};
+//! use minecraft_protocol::decoder::Decoder;
+//! use minecraft_protocol::encoder::Encoder;
+//!
+//! assert_eq!(community::lookup("velocity:player_info").unwrap().project, "Velocity");
+//!
+//! let message = BungeeCordMessage::new("GetServer", Vec::new());
+//!
+//! let mut vec = Vec::new();
+//! message.encode(&mut vec).unwrap();
+//!
+//! let decoded = BungeeCordMessage::decode(&mut vec.as_slice()).unwrap();
+//! assert_eq!(decoded.subchannel, "GetServer");
+//! ```
+use crate::data::identifier::Identifier;
+use crate::decoder::{read_length, Decoder, DecoderReadExt};
+use crate::encoder::{write_length, Encoder, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use minecraft_protocol_derive::{Decoder, Encoder};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use uuid::Uuid;
+
+pub const REGISTER: &str = "minecraft:register";
+pub const UNREGISTER: &str = "minecraft:unregister";
+pub const VELOCITY_PLAYER_INFO: &str = "velocity:player_info";
+pub const BUNGEECORD: &str = "bungeecord:main";
+/// Channel name used by BungeeCord before 1.13.
+pub const BUNGEECORD_LEGACY: &str = "BungeeCord";
+pub const FABRIC_REGISTRY_SYNC: &str = "fabric:registry/sync";
+pub const FABRIC_REGISTRY_SYNC_DIRECT: &str = "fabric:registry/sync/direct";
+pub const FML_LOGIN_WRAPPER: &str = "fml:loginwrapper";
+pub const FML_HANDSHAKE: &str = "fml:handshake";
+pub const FML_PLAY: &str = "fml:play";
+
+/// Velocity forwarding version without player key.
+pub const VELOCITY_FORWARDING_DEFAULT: i32 = 1;
+
+/// Channel used by community software.
+#[derive(Debug, Eq, PartialEq)]
+pub struct CommunityChannel {
+    pub name: &'static str,
+    pub project: &'static str,
+    pub description: &'static str,
+}
+
+pub const COMMUNITY_CHANNELS: &[CommunityChannel] = &[
+    CommunityChannel {
+        name: REGISTER,
+        project: "Bukkit",
+        description: "Channels peer listens on, separated by NUL",
+    },
+    CommunityChannel {
+        name: UNREGISTER,
+        project: "Bukkit",
+        description: "Channels peer no longer listens on, separated by NUL",
+    },
+    CommunityChannel {
+        name: VELOCITY_PLAYER_INFO,
+        project: "Velocity",
+        description: "Modern player info forwarding in login plugin messages",
+    },
+    CommunityChannel {
+        name: BUNGEECORD,
+        project: "BungeeCord",
+        description: "Plugin messaging between servers and proxy",
+    },
+    CommunityChannel {
+        name: BUNGEECORD_LEGACY,
+        project: "BungeeCord",
+        description: "Plugin messaging between servers and proxy before 1.13",
+    },
+    CommunityChannel {
+        name: FABRIC_REGISTRY_SYNC,
+        project: "Fabric",
+        description: "Registry synchronization",
+    },
+    CommunityChannel {
+        name: FABRIC_REGISTRY_SYNC_DIRECT,
+        project: "Fabric",
+        description: "Uncompressed registry synchronization",
+    },
+    CommunityChannel {
+        name: FML_LOGIN_WRAPPER,
+        project: "Forge",
+        description: "Wraps handshake channels in login plugin messages",
+    },
+    CommunityChannel {
+        name: FML_HANDSHAKE,
+        project: "Forge",
+        description: "Mod list and registry handshake",
+    },
+    CommunityChannel {
+        name: FML_PLAY,
+        project: "Forge",
+        description: "Entity spawn and container messages",
+    },
+];
+
+/// Returns known channel by its name.
+pub fn lookup(channel: &str) -> Option<&'static CommunityChannel> {
+    COMMUNITY_CHANNELS.iter().find(|c| c.name == channel)
+}
+
+/// Payload of `minecraft:register` and `minecraft:unregister` channels.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RegisterChannels {
+    pub channels: Vec<Identifier>,
+}
+
+impl Encoder for RegisterChannels {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        for (index, channel) in self.channels.iter().enumerate() {
+            if index > 0 {
+                writer.write_u8(0)?;
+            }
+
+            writer.write_all(channel.as_str().as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Decoder for RegisterChannels {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        let channels = data
+            .split(|byte| *byte == 0)
+            .filter(|name| !name.is_empty())
+            .map(|name| Ok(Identifier::from(String::from_utf8(name.to_vec())?)))
+            .collect::<Result<_, DecodeError>>()?;
+
+        Ok(RegisterChannels { channels })
+    }
+}
+
+/// Payload of Velocity login plugin request.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct VelocityForwardingRequest {
+    /// Max forwarding version supported by server, sent since Velocity 3.
+    pub max_version: Option<u8>,
+}
+
+impl Encoder for VelocityForwardingRequest {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        if let Some(max_version) = self.max_version {
+            writer.write_u8(max_version)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Decoder for VelocityForwardingRequest {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        Ok(VelocityForwardingRequest {
+            max_version: data.first().copied(),
+        })
+    }
+}
+
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ProfileProperty {
+    pub name: String,
+    pub value: String,
+    pub signature: Option<String>,
+}
+
+/// Payload of Velocity login plugin response with forwarded player info.
+///
+/// Signature is HMAC-SHA256 of signed data with secret shared by proxy and server, it has to be
+/// checked before player info is trusted.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct VelocityPlayerInfo {
+    pub signature: [u8; 32],
+    pub version: i32,
+    pub address: String,
+    pub uuid: Uuid,
+    pub username: String,
+    pub properties: Vec<ProfileProperty>,
+    /// Fields added by forwarding versions after `VELOCITY_FORWARDING_DEFAULT`, e.g. player key.
+    pub extra: Vec<u8>,
+}
+
+impl VelocityPlayerInfo {
+    /// Returns data covered by signature.
+    pub fn signed_data(&self) -> Result<Vec<u8>, EncodeError> {
+        let mut data = Vec::new();
+
+        data.write_var_i32(self.version)?;
+        self.address.encode(&mut data)?;
+        self.uuid.encode(&mut data)?;
+        data.write_string(&self.username, 16)?;
+        write_length(&mut data, self.properties.len())?;
+
+        for property in &self.properties {
+            property.encode(&mut data)?;
+        }
+
+        data.write_all(&self.extra)?;
+
+        Ok(data)
+    }
+}
+
+impl Encoder for VelocityPlayerInfo {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        writer.write_all(&self.signature)?;
+        writer.write_all(&self.signed_data()?)?;
+
+        Ok(())
+    }
+}
+
+impl Decoder for VelocityPlayerInfo {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let mut signature = [0; 32];
+        reader.read_exact(&mut signature)?;
+
+        let version = reader.read_var_i32()?;
+        let address = String::decode(reader)?;
+        let uuid = Uuid::decode(reader)?;
+        let username = reader.read_string(16)?;
+
+        let length = read_length(reader)?;
+        let mut properties = Vec::new();
+
+        for _ in 0..length {
+            properties.push(ProfileProperty::decode(reader)?);
+        }
+
+        let mut extra = Vec::new();
+        reader.read_to_end(&mut extra)?;
+
+        Ok(VelocityPlayerInfo {
+            signature,
+            version,
+            address,
+            uuid,
+            username,
+            properties,
+            extra,
+        })
+    }
+}
+
+/// BungeeCord plugin message, data format depends on subchannel.
+///
+/// Subchannel is written like Java `DataOutput::writeUTF` with unsigned short length prefix.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct BungeeCordMessage {
+    pub subchannel: String,
+    pub data: Vec<u8>,
+}
+
+impl BungeeCordMessage {
+    pub fn new(subchannel: &str, data: Vec<u8>) -> Self {
+        BungeeCordMessage {
+            subchannel: subchannel.to_owned(),
+            data,
+        }
+    }
+}
+
+impl Encoder for BungeeCordMessage {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        let length = self.subchannel.len();
+
+        if length > u16::MAX as usize {
+            return Err(EncodeError::StringTooLong {
+                field: Some("subchannel"),
+                length,
+                max_length: u16::MAX,
+            });
+        }
+
+        writer.write_u16::<BigEndian>(length as u16)?;
+        writer.write_all(self.subchannel.as_bytes())?;
+        writer.write_all(&self.data)?;
+
+        Ok(())
+    }
+}
+
+impl Decoder for BungeeCordMessage {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let length = reader.read_u16::<BigEndian>()?;
+        let mut buf = vec![0; length as usize];
+        reader.read_exact(&mut buf)?;
+
+        let subchannel = String::from_utf8(buf)?;
+
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        Ok(BungeeCordMessage { subchannel, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channels::community::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup(FML_HANDSHAKE).unwrap().project, "Forge");
+        assert_eq!(lookup("minecraft:brand"), None);
+    }
+
+    #[test]
+    fn test_register_channels_round_trip() {
+        let register_channels = RegisterChannels {
+            channels: vec![
+                Identifier::from(BUNGEECORD),
+                Identifier::from("fabric:test"),
+            ],
+        };
+
+        let mut vec = Vec::new();
+        register_channels.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, b"bungeecord:main\0fabric:test");
+        assert_eq!(
+            RegisterChannels::decode(&mut Cursor::new(vec)).unwrap(),
+            register_channels
+        );
+    }
+
+    #[test]
+    fn test_velocity_forwarding_request() {
+        let request = VelocityForwardingRequest::decode(&mut Cursor::new(vec![])).unwrap();
+        assert_eq!(request.max_version, None);
+
+        let request = VelocityForwardingRequest::decode(&mut Cursor::new(vec![4])).unwrap();
+        assert_eq!(request.max_version, Some(4));
+    }
+
+    #[test]
+    fn test_velocity_player_info_round_trip() {
+        let player_info = VelocityPlayerInfo {
+            signature: [7; 32],
+            version: VELOCITY_FORWARDING_DEFAULT,
+            address: String::from("192.168.0.1"),
+            uuid: Uuid::parse_str("2a1e1912-7103-4add-80fc-91ebc346cbce").unwrap(),
+            username: String::from("Username"),
+            properties: vec![ProfileProperty {
+                name: String::from("textures"),
+                value: String::from("e30="),
+                signature: None,
+            }],
+            extra: Vec::new(),
+        };
+
+        let mut vec = Vec::new();
+        player_info.encode(&mut vec).unwrap();
+
+        assert_eq!(&vec[32..], player_info.signed_data().unwrap().as_slice());
+        assert_eq!(
+            VelocityPlayerInfo::decode(&mut Cursor::new(vec)).unwrap(),
+            player_info
+        );
+    }
+
+    #[test]
+    fn test_bungeecord_message_round_trip() {
+        let message = BungeeCordMessage::new("Connect", b"\x00\x05lobby".to_vec());
+
+        let mut vec = Vec::new();
+        message.encode(&mut vec).unwrap();
+
+        assert_eq!(&vec[..9], b"\x00\x07Connect");
+        assert_eq!(
+            BungeeCordMessage::decode(&mut Cursor::new(vec)).unwrap(),
+            message
+        );
+    }
+}
@@ -0,0 +1,6 @@
+//! Plugin message channels and their payloads.
+//!
+//! Payloads of plugin messages and login plugin requests are opaque bytes for the protocol. Types
+//! here give structure to channels used widely enough that tools need to understand them.
+#[cfg(feature = "community-channels")]
+pub mod community;
@@ -0,0 +1,170 @@
+//! Grouping of chunk packets into per-tick batches.
+//!
+//! Sending every loaded chunk at once saturates slow connections, so servers spread chunks over
+//! ticks. `ChunkBatcher` queues chunk packets and hands out batches which fit into a byte budget.
+//! Since 1.20.2 each batch is wrapped with `ChunkBatchStart` and `ChunkBatchFinished` packets and
+//! client reports how many chunks per tick it can handle with `ChunkBatchReceived`.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::chunk_batch::ChunkBatcher;
+//! use minecraft_protocol::version::v1_14_4::game::ClientBoundKeepAlive;
+//!
+//! let mut batcher = ChunkBatcher::new(24);
+//!
+//! for id in 0..3 {
+//!     // Each packet takes 10 bytes.
+//!     batcher.push(ClientBoundKeepAlive::new(id)).unwrap();
+//! }
+//!
+//! assert_eq!(batcher.next_batch().len(), 2);
+//! assert_eq!(batcher.next_batch().len(), 1);
+//! assert!(batcher.is_empty());
+//! # }
+//! ```
+use crate::error::EncodeError;
+use crate::packet::Packet;
+#[cfg(feature = "rayon")]
+use crate::par_encode::{par_encode, BufferPool};
+use std::collections::VecDeque;
+
+/// Queue of packets split into batches under byte budget.
+#[derive(Debug)]
+pub struct ChunkBatcher<P> {
+    budget: usize,
+    queue: VecDeque<(P, usize)>,
+}
+
+impl<P: Packet> ChunkBatcher<P> {
+    /// Creates batcher with budget in bytes per batch.
+    pub fn new(budget: usize) -> Self {
+        ChunkBatcher {
+            budget,
+            queue: VecDeque::new(),
+        }
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Changes budget, for example after client reports its processing rate.
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+    }
+
+    /// Queues packet. Encoded length is computed once here.
+    pub fn push(&mut self, packet: P) -> Result<(), EncodeError> {
+        let length = packet.encoded_len()?;
+        self.queue.push_back((packet, length));
+
+        Ok(())
+    }
+
+    /// Returns queued packets which fit into budget in queue order.
+    ///
+    /// Packet bigger than budget is returned alone, so the queue always makes progress.
+    pub fn next_batch(&mut self) -> Vec<P> {
+        let mut batch = Vec::new();
+        let mut size = 0;
+
+        while let Some((_, length)) = self.queue.front() {
+            if !batch.is_empty() && size + length > self.budget {
+                break;
+            }
+
+            let (packet, length) = self.queue.pop_front().unwrap();
+            size += length;
+            batch.push(packet);
+        }
+
+        batch
+    }
+
+    /// Returns total encoded length of queued packets.
+    pub fn queued_len(&self) -> usize {
+        self.queue.iter().map(|(_, length)| length).sum()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<P: Packet + Sync> ChunkBatcher<P> {
+    /// Returns next batch encoded into frames in parallel. See `par_encode`.
+    pub fn next_batch_encoded(
+        &mut self,
+        compression_threshold: Option<usize>,
+        pool: &BufferPool,
+    ) -> Result<Vec<Vec<u8>>, EncodeError> {
+        par_encode(&self.next_batch(), compression_threshold, pool)
+    }
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::chunk_batch::ChunkBatcher;
+    use crate::packet::Packet;
+    use crate::version::v1_14_4::game::{ChunkData, GameClientBoundPacket};
+    use nbt::CompoundTag;
+
+    fn chunk_data(size: usize) -> GameClientBoundPacket {
+        ChunkData::new(0, 0, true, 0, CompoundTag::new(), vec![0; size], vec![])
+    }
+
+    #[test]
+    fn test_next_batch_under_budget() {
+        let packet_len = chunk_data(1000).encoded_len().unwrap();
+        let mut batcher = ChunkBatcher::new(packet_len * 2);
+
+        for _ in 0..5 {
+            batcher.push(chunk_data(1000)).unwrap();
+        }
+
+        assert_eq!(batcher.queued_len(), packet_len * 5);
+        assert_eq!(batcher.next_batch().len(), 2);
+        assert_eq!(batcher.next_batch().len(), 2);
+        assert_eq!(batcher.next_batch().len(), 1);
+        assert!(batcher.next_batch().is_empty());
+    }
+
+    #[test]
+    fn test_next_batch_packet_over_budget() {
+        let mut batcher = ChunkBatcher::new(100);
+        batcher.push(chunk_data(1000)).unwrap();
+        batcher.push(chunk_data(10)).unwrap();
+
+        assert_eq!(batcher.next_batch().len(), 1);
+        assert_eq!(batcher.len(), 1);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_next_batch_encoded() {
+        use crate::par_encode::BufferPool;
+
+        let packet_len = chunk_data(1000).encoded_len().unwrap();
+        let mut batcher = ChunkBatcher::new(packet_len * 2);
+
+        for _ in 0..3 {
+            batcher.push(chunk_data(1000)).unwrap();
+        }
+
+        let frames = batcher
+            .next_batch_encoded(None, &BufferPool::new())
+            .unwrap();
+
+        assert_eq!(frames.len(), 2);
+        assert!(frames.iter().all(|frame| frame.len() == packet_len));
+        assert_eq!(batcher.len(), 1);
+    }
+}
@@ -0,0 +1,303 @@
+//! Tracking of chunks loaded by client.
+//!
+//! Client keeps chunks within view distance around its view position, which server moves with
+//! update view position packets. `ChunkTracker` computes chunks to send with chunk data and to
+//! drop with unload chunk packets when player moves, view distance changes or player respawns.
+//!
+//! Chunks to load are ordered in a square spiral starting at the view position like vanilla sends
+//! them, so pushing them to `ChunkBatcher` sends the nearest chunks first.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::chunk_tracker::ChunkTracker;
+//! use minecraft_protocol::data::game::ChunkPosition;
+//!
+//! let mut tracker = ChunkTracker::new(2);
+//!
+//! let update = tracker.move_to(ChunkPosition::containing(8.0, 8.0));
+//! assert_eq!(update.load.len(), 25);
+//! assert_eq!(update.load[0], ChunkPosition::new(0, 0));
+//!
+//! // Moving one chunk east loads and unloads a column of 5 chunks.
+//! let update = tracker.move_to(ChunkPosition::containing(24.0, 8.0));
+//! assert_eq!(update.load.len(), 5);
+//! assert_eq!(update.unload.len(), 5);
+//! ```
+use crate::chunk_batch::ChunkBatcher;
+use crate::data::game::ChunkPosition;
+use crate::error::EncodeError;
+use crate::packet::Packet;
+
+/// Chunks to send after tracker update.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ChunkUpdate {
+    /// New view position to send with update view position packet.
+    pub view_position: Option<ChunkPosition>,
+    /// Chunks to send in spiral order around view position.
+    pub load: Vec<ChunkPosition>,
+    /// Chunks to unload.
+    pub unload: Vec<ChunkPosition>,
+}
+
+impl ChunkUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.view_position.is_none() && self.load.is_empty() && self.unload.is_empty()
+    }
+
+    /// Queues chunk packets created by `chunk` in load order.
+    pub fn push_loads<P, F>(
+        &self,
+        batcher: &mut ChunkBatcher<P>,
+        mut chunk: F,
+    ) -> Result<(), EncodeError>
+    where
+        P: Packet,
+        F: FnMut(ChunkPosition) -> P,
+    {
+        for position in &self.load {
+            batcher.push(chunk(*position))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Chunks loaded by one client.
+#[derive(Debug, Clone)]
+pub struct ChunkTracker {
+    view_distance: u32,
+    view_position: Option<ChunkPosition>,
+}
+
+impl ChunkTracker {
+    /// Creates tracker with view distance in chunks. Nothing is loaded before `move_to`.
+    pub fn new(view_distance: u32) -> Self {
+        ChunkTracker {
+            view_distance,
+            view_position: None,
+        }
+    }
+
+    pub fn view_distance(&self) -> u32 {
+        self.view_distance
+    }
+
+    pub fn view_position(&self) -> Option<ChunkPosition> {
+        self.view_position
+    }
+
+    /// Returns whether client has chunk loaded.
+    pub fn is_loaded(&self, position: ChunkPosition) -> bool {
+        match self.view_position {
+            Some(view_position) => view_position.distance(position) <= self.view_distance,
+            None => false,
+        }
+    }
+
+    /// Returns loaded chunks in spiral order.
+    pub fn loaded(&self) -> Vec<ChunkPosition> {
+        match self.view_position {
+            Some(view_position) => spiral(view_position, self.view_distance).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Moves view position to chunk player is in. Nothing changes while player stays in chunk.
+    pub fn move_to(&mut self, view_position: ChunkPosition) -> ChunkUpdate {
+        if self.view_position == Some(view_position) {
+            return ChunkUpdate::default();
+        }
+
+        self.update(Some(view_position), self.view_distance)
+    }
+
+    /// Changes view distance, e.g. after client settings or server config change.
+    pub fn set_view_distance(&mut self, view_distance: u32) -> ChunkUpdate {
+        let mut update = self.update(self.view_position, view_distance);
+        update.view_position = None;
+
+        update
+    }
+
+    /// Resets tracker after respawn packet, as client drops all chunks on respawn.
+    pub fn respawn(&mut self, view_position: ChunkPosition) -> ChunkUpdate {
+        self.view_position = None;
+
+        self.update(Some(view_position), self.view_distance)
+    }
+
+    /// Forgets loaded chunks, e.g. when player disconnects.
+    pub fn clear(&mut self) {
+        self.view_position = None;
+    }
+
+    fn update(&mut self, view_position: Option<ChunkPosition>, view_distance: u32) -> ChunkUpdate {
+        let old = self.clone();
+
+        self.view_position = view_position;
+        self.view_distance = view_distance;
+
+        let load = self
+            .loaded()
+            .into_iter()
+            .filter(|position| !old.is_loaded(*position))
+            .collect();
+        let unload = old
+            .loaded()
+            .into_iter()
+            .filter(|position| !self.is_loaded(*position))
+            .collect();
+
+        ChunkUpdate {
+            view_position,
+            load,
+            unload,
+        }
+    }
+}
+
+/// Returns chunks within distance, ring by ring starting at center.
+///
+/// Each ring is walked clockwise from its north west corner.
+pub fn spiral(center: ChunkPosition, distance: u32) -> impl Iterator<Item = ChunkPosition> {
+    let distance = distance as i32;
+
+    (0..=distance).flat_map(move |ring| {
+        let length = (8 * ring).max(1);
+
+        (0..length).map(move |index| {
+            let (dx, dz) = ring_offset(ring, index);
+
+            ChunkPosition::new(center.x + dx, center.z + dz)
+        })
+    })
+}
+
+fn ring_offset(ring: i32, index: i32) -> (i32, i32) {
+    if ring == 0 {
+        return (0, 0);
+    }
+
+    let side = 2 * ring;
+    let step = index % side;
+
+    match index / side {
+        0 => (-ring + step, -ring),
+        1 => (ring, -ring + step),
+        2 => (ring - step, ring),
+        _ => (-ring, ring - step),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chunk_tracker::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_spiral_order() {
+        let positions: Vec<_> = spiral(ChunkPosition::new(10, -5), 2).collect();
+        let unique: HashSet<_> = positions.iter().collect();
+
+        assert_eq!(positions.len(), 25);
+        assert_eq!(unique.len(), 25);
+        assert_eq!(positions[0], ChunkPosition::new(10, -5));
+        assert_eq!(positions[1], ChunkPosition::new(9, -6));
+        assert_eq!(positions[2], ChunkPosition::new(10, -6));
+        assert_eq!(positions[8], ChunkPosition::new(9, -5));
+
+        let center = ChunkPosition::new(10, -5);
+        let distances: Vec<_> = positions.iter().map(|p| p.distance(center)).collect();
+        let mut sorted = distances.clone();
+        sorted.sort_unstable();
+
+        assert_eq!(distances, sorted);
+    }
+
+    #[test]
+    fn test_move_diagonally() {
+        let mut tracker = ChunkTracker::new(3);
+        tracker.move_to(ChunkPosition::new(0, 0));
+
+        let update = tracker.move_to(ChunkPosition::new(1, 1));
+
+        assert_eq!(update.view_position, Some(ChunkPosition::new(1, 1)));
+        assert_eq!(update.load.len(), 13);
+        assert_eq!(update.unload.len(), 13);
+        assert!(update.load.iter().all(|p| p.x == 4 || p.z == 4));
+        assert!(update.unload.iter().all(|p| p.x == -3 || p.z == -3));
+        assert!(tracker.move_to(ChunkPosition::new(1, 1)).is_empty());
+    }
+
+    #[test]
+    fn test_teleport_far() {
+        let mut tracker = ChunkTracker::new(2);
+        tracker.move_to(ChunkPosition::new(0, 0));
+
+        let update = tracker.move_to(ChunkPosition::new(100, 0));
+
+        assert_eq!(update.load.len(), 25);
+        assert_eq!(update.unload.len(), 25);
+        assert_eq!(update.load[0], ChunkPosition::new(100, 0));
+    }
+
+    #[test]
+    fn test_set_view_distance() {
+        let mut tracker = ChunkTracker::new(2);
+        tracker.move_to(ChunkPosition::new(0, 0));
+
+        let update = tracker.set_view_distance(3);
+        assert_eq!(update.view_position, None);
+        assert_eq!(update.load.len(), 49 - 25);
+        assert!(update.unload.is_empty());
+
+        let update = tracker.set_view_distance(1);
+        assert!(update.load.is_empty());
+        assert_eq!(update.unload.len(), 49 - 9);
+        assert_eq!(tracker.loaded().len(), 9);
+    }
+
+    #[test]
+    fn test_respawn_loads_everything() {
+        let mut tracker = ChunkTracker::new(1);
+        tracker.move_to(ChunkPosition::new(0, 0));
+
+        let update = tracker.respawn(ChunkPosition::new(0, 0));
+
+        assert_eq!(update.load.len(), 9);
+        assert!(update.unload.is_empty());
+    }
+
+    #[cfg(feature = "v1_14_4")]
+    #[test]
+    fn test_push_loads() {
+        use crate::version::v1_14_4::game::{ChunkData, GameClientBoundPacket};
+        use nbt::CompoundTag;
+
+        let mut tracker = ChunkTracker::new(1);
+        let update = tracker.move_to(ChunkPosition::new(5, 5));
+
+        let mut batcher = ChunkBatcher::new(usize::MAX);
+        update
+            .push_loads(&mut batcher, |position| {
+                ChunkData::new(
+                    position.x,
+                    position.z,
+                    true,
+                    0,
+                    CompoundTag::new(),
+                    vec![],
+                    vec![],
+                )
+            })
+            .unwrap();
+
+        match &batcher.next_batch()[0] {
+            GameClientBoundPacket::ChunkData(chunk_data) => {
+                assert_eq!((chunk_data.x, chunk_data.z), (5, 5))
+            }
+            _ => panic!("Expected chunk data packet"),
+        }
+    }
+}
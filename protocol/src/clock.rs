@@ -0,0 +1,142 @@
+//! Source of time for timeouts, rate limits and latency.
+//!
+//! Helpers which measure time read it from a `Clock`. `SystemClock` reads time of the operating
+//! system, `MockClock` moves only when advanced, so tests of expiry and timeouts don't sleep and
+//! always give the same result.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::clock::{Clock, MockClock};
+//! use minecraft_protocol::login::LoginRateLimiter;
+//! use std::net::{IpAddr, Ipv4Addr};
+//! use std::time::Duration;
+//!
+//! let clock = MockClock::new();
+//! let mut rate_limiter = LoginRateLimiter::with_clock(1, Duration::from_secs(30), clock.clone());
+//! let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+//!
+//! assert!(rate_limiter.try_acquire(addr));
+//! assert!(!rate_limiter.try_acquire(addr));
+//!
+//! clock.advance(Duration::from_secs(30));
+//! assert!(rate_limiter.try_acquire(addr));
+//! ```
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of monotonic and wall-clock time.
+pub trait Clock {
+    /// Monotonic time used for timeouts and latency.
+    fn now(&self) -> Instant;
+
+    /// Wall-clock time used for timestamps sent to peer.
+    fn system_time(&self) -> SystemTime;
+
+    /// Returns time passed since earlier instant, zero if it's in the future.
+    fn elapsed_since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn system_time(&self) -> SystemTime {
+        (**self).system_time()
+    }
+}
+
+/// Time of the operating system.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Clock which moves only when advanced. Clones share time.
+///
+/// Wall-clock time starts at Unix epoch unless set with `starting_at`.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    start: Instant,
+    start_system_time: SystemTime,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        MockClock::starting_at(UNIX_EPOCH)
+    }
+
+    pub fn starting_at(system_time: SystemTime) -> Self {
+        MockClock {
+            start: Instant::now(),
+            start_system_time: system_time,
+            elapsed: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    /// Moves time forward for all clones of the clock.
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+
+    /// Time the clock was advanced by since it was created.
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        MockClock::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        MockClock::now(self)
+    }
+
+    fn system_time(&self) -> SystemTime {
+        self.start_system_time + self.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::*;
+
+    #[test]
+    fn test_mock_clock_shared_by_clones() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let clone = clock.clone();
+
+        clone.advance(Duration::from_secs(2));
+
+        assert_eq!(clock.elapsed_since(start), Duration::from_secs(2));
+        assert_eq!(
+            Clock::system_time(&clock),
+            UNIX_EPOCH + Duration::from_secs(2)
+        );
+        assert_eq!(
+            clock.elapsed_since(start + Duration::from_secs(5)),
+            Duration::ZERO
+        );
+    }
+}
@@ -0,0 +1,1064 @@
+//! Packet codec for connection streams.
+//!
+//! `PacketCodec` reads and writes packet frames on a stream. Frame header and packet data are
+//! written with one vectored write, and flushing is controlled by `FlushPolicy`, so small packets
+//! can be coalesced when the stream is buffered.
+//!
+//! Frames longer than `max_packet_length` are rejected in both directions, by default it's the
+//! vanilla limit of 2 MiB.
+//!
+//! Compression is enabled with `set_compression_threshold` after `SetCompression` packet is sent
+//! or received. Packets with ids set by `set_compression_override` are compressed as override
+//! tells, e.g. chunk data with higher level and plugin messages carrying compressed data not at
+//! all.
+//!
+//! Packets which are sent to many players again and again, like tab list updates, can be written
+//! with `write_cached`. Their frames are kept in `FrameCache` keyed by stable hash of the packet,
+//! so they are not compressed and allocated again. Hash is not collision resistant, cached bytes
+//! of packet are compared on every hit.
+//!
+//! Packet data is buffered before it's framed as set by `EncodeStrategy`. By default every packet
+//! is encoded into a new vector, `Buffered` avoids allocations for small packets and `TwoPass`
+//! encodes huge packets straight into the stream.
+//!
+//! Codec settings can be saved with `export_state` and applied to another codec over the same
+//! connection with `restore_state`, e.g. when proxy hands socket over to a new process. Codec
+//! doesn't buffer read data, so no bytes are lost between the two.
+//!
+//! Reading and writing can be moved to separate threads with `into_split`, see `split` module.
+//! With `encryption` feature, `enable_encryption` encrypts the stream after login, see
+//! `encryption` module.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::codec::{FlushPolicy, PacketCodec};
+//! use minecraft_protocol::version::v1_14_4::game::{ClientBoundKeepAlive, GameClientBoundPacket};
+//!
+//! let mut codec = PacketCodec::new(Vec::new()).with_flush_policy(FlushPolicy::Manual);
+//! let packets = vec![ClientBoundKeepAlive::new(1), ClientBoundKeepAlive::new(2)];
+//!
+//! codec.write_bundle(&packets).unwrap();
+//! codec.flush().unwrap();
+//!
+//! let stream = codec.into_inner();
+//! let mut codec = PacketCodec::new(stream.as_slice());
+//! let packet: GameClientBoundPacket = codec.read_packet().unwrap();
+//!
+//! assert!(matches!(packet, GameClientBoundPacket::ClientBoundKeepAlive(_)));
+//! # }
+//! ```
+use crate::compression::{CompressedRawPacket, CompressionOverride};
+use crate::decoder::DecoderReadExt;
+use crate::encoder::{var_i32_size, EncoderWriteExt};
+use crate::error::StateError;
+use crate::error::{DecodeError, EncodeError};
+use crate::limits::{self, DecodeLimits};
+use crate::packet::{write_all_vectored, LengthCounter, Packet, RawPacket, PACKET_MAX_LENGTH};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, IoSlice, Read, Write};
+
+/// Version of `CodecState` format, increased on incompatible changes.
+pub const CODEC_STATE_VERSION: u32 = 1;
+
+/// When codec flushes underlying stream.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum FlushPolicy {
+    /// Flush after every written packet and bundle.
+    #[default]
+    PerPacket,
+    /// Flush after every bundle, single packets are not flushed.
+    PerBundle,
+    /// Flush only when `flush` is called.
+    Manual,
+}
+
+/// Length of stack buffer used by `EncodeStrategy::Buffered`.
+pub const STACK_BUFFER_LENGTH: usize = 256;
+
+/// How `write_packet` buffers packet data before writing the frame.
+///
+/// Strategy applies only without compression, compressed packets are always encoded into a new
+/// vector as they are compressed anyway.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum EncodeStrategy {
+    /// Data is encoded into a new vector for every packet.
+    #[default]
+    Allocate,
+    /// Data up to `STACK_BUFFER_LENGTH` bytes is encoded on stack, bigger packets continue in
+    /// heap buffer which codec keeps for the next packets.
+    Buffered,
+    /// Packet is encoded twice, first only to count its length and then straight into the
+    /// stream after the frame header. Nothing is buffered, but stream gets many small writes, so
+    /// it should be buffered.
+    TwoPass,
+}
+
+/// Settings of `PacketCodec` which must survive connection handover.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CodecState {
+    /// Format version, see `CODEC_STATE_VERSION`.
+    pub version: u32,
+    pub flush_policy: FlushPolicy,
+    pub compression_threshold: Option<usize>,
+    /// Missing in states exported before it was added.
+    #[serde(default = "default_max_packet_length")]
+    pub max_packet_length: usize,
+}
+
+fn default_max_packet_length() -> usize {
+    PACKET_MAX_LENGTH
+}
+
+/// Reads and writes packet frames on a stream.
+#[derive(Debug)]
+pub struct PacketCodec<S> {
+    stream: S,
+    flush_policy: FlushPolicy,
+    compression_threshold: Option<usize>,
+    max_packet_length: usize,
+    encode_strategy: EncodeStrategy,
+    compression_overrides: HashMap<i32, CompressionOverride>,
+    buffer: Vec<u8>,
+    decode_limits: Option<DecodeLimits>,
+}
+
+impl<S> PacketCodec<S> {
+    pub fn new(stream: S) -> Self {
+        PacketCodec {
+            stream,
+            flush_policy: FlushPolicy::default(),
+            compression_threshold: None,
+            max_packet_length: PACKET_MAX_LENGTH,
+            encode_strategy: EncodeStrategy::default(),
+            compression_overrides: HashMap::new(),
+            buffer: Vec::new(),
+            decode_limits: None,
+        }
+    }
+
+    pub fn with_flush_policy(mut self, flush_policy: FlushPolicy) -> Self {
+        self.flush_policy = flush_policy;
+        self
+    }
+
+    pub fn flush_policy(&self) -> FlushPolicy {
+        self.flush_policy
+    }
+
+    pub fn set_flush_policy(&mut self, flush_policy: FlushPolicy) {
+        self.flush_policy = flush_policy;
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.compression_threshold
+    }
+
+    /// Enables compression for packets of at least threshold length or disables it with `None`.
+    pub fn set_compression_threshold(&mut self, compression_threshold: Option<usize>) {
+        self.compression_threshold = compression_threshold;
+    }
+
+    pub fn with_max_packet_length(mut self, max_packet_length: usize) -> Self {
+        self.max_packet_length = max_packet_length;
+        self
+    }
+
+    /// Returns max frame length without length prefix.
+    pub fn max_packet_length(&self) -> usize {
+        self.max_packet_length
+    }
+
+    pub fn set_max_packet_length(&mut self, max_packet_length: usize) {
+        self.max_packet_length = max_packet_length;
+    }
+
+    /// Decodes packets with limits instead of limits of the thread, see `limits` module.
+    pub fn with_decode_limits(mut self, decode_limits: DecodeLimits) -> Self {
+        self.decode_limits = Some(decode_limits);
+        self
+    }
+
+    /// Returns limits set for codec, `None` if packets are decoded with limits of the thread.
+    pub fn decode_limits(&self) -> Option<DecodeLimits> {
+        self.decode_limits
+    }
+
+    pub fn set_decode_limits(&mut self, decode_limits: Option<DecodeLimits>) {
+        self.decode_limits = decode_limits;
+    }
+
+    pub fn with_encode_strategy(mut self, encode_strategy: EncodeStrategy) -> Self {
+        self.encode_strategy = encode_strategy;
+        self
+    }
+
+    pub fn encode_strategy(&self) -> EncodeStrategy {
+        self.encode_strategy
+    }
+
+    /// Changes strategy, heap buffer kept by `Buffered` strategy is released.
+    pub fn set_encode_strategy(&mut self, encode_strategy: EncodeStrategy) {
+        self.encode_strategy = encode_strategy;
+        self.buffer = Vec::new();
+    }
+
+    pub fn with_compression_override(
+        mut self,
+        id: i32,
+        compression_override: CompressionOverride,
+    ) -> Self {
+        self.set_compression_override(id, Some(compression_override));
+        self
+    }
+
+    pub fn compression_override(&self, id: i32) -> Option<CompressionOverride> {
+        self.compression_overrides.get(&id).copied()
+    }
+
+    /// Overrides compression of packets with id, `None` makes them follow threshold again.
+    ///
+    /// Overrides apply only while compression is enabled and not to frames of `write_cached`.
+    pub fn set_compression_override(
+        &mut self,
+        id: i32,
+        compression_override: Option<CompressionOverride>,
+    ) {
+        match compression_override {
+            Some(compression_override) => {
+                self.compression_overrides.insert(id, compression_override);
+            }
+            None => {
+                self.compression_overrides.remove(&id);
+            }
+        }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    /// Moves settings to codec over another stream, returning the old one.
+    pub(crate) fn replace_stream<T>(self, stream: T) -> (S, PacketCodec<T>) {
+        let codec = PacketCodec {
+            stream,
+            flush_policy: self.flush_policy,
+            compression_threshold: self.compression_threshold,
+            max_packet_length: self.max_packet_length,
+            encode_strategy: self.encode_strategy,
+            compression_overrides: self.compression_overrides,
+            buffer: self.buffer,
+            decode_limits: self.decode_limits,
+        };
+
+        (self.stream, codec)
+    }
+
+    pub fn export_state(&self) -> CodecState {
+        CodecState {
+            version: CODEC_STATE_VERSION,
+            flush_policy: self.flush_policy,
+            compression_threshold: self.compression_threshold,
+            max_packet_length: self.max_packet_length,
+        }
+    }
+
+    /// Creates codec which continues connection from exported state.
+    ///
+    /// Returns `StateError::UnsupportedVersion` if state was exported by incompatible version
+    /// of this crate.
+    pub fn restore_state(stream: S, state: CodecState) -> Result<Self, StateError> {
+        if state.version != CODEC_STATE_VERSION {
+            return Err(StateError::UnsupportedVersion {
+                version: state.version,
+            });
+        }
+
+        Ok(PacketCodec {
+            stream,
+            flush_policy: state.flush_policy,
+            compression_threshold: state.compression_threshold,
+            max_packet_length: state.max_packet_length,
+            encode_strategy: EncodeStrategy::default(),
+            compression_overrides: HashMap::new(),
+            buffer: Vec::new(),
+            decode_limits: None,
+        })
+    }
+}
+
+impl<S: Read> PacketCodec<S> {
+    /// Returns `DecodeError::PacketTooLong` if frame is longer than max packet length.
+    pub fn read_raw_packet(&mut self) -> Result<RawPacket, DecodeError> {
+        let max_length = self.max_packet_length;
+
+        match self.compression_threshold {
+            Some(threshold) => {
+                CompressedRawPacket::decode_with_max_length(&mut self.stream, threshold, max_length)
+                    .map(|p| p.into_inner())
+            }
+            None => RawPacket::decode_with_max_length(&mut self.stream, max_length),
+        }
+    }
+
+    pub fn read_packet<P: Packet>(&mut self) -> Result<P, DecodeError> {
+        let raw_packet = self.read_raw_packet()?;
+
+        limits::with_optional_limits(self.decode_limits, || raw_packet.decode_packet())
+    }
+}
+
+impl<S: Write> PacketCodec<S> {
+    /// Returns `EncodeError::PacketTooLong` if frame is longer than max packet length.
+    pub fn write_raw_packet(&mut self, raw_packet: &RawPacket) -> Result<(), EncodeError> {
+        match self.compression_threshold {
+            Some(_) => {
+                let mut buf = Vec::new();
+                self.encode_frame(raw_packet, &mut buf)?;
+                self.stream.write_all(&buf)?;
+            }
+            None => {
+                self.check_length(var_i32_size(raw_packet.id) + raw_packet.data.len())?;
+
+                let header = raw_packet.header()?;
+                let mut bufs = [IoSlice::new(&header), IoSlice::new(&raw_packet.data)];
+
+                write_all_vectored(&mut self.stream, &mut bufs)?;
+            }
+        }
+
+        if self.flush_policy == FlushPolicy::PerPacket {
+            self.stream.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Encodes packet following encode strategy.
+    pub fn write_packet<P: Packet>(&mut self, packet: &P) -> Result<(), EncodeError> {
+        match (self.encode_strategy, self.compression_threshold) {
+            (EncodeStrategy::Buffered, None) => self.write_buffered(packet)?,
+            (EncodeStrategy::TwoPass, None) => self.write_two_pass(packet)?,
+            _ => return self.write_raw_packet(&RawPacket::from_packet(packet)?),
+        }
+
+        if self.flush_policy == FlushPolicy::PerPacket {
+            self.stream.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes packets with one write call.
+    pub fn write_bundle<P: Packet>(&mut self, packets: &[P]) -> Result<(), EncodeError> {
+        let mut buf = Vec::new();
+
+        for packet in packets {
+            self.encode_frame(&RawPacket::from_packet(packet)?, &mut buf)?;
+        }
+
+        self.stream.write_all(&buf)?;
+
+        if self.flush_policy != FlushPolicy::Manual {
+            self.stream.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes frame from cache, encoding packet only if it's not cached yet.
+    pub fn write_cached<P: Packet>(
+        &mut self,
+        packet: &P,
+        cache: &mut FrameCache,
+    ) -> Result<(), EncodeError> {
+        let frame = cache.get_or_encode(packet, self.compression_threshold)?;
+        self.check_length(frame_body_length(frame))?;
+        self.stream.write_all(frame)?;
+
+        if self.flush_policy == FlushPolicy::PerPacket {
+            self.stream.flush()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), EncodeError> {
+        Ok(self.stream.flush()?)
+    }
+
+    fn write_buffered<P: Packet>(&mut self, packet: &P) -> Result<(), EncodeError> {
+        let mut heap = std::mem::take(&mut self.buffer);
+        let result = self.write_spilled(packet, &mut heap);
+        self.buffer = heap;
+
+        result
+    }
+
+    fn write_spilled<P: Packet>(
+        &mut self,
+        packet: &P,
+        heap: &mut Vec<u8>,
+    ) -> Result<(), EncodeError> {
+        let mut buffer = SpillBuffer::new(heap);
+        packet.encode(&mut buffer)?;
+
+        let data = buffer.as_slice();
+        let header = self.frame_header(packet.get_type_id() as i32, data.len())?;
+        let mut bufs = [IoSlice::new(header.as_slice()), IoSlice::new(data)];
+
+        Ok(write_all_vectored(&mut self.stream, &mut bufs)?)
+    }
+
+    fn write_two_pass<P: Packet>(&mut self, packet: &P) -> Result<(), EncodeError> {
+        let mut counter = LengthCounter(0);
+        packet.encode(&mut counter)?;
+
+        let header = self.frame_header(packet.get_type_id() as i32, counter.0)?;
+        self.stream.write_all(header.as_slice())?;
+
+        packet.encode(&mut self.stream)
+    }
+
+    /// Returns frame length and packet id after checking frame length.
+    fn frame_header(&self, id: i32, data_length: usize) -> Result<FrameHeader, EncodeError> {
+        let length = var_i32_size(id) + data_length;
+        self.check_length(length)?;
+
+        let mut header = FrameHeader([0; 10], 0);
+        let mut writer = &mut header.0[..];
+        writer.write_var_i32(length as i32)?;
+        writer.write_var_i32(id)?;
+        header.1 = 10 - writer.len();
+
+        Ok(header)
+    }
+
+    fn encode_frame(&self, raw_packet: &RawPacket, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
+        let start = buf.len();
+
+        match self.compression_threshold {
+            Some(threshold) => match self.compression_override(raw_packet.id) {
+                Some(compression_override) => CompressedRawPacket::from(raw_packet.clone())
+                    .encode_with_override(buf, threshold, compression_override)?,
+                None => CompressedRawPacket::from(raw_packet.clone()).encode(buf, threshold)?,
+            },
+            None => raw_packet.encode(buf)?,
+        }
+
+        self.check_length(frame_body_length(&buf[start..]))
+    }
+
+    fn check_length(&self, length: usize) -> Result<(), EncodeError> {
+        if length > self.max_packet_length {
+            return Err(EncodeError::PacketTooLong {
+                length,
+                max_length: self.max_packet_length,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Frame length and packet id var ints, which take at most 10 bytes.
+struct FrameHeader([u8; 10], usize);
+
+impl FrameHeader {
+    fn as_slice(&self) -> &[u8] {
+        &self.0[..self.1]
+    }
+}
+
+/// Buffer on stack which moves into heap buffer once it's full.
+struct SpillBuffer<'a> {
+    stack: [u8; STACK_BUFFER_LENGTH],
+    length: usize,
+    heap: &'a mut Vec<u8>,
+    spilled: bool,
+}
+
+impl<'a> SpillBuffer<'a> {
+    fn new(heap: &'a mut Vec<u8>) -> Self {
+        heap.clear();
+
+        SpillBuffer {
+            stack: [0; STACK_BUFFER_LENGTH],
+            length: 0,
+            heap,
+            spilled: false,
+        }
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        if self.spilled {
+            self.heap
+        } else {
+            &self.stack[..self.length]
+        }
+    }
+}
+
+impl Write for SpillBuffer<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.spilled {
+            if self.length + buf.len() <= STACK_BUFFER_LENGTH {
+                self.stack[self.length..self.length + buf.len()].copy_from_slice(buf);
+                self.length += buf.len();
+
+                return Ok(buf.len());
+            }
+
+            self.heap.extend_from_slice(&self.stack[..self.length]);
+            self.spilled = true;
+        }
+
+        self.heap.extend_from_slice(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns whether packet id and data encode to bytes, without allocating.
+fn packet_equals<P: Packet>(packet: &P, bytes: &[u8]) -> Result<bool, EncodeError> {
+    let mut comparer = BytesComparer {
+        remaining: bytes,
+        equal: true,
+    };
+    comparer.write_var_i32(packet.get_type_id() as i32)?;
+    packet.encode(&mut comparer)?;
+
+    Ok(comparer.equal && comparer.remaining.is_empty())
+}
+
+/// Writer comparing written bytes with expected ones.
+struct BytesComparer<'a> {
+    remaining: &'a [u8],
+    equal: bool,
+}
+
+impl Write for BytesComparer<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.remaining.strip_prefix(buf) {
+            Some(remaining) if self.equal => self.remaining = remaining,
+            _ => self.equal = false,
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns frame length without length prefix.
+fn frame_body_length(mut frame: &[u8]) -> usize {
+    // Frame was just encoded, so it starts with valid var int.
+    frame.read_var_i32().unwrap() as u32 as usize
+}
+
+/// Encoded frames keyed by stable hash of packet and compression threshold.
+///
+/// Packet is still encoded to be hashed and compared with bytes of cached packets, cache saves
+/// compression and allocation. Cache is not bounded, so it should be cleared when cached packets
+/// are not sent anymore.
+#[derive(Debug, Default)]
+pub struct FrameCache {
+    frames: HashMap<(u64, Option<usize>), Vec<CachedFrame>>,
+}
+
+/// Frame with id and data of packet it was encoded from.
+#[derive(Debug)]
+struct CachedFrame {
+    packet: Vec<u8>,
+    frame: Vec<u8>,
+}
+
+impl FrameCache {
+    pub fn new() -> Self {
+        FrameCache::default()
+    }
+
+    /// Returns frame of packet, encoding it on the first call.
+    pub fn get_or_encode<P: Packet>(
+        &mut self,
+        packet: &P,
+        compression_threshold: Option<usize>,
+    ) -> Result<&[u8], EncodeError> {
+        let hash = packet.stable_hash()?;
+
+        self.get_or_encode_hashed(hash, packet, compression_threshold)
+    }
+
+    fn get_or_encode_hashed<P: Packet>(
+        &mut self,
+        hash: u64,
+        packet: &P,
+        compression_threshold: Option<usize>,
+    ) -> Result<&[u8], EncodeError> {
+        let cached_frames = self
+            .frames
+            .entry((hash, compression_threshold))
+            .or_default();
+
+        let mut index = None;
+
+        for (i, cached_frame) in cached_frames.iter().enumerate() {
+            if packet_equals(packet, &cached_frame.packet)? {
+                index = Some(i);
+                break;
+            }
+        }
+
+        let index = match index {
+            Some(index) => index,
+            None => {
+                let raw_packet = RawPacket::from_packet(packet)?;
+                let mut bytes =
+                    Vec::with_capacity(var_i32_size(raw_packet.id) + raw_packet.data.len());
+                bytes.write_var_i32(raw_packet.id)?;
+                bytes.extend_from_slice(&raw_packet.data);
+
+                let mut frame = Vec::new();
+
+                match compression_threshold {
+                    Some(threshold) => {
+                        CompressedRawPacket::from(raw_packet).encode(&mut frame, threshold)?
+                    }
+                    None => raw_packet.encode(&mut frame)?,
+                }
+
+                cached_frames.push(CachedFrame {
+                    packet: bytes,
+                    frame,
+                });
+
+                cached_frames.len() - 1
+            }
+        };
+
+        Ok(&cached_frames[index].frame)
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::codec::*;
+    use crate::version::v1_14_4::login::{LoginServerBoundPacket, LoginStart};
+    use std::io::{self, Cursor};
+
+    #[derive(Default)]
+    struct CountingWriter {
+        buf: Vec<u8>,
+        writes: usize,
+        flushes: usize,
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writes += 1;
+            self.buf.extend_from_slice(buf);
+
+            Ok(buf.len())
+        }
+
+        fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+            self.writes += 1;
+
+            for buf in bufs {
+                self.buf.extend_from_slice(buf);
+            }
+
+            Ok(bufs.iter().map(|buf| buf.len()).sum())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushes += 1;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_packet_single_write() {
+        let mut codec = PacketCodec::new(CountingWriter::default());
+        codec
+            .write_packet(&LoginStart::new(String::from("Username")))
+            .unwrap();
+
+        let writer = codec.into_inner();
+
+        assert_eq!(writer.writes, 1);
+        assert_eq!(writer.flushes, 1);
+
+        let mut codec = PacketCodec::new(writer.buf.as_slice());
+
+        match codec.read_packet().unwrap() {
+            LoginServerBoundPacket::LoginStart(login_start) => {
+                assert_eq!(login_start.name, "Username")
+            }
+            _ => panic!("Expected login start packet"),
+        }
+    }
+
+    #[test]
+    fn test_decode_limits_follow_codec() {
+        use crate::data::chat::Message;
+        use crate::version::v1_14_4::login::{LoginClientBoundPacket, LoginDisconnect};
+        use std::thread;
+
+        let mut codec = PacketCodec::new(Vec::new());
+        codec
+            .write_packet(&LoginDisconnect::new(Message::from_str("Server is full")))
+            .unwrap();
+        let stream = codec.into_inner();
+
+        let limits = DecodeLimits {
+            json_max_length: 8,
+            ..DecodeLimits::default()
+        };
+        let mut codec = PacketCodec::new(Cursor::new(stream.clone())).with_decode_limits(limits);
+
+        // Limits are kept by codec, not by thread which set them up.
+        let decode_error = thread::spawn(move || codec.read_packet::<LoginClientBoundPacket>())
+            .join()
+            .unwrap()
+            .expect_err("Expected error `JsonTooLong` because reason is longer than 8 bytes");
+
+        assert!(matches!(
+            decode_error,
+            DecodeError::JsonTooLong { max_length: 8, .. }
+        ));
+
+        let mut codec = PacketCodec::new(Cursor::new(stream));
+        assert!(codec.read_packet::<LoginClientBoundPacket>().is_ok());
+    }
+
+    fn encode_with(encode_strategy: EncodeStrategy, name: &str) -> CountingWriter {
+        let mut codec =
+            PacketCodec::new(CountingWriter::default()).with_encode_strategy(encode_strategy);
+        codec
+            .write_packet(&LoginStart::new(String::from(name)))
+            .unwrap();
+
+        codec.into_inner()
+    }
+
+    #[test]
+    fn test_encode_strategies_write_same_frames() {
+        let long_name = "a".repeat(STACK_BUFFER_LENGTH * 2);
+
+        for name in &["Username", long_name.as_str()] {
+            let expected = encode_with(EncodeStrategy::Allocate, name);
+
+            for encode_strategy in [EncodeStrategy::Buffered, EncodeStrategy::TwoPass] {
+                let writer = encode_with(encode_strategy, name);
+
+                assert_eq!(writer.buf, expected.buf);
+                assert_eq!(writer.flushes, 1);
+            }
+        }
+
+        assert_eq!(encode_with(EncodeStrategy::Buffered, &long_name).writes, 1);
+    }
+
+    #[test]
+    fn test_buffered_keeps_heap_buffer() {
+        let mut codec = PacketCodec::new(Vec::new()).with_encode_strategy(EncodeStrategy::Buffered);
+        let long_name = "a".repeat(STACK_BUFFER_LENGTH * 2);
+
+        codec
+            .write_packet(&LoginStart::new(long_name.clone()))
+            .unwrap();
+        let capacity = codec.buffer.capacity();
+
+        codec.write_packet(&LoginStart::new(long_name)).unwrap();
+
+        assert!(capacity > STACK_BUFFER_LENGTH);
+        assert_eq!(codec.buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_two_pass_max_packet_length() {
+        let mut codec = PacketCodec::new(Vec::new())
+            .with_encode_strategy(EncodeStrategy::TwoPass)
+            .with_max_packet_length(8);
+
+        let encode_error = codec
+            .write_packet(&LoginStart::new(String::from("Username")))
+            .expect_err("Expected error `PacketTooLong` because frame has 10 bytes");
+
+        match encode_error {
+            EncodeError::PacketTooLong { length, max_length } => {
+                assert_eq!(length, 10);
+                assert_eq!(max_length, 8);
+            }
+            _ => panic!("Expected `PacketTooLong` but got `{:?}`", encode_error),
+        }
+
+        assert!(codec.get_ref().is_empty());
+    }
+
+    #[test]
+    fn test_compression_threshold() {
+        let mut codec = PacketCodec::new(Vec::new());
+        codec.set_compression_threshold(Some(8));
+        codec
+            .write_packet(&LoginStart::new(String::from("Username")))
+            .unwrap();
+
+        assert_ne!(codec.get_ref()[1], 0);
+
+        let stream = codec.into_inner();
+        let mut codec = PacketCodec::new(stream.as_slice());
+        codec.set_compression_threshold(Some(8));
+
+        match codec.read_packet().unwrap() {
+            LoginServerBoundPacket::LoginStart(login_start) => {
+                assert_eq!(login_start.name, "Username")
+            }
+            _ => panic!("Expected login start packet"),
+        }
+    }
+
+    #[test]
+    fn test_compression_override() {
+        let mut codec = PacketCodec::new(Vec::new())
+            .with_compression_override(0x00, CompressionOverride::Skip)
+            .with_compression_override(0x21, CompressionOverride::Level(9));
+        codec.set_compression_threshold(Some(8));
+
+        codec
+            .write_packet(&LoginStart::new(String::from("Username")))
+            .unwrap();
+        let skipped_length = codec.get_ref().len();
+        codec
+            .write_raw_packet(&RawPacket::new(0x21, vec![5; 1024]))
+            .unwrap();
+
+        assert_eq!(codec.get_ref()[1], 0);
+        assert!(codec.get_ref().len() - skipped_length < 64);
+
+        codec.set_compression_override(0x00, None);
+        assert_eq!(codec.compression_override(0x00), None);
+
+        let stream = codec.into_inner();
+        let mut codec = PacketCodec::new(stream.as_slice());
+        codec.set_compression_threshold(Some(8));
+
+        assert_eq!(codec.read_raw_packet().unwrap().id, 0x00);
+        assert_eq!(
+            codec.read_raw_packet().unwrap(),
+            RawPacket::new(0x21, vec![5; 1024])
+        );
+    }
+
+    #[test]
+    fn test_restore_state() {
+        let mut codec = PacketCodec::new(Vec::new()).with_flush_policy(FlushPolicy::PerBundle);
+        codec.set_compression_threshold(Some(8));
+        codec
+            .write_packet(&LoginStart::new(String::from("Before")))
+            .unwrap();
+
+        let json = serde_json::to_string(&codec.export_state()).unwrap();
+        let stream = codec.into_inner();
+
+        let state = serde_json::from_str(&json).unwrap();
+        let mut codec = PacketCodec::restore_state(stream, state).unwrap();
+        codec
+            .write_packet(&LoginStart::new(String::from("After")))
+            .unwrap();
+
+        assert_eq!(codec.flush_policy(), FlushPolicy::PerBundle);
+
+        let stream = codec.into_inner();
+        let mut codec = PacketCodec::new(stream.as_slice());
+        codec.set_compression_threshold(Some(8));
+
+        for name in &["Before", "After"] {
+            match codec.read_packet().unwrap() {
+                LoginServerBoundPacket::LoginStart(login_start) => {
+                    assert_eq!(&login_start.name, name)
+                }
+                _ => panic!("Expected login start packet"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_restore_state_without_max_packet_length() {
+        let json = r#"{"version":1,"flush_policy":"Manual","compression_threshold":null}"#;
+        let state = serde_json::from_str(json).unwrap();
+        let codec = PacketCodec::restore_state(Vec::<u8>::new(), state).unwrap();
+
+        assert_eq!(codec.max_packet_length(), PACKET_MAX_LENGTH);
+    }
+
+    #[test]
+    fn test_max_packet_length() {
+        let raw_packet = RawPacket::new(0x00, vec![1; 16]);
+
+        for compression_threshold in [None, Some(1024)] {
+            let mut codec = PacketCodec::new(Vec::new()).with_max_packet_length(16);
+            codec.set_compression_threshold(compression_threshold);
+
+            let encode_error = codec
+                .write_raw_packet(&raw_packet)
+                .expect_err("Expected error `PacketTooLong` because packet is too long");
+
+            let length = if compression_threshold.is_some() {
+                18
+            } else {
+                17
+            };
+            assert_eq!(
+                encode_error,
+                EncodeError::PacketTooLong {
+                    length,
+                    max_length: 16
+                }
+            );
+
+            codec.set_max_packet_length(PACKET_MAX_LENGTH);
+            codec.write_raw_packet(&raw_packet).unwrap();
+
+            let stream = codec.into_inner();
+            let mut codec = PacketCodec::new(stream.as_slice()).with_max_packet_length(16);
+            codec.set_compression_threshold(compression_threshold);
+
+            let decode_error = codec
+                .read_raw_packet()
+                .expect_err("Expected error `PacketTooLong` because packet is too long");
+
+            match decode_error {
+                DecodeError::PacketTooLong {
+                    length: 17..=18,
+                    max_length: 16,
+                } => {}
+                _ => panic!("Expected `PacketTooLong` but got `{:?}`", decode_error),
+            }
+        }
+    }
+
+    #[test]
+    fn test_restore_state_unsupported_version() {
+        let mut state = PacketCodec::new(Vec::<u8>::new()).export_state();
+        state.version = CODEC_STATE_VERSION + 1;
+
+        let state_error = PacketCodec::restore_state(Vec::<u8>::new(), state)
+            .expect_err("Expected error `UnsupportedVersion` because version is from the future");
+
+        match state_error {
+            StateError::UnsupportedVersion { version } => {
+                assert_eq!(version, CODEC_STATE_VERSION + 1)
+            }
+        }
+    }
+
+    #[test]
+    fn test_flush_policy() {
+        let packets = vec![
+            LoginStart::new(String::from("First")),
+            LoginStart::new(String::from("Second")),
+        ];
+
+        let mut codec =
+            PacketCodec::new(CountingWriter::default()).with_flush_policy(FlushPolicy::PerBundle);
+        codec.write_packet(&packets[0]).unwrap();
+        codec.write_bundle(&packets).unwrap();
+
+        assert_eq!(codec.get_ref().writes, 2);
+        assert_eq!(codec.get_ref().flushes, 1);
+
+        codec.set_flush_policy(FlushPolicy::Manual);
+        codec.write_packet(&packets[0]).unwrap();
+        codec.write_bundle(&packets).unwrap();
+
+        assert_eq!(codec.get_ref().flushes, 1);
+
+        codec.flush().unwrap();
+
+        assert_eq!(codec.get_ref().flushes, 2);
+    }
+
+    #[test]
+    fn test_write_cached() {
+        let packets = [
+            LoginStart::new(String::from("Username")),
+            LoginStart::new(String::from("Username")),
+            LoginStart::new(String::from("Other")),
+        ];
+
+        for compression_threshold in [None, Some(4)] {
+            let mut expected = PacketCodec::new(Vec::new());
+            expected.set_compression_threshold(compression_threshold);
+
+            let mut codec = PacketCodec::new(Vec::new());
+            codec.set_compression_threshold(compression_threshold);
+
+            let mut cache = FrameCache::new();
+
+            for packet in &packets {
+                expected.write_packet(packet).unwrap();
+                codec.write_cached(packet, &mut cache).unwrap();
+            }
+
+            assert_eq!(codec.into_inner(), expected.into_inner());
+            assert_eq!(cache.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_frame_cache_hash_collision() {
+        let username = LoginStart::new(String::from("Username"));
+        let other = LoginStart::new(String::from("Other"));
+
+        let mut cache = FrameCache::new();
+
+        // Both packets are cached with the same hash, as if hash collided.
+        for _ in 0..2 {
+            for packet in [&username, &other] {
+                let frame = cache
+                    .get_or_encode_hashed(0, packet, None)
+                    .unwrap()
+                    .to_vec();
+
+                let mut expected = PacketCodec::new(Vec::new());
+                expected.write_packet(packet).unwrap();
+
+                assert_eq!(frame, expected.into_inner());
+            }
+        }
+
+        assert_eq!(cache.len(), 2);
+    }
+}
@@ -0,0 +1,318 @@
+//! Packet compression enabled by `SetCompression` packet.
+//!
+//! After compression is enabled every frame has uncompressed data length after the frame length.
+//! Packets smaller than threshold are sent as is with data length 0, bigger ones are compressed
+//! with zlib.
+//!
+//! `CompressionOverride` changes that for single packets: already compressed payloads can skip
+//! compression and big packets like chunk data can be compressed harder.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::compression::CompressedRawPacket;
+//! use minecraft_protocol::packet::RawPacket;
+//!
+//! let raw_packet = RawPacket::new(0x0E, vec![0; 512]);
+//!
+//! let mut vec = Vec::new();
+//! CompressedRawPacket::from(raw_packet.clone()).encode(&mut vec, 256).unwrap();
+//!
+//! assert!(vec.len() < 512);
+//!
+//! let decoded = CompressedRawPacket::decode(&mut vec.as_slice(), 256).unwrap();
+//! assert_eq!(decoded.into_inner(), raw_packet);
+//! ```
+use crate::decoder::DecoderReadExt;
+use crate::encoder::{var_i32_size, write_length, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::{read_frame, RawPacket, PACKET_MAX_LENGTH};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// Protocol limits uncompressed packet length to 8 MiB.
+pub const DATA_MAX_LENGTH: usize = 8_388_608;
+
+/// Max zlib compression level.
+pub const MAX_COMPRESSION_LEVEL: u32 = 9;
+
+/// Compression of packet which differs from threshold alone.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CompressionOverride {
+    /// Packet is sent uncompressed at any length, which peers always accept.
+    Skip,
+    /// Packet is compressed at any length.
+    ///
+    /// Vanilla and `CompressedRawPacket::decode` reject compressed packets below their threshold,
+    /// so it's only for peers which don't.
+    Force,
+    /// Packet of at least threshold length is compressed with zlib level up to
+    /// `MAX_COMPRESSION_LEVEL`, 1 is the fastest and 9 compresses best.
+    Level(u32),
+}
+
+/// Packet frame in compressed format.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CompressedRawPacket(RawPacket);
+
+impl CompressedRawPacket {
+    pub fn into_inner(self) -> RawPacket {
+        self.0
+    }
+
+    /// Reads frame in compressed format.
+    ///
+    /// Returns `DecodeError::CompressionMismatch` if packet is compressed although it's smaller
+    /// than threshold or compressed data is not zlib stream, which means peer doesn't use the
+    /// same compression settings.
+    pub fn decode<R: Read>(reader: &mut R, threshold: usize) -> Result<Self, DecodeError> {
+        CompressedRawPacket::decode_with_max_length(reader, threshold, PACKET_MAX_LENGTH)
+    }
+
+    /// Reads frame in compressed format returning `DecodeError::PacketTooLong` if frame is
+    /// longer than max length.
+    pub fn decode_with_max_length<R: Read>(
+        reader: &mut R,
+        threshold: usize,
+        max_length: usize,
+    ) -> Result<Self, DecodeError> {
+        CompressedRawPacket::from_body(read_frame(reader, max_length)?, threshold)
+    }
+
+    /// Decodes frame body, which starts with uncompressed data length.
+    pub(crate) fn from_body(body: Vec<u8>, threshold: usize) -> Result<Self, DecodeError> {
+        let mut cursor = body.as_slice();
+        let data_length = cursor.read_var_i32()? as u32 as usize;
+
+        if data_length == 0 {
+            return Ok(CompressedRawPacket(RawPacket::from_body(cursor.to_vec())?));
+        }
+
+        let mismatch = DecodeError::CompressionMismatch {
+            data_length,
+            threshold,
+        };
+
+        if data_length < threshold || !is_zlib_header(cursor) {
+            return Err(mismatch);
+        }
+
+        if data_length > DATA_MAX_LENGTH {
+            return Err(DecodeError::PacketTooLong {
+                length: data_length,
+                max_length: DATA_MAX_LENGTH,
+            });
+        }
+
+        let mut body = Vec::with_capacity(data_length);
+        ZlibDecoder::new(cursor)
+            .take(data_length as u64 + 1)
+            .read_to_end(&mut body)?;
+
+        if body.len() != data_length {
+            return Err(mismatch);
+        }
+
+        Ok(CompressedRawPacket(RawPacket::from_body(body)?))
+    }
+
+    /// Writes frame in compressed format. Packet is compressed if its length is at least
+    /// threshold.
+    pub fn encode<W: Write>(&self, writer: &mut W, threshold: usize) -> Result<(), EncodeError> {
+        let body = self.encode_body(threshold)?;
+
+        write_length(writer, body.len())?;
+        writer.write_all(&body)?;
+
+        Ok(())
+    }
+
+    /// Writes frame in compressed format, compressing it as override tells.
+    pub fn encode_with_override<W: Write>(
+        &self,
+        writer: &mut W,
+        threshold: usize,
+        compression_override: CompressionOverride,
+    ) -> Result<(), EncodeError> {
+        let body = self.encode_body_with_override(threshold, Some(compression_override))?;
+
+        write_length(writer, body.len())?;
+        writer.write_all(&body)?;
+
+        Ok(())
+    }
+
+    /// Returns frame body, which starts with uncompressed data length.
+    pub(crate) fn encode_body(&self, threshold: usize) -> Result<Vec<u8>, EncodeError> {
+        self.encode_body_with_override(threshold, None)
+    }
+
+    pub(crate) fn encode_body_with_override(
+        &self,
+        threshold: usize,
+        compression_override: Option<CompressionOverride>,
+    ) -> Result<Vec<u8>, EncodeError> {
+        let raw_packet = &self.0;
+        let data_length = var_i32_size(raw_packet.id) + raw_packet.data.len();
+
+        let (compressed, compression) = match compression_override {
+            None => (data_length >= threshold, Compression::default()),
+            Some(CompressionOverride::Skip) => (false, Compression::default()),
+            Some(CompressionOverride::Force) => (true, Compression::default()),
+            Some(CompressionOverride::Level(level)) => (
+                data_length >= threshold,
+                Compression::new(level.min(MAX_COMPRESSION_LEVEL)),
+            ),
+        };
+
+        if !compressed {
+            let mut body = Vec::with_capacity(data_length + 1);
+            body.write_var_i32(0)?;
+            body.write_var_i32(raw_packet.id)?;
+            body.write_all(&raw_packet.data)?;
+
+            return Ok(body);
+        }
+
+        let mut body = Vec::new();
+        write_length(&mut body, data_length)?;
+
+        let mut encoder = ZlibEncoder::new(body, compression);
+        encoder.write_var_i32(raw_packet.id)?;
+        encoder.write_all(&raw_packet.data)?;
+
+        Ok(encoder.finish()?)
+    }
+}
+
+impl From<RawPacket> for CompressedRawPacket {
+    fn from(raw_packet: RawPacket) -> Self {
+        CompressedRawPacket(raw_packet)
+    }
+}
+
+/// Checks zlib header: deflate method and valid header checksum.
+fn is_zlib_header(data: &[u8]) -> bool {
+    match data {
+        [cmf, flg, ..] => cmf & 0x0F == 8 && (*cmf as u16 * 256 + *flg as u16).is_multiple_of(31),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compression::*;
+    use crate::error::DecodeError;
+
+    const THRESHOLD: usize = 64;
+
+    fn encode(raw_packet: RawPacket) -> Vec<u8> {
+        let mut vec = Vec::new();
+        CompressedRawPacket::from(raw_packet)
+            .encode(&mut vec, THRESHOLD)
+            .unwrap();
+
+        vec
+    }
+
+    #[test]
+    fn test_threshold_boundary() {
+        let below = RawPacket::new(0x0E, vec![1; THRESHOLD - 2]);
+        let equal = RawPacket::new(0x0E, vec![1; THRESHOLD - 1]);
+
+        let below_vec = encode(below.clone());
+        let equal_vec = encode(equal.clone());
+
+        assert_eq!(&below_vec[..3], [THRESHOLD as u8, 0, 0x0E]);
+        assert_eq!(equal_vec[1], THRESHOLD as u8);
+
+        let decoded = CompressedRawPacket::decode(&mut below_vec.as_slice(), THRESHOLD).unwrap();
+        assert_eq!(decoded.into_inner(), below);
+
+        let decoded = CompressedRawPacket::decode(&mut equal_vec.as_slice(), THRESHOLD).unwrap();
+        assert_eq!(decoded.into_inner(), equal);
+    }
+
+    #[test]
+    fn test_compressed_below_threshold() {
+        let vec = encode(RawPacket::new(0x0E, vec![1; THRESHOLD - 1]));
+
+        let decode_error = CompressedRawPacket::decode(&mut vec.as_slice(), THRESHOLD + 1)
+            .expect_err("Expected error `CompressionMismatch` because packet is below threshold");
+
+        match decode_error {
+            DecodeError::CompressionMismatch {
+                data_length,
+                threshold,
+            } => {
+                assert_eq!(data_length, THRESHOLD);
+                assert_eq!(threshold, THRESHOLD + 1);
+            }
+            _ => panic!(
+                "Expected `CompressionMismatch` but got `{:?}`",
+                decode_error
+            ),
+        }
+    }
+
+    #[test]
+    fn test_override_skip_and_level() {
+        let raw_packet = CompressedRawPacket::from(RawPacket::new(0x21, vec![3; 4096]));
+
+        let mut skipped = Vec::new();
+        raw_packet
+            .encode_with_override(&mut skipped, THRESHOLD, CompressionOverride::Skip)
+            .unwrap();
+
+        let mut fastest = Vec::new();
+        raw_packet
+            .encode_with_override(&mut fastest, THRESHOLD, CompressionOverride::Level(1))
+            .unwrap();
+
+        assert_eq!(&skipped[2..4], [0, 0x21]);
+        assert!(fastest.len() < 100);
+
+        for vec in [skipped, fastest] {
+            let decoded = CompressedRawPacket::decode(&mut vec.as_slice(), THRESHOLD).unwrap();
+            assert_eq!(decoded, raw_packet);
+        }
+    }
+
+    #[test]
+    fn test_override_force() {
+        let raw_packet = CompressedRawPacket::from(RawPacket::new(0x0E, vec![1; 8]));
+
+        let mut vec = Vec::new();
+        raw_packet
+            .encode_with_override(&mut vec, THRESHOLD, CompressionOverride::Force)
+            .unwrap();
+
+        assert_eq!(vec[1], 9);
+        assert!(is_zlib_header(&vec[2..]));
+
+        let decoded = CompressedRawPacket::decode(&mut vec.as_slice(), 0).unwrap();
+        assert_eq!(decoded, raw_packet);
+    }
+
+    #[test]
+    fn test_uncompressed_format_after_compression() {
+        let mut vec = Vec::new();
+        RawPacket::new(0x0E, vec![1; THRESHOLD * 2])
+            .encode(&mut vec)
+            .unwrap();
+
+        let decode_error = CompressedRawPacket::decode(&mut vec.as_slice(), 0)
+            .expect_err("Expected error `CompressionMismatch` because data is not zlib stream");
+
+        assert!(matches!(
+            decode_error,
+            DecodeError::CompressionMismatch {
+                data_length: 0x0E,
+                ..
+            }
+        ));
+    }
+}
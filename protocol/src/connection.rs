@@ -0,0 +1,729 @@
+//! Blocking connection over a stream.
+//!
+//! `Connection` moves bytes between a stream and `ProtocolDriver`, so the connection state and
+//! compression follow packets which are sent and received. After `SetCompression` both sides
+//! compress packets without any extra calls.
+//!
+//! Timeout policy set with `set_timeout_policy` is checked before each read. Blocking reads
+//! should have a read timeout set on the stream, otherwise peer which sends nothing stalls
+//! connection until it's closed. Single reads can be limited with `read_packet_deadline` and
+//! `read_packet_timeout`, which keep partially received frame for the next read.
+//!
+//! `read_state_packet` decodes packet of the current connection state into `StatePacket` enum,
+//! e.g. `v1_14_4::ServerBoundPacket`, and `write_state_packet` refuses packet of other state.
+//! With `encryption` feature, `enable_encryption` encrypts all bytes after `EncryptionResponse`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::connection::Connection;
+//! use minecraft_protocol::driver::Side;
+//! use minecraft_protocol::version::v1_14_4::handshake::{Handshake, NextState};
+//! use minecraft_protocol::version::v1_14_4::login::{LoginClientBoundPacket, LoginStart};
+//! use std::net::TcpStream;
+//!
+//! let stream = TcpStream::connect("127.0.0.1:25565").unwrap();
+//! let mut connection = Connection::new(stream, Side::Client);
+//!
+//! connection
+//!     .write_packet(&Handshake::new(498, String::from("127.0.0.1"), 25565, NextState::Login))
+//!     .unwrap();
+//! connection
+//!     .write_packet(&LoginStart::new(String::from("Username")))
+//!     .unwrap();
+//!
+//! // Compression is enabled when `SetCompression` is read.
+//! let packet: LoginClientBoundPacket = connection.read_packet().unwrap();
+//! # }
+//! ```
+use crate::driver::{
+    DriverCommand, DriverEvent, ProtocolDriver, ProtocolState, Side, TimeoutPolicy,
+};
+#[cfg(feature = "encryption")]
+use crate::encryption::{StreamCipher, SHARED_SECRET_LENGTH};
+use crate::error::{DecodeError, EncodeError};
+use crate::handler::PacketHandlers;
+use crate::limits::{self, DecodeLimits};
+use crate::packet::{Packet, RawPacket};
+use std::io::{self, ErrorKind, Read, Write};
+use std::time::{Duration, Instant};
+
+/// Size of buffer for reading from stream.
+const READ_BUFFER_LENGTH: usize = 4096;
+
+/// Packet enum joining packets of all connection states in one direction.
+pub trait StatePacket: Sized {
+    /// Decodes packet as packet of given connection state.
+    fn decode_in_state(state: ProtocolState, raw_packet: &RawPacket) -> Result<Self, DecodeError>;
+
+    /// Returns connection state in which packet is sent.
+    fn state(&self) -> ProtocolState;
+
+    fn name(&self) -> &'static str;
+
+    fn to_raw_packet(&self) -> Result<RawPacket, EncodeError>;
+}
+
+/// Reads and writes packets on a stream following connection state.
+#[derive(Debug)]
+pub struct Connection<S> {
+    stream: S,
+    driver: ProtocolDriver,
+    #[cfg(feature = "encryption")]
+    cipher: Option<StreamCipher>,
+    decode_limits: Option<DecodeLimits>,
+}
+
+impl<S> Connection<S> {
+    pub fn new(stream: S, side: Side) -> Self {
+        Connection {
+            stream,
+            driver: ProtocolDriver::new(side),
+            #[cfg(feature = "encryption")]
+            cipher: None,
+            decode_limits: None,
+        }
+    }
+
+    /// Decodes packets with limits instead of limits of the thread, see `limits` module.
+    pub fn with_decode_limits(mut self, decode_limits: DecodeLimits) -> Self {
+        self.decode_limits = Some(decode_limits);
+        self
+    }
+
+    /// Returns limits set for connection, `None` if packets are decoded with limits of the thread.
+    pub fn decode_limits(&self) -> Option<DecodeLimits> {
+        self.decode_limits
+    }
+
+    pub fn set_decode_limits(&mut self, decode_limits: Option<DecodeLimits>) {
+        self.decode_limits = decode_limits;
+    }
+
+    pub fn state(&self) -> ProtocolState {
+        self.driver.state()
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.driver.compression_threshold()
+    }
+
+    /// Compresses packets of at least threshold length from now on.
+    ///
+    /// Compression is enabled automatically when `SetCompression` is sent or received.
+    pub fn enable_compression(&mut self, threshold: usize) {
+        self.driver.enable_compression(threshold);
+    }
+
+    /// Encrypts bytes in both directions from now on.
+    ///
+    /// Server enables encryption after reading `EncryptionResponse`, client after writing it.
+    /// Bytes received after the last read packet are decrypted too.
+    #[cfg(feature = "encryption")]
+    pub fn enable_encryption(&mut self, shared_secret: &[u8; SHARED_SECRET_LENGTH]) {
+        let mut cipher = StreamCipher::new(shared_secret);
+        cipher.decrypt(self.driver.input_mut());
+
+        self.cipher = Some(cipher);
+    }
+
+    #[cfg(feature = "encryption")]
+    pub fn is_encrypted(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    pub fn with_max_packet_length(mut self, max_packet_length: usize) -> Self {
+        self.driver.set_max_packet_length(max_packet_length);
+        self
+    }
+
+    /// Returns max frame length without length prefix.
+    pub fn max_packet_length(&self) -> usize {
+        self.driver.max_packet_length()
+    }
+
+    /// Limits length of read and written frames, see `ProtocolDriver::set_max_packet_length`.
+    pub fn set_max_packet_length(&mut self, max_packet_length: usize) {
+        self.driver.set_max_packet_length(max_packet_length);
+    }
+
+    /// Limits time peer may spend in handshake, status and login states.
+    pub fn set_timeout_policy(&mut self, timeout_policy: TimeoutPolicy) {
+        self.driver.set_timeout_policy(timeout_policy);
+    }
+
+    pub fn driver(&self) -> &ProtocolDriver {
+        &self.driver
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Read> Connection<S> {
+    /// Reads frame blocking until it's received completely.
+    ///
+    /// Read timeouts of the stream are retried while state is limited by timeout policy, so
+    /// `DecodeError::Timeout` is returned once peer stalls for too long.
+    pub fn read_raw_packet(&mut self) -> Result<RawPacket, DecodeError> {
+        self.read_raw_packet_until(None)
+    }
+
+    /// Reads frame, giving up with `ErrorKind::TimedOut` error once deadline passes.
+    ///
+    /// Stream must have a read timeout or be non-blocking, deadline is checked only between
+    /// reads. Bytes of the frame received before deadline stay buffered, so the next read
+    /// continues the same frame and nothing is lost when reading is abandoned.
+    pub fn read_raw_packet_deadline(
+        &mut self,
+        deadline: Instant,
+    ) -> Result<RawPacket, DecodeError> {
+        self.read_raw_packet_until(Some(deadline))
+    }
+
+    /// Reads frame waiting at most `timeout`. See `read_raw_packet_deadline`.
+    pub fn read_raw_packet_timeout(&mut self, timeout: Duration) -> Result<RawPacket, DecodeError> {
+        self.read_raw_packet_deadline(Instant::now() + timeout)
+    }
+
+    pub fn read_packet_deadline<P: Packet>(&mut self, deadline: Instant) -> Result<P, DecodeError> {
+        let raw_packet = self.read_raw_packet_deadline(deadline)?;
+
+        self.decode(|| raw_packet.decode_packet())
+    }
+
+    pub fn read_packet_timeout<P: Packet>(&mut self, timeout: Duration) -> Result<P, DecodeError> {
+        let raw_packet = self.read_raw_packet_timeout(timeout)?;
+
+        self.decode(|| raw_packet.decode_packet())
+    }
+
+    fn read_raw_packet_until(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<RawPacket, DecodeError> {
+        let mut buf = [0; READ_BUFFER_LENGTH];
+
+        loop {
+            match self.driver.poll_event()? {
+                DriverEvent::Packet(raw_packet) => return Ok(raw_packet),
+                DriverEvent::StateChanged(_) => {}
+                DriverEvent::NeedMoreData => {
+                    let now = Instant::now();
+                    self.driver.check_timeout(now)?;
+
+                    if deadline.is_some_and(|deadline| now >= deadline) {
+                        return Err(io::Error::from(ErrorKind::TimedOut).into());
+                    }
+
+                    match self.stream.read(&mut buf) {
+                        Ok(0) => return Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+                        Ok(length) => {
+                            #[cfg(feature = "encryption")]
+                            if let Some(cipher) = &mut self.cipher {
+                                cipher.decrypt(&mut buf[..length]);
+                            }
+
+                            self.driver.receive(&buf[..length])
+                        }
+                        Err(io_error) if io_error.kind() == ErrorKind::Interrupted => {}
+                        Err(io_error)
+                            if is_timeout(&io_error)
+                                && (deadline.is_some() || self.driver.deadline().is_some()) => {}
+                        Err(io_error) => return Err(io_error.into()),
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn read_packet<P: Packet>(&mut self) -> Result<P, DecodeError> {
+        let raw_packet = self.read_raw_packet()?;
+
+        self.decode(|| raw_packet.decode_packet())
+    }
+
+    /// Reads packet of the current connection state.
+    pub fn read_state_packet<P: StatePacket>(&mut self) -> Result<P, DecodeError> {
+        // State may change right after the packet, e.g. after handshake.
+        let state = self.state();
+        let raw_packet = self.read_raw_packet()?;
+
+        self.decode(|| P::decode_in_state(state, &raw_packet))
+    }
+
+    fn decode<T, F: FnOnce() -> T>(&self, f: F) -> T {
+        limits::with_optional_limits(self.decode_limits, f)
+    }
+
+    /// Reads packet and calls its handlers, packet is returned for further processing.
+    pub fn read_and_dispatch<P: Packet, C>(
+        &mut self,
+        handlers: &mut PacketHandlers<P, C>,
+        context: &mut C,
+    ) -> Result<P, DecodeError> {
+        let packet = self.read_packet()?;
+        handlers.dispatch(&packet, context);
+
+        Ok(packet)
+    }
+}
+
+impl<S: Write> Connection<S> {
+    /// Writes frame and flushes stream.
+    pub fn write_raw_packet(&mut self, raw_packet: RawPacket) -> Result<(), EncodeError> {
+        self.driver.handle(DriverCommand::SendPacket(raw_packet))?;
+
+        #[allow(unused_mut)]
+        let mut output = self.driver.take_output();
+
+        #[cfg(feature = "encryption")]
+        if let Some(cipher) = &mut self.cipher {
+            cipher.encrypt(&mut output);
+        }
+
+        self.stream.write_all(&output)?;
+        self.stream.flush()?;
+
+        Ok(())
+    }
+
+    pub fn write_packet<P: Packet>(&mut self, packet: &P) -> Result<(), EncodeError> {
+        self.write_raw_packet(RawPacket::from_packet(packet)?)
+    }
+
+    /// Writes packet, returning `EncodeError::WrongState` if it belongs to other state.
+    pub fn write_state_packet<P: StatePacket>(&mut self, packet: &P) -> Result<(), EncodeError> {
+        let state = self.state();
+
+        if packet.state() != state {
+            return Err(EncodeError::WrongState {
+                name: packet.name(),
+                packet_state: packet.state(),
+                state,
+            });
+        }
+
+        self.write_raw_packet(packet.to_raw_packet()?)
+    }
+}
+
+/// Returns whether error is caused by read timeout, kind differs between platforms.
+fn is_timeout(io_error: &io::Error) -> bool {
+    matches!(io_error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::connection::*;
+    use crate::error::TimeoutError;
+    use crate::version::v1_14_4::game::{GameServerBoundPacket, ServerBoundChatMessage};
+    use crate::version::v1_14_4::handshake::{Handshake, NextState};
+    use crate::version::v1_14_4::login::{
+        LoginClientBoundPacket, LoginServerBoundPacket, LoginStart, LoginSuccess, SetCompression,
+    };
+    use crate::version::v1_14_4::{ClientBoundPacket, ServerBoundPacket};
+    use std::collections::VecDeque;
+    use std::io::Cursor;
+    use uuid::Uuid;
+
+    const THRESHOLD: usize = 64;
+
+    /// Stream reading prepared bytes and collecting written ones.
+    #[derive(Default)]
+    struct Stream {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Read for Stream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Stream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Moves written bytes from one connection to the other.
+    fn transfer(from: &mut Connection<Stream>, to: &mut Connection<Stream>) {
+        let bytes = std::mem::take(&mut from.get_mut().output);
+        to.get_mut().input = Cursor::new(bytes);
+    }
+
+    /// Packets right below, at and above the threshold.
+    fn boundary_packets() -> Vec<RawPacket> {
+        (THRESHOLD - 2..=THRESHOLD)
+            .map(|length| RawPacket::new(0x20, vec![0; length]))
+            .collect()
+    }
+
+    #[test]
+    fn test_compression_negotiated_both_directions() {
+        let mut client = Connection::new(Stream::default(), Side::Client);
+        let mut server = Connection::new(Stream::default(), Side::Server);
+
+        client
+            .write_packet(&Handshake::new(
+                498,
+                String::from("localhost"),
+                25565,
+                NextState::Login,
+            ))
+            .unwrap();
+        transfer(&mut client, &mut server);
+        server.read_raw_packet().unwrap();
+        assert_eq!(server.state(), ProtocolState::Login);
+
+        server
+            .write_packet(&SetCompression::new(THRESHOLD as i32))
+            .unwrap();
+        server
+            .write_packet(&LoginSuccess::new(Uuid::nil(), String::from("Username")))
+            .unwrap();
+        assert_eq!(server.compression_threshold(), Some(THRESHOLD));
+
+        for raw_packet in boundary_packets() {
+            server.write_raw_packet(raw_packet).unwrap();
+        }
+
+        transfer(&mut server, &mut client);
+
+        let packet: LoginClientBoundPacket = client.read_packet().unwrap();
+        assert!(matches!(packet, LoginClientBoundPacket::SetCompression(_)));
+        assert_eq!(client.compression_threshold(), Some(THRESHOLD));
+
+        let packet: LoginClientBoundPacket = client.read_packet().unwrap();
+        assert!(matches!(packet, LoginClientBoundPacket::LoginSuccess(_)));
+        assert_eq!(client.state(), ProtocolState::Game);
+
+        for raw_packet in boundary_packets() {
+            assert_eq!(client.read_raw_packet().unwrap(), raw_packet);
+        }
+
+        for raw_packet in boundary_packets() {
+            client.write_raw_packet(raw_packet).unwrap();
+        }
+
+        // Frame length and data length are followed by zlib header only if packet is compressed.
+        let output = &client.get_ref().output;
+        assert_eq!(output[1], 0);
+        assert_eq!(output[0] as usize, THRESHOLD);
+        assert!(output.len() < 3 * THRESHOLD);
+
+        transfer(&mut client, &mut server);
+
+        for raw_packet in boundary_packets() {
+            assert_eq!(server.read_raw_packet().unwrap(), raw_packet);
+        }
+    }
+
+    #[test]
+    fn test_state_packets_follow_connection_state() {
+        let mut client = Connection::new(Stream::default(), Side::Client);
+        let mut server = Connection::new(Stream::default(), Side::Server);
+
+        let handshake = Handshake::new(498, String::from("localhost"), 25565, NextState::Login);
+        client
+            .write_state_packet(&ServerBoundPacket::from(handshake))
+            .unwrap();
+        let login_start = LoginStart::new(String::from("Username"));
+        client
+            .write_state_packet(&ServerBoundPacket::from(login_start))
+            .unwrap();
+        transfer(&mut client, &mut server);
+
+        match server.read_state_packet().unwrap() {
+            ServerBoundPacket::Handshake(_) => {}
+            packet => panic!("Expected `Handshake` but got `{:?}`", packet),
+        }
+
+        match server.read_state_packet().unwrap() {
+            ServerBoundPacket::Login(LoginServerBoundPacket::LoginStart(login_start)) => {
+                assert_eq!(login_start.name, "Username")
+            }
+            packet => panic!("Expected `LoginStart` but got `{:?}`", packet),
+        }
+
+        let login_success = LoginSuccess::new(Uuid::nil(), String::from("Username"));
+        server
+            .write_state_packet(&ClientBoundPacket::from(login_success))
+            .unwrap();
+        transfer(&mut server, &mut client);
+
+        match client.read_state_packet().unwrap() {
+            ClientBoundPacket::Login(LoginClientBoundPacket::LoginSuccess(_)) => {}
+            packet => panic!("Expected `LoginSuccess` but got `{:?}`", packet),
+        }
+
+        let chat_message = ServerBoundChatMessage::new(String::from("Hello"));
+        client
+            .write_state_packet(&ServerBoundPacket::from(chat_message))
+            .unwrap();
+        transfer(&mut client, &mut server);
+
+        match server.read_state_packet().unwrap() {
+            ServerBoundPacket::Game(GameServerBoundPacket::ServerBoundChatMessage(message)) => {
+                assert_eq!(message.message, "Hello")
+            }
+            packet => panic!("Expected `ServerBoundChatMessage` but got `{:?}`", packet),
+        }
+    }
+
+    #[test]
+    fn test_write_state_packet_wrong_state() {
+        let mut client = Connection::new(Stream::default(), Side::Client);
+        let login_start = ServerBoundPacket::from(LoginStart::new(String::from("Username")));
+
+        let encode_error = client
+            .write_state_packet(&login_start)
+            .expect_err("Expected error `WrongState` because handshake wasn't sent");
+
+        match encode_error {
+            EncodeError::WrongState {
+                name,
+                packet_state,
+                state,
+            } => {
+                assert_eq!(name, "LoginStart");
+                assert_eq!(packet_state, ProtocolState::Login);
+                assert_eq!(state, ProtocolState::Handshake);
+            }
+            _ => panic!("Expected `WrongState` but got `{:?}`", encode_error),
+        }
+
+        assert!(client.get_ref().output.is_empty());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encryption_both_directions() {
+        use crate::version::v1_14_4::login::{EncryptionResponse, LoginPluginResponse};
+
+        let shared_secret = [3; SHARED_SECRET_LENGTH];
+        let mut client = Connection::new(Stream::default(), Side::Client);
+        let mut server = Connection::new(Stream::default(), Side::Server);
+
+        client
+            .write_packet(&Handshake::new(
+                498,
+                String::from("localhost"),
+                25565,
+                NextState::Login,
+            ))
+            .unwrap();
+        client
+            .write_packet(&EncryptionResponse::new(vec![1; 128], vec![2; 128]))
+            .unwrap();
+        client.enable_encryption(&shared_secret);
+        client
+            .write_packet(&LoginPluginResponse::new(1, true, b"Plaintext".to_vec()))
+            .unwrap();
+
+        assert!(!client
+            .get_ref()
+            .output
+            .windows(9)
+            .any(|window| window == b"Plaintext"));
+
+        // Encrypted packet is received together with `EncryptionResponse`.
+        transfer(&mut client, &mut server);
+        server.read_raw_packet().unwrap();
+        let packet: LoginServerBoundPacket = server.read_packet().unwrap();
+        assert!(matches!(
+            packet,
+            LoginServerBoundPacket::EncryptionResponse(_)
+        ));
+
+        server.enable_encryption(&shared_secret);
+        assert!(server.is_encrypted());
+
+        match server.read_packet::<LoginServerBoundPacket>().unwrap() {
+            LoginServerBoundPacket::LoginPluginResponse(response) => {
+                assert_eq!(response.data, b"Plaintext")
+            }
+            packet => panic!("Expected `LoginPluginResponse` but got `{:?}`", packet),
+        }
+
+        server
+            .write_packet(&LoginSuccess::new(Uuid::nil(), String::from("Username")))
+            .unwrap();
+        transfer(&mut server, &mut client);
+
+        let packet: LoginClientBoundPacket = client.read_packet().unwrap();
+        assert!(matches!(packet, LoginClientBoundPacket::LoginSuccess(_)));
+        assert_eq!(client.state(), ProtocolState::Game);
+    }
+
+    #[test]
+    fn test_read_and_dispatch() {
+        let mut server = Connection::new(Stream::default(), Side::Server);
+        let mut client = Connection::new(Stream::default(), Side::Client);
+
+        server
+            .write_packet(&SetCompression::new(THRESHOLD as i32))
+            .unwrap();
+        transfer(&mut server, &mut client);
+
+        let mut handlers = PacketHandlers::new();
+        handlers.on(
+            |set_compression: &SetCompression, thresholds: &mut Vec<i32>| {
+                thresholds.push(set_compression.threshold)
+            },
+        );
+
+        let mut thresholds = Vec::new();
+        let packet: LoginClientBoundPacket = client
+            .read_and_dispatch(&mut handlers, &mut thresholds)
+            .unwrap();
+
+        assert!(matches!(packet, LoginClientBoundPacket::SetCompression(_)));
+        assert_eq!(thresholds, [THRESHOLD as i32]);
+    }
+
+    #[test]
+    fn test_read_unexpected_eof() {
+        let mut connection = Connection::new(Stream::default(), Side::Server);
+        connection.get_mut().input = Cursor::new(vec![5, 0]);
+
+        let decode_error = connection
+            .read_raw_packet()
+            .expect_err("Expected error `IOError` because stream ended inside frame");
+
+        match decode_error {
+            DecodeError::IOError { io_error } => {
+                assert_eq!(io_error.kind(), ErrorKind::UnexpectedEof)
+            }
+            _ => panic!("Expected `IOError` but got `{:?}`", decode_error),
+        }
+    }
+
+    /// Stream returning `WouldBlock` when it has no bytes, like a non-blocking socket.
+    #[derive(Default)]
+    struct NonBlockingStream {
+        input: VecDeque<u8>,
+    }
+
+    impl Read for NonBlockingStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.input.is_empty() {
+                return Err(io::Error::from(ErrorKind::WouldBlock));
+            }
+
+            self.input.read(buf)
+        }
+    }
+
+    #[test]
+    fn test_read_deadline_keeps_partial_frame() {
+        let mut frame = Vec::new();
+        RawPacket::new(0x20, vec![1, 2, 3])
+            .encode(&mut frame)
+            .unwrap();
+        let (first, second) = frame.split_at(2);
+
+        let mut connection = Connection::new(NonBlockingStream::default(), Side::Client);
+        connection.get_mut().input.extend(first);
+
+        let decode_error = connection
+            .read_raw_packet_timeout(Duration::from_millis(10))
+            .expect_err("Expected error `IOError` because frame is incomplete");
+
+        match decode_error {
+            DecodeError::IOError { io_error } => assert_eq!(io_error.kind(), ErrorKind::TimedOut),
+            _ => panic!("Expected `IOError` but got `{:?}`", decode_error),
+        }
+
+        connection.get_mut().input.extend(second);
+
+        let raw_packet = connection
+            .read_raw_packet_deadline(Instant::now() + Duration::from_millis(10))
+            .unwrap();
+
+        assert_eq!(raw_packet, RawPacket::new(0x20, vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_read_deadline_passed() {
+        let mut connection = Connection::new(Stream::default(), Side::Client);
+        connection.get_mut().input = Cursor::new(vec![1, 0x20, 1, 0x21]);
+        connection.read_raw_packet().unwrap();
+
+        // Buffered packet is returned even if deadline passed.
+        let raw_packet = connection.read_raw_packet_deadline(Instant::now()).unwrap();
+        assert_eq!(raw_packet.id, 0x21);
+
+        let decode_error = connection
+            .read_raw_packet_deadline(Instant::now())
+            .expect_err("Expected error `IOError` because deadline passed");
+
+        match decode_error {
+            DecodeError::IOError { io_error } => assert_eq!(io_error.kind(), ErrorKind::TimedOut),
+            _ => panic!("Expected `IOError` but got `{:?}`", decode_error),
+        }
+    }
+
+    /// Stream which times out on every read.
+    struct StalledStream;
+
+    impl Read for StalledStream {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::from(ErrorKind::WouldBlock))
+        }
+    }
+
+    #[test]
+    fn test_read_handshake_timeout() {
+        let mut connection = Connection::new(StalledStream, Side::Server);
+        connection.set_timeout_policy(TimeoutPolicy {
+            handshake: Some(Duration::from_millis(10)),
+            ..TimeoutPolicy::none()
+        });
+
+        let decode_error = connection
+            .read_raw_packet()
+            .expect_err("Expected error `Timeout` because peer sent no handshake");
+
+        match decode_error {
+            DecodeError::Timeout {
+                timeout_error: TimeoutError { state, limit, .. },
+            } => {
+                assert_eq!(state, ProtocolState::Handshake);
+                assert_eq!(limit, Duration::from_millis(10));
+            }
+            _ => panic!("Expected `Timeout` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_timeout_without_policy() {
+        let mut connection = Connection::new(StalledStream, Side::Server);
+
+        let decode_error = connection
+            .read_raw_packet()
+            .expect_err("Expected error `IOError` because state is not limited");
+
+        match decode_error {
+            DecodeError::IOError { io_error } => assert_eq!(io_error.kind(), ErrorKind::WouldBlock),
+            _ => panic!("Expected `IOError` but got `{:?}`", decode_error),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Variable size bit set.
+//!
+//! Bits are sent as var int prefixed array of longs, bit `n` is stored in long `n / 64` at
+//! position `n % 64`. Trailing empty longs are not kept, like in vanilla `BitSet::toLongArray`.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::data::bit_set::BitSet;
+//!
+//! let mut bit_set = BitSet::new();
+//! bit_set.set(65, true);
+//!
+//! assert!(bit_set.get(65));
+//! assert_eq!(bit_set.words(), &[0, 2]);
+//! ```
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+const WORD_BITS: usize = 64;
+
+#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BitSet {
+    words: Vec<i64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        BitSet::default()
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        match self.words.get(index / WORD_BITS) {
+            Some(word) => word & (1 << (index % WORD_BITS)) != 0,
+            None => false,
+        }
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        let word_index = index / WORD_BITS;
+        let mask = 1 << (index % WORD_BITS);
+
+        if value {
+            if word_index >= self.words.len() {
+                self.words.resize(word_index + 1, 0);
+            }
+
+            self.words[word_index] |= mask;
+        } else if let Some(word) = self.words.get_mut(word_index) {
+            *word &= !mask;
+
+            while self.words.last() == Some(&0) {
+                self.words.pop();
+            }
+        }
+    }
+
+    /// Returns true if no bit is set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    pub fn words(&self) -> &[i64] {
+        &self.words
+    }
+}
+
+impl From<Vec<i64>> for BitSet {
+    fn from(words: Vec<i64>) -> Self {
+        BitSet { words }
+    }
+}
+
+impl From<BitSet> for Vec<i64> {
+    fn from(bit_set: BitSet) -> Self {
+        bit_set.words
+    }
+}
+
+impl Encoder for BitSet {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        self.words.encode(writer)
+    }
+}
+
+impl Decoder for BitSet {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        Ok(BitSet::from(Vec::<i64>::decode(reader)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::bit_set::BitSet;
+    use crate::decoder::Decoder;
+    use crate::encoder::Encoder;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_bit_set_get_set() {
+        let mut bit_set = BitSet::new();
+        bit_set.set(0, true);
+        bit_set.set(63, true);
+        bit_set.set(130, true);
+
+        assert!(bit_set.get(0));
+        assert!(bit_set.get(63));
+        assert!(bit_set.get(130));
+        assert!(!bit_set.get(1));
+        assert!(!bit_set.get(1000));
+        assert_eq!(bit_set.words(), &[i64::MIN + 1, 0, 4]);
+
+        bit_set.set(130, false);
+
+        assert_eq!(bit_set.words(), &[i64::MIN + 1]);
+    }
+
+    #[test]
+    fn test_bit_set_encode_decode() {
+        let mut bit_set = BitSet::new();
+        bit_set.set(3, true);
+        bit_set.set(64, true);
+
+        let mut vec = Vec::new();
+        bit_set.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [2, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(BitSet::decode(&mut Cursor::new(vec)).unwrap(), bit_set);
+    }
+}
@@ -0,0 +1,160 @@
+//! Block entities of a chunk keyed by position.
+//!
+//! Chunk data sends block entities as a list of NBT tags with position stored in `x`, `y` and `z`
+//! int tags. `BlockEntities` indexes them by position, so updates from block entity data packets
+//! can replace single entities before the list is sent again.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::data::block_entity::BlockEntities;
+//! use minecraft_protocol::data::game::BlockPosition;
+//! use nbt::CompoundTag;
+//!
+//! let mut chest = CompoundTag::new();
+//! chest.insert_str("id", "minecraft:chest");
+//!
+//! let mut block_entities = BlockEntities::new();
+//! block_entities.update(BlockPosition::new(1, 64, 2), chest);
+//!
+//! let tiles = block_entities.into_tags();
+//! assert_eq!(tiles[0].get_i32("y").unwrap(), 64);
+//! ```
+use crate::data::game::BlockPosition;
+use nbt::CompoundTag;
+use std::collections::BTreeMap;
+
+/// Returns block entity position, `None` if any coordinate is missing.
+pub fn block_entity_position(compound_tag: &CompoundTag) -> Option<BlockPosition> {
+    Some(BlockPosition {
+        x: compound_tag.get_i32("x").ok()?,
+        y: compound_tag.get_i32("y").ok()?,
+        z: compound_tag.get_i32("z").ok()?,
+    })
+}
+
+/// Block entities in position order.
+#[derive(Debug, Clone, Default)]
+pub struct BlockEntities {
+    entities: BTreeMap<BlockPosition, CompoundTag>,
+}
+
+impl BlockEntities {
+    pub fn new() -> Self {
+        BlockEntities {
+            entities: BTreeMap::new(),
+        }
+    }
+
+    /// Indexes tags from chunk data, tags without position are skipped.
+    pub fn from_tags<I: IntoIterator<Item = CompoundTag>>(tags: I) -> Self {
+        let entities = tags
+            .into_iter()
+            .filter_map(|tag| Some((block_entity_position(&tag)?, tag)))
+            .collect();
+
+        BlockEntities { entities }
+    }
+
+    pub fn get(&self, position: BlockPosition) -> Option<&CompoundTag> {
+        self.entities.get(&position)
+    }
+
+    /// Replaces block entity at position, e.g. with data of block entity data packet.
+    ///
+    /// Position tags are set from `position`, as packet data may omit them.
+    pub fn update(
+        &mut self,
+        position: BlockPosition,
+        mut data: CompoundTag,
+    ) -> Option<CompoundTag> {
+        data.insert_i32("x", position.x);
+        data.insert_i32("y", position.y);
+        data.insert_i32("z", position.z);
+
+        self.entities.insert(position, data)
+    }
+
+    /// Removes block entity, e.g. when its block is broken.
+    pub fn remove(&mut self, position: BlockPosition) -> Option<CompoundTag> {
+        self.entities.remove(&position)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (BlockPosition, &CompoundTag)> {
+        self.entities
+            .iter()
+            .map(|(position, compound_tag)| (*position, compound_tag))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Returns tags in position order to be sent in chunk data.
+    pub fn into_tags(self) -> Vec<CompoundTag> {
+        self.entities.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::block_entity::*;
+
+    fn tag(id: &str, x: i32, y: i32, z: i32) -> CompoundTag {
+        let mut compound_tag = CompoundTag::new();
+        compound_tag.insert_str("id", id);
+        compound_tag.insert_i32("x", x);
+        compound_tag.insert_i32("y", y);
+        compound_tag.insert_i32("z", z);
+
+        compound_tag
+    }
+
+    #[test]
+    fn test_from_tags_skips_tags_without_position() {
+        let block_entities = BlockEntities::from_tags(vec![
+            tag("minecraft:sign", 3, 70, 1),
+            CompoundTag::named("TileEntity"),
+            tag("minecraft:chest", 1, 64, 2),
+        ]);
+
+        assert_eq!(block_entities.len(), 2);
+        assert_eq!(
+            block_entities
+                .get(BlockPosition::new(1, 64, 2))
+                .unwrap()
+                .get_str("id")
+                .unwrap(),
+            "minecraft:chest"
+        );
+    }
+
+    #[test]
+    fn test_update_and_into_tags() {
+        let mut block_entities = BlockEntities::from_tags(vec![
+            tag("minecraft:sign", 3, 70, 1),
+            tag("minecraft:chest", 1, 64, 2),
+        ]);
+
+        let mut banner = CompoundTag::new();
+        banner.insert_str("id", "minecraft:banner");
+
+        let previous = block_entities.update(BlockPosition::new(3, 70, 1), banner);
+        assert_eq!(previous.unwrap().get_str("id").unwrap(), "minecraft:sign");
+
+        block_entities.remove(BlockPosition::new(1, 64, 2));
+
+        let tags = block_entities.into_tags();
+
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].get_str("id").unwrap(), "minecraft:banner");
+        assert_eq!(
+            block_entity_position(&tags[0]),
+            Some(BlockPosition::new(3, 70, 1))
+        );
+    }
+}
@@ -22,6 +22,14 @@
 //! println!("{}", message.to_json().unwrap());
 //! ```
 //!
+//! Messages with legacy formatting codes can be built with `msg!`:
+//!
+//! ```
+//! use minecraft_protocol::msg;
+//!
+//! let message = msg!("&eHello &a&l{}&e!", "world");
+//! ```
+//!
 //! ## Deserialize
 //!
 //! ```
@@ -61,12 +69,20 @@
 //! assert_eq!(expected_message, Message::from_json(json).unwrap());
 //! ```
 
+use crate::encoder;
+use crate::error::ColorError;
 use crate::impl_json_encoder_decoder;
+use crate::limits;
 use serde::{
     de::{self, Visitor},
     Deserialize, Serialize,
 };
 use serde_json::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "signing")]
+pub mod signing;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Color {
@@ -88,7 +104,7 @@ pub enum Color {
     White,
     /// A hex color string
     ///
-    /// Support for this was added in 1.16.
+    /// Support for this was added in 1.16. Use `Color::from_hex` to validate the string.
     ///
     /// # Examples
     ///
@@ -96,16 +112,82 @@ pub enum Color {
     /// use minecraft_protocol::data::chat::Color;
     ///
     /// let color = Color::Hex("#f98aff".into());
+    ///
+    /// assert_eq!(color.to_rgb(), Ok((0xf9, 0x8a, 0xff)));
+    /// assert_eq!(color.to_named(), Ok(Color::LightPurple));
     /// ```
     Hex(String),
 }
 
-impl Serialize for Color {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(match self {
+/// Named colors with their RGB values.
+const NAMED_COLORS: [(Color, u32); 16] = [
+    (Color::Black, 0x000000),
+    (Color::DarkBlue, 0x0000AA),
+    (Color::DarkGreen, 0x00AA00),
+    (Color::DarkAqua, 0x00AAAA),
+    (Color::DarkRed, 0xAA0000),
+    (Color::DarkPurple, 0xAA00AA),
+    (Color::Gold, 0xFFAA00),
+    (Color::Gray, 0xAAAAAA),
+    (Color::DarkGray, 0x555555),
+    (Color::Blue, 0x5555FF),
+    (Color::Green, 0x55FF55),
+    (Color::Aqua, 0x55FFFF),
+    (Color::Red, 0xFF5555),
+    (Color::LightPurple, 0xFF55FF),
+    (Color::Yellow, 0xFFFF55),
+    (Color::White, 0xFFFFFF),
+];
+
+impl Color {
+    /// Parses hex color in `#RRGGBB` format.
+    pub fn from_hex(hex: &str) -> Result<Self, ColorError> {
+        parse_hex(hex)?;
+
+        Ok(Color::Hex(hex.to_owned()))
+    }
+
+    pub fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        Color::Hex(format!("#{:02x}{:02x}{:02x}", red, green, blue))
+    }
+
+    /// Returns red, green and blue components of color.
+    pub fn to_rgb(&self) -> Result<(u8, u8, u8), ColorError> {
+        let rgb = match self {
+            Color::Hex(hex) => parse_hex(hex)?,
+            named => {
+                let (_, rgb) = NAMED_COLORS
+                    .iter()
+                    .find(|(color, _)| color == named)
+                    .expect("Named color is in table");
+
+                *rgb
+            }
+        };
+
+        Ok(((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+    }
+
+    /// Returns the nearest named color, which clients before 1.16 can display.
+    pub fn to_named(&self) -> Result<Self, ColorError> {
+        let (red, green, blue) = self.to_rgb()?;
+
+        let distance = |other: u32| {
+            let channel = |value: u8, shift: u32| value as i32 - ((other >> shift) & 0xFF) as i32;
+
+            channel(red, 16).pow(2) + channel(green, 8).pow(2) + channel(blue, 0).pow(2)
+        };
+
+        let (color, _) = NAMED_COLORS
+            .iter()
+            .min_by_key(|(_, other)| distance(*other))
+            .expect("Named colors are not empty");
+
+        Ok(color.clone())
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
             Color::Black => "black",
             Color::DarkBlue => "dark_blue",
             Color::DarkGreen => "dark_green",
@@ -123,7 +205,50 @@ impl Serialize for Color {
             Color::Yellow => "yellow",
             Color::White => "white",
             Color::Hex(val) => val,
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<u32, ColorError> {
+    hex.strip_prefix('#')
+        .filter(|digits| digits.len() == 6 && digits.bytes().all(|c| c.is_ascii_hexdigit()))
+        .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+        .ok_or_else(|| ColorError::InvalidHex {
+            value: hex.to_owned(),
         })
+}
+
+impl FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('#') {
+            return Color::from_hex(s);
+        }
+
+        NAMED_COLORS
+            .iter()
+            .map(|(color, _)| color)
+            .find(|color| color.as_str() == s)
+            .cloned()
+            .ok_or_else(|| ColorError::UnknownName {
+                value: s.to_owned(),
+            })
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -140,29 +265,8 @@ impl<'de> Visitor<'de> for ColorVisitor {
     where
         E: de::Error,
     {
-        Ok(if v.starts_with("#") {
-            Color::Hex(v.into())
-        } else {
-            match v {
-                "black" => Color::Black,
-                "dark_blue" => Color::DarkBlue,
-                "dark_green" => Color::DarkGreen,
-                "dark_aqua" => Color::DarkAqua,
-                "dark_red" => Color::DarkRed,
-                "dark_purple" => Color::DarkPurple,
-                "gold" => Color::Gold,
-                "gray" => Color::Gray,
-                "dark_gray" => Color::DarkGray,
-                "blue" => Color::Blue,
-                "green" => Color::Green,
-                "aqua" => Color::Aqua,
-                "red" => Color::Red,
-                "light_purple" => Color::LightPurple,
-                "yellow" => Color::Yellow,
-                "white" => Color::White,
-                _ => return Err(E::invalid_value(de::Unexpected::Str(v), &self)),
-            }
-        })
+        v.parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
     }
 }
 
@@ -278,6 +382,37 @@ impl Payload {
             selector: selector.to_owned(),
         }
     }
+
+    /// Returns json keys of payload.
+    #[cfg(feature = "chat-unknown-fields")]
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            Payload::Text { .. } => &["text"],
+            Payload::Translation { .. } => &["translate", "with"],
+            Payload::Keybind { .. } => &["keybind"],
+            Payload::Score { .. } => &["name", "objective", "value"],
+            Payload::Selector { .. } => &["selector"],
+        }
+    }
+}
+
+/// Flattened map receives payload fields too, they are removed so they aren't serialized twice.
+#[cfg(feature = "chat-unknown-fields")]
+fn deserialize_unknown<'de, D>(
+    deserializer: D,
+) -> Result<serde_json::Map<String, serde_json::Value>, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    let mut unknown = serde_json::Map::deserialize(deserializer)?;
+
+    if let Ok(payload) = Payload::deserialize(serde_json::Value::Object(unknown.clone())) {
+        for key in payload.keys() {
+            unknown.remove(*key);
+        }
+    }
+
+    Ok(unknown)
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -305,6 +440,10 @@ pub struct Message {
     pub payload: Payload,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub extra: Vec<Message>,
+    /// Fields not known to this crate, e.g. added by client mods. Serialized back unchanged.
+    #[cfg(feature = "chat-unknown-fields")]
+    #[serde(flatten, deserialize_with = "deserialize_unknown")]
+    pub unknown: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Message {
@@ -321,24 +460,73 @@ impl Message {
             hover_event: None,
             payload,
             extra: vec![],
+            #[cfg(feature = "chat-unknown-fields")]
+            unknown: serde_json::Map::new(),
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(text: &str) -> Message {
         Message::new(Payload::text(text))
     }
 
+    /// Parses message checking nesting against current decode limits.
     pub fn from_json(json: &str) -> Result<Self, Error> {
+        let max_depth = limits::current().json_max_depth;
+
+        if limits::check_json_depth(json, max_depth).is_err() {
+            return Err(de::Error::custom(format!(
+                "chat component nesting exceeds max depth {}",
+                max_depth
+            )));
+        }
+
         serde_json::from_str(json)
     }
 
     pub fn to_json(&self) -> Result<String, Error> {
         serde_json::to_string(&self)
     }
+
+    /// Same as `to_json`, but keys are sorted, so output is stable e.g. for fixtures.
+    pub fn to_canonical_json(&self) -> Result<String, Error> {
+        encoder::to_canonical_json(&self)
+    }
 }
 
 impl_json_encoder_decoder!(Message);
 
+/// Traversal of chat messages contained in packets.
+///
+/// Can be derived with `#[derive(VisitMessages)]`. Fields with `Message` in their type are visited,
+/// fields of other types containing messages have to be marked with `#[visit]`.
+pub trait VisitMessages {
+    /// Calls visitor with every message, visitor may change it.
+    fn visit_messages(&mut self, visitor: &mut dyn FnMut(&mut Message));
+}
+
+impl VisitMessages for Message {
+    fn visit_messages(&mut self, visitor: &mut dyn FnMut(&mut Message)) {
+        visitor(self)
+    }
+}
+
+impl<T: VisitMessages> VisitMessages for Option<T> {
+    fn visit_messages(&mut self, visitor: &mut dyn FnMut(&mut Message)) {
+        if let Some(value) = self {
+            value.visit_messages(visitor)
+        }
+    }
+}
+
+impl<T: VisitMessages> VisitMessages for Vec<T> {
+    fn visit_messages(&mut self, visitor: &mut dyn FnMut(&mut Message)) {
+        for value in self {
+            value.visit_messages(visitor)
+        }
+    }
+}
+
 pub struct MessageBuilder {
     current: Message,
     root: Option<Message>,
@@ -452,6 +640,19 @@ fn test_serialize_text_hello_world() {
     );
 }
 
+#[test]
+fn test_canonical_json_text_hello_world() {
+    let message = MessageBuilder::builder(Payload::text("Hello"))
+        .color(Color::Yellow)
+        .bold(true)
+        .build();
+
+    assert_eq!(
+        message.to_canonical_json().unwrap(),
+        r#"{"bold":true,"color":"yellow","text":"Hello"}"#
+    );
+}
+
 #[test]
 fn test_deserialize_text_hello_world() {
     let expected_message = MessageBuilder::builder(Payload::text("Hello"))
@@ -748,3 +949,126 @@ fn test_deserialize_hex_color() {
         expected_message
     );
 }
+
+#[test]
+fn test_deserialize_too_deep() {
+    let json = r#"{"text":"a","extra":[{"text":"b","extra":[{"text":"c"}]}]}"#;
+    let limits = limits::DecodeLimits {
+        json_max_depth: 3,
+        ..limits::DecodeLimits::default()
+    };
+
+    assert!(limits::with_limits(limits, || Message::from_json(json)).is_err());
+    assert!(Message::from_json(json).is_ok());
+}
+
+#[cfg(feature = "chat-unknown-fields")]
+#[test]
+fn test_unknown_fields_preserved() {
+    let json = r#"{"bold":true,"text":"Hello","font":"minecraft:uniform","extra":[{"text":"!","shadow_color":-1}],"modded":{"glow":[1,2]}}"#;
+
+    let message = Message::from_json(json).unwrap();
+
+    assert_eq!(message.payload, Payload::text("Hello"));
+    assert_eq!(message.unknown.len(), 2);
+    assert_eq!(message.unknown["font"], "minecraft:uniform");
+    assert_eq!(message.extra[0].unknown["shadow_color"], -1);
+
+    let expected_value: serde_json::Value = serde_json::from_str(json).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&message.to_json().unwrap()).unwrap();
+
+    assert_eq!(value, expected_value);
+}
+
+#[test]
+fn test_color_from_str() {
+    assert_eq!("dark_aqua".parse(), Ok(Color::DarkAqua));
+    assert_eq!("#a0B0c0".parse(), Ok(Color::Hex(String::from("#a0B0c0"))));
+    assert_eq!(
+        "#a0b0c".parse::<Color>(),
+        Err(ColorError::InvalidHex {
+            value: String::from("#a0b0c")
+        })
+    );
+    assert_eq!(
+        "#+0b0c0".parse::<Color>(),
+        Err(ColorError::InvalidHex {
+            value: String::from("#+0b0c0")
+        })
+    );
+    assert_eq!(
+        "pink".parse::<Color>(),
+        Err(ColorError::UnknownName {
+            value: String::from("pink")
+        })
+    );
+}
+
+#[test]
+fn test_color_display() {
+    assert_eq!(Color::LightPurple.to_string(), "light_purple");
+    assert_eq!(Color::from_rgb(0xff, 0x50, 0x0a).to_string(), "#ff500a");
+}
+
+#[test]
+fn test_color_rgb() {
+    assert_eq!(Color::Gold.to_rgb(), Ok((0xff, 0xaa, 0x00)));
+    assert_eq!(Color::from_rgb(1, 2, 3).to_rgb(), Ok((1, 2, 3)));
+}
+
+#[test]
+fn test_color_to_named() {
+    assert_eq!(Color::from_rgb(0xff, 0x4f, 0x4f).to_named(), Ok(Color::Red));
+    assert_eq!(
+        Color::from_rgb(0x10, 0x10, 0x10).to_named(),
+        Ok(Color::Black)
+    );
+    assert_eq!(Color::Aqua.to_named(), Ok(Color::Aqua));
+}
+
+#[test]
+fn test_deserialize_invalid_hex_color() {
+    assert!(Message::from_json(r##"{"text":"a","color":"#ggg000"}"##).is_err());
+}
+
+#[test]
+fn test_msg_plain_text() {
+    let message = crate::msg!("&eHello {}!", 42);
+
+    let expected_message = MessageBuilder::builder(Payload::text("Hello 42!"))
+        .color(Color::Yellow)
+        .build();
+
+    assert_eq!(message, expected_message);
+}
+
+#[test]
+fn test_msg_color_code_resets_style() {
+    let player_name = String::from("Steve");
+    let message = crate::msg!("&aHello &l{}&6, welcome&r!", player_name);
+
+    let expected_message = MessageBuilder::builder(Payload::text(""))
+        .then(Payload::text("Hello "))
+        .color(Color::Green)
+        .then(Payload::text("Steve"))
+        .color(Color::Green)
+        .bold(true)
+        .then(Payload::text(", welcome"))
+        .color(Color::Gold)
+        .then(Payload::text("!"))
+        .build();
+
+    assert_eq!(message, expected_message);
+}
+
+#[test]
+fn test_msg_escapes_and_hex_color() {
+    let message = crate::msg!("§#f98aff§o{{&&}} {:>3}", 7);
+
+    let expected_message = MessageBuilder::builder(Payload::text("{&}   7"))
+        .color(Color::Hex(String::from("#f98aff")))
+        .italic(true)
+        .build();
+
+    assert_eq!(message, expected_message);
+}
@@ -0,0 +1,302 @@
+//! Chat message signing used since 1.19.3.
+//!
+//! Player signs every chat message with the private key of its profile key pair. The signature
+//! covers message link (sender, chat session and message index) and message body, which includes
+//! signatures of the last messages seen by player. `LastSeenTracker` collects these signatures on
+//! client side.
+//!
+//! Available with `signing` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::data::chat::signing::{SignedMessageBody, SignedMessageLink};
+//! use minecraft_protocol::uuid::Uuid;
+//!
+//! let link = SignedMessageLink::new(Uuid::nil(), Uuid::nil(), 0);
+//! let body = SignedMessageBody::new(String::from("Hello"), 1577735845610, 42, vec![]);
+//!
+//! let mut signed_bytes = Vec::new();
+//! link.write_signed_bytes(&mut signed_bytes);
+//! body.write_signed_bytes(&mut signed_bytes);
+//!
+//! assert_eq!(signed_bytes.len(), 36 + 8 + 8 + 4 + 5 + 4);
+//! ```
+use crate::error::SigningError;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+pub use crate::data::message_signature::{LastSeenUpdate, MessageSignature, SIGNATURE_LENGTH};
+
+/// Number of last seen messages tracked by client.
+pub const LAST_SEEN_MAX: usize = 20;
+
+/// Version of signed data layout.
+const SIGNATURE_VERSION: i32 = 1;
+
+/// Position of message in the chain of messages sent by player.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SignedMessageLink {
+    pub sender: Uuid,
+    /// Id of chat session started by player with `PlayerSession` packet.
+    pub session_id: Uuid,
+    /// Index of message in session.
+    pub index: i32,
+}
+
+impl SignedMessageLink {
+    pub fn new(sender: Uuid, session_id: Uuid, index: i32) -> Self {
+        SignedMessageLink {
+            sender,
+            session_id,
+            index,
+        }
+    }
+
+    /// Writes link part of signed data.
+    pub fn write_signed_bytes(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.sender.as_bytes());
+        buf.extend_from_slice(self.session_id.as_bytes());
+        buf.extend_from_slice(&self.index.to_be_bytes());
+    }
+}
+
+/// Signed part of chat message.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SignedMessageBody {
+    pub content: String,
+    /// Milliseconds since epoch. Only whole seconds are signed.
+    pub timestamp: i64,
+    pub salt: i64,
+    /// Signatures of messages seen by player, oldest first.
+    pub last_seen: Vec<MessageSignature>,
+}
+
+impl SignedMessageBody {
+    pub fn new(
+        content: String,
+        timestamp: i64,
+        salt: i64,
+        last_seen: Vec<MessageSignature>,
+    ) -> Self {
+        SignedMessageBody {
+            content,
+            timestamp,
+            salt,
+            last_seen,
+        }
+    }
+
+    /// Writes body part of signed data.
+    pub fn write_signed_bytes(&self, buf: &mut Vec<u8>) {
+        let content = self.content.as_bytes();
+
+        buf.extend_from_slice(&self.salt.to_be_bytes());
+        buf.extend_from_slice(&(self.timestamp / 1000).to_be_bytes());
+        buf.extend_from_slice(&(content.len() as i32).to_be_bytes());
+        buf.extend_from_slice(content);
+        buf.extend_from_slice(&(self.last_seen.len() as i32).to_be_bytes());
+
+        for signature in &self.last_seen {
+            buf.extend_from_slice(&signature.0);
+        }
+    }
+}
+
+/// Returns data which player signs for message.
+pub fn signed_bytes(link: &SignedMessageLink, body: &SignedMessageBody) -> Vec<u8> {
+    let mut buf = SIGNATURE_VERSION.to_be_bytes().to_vec();
+    link.write_signed_bytes(&mut buf);
+    body.write_signed_bytes(&mut buf);
+
+    buf
+}
+
+/// Public part of player profile key pair.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProfilePublicKey {
+    key: RsaPublicKey,
+}
+
+impl ProfilePublicKey {
+    /// Parses key in X.509 DER format as sent in `PlayerSession` packet.
+    pub fn from_der(der: &[u8]) -> Result<Self, SigningError> {
+        let key = RsaPublicKey::from_public_key_der(der).map_err(|_| SigningError::InvalidKey)?;
+
+        Ok(ProfilePublicKey { key })
+    }
+
+    /// Verifies SHA256withRSA signature of the message.
+    pub fn verify(
+        &self,
+        link: &SignedMessageLink,
+        body: &SignedMessageBody,
+        signature: &MessageSignature,
+    ) -> Result<(), SigningError> {
+        let hashed = Sha256::digest(signed_bytes(link, body));
+
+        self.key
+            .verify(Pkcs1v15Sign::new::<Sha256>(), &hashed, &signature.0)
+            .map_err(|_| SigningError::InvalidSignature)
+    }
+}
+
+impl From<RsaPublicKey> for ProfilePublicKey {
+    fn from(key: RsaPublicKey) -> Self {
+        ProfilePublicKey { key }
+    }
+}
+
+/// Tracks signatures of last received messages on client side.
+#[derive(Debug, Clone)]
+pub struct LastSeenTracker {
+    tracked: Vec<Option<MessageSignature>>,
+    tail: usize,
+    offset: i32,
+}
+
+impl LastSeenTracker {
+    pub fn new() -> Self {
+        LastSeenTracker {
+            tracked: vec![None; LAST_SEEN_MAX],
+            tail: 0,
+            offset: 0,
+        }
+    }
+
+    /// Adds signature of received message. Repeated signature of the latest message is ignored.
+    pub fn add(&mut self, signature: MessageSignature) {
+        let latest = (self.tail + LAST_SEEN_MAX - 1) % LAST_SEEN_MAX;
+
+        if self.tracked[latest].as_ref() == Some(&signature) {
+            return;
+        }
+
+        self.tracked[self.tail] = Some(signature);
+        self.tail = (self.tail + 1) % LAST_SEEN_MAX;
+        self.offset += 1;
+    }
+
+    /// Number of messages received since the last update.
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// Returns last seen signatures to sign with next message and update to send with it.
+    pub fn update(&mut self) -> (Vec<MessageSignature>, LastSeenUpdate) {
+        let mut last_seen = Vec::new();
+        let mut acknowledged = 0;
+
+        for i in 0..LAST_SEEN_MAX {
+            if let Some(signature) = &self.tracked[(self.tail + i) % LAST_SEEN_MAX] {
+                acknowledged |= 1 << i;
+                last_seen.push(signature.clone());
+            }
+        }
+
+        let update = LastSeenUpdate {
+            offset: self.offset,
+            acknowledged,
+        };
+
+        self.offset = 0;
+
+        (last_seen, update)
+    }
+}
+
+impl Default for LastSeenTracker {
+    fn default() -> Self {
+        LastSeenTracker::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::chat::signing::*;
+    use rsa::RsaPrivateKey;
+
+    fn signature(byte: u8) -> MessageSignature {
+        MessageSignature([byte; SIGNATURE_LENGTH])
+    }
+
+    #[test]
+    fn test_signed_bytes() {
+        let sender = Uuid::parse_str("35ee313b-d89a-41b8-b25e-d32e8aff0389").unwrap();
+        let link = SignedMessageLink::new(sender, Uuid::nil(), 3);
+        let body = SignedMessageBody::new(String::from("Hi"), 1577735845610, 1, vec![signature(7)]);
+
+        let bytes = signed_bytes(&link, &body);
+
+        assert_eq!(&bytes[..4], [0, 0, 0, 1]);
+        assert_eq!(&bytes[4..20], sender.as_bytes());
+        assert_eq!(&bytes[36..40], [0, 0, 0, 3]);
+        assert_eq!(&bytes[40..48], [0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(&bytes[48..56], 1577735845i64.to_be_bytes());
+        assert_eq!(&bytes[56..62], [0, 0, 0, 2, b'H', b'i']);
+        assert_eq!(&bytes[62..66], [0, 0, 0, 1]);
+        assert_eq!(&bytes[66..], [7; SIGNATURE_LENGTH]);
+    }
+
+    #[test]
+    fn test_verify() {
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 2048).unwrap();
+        let public_key = ProfilePublicKey::from(private_key.to_public_key());
+
+        let link = SignedMessageLink::new(Uuid::new_v4(), Uuid::new_v4(), 0);
+        let body = SignedMessageBody::new(String::from("Hello"), 1577735845610, 42, vec![]);
+
+        let hashed = Sha256::digest(signed_bytes(&link, &body));
+        let bytes = private_key
+            .sign(Pkcs1v15Sign::new::<Sha256>(), &hashed)
+            .unwrap();
+
+        let mut signature = signature(0);
+        signature.0.copy_from_slice(&bytes);
+
+        assert_eq!(public_key.verify(&link, &body, &signature), Ok(()));
+
+        let tampered = SignedMessageBody::new(String::from("Hello!"), 1577735845610, 42, vec![]);
+
+        assert_eq!(
+            public_key.verify(&link, &tampered, &signature),
+            Err(SigningError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_invalid_key() {
+        assert_eq!(
+            ProfilePublicKey::from_der(&[1, 2, 3]),
+            Err(SigningError::InvalidKey)
+        );
+    }
+
+    #[test]
+    fn test_last_seen_tracker() {
+        let mut tracker = LastSeenTracker::new();
+        tracker.add(signature(1));
+        tracker.add(signature(1));
+        tracker.add(signature(2));
+
+        let (last_seen, update) = tracker.update();
+
+        assert_eq!(last_seen, [signature(1), signature(2)]);
+        assert_eq!(update.offset, 2);
+        assert_eq!(update.acknowledged, 0b11 << (LAST_SEEN_MAX - 2));
+        assert_eq!(tracker.offset(), 0);
+
+        for byte in 3..30 {
+            tracker.add(signature(byte));
+        }
+
+        let (last_seen, update) = tracker.update();
+
+        assert_eq!(last_seen.len(), LAST_SEEN_MAX);
+        assert_eq!(last_seen[0], signature(10));
+        assert_eq!(update.offset, 27);
+        assert_eq!(update.acknowledged, (1 << LAST_SEEN_MAX) - 1);
+    }
+}
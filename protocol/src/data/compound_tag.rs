@@ -0,0 +1,269 @@
+//! Serde support and encoding cache for NBT compound tags used in packets.
+//!
+//! `nbt::CompoundTag` doesn't implement serde traits, so tags are represented as a hex string
+//! of their binary NBT encoding. This keeps the representation lossless and lets packets carrying
+//! tags be converted to and from formats like JSON.
+//!
+//! `nbt` encodes tags by value, so every encode clones the tag. Tags which are sent many times,
+//! like chunk heightmaps, can be shared with `Arc` and encoded once with `CompoundTagCache`.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::data::compound_tag;
+//! use nbt::CompoundTag;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Chunk {
+//!     #[serde(with = "compound_tag")]
+//!     heights: CompoundTag,
+//! }
+//! ```
+use crate::decoder::DecoderReadExt;
+use crate::encoder::EncoderWriteExt;
+use crate::error::EncodeError;
+use nbt::CompoundTag;
+use serde::{de, Deserialize, Deserializer, Serializer};
+use std::collections::hash_map::{Entry, HashMap};
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+
+pub fn serialize<S: Serializer>(value: &CompoundTag, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&to_hex(value).map_err(serde::ser::Error::custom)?)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CompoundTag, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+
+    from_hex(&hex).map_err(de::Error::custom)
+}
+
+/// Serde support for a list of compound tags.
+pub mod vec {
+    use super::{from_hex, to_hex};
+    use nbt::CompoundTag;
+    use serde::ser::SerializeSeq;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &[CompoundTag],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(value.len()))?;
+
+        for compound_tag in value {
+            seq.serialize_element(&to_hex(compound_tag).map_err(serde::ser::Error::custom)?)?;
+        }
+
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<CompoundTag>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|hex| from_hex(hex).map_err(de::Error::custom))
+            .collect()
+    }
+}
+
+/// Serde support for an optional compound tag.
+pub mod option {
+    use super::{from_hex, to_hex};
+    use nbt::CompoundTag;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<CompoundTag>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(compound_tag) => {
+                serializer.serialize_some(&to_hex(compound_tag).map_err(serde::ser::Error::custom)?)
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<CompoundTag>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|hex| from_hex(&hex).map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+/// Serde support for a shared compound tag.
+pub mod arc {
+    use nbt::CompoundTag;
+    use serde::{Deserializer, Serializer};
+    use std::sync::Arc;
+
+    pub fn serialize<S: Serializer>(
+        value: &Arc<CompoundTag>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize(value, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arc<CompoundTag>, D::Error> {
+        super::deserialize(deserializer).map(Arc::new)
+    }
+}
+
+/// Encoded compound tags keyed by `Arc` identity.
+///
+/// Cache holds a reference to every tag, so a cached tag can't be changed or replaced by another
+/// tag at the same address. Tags which are not used anywhere else are dropped by `prune`.
+#[derive(Debug, Default)]
+pub struct CompoundTagCache {
+    entries: HashMap<usize, (Arc<CompoundTag>, Vec<u8>)>,
+}
+
+impl CompoundTagCache {
+    pub fn new() -> Self {
+        CompoundTagCache::default()
+    }
+
+    /// Returns encoded tag, encoding it on the first call.
+    pub fn get_or_encode(&mut self, tag: &Arc<CompoundTag>) -> Result<&[u8], EncodeError> {
+        let entry = match self.entries.entry(Arc::as_ptr(tag) as usize) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mut encoded = Vec::new();
+                encoded.write_compound_tag(tag)?;
+
+                entry.insert((tag.clone(), encoded))
+            }
+        };
+
+        Ok(&entry.1)
+    }
+
+    /// Writes tag encoded on the first call.
+    pub fn write<W: Write>(
+        &mut self,
+        writer: &mut W,
+        tag: &Arc<CompoundTag>,
+    ) -> Result<(), EncodeError> {
+        let encoded = self.get_or_encode(tag)?;
+        writer.write_all(encoded)?;
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, tag: &Arc<CompoundTag>) -> bool {
+        self.entries.remove(&(Arc::as_ptr(tag) as usize)).is_some()
+    }
+
+    /// Drops tags which are referenced only by the cache.
+    pub fn prune(&mut self) {
+        self.entries
+            .retain(|_, (tag, _)| Arc::strong_count(tag) > 1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn to_hex(value: &CompoundTag) -> Result<String, String> {
+    let mut vec = Vec::new();
+    vec.write_compound_tag(value)
+        .map_err(|e| format!("failed to encode compound tag: {:?}", e))?;
+
+    Ok(vec.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+fn from_hex(hex: &str) -> Result<CompoundTag, String> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err(String::from("compound tag hex string has invalid length"));
+    }
+
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|e| format!("compound tag hex string is invalid: {}", e))?;
+
+    Cursor::new(bytes)
+        .read_compound_tag()
+        .map_err(|e| format!("failed to decode compound tag: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::compound_tag::*;
+    use serde::Serialize;
+
+    #[derive(Serialize, Deserialize)]
+    struct Tags {
+        #[serde(with = "super")]
+        tag: CompoundTag,
+        #[serde(with = "super::vec")]
+        tags: Vec<CompoundTag>,
+    }
+
+    #[test]
+    fn test_compound_tag_json_round_trip() {
+        let mut tag = CompoundTag::named("HeightMaps");
+        tag.insert_i32("x", 5);
+
+        let tags = Tags {
+            tag,
+            tags: vec![CompoundTag::named("TileEntity")],
+        };
+
+        let json = serde_json::to_string(&tags).unwrap();
+        let decoded: Tags = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded.tag.name, Some(String::from("HeightMaps")));
+        assert_eq!(decoded.tag.get_i32("x").unwrap(), 5);
+        assert_eq!(decoded.tags[0].name, Some(String::from("TileEntity")));
+    }
+
+    #[test]
+    fn test_compound_tag_invalid_hex() {
+        let json = r#"{"tag":"0a0","tags":[]}"#;
+
+        assert!(serde_json::from_str::<Tags>(json).is_err());
+    }
+
+    #[test]
+    fn test_cache_encodes_once() {
+        let mut tag = CompoundTag::named("HeightMaps");
+        tag.insert_i64_vec("MOTION_BLOCKING", vec![0; 36]);
+        let tag = Arc::new(tag);
+
+        let mut expected = Vec::new();
+        expected.write_compound_tag(&tag).unwrap();
+
+        let mut cache = CompoundTagCache::new();
+        let mut vec = Vec::new();
+        cache.write(&mut vec, &tag).unwrap();
+        cache.write(&mut vec, &tag.clone()).unwrap();
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(vec, [expected.clone(), expected].concat());
+
+        let other = Arc::new(CompoundTag::named("HeightMaps"));
+        cache.get_or_encode(&other).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        drop(other);
+        cache.prune();
+
+        assert_eq!(cache.len(), 1);
+        assert!(cache.remove(&tag));
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+//! Cookies stored by client on behalf of server.
+//!
+//! Server asks client to store cookie and requests it back later, e.g. after transferring
+//! client to another server. Client is not trusted to keep cookies intact, so their content should
+//! be signed by server.
+use crate::decoder::{read_length, Decoder};
+use crate::encoder::{Encoder, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// Vanilla client and server limit cookie payload.
+pub const COOKIE_PAYLOAD_MAX_LENGTH: usize = 5120;
+
+/// Length prefixed cookie payload, at most `COOKIE_PAYLOAD_MAX_LENGTH` bytes.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CookiePayload(Vec<u8>);
+
+impl CookiePayload {
+    pub fn new(data: Vec<u8>) -> Result<Self, EncodeError> {
+        check_length(data.len())?;
+
+        Ok(CookiePayload(data))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl Encoder for CookiePayload {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        check_length(self.0.len())?;
+
+        writer.write_byte_array(&self.0)
+    }
+}
+
+impl Decoder for CookiePayload {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let length = read_length(reader)?;
+
+        if length > COOKIE_PAYLOAD_MAX_LENGTH {
+            return Err(DecodeError::PayloadTooLong {
+                length,
+                max_length: COOKIE_PAYLOAD_MAX_LENGTH,
+            });
+        }
+
+        let mut data = vec![0; length];
+        reader.read_exact(&mut data)?;
+
+        Ok(CookiePayload(data))
+    }
+}
+
+fn check_length(length: usize) -> Result<(), EncodeError> {
+    if length > COOKIE_PAYLOAD_MAX_LENGTH {
+        return Err(EncodeError::PayloadTooLong {
+            length,
+            max_length: COOKIE_PAYLOAD_MAX_LENGTH,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::cookie::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_cookie_payload_round_trip() {
+        let payload = CookiePayload::new(vec![1, 2, 3]).unwrap();
+
+        let mut vec = Vec::new();
+        payload.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [3, 1, 2, 3]);
+        assert_eq!(
+            CookiePayload::decode(&mut Cursor::new(vec)).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn test_cookie_payload_negative_length() {
+        let mut cursor = Cursor::new(vec![0xff, 0xff, 0xff, 0xff, 0x0f, 1]);
+
+        let decode_error = CookiePayload::decode(&mut cursor)
+            .expect_err("Expected error `NegativeLength` because length prefix is -1");
+
+        match decode_error {
+            DecodeError::NegativeLength { length } => assert_eq!(length, -1),
+            _ => panic!("Expected `NegativeLength` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_cookie_payload_too_long() {
+        let encode_error = CookiePayload::new(vec![0; COOKIE_PAYLOAD_MAX_LENGTH + 1])
+            .expect_err("Expected error `PayloadTooLong` because payload is too long");
+
+        assert_eq!(
+            encode_error,
+            EncodeError::PayloadTooLong {
+                length: COOKIE_PAYLOAD_MAX_LENGTH + 1,
+                max_length: COOKIE_PAYLOAD_MAX_LENGTH,
+            }
+        );
+
+        let mut vec = Vec::new();
+        vec.write_var_i32(COOKIE_PAYLOAD_MAX_LENGTH as i32 + 1)
+            .unwrap();
+
+        let decode_error = CookiePayload::decode(&mut Cursor::new(vec))
+            .expect_err("Expected error `PayloadTooLong` because payload is too long");
+
+        match decode_error {
+            DecodeError::PayloadTooLong { length, max_length } => {
+                assert_eq!(length, COOKIE_PAYLOAD_MAX_LENGTH + 1);
+                assert_eq!(max_length, COOKIE_PAYLOAD_MAX_LENGTH);
+            }
+            _ => panic!("Expected `PayloadTooLong` but got `{:?}`", decode_error),
+        }
+    }
+}
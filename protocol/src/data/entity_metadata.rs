@@ -0,0 +1,411 @@
+//! Entity metadata sent in entity metadata packets.
+//!
+//! Metadata is a list of values keyed by index, meaning of each index depends on entity type.
+//! `EntityMetadataBuilder` sets indices shared by all entities, `Metadata::diff` keeps only entries
+//! which changed, so update packets don't resend the whole entity state.
+//!
+//! Value type ids and indices are the ones used by 1.14.4. Slot, NBT and particle values are not
+//! supported, metadata containing them fails to decode with `DecodeError::UnknownEnumType`.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::data::chat::Message;
+//! use minecraft_protocol::data::entity_metadata::{EntityMetadataBuilder, Pose};
+//!
+//! let spawned = EntityMetadataBuilder::new()
+//!     .custom_name(Some(Message::from_str("Guard")))
+//!     .pose(Pose::Standing)
+//!     .build();
+//!
+//! let sneaking = EntityMetadataBuilder::new()
+//!     .custom_name(Some(Message::from_str("Guard")))
+//!     .sneaking(true)
+//!     .pose(Pose::Sneaking)
+//!     .build();
+//!
+//! // Only flags and pose are sent.
+//! assert_eq!(spawned.diff(&sneaking).len(), 2);
+//! ```
+use crate::data::chat::{Message, VisitMessages};
+use crate::data::game::BlockPosition;
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use byteorder::{ReadBytesExt, WriteBytesExt};
+use minecraft_protocol_derive::{Decoder, Encoder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use uuid::Uuid;
+
+/// Index which ends metadata.
+const METADATA_END: u8 = 0xFF;
+
+const FLAGS_INDEX: u8 = 0;
+const AIR_INDEX: u8 = 1;
+const CUSTOM_NAME_INDEX: u8 = 2;
+const CUSTOM_NAME_VISIBLE_INDEX: u8 = 3;
+const SILENT_INDEX: u8 = 4;
+const NO_GRAVITY_INDEX: u8 = 5;
+const POSE_INDEX: u8 = 6;
+
+const ON_FIRE_FLAG: i8 = 0x01;
+const SNEAKING_FLAG: i8 = 0x02;
+const SPRINTING_FLAG: i8 = 0x08;
+const SWIMMING_FLAG: i8 = 0x10;
+const INVISIBLE_FLAG: i8 = 0x20;
+const GLOWING_FLAG: i8 = 0x40;
+const ELYTRA_FLYING_FLAG: i8 = -0x80;
+
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[data_type(with = "var_int")]
+#[repr(i32)]
+pub enum MetadataValue {
+    Byte {
+        value: i8,
+    } = 0,
+    VarInt {
+        #[data_type(with = "var_int")]
+        value: i32,
+    } = 1,
+    Float {
+        value: f32,
+    } = 2,
+    String {
+        value: String,
+    } = 3,
+    Chat {
+        value: Message,
+    } = 4,
+    OptChat {
+        value: Option<Message>,
+    } = 5,
+    Boolean {
+        value: bool,
+    } = 7,
+    Rotation {
+        x: f32,
+        y: f32,
+        z: f32,
+    } = 8,
+    Position {
+        value: BlockPosition,
+    } = 9,
+    OptPosition {
+        value: Option<BlockPosition>,
+    } = 10,
+    Direction {
+        #[data_type(with = "var_int")]
+        value: i32,
+    } = 11,
+    OptUuid {
+        value: Option<Uuid>,
+    } = 12,
+    /// Block state id, 0 means absent.
+    OptBlockState {
+        #[data_type(with = "var_int")]
+        value: i32,
+    } = 13,
+    VillagerData {
+        #[data_type(with = "var_int")]
+        villager_type: i32,
+        #[data_type(with = "var_int")]
+        profession: i32,
+        #[data_type(with = "var_int")]
+        level: i32,
+    } = 16,
+    /// Value plus one, 0 means absent.
+    OptVarInt {
+        #[data_type(with = "var_int")]
+        value: i32,
+    } = 17,
+    Pose {
+        value: Pose,
+    } = 18,
+}
+
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[data_type(with = "var_int")]
+pub enum Pose {
+    Standing,
+    FallFlying,
+    Sleeping,
+    Swimming,
+    SpinAttack,
+    Sneaking,
+    Dying,
+}
+
+/// Metadata entries in index order.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    entries: BTreeMap<u8, MetadataValue>,
+}
+
+impl Metadata {
+    pub fn new() -> Self {
+        Metadata {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    pub fn get(&self, index: u8) -> Option<&MetadataValue> {
+        self.entries.get(&index)
+    }
+
+    /// Sets value at index, `0xFF` can't be used as it ends metadata.
+    pub fn set(&mut self, index: u8, value: MetadataValue) {
+        assert_ne!(index, METADATA_END, "Index 0xFF ends metadata");
+
+        self.entries.insert(index, value);
+    }
+
+    pub fn remove(&mut self, index: u8) -> Option<MetadataValue> {
+        self.entries.remove(&index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &MetadataValue)> {
+        self.entries.iter().map(|(index, value)| (*index, value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns entries of `new` which are missing or different in this metadata.
+    pub fn diff(&self, new: &Metadata) -> Metadata {
+        let entries = new
+            .entries
+            .iter()
+            .filter(|(index, value)| self.entries.get(index) != Some(value))
+            .map(|(index, value)| (*index, value.clone()))
+            .collect();
+
+        Metadata { entries }
+    }
+
+    /// Applies entries of update, e.g. received in metadata packet.
+    pub fn merge(&mut self, update: Metadata) {
+        self.entries.extend(update.entries)
+    }
+}
+
+impl Encoder for Metadata {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        for (index, value) in &self.entries {
+            writer.write_u8(*index)?;
+            value.encode(writer)?;
+        }
+
+        Ok(writer.write_u8(METADATA_END)?)
+    }
+}
+
+impl Decoder for Metadata {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let mut entries = BTreeMap::new();
+
+        loop {
+            let index = reader.read_u8()?;
+
+            if index == METADATA_END {
+                break;
+            }
+
+            entries.insert(index, MetadataValue::decode(reader)?);
+        }
+
+        Ok(Metadata { entries })
+    }
+}
+
+impl VisitMessages for Metadata {
+    fn visit_messages(&mut self, visitor: &mut dyn FnMut(&mut Message)) {
+        for value in self.entries.values_mut() {
+            match value {
+                MetadataValue::Chat { value } => visitor(value),
+                MetadataValue::OptChat { value } => value.visit_messages(visitor),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Builds metadata of indices shared by all entities.
+#[derive(Debug, Default)]
+pub struct EntityMetadataBuilder {
+    flags: Option<i8>,
+    metadata: Metadata,
+}
+
+impl EntityMetadataBuilder {
+    pub fn new() -> Self {
+        EntityMetadataBuilder::default()
+    }
+
+    pub fn on_fire(self, value: bool) -> Self {
+        self.flag(ON_FIRE_FLAG, value)
+    }
+
+    pub fn sneaking(self, value: bool) -> Self {
+        self.flag(SNEAKING_FLAG, value)
+    }
+
+    pub fn sprinting(self, value: bool) -> Self {
+        self.flag(SPRINTING_FLAG, value)
+    }
+
+    pub fn swimming(self, value: bool) -> Self {
+        self.flag(SWIMMING_FLAG, value)
+    }
+
+    pub fn invisible(self, value: bool) -> Self {
+        self.flag(INVISIBLE_FLAG, value)
+    }
+
+    pub fn glowing(self, value: bool) -> Self {
+        self.flag(GLOWING_FLAG, value)
+    }
+
+    pub fn elytra_flying(self, value: bool) -> Self {
+        self.flag(ELYTRA_FLYING_FLAG, value)
+    }
+
+    /// Remaining air in ticks, 300 by default.
+    pub fn air(self, value: i32) -> Self {
+        self.value(AIR_INDEX, MetadataValue::VarInt { value })
+    }
+
+    pub fn custom_name(self, value: Option<Message>) -> Self {
+        self.value(CUSTOM_NAME_INDEX, MetadataValue::OptChat { value })
+    }
+
+    pub fn custom_name_visible(self, value: bool) -> Self {
+        self.value(CUSTOM_NAME_VISIBLE_INDEX, MetadataValue::Boolean { value })
+    }
+
+    pub fn silent(self, value: bool) -> Self {
+        self.value(SILENT_INDEX, MetadataValue::Boolean { value })
+    }
+
+    pub fn no_gravity(self, value: bool) -> Self {
+        self.value(NO_GRAVITY_INDEX, MetadataValue::Boolean { value })
+    }
+
+    pub fn pose(self, value: Pose) -> Self {
+        self.value(POSE_INDEX, MetadataValue::Pose { value })
+    }
+
+    /// Sets value at index specific to entity type.
+    pub fn value(mut self, index: u8, value: MetadataValue) -> Self {
+        self.metadata.set(index, value);
+        self
+    }
+
+    pub fn build(mut self) -> Metadata {
+        if let Some(value) = self.flags {
+            self.metadata
+                .set(FLAGS_INDEX, MetadataValue::Byte { value });
+        }
+
+        self.metadata
+    }
+
+    fn flag(mut self, flag: i8, value: bool) -> Self {
+        let flags = self.flags.get_or_insert(0);
+
+        if value {
+            *flags |= flag;
+        } else {
+            *flags &= !flag;
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::entity_metadata::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let metadata = EntityMetadataBuilder::new()
+            .on_fire(true)
+            .elytra_flying(true)
+            .air(300)
+            .pose(Pose::Swimming)
+            .build();
+
+        let mut vec = Vec::new();
+        metadata.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [0, 0, 0x81, 1, 1, 0xAC, 0x02, 6, 18, 3, METADATA_END]);
+        assert_eq!(Metadata::decode(&mut Cursor::new(vec)).unwrap(), metadata);
+    }
+
+    #[test]
+    fn test_metadata_unsupported_type() {
+        let decode_error = Metadata::decode(&mut Cursor::new(vec![0, 6, 0]))
+            .expect_err("Expected error `UnknownEnumType` because slot values are unsupported");
+
+        match decode_error {
+            DecodeError::UnknownEnumType { type_id } => assert_eq!(type_id, 6),
+            _ => panic!("Expected `UnknownEnumType` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_metadata_diff() {
+        let old = EntityMetadataBuilder::new()
+            .silent(true)
+            .air(300)
+            .value(8, MetadataValue::Float { value: 20.0 })
+            .build();
+
+        let new = EntityMetadataBuilder::new()
+            .silent(true)
+            .air(120)
+            .value(8, MetadataValue::Float { value: 20.0 })
+            .custom_name_visible(true)
+            .build();
+
+        let diff = old.diff(&new);
+
+        assert_eq!(
+            diff.iter().collect::<Vec<_>>(),
+            [
+                (AIR_INDEX, &MetadataValue::VarInt { value: 120 }),
+                (
+                    CUSTOM_NAME_VISIBLE_INDEX,
+                    &MetadataValue::Boolean { value: true }
+                ),
+            ]
+        );
+
+        let mut merged = old;
+        merged.merge(diff);
+
+        assert_eq!(merged, new);
+    }
+
+    #[test]
+    fn test_metadata_visit_messages() {
+        let mut metadata = EntityMetadataBuilder::new()
+            .custom_name(Some(Message::from_str("Guard")))
+            .build();
+
+        let mut count = 0;
+        metadata.visit_messages(&mut |_| count += 1);
+
+        assert_eq!(count, 1);
+    }
+}
@@ -0,0 +1,533 @@
+//! Game data types shared by several protocol versions.
+use crate::data::chat::Message;
+use crate::data::compound_tag;
+use crate::data::units::Ticks;
+use crate::decoder::{Decoder, DecoderReadExt};
+use crate::encoder::{Encoder, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use minecraft_protocol_derive::{Decoder, Encoder, VisitMessages};
+use nbt::CompoundTag;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::ops::BitOr;
+
+/// Sign lines are stored in `Text1`..`Text4` tags.
+const SIGN_TEXT_TAGS: [&str; 4] = ["Text1", "Text2", "Text3", "Text4"];
+
+/// NBT type of `TAG_End`, sent instead of compound tag when there is no tag.
+const TAG_END: u8 = 0;
+
+/// Chat message position used before 1.19.
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub enum MessagePosition {
+    Chat,
+    System,
+    HotBar,
+}
+
+/// Hand used by player to interact.
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[data_type(with = "var_int")]
+pub enum InteractionHand {
+    MainHand,
+    OffHand,
+}
+
+/// Action requested by client with `ClientStatus` packet.
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[data_type(with = "var_int")]
+pub enum ClientStatusAction {
+    /// Respawn after death or leave end credits.
+    PerformRespawn,
+    /// Request statistics, server answers with `Statistics` packet.
+    RequestStats,
+}
+
+#[derive(Encoder, Decoder, VisitMessages, Serialize, Deserialize, Debug, PartialEq)]
+#[data_type(with = "var_int")]
+pub enum TitleAction {
+    SetTitle {
+        text: Message,
+    },
+    SetSubtitle {
+        text: Message,
+    },
+    SetActionBar {
+        text: Message,
+    },
+    SetTimes {
+        fade_in: Ticks,
+        stay: Ticks,
+        fade_out: Ticks,
+    },
+    Hide,
+    Reset,
+}
+
+/// Boss bar flags.
+///
+/// Unknown bits are dropped on decode, so flags added by newer versions don't fail decoding.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct BossBarFlags(u8);
+
+impl BossBarFlags {
+    pub const DARKEN_SKY: BossBarFlags = BossBarFlags(0x01);
+    /// Plays end music.
+    pub const DRAGON_BAR: BossBarFlags = BossBarFlags(0x02);
+    pub const CREATE_FOG: BossBarFlags = BossBarFlags(0x04);
+
+    const ALL: u8 = 0x07;
+
+    pub fn empty() -> Self {
+        BossBarFlags(0)
+    }
+
+    pub fn all() -> Self {
+        BossBarFlags(Self::ALL)
+    }
+
+    /// Returns flags with known bits set, other bits are dropped.
+    pub fn from_bits_truncate(bits: u8) -> Self {
+        BossBarFlags(bits & Self::ALL)
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn contains(self, other: BossBarFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: BossBarFlags) {
+        self.0 |= other.0
+    }
+
+    pub fn remove(&mut self, other: BossBarFlags) {
+        self.0 &= !other.0
+    }
+}
+
+impl BitOr for BossBarFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        BossBarFlags(self.0 | other.0)
+    }
+}
+
+impl Encoder for BossBarFlags {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        Ok(writer.write_u8(self.0)?)
+    }
+}
+
+impl Decoder for BossBarFlags {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        Ok(BossBarFlags::from_bits_truncate(reader.read_u8()?))
+    }
+}
+
+/// Block position packed into 64 bits: 26 bits for x, 26 bits for z and 12 bits for y.
+///
+/// This layout is used since 1.14. Earlier versions pack y before z, packets of those versions
+/// use `#[data_type(with = "legacy_position")]`.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct BlockPosition {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl BlockPosition {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        BlockPosition { x, y, z }
+    }
+
+    /// Packs position as x, z, y, used since 1.14.
+    pub fn to_packed(self) -> Result<i64, EncodeError> {
+        self.check_range()?;
+
+        Ok(((self.x as i64 & 0x3FF_FFFF) << 38)
+            | ((self.z as i64 & 0x3FF_FFFF) << 12)
+            | (self.y as i64 & 0xFFF))
+    }
+
+    pub fn from_packed(value: i64) -> Self {
+        BlockPosition {
+            x: (value >> 38) as i32,
+            y: (value << 52 >> 52) as i32,
+            z: (value << 26 >> 38) as i32,
+        }
+    }
+
+    /// Packs position as x, y, z, used before 1.14.
+    pub fn to_legacy_packed(self) -> Result<i64, EncodeError> {
+        self.check_range()?;
+
+        Ok(((self.x as i64 & 0x3FF_FFFF) << 38)
+            | ((self.y as i64 & 0xFFF) << 26)
+            | (self.z as i64 & 0x3FF_FFFF))
+    }
+
+    pub fn from_legacy_packed(value: i64) -> Self {
+        BlockPosition {
+            x: (value >> 38) as i32,
+            y: (value << 26 >> 52) as i32,
+            z: (value << 38 >> 38) as i32,
+        }
+    }
+
+    fn check_range(self) -> Result<(), EncodeError> {
+        let fits = |value: i32, bits: u32| value >= -(1 << (bits - 1)) && value < 1 << (bits - 1);
+
+        if !fits(self.x, 26) || !fits(self.z, 26) || !fits(self.y, 12) {
+            return Err(EncodeError::PositionOutOfRange {
+                x: self.x,
+                y: self.y,
+                z: self.z,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Encoder for BlockPosition {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        Ok(writer.write_i64::<BigEndian>(self.to_packed()?)?)
+    }
+}
+
+impl Decoder for BlockPosition {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        Ok(BlockPosition::from_packed(reader.read_i64::<BigEndian>()?))
+    }
+}
+
+/// Position of 16x16 column of blocks.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ChunkPosition {
+    pub x: i32,
+    pub z: i32,
+}
+
+impl ChunkPosition {
+    pub fn new(x: i32, z: i32) -> Self {
+        ChunkPosition { x, z }
+    }
+
+    /// Returns chunk containing player or entity coordinates.
+    pub fn containing(x: f64, z: f64) -> Self {
+        ChunkPosition {
+            x: (x.floor() as i32) >> 4,
+            z: (z.floor() as i32) >> 4,
+        }
+    }
+
+    /// Returns chunk containing block.
+    pub fn of_block(position: BlockPosition) -> Self {
+        ChunkPosition {
+            x: position.x >> 4,
+            z: position.z >> 4,
+        }
+    }
+
+    /// Returns the larger of x and z distances, vanilla view distance is measured this way.
+    pub fn distance(&self, other: ChunkPosition) -> u32 {
+        let dx = (self.x as i64 - other.x as i64).unsigned_abs();
+        let dz = (self.z as i64 - other.z as i64).unsigned_abs();
+
+        dx.max(dz) as u32
+    }
+}
+
+/// Item stack in inventory slot, used from 1.13.2 until item components of 1.20.5.
+///
+/// Slot is prefixed with present flag, so empty slot is `None` of `Option<Slot>` and is encoded
+/// like any other optional value. Missing NBT is sent as a single `TAG_End` byte.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Slot {
+    pub item_id: i32,
+    pub count: i8,
+    #[serde(with = "compound_tag::option")]
+    pub nbt: Option<CompoundTag>,
+}
+
+impl Slot {
+    pub fn new(item_id: i32, count: i8) -> Self {
+        Slot {
+            item_id,
+            count,
+            nbt: None,
+        }
+    }
+
+    pub fn with_nbt(item_id: i32, count: i8, nbt: CompoundTag) -> Self {
+        Slot {
+            item_id,
+            count,
+            nbt: Some(nbt),
+        }
+    }
+}
+
+impl Encoder for Slot {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        writer.write_var_i32(self.item_id)?;
+        writer.write_i8(self.count)?;
+
+        match &self.nbt {
+            Some(nbt) => writer.write_compound_tag(nbt),
+            None => Ok(writer.write_u8(TAG_END)?),
+        }
+    }
+}
+
+impl Decoder for Slot {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let item_id = reader.read_var_i32()?;
+        let count = reader.read_i8()?;
+        let tag_type = reader.read_u8()?;
+
+        let nbt = if tag_type == TAG_END {
+            None
+        } else {
+            // Tag type was already read, compound tag decoder needs it back.
+            Some([tag_type].as_slice().chain(reader).read_compound_tag()?)
+        };
+
+        Ok(Slot {
+            item_id,
+            count,
+            nbt,
+        })
+    }
+}
+
+/// Builds sign block entity data as sent in chunk data and block entity data packets.
+pub fn sign_block_entity(
+    position: BlockPosition,
+    lines: &[Message; 4],
+) -> Result<CompoundTag, serde_json::Error> {
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_str("id", "minecraft:sign");
+    compound_tag.insert_i32("x", position.x);
+    compound_tag.insert_i32("y", position.y);
+    compound_tag.insert_i32("z", position.z);
+
+    for (tag, line) in SIGN_TEXT_TAGS.iter().zip(lines) {
+        compound_tag.insert_str(tag, &line.to_json()?);
+    }
+
+    Ok(compound_tag)
+}
+
+/// Reads sign lines from block entity data, `None` if tag isn't valid sign data.
+pub fn sign_lines(compound_tag: &CompoundTag) -> Option<[Message; 4]> {
+    let mut lines = [
+        Message::from_str(""),
+        Message::from_str(""),
+        Message::from_str(""),
+        Message::from_str(""),
+    ];
+
+    for (tag, line) in SIGN_TEXT_TAGS.iter().zip(lines.iter_mut()) {
+        *line = Message::from_json(compound_tag.get_str(tag).ok()?).ok()?;
+    }
+
+    Some(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::game::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_chunk_position_containing() {
+        assert_eq!(
+            ChunkPosition::containing(15.9, 16.0),
+            ChunkPosition::new(0, 1)
+        );
+        assert_eq!(
+            ChunkPosition::containing(-0.5, -16.0),
+            ChunkPosition::new(-1, -1)
+        );
+        assert_eq!(
+            ChunkPosition::of_block(BlockPosition::new(-17, 64, 33)),
+            ChunkPosition::new(-2, 2)
+        );
+    }
+
+    #[test]
+    fn test_boss_bar_flags() {
+        let mut flags = BossBarFlags::DARKEN_SKY | BossBarFlags::CREATE_FOG;
+
+        assert!(flags.contains(BossBarFlags::CREATE_FOG));
+        assert!(!flags.contains(BossBarFlags::DRAGON_BAR));
+
+        flags.remove(BossBarFlags::DARKEN_SKY);
+        flags.insert(BossBarFlags::DRAGON_BAR);
+
+        assert_eq!(flags.bits(), 0x06);
+        assert_eq!(
+            BossBarFlags::decode(&mut Cursor::new(vec![0xFF])).unwrap(),
+            BossBarFlags::all()
+        );
+    }
+
+    #[test]
+    fn test_block_position_round_trip() {
+        let position = BlockPosition::new(-33_554_432, -2048, 33_554_431);
+
+        let mut vec = Vec::new();
+        position.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [0x80, 0, 0, 0x1F, 0xFF, 0xFF, 0xF8, 0]);
+        assert_eq!(
+            BlockPosition::decode(&mut Cursor::new(vec)).unwrap(),
+            position
+        );
+
+        let mut vec = Vec::new();
+        BlockPosition::new(18357644, 831, -20882616)
+            .encode(&mut vec)
+            .unwrap();
+
+        assert_eq!(vec, [0x46, 0x07, 0x63, 0x2C, 0x15, 0xB4, 0x83, 0x3F]);
+    }
+
+    #[test]
+    fn test_block_position_sign_extension() {
+        let position = BlockPosition::new(-1, -1, -1);
+
+        let mut vec = Vec::new();
+        position.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [0xFF; 8]);
+        assert_eq!(
+            BlockPosition::decode(&mut Cursor::new(vec)).unwrap(),
+            position
+        );
+    }
+
+    #[test]
+    fn test_block_position_out_of_range() {
+        let encode_error = BlockPosition::new(0, 2048, 0)
+            .encode(&mut Vec::new())
+            .expect_err("Expected error `PositionOutOfRange` because y doesn't fit 12 bits");
+
+        assert_eq!(
+            encode_error,
+            EncodeError::PositionOutOfRange {
+                x: 0,
+                y: 2048,
+                z: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_slot_round_trip() {
+        let mut nbt = CompoundTag::new();
+        nbt.insert_i32("Damage", 3);
+        let slots = [
+            Some(Slot::with_nbt(598, 1, nbt)),
+            Some(Slot::new(1, 64)),
+            None,
+        ];
+
+        let mut vec = Vec::new();
+
+        for slot in &slots {
+            slot.encode(&mut vec).unwrap();
+        }
+
+        assert_eq!(&vec[vec.len() - 5..], [1, 1, 64, 0, 0]);
+
+        let mut reader = vec.as_slice();
+        let damaged = Option::<Slot>::decode(&mut reader).unwrap().unwrap();
+
+        assert_eq!(damaged.item_id, 598);
+        assert_eq!(damaged.nbt.unwrap().get_i32("Damage").unwrap(), 3);
+
+        let stone = Option::<Slot>::decode(&mut reader).unwrap().unwrap();
+
+        assert_eq!((stone.item_id, stone.count), (1, 64));
+        assert!(stone.nbt.is_none());
+        assert!(Option::<Slot>::decode(&mut reader).unwrap().is_none());
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_slot_json_round_trip() {
+        let mut nbt = CompoundTag::new();
+        nbt.insert_str("Name", "Sword");
+
+        let json = serde_json::to_string(&Slot::with_nbt(598, 1, nbt)).unwrap();
+        let slot: Slot = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(slot.nbt.unwrap().get_str("Name").unwrap(), "Sword");
+
+        let json = serde_json::to_string(&Slot::new(1, 64)).unwrap();
+
+        assert!(json.ends_with(r#""nbt":null}"#));
+        assert!(serde_json::from_str::<Slot>(&json).unwrap().nbt.is_none());
+    }
+
+    #[test]
+    fn test_legacy_block_position_round_trip() {
+        let position = BlockPosition::new(18357644, 831, -20882616);
+
+        let mut vec = Vec::new();
+        crate::encoder::legacy_position::encode(&position, &mut vec).unwrap();
+
+        assert_eq!(vec, [0x46, 0x07, 0x63, 0x0C, 0xFE, 0xC1, 0x5B, 0x48]);
+        assert_eq!(
+            crate::decoder::legacy_position::decode(&mut vec.as_slice()).unwrap(),
+            position
+        );
+
+        let position = BlockPosition::new(-1, -2048, 33_554_431);
+
+        assert_eq!(
+            BlockPosition::from_legacy_packed(position.to_legacy_packed().unwrap()),
+            position
+        );
+        assert_eq!(
+            BlockPosition::new(0, 2048, 0).to_legacy_packed(),
+            Err(EncodeError::PositionOutOfRange {
+                x: 0,
+                y: 2048,
+                z: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_sign_block_entity() {
+        let lines = [
+            Message::from_str("Welcome"),
+            Message::from_str(""),
+            Message::from_str("to"),
+            Message::from_str("spawn"),
+        ];
+
+        let compound_tag = sign_block_entity(BlockPosition::new(1, 64, -1), &lines).unwrap();
+
+        assert_eq!(compound_tag.get_str("id").unwrap(), "minecraft:sign");
+        assert_eq!(compound_tag.get_i32("z").unwrap(), -1);
+        assert_eq!(compound_tag.get_str("Text3").unwrap(), r#"{"text":"to"}"#);
+        assert_eq!(sign_lines(&compound_tag), Some(lines));
+        assert_eq!(sign_lines(&CompoundTag::new()), None);
+    }
+}
@@ -0,0 +1,144 @@
+//! Namespaced identifiers like `minecraft:story/root`.
+//!
+//! Identifier without namespace belongs to `minecraft` namespace. Namespace may contain only
+//! lowercase letters, digits and `_-.` characters, path may also contain `/`. Vanilla disconnects
+//! peer which sends invalid identifier, so it's checked on both encoding and decoding.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::data::identifier::Identifier;
+//!
+//! let identifier = Identifier::from("story/root");
+//!
+//! assert_eq!(identifier.namespace(), "minecraft");
+//! assert_eq!(identifier.path(), "story/root");
+//! ```
+use crate::decoder::{Decoder, DecoderReadExt};
+use crate::encoder::{Encoder, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use crate::STRING_MAX_LENGTH;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{Read, Write};
+
+pub const DEFAULT_NAMESPACE: &str = "minecraft";
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Identifier(String);
+
+impl Identifier {
+    pub fn new(namespace: &str, path: &str) -> Self {
+        Identifier(format!("{}:{}", namespace, path))
+    }
+
+    pub fn namespace(&self) -> &str {
+        match self.0.split_once(':') {
+            Some((namespace, _)) => namespace,
+            None => DEFAULT_NAMESPACE,
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self.0.split_once(':') {
+            Some((_, path)) => path,
+            None => &self.0,
+        }
+    }
+
+    /// Checks that namespace and path contain only allowed characters.
+    pub fn is_valid(&self) -> bool {
+        let (namespace, path) = match self.0.split_once(':') {
+            Some((namespace, path)) => (namespace, path),
+            None => ("", self.0.as_str()),
+        };
+
+        namespace.bytes().all(is_namespace_char)
+            && path.bytes().all(|c| c == b'/' || is_namespace_char(c))
+    }
+
+    /// Returns identifier as sent over network.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Identifier {
+    fn from(value: &str) -> Self {
+        Identifier(value.to_owned())
+    }
+}
+
+impl From<String> for Identifier {
+    fn from(value: String) -> Self {
+        Identifier(value)
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace(), self.path())
+    }
+}
+
+impl Encoder for Identifier {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        if !self.is_valid() {
+            return Err(EncodeError::InvalidIdentifier {
+                value: self.0.clone(),
+            });
+        }
+
+        writer.write_string(&self.0, STRING_MAX_LENGTH)
+    }
+}
+
+impl Decoder for Identifier {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let identifier = Identifier(reader.read_string(STRING_MAX_LENGTH)?);
+
+        if !identifier.is_valid() {
+            return Err(DecodeError::InvalidIdentifier {
+                value: identifier.0,
+            });
+        }
+
+        Ok(identifier)
+    }
+}
+
+fn is_namespace_char(c: u8) -> bool {
+    matches!(c, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::identifier::*;
+
+    #[test]
+    fn test_is_valid() {
+        assert!(Identifier::from("minecraft:brand").is_valid());
+        assert!(Identifier::from("bungeecord:main").is_valid());
+        assert!(Identifier::from("story/root").is_valid());
+        assert!(!Identifier::from("Minecraft:brand").is_valid());
+        assert!(!Identifier::from("mod/name:brand").is_valid());
+        assert!(!Identifier::from("minecraft:a:b").is_valid());
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let mut vec = Vec::new();
+        vec.write_string("BungeeCord", STRING_MAX_LENGTH).unwrap();
+
+        let decode_error = Identifier::decode(&mut vec.as_slice())
+            .expect_err("Expected error `InvalidIdentifier` because of uppercase letters");
+
+        match decode_error {
+            DecodeError::InvalidIdentifier { value } => assert_eq!(value, "BungeeCord"),
+            _ => panic!("Expected `InvalidIdentifier` but got `{:?}`", decode_error),
+        }
+    }
+}
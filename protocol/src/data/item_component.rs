@@ -0,0 +1,589 @@
+//! Item stack components used since 1.20.5.
+//!
+//! Item NBT was replaced with typed components. Item stack sends only the difference from default
+//! components of its item: components which were added or changed and types of components which
+//! were removed. Each component is keyed by var int type id from the component registry.
+//!
+//! Components don't have length prefix, so stack with component of unsupported type can't be
+//! decoded and `DecodeError::UnknownEnumType` is returned.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::data::item_component::{ComponentType, ItemComponent, ItemStack};
+//! use minecraft_protocol::decoder::Decoder;
+//! use minecraft_protocol::encoder::Encoder;
+//!
+//! let mut item_stack = ItemStack::new(830, 1);
+//! item_stack.components.add(ItemComponent::Damage(12));
+//! item_stack.components.remove(ComponentType::Enchantments);
+//!
+//! let mut vec = Vec::new();
+//! item_stack.encode(&mut vec).unwrap();
+//!
+//! let decoded = ItemStack::decode(&mut vec.as_slice()).unwrap();
+//! assert_eq!(decoded.components.removed, [ComponentType::Enchantments]);
+//! ```
+use crate::data::compound_tag;
+use crate::decoder::{read_length, Decoder, DecoderReadExt};
+use crate::encoder::{write_length, Encoder, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use minecraft_protocol_derive::{Decoder, Encoder};
+use nbt::CompoundTag;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+const NBT_STRING_TYPE: u8 = 8;
+const NBT_COMPOUND_TYPE: u8 = 10;
+
+/// Type ids of component registry.
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[data_type(with = "var_int")]
+pub enum ComponentType {
+    CustomData = 0,
+    MaxStackSize = 1,
+    MaxDamage = 2,
+    Damage = 3,
+    Unbreakable = 4,
+    CustomName = 5,
+    ItemName = 6,
+    Lore = 7,
+    Rarity = 8,
+    Enchantments = 9,
+    CustomModelData = 13,
+    HideAdditionalTooltip = 14,
+    HideTooltip = 15,
+    RepairCost = 16,
+    CreativeSlotLock = 17,
+    EnchantmentGlintOverride = 18,
+    FireResistant = 21,
+    StoredEnchantments = 23,
+    DyedColor = 24,
+    MapId = 26,
+}
+
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[data_type(with = "var_int")]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+/// Text component sent as NBT. Plain text is sent as string tag.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TextComponent {
+    Plain(String),
+    Compound(#[serde(with = "compound_tag")] CompoundTag),
+}
+
+impl Encoder for TextComponent {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            TextComponent::Plain(text) => {
+                writer.write_u8(NBT_STRING_TYPE)?;
+                writer.write_u16::<BigEndian>(text.len() as u16)?;
+                writer.write_all(text.as_bytes())?;
+
+                Ok(())
+            }
+            TextComponent::Compound(compound_tag) => {
+                write_nameless_compound_tag(writer, compound_tag)
+            }
+        }
+    }
+}
+
+impl Decoder for TextComponent {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        match reader.read_u8()? {
+            NBT_STRING_TYPE => {
+                let length = reader.read_u16::<BigEndian>()?;
+                let mut buf = vec![0; length as usize];
+                reader.read_exact(&mut buf)?;
+
+                Ok(TextComponent::Plain(String::from_utf8(buf)?))
+            }
+            NBT_COMPOUND_TYPE => Ok(TextComponent::Compound(read_nameless_compound_tag(reader)?)),
+            type_id => Err(DecodeError::UnknownEnumType {
+                type_id: type_id as usize,
+            }),
+        }
+    }
+}
+
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct EnchantmentLevel {
+    #[data_type(with = "var_int")]
+    pub enchantment: i32,
+    #[data_type(with = "var_int")]
+    pub level: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct Enchantments {
+    pub levels: Vec<EnchantmentLevel>,
+    pub show_in_tooltip: bool,
+}
+
+impl Encoder for Enchantments {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        write_length(writer, self.levels.len())?;
+
+        for level in &self.levels {
+            level.encode(writer)?;
+        }
+
+        writer.write_bool(self.show_in_tooltip)
+    }
+}
+
+impl Decoder for Enchantments {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let length = read_length(reader)?;
+        let mut levels = Vec::new();
+
+        for _ in 0..length {
+            levels.push(EnchantmentLevel::decode(reader)?);
+        }
+
+        let show_in_tooltip = reader.read_bool()?;
+
+        Ok(Enchantments {
+            levels,
+            show_in_tooltip,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ItemComponent {
+    CustomData(#[serde(with = "compound_tag")] CompoundTag),
+    MaxStackSize(i32),
+    MaxDamage(i32),
+    Damage(i32),
+    Unbreakable { show_in_tooltip: bool },
+    CustomName(TextComponent),
+    ItemName(TextComponent),
+    Lore(Vec<TextComponent>),
+    Rarity(Rarity),
+    Enchantments(Enchantments),
+    CustomModelData(i32),
+    HideAdditionalTooltip,
+    HideTooltip,
+    RepairCost(i32),
+    CreativeSlotLock,
+    EnchantmentGlintOverride(bool),
+    FireResistant,
+    StoredEnchantments(Enchantments),
+    DyedColor { color: i32, show_in_tooltip: bool },
+    MapId(i32),
+}
+
+impl ItemComponent {
+    pub fn component_type(&self) -> ComponentType {
+        match self {
+            ItemComponent::CustomData(_) => ComponentType::CustomData,
+            ItemComponent::MaxStackSize(_) => ComponentType::MaxStackSize,
+            ItemComponent::MaxDamage(_) => ComponentType::MaxDamage,
+            ItemComponent::Damage(_) => ComponentType::Damage,
+            ItemComponent::Unbreakable { .. } => ComponentType::Unbreakable,
+            ItemComponent::CustomName(_) => ComponentType::CustomName,
+            ItemComponent::ItemName(_) => ComponentType::ItemName,
+            ItemComponent::Lore(_) => ComponentType::Lore,
+            ItemComponent::Rarity(_) => ComponentType::Rarity,
+            ItemComponent::Enchantments(_) => ComponentType::Enchantments,
+            ItemComponent::CustomModelData(_) => ComponentType::CustomModelData,
+            ItemComponent::HideAdditionalTooltip => ComponentType::HideAdditionalTooltip,
+            ItemComponent::HideTooltip => ComponentType::HideTooltip,
+            ItemComponent::RepairCost(_) => ComponentType::RepairCost,
+            ItemComponent::CreativeSlotLock => ComponentType::CreativeSlotLock,
+            ItemComponent::EnchantmentGlintOverride(_) => ComponentType::EnchantmentGlintOverride,
+            ItemComponent::FireResistant => ComponentType::FireResistant,
+            ItemComponent::StoredEnchantments(_) => ComponentType::StoredEnchantments,
+            ItemComponent::DyedColor { .. } => ComponentType::DyedColor,
+            ItemComponent::MapId(_) => ComponentType::MapId,
+        }
+    }
+}
+
+impl Encoder for ItemComponent {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        self.component_type().encode(writer)?;
+
+        match self {
+            ItemComponent::CustomData(compound_tag) => {
+                write_nameless_compound_tag(writer, compound_tag)
+            }
+            ItemComponent::MaxStackSize(value)
+            | ItemComponent::MaxDamage(value)
+            | ItemComponent::Damage(value)
+            | ItemComponent::CustomModelData(value)
+            | ItemComponent::RepairCost(value)
+            | ItemComponent::MapId(value) => writer.write_var_i32(*value),
+            ItemComponent::Unbreakable { show_in_tooltip } => writer.write_bool(*show_in_tooltip),
+            ItemComponent::CustomName(text) | ItemComponent::ItemName(text) => text.encode(writer),
+            ItemComponent::Lore(lines) => {
+                write_length(writer, lines.len())?;
+
+                for line in lines {
+                    line.encode(writer)?;
+                }
+
+                Ok(())
+            }
+            ItemComponent::Rarity(rarity) => rarity.encode(writer),
+            ItemComponent::Enchantments(enchantments)
+            | ItemComponent::StoredEnchantments(enchantments) => enchantments.encode(writer),
+            ItemComponent::HideAdditionalTooltip
+            | ItemComponent::HideTooltip
+            | ItemComponent::CreativeSlotLock
+            | ItemComponent::FireResistant => Ok(()),
+            ItemComponent::EnchantmentGlintOverride(value) => writer.write_bool(*value),
+            ItemComponent::DyedColor {
+                color,
+                show_in_tooltip,
+            } => {
+                writer.write_i32::<BigEndian>(*color)?;
+                writer.write_bool(*show_in_tooltip)
+            }
+        }
+    }
+}
+
+impl Decoder for ItemComponent {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let component = match ComponentType::decode(reader)? {
+            ComponentType::CustomData => match reader.read_u8()? {
+                NBT_COMPOUND_TYPE => ItemComponent::CustomData(read_nameless_compound_tag(reader)?),
+                type_id => {
+                    return Err(DecodeError::UnknownEnumType {
+                        type_id: type_id as usize,
+                    })
+                }
+            },
+            ComponentType::MaxStackSize => ItemComponent::MaxStackSize(reader.read_var_i32()?),
+            ComponentType::MaxDamage => ItemComponent::MaxDamage(reader.read_var_i32()?),
+            ComponentType::Damage => ItemComponent::Damage(reader.read_var_i32()?),
+            ComponentType::Unbreakable => ItemComponent::Unbreakable {
+                show_in_tooltip: reader.read_bool()?,
+            },
+            ComponentType::CustomName => ItemComponent::CustomName(TextComponent::decode(reader)?),
+            ComponentType::ItemName => ItemComponent::ItemName(TextComponent::decode(reader)?),
+            ComponentType::Lore => {
+                let length = read_length(reader)?;
+                let mut lines = Vec::new();
+
+                for _ in 0..length {
+                    lines.push(TextComponent::decode(reader)?);
+                }
+
+                ItemComponent::Lore(lines)
+            }
+            ComponentType::Rarity => ItemComponent::Rarity(Rarity::decode(reader)?),
+            ComponentType::Enchantments => {
+                ItemComponent::Enchantments(Enchantments::decode(reader)?)
+            }
+            ComponentType::CustomModelData => {
+                ItemComponent::CustomModelData(reader.read_var_i32()?)
+            }
+            ComponentType::HideAdditionalTooltip => ItemComponent::HideAdditionalTooltip,
+            ComponentType::HideTooltip => ItemComponent::HideTooltip,
+            ComponentType::RepairCost => ItemComponent::RepairCost(reader.read_var_i32()?),
+            ComponentType::CreativeSlotLock => ItemComponent::CreativeSlotLock,
+            ComponentType::EnchantmentGlintOverride => {
+                ItemComponent::EnchantmentGlintOverride(reader.read_bool()?)
+            }
+            ComponentType::FireResistant => ItemComponent::FireResistant,
+            ComponentType::StoredEnchantments => {
+                ItemComponent::StoredEnchantments(Enchantments::decode(reader)?)
+            }
+            ComponentType::DyedColor => ItemComponent::DyedColor {
+                color: reader.read_i32::<BigEndian>()?,
+                show_in_tooltip: reader.read_bool()?,
+            },
+            ComponentType::MapId => ItemComponent::MapId(reader.read_var_i32()?),
+        };
+
+        Ok(component)
+    }
+}
+
+/// Difference between item stack components and default components of its item.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ItemComponents {
+    /// Components which were added or changed.
+    pub added: Vec<ItemComponent>,
+    /// Default components which were removed.
+    pub removed: Vec<ComponentType>,
+}
+
+impl ItemComponents {
+    pub fn get(&self, component_type: ComponentType) -> Option<&ItemComponent> {
+        self.added
+            .iter()
+            .find(|component| component.component_type() == component_type)
+    }
+
+    /// Adds component replacing component of the same type.
+    pub fn add(&mut self, component: ItemComponent) {
+        let component_type = component.component_type();
+
+        self.added.retain(|c| c.component_type() != component_type);
+        self.removed.retain(|t| *t != component_type);
+        self.added.push(component);
+    }
+
+    /// Removes component, including default component of the item.
+    pub fn remove(&mut self, component_type: ComponentType) {
+        self.added.retain(|c| c.component_type() != component_type);
+
+        if !self.removed.contains(&component_type) {
+            self.removed.push(component_type);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl Encoder for ItemComponents {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        write_length(writer, self.added.len())?;
+        write_length(writer, self.removed.len())?;
+
+        for component in &self.added {
+            component.encode(writer)?;
+        }
+
+        for component_type in &self.removed {
+            component_type.encode(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Decoder for ItemComponents {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let added_length = reader.read_var_i32()? as usize;
+        let removed_length = reader.read_var_i32()? as usize;
+
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+
+        for _ in 0..added_length {
+            added.push(ItemComponent::decode(reader)?);
+        }
+
+        for _ in 0..removed_length {
+            removed.push(ComponentType::decode(reader)?);
+        }
+
+        Ok(ItemComponents { added, removed })
+    }
+}
+
+/// Item stack in slot. Empty slot is stack with zero count.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ItemStack {
+    pub count: i32,
+    pub item_id: i32,
+    pub components: ItemComponents,
+}
+
+impl ItemStack {
+    pub fn new(item_id: i32, count: i32) -> Self {
+        ItemStack {
+            count,
+            item_id,
+            components: ItemComponents::default(),
+        }
+    }
+
+    pub fn empty() -> Self {
+        ItemStack::new(0, 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count <= 0
+    }
+}
+
+impl Encoder for ItemStack {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        if self.is_empty() {
+            return writer.write_var_i32(0);
+        }
+
+        writer.write_var_i32(self.count)?;
+        writer.write_var_i32(self.item_id)?;
+        self.components.encode(writer)
+    }
+}
+
+impl Decoder for ItemStack {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let count = reader.read_var_i32()?;
+
+        if count <= 0 {
+            return Ok(ItemStack::empty());
+        }
+
+        let item_id = reader.read_var_i32()?;
+        let components = ItemComponents::decode(reader)?;
+
+        Ok(ItemStack {
+            count,
+            item_id,
+            components,
+        })
+    }
+}
+
+/// Writes compound tag without root name as sent over network since 1.20.2.
+fn write_nameless_compound_tag<W: Write>(
+    writer: &mut W,
+    compound_tag: &CompoundTag,
+) -> Result<(), EncodeError> {
+    let mut buf = Vec::new();
+    buf.write_compound_tag(compound_tag)?;
+
+    let name_length = u16::from_be_bytes([buf[1], buf[2]]) as usize;
+
+    writer.write_u8(NBT_COMPOUND_TYPE)?;
+    writer.write_all(&buf[3 + name_length..])?;
+
+    Ok(())
+}
+
+/// Reads compound tag without root name. Tag type is expected to be already read.
+fn read_nameless_compound_tag<R: Read>(reader: &mut R) -> Result<CompoundTag, DecodeError> {
+    let header = [NBT_COMPOUND_TYPE, 0, 0];
+
+    header.as_ref().chain(reader).read_compound_tag()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::item_component::*;
+
+    fn round_trip(item_stack: &ItemStack) -> (Vec<u8>, ItemStack) {
+        let mut vec = Vec::new();
+        item_stack.encode(&mut vec).unwrap();
+
+        let decoded = ItemStack::decode(&mut vec.as_slice()).unwrap();
+
+        (vec, decoded)
+    }
+
+    #[test]
+    fn test_empty_item_stack() {
+        let (vec, decoded) = round_trip(&ItemStack::empty());
+
+        assert_eq!(vec, [0]);
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn test_item_stack_encode() {
+        let mut item_stack = ItemStack::new(830, 2);
+        item_stack.components.add(ItemComponent::Damage(300));
+        item_stack.components.remove(ComponentType::Rarity);
+
+        let (vec, _) = round_trip(&item_stack);
+
+        assert_eq!(vec, [2, 0xBE, 0x06, 1, 1, 3, 0xAC, 0x02, 8]);
+    }
+
+    #[test]
+    fn test_item_stack_round_trip() {
+        let mut custom_data = CompoundTag::new();
+        custom_data.insert_str("id", "sword");
+
+        let mut item_stack = ItemStack::new(830, 1);
+        item_stack
+            .components
+            .add(ItemComponent::CustomData(custom_data));
+        item_stack
+            .components
+            .add(ItemComponent::CustomName(TextComponent::Plain(
+                String::from("Excalibur"),
+            )));
+        item_stack
+            .components
+            .add(ItemComponent::Enchantments(Enchantments {
+                levels: vec![EnchantmentLevel {
+                    enchantment: 12,
+                    level: 5,
+                }],
+                show_in_tooltip: true,
+            }));
+        item_stack.components.add(ItemComponent::DyedColor {
+            color: 0xFF5050,
+            show_in_tooltip: false,
+        });
+
+        let (_, decoded) = round_trip(&item_stack);
+
+        assert_eq!(decoded.count, 1);
+        assert_eq!(decoded.item_id, 830);
+        assert_eq!(decoded.components.added.len(), 4);
+
+        match decoded.components.get(ComponentType::CustomData) {
+            Some(ItemComponent::CustomData(custom_data)) => {
+                assert_eq!(custom_data.get_str("id").unwrap(), "sword")
+            }
+            _ => panic!("Expected custom data component"),
+        }
+
+        match decoded.components.get(ComponentType::CustomName) {
+            Some(ItemComponent::CustomName(TextComponent::Plain(name))) => {
+                assert_eq!(name, "Excalibur")
+            }
+            _ => panic!("Expected custom name component"),
+        }
+
+        match decoded.components.get(ComponentType::DyedColor) {
+            Some(ItemComponent::DyedColor { color, .. }) => assert_eq!(*color, 0xFF5050),
+            _ => panic!("Expected dyed color component"),
+        }
+    }
+
+    #[test]
+    fn test_add_replaces_removed() {
+        let mut components = ItemComponents::default();
+        components.remove(ComponentType::Damage);
+        components.add(ItemComponent::Damage(1));
+        components.add(ItemComponent::Damage(2));
+
+        assert!(components.removed.is_empty());
+        assert_eq!(components.added.len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_component_type() {
+        let mut cursor = [1, 0xBE, 0x06, 1, 0, 10].as_ref();
+
+        let decode_error = ItemStack::decode(&mut cursor)
+            .expect_err("Expected error `UnknownEnumType` because component type is not supported");
+
+        match decode_error {
+            DecodeError::UnknownEnumType { type_id } => assert_eq!(type_id, 10),
+            _ => panic!("Expected `UnknownEnumType` but got `{:?}`", decode_error),
+        }
+    }
+}
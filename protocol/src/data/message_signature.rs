@@ -0,0 +1,153 @@
+//! Signatures sent with chat messages and commands since 1.19.3.
+//!
+//! Player starts chat session with `PlayerSession` packet carrying its profile public key, then
+//! signs every chat message and signable command argument. Signatures are always 256 bytes long.
+//! Verifying them needs `signing` feature, see `data::chat::signing`.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::data::message_signature::{MessageSignature, SIGNATURE_LENGTH};
+//! use minecraft_protocol::encoder::Encoder;
+//!
+//! let signature = MessageSignature([7; SIGNATURE_LENGTH]);
+//!
+//! let mut vec = Vec::new();
+//! Some(signature).encode(&mut vec).unwrap();
+//!
+//! assert_eq!(vec.len(), 1 + SIGNATURE_LENGTH);
+//! ```
+use crate::decoder::{Decoder, DecoderReadExt};
+use crate::encoder::{Encoder, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use minecraft_protocol_derive::{Decoder, Encoder};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+/// Length of message signature in bytes.
+pub const SIGNATURE_LENGTH: usize = 256;
+
+/// Signature of chat message or command argument.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MessageSignature(pub [u8; SIGNATURE_LENGTH]);
+
+impl Encoder for MessageSignature {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        writer.write_all(&self.0)?;
+
+        Ok(())
+    }
+}
+
+impl Decoder for MessageSignature {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let mut buf = [0; SIGNATURE_LENGTH];
+        reader.read_exact(&mut buf)?;
+
+        Ok(MessageSignature(buf))
+    }
+}
+
+impl Serialize for MessageSignature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for MessageSignature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        let length = bytes.len();
+
+        <[u8; SIGNATURE_LENGTH]>::try_from(bytes)
+            .map(MessageSignature)
+            .map_err(|_| de::Error::invalid_length(length, &"256 bytes"))
+    }
+}
+
+/// Signature of command argument, e.g. message of `/msg`.
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ArgumentSignature {
+    #[data_type(max_length = 16)]
+    pub name: String,
+    pub signature: MessageSignature,
+}
+
+/// Changes of last seen messages since the previous message sent by client.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct LastSeenUpdate {
+    /// Number of messages received since the previous update.
+    pub offset: i32,
+    /// Bit `n` is set if `n`-th tracked message is included in last seen list.
+    pub acknowledged: u32,
+}
+
+impl Encoder for LastSeenUpdate {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        writer.write_var_i32(self.offset)?;
+        writer.write_all(&self.acknowledged.to_le_bytes()[..3])?;
+
+        Ok(())
+    }
+}
+
+impl Decoder for LastSeenUpdate {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let offset = reader.read_var_i32()?;
+        let mut buf = [0; 4];
+        reader.read_exact(&mut buf[..3])?;
+
+        Ok(LastSeenUpdate {
+            offset,
+            acknowledged: u32::from_le_bytes(buf),
+        })
+    }
+}
+
+/// Profile public key of player signed by Mojang.
+#[derive(Encoder, Decoder, Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ProfilePublicKeyData {
+    /// Milliseconds since epoch.
+    pub expires_at: i64,
+    /// RSA key in X.509 DER format.
+    pub key: Vec<u8>,
+    /// Signature of key and expiry by Mojang.
+    pub key_signature: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::message_signature::*;
+
+    #[test]
+    fn test_last_seen_update_round_trip() {
+        let update = LastSeenUpdate {
+            offset: 3,
+            acknowledged: 0b1000_0000_0000_0000_0101,
+        };
+
+        let mut vec = Vec::new();
+        update.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [3, 0b101, 0, 0b1000]);
+        assert_eq!(LastSeenUpdate::decode(&mut vec.as_slice()).unwrap(), update);
+    }
+
+    #[test]
+    fn test_signature_json_round_trip() {
+        let signature = MessageSignature([7; SIGNATURE_LENGTH]);
+
+        let json = serde_json::to_string(&signature).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<MessageSignature>(&json).unwrap(),
+            signature
+        );
+        assert!(serde_json::from_str::<MessageSignature>("[1, 2, 3]").is_err());
+    }
+}
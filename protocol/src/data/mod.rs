@@ -1,2 +1,14 @@
+pub mod bit_set;
+pub mod block_entity;
 pub mod chat;
+pub mod compound_tag;
+pub mod cookie;
+pub mod entity_metadata;
+pub mod game;
+pub mod identifier;
+pub mod item_component;
+pub mod message_signature;
+#[cfg(feature = "registries")]
+pub mod registry;
 pub mod server_status;
+pub mod units;
@@ -0,0 +1,149 @@
+//! Vanilla registries mapping names to numeric ids sent in packets.
+//!
+//! Registry ids, e.g. entity type of spawned entity or particle type, change between versions as
+//! new entries are added. Tables of each version follow minecraft-data and are enabled with
+//! `registries` feature. Names are stored without `minecraft:` namespace, lookups accept both.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::data::registry::Registries;
+//! use minecraft_protocol::version::ProtocolVersion;
+//!
+//! let v1_14_4 = Registries::of(ProtocolVersion::V1_14_4).unwrap();
+//! let v1_16_5 = Registries::of(ProtocolVersion::V1_16_5).unwrap();
+//!
+//! let zombie = v1_14_4.entity_types.id("minecraft:zombie").unwrap();
+//! assert_eq!(zombie, 93);
+//! assert_eq!(v1_14_4.entity_types.convert(zombie, &v1_16_5.entity_types), Some(102));
+//! ```
+use crate::version::ProtocolVersion;
+use std::convert::TryFrom;
+
+/// Registry with entries in id order.
+#[derive(Debug)]
+pub struct Registry {
+    names: &'static [&'static str],
+}
+
+impl Registry {
+    pub const fn new(names: &'static [&'static str]) -> Self {
+        Registry { names }
+    }
+
+    /// Returns id of entry, name may have `minecraft:` namespace.
+    pub fn id(&self, name: &str) -> Option<i32> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+
+        self.names
+            .iter()
+            .position(|entry| *entry == name)
+            .map(|index| index as i32)
+    }
+
+    /// Returns name of entry without namespace.
+    pub fn name(&self, id: i32) -> Option<&'static str> {
+        usize::try_from(id)
+            .ok()
+            .and_then(|index| self.names.get(index))
+            .copied()
+    }
+
+    /// Returns id of the same entry in registry of other version.
+    pub fn convert(&self, id: i32, other: &Registry) -> Option<i32> {
+        other.id(self.name(id)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns entries with their ids.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &'static str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (index as i32, *name))
+    }
+}
+
+/// Registries of one protocol version.
+#[derive(Debug)]
+pub struct Registries {
+    pub entity_types: Registry,
+    pub particles: Registry,
+}
+
+impl Registries {
+    /// Returns registries of version, `None` if its tables aren't shipped or version is disabled.
+    pub fn of(version: ProtocolVersion) -> Option<&'static Registries> {
+        match version {
+            #[cfg(feature = "v1_14_4")]
+            ProtocolVersion::V1_14_4 => Some(&crate::version::v1_14_4::registry::REGISTRIES),
+            #[cfg(feature = "v1_16_5")]
+            ProtocolVersion::V1_16_5 => Some(&crate::version::v1_16_5::registry::REGISTRIES),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "v1_14_4", feature = "v1_16_5"))]
+mod tests {
+    use crate::data::registry::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_entries_are_unique() {
+        for version in ProtocolVersion::ALL {
+            if let Some(registries) = Registries::of(version) {
+                for registry in [&registries.entity_types, &registries.particles] {
+                    let names: HashSet<_> = registry.iter().map(|(_, name)| name).collect();
+                    assert_eq!(names.len(), registry.len());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let registries = Registries::of(ProtocolVersion::V1_14_4).unwrap();
+
+        assert_eq!(registries.entity_types.len(), 102);
+        assert_eq!(registries.entity_types.id("player"), Some(99));
+        assert_eq!(registries.entity_types.name(0), Some("area_effect_cloud"));
+        assert_eq!(registries.entity_types.name(102), None);
+        assert_eq!(registries.entity_types.name(-1), None);
+        assert_eq!(registries.particles.id("minecraft:flame"), Some(26));
+        assert_eq!(registries.particles.id("minecraft:soul"), None);
+    }
+
+    #[test]
+    fn test_convert() {
+        let v1_14_4 = Registries::of(ProtocolVersion::V1_14_4).unwrap();
+        let v1_16_5 = Registries::of(ProtocolVersion::V1_16_5).unwrap();
+
+        let pig = v1_14_4.entity_types.id("pig").unwrap();
+        assert_eq!(
+            v1_14_4.entity_types.convert(pig, &v1_16_5.entity_types),
+            Some(59)
+        );
+
+        // Renamed in 1.16.
+        let zombie_pigman = v1_14_4.entity_types.id("zombie_pigman").unwrap();
+        assert_eq!(
+            v1_14_4
+                .entity_types
+                .convert(zombie_pigman, &v1_16_5.entity_types),
+            None
+        );
+    }
+
+    #[test]
+    fn test_version_without_tables() {
+        assert!(Registries::of(ProtocolVersion::V1_20_5).is_none());
+    }
+}
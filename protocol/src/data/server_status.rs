@@ -1,13 +1,32 @@
+//! Server status sent in response to server list ping.
+//!
+//! Servers don't agree on the format of `description`: vanilla sends a chat component, but many
+//! servers and proxies send a plain string or an array of components. All forms are accepted and
+//! normalized to `Message`, status is always serialized with a component description.
+//!
+//! Optional fields are omitted from JSON when they are not set, vanilla client doesn't accept
+//! `null` in their place.
 use crate::data::chat::Message;
 use crate::impl_json_encoder_decoder;
-use serde::{Deserialize, Serialize};
+use minecraft_protocol_derive::VisitMessages;
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(VisitMessages, Clone, Serialize, Deserialize, Debug)]
 pub struct ServerStatus {
     pub version: ServerVersion,
     pub players: OnlinePlayers,
+    #[serde(deserialize_with = "deserialize_description")]
     pub description: Message,
+    /// PNG image 64x64 encoded as `data:image/png;base64,...`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<String>,
+    #[serde(
+        rename = "enforcesSecureChat",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub enforces_secure_chat: Option<bool>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -31,3 +50,143 @@ pub struct OnlinePlayer {
 }
 
 impl_json_encoder_decoder!(ServerStatus);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Description {
+    Text(String),
+    Component(Message),
+    Array(Vec<Description>),
+}
+
+impl From<Description> for Message {
+    fn from(description: Description) -> Self {
+        match description {
+            Description::Text(text) => Message::from_str(&text),
+            Description::Component(message) => message,
+            Description::Array(descriptions) => {
+                let mut messages = descriptions.into_iter().map(Message::from);
+
+                match messages.next() {
+                    Some(mut root) => {
+                        root.extra.extend(messages);
+                        root
+                    }
+                    None => Message::from_str(""),
+                }
+            }
+        }
+    }
+}
+
+/// Deserializes description sent as a string, a component or an array of components.
+fn deserialize_description<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Message, D::Error> {
+    Description::deserialize(deserializer).map(Message::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::chat::{Color, MessageBuilder, Payload};
+    use crate::data::server_status::*;
+    use crate::encoder::Encoder;
+
+    #[test]
+    fn test_description_component() {
+        let server_status: ServerStatus =
+            serde_json::from_str(include_str!("../../test/status/vanilla.json")).unwrap();
+
+        assert_eq!(server_status.version.protocol, 498);
+        assert_eq!(server_status.favicon, None);
+        assert_eq!(
+            server_status.description,
+            Message::from_str("A Minecraft Server")
+        );
+    }
+
+    #[test]
+    fn test_description_string() {
+        let server_status: ServerStatus =
+            serde_json::from_str(include_str!("../../test/status/string_description.json"))
+                .unwrap();
+
+        assert_eq!(server_status.players.online, 3);
+        assert_eq!(
+            server_status.favicon.as_deref(),
+            Some("data:image/png;base64,iVBORw0KGgo=")
+        );
+        assert_eq!(server_status.enforces_secure_chat, None);
+        assert_eq!(
+            server_status.description,
+            Message::from_str("\u{a7}aSurvival \u{a7}7| \u{a7}fJoin now!")
+        );
+    }
+
+    #[test]
+    fn test_description_extra() {
+        let server_status: ServerStatus =
+            serde_json::from_str(include_str!("../../test/status/extra_description.json")).unwrap();
+
+        let expected_description = MessageBuilder::builder(Payload::text(""))
+            .then(Payload::text("Hub "))
+            .color(Color::Gold)
+            .bold(true)
+            .then(Payload::text("Network"))
+            .color(Color::Gray)
+            .build();
+
+        assert_eq!(server_status.players.sample.len(), 1);
+        assert_eq!(server_status.description, expected_description);
+    }
+
+    #[test]
+    fn test_description_array() {
+        let server_status: ServerStatus =
+            serde_json::from_str(include_str!("../../test/status/array_description.json")).unwrap();
+
+        let expected_description = MessageBuilder::builder(Payload::text("Welcome to "))
+            .color(Color::Gold)
+            .then(Payload::text("Lobby"))
+            .then(Payload::text("!"))
+            .color(Color::Red)
+            .build();
+
+        assert_eq!(server_status.description, expected_description);
+    }
+
+    #[test]
+    fn test_optional_fields_serialized_when_set() {
+        let json = r#"{"version":{"name":"1.19","protocol":759},"players":{"max":20,"online":0,"sample":[]},"description":{"text":"Hi"},"favicon":"data:image/png;base64,","enforcesSecureChat":true}"#;
+        let server_status: ServerStatus = serde_json::from_str(json).unwrap();
+
+        assert_eq!(server_status.enforces_secure_chat, Some(true));
+        assert_eq!(serde_json::to_string(&server_status).unwrap(), json);
+    }
+
+    #[test]
+    fn test_encoded_json_is_canonical() {
+        let json = r#"{"version":{"name":"1.14.4","protocol":498},"players":{"max":20,"online":0},"description":"Hi"}"#;
+        let server_status: ServerStatus = serde_json::from_str(json).unwrap();
+
+        let mut vec = Vec::new();
+        server_status.encode(&mut vec).unwrap();
+
+        assert_eq!(vec[0], 116);
+        assert_eq!(
+            &vec[1..],
+            br#"{"description":{"text":"Hi"},"players":{"max":20,"online":0,"sample":[]},"version":{"name":"1.14.4","protocol":498}}"#
+        );
+    }
+
+    #[test]
+    fn test_description_serialized_as_component() {
+        let json = r#"{"version":{"name":"1.14.4","protocol":498},"players":{"max":20,"online":0},"description":"Hi"}"#;
+        let server_status: ServerStatus = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&server_status).unwrap(),
+            r#"{"version":{"name":"1.14.4","protocol":498},"players":{"max":20,"online":0,"sample":[]},"description":{"text":"Hi"}}"#
+        );
+    }
+}
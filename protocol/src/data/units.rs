@@ -0,0 +1,194 @@
+//! Fixed-point and time units used by packets.
+//!
+//! Packets send fractional values as scaled integers to save space and durations as number of
+//! game ticks. Types here keep the raw value and convert it to and from blocks or `Duration`, so
+//! scale constants don't leak into packet consumers.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::data::units::{Ticks, Velocity};
+//! use std::time::Duration;
+//!
+//! let velocity = Velocity::from_blocks_per_tick(0.5);
+//!
+//! assert_eq!(velocity.0, 4000);
+//! assert_eq!(velocity.to_blocks_per_tick(), 0.5);
+//!
+//! let fade_in = Ticks::<i32>::from_duration(Duration::from_secs(2));
+//!
+//! assert_eq!(fade_in, Ticks(40));
+//! assert_eq!(fade_in.to_duration(), Some(Duration::from_secs(2)));
+//! ```
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+/// Game ticks in one second when server isn't lagging.
+pub const TICKS_PER_SECOND: u32 = 20;
+
+/// Duration of one game tick.
+pub const TICK: Duration = Duration::from_millis(MILLIS_PER_TICK);
+
+const MILLIS_PER_TICK: u64 = 1000 / TICKS_PER_SECOND as u64;
+
+/// Velocity units in one block per tick.
+const VELOCITY_SCALE: f64 = 8000.0;
+
+/// Fixed-point units in one block.
+const FIXED_POINT_SCALE: f64 = 32.0;
+
+/// Entity velocity along one axis in units of 1/8000 block per tick.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Velocity(pub i16);
+
+impl Velocity {
+    /// Converts velocity, clamping it to the representable range of about ±4.1 blocks per tick.
+    pub fn from_blocks_per_tick(value: f64) -> Self {
+        let scaled = (value * VELOCITY_SCALE).round();
+
+        Velocity(scaled.max(i16::MIN as f64).min(i16::MAX as f64) as i16)
+    }
+
+    pub fn to_blocks_per_tick(self) -> f64 {
+        self.0 as f64 / VELOCITY_SCALE
+    }
+}
+
+impl Encoder for Velocity {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        Ok(writer.write_i16::<BigEndian>(self.0)?)
+    }
+}
+
+impl Decoder for Velocity {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        Ok(Velocity(reader.read_i16::<BigEndian>()?))
+    }
+}
+
+/// Position coordinate in units of 1/32 block.
+///
+/// Used for absolute entity positions before 1.9.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FixedPoint(pub i32);
+
+impl FixedPoint {
+    /// Converts coordinate, clamping it to the representable range.
+    pub fn from_f64(value: f64) -> Self {
+        let scaled = (value * FIXED_POINT_SCALE).floor();
+
+        FixedPoint(scaled.max(i32::MIN as f64).min(i32::MAX as f64) as i32)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / FIXED_POINT_SCALE
+    }
+}
+
+impl Encoder for FixedPoint {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        Ok(writer.write_i32::<BigEndian>(self.0)?)
+    }
+}
+
+impl Decoder for FixedPoint {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        Ok(FixedPoint(reader.read_i32::<BigEndian>()?))
+    }
+}
+
+/// Number of game ticks, e.g. title fade time or world age.
+///
+/// Fields sent as `Int` use `Ticks<i32>`, fields sent as `Long` use `Ticks<i64>` and fields sent
+/// as var int use `#[data_type(with = "var_int_ticks")]`.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Ticks<T = i32>(pub T);
+
+macro_rules! impl_ticks (
+    ($type: ident, $write: ident, $read: ident) => (
+        impl Ticks<$type> {
+            /// Converts duration rounded down to whole ticks, saturating at the maximum value.
+            pub fn from_duration(duration: Duration) -> Self {
+                let ticks = duration.as_millis() / MILLIS_PER_TICK as u128;
+
+                Ticks($type::try_from(ticks).unwrap_or($type::MAX))
+            }
+
+            /// Returns `None` for negative ticks, which some packets use as a flag.
+            pub fn to_duration(self) -> Option<Duration> {
+                let ticks = u64::try_from(self.0).ok()?;
+
+                Some(Duration::from_millis(ticks.saturating_mul(MILLIS_PER_TICK)))
+            }
+        }
+
+        impl Encoder for Ticks<$type> {
+            fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+                Ok(writer.$write::<BigEndian>(self.0)?)
+            }
+        }
+
+        impl Decoder for Ticks<$type> {
+            type Output = Self;
+
+            fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+                Ok(Ticks(reader.$read::<BigEndian>()?))
+            }
+        }
+    );
+);
+
+impl_ticks!(i32, write_i32, read_i32);
+impl_ticks!(i64, write_i64, read_i64);
+
+#[cfg(test)]
+mod tests {
+    use crate::data::units::*;
+
+    #[test]
+    fn test_velocity_from_blocks_per_tick() {
+        assert_eq!(Velocity::from_blocks_per_tick(-0.25), Velocity(-2000));
+        assert_eq!(Velocity::from_blocks_per_tick(100.0), Velocity(i16::MAX));
+        assert_eq!(Velocity::from_blocks_per_tick(-100.0), Velocity(i16::MIN));
+    }
+
+    #[test]
+    fn test_fixed_point_conversion() {
+        assert_eq!(FixedPoint::from_f64(1.5), FixedPoint(48));
+        assert_eq!(FixedPoint::from_f64(-0.01), FixedPoint(-1));
+        assert_eq!(FixedPoint(-48).to_f64(), -1.5);
+    }
+
+    #[test]
+    fn test_ticks_duration_conversion() {
+        assert_eq!(
+            Ticks::<i32>::from_duration(Duration::from_millis(1_099)),
+            Ticks(21)
+        );
+        assert_eq!(
+            Ticks::<i32>::from_duration(Duration::from_secs(u64::MAX)),
+            Ticks(i32::MAX)
+        );
+        assert_eq!(
+            Ticks(24_000i64).to_duration(),
+            Some(Duration::from_secs(1_200))
+        );
+        assert_eq!(Ticks(-6_000i64).to_duration(), None);
+        assert!(Ticks(i64::MAX).to_duration().is_some());
+    }
+}
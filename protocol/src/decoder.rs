@@ -1,9 +1,22 @@
 use crate::error::DecodeError;
+use crate::limits;
 use byteorder::{BigEndian, ReadBytesExt};
 use nbt::CompoundTag;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::hash::Hash;
 use std::io::Read;
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8,
+};
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Bytes of string validated at once.
+const STRING_CHUNK_LENGTH: usize = 1024;
+
 pub trait Decoder {
     type Output;
 
@@ -16,6 +29,14 @@ pub trait DecoderReadExt {
 
     fn read_string(&mut self, max_length: u16) -> Result<String, DecodeError>;
 
+    /// Reads string into buffer, which is cleared first.
+    ///
+    /// Length in bytes is checked against 3 bytes per UTF-16 code unit before anything is read,
+    /// and length in UTF-16 code units once string is read, like vanilla does. Bytes are
+    /// validated in chunks, so buffer grows with data actually received instead of declared
+    /// length. Buffer can be reused for many strings to avoid allocations.
+    fn read_string_into(&mut self, max_length: u16, buf: &mut String) -> Result<(), DecodeError>;
+
     fn read_byte_array(&mut self) -> Result<Vec<u8>, DecodeError>;
 
     fn read_compound_tag(&mut self) -> Result<CompoundTag, DecodeError>;
@@ -25,6 +46,15 @@ pub trait DecoderReadExt {
     fn read_var_i64(&mut self) -> Result<i64, DecodeError>;
 }
 
+/// Reads var int length prefix, returning `DecodeError::NegativeLength` if it's negative.
+pub(crate) fn read_length<R: Read>(reader: &mut R) -> Result<usize, DecodeError> {
+    let length = reader.read_var_i32()?;
+
+    u32::try_from(length)
+        .map(|length| length as usize)
+        .map_err(|_| DecodeError::NegativeLength { length })
+}
+
 macro_rules! read_signed_var_int (
     ($type: ident, $name: ident, $max_bytes: expr) => (
         fn $name(&mut self) -> Result<$type, DecodeError> {
@@ -32,16 +62,16 @@ macro_rules! read_signed_var_int (
             let mut output = 0;
 
             loop {
+                if bytes == $max_bytes {
+                    return Err(DecodeError::VarIntTooLong { max_bytes: $max_bytes })
+                }
+
                 let byte = self.read_u8()?;
                 let value = (byte & 0b01111111) as $type;
 
                 output |= value << 7 * bytes;
                 bytes += 1;
 
-                if bytes > $max_bytes {
-                    return Err(DecodeError::VarIntTooLong { max_bytes: $max_bytes })
-                }
-
                 if (byte & 0b10000000) == 0 {
                     break;
                 }
@@ -62,29 +92,69 @@ impl<R: Read> DecoderReadExt for R {
     }
 
     fn read_string(&mut self, max_length: u16) -> Result<String, DecodeError> {
-        let length = self.read_var_i32()? as usize;
+        let mut buf = String::new();
+        self.read_string_into(max_length, &mut buf)?;
+
+        Ok(buf)
+    }
 
-        if length as u16 > max_length {
+    fn read_string_into(&mut self, max_length: u16, buf: &mut String) -> Result<(), DecodeError> {
+        let length = read_length(self)?;
+
+        if length > crate::string_max_bytes(max_length) {
             return Err(DecodeError::StringTooLong { length, max_length });
         }
 
-        let mut buf = vec![0; length as usize];
-        self.read_exact(&mut buf)?;
+        buf.clear();
+
+        // Incomplete UTF-8 sequence at the end of chunk is moved to the start of the next one.
+        let mut chunk = [0; STRING_CHUNK_LENGTH + 3];
+        let mut pending = 0;
+        let mut remaining = length;
+
+        while remaining > 0 {
+            let read_length = remaining.min(STRING_CHUNK_LENGTH);
+            self.read_exact(&mut chunk[pending..pending + read_length])?;
 
-        Ok(String::from_utf8(buf)?)
+            remaining -= read_length;
+            let filled = pending + read_length;
+
+            match std::str::from_utf8(&chunk[..filled]) {
+                Ok(text) => {
+                    buf.push_str(text);
+                    pending = 0;
+                }
+                Err(e) if e.error_len().is_none() && remaining > 0 => {
+                    let valid = e.valid_up_to();
+                    buf.push_str(std::str::from_utf8(&chunk[..valid]).unwrap_or_default());
+
+                    chunk.copy_within(valid..filled, 0);
+                    pending = filled - valid;
+                }
+                Err(_) => {
+                    // Same bytes fail again, returning `FromUtf8Error`.
+                    String::from_utf8(chunk[..filled].to_vec())?;
+                }
+            }
+        }
+
+        match crate::utf16_length_over(buf, max_length) {
+            Some(length) => Err(DecodeError::StringTooLong { length, max_length }),
+            None => Ok(()),
+        }
     }
 
     fn read_byte_array(&mut self) -> Result<Vec<u8>, DecodeError> {
-        let length = self.read_var_i32()?;
+        let length = read_length(self)?;
 
-        let mut buf = vec![0; length as usize];
+        let mut buf = vec![0; length];
         self.read_exact(&mut buf)?;
 
         Ok(buf)
     }
 
     fn read_compound_tag(&mut self) -> Result<CompoundTag, DecodeError> {
-        Ok(nbt::decode::read_compound_tag(self)?)
+        limits::read_compound_tag(self)
     }
 
     read_signed_var_int!(i32, read_var_i32, 5);
@@ -99,6 +169,14 @@ impl Decoder for u8 {
     }
 }
 
+impl Decoder for i8 {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        Ok(reader.read_i8()?)
+    }
+}
+
 impl Decoder for i16 {
     type Output = Self;
 
@@ -147,6 +225,50 @@ impl Decoder for u64 {
     }
 }
 
+impl Decoder for i128 {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        Ok(reader.read_i128::<BigEndian>()?)
+    }
+}
+
+impl Decoder for u128 {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        Ok(reader.read_u128::<BigEndian>()?)
+    }
+}
+
+/// Non-zero integers are encoded as their value, zero is rejected.
+macro_rules! impl_non_zero_decoder (
+    ($($non_zero: ident: $integer: ty),*) => (
+        $(
+            impl Decoder for $non_zero {
+                type Output = Self;
+
+                fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+                    $non_zero::new(<$integer>::decode(reader)?).ok_or(DecodeError::ZeroValue)
+                }
+            }
+        )*
+    );
+);
+
+impl_non_zero_decoder!(
+    NonZeroU8: u8,
+    NonZeroI8: i8,
+    NonZeroU16: u16,
+    NonZeroI16: i16,
+    NonZeroU32: u32,
+    NonZeroI32: i32,
+    NonZeroU64: u64,
+    NonZeroI64: i64,
+    NonZeroU128: u128,
+    NonZeroI128: i128
+);
+
 impl Decoder for f32 {
     type Output = Self;
 
@@ -167,7 +289,7 @@ impl Decoder for String {
     type Output = Self;
 
     fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
-        Ok(reader.read_string(32_768)?)
+        reader.read_string(32_768)
     }
 }
 
@@ -175,7 +297,30 @@ impl Decoder for bool {
     type Output = Self;
 
     fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
-        Ok(reader.read_bool()?)
+        reader.read_bool()
+    }
+}
+
+impl<T: Decoder<Output = T>> Decoder for Arc<T> {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        Ok(Arc::new(T::decode(reader)?))
+    }
+}
+
+/// Optional value is prefixed with boolean which tells whether value is present.
+///
+/// Fields sent as JSON `null` when absent use `#[data_type(with = "nullable_json")]` instead.
+impl<T: Decoder<Output = T>> Decoder for Option<T> {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        if reader.read_bool()? {
+            Ok(Some(T::decode(reader)?))
+        } else {
+            Ok(None)
+        }
     }
 }
 
@@ -183,7 +328,85 @@ impl Decoder for Vec<u8> {
     type Output = Self;
 
     fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
-        Ok(reader.read_byte_array()?)
+        reader.read_byte_array()
+    }
+}
+
+/// Decoded strings are always owned, so packets with `Cow` fields can both borrow data for
+/// encoding and be decoded.
+impl Decoder for Cow<'_, str> {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        String::decode(reader).map(Cow::Owned)
+    }
+}
+
+impl Decoder for Cow<'_, [u8]> {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        reader.read_byte_array().map(Cow::Owned)
+    }
+}
+
+impl Decoder for Vec<i64> {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let length = read_length(reader)?;
+        let mut vec = Vec::new();
+
+        for _ in 0..length {
+            let value = reader.read_i64::<BigEndian>()?;
+            vec.push(value);
+        }
+
+        Ok(vec)
+    }
+}
+
+/// Map is prefixed with entry count. Value of repeated key replaces the previous one.
+impl<K, V> Decoder for HashMap<K, V>
+where
+    K: Decoder<Output = K> + Eq + Hash,
+    V: Decoder<Output = V>,
+{
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let length = read_length(reader)?;
+        let mut map = HashMap::new();
+
+        for _ in 0..length {
+            let key = K::decode(reader)?;
+            let value = V::decode(reader)?;
+            map.insert(key, value);
+        }
+
+        Ok(map)
+    }
+}
+
+/// Map is prefixed with entry count. Value of repeated key replaces the previous one.
+impl<K, V> Decoder for BTreeMap<K, V>
+where
+    K: Decoder<Output = K> + Ord,
+    V: Decoder<Output = V>,
+{
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let length = read_length(reader)?;
+        let mut map = BTreeMap::new();
+
+        for _ in 0..length {
+            let key = K::decode(reader)?;
+            let value = V::decode(reader)?;
+            map.insert(key, value);
+        }
+
+        Ok(map)
     }
 }
 
@@ -202,7 +425,7 @@ impl Decoder for CompoundTag {
     type Output = Self;
 
     fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
-        Ok(reader.read_compound_tag()?)
+        reader.read_compound_tag()
     }
 }
 
@@ -210,15 +433,7 @@ impl Decoder for Vec<CompoundTag> {
     type Output = Self;
 
     fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
-        let length = reader.read_var_i32()? as usize;
-        let mut vec = Vec::with_capacity(length);
-
-        for _ in 0..length {
-            let compound_tag = reader.read_compound_tag()?;
-            vec.push(compound_tag);
-        }
-
-        Ok(vec)
+        limits::read_compound_tags(reader)
     }
 }
 
@@ -228,7 +443,19 @@ pub mod var_int {
     use std::io::Read;
 
     pub fn decode<R: Read>(reader: &mut R) -> Result<i32, DecodeError> {
-        Ok(reader.read_var_i32()?)
+        reader.read_var_i32()
+    }
+}
+
+/// Ticks sent as var int, e.g. item cooldown.
+pub mod var_int_ticks {
+    use crate::data::units::Ticks;
+    use crate::decoder::DecoderReadExt;
+    use crate::error::DecodeError;
+    use std::io::Read;
+
+    pub fn decode<R: Read>(reader: &mut R) -> Result<Ticks, DecodeError> {
+        Ok(Ticks(reader.read_var_i32()?))
     }
 }
 
@@ -238,7 +465,21 @@ pub mod var_long {
     use std::io::Read;
 
     pub fn decode<R: Read>(reader: &mut R) -> Result<i64, DecodeError> {
-        Ok(reader.read_var_i64()?)
+        reader.read_var_i64()
+    }
+}
+
+/// Block position packed as x, y, z, used before 1.14.
+pub mod legacy_position {
+    use crate::data::game::BlockPosition;
+    use crate::error::DecodeError;
+    use byteorder::{BigEndian, ReadBytesExt};
+    use std::io::Read;
+
+    pub fn decode<R: Read>(reader: &mut R) -> Result<BlockPosition, DecodeError> {
+        Ok(BlockPosition::from_legacy_packed(
+            reader.read_i64::<BigEndian>()?,
+        ))
     }
 }
 
@@ -254,6 +495,65 @@ pub mod rest {
     }
 }
 
+/// Optional JSON value, `null` is decoded as `None`.
+pub mod nullable_json {
+    use crate::error::DecodeError;
+    use crate::limits;
+    use serde::de::DeserializeOwned;
+    use std::io::Read;
+
+    pub fn decode<T: DeserializeOwned, R: Read>(reader: &mut R) -> Result<Option<T>, DecodeError> {
+        let json = limits::read_json(reader)?;
+
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// Values prefixed with var int count.
+pub mod list {
+    use crate::decoder::{read_length, Decoder};
+    use crate::error::DecodeError;
+    use std::io::Read;
+
+    pub fn decode<T: Decoder<Output = T>, R: Read>(reader: &mut R) -> Result<Vec<T>, DecodeError> {
+        let length = read_length(reader)?;
+        // Count is not trusted for allocation, reader ends on malformed count.
+        let mut value = Vec::new();
+
+        for _ in 0..length {
+            value.push(T::decode(reader)?);
+        }
+
+        Ok(value)
+    }
+}
+
+/// Values prefixed with short count, e.g. slots of window before 1.17.
+pub mod short_list {
+    use crate::decoder::Decoder;
+    use crate::error::DecodeError;
+    use byteorder::{BigEndian, ReadBytesExt};
+    use std::io::Read;
+
+    pub fn decode<T: Decoder<Output = T>, R: Read>(reader: &mut R) -> Result<Vec<T>, DecodeError> {
+        let length = reader.read_i16::<BigEndian>()?;
+
+        if length < 0 {
+            return Err(DecodeError::NegativeLength {
+                length: length as i32,
+            });
+        }
+
+        let mut value = Vec::new();
+
+        for _ in 0..length {
+            value.push(T::decode(reader)?);
+        }
+
+        Ok(value)
+    }
+}
+
 pub mod uuid_hyp_str {
     use crate::decoder::DecoderReadExt;
     use crate::error::DecodeError;
@@ -270,8 +570,35 @@ pub mod uuid_hyp_str {
 
 #[cfg(test)]
 mod tests {
-    use crate::decoder::DecoderReadExt;
+    use crate::data::chat::Message;
+    use crate::decoder::{Decoder, DecoderReadExt};
+    use crate::encoder::{Encoder, EncoderWriteExt};
+    use crate::error::DecodeError;
+    use minecraft_protocol_derive::{Decoder, Encoder};
+    use std::collections::{BTreeMap, HashMap};
     use std::io::Cursor;
+    use std::num::{NonZeroI128, NonZeroU32};
+
+    #[derive(Encoder, Decoder, Debug)]
+    struct Statistics {
+        values: HashMap<String, i32>,
+    }
+
+    #[derive(Encoder, Decoder, Debug, PartialEq)]
+    #[data_type(with = "var_int")]
+    #[repr(i32)]
+    enum Particle {
+        Dust { scale: f32 },
+        Flame = 300,
+    }
+
+    #[derive(Encoder, Decoder, Debug, PartialEq)]
+    #[data_type(with = "i32")]
+    #[repr(i32)]
+    enum Effect {
+        DoorOpen = 1005,
+        Record { id: u8 } = 1010,
+    }
 
     #[test]
     fn test_read_variable_i32_2_bytes_value() {
@@ -288,4 +615,302 @@ mod tests {
 
         assert_eq!(value, 2147483647);
     }
+
+    #[test]
+    fn test_read_variable_min_values() {
+        let mut cursor = Cursor::new(vec![0x80, 0x80, 0x80, 0x80, 0x08]);
+        assert_eq!(cursor.read_var_i32().unwrap(), i32::MIN);
+
+        let mut cursor = Cursor::new(vec![
+            0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01,
+        ]);
+        assert_eq!(cursor.read_var_i64().unwrap(), i64::MIN);
+    }
+
+    #[test]
+    fn test_read_var_i32_too_long() {
+        let mut cursor = Cursor::new(vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]);
+
+        let decode_error = cursor
+            .read_var_i32()
+            .expect_err("Expected error `VarIntTooLong` because var int has 6 bytes");
+
+        match decode_error {
+            DecodeError::VarIntTooLong { max_bytes } => assert_eq!(max_bytes, 5),
+            _ => panic!("Expected `VarIntTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_var_i64_too_long() {
+        let mut cursor = Cursor::new(vec![0x80; 11]);
+
+        let decode_error = cursor
+            .read_var_i64()
+            .expect_err("Expected error `VarIntTooLong` because var long has 11 bytes");
+
+        match decode_error {
+            DecodeError::VarIntTooLong { max_bytes } => assert_eq!(max_bytes, 10),
+            _ => panic!("Expected `VarIntTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_byte_array_negative_length() {
+        let mut cursor = Cursor::new(vec![0xff, 0xff, 0xff, 0xff, 0x0f, 1, 2]);
+
+        let decode_error = cursor
+            .read_byte_array()
+            .expect_err("Expected error `NegativeLength` because length prefix is -1");
+
+        match decode_error {
+            DecodeError::NegativeLength { length } => assert_eq!(length, -1),
+            _ => panic!("Expected `NegativeLength` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_string_negative_length() {
+        let mut cursor = Cursor::new(vec![0xff, 0xff, 0xff, 0xff, 0x0f, b'a']);
+
+        let decode_error = cursor
+            .read_string(16)
+            .expect_err("Expected error `NegativeLength` because length prefix is -1");
+
+        match decode_error {
+            DecodeError::NegativeLength { length } => assert_eq!(length, -1),
+            _ => panic!("Expected `NegativeLength` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_i8() {
+        let mut cursor = Cursor::new(vec![0xff]);
+        let value = i8::decode(&mut cursor).unwrap();
+
+        assert_eq!(value, -1);
+    }
+
+    #[test]
+    fn test_read_option() {
+        let mut cursor = Cursor::new(vec![1, 0xff, 0]);
+
+        assert_eq!(Option::<i8>::decode(&mut cursor).unwrap(), Some(-1));
+        assert_eq!(Option::<i8>::decode(&mut cursor).unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_btree_map() {
+        let mut cursor = Cursor::new(vec![2, 2, 0xff, 1, 5]);
+        let value = BTreeMap::<u8, i8>::decode(&mut cursor).unwrap();
+
+        assert_eq!(value.into_iter().collect::<Vec<_>>(), [(1, 5), (2, -1)]);
+    }
+
+    #[test]
+    fn test_map_field_round_trip() {
+        let mut values = HashMap::new();
+        values.insert(String::from("minecraft:jump"), 12);
+        values.insert(String::from("minecraft:deaths"), 1);
+
+        let statistics = Statistics { values };
+
+        let mut vec = Vec::new();
+        statistics.encode(&mut vec).unwrap();
+
+        let decoded = Statistics::decode(&mut Cursor::new(vec)).unwrap();
+
+        assert_eq!(decoded.values, statistics.values);
+    }
+
+    #[test]
+    fn test_var_int_discriminant_round_trip() {
+        let mut vec = Vec::new();
+        Particle::Flame.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [0b10101100, 0b00000010]);
+        assert_eq!(
+            Particle::decode(&mut Cursor::new(vec)).unwrap(),
+            Particle::Flame
+        );
+
+        let mut vec = Vec::new();
+        Particle::Dust { scale: 1.5 }.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [0, 0x3f, 0xc0, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_i32_discriminant_round_trip() {
+        let mut vec = Vec::new();
+        Effect::Record { id: 3 }.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [0, 0, 0x03, 0xf2, 3]);
+        assert_eq!(
+            Effect::decode(&mut Cursor::new(vec)).unwrap(),
+            Effect::Record { id: 3 }
+        );
+
+        let decode_error = Effect::decode(&mut Cursor::new(vec![0, 0, 0x03, 0xec]))
+            .expect_err("Expected error `UnknownEnumType` because effect 1004 is unknown");
+
+        match decode_error {
+            DecodeError::UnknownEnumType { type_id } => assert_eq!(type_id, 1004),
+            _ => panic!("Expected `UnknownEnumType` but got `{:?}`", decode_error),
+        }
+
+        // Negative type id is reported the same way on 32-bit and 64-bit targets.
+        let decode_error = Effect::decode(&mut Cursor::new(vec![0xff, 0xff, 0xff, 0xff]))
+            .expect_err("Expected error `UnknownEnumType` because effect -1 is unknown");
+
+        match decode_error {
+            DecodeError::UnknownEnumType { type_id } => assert_eq!(type_id, u32::MAX as usize),
+            _ => panic!("Expected `UnknownEnumType` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[derive(Encoder, Decoder, Debug)]
+    struct Motd {
+        #[data_type(with = "nullable_json")]
+        text: Option<Message>,
+        icon: Option<Vec<u8>>,
+    }
+
+    #[test]
+    fn test_optional_fields_round_trip() {
+        let motd = Motd {
+            text: None,
+            icon: Some(vec![0x89]),
+        };
+
+        let mut vec = Vec::new();
+        motd.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [4, b'n', b'u', b'l', b'l', 1, 1, 0x89]);
+
+        let decoded = Motd::decode(&mut Cursor::new(vec)).unwrap();
+
+        assert_eq!(decoded.text, None);
+        assert_eq!(decoded.icon, Some(vec![0x89]));
+
+        let motd = Motd {
+            text: Some(Message::from_str("Hi")),
+            icon: None,
+        };
+
+        let mut vec = Vec::new();
+        motd.encode(&mut vec).unwrap();
+
+        let decoded = Motd::decode(&mut Cursor::new(vec)).unwrap();
+
+        assert_eq!(decoded.text, Some(Message::from_str("Hi")));
+        assert_eq!(decoded.icon, None);
+    }
+
+    #[test]
+    fn test_read_string_across_chunks() {
+        // Character is split at chunk boundary.
+        let text = format!("a{}", "ё".repeat(600));
+
+        let mut vec = Vec::new();
+        vec.write_string(&text, 2048).unwrap();
+
+        let mut buf = String::from("previous");
+        Cursor::new(vec).read_string_into(2048, &mut buf).unwrap();
+
+        assert_eq!(buf, text);
+    }
+
+    #[test]
+    fn test_read_string_too_long_before_data() {
+        let mut cursor = Cursor::new(vec![0xFF, 0xFF, 0x01]);
+
+        let decode_error = cursor
+            .read_string(256)
+            .expect_err("Expected error `StringTooLong` because declared length is over max");
+
+        match decode_error {
+            DecodeError::StringTooLong { length, max_length } => {
+                assert_eq!(length, 32_767);
+                assert_eq!(max_length, 256);
+            }
+            _ => panic!("Expected `StringTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_string_invalid_utf8() {
+        let mut cursor = Cursor::new(vec![2, b'a', 0xC3]);
+
+        let decode_error = cursor
+            .read_string(16)
+            .expect_err("Expected error `Utf8Error` because string ends inside a character");
+
+        match decode_error {
+            DecodeError::Utf8Error { .. } => {}
+            _ => panic!("Expected `Utf8Error` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_non_zero() {
+        let mut cursor = Cursor::new(vec![0, 0, 1, 0]);
+        let value = NonZeroU32::decode(&mut cursor).unwrap();
+
+        assert_eq!(value.get(), 256);
+    }
+
+    #[test]
+    fn test_read_non_zero_zero_value() {
+        let mut cursor = Cursor::new(vec![0; 16]);
+        let decode_error = NonZeroI128::decode(&mut cursor)
+            .expect_err("Expected error `ZeroValue` because value is zero");
+
+        match decode_error {
+            DecodeError::ZeroValue => {}
+            _ => panic!("Expected `ZeroValue` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_u128() {
+        let mut cursor = Cursor::new(vec![0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2]);
+        let value = u128::decode(&mut cursor).unwrap();
+
+        assert_eq!(value, (1 << 64) | 2);
+    }
+
+    #[test]
+    fn test_read_i128() {
+        let mut cursor = Cursor::new(vec![0xff; 16]);
+        let value = i128::decode(&mut cursor).unwrap();
+
+        assert_eq!(value, -1);
+    }
+
+    #[test]
+    fn test_read_f32() {
+        let mut cursor = Cursor::new(vec![0x3f, 0xc0, 0x00, 0x00]);
+        let value = f32::decode(&mut cursor).unwrap();
+
+        assert_eq!(value, 1.5);
+    }
+
+    #[test]
+    fn test_read_f64() {
+        let mut cursor = Cursor::new(vec![0xc0, 0x04, 0, 0, 0, 0, 0, 0]);
+        let value = f64::decode(&mut cursor).unwrap();
+
+        assert_eq!(value, -2.5);
+    }
+
+    #[test]
+    fn test_read_i64_array() {
+        let mut cursor = Cursor::new(vec![
+            2, 0, 0, 0, 0, 0, 0, 0, 1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ]);
+        let value = Vec::<i64>::decode(&mut cursor).unwrap();
+
+        assert_eq!(value, vec![1, -1]);
+    }
 }
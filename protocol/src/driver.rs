@@ -0,0 +1,657 @@
+//! Sans-io protocol driver.
+//!
+//! `ProtocolDriver` doesn't own a socket. Bytes received from the transport are passed to
+//! `receive` and turned into events by `poll_event`, commands passed to `handle` append bytes
+//! which should be sent to `output`. This lets the crate run over any transport, e.g. io_uring or
+//! a QUIC stream, and sync or async connections are thin wrappers which only move bytes.
+//!
+//! Driver follows the connection state: handshake switches to status or login, `LoginSuccess`
+//! switches to game and `SetCompression` enables compression. These packets are recognized in
+//! both directions, so the same driver works on client and server side.
+//!
+//! Server side driver also records `ClientFingerprint` of the client.
+//!
+//! `TimeoutPolicy` limits how long peer may stay in handshake, status and login states, vanilla
+//! server disconnects clients which stall there. Driver doesn't have timers, `check_timeout` is
+//! called by the transport, e.g. when read times out.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::driver::{DriverCommand, DriverEvent, ProtocolDriver, Side};
+//! use minecraft_protocol::packet::RawPacket;
+//!
+//! let mut client = ProtocolDriver::new(Side::Client);
+//! let mut server = ProtocolDriver::new(Side::Server);
+//!
+//! let ping = RawPacket::new(0x01, vec![0; 8]);
+//! client.handle(DriverCommand::SendPacket(ping.clone())).unwrap();
+//!
+//! // Move bytes over any transport.
+//! let bytes = client.take_output();
+//! server.receive(&bytes[..4]);
+//! assert!(matches!(server.poll_event(), Ok(DriverEvent::NeedMoreData)));
+//!
+//! server.receive(&bytes[4..]);
+//! assert!(matches!(server.poll_event(), Ok(DriverEvent::Packet(packet)) if packet == ping));
+//! ```
+use crate::compression::CompressedRawPacket;
+use crate::decoder::DecoderReadExt;
+use crate::error::{DecodeError, EncodeError, TimeoutError};
+use crate::fingerprint::ClientFingerprint;
+use crate::packet::{RawPacket, PACKET_MAX_LENGTH};
+use crate::STRING_MAX_LENGTH;
+use byteorder::{BigEndian, ReadBytesExt};
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::mem;
+use std::time::{Duration, Instant};
+
+const HANDSHAKE_ID: i32 = 0x00;
+const LOGIN_SUCCESS_ID: i32 = 0x02;
+const SET_COMPRESSION_ID: i32 = 0x03;
+
+/// Time vanilla server allows to complete login.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Side of the connection driven by `ProtocolDriver`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Side {
+    Client,
+    Server,
+}
+
+/// Connection state which determines packet set.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ProtocolState {
+    Handshake,
+    Status,
+    Login,
+    Game,
+}
+
+/// Time allowed in each connection state before game, `None` means no limit.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TimeoutPolicy {
+    pub handshake: Option<Duration>,
+    pub status: Option<Duration>,
+    pub login: Option<Duration>,
+}
+
+impl TimeoutPolicy {
+    /// Policy without any limits, used by driver unless other policy is set.
+    pub fn none() -> Self {
+        TimeoutPolicy {
+            handshake: None,
+            status: None,
+            login: None,
+        }
+    }
+
+    /// Returns time allowed in state, game state is never limited.
+    pub fn limit(&self, state: ProtocolState) -> Option<Duration> {
+        match state {
+            ProtocolState::Handshake => self.handshake,
+            ProtocolState::Status => self.status,
+            ProtocolState::Login => self.login,
+            ProtocolState::Game => None,
+        }
+    }
+}
+
+impl Default for TimeoutPolicy {
+    /// Limits of vanilla server.
+    fn default() -> Self {
+        TimeoutPolicy {
+            handshake: Some(DEFAULT_TIMEOUT),
+            status: Some(DEFAULT_TIMEOUT),
+            login: Some(DEFAULT_TIMEOUT),
+        }
+    }
+}
+
+/// Event produced from received bytes.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DriverEvent {
+    /// Received bytes don't contain complete frame.
+    NeedMoreData,
+    /// Frame received from peer.
+    Packet(RawPacket),
+    /// State changed by received or sent packet.
+    StateChanged(ProtocolState),
+}
+
+/// Command which produces bytes to send.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum DriverCommand {
+    SendPacket(RawPacket),
+}
+
+/// Frames connection bytes without doing any IO.
+#[derive(Debug)]
+pub struct ProtocolDriver {
+    side: Side,
+    state: ProtocolState,
+    compression_threshold: Option<usize>,
+    max_packet_length: usize,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    events: VecDeque<DriverEvent>,
+    fingerprint: Option<ClientFingerprint>,
+    received_at: Option<Instant>,
+    timeout_policy: TimeoutPolicy,
+    state_entered_at: Instant,
+}
+
+impl ProtocolDriver {
+    pub fn new(side: Side) -> Self {
+        ProtocolDriver {
+            side,
+            state: ProtocolState::Handshake,
+            compression_threshold: None,
+            max_packet_length: PACKET_MAX_LENGTH,
+            input: Vec::new(),
+            output: Vec::new(),
+            events: VecDeque::new(),
+            fingerprint: (side == Side::Server).then(ClientFingerprint::new),
+            received_at: None,
+            timeout_policy: TimeoutPolicy::none(),
+            state_entered_at: Instant::now(),
+        }
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn state(&self) -> ProtocolState {
+        self.state
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.compression_threshold
+    }
+
+    /// Compresses frames of at least threshold length from now on.
+    ///
+    /// Called when `SetCompression` is sent or received, so it's needed only when compression
+    /// was negotiated before the driver took over the connection.
+    pub fn enable_compression(&mut self, threshold: usize) {
+        self.compression_threshold = Some(threshold);
+    }
+
+    pub fn disable_compression(&mut self) {
+        self.compression_threshold = None;
+    }
+
+    /// Returns max frame length without length prefix.
+    pub fn max_packet_length(&self) -> usize {
+        self.max_packet_length
+    }
+
+    /// Limits length of received and sent frames, `PACKET_MAX_LENGTH` by default.
+    pub fn set_max_packet_length(&mut self, max_packet_length: usize) {
+        self.max_packet_length = max_packet_length;
+    }
+
+    pub fn timeout_policy(&self) -> TimeoutPolicy {
+        self.timeout_policy
+    }
+
+    pub fn set_timeout_policy(&mut self, timeout_policy: TimeoutPolicy) {
+        self.timeout_policy = timeout_policy;
+    }
+
+    /// Returns time when current state times out, `None` if it's not limited.
+    pub fn deadline(&self) -> Option<Instant> {
+        let limit = self.timeout_policy.limit(self.state)?;
+
+        Some(self.state_entered_at + limit)
+    }
+
+    /// Returns error if peer stayed in current state for longer than allowed at `now`.
+    pub fn check_timeout(&self, now: Instant) -> Result<(), TimeoutError> {
+        let limit = match self.timeout_policy.limit(self.state) {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+
+        let elapsed = now.saturating_duration_since(self.state_entered_at);
+
+        if elapsed >= limit {
+            return Err(TimeoutError {
+                state: self.state,
+                limit,
+                elapsed,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns fingerprint of the client, recorded only on server side.
+    pub fn fingerprint(&self) -> Option<&ClientFingerprint> {
+        self.fingerprint.as_ref()
+    }
+
+    /// Appends bytes received from peer.
+    pub fn receive(&mut self, bytes: &[u8]) {
+        self.input.extend_from_slice(bytes);
+        self.received_at = Some(Instant::now());
+    }
+
+    /// Returns received bytes which are not framed yet, e.g. to decrypt them once encryption
+    /// starts in the middle of received data.
+    pub fn input_mut(&mut self) -> &mut [u8] {
+        &mut self.input
+    }
+
+    /// Returns next event. Frames which are not received completely stay buffered.
+    ///
+    /// Only length prefix is parsed until whole frame is received, frame longer than max packet
+    /// length is refused with `DecodeError::PacketTooLong` as soon as its prefix arrives.
+    /// Errors are not recoverable, connection should be closed.
+    pub fn poll_event(&mut self) -> Result<DriverEvent, DecodeError> {
+        if let Some(event) = self.events.pop_front() {
+            return Ok(event);
+        }
+
+        let mut reader = self.input.as_slice();
+
+        let length = match reader.read_var_i32() {
+            Ok(length) => length as u32 as usize,
+            Err(DecodeError::IOError { io_error })
+                if io_error.kind() == ErrorKind::UnexpectedEof =>
+            {
+                return Ok(DriverEvent::NeedMoreData);
+            }
+            Err(decode_error) => return Err(decode_error),
+        };
+
+        if length > self.max_packet_length {
+            return Err(DecodeError::PacketTooLong {
+                length,
+                max_length: self.max_packet_length,
+            });
+        }
+
+        let prefix_length = self.input.len() - reader.len();
+        let frame_length = prefix_length + length;
+
+        if self.input.len() < frame_length {
+            return Ok(DriverEvent::NeedMoreData);
+        }
+
+        let body = self.input[prefix_length..frame_length].to_vec();
+        self.input.drain(..frame_length);
+
+        let raw_packet = match self.compression_threshold {
+            Some(threshold) => CompressedRawPacket::from_body(body, threshold)?.into_inner(),
+            None => RawPacket::from_body(body)?,
+        };
+
+        if let (Some(fingerprint), Some(received_at)) = (&mut self.fingerprint, self.received_at) {
+            fingerprint.record(self.state, &raw_packet, received_at);
+        }
+
+        let server_bound = self.side == Side::Server;
+        let transition = self.transition(&raw_packet, server_bound)?;
+        self.apply(transition);
+
+        Ok(DriverEvent::Packet(raw_packet))
+    }
+
+    /// Handles command appending bytes to output.
+    ///
+    /// Returns `EncodeError::PacketTooLong` if frame is longer than max packet length, nothing is
+    /// appended then.
+    pub fn handle(&mut self, command: DriverCommand) -> Result<(), EncodeError> {
+        match command {
+            DriverCommand::SendPacket(raw_packet) => {
+                let server_bound = self.side == Side::Client;
+                let transition = self.transition(&raw_packet, server_bound).map_err(|e| {
+                    EncodeError::MalformedPacket {
+                        id: raw_packet.id,
+                        reason: format!("{:?}", e),
+                    }
+                })?;
+
+                let start = self.output.len();
+
+                match self.compression_threshold {
+                    Some(threshold) => {
+                        CompressedRawPacket::from(raw_packet).encode(&mut self.output, threshold)?
+                    }
+                    None => raw_packet.encode(&mut self.output)?,
+                }
+
+                // Frame was just written, so its length prefix is valid.
+                let length = (&self.output[start..]).read_var_i32().unwrap() as usize;
+
+                if length > self.max_packet_length {
+                    self.output.truncate(start);
+
+                    return Err(EncodeError::PacketTooLong {
+                        length,
+                        max_length: self.max_packet_length,
+                    });
+                }
+
+                self.apply(transition);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns bytes which should be sent to peer.
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /// Removes sent bytes from output, e.g. after partial write to non-blocking socket.
+    pub fn advance_output(&mut self, sent: usize) {
+        self.output.drain(..sent);
+    }
+
+    /// Takes all bytes which should be sent to peer.
+    pub fn take_output(&mut self) -> Vec<u8> {
+        mem::take(&mut self.output)
+    }
+
+    /// Returns how packet changes connection, packet itself is framed before the change.
+    fn transition(
+        &self,
+        raw_packet: &RawPacket,
+        server_bound: bool,
+    ) -> Result<Option<Transition>, DecodeError> {
+        let mut data = raw_packet.data.as_slice();
+
+        match (self.state, server_bound, raw_packet.id) {
+            (ProtocolState::Handshake, true, HANDSHAKE_ID) => {
+                data.read_var_i32()?;
+                data.read_string(STRING_MAX_LENGTH)?;
+                data.read_u16::<BigEndian>()?;
+
+                match data.read_var_i32()? {
+                    1 => Ok(Some(Transition::State(ProtocolState::Status))),
+                    // Transferred client logs in like any other.
+                    2 | 3 => Ok(Some(Transition::State(ProtocolState::Login))),
+                    next_state => Err(DecodeError::UnknownEnumType {
+                        type_id: next_state as usize,
+                    }),
+                }
+            }
+            (ProtocolState::Login, false, LOGIN_SUCCESS_ID) => {
+                Ok(Some(Transition::State(ProtocolState::Game)))
+            }
+            (ProtocolState::Login, false, SET_COMPRESSION_ID) => {
+                let threshold = data.read_var_i32()?;
+
+                Ok(Some(Transition::Compression(
+                    (threshold >= 0).then_some(threshold as usize),
+                )))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn apply(&mut self, transition: Option<Transition>) {
+        match transition {
+            Some(Transition::State(state)) => {
+                self.state = state;
+                self.state_entered_at = Instant::now();
+                self.events.push_back(DriverEvent::StateChanged(state));
+            }
+            Some(Transition::Compression(Some(threshold))) => self.enable_compression(threshold),
+            Some(Transition::Compression(None)) => self.disable_compression(),
+            None => {}
+        }
+    }
+}
+
+enum Transition {
+    State(ProtocolState),
+    Compression(Option<usize>),
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::driver::*;
+    use crate::version::v1_14_4::game::{ClientBoundKeepAlive, GameClientBoundPacket};
+    use crate::version::v1_14_4::handshake::{Handshake, NextState};
+    use crate::version::v1_14_4::login::{LoginStart, LoginSuccess, SetCompression};
+    use uuid::Uuid;
+
+    fn send<P: crate::packet::Packet>(driver: &mut ProtocolDriver, packet: &P) {
+        let raw_packet = RawPacket::from_packet(packet).unwrap();
+        driver
+            .handle(DriverCommand::SendPacket(raw_packet))
+            .unwrap();
+    }
+
+    fn poll_all(driver: &mut ProtocolDriver) -> Vec<DriverEvent> {
+        let mut events = Vec::new();
+
+        loop {
+            match driver.poll_event().unwrap() {
+                DriverEvent::NeedMoreData => return events,
+                event => events.push(event),
+            }
+        }
+    }
+
+    #[test]
+    fn test_login_session() {
+        let mut client = ProtocolDriver::new(Side::Client);
+        let mut server = ProtocolDriver::new(Side::Server);
+
+        send(
+            &mut client,
+            &Handshake::new(498, String::from("localhost"), 25565, NextState::Login),
+        );
+        send(&mut client, &LoginStart::new(String::from("Username")));
+        assert_eq!(client.state(), ProtocolState::Login);
+
+        server.receive(&client.take_output());
+        let events = poll_all(&mut server);
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1], DriverEvent::StateChanged(ProtocolState::Login));
+        assert_eq!(server.state(), ProtocolState::Login);
+
+        let fingerprint = server.fingerprint().unwrap();
+        assert_eq!(fingerprint.username.as_deref(), Some("Username"));
+        assert_eq!(fingerprint.packet_ids(ProtocolState::Login), [0x00]);
+        assert!(client.fingerprint().is_none());
+
+        send(&mut server, &SetCompression::new(64));
+        send(
+            &mut server,
+            &LoginSuccess::new(Uuid::nil(), String::from("Username")),
+        );
+        send(&mut server, &ClientBoundKeepAlive::new(1));
+        assert_eq!(server.compression_threshold(), Some(64));
+        assert_eq!(server.state(), ProtocolState::Game);
+
+        let bytes = server.take_output();
+        let mut events = Vec::new();
+
+        for byte in bytes {
+            client.receive(&[byte]);
+            events.extend(poll_all(&mut client));
+        }
+
+        // Client also reports state change caused by sent handshake.
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0], DriverEvent::StateChanged(ProtocolState::Login));
+        assert_eq!(events[3], DriverEvent::StateChanged(ProtocolState::Game));
+        assert_eq!(client.compression_threshold(), Some(64));
+
+        match &events[4] {
+            DriverEvent::Packet(raw_packet) => {
+                let packet: GameClientBoundPacket = raw_packet.decode_packet().unwrap();
+                assert!(matches!(
+                    packet,
+                    GameClientBoundPacket::ClientBoundKeepAlive(_)
+                ));
+            }
+            event => panic!("Expected `Packet` but got `{:?}`", event),
+        }
+    }
+
+    #[test]
+    fn test_login_timeout() {
+        let mut client = ProtocolDriver::new(Side::Client);
+        let mut server = ProtocolDriver::new(Side::Server);
+        server.set_timeout_policy(TimeoutPolicy::default());
+
+        send(
+            &mut client,
+            &Handshake::new(498, String::from("localhost"), 25565, NextState::Login),
+        );
+        server.receive(&client.take_output());
+        poll_all(&mut server);
+
+        let deadline = server.deadline().unwrap();
+        assert!(server.check_timeout(Instant::now()).is_ok());
+
+        let timeout_error = server
+            .check_timeout(deadline)
+            .expect_err("Expected error `TimeoutError` because login deadline passed");
+
+        assert_eq!(timeout_error.state, ProtocolState::Login);
+        assert_eq!(timeout_error.limit, DEFAULT_TIMEOUT);
+
+        // Client driver has no limits.
+        assert!(client.deadline().is_none());
+        assert!(client.check_timeout(deadline).is_ok());
+    }
+
+    #[test]
+    fn test_game_not_limited() {
+        let mut client = ProtocolDriver::new(Side::Client);
+        client.set_timeout_policy(TimeoutPolicy::default());
+
+        client.receive(&[]);
+        client.apply(Some(Transition::State(ProtocolState::Game)));
+
+        assert!(client.deadline().is_none());
+        assert!(client
+            .check_timeout(Instant::now() + DEFAULT_TIMEOUT * 2)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_transfer_logs_in() {
+        let mut client = ProtocolDriver::new(Side::Client);
+
+        send(
+            &mut client,
+            &Handshake::new(766, String::from("localhost"), 25565, NextState::Transfer),
+        );
+
+        assert_eq!(client.state(), ProtocolState::Login);
+    }
+
+    #[test]
+    fn test_invalid_next_state() {
+        // Handshake of protocol 498 to localhost:25565 with next state 5.
+        let mut data = vec![0xF2, 0x03, 9];
+        data.extend_from_slice(b"localhost");
+        data.extend_from_slice(&[0x63, 0xDD, 5]);
+        let raw_packet = RawPacket::new(0x00, data);
+
+        let mut client = ProtocolDriver::new(Side::Client);
+        let encode_error = client
+            .handle(DriverCommand::SendPacket(raw_packet.clone()))
+            .expect_err("Expected error `MalformedPacket` because next state is invalid");
+
+        assert!(matches!(
+            encode_error,
+            EncodeError::MalformedPacket { id: 0x00, .. }
+        ));
+        assert!(client.output().is_empty());
+
+        let mut vec = Vec::new();
+        raw_packet.encode(&mut vec).unwrap();
+
+        let mut server = ProtocolDriver::new(Side::Server);
+        server.receive(&vec);
+
+        let decode_error = server
+            .poll_event()
+            .expect_err("Expected error `UnknownEnumType` because next state is invalid");
+
+        match decode_error {
+            DecodeError::UnknownEnumType { type_id } => assert_eq!(type_id, 5),
+            _ => panic!("Expected `UnknownEnumType` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_frame_decoded_when_complete() {
+        let mut server = ProtocolDriver::new(Side::Server);
+
+        // Frame of length 10 with malformed packet id, error shows when frame is decoded.
+        let mut data = vec![10];
+        data.extend_from_slice(&[0xFF; 10]);
+        let (last, data) = data.split_last().unwrap();
+
+        for byte in data {
+            server.receive(&[*byte]);
+            assert_eq!(server.poll_event().unwrap(), DriverEvent::NeedMoreData);
+        }
+
+        server.receive(&[*last]);
+
+        let decode_error = server
+            .poll_event()
+            .expect_err("Expected error `VarIntTooLong` because packet id is malformed");
+
+        match decode_error {
+            DecodeError::VarIntTooLong { .. } => {}
+            _ => panic!("Expected `VarIntTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_max_packet_length() {
+        let mut server = ProtocolDriver::new(Side::Server);
+        server.set_max_packet_length(16);
+        assert_eq!(server.max_packet_length(), 16);
+
+        // Only length prefix is received.
+        server.receive(&[17]);
+
+        let decode_error = server
+            .poll_event()
+            .expect_err("Expected error `PacketTooLong` because declared length is over limit");
+
+        match decode_error {
+            DecodeError::PacketTooLong { length, max_length } => {
+                assert_eq!(length, 17);
+                assert_eq!(max_length, 16);
+            }
+            _ => panic!("Expected `PacketTooLong` but got `{:?}`", decode_error),
+        }
+
+        let mut client = ProtocolDriver::new(Side::Client);
+        client.apply(Some(Transition::State(ProtocolState::Game)));
+        client.set_max_packet_length(16);
+
+        let encode_error = client
+            .handle(DriverCommand::SendPacket(RawPacket::new(0x00, vec![0; 16])))
+            .expect_err("Expected error `PacketTooLong` because frame is over limit");
+
+        assert_eq!(
+            encode_error,
+            EncodeError::PacketTooLong {
+                length: 17,
+                max_length: 16
+            }
+        );
+        assert!(client.output().is_empty());
+    }
+}
@@ -1,7 +1,18 @@
 use crate::error::EncodeError;
+use crate::limits::JSON_MAX_LENGTH;
 use byteorder::{BigEndian, WriteBytesExt};
 use nbt::CompoundTag;
+use serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
 use std::io::Write;
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8,
+};
+use std::sync::Arc;
 use uuid::Uuid;
 
 pub trait Encoder {
@@ -23,12 +34,48 @@ pub trait EncoderWriteExt {
     fn write_var_i64(&mut self, value: i64) -> Result<(), EncodeError>;
 }
 
+/// Writes var int length prefix, returning `EncodeError::LengthOutOfRange` if length doesn't fit.
+pub(crate) fn write_length<W: Write>(writer: &mut W, length: usize) -> Result<(), EncodeError> {
+    let length = i32::try_from(length).map_err(|_| EncodeError::LengthOutOfRange { length })?;
+
+    writer.write_var_i32(length)
+}
+
+/// Returns number of bytes var int takes on the wire.
+pub const fn var_i32_size(value: i32) -> usize {
+    let mut value = value as u32 >> 7;
+    let mut size = 1;
+
+    while value != 0 {
+        value >>= 7;
+        size += 1;
+    }
+
+    size
+}
+
+/// Returns number of bytes var long takes on the wire.
+pub const fn var_i64_size(value: i64) -> usize {
+    let mut value = value as u64 >> 7;
+    let mut size = 1;
+
+    while value != 0 {
+        value >>= 7;
+        size += 1;
+    }
+
+    size
+}
+
 macro_rules! write_signed_var_int (
-    ($type: ident, $name: ident) => (
-        fn $name(&mut self, mut value: $type) -> Result<(), EncodeError> {
+    ($type: ident, $unsigned: ident, $name: ident) => (
+        fn $name(&mut self, value: $type) -> Result<(), EncodeError> {
+            // Negative values are shifted without sign extension, so they end after max bytes.
+            let mut value = value as $unsigned;
+
             loop {
                 let mut byte = (value & 0b01111111) as u8;
-                value = value >> 7;
+                value >>= 7;
 
                 if value != 0 {
                     byte |= 0b10000000;
@@ -58,33 +105,37 @@ impl<W: Write> EncoderWriteExt for W {
     }
 
     fn write_string(&mut self, value: &str, max_length: u16) -> Result<(), EncodeError> {
-        let length = value.len();
-
-        if length > max_length as usize {
-            return Err(EncodeError::StringTooLong { length, max_length });
+        if let Some(length) = crate::utf16_length_over(value, max_length) {
+            return Err(EncodeError::StringTooLong {
+                field: None,
+                length,
+                max_length,
+            });
         }
 
-        self.write_var_i32(value.len() as i32)?;
+        write_length(self, value.len())?;
         self.write_all(value.as_bytes())?;
 
         Ok(())
     }
 
     fn write_byte_array(&mut self, value: &[u8]) -> Result<(), EncodeError> {
-        self.write_var_i32(value.len() as i32)?;
+        write_length(self, value.len())?;
         self.write_all(value)?;
 
         Ok(())
     }
 
     fn write_compound_tag(&mut self, value: &CompoundTag) -> Result<(), EncodeError> {
+        // `nbt` takes tag by value and doesn't expose its entries, so it has to be cloned.
+        // Use `CompoundTagCache` for tags which are encoded repeatedly.
         nbt::encode::write_compound_tag(self, value.clone())?;
 
         Ok(())
     }
 
-    write_signed_var_int!(i32, write_var_i32);
-    write_signed_var_int!(i64, write_var_i64);
+    write_signed_var_int!(i32, u32, write_var_i32);
+    write_signed_var_int!(i64, u64, write_var_i64);
 }
 
 impl Encoder for u8 {
@@ -93,6 +144,12 @@ impl Encoder for u8 {
     }
 }
 
+impl Encoder for i8 {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        Ok(writer.write_i8(*self)?)
+    }
+}
+
 impl Encoder for i16 {
     fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
         Ok(writer.write_i16::<BigEndian>(*self)?)
@@ -129,6 +186,43 @@ impl Encoder for u64 {
     }
 }
 
+impl Encoder for i128 {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        Ok(writer.write_i128::<BigEndian>(*self)?)
+    }
+}
+
+impl Encoder for u128 {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        Ok(writer.write_u128::<BigEndian>(*self)?)
+    }
+}
+
+macro_rules! impl_non_zero_encoder (
+    ($($non_zero: ident),*) => (
+        $(
+            impl Encoder for $non_zero {
+                fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+                    self.get().encode(writer)
+                }
+            }
+        )*
+    );
+);
+
+impl_non_zero_encoder!(
+    NonZeroU8,
+    NonZeroI8,
+    NonZeroU16,
+    NonZeroI16,
+    NonZeroU32,
+    NonZeroI32,
+    NonZeroU64,
+    NonZeroI64,
+    NonZeroU128,
+    NonZeroI128
+);
+
 impl Encoder for f32 {
     fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
         Ok(writer.write_f32::<BigEndian>(*self)?)
@@ -143,19 +237,110 @@ impl Encoder for f64 {
 
 impl Encoder for String {
     fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
-        Ok(writer.write_string(self, 32_768)?)
+        writer.write_string(self, 32_768)
+    }
+}
+
+impl Encoder for str {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        writer.write_string(self, 32_768)
     }
 }
 
 impl Encoder for bool {
     fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
-        Ok(writer.write_bool(*self)?)
+        writer.write_bool(*self)
+    }
+}
+
+/// Optional value is prefixed with boolean which tells whether value is present.
+///
+/// Fields sent as JSON `null` when absent use `#[data_type(with = "nullable_json")]` instead.
+impl<T: Encoder> Encoder for Option<T> {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            Some(value) => {
+                writer.write_bool(true)?;
+                value.encode(writer)
+            }
+            None => writer.write_bool(false),
+        }
     }
 }
 
 impl Encoder for Vec<u8> {
     fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
-        Ok(writer.write_byte_array(self)?)
+        writer.write_byte_array(self)
+    }
+}
+
+impl Encoder for [u8] {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        writer.write_byte_array(self)
+    }
+}
+
+/// Borrowed values are encoded as values they point to, so packet views can borrow data.
+impl<T: Encoder + ?Sized> Encoder for &T {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        (**self).encode(writer)
+    }
+}
+
+impl<T: Encoder + ToOwned + ?Sized> Encoder for Cow<'_, T> {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        self.as_ref().encode(writer)
+    }
+}
+
+impl Encoder for Vec<i64> {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        write_length(writer, self.len())?;
+
+        for value in self {
+            writer.write_i64::<BigEndian>(*value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Map is prefixed with entry count. Entries of `HashMap` are sorted by encoded key, because
+/// iteration order differs between maps and runs, and encoded bytes must be stable for caches
+/// keyed by `Packet::stable_hash`.
+impl<K: Encoder, V: Encoder> Encoder for HashMap<K, V> {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        let mut entries = Vec::with_capacity(self.len());
+
+        for (key, value) in self {
+            let mut encoded_key = Vec::new();
+            key.encode(&mut encoded_key)?;
+            entries.push((encoded_key, value));
+        }
+
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        write_length(writer, self.len())?;
+
+        for (encoded_key, value) in entries {
+            writer.write_all(&encoded_key)?;
+            value.encode(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Map is prefixed with entry count. Entries are written in key order.
+impl<K: Encoder, V: Encoder> Encoder for BTreeMap<K, V> {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        write_length(writer, self.len())?;
+
+        for (key, value) in self {
+            key.encode(writer)?;
+            value.encode(writer)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -165,18 +350,24 @@ impl Encoder for Uuid {
     }
 }
 
+impl<T: Encoder> Encoder for Arc<T> {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        self.as_ref().encode(writer)
+    }
+}
+
 impl Encoder for CompoundTag {
     fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
-        Ok(writer.write_compound_tag(self)?)
+        writer.write_compound_tag(self)
     }
 }
 
 impl Encoder for Vec<CompoundTag> {
     fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
-        writer.write_var_i32(self.len() as i32)?;
+        write_length(writer, self.len())?;
 
         for compound_tag in self {
-            writer.write_compound_tag(&compound_tag)?;
+            writer.write_compound_tag(compound_tag)?;
         }
 
         Ok(())
@@ -195,6 +386,20 @@ pub mod var_int {
     }
 }
 
+/// Ticks sent as var int, e.g. item cooldown.
+pub mod var_int_ticks {
+    use crate::data::units::Ticks;
+    use crate::encoder::EncoderWriteExt;
+    use crate::error::EncodeError;
+    use std::io::Write;
+
+    pub fn encode<W: Write>(value: &Ticks, writer: &mut W) -> Result<(), EncodeError> {
+        writer.write_var_i32(value.0)?;
+
+        Ok(())
+    }
+}
+
 pub mod var_long {
     use crate::encoder::EncoderWriteExt;
     use crate::error::EncodeError;
@@ -207,6 +412,20 @@ pub mod var_long {
     }
 }
 
+/// Block position packed as x, y, z, used before 1.14.
+pub mod legacy_position {
+    use crate::data::game::BlockPosition;
+    use crate::error::EncodeError;
+    use byteorder::{BigEndian, WriteBytesExt};
+    use std::io::Write;
+
+    pub fn encode<W: Write>(value: &BlockPosition, writer: &mut W) -> Result<(), EncodeError> {
+        writer.write_i64::<BigEndian>(value.to_legacy_packed()?)?;
+
+        Ok(())
+    }
+}
+
 pub mod rest {
     use crate::error::EncodeError;
     use std::io::Write;
@@ -218,6 +437,136 @@ pub mod rest {
     }
 }
 
+/// Serializes value to JSON with object keys sorted at every level.
+///
+/// Output doesn't depend on field declaration order, flattened fields or map implementation used by
+/// `serde_json`. Numbers are written as `serde_json` does, floats in shortest form which reads back
+/// to the same value.
+pub fn to_canonical_json<T: Serialize>(value: &T) -> Result<String, serde_json::Error> {
+    let value = serde_json::to_value(value)?;
+    let mut json = String::new();
+    write_canonical_value(&value, &mut json)?;
+
+    Ok(json)
+}
+
+fn write_canonical_value(value: &Value, json: &mut String) -> Result<(), serde_json::Error> {
+    match value {
+        Value::Array(values) => {
+            json.push('[');
+
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+
+                write_canonical_value(value, json)?;
+            }
+
+            json.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+
+            json.push('{');
+
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+
+                json.push_str(&serde_json::to_string(key)?);
+                json.push(':');
+                write_canonical_value(value, json)?;
+            }
+
+            json.push('}');
+        }
+        _ => json.push_str(&serde_json::to_string(value)?),
+    }
+
+    Ok(())
+}
+
+/// Writes value as JSON string, this is how JSON data types are sent.
+///
+/// Returns `EncodeError::JsonTooLong` if JSON is longer than `JSON_MAX_LENGTH` bytes.
+///
+/// JSON is canonical in tests and with `canonical-json` feature, so encoded bytes are stable.
+/// Otherwise keys of structs follow field order, but entries of `HashMap` fields, e.g. flattened
+/// ones, follow iteration order which differs between runs.
+pub fn write_json<T: Serialize, W: Write>(writer: &mut W, value: &T) -> Result<(), EncodeError> {
+    #[cfg(any(test, feature = "canonical-json"))]
+    let json = to_canonical_json(value)?;
+    #[cfg(not(any(test, feature = "canonical-json")))]
+    let json = serde_json::to_string(value)?;
+
+    if json.len() > JSON_MAX_LENGTH {
+        return Err(EncodeError::JsonTooLong {
+            length: json.len(),
+            max_length: JSON_MAX_LENGTH,
+        });
+    }
+
+    writer.write_byte_array(json.as_bytes())
+}
+
+/// Optional JSON value, e.g. chat component, sent as JSON `null` when absent.
+///
+/// Most optional fields are prefixed with boolean like `Option<T>` encoder does, use this one only
+/// where protocol sends JSON string in both cases.
+pub mod nullable_json {
+    use crate::error::EncodeError;
+    use serde::Serialize;
+    use std::io::Write;
+
+    pub fn encode<T: Serialize, W: Write>(
+        value: &Option<T>,
+        writer: &mut W,
+    ) -> Result<(), EncodeError> {
+        crate::encoder::write_json(writer, value)
+    }
+}
+
+/// Values prefixed with var int count.
+pub mod list {
+    use crate::encoder::{write_length, Encoder};
+    use crate::error::EncodeError;
+    use std::io::Write;
+
+    pub fn encode<T: Encoder, W: Write>(value: &[T], writer: &mut W) -> Result<(), EncodeError> {
+        write_length(writer, value.len())?;
+
+        for item in value {
+            item.encode(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Values prefixed with short count, e.g. slots of window before 1.17.
+pub mod short_list {
+    use crate::encoder::Encoder;
+    use crate::error::EncodeError;
+    use byteorder::{BigEndian, WriteBytesExt};
+    use std::convert::TryFrom;
+    use std::io::Write;
+
+    pub fn encode<T: Encoder, W: Write>(value: &[T], writer: &mut W) -> Result<(), EncodeError> {
+        let length = value.len();
+        let count = i16::try_from(length).map_err(|_| EncodeError::LengthOutOfRange { length })?;
+        writer.write_i16::<BigEndian>(count)?;
+
+        for item in value {
+            item.encode(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
 pub mod uuid_hyp_str {
     use crate::encoder::EncoderWriteExt;
     use crate::error::EncodeError;
@@ -234,8 +583,47 @@ pub mod uuid_hyp_str {
 
 #[cfg(test)]
 mod tests {
-    use crate::encoder::EncoderWriteExt;
-    use std::io::Cursor;
+    use crate::encoder::{
+        to_canonical_json, var_i32_size, var_i64_size, write_length, Encoder, EncoderWriteExt,
+    };
+    use crate::error::EncodeError;
+    use minecraft_protocol_derive::Encoder;
+    use serde::Serialize;
+    use std::collections::{BTreeMap, HashMap};
+    use std::io::{Cursor, Error as IoError, ErrorKind};
+    use std::num::{NonZeroI16, NonZeroU8};
+
+    #[derive(Encoder)]
+    struct Profile {
+        #[data_type(max_length = 16)]
+        name: String,
+        #[data_type(max_length = 4)]
+        skin: String,
+    }
+
+    #[test]
+    fn test_var_i32_size() {
+        for &value in &[0, 127, 128, 300, 2_097_151, 2_097_152, i32::MAX] {
+            let mut vec = Vec::new();
+            vec.write_var_i32(value).unwrap();
+
+            assert_eq!(var_i32_size(value), vec.len());
+        }
+
+        assert_eq!(var_i32_size(-1), 5);
+    }
+
+    #[test]
+    fn test_var_i64_size() {
+        for &value in &[0, 127, 128, 2_147_483_647, i64::MAX] {
+            let mut vec = Vec::new();
+            vec.write_var_i64(value).unwrap();
+
+            assert_eq!(var_i64_size(value), vec.len());
+        }
+
+        assert_eq!(var_i64_size(-1), 10);
+    }
 
     #[test]
     fn test_write_variable_i32_2_bytes_value() {
@@ -252,4 +640,218 @@ mod tests {
 
         assert_eq!(cursor.into_inner(), vec![0xff, 0xff, 0xff, 0xff, 0x07]);
     }
+
+    #[test]
+    fn test_write_variable_min_values() {
+        let mut vec = Vec::new();
+        vec.write_var_i32(i32::MIN).unwrap();
+
+        assert_eq!(vec, [0x80, 0x80, 0x80, 0x80, 0x08]);
+        assert_eq!(var_i32_size(i32::MIN), vec.len());
+
+        let mut vec = Vec::new();
+        vec.write_var_i64(i64::MIN).unwrap();
+
+        assert_eq!(
+            vec,
+            [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01]
+        );
+        assert_eq!(var_i64_size(i64::MIN), vec.len());
+    }
+
+    #[test]
+    fn test_length_out_of_range() {
+        let mut vec = Vec::new();
+        let length = i32::MAX as usize + 1;
+
+        let encode_error = write_length(&mut vec, length)
+            .expect_err("Expected error `LengthOutOfRange` because length doesn't fit var int");
+
+        assert_eq!(encode_error, EncodeError::LengthOutOfRange { length });
+        assert!(vec.is_empty());
+
+        write_length(&mut vec, i32::MAX as usize).unwrap();
+        assert_eq!(vec, [0xff, 0xff, 0xff, 0xff, 0x07]);
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_length_above_u32_out_of_range() {
+        let length = u32::MAX as usize + 1;
+
+        assert_eq!(
+            write_length(&mut Vec::new(), length),
+            Err(EncodeError::LengthOutOfRange { length })
+        );
+    }
+
+    #[test]
+    fn test_write_i8() {
+        let mut vec = Vec::new();
+        (-1i8).encode(&mut vec).unwrap();
+
+        assert_eq!(vec, vec![0xff]);
+    }
+
+    #[test]
+    fn test_write_option() {
+        let mut vec = Vec::new();
+        Some(-1i8).encode(&mut vec).unwrap();
+        None::<i8>.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, vec![1, 0xff, 0]);
+    }
+
+    #[test]
+    fn test_write_btree_map() {
+        let mut map = BTreeMap::new();
+        map.insert(2u8, -1i8);
+        map.insert(1u8, 5i8);
+
+        let mut vec = Vec::new();
+        map.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [2, 1, 5, 2, 0xff]);
+    }
+
+    #[test]
+    fn test_write_non_zero() {
+        let mut vec = Vec::new();
+        NonZeroI16::new(-2).unwrap().encode(&mut vec).unwrap();
+        NonZeroU8::new(7).unwrap().encode(&mut vec).unwrap();
+
+        assert_eq!(vec, vec![0xff, 0xfe, 7]);
+    }
+
+    #[test]
+    fn test_write_u128() {
+        let mut vec = Vec::new();
+        ((1u128 << 64) | 2).encode(&mut vec).unwrap();
+
+        assert_eq!(vec, vec![0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    fn test_write_i128() {
+        let mut vec = Vec::new();
+        (-1i128).encode(&mut vec).unwrap();
+
+        assert_eq!(vec, vec![0xff; 16]);
+    }
+
+    #[test]
+    fn test_write_i64_array() {
+        let mut vec = Vec::new();
+        vec![1i64, -1].encode(&mut vec).unwrap();
+
+        assert_eq!(
+            vec,
+            vec![2, 0, 0, 0, 0, 0, 0, 0, 1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+        );
+    }
+
+    #[test]
+    fn test_string_too_long_field() {
+        let profile = Profile {
+            name: String::from("Username"),
+            skin: String::from("steve"),
+        };
+
+        let encode_error = profile
+            .encode(&mut Vec::new())
+            .expect_err("Expected error `StringTooLong` because skin is too long");
+
+        assert_eq!(
+            encode_error,
+            EncodeError::StringTooLong {
+                field: Some("skin"),
+                length: 5,
+                max_length: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_encode_error_eq() {
+        let io_error = EncodeError::from(IoError::new(ErrorKind::WriteZero, "first"));
+
+        assert_eq!(
+            io_error,
+            EncodeError::from(IoError::new(ErrorKind::WriteZero, "second"))
+        );
+        assert_ne!(io_error, EncodeError::from(IoError::from(ErrorKind::Other)));
+        assert_eq!(
+            EncodeError::StringTooLong {
+                field: Some("skin"),
+                length: 5,
+                max_length: 4,
+            }
+            .with_field("profile"),
+            EncodeError::StringTooLong {
+                field: Some("skin"),
+                length: 5,
+                max_length: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_hash_map_sorted_by_encoded_key() {
+        let mut map = HashMap::new();
+        map.insert(2u8, -1i8);
+        map.insert(1u8, 5i8);
+
+        let mut vec = Vec::new();
+        map.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [2, 1, 5, 2, 0xff]);
+    }
+
+    #[test]
+    fn test_hash_map_encoding_independent_of_insertion_order() {
+        let keys: Vec<String> = (0..64).map(|i| format!("minecraft:stat_{}", i)).collect();
+
+        let forward: HashMap<_, _> = keys.iter().cloned().zip(0..64).collect();
+        let mut backward = HashMap::with_capacity(256);
+
+        for (key, value) in keys.iter().cloned().zip(0..64).rev() {
+            backward.insert(key, value);
+        }
+
+        let mut forward_vec = Vec::new();
+        forward.encode(&mut forward_vec).unwrap();
+        let mut backward_vec = Vec::new();
+        backward.encode(&mut backward_vec).unwrap();
+
+        assert_eq!(forward_vec, backward_vec);
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_keys() {
+        #[derive(Serialize)]
+        struct Entry {
+            name: &'static str,
+            #[serde(flatten)]
+            extra: HashMap<&'static str, f32>,
+            nested: Vec<BTreeMap<&'static str, bool>>,
+        }
+
+        let mut extra = HashMap::new();
+        extra.insert("z", 1.0);
+        extra.insert("b", 0.1);
+
+        let mut flags = BTreeMap::new();
+        flags.insert("on", true);
+
+        let entry = Entry {
+            name: "entry",
+            extra,
+            nested: vec![flags],
+        };
+
+        assert_eq!(
+            to_canonical_json(&entry).unwrap(),
+            r#"{"b":0.10000000149011612,"name":"entry","nested":[{"on":true}],"z":1.0}"#
+        );
+    }
 }
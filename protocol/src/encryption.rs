@@ -0,0 +1,349 @@
+//! Encryption of connections in online mode.
+//!
+//! Once client sends `EncryptionResponse`, both sides encrypt every following byte with AES-128
+//! in CFB8 mode, using the shared secret as both key and IV. Cipher is a stream cipher, so each
+//! direction keeps its own state for the whole connection and bytes must be processed in order.
+//!
+//! `Connection::enable_encryption` encrypts a connection, `StreamCipher` can be used directly
+//! with `ProtocolDriver`. `PacketCodec::enable_encryption` wraps stream of codec into
+//! `EncryptedStream`, which can still be split into `EncryptedReader` and `EncryptedWriter`.
+//!
+//! Encrypted bytes are buffered by writer until it's flushed, which codec does after every
+//! packet by default.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::encryption::StreamCipher;
+//!
+//! let shared_secret = [7; 16];
+//! let mut client = StreamCipher::new(&shared_secret);
+//! let mut server = StreamCipher::new(&shared_secret);
+//!
+//! let mut bytes = *b"Hello";
+//! client.encrypt(&mut bytes);
+//! assert_ne!(&bytes, b"Hello");
+//!
+//! server.decrypt(&mut bytes);
+//! assert_eq!(&bytes, b"Hello");
+//! ```
+use crate::codec::PacketCodec;
+use crate::split::SplitStream;
+use aes::Aes128;
+use cfb8::cipher::generic_array::GenericArray;
+use cfb8::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// Length of shared secret generated by client.
+pub const SHARED_SECRET_LENGTH: usize = 16;
+
+/// Encrypted bytes which writer buffers before writing them to the stream.
+const WRITE_BUFFER_LENGTH: usize = 8192;
+
+type Encryptor = cfb8::Encryptor<Aes128>;
+type Decryptor = cfb8::Decryptor<Aes128>;
+
+/// Cipher state of both directions of connection.
+pub struct StreamCipher {
+    encryptor: Encryptor,
+    decryptor: Decryptor,
+}
+
+impl StreamCipher {
+    pub fn new(shared_secret: &[u8; SHARED_SECRET_LENGTH]) -> Self {
+        StreamCipher {
+            encryptor: Encryptor::new(shared_secret.into(), shared_secret.into()),
+            decryptor: Decryptor::new(shared_secret.into(), shared_secret.into()),
+        }
+    }
+
+    /// Encrypts bytes sent to peer in place.
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        encrypt(&mut self.encryptor, data)
+    }
+
+    /// Decrypts bytes received from peer in place.
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        decrypt(&mut self.decryptor, data)
+    }
+}
+
+fn encrypt(encryptor: &mut Encryptor, data: &mut [u8]) {
+    for byte in data {
+        let block = GenericArray::from_mut_slice(std::slice::from_mut(byte));
+        encryptor.encrypt_block_mut(block);
+    }
+}
+
+fn decrypt(decryptor: &mut Decryptor, data: &mut [u8]) {
+    for byte in data {
+        let block = GenericArray::from_mut_slice(std::slice::from_mut(byte));
+        decryptor.decrypt_block_mut(block);
+    }
+}
+
+impl fmt::Debug for StreamCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamCipher").finish_non_exhaustive()
+    }
+}
+
+/// Reader decrypting bytes received from peer. Writes pass through unchanged.
+pub struct EncryptedReader<R> {
+    reader: R,
+    decryptor: Decryptor,
+}
+
+impl<R> EncryptedReader<R> {
+    pub fn new(reader: R, shared_secret: &[u8; SHARED_SECRET_LENGTH]) -> Self {
+        EncryptedReader {
+            reader,
+            decryptor: Decryptor::new(shared_secret.into(), shared_secret.into()),
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Read for EncryptedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = self.reader.read(buf)?;
+        decrypt(&mut self.decryptor, &mut buf[..length]);
+
+        Ok(length)
+    }
+}
+
+impl<R: Write> Write for EncryptedReader<R> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.reader.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.reader.flush()
+    }
+}
+
+impl<R: fmt::Debug> fmt::Debug for EncryptedReader<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedReader")
+            .field("reader", &self.reader)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Writer encrypting bytes sent to peer. Reads pass through unchanged.
+///
+/// Cipher state advances as soon as bytes are accepted, so they are buffered and written on
+/// flush, or once buffer is full. Bytes still buffered when writer is dropped are lost.
+pub struct EncryptedWriter<W> {
+    writer: W,
+    encryptor: Encryptor,
+    buffer: Vec<u8>,
+}
+
+impl<W> EncryptedWriter<W> {
+    pub fn new(writer: W, shared_secret: &[u8; SHARED_SECRET_LENGTH]) -> Self {
+        EncryptedWriter {
+            writer,
+            encryptor: Encryptor::new(shared_secret.into(), shared_secret.into()),
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns writer, encrypted bytes which were not flushed are dropped.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> EncryptedWriter<W> {
+    fn write_buffer(&mut self) -> io::Result<()> {
+        while !self.buffer.is_empty() {
+            match self.writer.write(&self.buffer) {
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::WriteZero)),
+                Ok(length) => {
+                    self.buffer.drain(..length);
+                }
+                Err(io_error) if io_error.kind() == io::ErrorKind::Interrupted => {}
+                Err(io_error) => return Err(io_error),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for EncryptedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.buffer.len() + buf.len() > WRITE_BUFFER_LENGTH {
+            self.write_buffer()?;
+        }
+
+        let start = self.buffer.len();
+        self.buffer.extend_from_slice(buf);
+        encrypt(&mut self.encryptor, &mut self.buffer[start..]);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_buffer()?;
+        self.writer.flush()
+    }
+}
+
+impl<W: Read> Read for EncryptedWriter<W> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.writer.read(buf)
+    }
+}
+
+impl<W: fmt::Debug> fmt::Debug for EncryptedWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedWriter")
+            .field("writer", &self.writer)
+            .field("buffered", &self.buffer.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Stream encrypted in both directions.
+pub type EncryptedStream<S> = EncryptedWriter<EncryptedReader<S>>;
+
+/// Wraps stream to encrypt bytes in both directions from now on.
+pub fn encrypt_stream<S>(
+    stream: S,
+    shared_secret: &[u8; SHARED_SECRET_LENGTH],
+) -> EncryptedStream<S> {
+    EncryptedWriter::new(EncryptedReader::new(stream, shared_secret), shared_secret)
+}
+
+impl<S: SplitStream> SplitStream for EncryptedStream<S> {
+    type Read = EncryptedReader<S::Read>;
+    type Write = EncryptedWriter<S::Write>;
+
+    /// Splits stream keeping cipher state of both directions, buffered bytes move to writer.
+    fn split_stream(self) -> io::Result<(Self::Read, Self::Write)> {
+        let EncryptedReader { reader, decryptor } = self.writer;
+        let (reader, writer) = reader.split_stream()?;
+
+        let read_half = EncryptedReader { reader, decryptor };
+        let write_half = EncryptedWriter {
+            writer,
+            encryptor: self.encryptor,
+            buffer: self.buffer,
+        };
+
+        Ok((read_half, write_half))
+    }
+}
+
+impl<S> PacketCodec<S> {
+    /// Encrypts stream of codec in both directions, keeping codec settings.
+    ///
+    /// Server enables encryption after reading `EncryptionResponse`, client after writing it.
+    /// Codec doesn't buffer read data, so no received bytes are missed.
+    pub fn enable_encryption(
+        self,
+        shared_secret: &[u8; SHARED_SECRET_LENGTH],
+    ) -> PacketCodec<EncryptedStream<S>> {
+        let (stream, codec) = self.replace_stream(());
+
+        codec
+            .replace_stream(encrypt_stream(stream, shared_secret))
+            .1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::FlushPolicy;
+    use crate::encryption::*;
+    use crate::packet::RawPacket;
+    use std::io::Cursor;
+    use std::net::{TcpListener, TcpStream};
+
+    const SECRET: [u8; SHARED_SECRET_LENGTH] = [
+        0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6, 0xAB, 0xF7, 0x15, 0x88, 0x09, 0xCF, 0x4F,
+        0x3C,
+    ];
+
+    #[test]
+    fn test_encrypt_in_chunks() {
+        let mut whole = StreamCipher::new(&SECRET);
+        let mut chunked = StreamCipher::new(&SECRET);
+
+        let mut data = (0..64).collect::<Vec<u8>>();
+        let mut chunks = data.clone();
+        whole.encrypt(&mut data);
+
+        for chunk in chunks.chunks_mut(7) {
+            chunked.encrypt(chunk);
+        }
+
+        assert_eq!(data, chunks);
+
+        let mut peer = StreamCipher::new(&SECRET);
+        peer.decrypt(&mut data);
+
+        assert_eq!(data, (0..64).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_codec_encryption() {
+        let raw_packet = RawPacket::new(0x20, b"Plaintext".to_vec());
+        let mut client = PacketCodec::new(Vec::new()).enable_encryption(&SECRET);
+        client.write_raw_packet(&raw_packet).unwrap();
+
+        let bytes = client.into_inner().into_inner().into_inner();
+        assert_eq!(bytes.len(), raw_packet.encoded_len());
+        assert!(!bytes.windows(9).any(|window| window == b"Plaintext"));
+
+        let mut server = PacketCodec::new(Cursor::new(bytes)).enable_encryption(&SECRET);
+        assert_eq!(server.read_raw_packet().unwrap(), raw_packet);
+    }
+
+    #[test]
+    fn test_split_keeps_cipher_state() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+
+        let first = RawPacket::new(0x20, vec![1; 32]);
+        let second = RawPacket::new(0x21, vec![2; 32]);
+
+        let mut client = PacketCodec::new(client)
+            .with_flush_policy(FlushPolicy::Manual)
+            .enable_encryption(&SECRET);
+        client.write_raw_packet(&first).unwrap();
+
+        // First packet is still buffered and is written by writing half.
+        let (_read_half, mut write_half) = client.into_split().unwrap();
+        write_half.write_raw_packet(&second).unwrap();
+        write_half.flush().unwrap();
+
+        let mut server = PacketCodec::new(server).enable_encryption(&SECRET);
+        assert_eq!(server.read_raw_packet().unwrap(), first);
+        assert_eq!(server.read_raw_packet().unwrap(), second);
+    }
+}
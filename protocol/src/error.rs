@@ -1,17 +1,24 @@
+use crate::driver::ProtocolState;
 use nbt::decode::TagDecodeError;
 use serde_json::error::Error as JsonError;
-use std::io::Error as IoError;
+use std::io::{Error as IoError, ErrorKind};
 use std::string::FromUtf8Error;
+use std::time::Duration;
 use uuid::parser::ParseError as UuidParseError;
 
 /// Possible errors while encoding packet.
+///
+/// Errors are equal if their variants and fields are equal. Wrapped IO and JSON errors are
+/// compared by kind.
 #[derive(Debug)]
 pub enum EncodeError {
     /// String length can't be more than provided value.
     StringTooLong {
-        /// String length.
+        /// Field of derived encoder which failed, if known.
+        field: Option<&'static str>,
+        /// String length in UTF-16 code units.
         length: usize,
-        /// Max string length.
+        /// Max string length in UTF-16 code units.
         max_length: u16,
     },
     IOError {
@@ -20,6 +27,165 @@ pub enum EncodeError {
     JsonError {
         json_error: JsonError,
     },
+    /// Identifier contains characters which are not allowed.
+    InvalidIdentifier {
+        value: String,
+    },
+    /// Plugin message payload length can't be more than provided value.
+    PayloadTooLong {
+        /// Payload length.
+        length: usize,
+        /// Max payload length.
+        max_length: usize,
+    },
+    /// Packet length can't be more than provided value.
+    PacketTooLong {
+        /// Packet length.
+        length: usize,
+        /// Max packet length.
+        max_length: usize,
+    },
+    /// JSON length can't be more than `limits::JSON_MAX_LENGTH`.
+    JsonTooLong {
+        /// JSON length in bytes.
+        length: usize,
+        /// Max JSON length in bytes.
+        max_length: usize,
+    },
+    /// Packet which changes connection state can't be parsed.
+    MalformedPacket {
+        /// Packet id.
+        id: i32,
+        /// Decode error of packet data.
+        reason: String,
+    },
+    /// Length of string, array or collection doesn't fit into its length prefix.
+    LengthOutOfRange {
+        length: usize,
+    },
+    /// Block position doesn't fit into packed position.
+    PositionOutOfRange {
+        x: i32,
+        y: i32,
+        z: i32,
+    },
+    /// Packet belongs to other connection state than the current one.
+    WrongState {
+        /// Packet name.
+        name: &'static str,
+        /// State of packet.
+        packet_state: ProtocolState,
+        /// Current connection state.
+        state: ProtocolState,
+    },
+}
+
+impl EncodeError {
+    /// Attaches field name to error unless it already has one.
+    pub fn with_field(self, field: &'static str) -> Self {
+        match self {
+            EncodeError::StringTooLong {
+                field: None,
+                length,
+                max_length,
+            } => EncodeError::StringTooLong {
+                field: Some(field),
+                length,
+                max_length,
+            },
+            encode_error => encode_error,
+        }
+    }
+}
+
+impl PartialEq for EncodeError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                EncodeError::StringTooLong {
+                    field,
+                    length,
+                    max_length,
+                },
+                EncodeError::StringTooLong {
+                    field: other_field,
+                    length: other_length,
+                    max_length: other_max_length,
+                },
+            ) => field == other_field && length == other_length && max_length == other_max_length,
+            (
+                EncodeError::IOError { io_error },
+                EncodeError::IOError {
+                    io_error: other_io_error,
+                },
+            ) => io_error.kind() == other_io_error.kind(),
+            (
+                EncodeError::JsonError { json_error },
+                EncodeError::JsonError {
+                    json_error: other_json_error,
+                },
+            ) => json_error.classify() == other_json_error.classify(),
+            (
+                EncodeError::InvalidIdentifier { value },
+                EncodeError::InvalidIdentifier { value: other_value },
+            ) => value == other_value,
+            (
+                EncodeError::PayloadTooLong { length, max_length },
+                EncodeError::PayloadTooLong {
+                    length: other_length,
+                    max_length: other_max_length,
+                },
+            ) => length == other_length && max_length == other_max_length,
+            (
+                EncodeError::PacketTooLong { length, max_length },
+                EncodeError::PacketTooLong {
+                    length: other_length,
+                    max_length: other_max_length,
+                },
+            ) => length == other_length && max_length == other_max_length,
+            (
+                EncodeError::JsonTooLong { length, max_length },
+                EncodeError::JsonTooLong {
+                    length: other_length,
+                    max_length: other_max_length,
+                },
+            ) => length == other_length && max_length == other_max_length,
+            (
+                EncodeError::MalformedPacket { id, reason },
+                EncodeError::MalformedPacket {
+                    id: other_id,
+                    reason: other_reason,
+                },
+            ) => id == other_id && reason == other_reason,
+            (
+                EncodeError::PositionOutOfRange { x, y, z },
+                EncodeError::PositionOutOfRange {
+                    x: other_x,
+                    y: other_y,
+                    z: other_z,
+                },
+            ) => x == other_x && y == other_y && z == other_z,
+            (
+                EncodeError::LengthOutOfRange { length },
+                EncodeError::LengthOutOfRange {
+                    length: other_length,
+                },
+            ) => length == other_length,
+            (
+                EncodeError::WrongState {
+                    name,
+                    packet_state,
+                    state,
+                },
+                EncodeError::WrongState {
+                    name: other_name,
+                    packet_state: other_packet_state,
+                    state: other_state,
+                },
+            ) => name == other_name && packet_state == other_packet_state && state == other_state,
+            _ => false,
+        }
+    }
 }
 
 impl From<IoError> for EncodeError {
@@ -41,11 +207,16 @@ pub enum DecodeError {
     UnknownPacketType {
         type_id: u8,
     },
+    /// Packet id doesn't fit into byte, so no packet enum has it.
+    PacketIdOutOfRange {
+        id: i32,
+    },
     /// String length can't be more than provided value.
     StringTooLong {
-        /// String length.
+        /// String length in UTF-16 code units, or in bytes if there are more than 3 bytes per max
+        /// length unit.
         length: usize,
-        /// Max string length.
+        /// Max string length in UTF-16 code units.
         max_length: u16,
     },
     IOError {
@@ -60,6 +231,8 @@ pub enum DecodeError {
     },
     /// Boolean are parsed from byte. Valid byte value are 0 or 1.
     NonBoolValue,
+    /// Value of non-zero integer type was zero.
+    ZeroValue,
     UuidParseError {
         uuid_parse_error: UuidParseError,
     },
@@ -67,12 +240,84 @@ pub enum DecodeError {
     UnknownEnumType {
         type_id: usize,
     },
+    /// Length prefix of array or collection is negative.
+    NegativeLength {
+        length: i32,
+    },
     TagDecodeError {
         tag_decode_error: TagDecodeError,
     },
     VarIntTooLong {
         max_bytes: usize,
     },
+    /// Packet length can't be more than provided value.
+    PacketTooLong {
+        /// Packet length.
+        length: usize,
+        /// Max packet length.
+        max_length: usize,
+    },
+    /// Frame ended before all bytes were received. Reading can be retried once more data arrives.
+    Incomplete {
+        /// Number of missing bytes if known.
+        needed: Option<usize>,
+    },
+    /// Packet doesn't match compression format, usually because peers disagree whether
+    /// compression is enabled or what the threshold is.
+    CompressionMismatch {
+        /// Uncompressed data length sent by peer.
+        data_length: usize,
+        /// Compression threshold.
+        threshold: usize,
+    },
+    /// JSON length can't be more than limit.
+    JsonTooLong {
+        /// JSON length in bytes.
+        length: usize,
+        /// Max JSON length in bytes.
+        max_length: usize,
+    },
+    /// JSON objects and arrays nesting can't be deeper than limit.
+    JsonTooDeep {
+        max_depth: usize,
+    },
+    /// NBT data can't be longer than limit.
+    NbtTooLong {
+        max_length: usize,
+    },
+    /// NBT compound and list tags nesting can't be deeper than limit.
+    NbtTooDeep {
+        max_depth: usize,
+    },
+    /// NBT data can't have more tags than limit.
+    NbtTooManyTags {
+        max_tags: usize,
+    },
+    /// Identifier contains characters which are not allowed.
+    InvalidIdentifier {
+        value: String,
+    },
+    /// Plugin message payload length can't be more than limit.
+    PayloadTooLong {
+        /// Payload length.
+        length: usize,
+        /// Max payload length.
+        max_length: usize,
+    },
+    /// Peer didn't complete connection state in time.
+    Timeout {
+        timeout_error: TimeoutError,
+    },
+    /// Packets of protocol version are not compiled, its cargo feature is disabled.
+    UnsupportedVersion {
+        protocol_version: i32,
+    },
+}
+
+impl From<TimeoutError> for DecodeError {
+    fn from(timeout_error: TimeoutError) -> Self {
+        DecodeError::Timeout { timeout_error }
+    }
 }
 
 impl From<IoError> for DecodeError {
@@ -104,3 +349,191 @@ impl From<TagDecodeError> for DecodeError {
         DecodeError::TagDecodeError { tag_decode_error }
     }
 }
+
+/// Possible errors while translating packet between protocol versions.
+#[derive(Debug, PartialEq)]
+pub enum TranslateError {
+    /// Packet can't be translated to target protocol version.
+    UnsupportedPacket {
+        /// Packet name.
+        name: &'static str,
+    },
+    /// Field value can't be represented in target protocol version.
+    UnrepresentableValue {
+        /// Field name.
+        field: &'static str,
+        /// Field value.
+        value: String,
+    },
+}
+
+/// Possible errors while building packet with generated builder.
+#[derive(Debug, PartialEq)]
+pub enum BuildError {
+    /// Required field was not set.
+    MissingField {
+        /// Field name.
+        field: &'static str,
+    },
+}
+
+/// Possible errors while keeping connection alive.
+#[derive(Debug)]
+pub enum KeepAliveError {
+    /// Peer didn't answer or send keep-alive in time.
+    TimedOut {
+        /// Time since last keep-alive.
+        elapsed: Duration,
+    },
+    DecodeError {
+        decode_error: DecodeError,
+    },
+    EncodeError {
+        encode_error: EncodeError,
+    },
+}
+
+impl From<DecodeError> for KeepAliveError {
+    fn from(decode_error: DecodeError) -> Self {
+        KeepAliveError::DecodeError { decode_error }
+    }
+}
+
+impl From<EncodeError> for KeepAliveError {
+    fn from(encode_error: EncodeError) -> Self {
+        KeepAliveError::EncodeError { encode_error }
+    }
+}
+
+/// Peer stalled in connection state for longer than allowed by timeout policy.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TimeoutError {
+    /// State which stalled.
+    pub state: ProtocolState,
+    /// Time allowed in state.
+    pub limit: Duration,
+    /// Time spent in state.
+    pub elapsed: Duration,
+}
+
+/// Possible errors while running fake client script.
+#[derive(Debug)]
+pub enum ScriptError {
+    /// Peer sent other packet than expected, `expected` is `None` if no packet was expected.
+    UnexpectedPacket {
+        expected: Option<&'static str>,
+        name: &'static str,
+    },
+    DecodeError {
+        decode_error: DecodeError,
+    },
+    EncodeError {
+        encode_error: EncodeError,
+    },
+}
+
+impl From<DecodeError> for ScriptError {
+    fn from(decode_error: DecodeError) -> Self {
+        ScriptError::DecodeError { decode_error }
+    }
+}
+
+impl From<EncodeError> for ScriptError {
+    fn from(encode_error: EncodeError) -> Self {
+        ScriptError::EncodeError { encode_error }
+    }
+}
+
+/// Possible errors while pinging server.
+#[derive(Debug)]
+pub enum PingError {
+    /// Server closed connection before answering.
+    ConnectionClosed,
+    /// Server didn't answer in time.
+    Timeout,
+    /// Server answered with data which is not a valid response.
+    InvalidResponse {
+        decode_error: DecodeError,
+        /// Time until response was received, known if a complete frame arrived.
+        latency: Option<Duration>,
+    },
+    IOError {
+        io_error: IoError,
+    },
+    EncodeError {
+        encode_error: EncodeError,
+    },
+}
+
+impl From<DecodeError> for PingError {
+    fn from(decode_error: DecodeError) -> Self {
+        match decode_error {
+            DecodeError::IOError { io_error } => PingError::from(io_error),
+            DecodeError::Timeout { .. } => PingError::Timeout,
+            decode_error => PingError::InvalidResponse {
+                decode_error,
+                latency: None,
+            },
+        }
+    }
+}
+
+impl From<EncodeError> for PingError {
+    fn from(encode_error: EncodeError) -> Self {
+        match encode_error {
+            EncodeError::IOError { io_error } => PingError::from(io_error),
+            encode_error => PingError::EncodeError { encode_error },
+        }
+    }
+}
+
+impl From<IoError> for PingError {
+    fn from(io_error: IoError) -> Self {
+        match io_error.kind() {
+            ErrorKind::UnexpectedEof
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::BrokenPipe => PingError::ConnectionClosed,
+            ErrorKind::TimedOut | ErrorKind::WouldBlock => PingError::Timeout,
+            _ => PingError::IOError { io_error },
+        }
+    }
+}
+
+/// Possible errors while restoring exported state.
+#[derive(Debug, PartialEq)]
+pub enum StateError {
+    /// State was exported in format which is not supported.
+    UnsupportedVersion { version: u32 },
+}
+
+/// Possible errors while verifying chat message signature.
+#[derive(Debug, PartialEq)]
+pub enum SigningError {
+    /// Public key is not valid RSA key in X.509 format.
+    InvalidKey,
+    /// Signature doesn't match signed data.
+    InvalidSignature,
+}
+
+/// Possible errors while parsing chat color.
+#[derive(Debug, PartialEq)]
+pub enum ColorError {
+    /// Hex color is not in `#RRGGBB` format.
+    InvalidHex { value: String },
+    /// Name is not one of named colors.
+    UnknownName { value: String },
+}
+
+/// Possible errors while parsing server address.
+#[derive(Debug, PartialEq)]
+pub enum AddressError {
+    /// Address has no host.
+    EmptyHost,
+    /// Host has empty label or label which is too long.
+    InvalidHost { host: String },
+    /// Host in ASCII form is longer than handshake allows.
+    HostTooLong { length: usize, max_length: usize },
+    /// Port is not a number in range 1-65535.
+    InvalidPort { value: String },
+}
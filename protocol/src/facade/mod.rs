@@ -0,0 +1,207 @@
+//! Packets which don't depend on protocol version.
+//!
+//! Application logic written against facade types keeps compiling when a new protocol version
+//! is added. Each version converts its packets with `From` when nothing is lost and `TryFrom`
+//! returning `TranslateError` when facade value can't be represented, e.g. hex colors before 1.16.
+//! Fields missing in facade are filled with defaults.
+//!
+//! Facade covers chat, disconnect, keep-alive, ping, client status and join game packets. Player
+//! movement is not covered, version modules don't have position packets yet.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_16_5")]
+//! # {
+//! use minecraft_protocol::facade::ChatMessageIn;
+//! use minecraft_protocol::version::{v1_14_4, v1_16_5};
+//!
+//! fn reply(chat_message: ChatMessageIn) -> ChatMessageIn {
+//!     ChatMessageIn::new(format!("Echo: {}", chat_message.message))
+//! }
+//!
+//! let chat_message = v1_16_5::game::ServerBoundChatMessage {
+//!     message: String::from("Hello"),
+//! };
+//! let answer: v1_14_4::game::ServerBoundChatMessage = reply(chat_message.into()).into();
+//!
+//! assert_eq!(answer.message, "Echo: Hello");
+//! # }
+//! ```
+use crate::data::chat::Message;
+use crate::data::game::{ClientStatusAction, MessagePosition};
+#[cfg(any(feature = "v1_19", feature = "v1_20_2"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+#[cfg(feature = "legacy")]
+mod v1_12_2;
+#[cfg(feature = "v1_14_4")]
+mod v1_14_4;
+#[cfg(feature = "v1_16_5")]
+mod v1_16_5;
+#[cfg(feature = "v1_19")]
+mod v1_19;
+#[cfg(feature = "v1_20_2")]
+mod v1_20_2;
+#[cfg(feature = "legacy")]
+mod v1_8;
+
+/// Chat message or command sent by client.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ChatMessageIn {
+    pub message: String,
+}
+
+impl ChatMessageIn {
+    pub fn new(message: String) -> Self {
+        ChatMessageIn { message }
+    }
+}
+
+/// Chat message sent by server before 1.19.
+#[derive(Debug, Eq, PartialEq)]
+pub struct ChatMessageOut {
+    pub message: Message,
+    pub position: MessagePosition,
+    /// Player who sent the message, dropped before 1.16.
+    pub sender: Option<Uuid>,
+}
+
+impl ChatMessageOut {
+    pub fn new(message: Message, position: MessagePosition) -> Self {
+        ChatMessageOut {
+            message,
+            position,
+            sender: None,
+        }
+    }
+}
+
+/// Disconnect sent by server in game state.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Disconnect {
+    pub reason: Message,
+}
+
+impl Disconnect {
+    pub fn new(reason: Message) -> Self {
+        Disconnect { reason }
+    }
+}
+
+/// Keep-alive of either direction, used before 1.19.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct KeepAlive {
+    pub id: u64,
+}
+
+impl KeepAlive {
+    pub fn new(id: u64) -> Self {
+        KeepAlive { id }
+    }
+}
+
+/// Ping or pong of game state since 1.17.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Ping {
+    pub id: i32,
+}
+
+impl Ping {
+    pub fn new(id: i32) -> Self {
+        Ping { id }
+    }
+}
+
+/// Respawn or statistics request sent by client.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ClientStatus {
+    pub action: ClientStatusAction,
+}
+
+impl ClientStatus {
+    pub fn new(action: ClientStatusAction) -> Self {
+        ClientStatus { action }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GameMode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+/// Player and world settings sent by server when player joins game.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct JoinGameInfo {
+    pub entity_id: u32,
+    pub game_mode: GameMode,
+    pub hardcore: bool,
+    pub max_players: u8,
+    pub view_distance: i32,
+    pub reduced_debug_info: bool,
+}
+
+/// Implements conversions of `ClientStatus` and `Ping` packets, which are the same since 1.19.
+#[cfg(any(feature = "v1_19", feature = "v1_20_2", feature = "v1_20_5"))]
+macro_rules! impl_ping_client_status_facade (
+    ($version: ident) => (
+        impl From<crate::version::$version::game::ClientStatus> for ClientStatus {
+            fn from(client_status: crate::version::$version::game::ClientStatus) -> Self {
+                ClientStatus {
+                    action: client_status.action,
+                }
+            }
+        }
+
+        impl From<ClientStatus> for crate::version::$version::game::ClientStatus {
+            fn from(client_status: ClientStatus) -> Self {
+                crate::version::$version::game::ClientStatus {
+                    action: client_status.action,
+                }
+            }
+        }
+
+        impl From<crate::version::$version::game::Ping> for Ping {
+            fn from(ping: crate::version::$version::game::Ping) -> Self {
+                Ping { id: ping.id }
+            }
+        }
+
+        impl From<Ping> for crate::version::$version::game::Ping {
+            fn from(ping: Ping) -> Self {
+                crate::version::$version::game::Ping { id: ping.id }
+            }
+        }
+
+        impl From<crate::version::$version::game::Pong> for Ping {
+            fn from(pong: crate::version::$version::game::Pong) -> Self {
+                Ping { id: pong.id }
+            }
+        }
+
+        impl From<Ping> for crate::version::$version::game::Pong {
+            fn from(ping: Ping) -> Self {
+                crate::version::$version::game::Pong { id: ping.id }
+            }
+        }
+    );
+);
+
+#[cfg(feature = "v1_19")]
+impl_ping_client_status_facade!(v1_19);
+#[cfg(feature = "v1_20_2")]
+impl_ping_client_status_facade!(v1_20_2);
+#[cfg(feature = "v1_20_5")]
+impl_ping_client_status_facade!(v1_20_5);
+
+/// Returns milliseconds since epoch, timestamp of chat messages sent since 1.19.
+#[cfg(any(feature = "v1_19", feature = "v1_20_2"))]
+fn timestamp_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as i64)
+}
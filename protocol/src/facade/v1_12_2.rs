@@ -0,0 +1,27 @@
+//! Conversions of 1.12.2 packets.
+use crate::facade::KeepAlive;
+use crate::version::v1_12_2::game as v1_12_2;
+
+impl From<v1_12_2::ServerBoundKeepAlive> for KeepAlive {
+    fn from(keep_alive: v1_12_2::ServerBoundKeepAlive) -> Self {
+        KeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<KeepAlive> for v1_12_2::ServerBoundKeepAlive {
+    fn from(keep_alive: KeepAlive) -> Self {
+        v1_12_2::ServerBoundKeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<v1_12_2::ClientBoundKeepAlive> for KeepAlive {
+    fn from(keep_alive: v1_12_2::ClientBoundKeepAlive) -> Self {
+        KeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<KeepAlive> for v1_12_2::ClientBoundKeepAlive {
+    fn from(keep_alive: KeepAlive) -> Self {
+        v1_12_2::ClientBoundKeepAlive { id: keep_alive.id }
+    }
+}
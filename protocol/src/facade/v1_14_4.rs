@@ -0,0 +1,216 @@
+//! Conversions of 1.14.4 packets.
+//!
+//! Hardcore is a game mode in 1.14.4 and is possible only in survival. Join game is sent with
+//! overworld dimension and default level type.
+use crate::error::TranslateError;
+use crate::facade::{
+    ChatMessageIn, ChatMessageOut, ClientStatus, Disconnect, GameMode, JoinGameInfo, KeepAlive,
+};
+use crate::translate::downgrade_message;
+use crate::version::v1_14_4::game as v1_14_4;
+use std::convert::TryFrom;
+
+impl From<v1_14_4::ServerBoundChatMessage> for ChatMessageIn {
+    fn from(chat_message: v1_14_4::ServerBoundChatMessage) -> Self {
+        ChatMessageIn {
+            message: chat_message.message,
+        }
+    }
+}
+
+impl From<ChatMessageIn> for v1_14_4::ServerBoundChatMessage {
+    fn from(chat_message: ChatMessageIn) -> Self {
+        v1_14_4::ServerBoundChatMessage {
+            message: chat_message.message,
+        }
+    }
+}
+
+impl From<v1_14_4::ClientBoundChatMessage> for ChatMessageOut {
+    fn from(chat_message: v1_14_4::ClientBoundChatMessage) -> Self {
+        ChatMessageOut {
+            message: chat_message.message,
+            position: chat_message.position,
+            sender: None,
+        }
+    }
+}
+
+impl TryFrom<ChatMessageOut> for v1_14_4::ClientBoundChatMessage {
+    type Error = TranslateError;
+
+    fn try_from(chat_message: ChatMessageOut) -> Result<Self, Self::Error> {
+        Ok(v1_14_4::ClientBoundChatMessage {
+            message: downgrade_message(chat_message.message)?,
+            position: chat_message.position,
+        })
+    }
+}
+
+impl From<v1_14_4::GameDisconnect> for Disconnect {
+    fn from(game_disconnect: v1_14_4::GameDisconnect) -> Self {
+        Disconnect {
+            reason: game_disconnect.reason,
+        }
+    }
+}
+
+impl TryFrom<Disconnect> for v1_14_4::GameDisconnect {
+    type Error = TranslateError;
+
+    fn try_from(disconnect: Disconnect) -> Result<Self, Self::Error> {
+        Ok(v1_14_4::GameDisconnect {
+            reason: downgrade_message(disconnect.reason)?,
+        })
+    }
+}
+
+impl From<v1_14_4::ServerBoundKeepAlive> for KeepAlive {
+    fn from(keep_alive: v1_14_4::ServerBoundKeepAlive) -> Self {
+        KeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<KeepAlive> for v1_14_4::ServerBoundKeepAlive {
+    fn from(keep_alive: KeepAlive) -> Self {
+        v1_14_4::ServerBoundKeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<v1_14_4::ClientBoundKeepAlive> for KeepAlive {
+    fn from(keep_alive: v1_14_4::ClientBoundKeepAlive) -> Self {
+        KeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<KeepAlive> for v1_14_4::ClientBoundKeepAlive {
+    fn from(keep_alive: KeepAlive) -> Self {
+        v1_14_4::ClientBoundKeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<v1_14_4::ClientStatus> for ClientStatus {
+    fn from(client_status: v1_14_4::ClientStatus) -> Self {
+        ClientStatus {
+            action: client_status.action,
+        }
+    }
+}
+
+impl From<ClientStatus> for v1_14_4::ClientStatus {
+    fn from(client_status: ClientStatus) -> Self {
+        v1_14_4::ClientStatus {
+            action: client_status.action,
+        }
+    }
+}
+
+impl From<v1_14_4::JoinGame> for JoinGameInfo {
+    fn from(join_game: v1_14_4::JoinGame) -> Self {
+        let (game_mode, hardcore) = match join_game.game_mode {
+            v1_14_4::GameMode::Survival => (GameMode::Survival, false),
+            v1_14_4::GameMode::Creative => (GameMode::Creative, false),
+            v1_14_4::GameMode::Adventure => (GameMode::Adventure, false),
+            v1_14_4::GameMode::Spectator => (GameMode::Spectator, false),
+            v1_14_4::GameMode::Hardcore => (GameMode::Survival, true),
+        };
+
+        JoinGameInfo {
+            entity_id: join_game.entity_id,
+            game_mode,
+            hardcore,
+            max_players: join_game.max_players,
+            view_distance: join_game.view_distance,
+            reduced_debug_info: join_game.reduced_debug_info,
+        }
+    }
+}
+
+impl TryFrom<JoinGameInfo> for v1_14_4::JoinGame {
+    type Error = TranslateError;
+
+    fn try_from(join_game_info: JoinGameInfo) -> Result<Self, Self::Error> {
+        let game_mode = match (join_game_info.game_mode, join_game_info.hardcore) {
+            (GameMode::Survival, false) => v1_14_4::GameMode::Survival,
+            (GameMode::Creative, false) => v1_14_4::GameMode::Creative,
+            (GameMode::Adventure, false) => v1_14_4::GameMode::Adventure,
+            (GameMode::Spectator, false) => v1_14_4::GameMode::Spectator,
+            (GameMode::Survival, true) => v1_14_4::GameMode::Hardcore,
+            (game_mode, true) => {
+                return Err(TranslateError::UnrepresentableValue {
+                    field: "game_mode",
+                    value: format!("hardcore {:?}", game_mode),
+                })
+            }
+        };
+
+        Ok(v1_14_4::JoinGame {
+            entity_id: join_game_info.entity_id,
+            game_mode,
+            dimension: 0,
+            max_players: join_game_info.max_players,
+            level_type: String::from("default"),
+            view_distance: join_game_info.view_distance,
+            reduced_debug_info: join_game_info.reduced_debug_info,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::chat::{Color, MessageBuilder, Payload};
+    use crate::error::TranslateError;
+    use crate::facade::{ChatMessageOut, GameMode, JoinGameInfo};
+    use crate::version::v1_14_4::game as v1_14_4;
+    use std::convert::TryFrom;
+
+    fn join_game_info(game_mode: GameMode, hardcore: bool) -> JoinGameInfo {
+        JoinGameInfo {
+            entity_id: 7,
+            game_mode,
+            hardcore,
+            max_players: 20,
+            view_distance: 10,
+            reduced_debug_info: false,
+        }
+    }
+
+    #[test]
+    fn test_join_game_hardcore_round_trip() {
+        let join_game =
+            v1_14_4::JoinGame::try_from(join_game_info(GameMode::Survival, true)).unwrap();
+
+        assert_eq!(join_game.game_mode, v1_14_4::GameMode::Hardcore);
+        assert_eq!(join_game.level_type, "default");
+        assert_eq!(
+            JoinGameInfo::from(join_game),
+            join_game_info(GameMode::Survival, true)
+        );
+    }
+
+    #[test]
+    fn test_join_game_hardcore_creative() {
+        let translate_error = v1_14_4::JoinGame::try_from(join_game_info(GameMode::Creative, true))
+            .expect_err("Expected error `UnrepresentableValue` because hardcore is survival");
+
+        match translate_error {
+            TranslateError::UnrepresentableValue { field, .. } => assert_eq!(field, "game_mode"),
+            _ => panic!(
+                "Expected `UnrepresentableValue` but got `{:?}`",
+                translate_error
+            ),
+        }
+    }
+
+    #[test]
+    fn test_chat_message_out_hex_color() {
+        let message = MessageBuilder::builder(Payload::text("Hello"))
+            .color(Color::Hex(String::from("#ff4f4f")))
+            .build();
+        let chat_message = ChatMessageOut::new(message, v1_14_4::MessagePosition::Chat);
+
+        let chat_message = v1_14_4::ClientBoundChatMessage::try_from(chat_message).unwrap();
+
+        assert_eq!(chat_message.message.color, Some(Color::Red));
+    }
+}
@@ -0,0 +1,126 @@
+//! Conversions of 1.16.5 packets.
+//!
+//! Chat messages not sent by a player have nil sender.
+use crate::facade::{ChatMessageIn, ChatMessageOut, ClientStatus, Disconnect, KeepAlive};
+use crate::version::v1_16_5::game as v1_16_5;
+use uuid::Uuid;
+
+impl From<v1_16_5::ServerBoundChatMessage> for ChatMessageIn {
+    fn from(chat_message: v1_16_5::ServerBoundChatMessage) -> Self {
+        ChatMessageIn {
+            message: chat_message.message,
+        }
+    }
+}
+
+impl From<ChatMessageIn> for v1_16_5::ServerBoundChatMessage {
+    fn from(chat_message: ChatMessageIn) -> Self {
+        v1_16_5::ServerBoundChatMessage {
+            message: chat_message.message,
+        }
+    }
+}
+
+impl From<v1_16_5::ClientBoundChatMessage> for ChatMessageOut {
+    fn from(chat_message: v1_16_5::ClientBoundChatMessage) -> Self {
+        let sender = Some(chat_message.sender).filter(|sender| !sender.is_nil());
+
+        ChatMessageOut {
+            message: chat_message.message,
+            position: chat_message.position,
+            sender,
+        }
+    }
+}
+
+impl From<ChatMessageOut> for v1_16_5::ClientBoundChatMessage {
+    fn from(chat_message: ChatMessageOut) -> Self {
+        v1_16_5::ClientBoundChatMessage {
+            message: chat_message.message,
+            position: chat_message.position,
+            sender: chat_message.sender.unwrap_or_else(Uuid::nil),
+        }
+    }
+}
+
+impl From<v1_16_5::GameDisconnect> for Disconnect {
+    fn from(game_disconnect: v1_16_5::GameDisconnect) -> Self {
+        Disconnect {
+            reason: game_disconnect.reason,
+        }
+    }
+}
+
+impl From<Disconnect> for v1_16_5::GameDisconnect {
+    fn from(disconnect: Disconnect) -> Self {
+        v1_16_5::GameDisconnect {
+            reason: disconnect.reason,
+        }
+    }
+}
+
+impl From<v1_16_5::ServerBoundKeepAlive> for KeepAlive {
+    fn from(keep_alive: v1_16_5::ServerBoundKeepAlive) -> Self {
+        KeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<KeepAlive> for v1_16_5::ServerBoundKeepAlive {
+    fn from(keep_alive: KeepAlive) -> Self {
+        v1_16_5::ServerBoundKeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<v1_16_5::ClientBoundKeepAlive> for KeepAlive {
+    fn from(keep_alive: v1_16_5::ClientBoundKeepAlive) -> Self {
+        KeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<KeepAlive> for v1_16_5::ClientBoundKeepAlive {
+    fn from(keep_alive: KeepAlive) -> Self {
+        v1_16_5::ClientBoundKeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<v1_16_5::ClientStatus> for ClientStatus {
+    fn from(client_status: v1_16_5::ClientStatus) -> Self {
+        ClientStatus {
+            action: client_status.action,
+        }
+    }
+}
+
+impl From<ClientStatus> for v1_16_5::ClientStatus {
+    fn from(client_status: ClientStatus) -> Self {
+        v1_16_5::ClientStatus {
+            action: client_status.action,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::chat::Message;
+    use crate::facade::ChatMessageOut;
+    use crate::version::v1_16_5::game as v1_16_5;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_chat_message_out_sender() {
+        let sender = Uuid::new_v4();
+        let chat_message = v1_16_5::ClientBoundChatMessage {
+            message: Message::from_str("Hello"),
+            position: v1_16_5::MessagePosition::Chat,
+            sender,
+        };
+
+        let chat_message = ChatMessageOut::from(chat_message);
+        assert_eq!(chat_message.sender, Some(sender));
+
+        let system_message =
+            ChatMessageOut::new(Message::from_str("Hello"), v1_16_5::MessagePosition::System);
+        let system_message = v1_16_5::ClientBoundChatMessage::from(system_message);
+        assert_eq!(system_message.sender, Uuid::nil());
+    }
+}
@@ -0,0 +1,43 @@
+//! Conversions of 1.19 packets.
+//!
+//! Chat messages are sent unsigned, with current time and zero salt.
+use crate::facade::{timestamp_now, ChatMessageIn};
+use crate::version::v1_19::game as v1_19;
+
+impl From<v1_19::ChatMessage> for ChatMessageIn {
+    fn from(chat_message: v1_19::ChatMessage) -> Self {
+        ChatMessageIn {
+            message: chat_message.message,
+        }
+    }
+}
+
+impl From<ChatMessageIn> for v1_19::ChatMessage {
+    fn from(chat_message: ChatMessageIn) -> Self {
+        v1_19::ChatMessage {
+            message: chat_message.message,
+            timestamp: timestamp_now(),
+            salt: 0,
+            signature: Vec::new(),
+            signed_preview: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::facade::ChatMessageIn;
+    use crate::version::v1_19::game as v1_19;
+
+    #[test]
+    fn test_chat_message_in_round_trip() {
+        let chat_message = v1_19::ChatMessage::from(ChatMessageIn::new(String::from("Hello")));
+
+        assert!(chat_message.signature.is_empty());
+        assert!(chat_message.timestamp > 0);
+        assert_eq!(
+            ChatMessageIn::from(chat_message),
+            ChatMessageIn::new(String::from("Hello"))
+        );
+    }
+}
@@ -0,0 +1,30 @@
+//! Conversions of 1.20.2 packets.
+//!
+//! Chat messages are sent unsigned, with current time, zero salt and no change of last seen
+//! messages.
+use crate::data::message_signature::LastSeenUpdate;
+use crate::facade::{timestamp_now, ChatMessageIn};
+use crate::version::v1_20_2::game as v1_20_2;
+
+impl From<v1_20_2::ChatMessage> for ChatMessageIn {
+    fn from(chat_message: v1_20_2::ChatMessage) -> Self {
+        ChatMessageIn {
+            message: chat_message.message,
+        }
+    }
+}
+
+impl From<ChatMessageIn> for v1_20_2::ChatMessage {
+    fn from(chat_message: ChatMessageIn) -> Self {
+        v1_20_2::ChatMessage {
+            message: chat_message.message,
+            timestamp: timestamp_now(),
+            salt: 0,
+            signature: None,
+            last_seen: LastSeenUpdate {
+                offset: 0,
+                acknowledged: 0,
+            },
+        }
+    }
+}
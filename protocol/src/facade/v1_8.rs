@@ -0,0 +1,83 @@
+//! Conversions of 1.8 packets.
+//!
+//! Keep-alive id is a 32-bit integer, its bits are kept as unsigned id of facade. Larger ids
+//! can't be converted.
+use crate::error::TranslateError;
+use crate::facade::KeepAlive;
+use crate::version::v1_8::game as v1_8;
+use std::convert::TryFrom;
+
+impl From<v1_8::ServerBoundKeepAlive> for KeepAlive {
+    fn from(keep_alive: v1_8::ServerBoundKeepAlive) -> Self {
+        KeepAlive {
+            id: keep_alive.id as u32 as u64,
+        }
+    }
+}
+
+impl TryFrom<KeepAlive> for v1_8::ServerBoundKeepAlive {
+    type Error = TranslateError;
+
+    fn try_from(keep_alive: KeepAlive) -> Result<Self, Self::Error> {
+        Ok(v1_8::ServerBoundKeepAlive {
+            id: keep_alive_id(keep_alive)?,
+        })
+    }
+}
+
+impl From<v1_8::ClientBoundKeepAlive> for KeepAlive {
+    fn from(keep_alive: v1_8::ClientBoundKeepAlive) -> Self {
+        KeepAlive {
+            id: keep_alive.id as u32 as u64,
+        }
+    }
+}
+
+impl TryFrom<KeepAlive> for v1_8::ClientBoundKeepAlive {
+    type Error = TranslateError;
+
+    fn try_from(keep_alive: KeepAlive) -> Result<Self, Self::Error> {
+        Ok(v1_8::ClientBoundKeepAlive {
+            id: keep_alive_id(keep_alive)?,
+        })
+    }
+}
+
+fn keep_alive_id(keep_alive: KeepAlive) -> Result<i32, TranslateError> {
+    u32::try_from(keep_alive.id)
+        .map(|id| id as i32)
+        .map_err(|_| TranslateError::UnrepresentableValue {
+            field: "id",
+            value: keep_alive.id.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::TranslateError;
+    use crate::facade::KeepAlive;
+    use crate::version::v1_8::game as v1_8;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_negative_keep_alive_round_trip() {
+        let keep_alive = KeepAlive::from(v1_8::ClientBoundKeepAlive { id: -5 });
+        let keep_alive = v1_8::ClientBoundKeepAlive::try_from(keep_alive).unwrap();
+
+        assert_eq!(keep_alive.id, -5);
+    }
+
+    #[test]
+    fn test_keep_alive_id_too_large() {
+        let translate_error = v1_8::ServerBoundKeepAlive::try_from(KeepAlive::new(1 << 40))
+            .expect_err("Expected error `UnrepresentableValue` because id has more than 32 bits");
+
+        match translate_error {
+            TranslateError::UnrepresentableValue { field, .. } => assert_eq!(field, "id"),
+            _ => panic!(
+                "Expected `UnrepresentableValue` but got `{:?}`",
+                translate_error
+            ),
+        }
+    }
+}
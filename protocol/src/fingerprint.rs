@@ -0,0 +1,234 @@
+//! Client fingerprints for bot detection.
+//!
+//! Bots often send the same handshake as real clients but behave differently: they send packets
+//! in another order, skip the brand plugin message or reply faster than a human can. Server side
+//! `ProtocolDriver` records what client sent during the first packets into `ClientFingerprint`,
+//! heuristics are up to the server.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::driver::{ProtocolDriver, Side};
+//! use minecraft_protocol::packet::RawPacket;
+//! use minecraft_protocol::version::v1_14_4::handshake::{Handshake, NextState};
+//!
+//! let mut driver = ProtocolDriver::new(Side::Server);
+//!
+//! let handshake = Handshake::new(498, String::from("localhost"), 25565, NextState::Login);
+//! let mut bytes = Vec::new();
+//! RawPacket::from_packet(&handshake).unwrap().encode(&mut bytes).unwrap();
+//!
+//! driver.receive(&bytes);
+//! driver.poll_event().unwrap();
+//!
+//! let fingerprint = driver.fingerprint().unwrap();
+//! assert_eq!(fingerprint.protocol_version, Some(498));
+//! assert_eq!(fingerprint.brand, None);
+//! # }
+//! ```
+use crate::decoder::DecoderReadExt;
+use crate::driver::ProtocolState;
+use crate::packet::RawPacket;
+use crate::STRING_MAX_LENGTH;
+use byteorder::{BigEndian, ReadBytesExt};
+use std::time::{Duration, Instant};
+
+/// Only the first packets are recorded, later ones don't tell much about the client.
+pub const MAX_RECORDED_PACKETS: usize = 32;
+
+const BRAND_CHANNEL: &str = "minecraft:brand";
+
+/// Packet received from client.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PacketRecord {
+    pub state: ProtocolState,
+    pub id: i32,
+    /// Length of packet data.
+    pub length: usize,
+    /// Time since the first packet was received.
+    pub elapsed: Duration,
+}
+
+/// What client sent during handshake, login and the beginning of the game.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ClientFingerprint {
+    /// Protocol version declared in handshake.
+    pub protocol_version: Option<i32>,
+    /// Server address from handshake, modded clients append markers to it.
+    pub server_address: Option<String>,
+    pub server_port: Option<u16>,
+    pub username: Option<String>,
+    /// Brand from `minecraft:brand` plugin message. Recognized only for implemented versions.
+    pub brand: Option<String>,
+    /// Received packets in order, at most `MAX_RECORDED_PACKETS`.
+    pub packets: Vec<PacketRecord>,
+    first_received_at: Option<Instant>,
+}
+
+impl ClientFingerprint {
+    pub fn new() -> Self {
+        ClientFingerprint::default()
+    }
+
+    /// Records packet received in state at given time.
+    ///
+    /// Malformed packets are recorded, but fields which can't be parsed stay unset.
+    pub fn record(&mut self, state: ProtocolState, raw_packet: &RawPacket, received_at: Instant) {
+        if self.packets.len() >= MAX_RECORDED_PACKETS {
+            return;
+        }
+
+        let first_received_at = *self.first_received_at.get_or_insert(received_at);
+
+        self.packets.push(PacketRecord {
+            state,
+            id: raw_packet.id,
+            length: raw_packet.data.len(),
+            elapsed: received_at.saturating_duration_since(first_received_at),
+        });
+
+        let mut data = raw_packet.data.as_slice();
+
+        match (state, raw_packet.id) {
+            (ProtocolState::Handshake, 0x00) => {
+                self.protocol_version = data.read_var_i32().ok();
+                self.server_address = data.read_string(STRING_MAX_LENGTH).ok();
+                self.server_port = data.read_u16::<BigEndian>().ok();
+            }
+            (ProtocolState::Login, 0x00) => {
+                self.username = data.read_string(STRING_MAX_LENGTH).ok();
+            }
+            (ProtocolState::Game, id)
+                if Some(id) == self.brand_packet_id()
+                    && data.read_string(STRING_MAX_LENGTH).ok().as_deref()
+                        == Some(BRAND_CHANNEL) =>
+            {
+                self.brand = data.read_string(STRING_MAX_LENGTH).ok();
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns time from handshake to the first packet received in state.
+    pub fn elapsed_until(&self, state: ProtocolState) -> Option<Duration> {
+        self.packets
+            .iter()
+            .find(|record| record.state == state)
+            .map(|record| record.elapsed)
+    }
+
+    /// Returns ids of recorded packets received in state.
+    pub fn packet_ids(&self, state: ProtocolState) -> Vec<i32> {
+        self.packets
+            .iter()
+            .filter(|record| record.state == state)
+            .map(|record| record.id)
+            .collect()
+    }
+
+    /// Returns id of server bound plugin message in declared protocol version.
+    fn brand_packet_id(&self) -> Option<i32> {
+        match self.protocol_version? {
+            // 1.14.4 and 1.16.5.
+            498 | 754 => Some(0x0B),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::encoder::EncoderWriteExt;
+    use crate::fingerprint::*;
+    use crate::version::v1_14_4::game::{ServerBoundChatMessage, ServerBoundPluginMessage};
+    use crate::version::v1_14_4::handshake::{Handshake, NextState};
+    use crate::version::v1_14_4::login::LoginStart;
+
+    fn brand(brand: &str) -> RawPacket {
+        let mut data = Vec::new();
+        data.write_string(brand, STRING_MAX_LENGTH).unwrap();
+
+        let plugin_message = ServerBoundPluginMessage::new("minecraft:brand".into(), data).unwrap();
+
+        RawPacket::from_packet(&plugin_message).unwrap()
+    }
+
+    #[test]
+    fn test_record_session() {
+        let start = Instant::now();
+        let handshake = Handshake::new(
+            498,
+            String::from("localhost\0FML\0"),
+            25565,
+            NextState::Login,
+        );
+        let login_start = LoginStart::new(String::from("Username"));
+        let chat_message = ServerBoundChatMessage::new(String::from("minecraft:brand"));
+
+        let mut fingerprint = ClientFingerprint::new();
+        fingerprint.record(
+            ProtocolState::Handshake,
+            &RawPacket::from_packet(&handshake).unwrap(),
+            start,
+        );
+        fingerprint.record(
+            ProtocolState::Login,
+            &RawPacket::from_packet(&login_start).unwrap(),
+            start + Duration::from_millis(5),
+        );
+        fingerprint.record(
+            ProtocolState::Game,
+            &RawPacket::from_packet(&chat_message).unwrap(),
+            start + Duration::from_millis(40),
+        );
+        fingerprint.record(
+            ProtocolState::Game,
+            &brand("vanilla"),
+            start + Duration::from_millis(50),
+        );
+
+        assert_eq!(fingerprint.protocol_version, Some(498));
+        assert_eq!(
+            fingerprint.server_address.as_deref(),
+            Some("localhost\0FML\0")
+        );
+        assert_eq!(fingerprint.server_port, Some(25565));
+        assert_eq!(fingerprint.username.as_deref(), Some("Username"));
+        assert_eq!(fingerprint.brand.as_deref(), Some("vanilla"));
+        assert_eq!(fingerprint.packet_ids(ProtocolState::Game), [0x03, 0x0B]);
+        assert_eq!(
+            fingerprint.elapsed_until(ProtocolState::Game),
+            Some(Duration::from_millis(40))
+        );
+    }
+
+    #[test]
+    fn test_brand_of_unknown_version() {
+        let handshake = Handshake::new(1, String::from("localhost"), 25565, NextState::Login);
+        let now = Instant::now();
+
+        let mut fingerprint = ClientFingerprint::new();
+        fingerprint.record(
+            ProtocolState::Handshake,
+            &RawPacket::from_packet(&handshake).unwrap(),
+            now,
+        );
+        fingerprint.record(ProtocolState::Game, &brand("vanilla"), now);
+
+        assert_eq!(fingerprint.brand, None);
+    }
+
+    #[test]
+    fn test_max_recorded_packets() {
+        let mut fingerprint = ClientFingerprint::new();
+        let now = Instant::now();
+
+        for _ in 0..MAX_RECORDED_PACKETS + 1 {
+            fingerprint.record(ProtocolState::Game, &RawPacket::new(0x0F, vec![]), now);
+        }
+
+        assert_eq!(fingerprint.packets.len(), MAX_RECORDED_PACKETS);
+    }
+}
@@ -0,0 +1,165 @@
+//! Callbacks for typed packets.
+//!
+//! Instead of matching every decoded packet enum, handlers are registered per packet and called
+//! when such packet is dispatched. Context is passed to every handler, so they can share state
+//! without `Rc<RefCell<_>>`. `Connection::read_and_dispatch` calls handlers of every packet read,
+//! packets decoded by `Dispatcher` can be passed to `PacketHandlers::dispatch` directly.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::data::chat::Message;
+//! use minecraft_protocol::handler::PacketHandlers;
+//! use minecraft_protocol::version::v1_14_4::game::*;
+//!
+//! let mut handlers = PacketHandlers::new();
+//!
+//! handlers
+//!     .on(|chat_message: &ClientBoundChatMessage, log: &mut Vec<String>| {
+//!         log.push(chat_message.message.to_json().unwrap())
+//!     })
+//!     .on(|keep_alive: &ClientBoundKeepAlive, log: &mut Vec<String>| {
+//!         log.push(format!("keep alive {}", keep_alive.id))
+//!     });
+//!
+//! let mut log = Vec::new();
+//! let packet = ClientBoundChatMessage::new(Message::from_str("Hi"), MessagePosition::Chat);
+//!
+//! assert_eq!(handlers.dispatch(&packet, &mut log), 1);
+//! assert_eq!(log, [r#"{"text":"Hi"}"#]);
+//! # }
+//! ```
+
+/// Packet which is a variant of packet enum `P`.
+///
+/// Can be derived for packet enum with `#[derive(PacketVariants)]`.
+pub trait PacketVariant<P> {
+    /// Returns packet if enum holds it.
+    fn from_packet(packet: &P) -> Option<&Self>;
+
+    /// Takes packet out of enum, enum is returned back if it holds other packet.
+    fn try_from_packet(packet: P) -> Result<Self, P>
+    where
+        Self: Sized;
+}
+
+type Handler<P, C> = Box<dyn FnMut(&P, &mut C) -> bool>;
+
+/// Handlers of packets from enum `P` sharing context `C`.
+pub struct PacketHandlers<P, C> {
+    handlers: Vec<Handler<P, C>>,
+}
+
+impl<P, C> PacketHandlers<P, C> {
+    pub fn new() -> Self {
+        PacketHandlers {
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Registers handler called for packets of type `T`.
+    pub fn on<T, F>(&mut self, mut handler: F) -> &mut Self
+    where
+        T: PacketVariant<P>,
+        F: FnMut(&T, &mut C) + 'static,
+    {
+        self.handlers.push(Box::new(move |packet, context| {
+            match T::from_packet(packet) {
+                Some(value) => {
+                    handler(value, context);
+                    true
+                }
+                None => false,
+            }
+        }));
+
+        self
+    }
+
+    /// Registers handler called for every packet.
+    pub fn on_any<F>(&mut self, mut handler: F) -> &mut Self
+    where
+        F: FnMut(&P, &mut C) + 'static,
+    {
+        self.handlers.push(Box::new(move |packet, context| {
+            handler(packet, context);
+            true
+        }));
+
+        self
+    }
+
+    /// Calls handlers of packet in registration order and returns how many were called.
+    pub fn dispatch(&mut self, packet: &P, context: &mut C) -> usize {
+        self.handlers
+            .iter_mut()
+            .filter_map(|handler| handler(packet, context).then_some(()))
+            .count()
+    }
+
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl<P, C> Default for PacketHandlers<P, C> {
+    fn default() -> Self {
+        PacketHandlers::new()
+    }
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::data::chat::Message;
+    use crate::handler::*;
+    use crate::version::v1_14_4::game::*;
+
+    #[test]
+    fn test_dispatch_to_matching_handlers() {
+        let mut handlers = PacketHandlers::new();
+
+        handlers
+            .on(|keep_alive: &ClientBoundKeepAlive, ids: &mut Vec<u64>| ids.push(keep_alive.id))
+            .on(|_: &ClientBoundChatMessage, ids: &mut Vec<u64>| ids.push(0))
+            .on_any(|packet: &GameClientBoundPacket, ids: &mut Vec<u64>| {
+                ids.push(packet.get_type_id() as u64)
+            });
+
+        let mut ids = Vec::new();
+
+        assert_eq!(
+            handlers.dispatch(&ClientBoundKeepAlive::new(42), &mut ids),
+            2
+        );
+        assert_eq!(
+            handlers.dispatch(&GameDisconnect::new(Message::from_str("Bye")), &mut ids),
+            1
+        );
+        assert_eq!(ids, [42, 0x20, 0x1A]);
+        assert_eq!(handlers.len(), 3);
+    }
+
+    #[test]
+    fn test_from_packet() {
+        let packet = ClientBoundChatMessage::new(Message::from_str("Hi"), MessagePosition::Chat);
+
+        assert!(ClientBoundChatMessage::from_packet(&packet).is_some());
+        assert!(ClientBoundKeepAlive::from_packet(&packet).is_none());
+    }
+
+    #[test]
+    fn test_try_from_packet() {
+        let packet = ClientBoundKeepAlive::new(42);
+        let packet = ClientBoundChatMessage::try_from_packet(packet)
+            .expect_err("Expected packet back because enum holds keep-alive");
+
+        let keep_alive = ClientBoundKeepAlive::try_from_packet(packet).unwrap();
+        assert_eq!(keep_alive.id, 42);
+    }
+}
@@ -0,0 +1,446 @@
+//! Server addresses as typed by players and sent in handshake.
+//!
+//! Vanilla client converts host to ASCII before connecting: labels with unicode characters are
+//! lowercased and encoded with punycode, ASCII labels are kept as typed. If SRV record
+//! `_minecraft._tcp.<host>` exists, client connects to its target and sends that target in
+//! handshake, trailing dot of DNS name included. Servers matching virtual hosts should compare
+//! with `ServerAddress::matches`, which ignores the dot and data appended by mod loaders.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::handshake::ServerAddress;
+//!
+//! let address: ServerAddress = "Bücher.example:25566".parse().unwrap();
+//! assert_eq!(address.host(), "xn--bcher-kva.example");
+//! assert_eq!(address.port(), 25566);
+//!
+//! // SRV record `_minecraft._tcp.bücher.example` points to `mc.example.` on port 25570.
+//! let target = address.redirect("mc.example.", 25570).unwrap();
+//! assert_eq!(target.host(), "mc.example.");
+//! assert!(target.matches("mc.example\0FML2\0"));
+//! ```
+use crate::error::AddressError;
+#[cfg(feature = "v1_14_4")]
+use crate::version::v1_14_4::handshake::{Handshake, HandshakeServerBoundPacket, NextState};
+use std::fmt;
+use std::str::FromStr;
+
+/// Port used when address doesn't have one.
+pub const DEFAULT_PORT: u16 = 25565;
+/// Max length of server address in handshake.
+pub const SERVER_ADDR_MAX_LENGTH: usize = 255;
+/// Max length of DNS label.
+const LABEL_MAX_LENGTH: usize = 63;
+/// Prefix of punycode encoded labels.
+const ACE_PREFIX: &str = "xn--";
+
+/// Host and port of server, host is in the form sent in handshake.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ServerAddress {
+    host: String,
+    port: u16,
+}
+
+impl ServerAddress {
+    /// Converts host to ASCII, returns `AddressError::InvalidPort` if port is 0.
+    pub fn new(host: &str, port: u16) -> Result<Self, AddressError> {
+        if port == 0 {
+            return Err(AddressError::InvalidPort {
+                value: port.to_string(),
+            });
+        }
+
+        let host = to_ascii(host)?;
+
+        Ok(ServerAddress { host, port })
+    }
+
+    /// Parses address typed by player, e.g. `example.com`, `example.com:25566` or `[::1]:25566`.
+    pub fn parse(address: &str) -> Result<Self, AddressError> {
+        let (host, port) = split_host_port(address)?;
+
+        let port = match port {
+            Some(port) => parse_port(port)?,
+            None => DEFAULT_PORT,
+        };
+
+        ServerAddress::new(host, port)
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns address which SRV record of this address points to.
+    ///
+    /// Target is kept as in DNS record, vanilla client sends it with trailing dot.
+    pub fn redirect(&self, target: &str, port: u16) -> Result<Self, AddressError> {
+        ServerAddress::new(target, port)
+    }
+
+    /// Checks if server address received in handshake is this address.
+    ///
+    /// Trailing dot and data appended after `\0` by mod loaders and proxies are ignored, hosts
+    /// are compared case-insensitively.
+    pub fn matches(&self, server_addr: &str) -> bool {
+        let host = self.host.strip_suffix('.').unwrap_or(&self.host);
+
+        virtual_host(server_addr).eq_ignore_ascii_case(host)
+    }
+
+    /// Returns handshake which vanilla client sends when connecting to this address.
+    #[cfg(feature = "v1_14_4")]
+    pub fn handshake(
+        &self,
+        protocol_version: i32,
+        next_state: NextState,
+    ) -> HandshakeServerBoundPacket {
+        Handshake::new(protocol_version, self.host.clone(), self.port, next_state)
+    }
+}
+
+impl FromStr for ServerAddress {
+    type Err = AddressError;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        ServerAddress::parse(address)
+    }
+}
+
+impl fmt::Display for ServerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// Splits address like Guava `HostAndPort`, which vanilla client uses. Addresses with several
+/// colons outside of brackets are IPv6 addresses without port.
+fn split_host_port(address: &str) -> Result<(&str, Option<&str>), AddressError> {
+    let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+        let invalid_host = || AddressError::InvalidHost {
+            host: address.to_owned(),
+        };
+        let (host, rest) = rest.split_once(']').ok_or_else(invalid_host)?;
+
+        match rest {
+            "" => (host, None),
+            rest => (host, Some(rest.strip_prefix(':').ok_or_else(invalid_host)?)),
+        }
+    } else {
+        match address.split_once(':') {
+            Some((host, port)) if !port.contains(':') => (host, Some(port)),
+            _ => (address, None),
+        }
+    };
+
+    if host.is_empty() {
+        return Err(AddressError::EmptyHost);
+    }
+
+    Ok((host, port))
+}
+
+fn parse_port(port: &str) -> Result<u16, AddressError> {
+    let invalid_port = || AddressError::InvalidPort {
+        value: port.to_owned(),
+    };
+
+    if !port.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid_port());
+    }
+
+    match port.parse() {
+        Ok(0) | Err(_) => Err(invalid_port()),
+        Ok(port) => Ok(port),
+    }
+}
+
+/// Converts host to the form sent by vanilla client, like Java `IDN.toASCII`.
+///
+/// Labels are separated by dots, including ideographic and fullwidth ones. Labels with unicode
+/// characters are lowercased and encoded with punycode, full Nameprep mapping is not applied.
+pub fn to_ascii(host: &str) -> Result<String, AddressError> {
+    if host.is_empty() {
+        return Err(AddressError::EmptyHost);
+    }
+
+    let invalid_host = || AddressError::InvalidHost {
+        host: host.to_owned(),
+    };
+    let labels: Vec<&str> = host.split(is_label_separator).collect();
+    let last = labels.len() - 1;
+    let mut ascii = String::with_capacity(host.len());
+
+    for (idx, label) in labels.into_iter().enumerate() {
+        // Only fully qualified names end with empty label.
+        if label.is_empty() && (idx != last || idx == 0) {
+            return Err(invalid_host());
+        }
+
+        if idx > 0 {
+            ascii.push('.');
+        }
+
+        if label.is_ascii() {
+            ascii.push_str(label);
+        } else {
+            let encoded = punycode_encode(&label.to_lowercase()).ok_or_else(invalid_host)?;
+            ascii.push_str(ACE_PREFIX);
+            ascii.push_str(&encoded);
+        }
+
+        if ascii.len() - ascii.rfind('.').map_or(0, |dot| dot + 1) > LABEL_MAX_LENGTH {
+            return Err(invalid_host());
+        }
+    }
+
+    if ascii.len() > SERVER_ADDR_MAX_LENGTH {
+        return Err(AddressError::HostTooLong {
+            length: ascii.len(),
+            max_length: SERVER_ADDR_MAX_LENGTH,
+        });
+    }
+
+    Ok(ascii)
+}
+
+fn is_label_separator(c: char) -> bool {
+    matches!(c, '.' | '\u{3002}' | '\u{FF0E}' | '\u{FF61}')
+}
+
+/// Returns host received in handshake without data appended by mod loaders and proxies after
+/// `\0` and without trailing dot of SRV target.
+pub fn virtual_host(server_addr: &str) -> &str {
+    let host = server_addr.split('\0').next().unwrap_or_default();
+
+    host.strip_suffix('.').unwrap_or(host)
+}
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+/// Encodes label with punycode as described in RFC 3492, without `xn--` prefix.
+///
+/// Returns `None` if label is too long to be encoded.
+pub fn punycode_encode(label: &str) -> Option<String> {
+    let code_points: Vec<u32> = label.chars().map(u32::from).collect();
+    let mut output: String = label.chars().filter(char::is_ascii).collect();
+
+    let basic_length = output.len() as u32;
+    let mut handled = basic_length;
+
+    if basic_length > 0 {
+        output.push('-');
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+
+    while (handled as usize) < code_points.len() {
+        let m = code_points.iter().copied().filter(|&c| c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+
+        for &c in &code_points {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+
+                loop {
+                    let t = threshold(k, bias);
+
+                    if q < t {
+                        break;
+                    }
+
+                    output.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+
+                output.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic_length);
+                delta = 0;
+                handled += 1;
+            }
+        }
+
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+
+    Some(output)
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta = if first_time { delta / DAMP } else { delta / 2 };
+    delta += delta / num_points;
+
+    let mut k = 0;
+
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn encode_digit(digit: u32) -> char {
+    match digit {
+        0..=25 => char::from(b'a' + digit as u8),
+        _ => char::from(b'0' + (digit - 26) as u8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::handshake::*;
+
+    #[test]
+    fn test_punycode_rfc_samples() {
+        assert_eq!(punycode_encode("bücher").unwrap(), "bcher-kva");
+        assert_eq!(punycode_encode("münchen").unwrap(), "mnchen-3ya");
+        assert_eq!(
+            punycode_encode("他们为什么不说中文").unwrap(),
+            "ihqwcrb4cv8a8dqg056pqjye"
+        );
+        assert_eq!(
+            punycode_encode("3年B組金八先生").unwrap(),
+            "3B-ww4c5e180e575a65lsy2b"
+        );
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        assert_eq!(to_ascii("Play.Example.COM").unwrap(), "Play.Example.COM");
+        assert_eq!(to_ascii("MÜNCHEN.de").unwrap(), "xn--mnchen-3ya.de");
+        assert_eq!(
+            to_ascii("bücher。example").unwrap(),
+            "xn--bcher-kva.example"
+        );
+        assert_eq!(to_ascii("mc.example.").unwrap(), "mc.example.");
+    }
+
+    #[test]
+    fn test_to_ascii_empty_label() {
+        let address_error = to_ascii("mc..example")
+            .expect_err("Expected error `InvalidHost` because host has empty label");
+
+        match address_error {
+            AddressError::InvalidHost { host } => assert_eq!(host, "mc..example"),
+            _ => panic!("Expected `InvalidHost` but got `{:?}`", address_error),
+        }
+    }
+
+    #[test]
+    fn test_to_ascii_host_too_long() {
+        let host = vec!["a".repeat(63); 5].join(".");
+
+        let address_error = to_ascii(&host)
+            .expect_err("Expected error `HostTooLong` because host is longer than 255 bytes");
+
+        match address_error {
+            AddressError::HostTooLong { length, max_length } => {
+                assert_eq!(length, 319);
+                assert_eq!(max_length, SERVER_ADDR_MAX_LENGTH);
+            }
+            _ => panic!("Expected `HostTooLong` but got `{:?}`", address_error),
+        }
+    }
+
+    #[test]
+    fn test_parse_address() {
+        let address = ServerAddress::parse("localhost").unwrap();
+        assert_eq!(address.host(), "localhost");
+        assert_eq!(address.port(), DEFAULT_PORT);
+
+        let address = ServerAddress::parse("[::1]:25566").unwrap();
+        assert_eq!(address.host(), "::1");
+        assert_eq!(address.port(), 25566);
+        assert_eq!(address.to_string(), "[::1]:25566");
+
+        let address = ServerAddress::parse("::1").unwrap();
+        assert_eq!(address.host(), "::1");
+        assert_eq!(address.port(), DEFAULT_PORT);
+    }
+
+    #[test]
+    fn test_parse_invalid_port() {
+        for value in ["0", "65536", "+1", ""] {
+            let address_error = ServerAddress::parse(&format!("localhost:{}", value))
+                .expect_err("Expected error `InvalidPort` because port is out of range");
+
+            match address_error {
+                AddressError::InvalidPort { value: port } => assert_eq!(port, value),
+                _ => panic!("Expected `InvalidPort` but got `{:?}`", address_error),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_empty_host() {
+        let address_error = ServerAddress::parse(":25565")
+            .expect_err("Expected error `EmptyHost` because address has only port");
+
+        match address_error {
+            AddressError::EmptyHost => {}
+            _ => panic!("Expected `EmptyHost` but got `{:?}`", address_error),
+        }
+    }
+
+    #[test]
+    fn test_matches_received_host() {
+        let address = ServerAddress::parse("Bücher.example").unwrap();
+
+        assert!(address.matches("xn--bcher-kva.example"));
+        assert!(address.matches("XN--BCHER-KVA.example.\0FML\0"));
+        assert!(!address.matches("bücher.example"));
+    }
+
+    #[cfg(feature = "v1_14_4")]
+    #[test]
+    fn test_handshake_of_redirected_address() {
+        use crate::validate::Validate;
+
+        let address = ServerAddress::parse("example.com").unwrap();
+        let target = address.redirect("mc.example.com.", 25570).unwrap();
+
+        match target.handshake(498, NextState::Login) {
+            HandshakeServerBoundPacket::Handshake(handshake) => {
+                assert_eq!(handshake.server_addr, "mc.example.com.");
+                assert_eq!(handshake.server_port, 25570);
+                assert!(handshake.validate().is_empty());
+            }
+        }
+    }
+}
@@ -0,0 +1,394 @@
+//! Automatic handling of keep-alive packets in game state.
+//!
+//! Server sends `ClientBoundKeepAlive` periodically and client must answer with
+//! `ServerBoundKeepAlive` with the same id, otherwise connection is closed. `KeepAliveResponder`
+//! answers keep-alives on client side and `KeepAliveDriver` sends them and checks answers on
+//! server side. Both swallow keep-alive packets unless `forward` is set in `KeepAliveConfig`, so
+//! only timeouts are surfaced to the caller.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::codec::PacketCodec;
+//! use minecraft_protocol::keep_alive::{KeepAliveConfig, KeepAliveResponder};
+//! use minecraft_protocol::version::v1_14_4::game::{ClientBoundKeepAlive, GameServerBoundPacket};
+//! use std::time::Instant;
+//!
+//! let mut codec = PacketCodec::new(Vec::new());
+//! let mut responder = KeepAliveResponder::new(KeepAliveConfig::default(), Instant::now());
+//!
+//! let packet = responder
+//!     .handle(&mut codec, ClientBoundKeepAlive::new(42), Instant::now())
+//!     .unwrap();
+//! assert!(packet.is_none());
+//!
+//! let stream = codec.into_inner();
+//! let mut codec = PacketCodec::new(stream.as_slice());
+//!
+//! match codec.read_packet().unwrap() {
+//!     GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => assert_eq!(keep_alive.id, 42),
+//!     _ => unreachable!(),
+//! }
+//! ```
+use crate::clock::{Clock, SystemClock};
+use crate::codec::PacketCodec;
+use crate::error::{DecodeError, KeepAliveError};
+use crate::version::v1_14_4::game::{
+    ClientBoundKeepAlive, GameClientBoundPacket, GameServerBoundPacket, ServerBoundKeepAlive,
+};
+use std::io::{ErrorKind, Read, Write};
+use std::time::{Duration, Instant};
+
+/// Vanilla server sends keep-alive every 15 seconds.
+pub const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// Vanilla client and server close connection after 30 seconds without answer.
+pub const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct KeepAliveConfig {
+    /// Interval between keep-alives sent by server.
+    pub interval: Duration,
+    /// Time without keep-alive after which connection is considered dead.
+    pub timeout: Duration,
+    /// Return keep-alive packets to caller after they are handled.
+    pub forward: bool,
+}
+
+impl Default for KeepAliveConfig {
+    fn default() -> Self {
+        KeepAliveConfig {
+            interval: KEEP_ALIVE_INTERVAL,
+            timeout: KEEP_ALIVE_TIMEOUT,
+            forward: false,
+        }
+    }
+}
+
+/// Client side keep-alive handling.
+#[derive(Debug)]
+pub struct KeepAliveResponder {
+    config: KeepAliveConfig,
+    last_received: Instant,
+}
+
+impl KeepAliveResponder {
+    pub fn new(config: KeepAliveConfig, now: Instant) -> Self {
+        KeepAliveResponder {
+            config,
+            last_received: now,
+        }
+    }
+
+    pub fn config(&self) -> &KeepAliveConfig {
+        &self.config
+    }
+
+    /// Answers keep-alive packet. Other packets are returned as is.
+    pub fn handle<S: Write>(
+        &mut self,
+        codec: &mut PacketCodec<S>,
+        packet: GameClientBoundPacket,
+        now: Instant,
+    ) -> Result<Option<GameClientBoundPacket>, KeepAliveError> {
+        self.last_received = now;
+
+        match packet {
+            GameClientBoundPacket::ClientBoundKeepAlive(ref keep_alive) => {
+                codec.write_packet(&ServerBoundKeepAlive::new(keep_alive.id))?;
+
+                Ok(Some(packet).filter(|_| self.config.forward))
+            }
+            _ => Ok(Some(packet)),
+        }
+    }
+
+    /// Returns `KeepAliveError::TimedOut` if server sent nothing for longer than timeout.
+    pub fn check_timeout(&self, now: Instant) -> Result<(), KeepAliveError> {
+        let elapsed = now.saturating_duration_since(self.last_received);
+
+        if elapsed > self.config.timeout {
+            return Err(KeepAliveError::TimedOut { elapsed });
+        }
+
+        Ok(())
+    }
+
+    /// Reads packets until one which is not swallowed keep-alive.
+    ///
+    /// Read timeout of the stream is reported as `KeepAliveError::TimedOut` once keep-alive
+    /// timeout has passed.
+    pub fn read_packet<S: Read + Write>(
+        &mut self,
+        codec: &mut PacketCodec<S>,
+    ) -> Result<GameClientBoundPacket, KeepAliveError> {
+        self.read_packet_with_clock(codec, &SystemClock)
+    }
+
+    /// Same as `read_packet`, reading time from clock.
+    pub fn read_packet_with_clock<S: Read + Write, C: Clock>(
+        &mut self,
+        codec: &mut PacketCodec<S>,
+        clock: &C,
+    ) -> Result<GameClientBoundPacket, KeepAliveError> {
+        loop {
+            match codec.read_packet() {
+                Ok(packet) => {
+                    if let Some(packet) = self.handle(codec, packet, clock.now())? {
+                        return Ok(packet);
+                    }
+                }
+                Err(DecodeError::IOError { io_error })
+                    if matches!(io_error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    self.check_timeout(clock.now())?;
+
+                    return Err(DecodeError::IOError { io_error }.into());
+                }
+                Err(decode_error) => return Err(decode_error.into()),
+            }
+        }
+    }
+}
+
+/// Server side keep-alive handling.
+#[derive(Debug)]
+pub struct KeepAliveDriver {
+    config: KeepAliveConfig,
+    last_sent: Instant,
+    pending: Option<(u64, Instant)>,
+    next_id: u64,
+    latency: Option<Duration>,
+}
+
+impl KeepAliveDriver {
+    pub fn new(config: KeepAliveConfig, now: Instant) -> Self {
+        KeepAliveDriver {
+            config,
+            last_sent: now,
+            pending: None,
+            next_id: 1,
+            latency: None,
+        }
+    }
+
+    pub fn config(&self) -> &KeepAliveConfig {
+        &self.config
+    }
+
+    /// Round trip time of the last answered keep-alive.
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    /// Sends keep-alive if interval has passed since the last one was answered.
+    ///
+    /// Should be called at least once per interval, e.g. every tick. Returns
+    /// `KeepAliveError::TimedOut` if client didn't answer in time.
+    pub fn poll<S: Write>(
+        &mut self,
+        codec: &mut PacketCodec<S>,
+        now: Instant,
+    ) -> Result<(), KeepAliveError> {
+        if let Some((_, sent)) = self.pending {
+            let elapsed = now.saturating_duration_since(sent);
+
+            if elapsed > self.config.timeout {
+                return Err(KeepAliveError::TimedOut { elapsed });
+            }
+        } else if now.saturating_duration_since(self.last_sent) >= self.config.interval {
+            let id = self.next_id;
+            codec.write_packet(&ClientBoundKeepAlive::new(id))?;
+
+            self.next_id = self.next_id.wrapping_add(1);
+            self.pending = Some((id, now));
+            self.last_sent = now;
+        }
+
+        Ok(())
+    }
+
+    /// Accepts keep-alive answer. Other packets are returned as is.
+    pub fn handle(
+        &mut self,
+        packet: GameServerBoundPacket,
+        now: Instant,
+    ) -> Option<GameServerBoundPacket> {
+        match packet {
+            GameServerBoundPacket::ServerBoundKeepAlive(ref keep_alive) => {
+                if let Some((id, sent)) = self.pending {
+                    if keep_alive.id == id {
+                        self.pending = None;
+                        self.latency = Some(now.saturating_duration_since(sent));
+                    }
+                }
+
+                Some(packet).filter(|_| self.config.forward)
+            }
+            _ => Some(packet),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::MockClock;
+    use crate::keep_alive::*;
+    use crate::version::v1_14_4::game::ServerBoundChatMessage;
+    use std::io;
+
+    fn read_client_bound(stream: Vec<u8>) -> GameClientBoundPacket {
+        PacketCodec::new(stream.as_slice()).read_packet().unwrap()
+    }
+
+    #[test]
+    fn test_responder_answers_keep_alive() {
+        let now = Instant::now();
+        let mut codec = PacketCodec::new(Vec::new());
+        let mut responder = KeepAliveResponder::new(KeepAliveConfig::default(), now);
+
+        let packet = responder
+            .handle(&mut codec, ClientBoundKeepAlive::new(7), now)
+            .unwrap();
+        assert!(packet.is_none());
+
+        let stream = codec.into_inner();
+        match PacketCodec::new(stream.as_slice()).read_packet().unwrap() {
+            GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => {
+                assert_eq!(keep_alive.id, 7)
+            }
+            _ => panic!("Expected keep alive packet"),
+        }
+    }
+
+    #[test]
+    fn test_responder_forward() {
+        let now = Instant::now();
+        let config = KeepAliveConfig {
+            forward: true,
+            ..KeepAliveConfig::default()
+        };
+        let mut codec = PacketCodec::new(Vec::new());
+        let mut responder = KeepAliveResponder::new(config, now);
+
+        let packet = responder
+            .handle(&mut codec, ClientBoundKeepAlive::new(7), now)
+            .unwrap();
+
+        assert!(matches!(
+            packet,
+            Some(GameClientBoundPacket::ClientBoundKeepAlive(_))
+        ));
+        assert!(!codec.into_inner().is_empty());
+    }
+
+    #[test]
+    fn test_responder_timeout() {
+        let now = Instant::now();
+        let responder = KeepAliveResponder::new(KeepAliveConfig::default(), now);
+
+        assert!(responder.check_timeout(now + KEEP_ALIVE_TIMEOUT).is_ok());
+
+        let keep_alive_error = responder
+            .check_timeout(now + KEEP_ALIVE_TIMEOUT * 2)
+            .expect_err("Expected error `TimedOut` because server sent nothing");
+
+        match keep_alive_error {
+            KeepAliveError::TimedOut { elapsed } => assert_eq!(elapsed, KEEP_ALIVE_TIMEOUT * 2),
+            _ => panic!("Expected `TimedOut` but got `{:?}`", keep_alive_error),
+        }
+    }
+
+    /// Stream which has no data yet.
+    struct Stalled;
+
+    impl Read for Stalled {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::from(ErrorKind::WouldBlock))
+        }
+    }
+
+    impl Write for Stalled {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_responder_read_timeout_with_clock() {
+        let clock = MockClock::new();
+        let mut codec = PacketCodec::new(Stalled);
+        let mut responder = KeepAliveResponder::new(KeepAliveConfig::default(), clock.now());
+
+        let keep_alive_error = responder
+            .read_packet_with_clock(&mut codec, &clock)
+            .expect_err("Expected error `IOError` because stream has no data");
+        assert!(matches!(
+            keep_alive_error,
+            KeepAliveError::DecodeError { .. }
+        ));
+
+        clock.advance(KEEP_ALIVE_TIMEOUT + Duration::from_secs(1));
+
+        let keep_alive_error = responder
+            .read_packet_with_clock(&mut codec, &clock)
+            .expect_err("Expected error `TimedOut` because server sent nothing");
+
+        match keep_alive_error {
+            KeepAliveError::TimedOut { elapsed } => {
+                assert_eq!(elapsed, KEEP_ALIVE_TIMEOUT + Duration::from_secs(1))
+            }
+            _ => panic!("Expected `TimedOut` but got `{:?}`", keep_alive_error),
+        }
+    }
+
+    #[test]
+    fn test_driver_sends_after_interval() {
+        let now = Instant::now();
+        let mut codec = PacketCodec::new(Vec::new());
+        let mut driver = KeepAliveDriver::new(KeepAliveConfig::default(), now);
+
+        driver.poll(&mut codec, now).unwrap();
+        assert!(codec.get_ref().is_empty());
+
+        driver.poll(&mut codec, now + KEEP_ALIVE_INTERVAL).unwrap();
+
+        let id = match read_client_bound(codec.into_inner()) {
+            GameClientBoundPacket::ClientBoundKeepAlive(keep_alive) => keep_alive.id,
+            _ => panic!("Expected keep alive packet"),
+        };
+
+        let answered = now + KEEP_ALIVE_INTERVAL + Duration::from_millis(50);
+        assert!(driver
+            .handle(ServerBoundKeepAlive::new(id), answered)
+            .is_none());
+        assert_eq!(driver.latency(), Some(Duration::from_millis(50)));
+
+        let packet = driver.handle(ServerBoundChatMessage::new(String::from("hi")), answered);
+        assert!(matches!(
+            packet,
+            Some(GameServerBoundPacket::ServerBoundChatMessage(_))
+        ));
+    }
+
+    #[test]
+    fn test_driver_timeout() {
+        let now = Instant::now();
+        let mut codec = PacketCodec::new(Vec::new());
+        let mut driver = KeepAliveDriver::new(KeepAliveConfig::default(), now);
+
+        driver.poll(&mut codec, now + KEEP_ALIVE_INTERVAL).unwrap();
+
+        let keep_alive_error = driver
+            .poll(
+                &mut codec,
+                now + KEEP_ALIVE_INTERVAL + KEEP_ALIVE_TIMEOUT * 2,
+            )
+            .expect_err("Expected error `TimedOut` because client didn't answer");
+
+        assert!(matches!(keep_alive_error, KeepAliveError::TimedOut { .. }));
+    }
+}
@@ -0,0 +1,100 @@
+//! Disconnect messages used by vanilla server when client violates protocol.
+//!
+//! Messages are translated by the client, so players see the same text in their language as when
+//! they are kicked by vanilla server.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::error::DecodeError;
+//! use minecraft_protocol::kick;
+//!
+//! let decode_error = DecodeError::PacketTooLong {
+//!     length: 2_097_152,
+//!     max_length: 2_097_151,
+//! };
+//!
+//! let reason = kick::for_decode_error(&decode_error);
+//! assert_eq!(reason, kick::packet_too_long(2_097_152, 2_097_151));
+//! ```
+use crate::data::chat::{Message, Payload};
+use crate::error::DecodeError;
+
+pub const GENERIC_REASON_KEY: &str = "disconnect.genericReason";
+pub const INVALID_PACKET_KEY: &str = "multiplayer.disconnect.invalid_packet";
+pub const ILLEGAL_CHARACTERS_KEY: &str = "multiplayer.disconnect.illegal_characters";
+pub const INVALID_PLAYER_MOVEMENT_KEY: &str = "multiplayer.disconnect.invalid_player_movement";
+pub const SPAM_KEY: &str = "disconnect.spam";
+pub const TIMEOUT_KEY: &str = "disconnect.timeout";
+
+/// "Internal Exception: ..." message vanilla sends when connection fails.
+pub fn generic_reason(reason: &str) -> Message {
+    translation(GENERIC_REASON_KEY, vec![Message::from_str(reason)])
+}
+
+/// Packet is longer than allowed.
+pub fn packet_too_long(length: usize, max_length: usize) -> Message {
+    generic_reason(&format!(
+        "io.netty.handler.codec.DecoderException: Badly compressed packet - size of {} is larger \
+         than protocol maximum of {}",
+        length, max_length
+    ))
+}
+
+/// "Server sent an invalid packet".
+pub fn invalid_packet() -> Message {
+    translation(INVALID_PACKET_KEY, vec![])
+}
+
+/// "Illegal characters in chat".
+pub fn illegal_characters() -> Message {
+    translation(ILLEGAL_CHARACTERS_KEY, vec![])
+}
+
+/// "Invalid move player packet received".
+pub fn invalid_player_movement() -> Message {
+    translation(INVALID_PLAYER_MOVEMENT_KEY, vec![])
+}
+
+/// "Kicked for spamming".
+pub fn spam() -> Message {
+    translation(SPAM_KEY, vec![])
+}
+
+/// "Timed out".
+pub fn timeout() -> Message {
+    translation(TIMEOUT_KEY, vec![])
+}
+
+/// Returns message for client whose packet failed to decode.
+pub fn for_decode_error(decode_error: &DecodeError) -> Message {
+    match decode_error {
+        DecodeError::PacketTooLong { length, max_length } => packet_too_long(*length, *max_length),
+        _ => invalid_packet(),
+    }
+}
+
+fn translation(key: &str, with: Vec<Message>) -> Message {
+    Message::new(Payload::translation(key, with))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kick::*;
+
+    #[test]
+    fn test_packet_too_long_json() {
+        assert_eq!(
+            packet_too_long(9, 8).to_json().unwrap(),
+            r#"{"translate":"disconnect.genericReason","with":[{"text":"io.netty.handler.codec.DecoderException: Badly compressed packet - size of 9 is larger than protocol maximum of 8"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_for_decode_error() {
+        assert_eq!(
+            for_decode_error(&DecodeError::NonBoolValue),
+            invalid_packet()
+        );
+    }
+}
@@ -1,34 +1,176 @@
 //! This crate implements Minecraft protocol.
 //!
 //! Information about protocol can be found at https://wiki.vg/Protocol.
+//!
+//! Derive macros are re-exported from `derive`, so own packets can be declared depending only on
+//! this crate:
+//!
+//! ```
+//! use minecraft_protocol::derive::{Decoder, Encoder};
+//!
+//! #[derive(Encoder, Decoder)]
+//! pub struct Ping {
+//!     #[data_type(with = "var_int")]
+//!     pub id: i32,
+//!     #[data_type(max_length = 16)]
+//!     pub name: String,
+//! }
+//! ```
+//!
+//! Optional parts are enabled with cargo features:
+//!
+//! - `v1_14_4`, `v1_16_5`, `v1_19`, `v1_20_2`, `v1_20_5`: packets of protocol versions, all of
+//!   them are enabled by default with `all-versions`.
+//! - `legacy`: packets of 1.8 and 1.12.2.
+//! - `serde`: `Serialize` and `Deserialize` of packets, enabled by command line tools.
+//! - `bytes`: decoding from `bytes` buffers.
+//! - `rayon`: parallel packet encoding.
+//! - `tokio`: packet framing over async streams of tokio.
+//! - `signing`: chat signature verification.
+//! - `encryption`: encryption of connections in online mode.
+//! - `community-channels`: plugin channels of proxies and mod loaders.
+//! - `canonical-json`: JSON with sorted keys.
+//! - `testing`: fake client for integration tests of servers.
+//! - `registries`: entity type and particle ids of versions.
+//! - `chat-unknown-fields`: keeping unknown chat component fields.
+//! - `cli`, `pcap-import` and `repl`: command line tools.
+#![allow(clippy::new_ret_no_self, clippy::large_enum_variant)]
+
+// Derived code refers to this crate by name, so it works both here and in dependent crates.
+extern crate self as minecraft_protocol;
+
+pub mod acceptor;
+#[cfg(feature = "tokio")]
+pub mod async_io;
+#[cfg(feature = "bytes")]
+pub mod buf;
+pub mod channels;
+pub mod chunk_batch;
+pub mod chunk_tracker;
+pub mod clock;
+pub mod codec;
+pub mod compression;
+pub mod connection;
 pub mod data;
 pub mod decoder;
+pub mod driver;
 pub mod encoder;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod error;
+pub mod facade;
+pub mod fingerprint;
+pub mod handler;
+pub mod handshake;
+#[cfg(feature = "v1_14_4")]
+pub mod keep_alive;
+pub mod kick;
+pub mod limits;
+pub mod login;
+#[cfg(feature = "v1_14_4")]
+pub mod login_plugin;
+pub mod packet;
+#[cfg(feature = "rayon")]
+pub mod par_encode;
+#[cfg(feature = "v1_14_4")]
+pub mod ping;
+pub mod prelude;
+pub mod proxy_protocol;
+pub mod split;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod translate;
+pub mod transport;
+pub mod validate;
 pub mod version;
 
+pub use nbt;
+pub use uuid;
+
+// Used by `impl_json_encoder_decoder`, so dependent crates don't need `serde_json` dependency.
+#[doc(hidden)]
+pub use serde_json as __serde_json;
+
+/// Derive macros for packets and data types.
+pub mod derive {
+    pub use minecraft_protocol_derive::{
+        Builder, Decoder, Encoder, PacketVariants, Validate, VisitMessages,
+    };
+}
+
+/// Builds chat `Message` from text with legacy formatting codes and `format!` placeholders.
+///
+/// Color codes `&0`-`&f` and hex colors `&#RRGGBB` reset formatting, `&k`-`&o` add obfuscated,
+/// bold, strikethrough, underlined and italic styles, `&r` resets everything. `§` can be used
+/// instead of `&`, `&&` writes `&`. Each formatted run of text becomes message with the same
+/// style, runs of styled text are children of message with empty text.
+///
+/// Unknown codes and wrong number of arguments are compile errors:
+///
+/// ```
+/// use minecraft_protocol::data::chat::{Color, Payload};
+/// use minecraft_protocol::msg;
+///
+/// let player_name = "Steve";
+/// let message = msg!("&aHello &l{}&r!", player_name);
+///
+/// assert_eq!(message.extra.len(), 3);
+/// assert_eq!(message.extra[1].payload, Payload::text("Steve"));
+/// assert_eq!(message.extra[1].color, Some(Color::Green));
+/// assert_eq!(message.extra[1].bold, Some(true));
+/// ```
+///
+/// ```compile_fail
+/// let message = minecraft_protocol::msg!("&zHello");
+/// ```
+///
+/// ```compile_fail
+/// let message = minecraft_protocol::msg!("&aHello {}!");
+/// ```
+pub use minecraft_protocol_derive::msg;
+
 /// Protocol limits maximum string length.
 const STRING_MAX_LENGTH: u16 = 32_768;
 
+/// Returns length of string in UTF-16 code units if it's more than max length.
+///
+/// Vanilla limits strings in UTF-16 code units rather than UTF-8 bytes. Code units never outnumber
+/// bytes, so strings which fit in bytes are not counted.
+pub(crate) fn utf16_length_over(value: &str, max_length: u16) -> Option<usize> {
+    if value.len() <= max_length as usize {
+        return None;
+    }
+
+    let length = value.encode_utf16().count();
+
+    if length > max_length as usize {
+        Some(length)
+    } else {
+        None
+    }
+}
+
+/// Returns max length of string in UTF-8 bytes, as each UTF-16 code unit takes up to 3 bytes.
+pub(crate) const fn string_max_bytes(max_length: u16) -> usize {
+    max_length as usize * 3
+}
+
 #[macro_export]
 macro_rules! impl_json_encoder_decoder (
     ($ty: ident) => (
-        impl crate::encoder::Encoder for $ty {
-            fn encode<W: std::io::Write>(&self, writer: &mut W) -> Result<(), crate::error::EncodeError> {
-                let json = serde_json::to_string(self)?;
-                crate::encoder::EncoderWriteExt::write_string(writer, &json, crate::STRING_MAX_LENGTH)?;
-
-                Ok(())
+        impl $crate::encoder::Encoder for $ty {
+            fn encode<W: std::io::Write>(&self, writer: &mut W) -> Result<(), $crate::error::EncodeError> {
+                $crate::encoder::write_json(writer, self)
             }
         }
 
-        impl crate::decoder::Decoder for $ty {
+        impl $crate::decoder::Decoder for $ty {
             type Output = Self;
 
-            fn decode<R: std::io::Read>(reader: &mut R) -> Result<Self::Output, crate::error::DecodeError> {
-                let json = crate::decoder::DecoderReadExt::read_string(reader, crate::STRING_MAX_LENGTH)?;
+            fn decode<R: std::io::Read>(reader: &mut R) -> Result<Self::Output, $crate::error::DecodeError> {
+                let json = $crate::limits::read_json(reader)?;
 
-                Ok(serde_json::from_str(&json)?)
+                Ok($crate::__serde_json::from_str(&json)?)
             }
         }
    );
@@ -0,0 +1,564 @@
+//! Limits applied while decoding untrusted data.
+//!
+//! JSON data like server status or chat messages is checked against limits before it's passed to
+//! `serde_json`, so a malicious peer can't stall decoding with huge or deeply nested documents.
+//! NBT data is scanned the same way before it's passed to `nbt`, limiting its length, nesting and
+//! number of tags.
+//! Plugin message payloads are limited like in vanilla.
+//!
+//! Limits are set per thread and apply to all decoding done inside `with_limits`. They belong to
+//! the thread rather than the connection: async task moved to another worker thread between polls
+//! doesn't keep them and decoding spawned on other threads, e.g. on rayon pool, doesn't see them.
+//! Codecs and connections take their own limits with `with_decode_limits`, which apply to every
+//! packet they decode on whatever thread it happens.
+//!
+//! JSON is limited by `JSON_MAX_LENGTH` when encoding, the default JSON limit of decoding, so
+//! JSON accepted with default limits can be sent back.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::decoder::Decoder;
+//! use minecraft_protocol::data::chat::Message;
+//! use minecraft_protocol::limits::{self, DecodeLimits};
+//! use std::io::Cursor;
+//!
+//! let limits = DecodeLimits {
+//!     json_max_length: 1024,
+//!     json_max_depth: 8,
+//!     ..DecodeLimits::default()
+//! };
+//!
+//! let mut cursor = Cursor::new(b"\x0d{\"text\":\"Hi\"}".to_vec());
+//! let message = limits::with_limits(limits, || Message::decode(&mut cursor)).unwrap();
+//!
+//! assert_eq!(message, Message::from_str("Hi"));
+//! ```
+use crate::decoder::{read_length, DecoderReadExt};
+use crate::error::DecodeError;
+use byteorder::{BigEndian, ReadBytesExt};
+use nbt::decode::TagDecodeError;
+use nbt::CompoundTag;
+use std::cell::Cell;
+use std::io::Read;
+
+/// Vanilla server limits payload of plugin messages sent by client.
+pub const SERVER_BOUND_PAYLOAD_MAX_LENGTH: usize = 32_767;
+/// Vanilla client limits payload of plugin messages sent by server.
+pub const CLIENT_BOUND_PAYLOAD_MAX_LENGTH: usize = 1_048_576;
+/// Vanilla limits NBT received from network to 2 MiB.
+pub const NBT_MAX_LENGTH: usize = 2_097_152;
+/// Vanilla limits nesting of NBT compound and list tags.
+pub const NBT_MAX_DEPTH: usize = 512;
+/// Vanilla limits JSON of chat components, the longest JSON strings of protocol, to 262144
+/// characters. Limit is applied to bytes.
+pub const JSON_MAX_LENGTH: usize = 262_144;
+
+thread_local! {
+    static LIMITS: Cell<DecodeLimits> = Cell::new(DecodeLimits::default());
+}
+
+/// Limits applied while decoding.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DecodeLimits {
+    /// Maximum JSON length in bytes.
+    pub json_max_length: usize,
+    /// Maximum nesting of JSON objects and arrays.
+    pub json_max_depth: usize,
+    /// Maximum payload length of plugin messages sent by client.
+    pub server_bound_payload_max_length: usize,
+    /// Maximum payload length of plugin messages sent by server.
+    pub client_bound_payload_max_length: usize,
+    /// Maximum length of NBT data in bytes, shared by all tags of a list of compound tags.
+    pub nbt_max_length: usize,
+    /// Maximum nesting of NBT compound and list tags.
+    pub nbt_max_depth: usize,
+    /// Maximum number of NBT tags, including list elements.
+    pub nbt_max_tags: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            json_max_length: JSON_MAX_LENGTH,
+            json_max_depth: 64,
+            server_bound_payload_max_length: SERVER_BOUND_PAYLOAD_MAX_LENGTH,
+            client_bound_payload_max_length: CLIENT_BOUND_PAYLOAD_MAX_LENGTH,
+            nbt_max_length: NBT_MAX_LENGTH,
+            nbt_max_depth: NBT_MAX_DEPTH,
+            nbt_max_tags: 65_536,
+        }
+    }
+}
+
+/// Returns limits of the current thread.
+pub fn current() -> DecodeLimits {
+    LIMITS.with(|limits| limits.get())
+}
+
+/// Runs function with limits set for the current thread.
+///
+/// Previous limits are restored afterwards, even if function panics.
+pub fn with_limits<T, F: FnOnce() -> T>(limits: DecodeLimits, f: F) -> T {
+    struct Restore(DecodeLimits);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0;
+            LIMITS.with(|limits| limits.set(previous));
+        }
+    }
+
+    let _restore = Restore(LIMITS.with(|current| current.replace(limits)));
+
+    f()
+}
+
+/// Runs function with limits if they are set, otherwise with limits of the current thread.
+pub(crate) fn with_optional_limits<T, F: FnOnce() -> T>(limits: Option<DecodeLimits>, f: F) -> T {
+    match limits {
+        Some(limits) => with_limits(limits, f),
+        None => f(),
+    }
+}
+
+/// Reads length prefixed JSON string checking it against current limits.
+pub fn read_json<R: Read>(reader: &mut R) -> Result<String, DecodeError> {
+    let limits = current();
+    let length = read_length(reader)?;
+
+    if length > limits.json_max_length {
+        return Err(DecodeError::JsonTooLong {
+            length,
+            max_length: limits.json_max_length,
+        });
+    }
+
+    let mut buf = vec![0; length];
+    reader.read_exact(&mut buf)?;
+
+    let json = String::from_utf8(buf)?;
+    check_json_depth(&json, limits.json_max_depth)?;
+
+    Ok(json)
+}
+
+/// Reads plugin message payload which takes the rest of the packet.
+///
+/// Packet is already limited by frame length, so payload is read fully before it's checked.
+pub fn read_payload<R: Read>(reader: &mut R, max_length: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    if data.len() > max_length {
+        return Err(DecodeError::PayloadTooLong {
+            length: data.len(),
+            max_length,
+        });
+    }
+
+    Ok(data)
+}
+
+/// Reads NBT compound tag checking it against current limits.
+pub fn read_compound_tag<R: Read>(reader: &mut R) -> Result<CompoundTag, DecodeError> {
+    NbtScanner::new(reader, current()).read_compound_tag()
+}
+
+/// Reads var int prefixed list of NBT compound tags, limits apply to the whole list.
+///
+/// Count is not trusted for allocation, as every tag takes at least a few bytes of the limit.
+pub fn read_compound_tags<R: Read>(reader: &mut R) -> Result<Vec<CompoundTag>, DecodeError> {
+    let length = reader.read_var_i32()? as u32;
+    let mut scanner = NbtScanner::new(reader, current());
+    let mut compound_tags = Vec::new();
+
+    for _ in 0..length {
+        compound_tags.push(scanner.read_compound_tag()?);
+    }
+
+    Ok(compound_tags)
+}
+
+/// Copies NBT data checking limits, so `nbt` decodes only data which fits into them.
+struct NbtScanner<'a, R> {
+    reader: &'a mut R,
+    limits: DecodeLimits,
+    buf: Vec<u8>,
+    length: usize,
+    tags: usize,
+}
+
+impl<'a, R: Read> NbtScanner<'a, R> {
+    fn new(reader: &'a mut R, limits: DecodeLimits) -> Self {
+        NbtScanner {
+            reader,
+            limits,
+            buf: Vec::new(),
+            length: 0,
+            tags: 0,
+        }
+    }
+
+    fn read_compound_tag(&mut self) -> Result<CompoundTag, DecodeError> {
+        self.buf.clear();
+
+        let tag_id = self.take(1)?[0];
+        self.skip_string()?;
+        self.skip_tag(tag_id, 0)?;
+
+        Ok(nbt::decode::read_compound_tag(&mut self.buf.as_slice())?)
+    }
+
+    fn skip_tag(&mut self, tag_id: u8, depth: usize) -> Result<(), DecodeError> {
+        self.tags += 1;
+
+        if self.tags > self.limits.nbt_max_tags {
+            return Err(DecodeError::NbtTooManyTags {
+                max_tags: self.limits.nbt_max_tags,
+            });
+        }
+
+        match tag_id {
+            1 => self.skip(1),
+            2 => self.skip(2),
+            3 | 5 => self.skip(4),
+            4 | 6 => self.skip(8),
+            7 => self.skip_array(1),
+            8 => self.skip_string(),
+            9 => {
+                self.check_depth(depth + 1)?;
+
+                let element_id = self.take(1)?[0];
+                let length = self.take_u32()?;
+
+                for _ in 0..length {
+                    self.skip_tag(element_id, depth + 1)?;
+                }
+
+                Ok(())
+            }
+            10 => {
+                self.check_depth(depth + 1)?;
+
+                loop {
+                    let tag_id = self.take(1)?[0];
+
+                    if tag_id == 0 {
+                        return Ok(());
+                    }
+
+                    self.skip_string()?;
+                    self.skip_tag(tag_id, depth + 1)?;
+                }
+            }
+            11 => self.skip_array(4),
+            12 => self.skip_array(8),
+            tag_type_id => Err(TagDecodeError::UnknownTagType { tag_type_id }.into()),
+        }
+    }
+
+    fn check_depth(&self, depth: usize) -> Result<(), DecodeError> {
+        if depth > self.limits.nbt_max_depth {
+            return Err(DecodeError::NbtTooDeep {
+                max_depth: self.limits.nbt_max_depth,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn skip_string(&mut self) -> Result<(), DecodeError> {
+        let length = self.take(2)?.read_u16::<BigEndian>()?;
+
+        self.skip(length as usize)
+    }
+
+    fn skip_array(&mut self, element_length: usize) -> Result<(), DecodeError> {
+        let length = self.take_u32()? as usize;
+
+        self.skip(length.saturating_mul(element_length))
+    }
+
+    fn take_u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(self.take(4)?.read_u32::<BigEndian>()?)
+    }
+
+    fn skip(&mut self, length: usize) -> Result<(), DecodeError> {
+        self.take(length).map(|_| ())
+    }
+
+    /// Reads bytes into buffer after checking they fit into length limit.
+    fn take(&mut self, length: usize) -> Result<&[u8], DecodeError> {
+        if length > self.limits.nbt_max_length - self.length {
+            return Err(DecodeError::NbtTooLong {
+                max_length: self.limits.nbt_max_length,
+            });
+        }
+
+        self.length += length;
+
+        let start = self.buf.len();
+        self.buf.resize(start + length, 0);
+        self.reader.read_exact(&mut self.buf[start..])?;
+
+        Ok(&self.buf[start..])
+    }
+}
+
+/// Checks that nesting of JSON objects and arrays doesn't exceed maximum depth.
+///
+/// Document is not validated, brackets inside strings are skipped.
+pub fn check_json_depth(json: &str, max_depth: usize) -> Result<(), DecodeError> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for byte in json.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+
+                if depth > max_depth {
+                    return Err(DecodeError::JsonTooDeep { max_depth });
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encoder::EncoderWriteExt;
+    use crate::error::DecodeError;
+    use crate::limits::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_json_too_long() {
+        let mut vec = Vec::new();
+        vec.write_string(r#"{"text":"Hello"}"#, 32).unwrap();
+
+        let limits = DecodeLimits {
+            json_max_length: 8,
+            ..DecodeLimits::default()
+        };
+
+        let decode_error = with_limits(limits, || read_json(&mut Cursor::new(vec)))
+            .expect_err("Expected error `JsonTooLong` because json is too long");
+
+        match decode_error {
+            DecodeError::JsonTooLong { length, max_length } => {
+                assert_eq!(length, 16);
+                assert_eq!(max_length, 8);
+            }
+            _ => panic!("Expected `JsonTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_json_max_length_in_both_directions() {
+        use crate::encoder::write_json;
+        use crate::error::EncodeError;
+        use serde_json::Value;
+
+        // Quotes take 2 bytes.
+        let json = Value::String("a".repeat(JSON_MAX_LENGTH - 2));
+
+        let mut vec = Vec::new();
+        write_json(&mut vec, &json).unwrap();
+        assert_eq!(
+            read_json(&mut Cursor::new(vec)).unwrap().len(),
+            JSON_MAX_LENGTH
+        );
+
+        let json = Value::String("a".repeat(JSON_MAX_LENGTH - 1));
+
+        assert_eq!(
+            write_json(&mut Vec::new(), &json),
+            Err(EncodeError::JsonTooLong {
+                length: JSON_MAX_LENGTH + 1,
+                max_length: JSON_MAX_LENGTH
+            })
+        );
+    }
+
+    #[test]
+    fn test_read_json_negative_length() {
+        let mut cursor = Cursor::new(vec![0xff, 0xff, 0xff, 0xff, 0x0f, b'{', b'}']);
+
+        let decode_error = read_json(&mut cursor)
+            .expect_err("Expected error `NegativeLength` because length prefix is -1");
+
+        match decode_error {
+            DecodeError::NegativeLength { length } => assert_eq!(length, -1),
+            _ => panic!("Expected `NegativeLength` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_json_too_deep() {
+        let json = "[".repeat(65) + &"]".repeat(65);
+        let mut vec = Vec::new();
+        vec.write_string(&json, 256).unwrap();
+
+        let decode_error = read_json(&mut Cursor::new(vec))
+            .expect_err("Expected error `JsonTooDeep` because json is too deep");
+
+        match decode_error {
+            DecodeError::JsonTooDeep { max_depth } => assert_eq!(max_depth, 64),
+            _ => panic!("Expected `JsonTooDeep` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_check_json_depth_skips_strings() {
+        let json = r#"{"text":"[[[{{{\"[[["}"#;
+
+        assert!(check_json_depth(json, 1).is_ok());
+        assert!(check_json_depth(r#"{"extra":[{}]}"#, 2).is_err());
+    }
+
+    /// Root compound tag with a single unnamed tag of type.
+    fn nbt_with_tag(tag_id: u8, payload: &[u8]) -> Vec<u8> {
+        let mut vec = vec![10, 0, 0, tag_id, 0, 0];
+        vec.extend_from_slice(payload);
+        vec.push(0);
+
+        vec
+    }
+
+    #[test]
+    fn test_read_compound_tag() {
+        let vec = nbt_with_tag(3, &[0, 0, 0, 42]);
+        let compound_tag = read_compound_tag(&mut Cursor::new(vec)).unwrap();
+
+        assert_eq!(compound_tag.get_i32("").unwrap(), 42);
+    }
+
+    #[test]
+    fn test_read_compound_tag_too_deep() {
+        // Lists of lists nested deeper than vanilla allows.
+        let mut payload = Vec::new();
+
+        for _ in 0..NBT_MAX_DEPTH {
+            payload.extend_from_slice(&[9, 0, 0, 0, 1]);
+        }
+
+        payload.extend_from_slice(&[0, 0, 0, 0, 0]);
+
+        let decode_error = read_compound_tag(&mut Cursor::new(nbt_with_tag(9, &payload)))
+            .expect_err("Expected error `NbtTooDeep` because lists are nested too deep");
+
+        match decode_error {
+            DecodeError::NbtTooDeep { max_depth } => assert_eq!(max_depth, NBT_MAX_DEPTH),
+            _ => panic!("Expected `NbtTooDeep` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_compound_tag_huge_array() {
+        // Byte array claiming 2 GiB of data.
+        let vec = nbt_with_tag(7, &[0x7F, 0xFF, 0xFF, 0xFF]);
+
+        let decode_error = read_compound_tag(&mut Cursor::new(vec))
+            .expect_err("Expected error `NbtTooLong` because array is longer than limit");
+
+        match decode_error {
+            DecodeError::NbtTooLong { max_length } => assert_eq!(max_length, NBT_MAX_LENGTH),
+            _ => panic!("Expected `NbtTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_compound_tag_too_many_tags() {
+        let mut payload = vec![1, 0, 0, 0, 100];
+        payload.extend_from_slice(&[0; 100]);
+
+        let limits = DecodeLimits {
+            nbt_max_tags: 10,
+            ..DecodeLimits::default()
+        };
+
+        let decode_error = with_limits(limits, || {
+            read_compound_tag(&mut Cursor::new(nbt_with_tag(9, &payload)))
+        })
+        .expect_err("Expected error `NbtTooManyTags` because list has 100 elements");
+
+        match decode_error {
+            DecodeError::NbtTooManyTags { max_tags } => assert_eq!(max_tags, 10),
+            _ => panic!("Expected `NbtTooManyTags` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_compound_tags_huge_count() {
+        let mut vec = Vec::new();
+        vec.write_var_i32(i32::MAX).unwrap();
+        vec.extend_from_slice(&[10, 0, 0, 0]);
+
+        let decode_error = read_compound_tags(&mut Cursor::new(vec))
+            .expect_err("Expected error `IOError` because only one tag is sent");
+
+        match decode_error {
+            DecodeError::IOError { .. } => {}
+            _ => panic!("Expected `IOError` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_read_compound_tags_share_length_limit() {
+        // Empty compound tags of 4 bytes each.
+        let compound_tags = |count: usize| {
+            let mut vec = Vec::new();
+            vec.write_var_i32(count as i32).unwrap();
+            vec.extend([10, 0, 0, 0].repeat(count));
+
+            Cursor::new(vec)
+        };
+
+        let limits = DecodeLimits {
+            nbt_max_length: 8,
+            ..DecodeLimits::default()
+        };
+
+        let result = with_limits(limits, || read_compound_tags(&mut compound_tags(2)));
+        assert_eq!(result.unwrap().len(), 2);
+
+        let decode_error = with_limits(limits, || read_compound_tags(&mut compound_tags(3)))
+            .expect_err("Expected error `NbtTooLong` because tags take 12 bytes");
+
+        match decode_error {
+            DecodeError::NbtTooLong { max_length } => assert_eq!(max_length, 8),
+            _ => panic!("Expected `NbtTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_with_limits_restores_previous() {
+        let limits = DecodeLimits {
+            json_max_length: 1,
+            json_max_depth: 1,
+            ..DecodeLimits::default()
+        };
+
+        assert_eq!(with_limits(limits, current), limits);
+        assert_eq!(current(), DecodeLimits::default());
+    }
+}
@@ -0,0 +1,300 @@
+//! Server side helpers for the login state.
+//!
+//! `TokenManager` issues verify tokens sent in `EncryptionRequest` and checks tokens returned in
+//! `EncryptionResponse`. Client encrypts returned token with server public key, so it must be
+//! decrypted before validation.
+//!
+//! `LoginRateLimiter` limits number of login attempts from a single address.
+//!
+//! Both read time from `SystemClock`, `with_clock` constructors take another `Clock`, e.g.
+//! `MockClock` in tests.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::login::TokenManager;
+//! use std::time::Duration;
+//!
+//! let mut token_manager = TokenManager::new(Duration::from_secs(30));
+//!
+//! // Send token in encryption request.
+//! let verify_token = token_manager.issue(1);
+//!
+//! // Validate decrypted token from encryption response.
+//! assert!(token_manager.validate(&1, &verify_token).is_ok());
+//! ```
+use crate::clock::{Clock, SystemClock};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Vanilla server uses 4 bytes verify tokens.
+pub const VERIFY_TOKEN_LENGTH: usize = 4;
+
+/// Possible errors while validating verify token.
+#[derive(Debug, Eq, PartialEq)]
+pub enum TokenError {
+    /// Token was not issued for connection or was already validated.
+    Missing,
+    /// Token was issued too long ago.
+    Expired,
+    /// Token doesn't match issued token.
+    Mismatch,
+}
+
+struct PendingToken {
+    token: [u8; VERIFY_TOKEN_LENGTH],
+    issued_at: Instant,
+}
+
+/// Keeps verify tokens of connections waiting for `EncryptionResponse`.
+///
+/// Tokens are keyed by any connection identifier and can be validated only once.
+pub struct TokenManager<K, C = SystemClock> {
+    tokens: HashMap<K, PendingToken>,
+    ttl: Duration,
+    clock: C,
+}
+
+impl<K: Eq + Hash> TokenManager<K> {
+    pub fn new(ttl: Duration) -> Self {
+        TokenManager::with_clock(ttl, SystemClock)
+    }
+}
+
+impl<K: Eq + Hash, C: Clock> TokenManager<K, C> {
+    pub fn with_clock(ttl: Duration, clock: C) -> Self {
+        TokenManager {
+            tokens: HashMap::new(),
+            ttl,
+            clock,
+        }
+    }
+
+    /// Generates random token for connection replacing previously issued token.
+    ///
+    /// # Panics
+    ///
+    /// Panics if operating system random number generator is not available.
+    pub fn issue(&mut self, key: K) -> Vec<u8> {
+        let mut token = [0; VERIFY_TOKEN_LENGTH];
+        getrandom::getrandom(&mut token).expect("Failed to generate verify token");
+
+        let pending_token = PendingToken {
+            token,
+            issued_at: self.clock.now(),
+        };
+
+        self.tokens.insert(key, pending_token);
+
+        token.to_vec()
+    }
+
+    /// Validates decrypted token and forgets issued token.
+    pub fn validate(&mut self, key: &K, token: &[u8]) -> Result<(), TokenError> {
+        let pending_token = self.tokens.remove(key).ok_or(TokenError::Missing)?;
+
+        if self.clock.elapsed_since(pending_token.issued_at) >= self.ttl {
+            return Err(TokenError::Expired);
+        }
+
+        if !constant_time_eq(&pending_token.token, token) {
+            return Err(TokenError::Mismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Forgets issued token, for example when connection is closed.
+    pub fn remove(&mut self, key: &K) {
+        self.tokens.remove(key);
+    }
+
+    /// Forgets all expired tokens.
+    pub fn remove_expired(&mut self) {
+        let now = self.clock.now();
+        let ttl = self.ttl;
+
+        self.tokens.retain(|_, pending_token| {
+            now.saturating_duration_since(pending_token.issued_at) < ttl
+        });
+    }
+
+    /// Number of tokens waiting for validation.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+struct Attempts {
+    count: u32,
+    window_start: Instant,
+}
+
+/// Limits number of login attempts per address within a time window.
+pub struct LoginRateLimiter<C = SystemClock> {
+    attempts: HashMap<IpAddr, Attempts>,
+    max_attempts: u32,
+    window: Duration,
+    clock: C,
+}
+
+impl LoginRateLimiter {
+    pub fn new(max_attempts: u32, window: Duration) -> Self {
+        LoginRateLimiter::with_clock(max_attempts, window, SystemClock)
+    }
+}
+
+impl<C: Clock> LoginRateLimiter<C> {
+    pub fn with_clock(max_attempts: u32, window: Duration, clock: C) -> Self {
+        LoginRateLimiter {
+            attempts: HashMap::new(),
+            max_attempts,
+            window,
+            clock,
+        }
+    }
+
+    /// Records login attempt and returns `false` if address exceeded the limit.
+    pub fn try_acquire(&mut self, addr: IpAddr) -> bool {
+        let now = self.clock.now();
+        let window = self.window;
+
+        let attempts = self.attempts.entry(addr).or_insert(Attempts {
+            count: 0,
+            window_start: now,
+        });
+
+        if now.duration_since(attempts.window_start) >= window {
+            attempts.count = 0;
+            attempts.window_start = now;
+        }
+
+        if attempts.count >= self.max_attempts {
+            return false;
+        }
+
+        attempts.count += 1;
+
+        true
+    }
+
+    /// Forgets addresses which window has passed.
+    pub fn remove_expired(&mut self) {
+        let now = self.clock.now();
+        let window = self.window;
+
+        self.attempts
+            .retain(|_, attempts| now.saturating_duration_since(attempts.window_start) < window);
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::MockClock;
+    use crate::login::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_token_validate() {
+        let mut token_manager = TokenManager::new(Duration::from_secs(30));
+        let token = token_manager.issue("player");
+
+        assert_eq!(token.len(), VERIFY_TOKEN_LENGTH);
+        assert_eq!(token_manager.validate(&"player", &token), Ok(()));
+        assert_eq!(
+            token_manager.validate(&"player", &token),
+            Err(TokenError::Missing)
+        );
+    }
+
+    #[test]
+    fn test_token_mismatch() {
+        let mut token_manager = TokenManager::new(Duration::from_secs(30));
+        let mut token = token_manager.issue(1);
+        token[0] = token[0].wrapping_add(1);
+
+        assert_eq!(
+            token_manager.validate(&1, &token),
+            Err(TokenError::Mismatch)
+        );
+        assert!(token_manager.is_empty());
+    }
+
+    #[test]
+    fn test_token_expired() {
+        let mut token_manager = TokenManager::new(Duration::from_secs(0));
+        let token = token_manager.issue(1);
+
+        assert_eq!(token_manager.validate(&1, &token), Err(TokenError::Expired));
+
+        token_manager.issue(2);
+        token_manager.remove_expired();
+
+        assert!(token_manager.is_empty());
+    }
+
+    #[test]
+    fn test_token_expires_with_clock() {
+        let clock = MockClock::new();
+        let mut token_manager = TokenManager::with_clock(Duration::from_secs(30), clock.clone());
+        let first = token_manager.issue(1);
+        token_manager.issue(2);
+
+        clock.advance(Duration::from_secs(29));
+        assert_eq!(token_manager.validate(&1, &first), Ok(()));
+
+        clock.advance(Duration::from_secs(1));
+        token_manager.remove_expired();
+        assert!(token_manager.is_empty());
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut rate_limiter = LoginRateLimiter::new(2, Duration::from_secs(30));
+        let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        assert!(rate_limiter.try_acquire(addr));
+        assert!(rate_limiter.try_acquire(addr));
+        assert!(!rate_limiter.try_acquire(addr));
+        assert!(rate_limiter.try_acquire(IpAddr::V4(Ipv4Addr::UNSPECIFIED)));
+    }
+
+    #[test]
+    fn test_rate_limiter_window_reset() {
+        let mut rate_limiter = LoginRateLimiter::new(1, Duration::from_secs(0));
+        let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        assert!(rate_limiter.try_acquire(addr));
+        assert!(rate_limiter.try_acquire(addr));
+    }
+
+    #[test]
+    fn test_rate_limiter_remove_expired() {
+        let clock = MockClock::new();
+        let mut rate_limiter = LoginRateLimiter::with_clock(1, Duration::from_secs(30), &clock);
+        let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        assert!(rate_limiter.try_acquire(addr));
+        clock.advance(Duration::from_secs(10));
+        rate_limiter.remove_expired();
+        assert!(!rate_limiter.try_acquire(addr));
+
+        clock.advance(Duration::from_secs(20));
+        rate_limiter.remove_expired();
+        assert!(rate_limiter.attempts.is_empty());
+    }
+}
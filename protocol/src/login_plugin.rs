@@ -0,0 +1,317 @@
+//! Negotiation with login plugin messages.
+//!
+//! Server sends `LoginPluginRequest` on a channel and client must answer every request with
+//! `LoginPluginResponse` carrying the same message id, even if it doesn't know the channel.
+//! Proxies use this loop for player info forwarding, mod loaders for their handshakes.
+//!
+//! `LoginPluginNegotiator` allocates message ids on server side and routes responses to handlers
+//! of channels which sent the requests. Login can continue once nothing is outstanding.
+//! `LoginPluginResponder` answers requests on client side, requests on channels without a
+//! handler are answered as not understood, like vanilla client does.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::login_plugin::{LoginPluginNegotiator, LoginPluginResponder};
+//! use minecraft_protocol::version::v1_14_4::login::*;
+//!
+//! let mut negotiator = LoginPluginNegotiator::new();
+//! negotiator.register("example:hello", |data: Option<&[u8]>, names: &mut Vec<String>| {
+//!     names.extend(data.map(|data| String::from_utf8_lossy(data).into_owned()))
+//! });
+//!
+//! let mut responder = LoginPluginResponder::new();
+//! responder.register("example:hello", |_: &[u8], _: &mut ()| Some(b"Username".to_vec()));
+//!
+//! let request = match negotiator.request("example:hello", Vec::new()).unwrap() {
+//!     LoginClientBoundPacket::LoginPluginRequest(request) => request,
+//!     _ => unreachable!(),
+//! };
+//!
+//! let response = match responder.respond(&request, &mut ()) {
+//!     LoginServerBoundPacket::LoginPluginResponse(response) => response,
+//!     _ => unreachable!(),
+//! };
+//!
+//! let mut names = Vec::new();
+//! negotiator.handle_response(&response, &mut names).unwrap();
+//!
+//! assert_eq!(names, ["Username"]);
+//! assert!(negotiator.is_finished());
+//! ```
+use crate::version::v1_14_4::login::{
+    LoginClientBoundPacket, LoginPluginRequest, LoginPluginResponse, LoginServerBoundPacket,
+};
+use std::collections::HashMap;
+
+/// Possible errors of login plugin negotiation.
+#[derive(Debug, Eq, PartialEq)]
+pub enum LoginPluginError {
+    /// Request was sent on channel without handler.
+    UnregisteredChannel { channel: String },
+    /// Response doesn't answer any outstanding request.
+    UnknownMessageId { message_id: i32 },
+}
+
+type ResponseHandler<C> = Box<dyn FnMut(Option<&[u8]>, &mut C)>;
+type RequestHandler<C> = Box<dyn FnMut(&[u8], &mut C) -> Option<Vec<u8>>>;
+
+/// Sends login plugin requests and routes responses to channel handlers on server side.
+pub struct LoginPluginNegotiator<C> {
+    handlers: HashMap<String, ResponseHandler<C>>,
+    /// Channels of requests waiting for response by message id.
+    outstanding: HashMap<i32, String>,
+    next_message_id: i32,
+}
+
+impl<C> LoginPluginNegotiator<C> {
+    pub fn new() -> Self {
+        LoginPluginNegotiator {
+            handlers: HashMap::new(),
+            outstanding: HashMap::new(),
+            next_message_id: 0,
+        }
+    }
+
+    /// Registers handler of responses on channel, replacing previous handler.
+    ///
+    /// Handler receives response data, or `None` if client didn't understand the request.
+    pub fn register<F>(&mut self, channel: &str, handler: F) -> &mut Self
+    where
+        F: FnMut(Option<&[u8]>, &mut C) + 'static,
+    {
+        self.handlers.insert(channel.to_owned(), Box::new(handler));
+
+        self
+    }
+
+    /// Returns request with new message id which must be sent to client.
+    pub fn request(
+        &mut self,
+        channel: &str,
+        data: Vec<u8>,
+    ) -> Result<LoginClientBoundPacket, LoginPluginError> {
+        if !self.handlers.contains_key(channel) {
+            return Err(LoginPluginError::UnregisteredChannel {
+                channel: channel.to_owned(),
+            });
+        }
+
+        let message_id = self.next_message_id;
+        self.next_message_id = self.next_message_id.wrapping_add(1);
+        self.outstanding.insert(message_id, channel.to_owned());
+
+        Ok(LoginPluginRequest::new(
+            message_id,
+            channel.to_owned(),
+            data,
+        ))
+    }
+
+    /// Calls handler of channel which sent the request answered by response.
+    pub fn handle_response(
+        &mut self,
+        response: &LoginPluginResponse,
+        context: &mut C,
+    ) -> Result<(), LoginPluginError> {
+        let message_id = response.message_id;
+        let channel = self
+            .outstanding
+            .remove(&message_id)
+            .ok_or(LoginPluginError::UnknownMessageId { message_id })?;
+
+        let data = response.successful.then_some(response.data.as_slice());
+
+        if let Some(handler) = self.handlers.get_mut(&channel) {
+            handler(data, context);
+        }
+
+        Ok(())
+    }
+
+    /// Returns channel of request with message id if it's still waiting for response.
+    pub fn outstanding_channel(&self, message_id: i32) -> Option<&str> {
+        self.outstanding.get(&message_id).map(String::as_str)
+    }
+
+    /// Number of requests waiting for response.
+    pub fn outstanding(&self) -> usize {
+        self.outstanding.len()
+    }
+
+    /// Returns `true` if every request was answered, so login can continue.
+    pub fn is_finished(&self) -> bool {
+        self.outstanding.is_empty()
+    }
+}
+
+impl<C> Default for LoginPluginNegotiator<C> {
+    fn default() -> Self {
+        LoginPluginNegotiator::new()
+    }
+}
+
+/// Answers login plugin requests on client side.
+pub struct LoginPluginResponder<C> {
+    handlers: HashMap<String, RequestHandler<C>>,
+}
+
+impl<C> LoginPluginResponder<C> {
+    pub fn new() -> Self {
+        LoginPluginResponder {
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Registers handler of requests on channel, replacing previous handler.
+    ///
+    /// Handler returns response data, or `None` if it doesn't understand the request.
+    pub fn register<F>(&mut self, channel: &str, handler: F) -> &mut Self
+    where
+        F: FnMut(&[u8], &mut C) -> Option<Vec<u8>> + 'static,
+    {
+        self.handlers.insert(channel.to_owned(), Box::new(handler));
+
+        self
+    }
+
+    /// Returns response to request, which is not understood if channel has no handler.
+    pub fn respond(
+        &mut self,
+        request: &LoginPluginRequest,
+        context: &mut C,
+    ) -> LoginServerBoundPacket {
+        let data = self
+            .handlers
+            .get_mut(&request.channel)
+            .and_then(|handler| handler(&request.data, context));
+
+        match data {
+            Some(data) => LoginPluginResponse::new(request.message_id, true, data),
+            None => LoginPluginResponse::new(request.message_id, false, Vec::new()),
+        }
+    }
+}
+
+impl<C> Default for LoginPluginResponder<C> {
+    fn default() -> Self {
+        LoginPluginResponder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::login_plugin::*;
+
+    fn request(packet: LoginClientBoundPacket) -> LoginPluginRequest {
+        match packet {
+            LoginClientBoundPacket::LoginPluginRequest(request) => request,
+            packet => panic!("Expected `LoginPluginRequest` but got `{:?}`", packet),
+        }
+    }
+
+    fn response(packet: LoginServerBoundPacket) -> LoginPluginResponse {
+        match packet {
+            LoginServerBoundPacket::LoginPluginResponse(response) => response,
+            packet => panic!("Expected `LoginPluginResponse` but got `{:?}`", packet),
+        }
+    }
+
+    #[test]
+    fn test_responses_routed_by_message_id() {
+        let mut negotiator = LoginPluginNegotiator::new();
+        negotiator
+            .register("a:first", |data: Option<&[u8]>, log: &mut Vec<String>| {
+                log.push(format!("first {:?}", data))
+            })
+            .register("a:second", |data: Option<&[u8]>, log: &mut Vec<String>| {
+                log.push(format!("second {:?}", data))
+            });
+
+        let first = request(negotiator.request("a:first", vec![1]).unwrap());
+        let second = request(negotiator.request("a:second", vec![2]).unwrap());
+
+        assert_ne!(first.message_id, second.message_id);
+        assert_eq!(negotiator.outstanding(), 2);
+        assert_eq!(
+            negotiator.outstanding_channel(second.message_id),
+            Some("a:second")
+        );
+
+        let mut responder = LoginPluginResponder::new();
+        responder.register("a:second", |data: &[u8], _: &mut ()| Some(data.to_vec()));
+
+        let mut log = Vec::new();
+        let second_response = response(responder.respond(&second, &mut ()));
+        negotiator
+            .handle_response(&second_response, &mut log)
+            .unwrap();
+
+        assert!(!negotiator.is_finished());
+
+        let first_response = response(responder.respond(&first, &mut ()));
+        negotiator
+            .handle_response(&first_response, &mut log)
+            .unwrap();
+
+        assert!(negotiator.is_finished());
+        assert_eq!(log, ["second Some([2])", "first None"]);
+    }
+
+    #[test]
+    fn test_not_understood_by_default() {
+        let mut responder: LoginPluginResponder<()> = LoginPluginResponder::new();
+        let request = request(LoginPluginRequest::new(
+            7,
+            String::from("velocity:player_info"),
+            vec![1],
+        ));
+
+        let response = response(responder.respond(&request, &mut ()));
+
+        assert_eq!(response.message_id, 7);
+        assert!(!response.successful);
+        assert!(response.data.is_empty());
+    }
+
+    #[test]
+    fn test_request_on_unregistered_channel() {
+        let mut negotiator: LoginPluginNegotiator<()> = LoginPluginNegotiator::new();
+
+        let error = negotiator
+            .request("a:missing", Vec::new())
+            .expect_err("Expected error `UnregisteredChannel` because channel has no handler");
+
+        match error {
+            LoginPluginError::UnregisteredChannel { channel } => assert_eq!(channel, "a:missing"),
+            _ => panic!("Expected `UnregisteredChannel` but got `{:?}`", error),
+        }
+
+        assert!(negotiator.is_finished());
+    }
+
+    #[test]
+    fn test_unknown_message_id() {
+        let mut negotiator = LoginPluginNegotiator::new();
+        negotiator.register("a:first", |_: Option<&[u8]>, _: &mut ()| {});
+
+        let request = request(negotiator.request("a:first", Vec::new()).unwrap());
+        let response = response(LoginPluginResponse::new(
+            request.message_id,
+            false,
+            Vec::new(),
+        ));
+        negotiator.handle_response(&response, &mut ()).unwrap();
+
+        let error = negotiator
+            .handle_response(&response, &mut ())
+            .expect_err("Expected error `UnknownMessageId` because response was already handled");
+
+        match error {
+            LoginPluginError::UnknownMessageId { message_id } => {
+                assert_eq!(message_id, request.message_id)
+            }
+            _ => panic!("Expected `UnknownMessageId` but got `{:?}`", error),
+        }
+    }
+}
@@ -0,0 +1,739 @@
+//! Packet framing and dispatch.
+//!
+//! Every packet is sent as a frame: var int length followed by var int packet id and packet data.
+//! `RawPacket` reads and writes frames without knowing packet types, `Dispatcher` decodes frames
+//! into packet enums and decides what to do with packets which are not implemented.
+//! `Dispatcher::with_capture_ring` also records which unknown packets peers send and how often,
+//! with a sample of their data, to find packets worth implementing next.
+//!
+//! Reader ending inside a frame is reported as `DecodeError::Incomplete`, so buffering code can
+//! wait for more data. Once a frame is read completely, running out of packet data while decoding
+//! it means the packet is malformed and is reported as IO error.
+//!
+//! Packet enums are `#[non_exhaustive]` because new packets are added without major release.
+//! Matches on them outside this crate need a catch-all arm, which usually logs `name()` of the
+//! packet and ignores it.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::packet::{Dispatched, Dispatcher, OnUnknown, RawPacket};
+//! use minecraft_protocol::version::v1_14_4::game::{ClientBoundKeepAlive, GameClientBoundPacket};
+//!
+//! let mut stream = Vec::new();
+//! RawPacket::new(0x7F, vec![1, 2, 3]).encode(&mut stream).unwrap();
+//! RawPacket::from_packet(&ClientBoundKeepAlive::new(42))
+//!     .unwrap()
+//!     .encode(&mut stream)
+//!     .unwrap();
+//!
+//! let dispatcher = Dispatcher::new(OnUnknown::Skip);
+//! let mut reader = stream.as_slice();
+//!
+//! let packet = dispatcher.read_packet::<GameClientBoundPacket, _>(&mut reader).unwrap();
+//! assert!(matches!(packet, Dispatched::Skipped { type_id: 0x7F }));
+//!
+//! let packet = dispatcher.read_packet::<GameClientBoundPacket, _>(&mut reader).unwrap();
+//! assert!(matches!(packet, Dispatched::Packet(GameClientBoundPacket::ClientBoundKeepAlive(_))));
+//! # }
+//! ```
+use crate::decoder::DecoderReadExt;
+use crate::encoder::{var_i32_size, write_length, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use std::collections::VecDeque;
+use std::io::{self, Cursor, ErrorKind, IoSlice, Read, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+/// Protocol limits packet length to 3 bytes var int.
+pub const PACKET_MAX_LENGTH: usize = 2_097_151;
+
+/// Packet enum of a protocol state and direction.
+pub trait Packet: Sized {
+    fn get_type_id(&self) -> u8;
+
+    /// Returns packet name, which is the name of enum variant.
+    fn name(&self) -> &'static str;
+
+    /// Returns type ids of all packets in enum.
+    fn iter_ids() -> impl Iterator<Item = u8>;
+
+    fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError>;
+
+    /// Encodes packet data without packet id.
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError>;
+
+    /// Returns length of uncompressed packet frame including length prefix.
+    ///
+    /// Packet is encoded to count bytes, but nothing is allocated for data.
+    fn encoded_len(&self) -> Result<usize, EncodeError> {
+        let mut counter = LengthCounter(0);
+        self.encode(&mut counter)?;
+
+        Ok(frame_len(
+            var_i32_size(self.get_type_id() as i32) + counter.0,
+        ))
+    }
+
+    /// Returns hash of packet id and encoded data.
+    ///
+    /// Hash doesn't depend on process, platform or crate version as long as packet is encoded
+    /// the same way, so it can be used as a key of encoded packet caches.
+    fn stable_hash(&self) -> Result<u64, EncodeError> {
+        let mut hasher = StableHasher::new();
+        hasher.write_var_i32(self.get_type_id() as i32)?;
+        self.encode(&mut hasher)?;
+
+        Ok(hasher.finish())
+    }
+}
+
+/// 64-bit FNV-1a hash of written bytes.
+#[derive(Debug, Copy, Clone)]
+pub struct StableHasher(u64);
+
+impl StableHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    pub fn new() -> Self {
+        StableHasher(Self::OFFSET_BASIS)
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher::new()
+    }
+}
+
+impl Write for StableHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for byte in buf {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(Self::PRIME);
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writer which only counts written bytes.
+pub(crate) struct LengthCounter(pub(crate) usize);
+
+impl Write for LengthCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn frame_len(body_len: usize) -> usize {
+    var_i32_size(body_len as i32) + body_len
+}
+
+// Unused when all version features are disabled.
+#[allow(unused_macros)]
+macro_rules! impl_packet (
+    ($ty: ident) => (
+        impl $crate::packet::Packet for $ty {
+            fn get_type_id(&self) -> u8 {
+                $ty::get_type_id(self)
+            }
+
+            fn name(&self) -> &'static str {
+                $ty::name(self)
+            }
+
+            fn iter_ids() -> impl Iterator<Item = u8> {
+                $ty::iter_ids()
+            }
+
+            fn decode<R: std::io::Read>(type_id: u8, reader: &mut R) -> Result<Self, $crate::error::DecodeError> {
+                $ty::decode(type_id, reader)
+            }
+
+            fn encode<W: std::io::Write>(&self, writer: &mut W) -> Result<(), $crate::error::EncodeError> {
+                $ty::encode(self, writer)
+            }
+        }
+    );
+);
+
+#[allow(unused_imports)]
+pub(crate) use impl_packet;
+
+/// Declares tests checking that packet value encodes into fixture under `test/packet` and the
+/// fixture decodes back into the same value.
+///
+/// Decoded value is compared by encoding it again, so packets don't need `PartialEq`.
+///
+/// ```ignore
+/// roundtrip_tests! {
+///     test_keep_alive_roundtrip: ClientBoundKeepAlive = ClientBoundKeepAlive { id: 240714 } => "game/client_bound_keep_alive.dat";
+/// }
+/// ```
+#[cfg(test)]
+#[allow(unused_macros)]
+macro_rules! roundtrip_tests (
+    ($($name: ident: $ty: ty = $value: expr => $fixture: literal;)*) => (
+        $(
+            #[test]
+            fn $name() {
+                let fixture = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/test/packet/", $fixture));
+                let value: $ty = $value;
+
+                $crate::packet::assert_roundtrip(&value, fixture);
+            }
+        )*
+    );
+);
+
+#[cfg(test)]
+#[allow(unused_imports)]
+pub(crate) use roundtrip_tests;
+
+/// Checks value against fixture, see `roundtrip_tests`.
+#[cfg(test)]
+#[allow(dead_code)]
+pub(crate) fn assert_roundtrip<T>(value: &T, fixture: &[u8])
+where
+    T: crate::encoder::Encoder + crate::decoder::Decoder<Output = T>,
+{
+    let mut vec = Vec::new();
+    value.encode(&mut vec).unwrap();
+    assert_eq!(vec, fixture, "value doesn't encode into fixture");
+
+    let mut reader = fixture;
+    let decoded = T::decode(&mut reader).unwrap();
+    assert!(reader.is_empty(), "fixture has {} bytes left", reader.len());
+
+    let mut vec = Vec::new();
+    decoded.encode(&mut vec).unwrap();
+    assert_eq!(vec, fixture, "decoded fixture doesn't encode into fixture");
+}
+
+/// Packet frame with undecoded packet data.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RawPacket {
+    pub id: i32,
+    pub data: Vec<u8>,
+}
+
+impl RawPacket {
+    pub fn new(id: i32, data: Vec<u8>) -> Self {
+        RawPacket { id, data }
+    }
+
+    pub fn from_packet<P: Packet>(packet: &P) -> Result<Self, EncodeError> {
+        let mut data = Vec::new();
+        packet.encode(&mut data)?;
+
+        Ok(RawPacket::new(packet.get_type_id() as i32, data))
+    }
+
+    /// Reads length prefixed frame.
+    ///
+    /// Returns `DecodeError::Incomplete` if reader ends inside the frame. Missing byte count is
+    /// known only when the length prefix was read completely.
+    pub fn decode<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        RawPacket::decode_with_max_length(reader, PACKET_MAX_LENGTH)
+    }
+
+    /// Reads length prefixed frame returning `DecodeError::PacketTooLong` if frame is longer
+    /// than max length.
+    pub fn decode_with_max_length<R: Read>(
+        reader: &mut R,
+        max_length: usize,
+    ) -> Result<Self, DecodeError> {
+        RawPacket::from_body(read_frame(reader, max_length)?)
+    }
+
+    /// Splits frame body into packet id and packet data.
+    pub(crate) fn from_body(body: Vec<u8>) -> Result<Self, DecodeError> {
+        let mut cursor = Cursor::new(body);
+        let id = cursor.read_var_i32()?;
+        let position = cursor.position() as usize;
+
+        let mut data = cursor.into_inner();
+        data.drain(..position);
+
+        Ok(RawPacket::new(id, data))
+    }
+
+    /// Writes length prefixed frame.
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        let header = self.header()?;
+        let mut bufs = [IoSlice::new(&header), IoSlice::new(&self.data)];
+
+        Ok(write_all_vectored(writer, &mut bufs)?)
+    }
+
+    /// Returns length of uncompressed frame including length prefix.
+    pub fn encoded_len(&self) -> usize {
+        frame_len(var_i32_size(self.id) + self.data.len())
+    }
+
+    /// Returns frame length and packet id which precede packet data.
+    ///
+    /// Returns `EncodeError::LengthOutOfRange` if frame length doesn't fit into var int.
+    pub fn header(&self) -> Result<Vec<u8>, EncodeError> {
+        let id_size = var_i32_size(self.id);
+        let length = id_size + self.data.len();
+
+        let mut header = Vec::with_capacity(frame_len(length) - self.data.len());
+        write_length(&mut header, length)?;
+        header.write_var_i32(self.id)?;
+
+        Ok(header)
+    }
+
+    /// Returns the same hash as `Packet::stable_hash` of the packet.
+    pub fn stable_hash(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        // Hasher never fails to write.
+        hasher.write_var_i32(self.id).unwrap();
+        hasher.write_all(&self.data).unwrap();
+
+        hasher.finish()
+    }
+
+    /// Decodes packet data as packet of given enum.
+    pub fn decode_packet<P: Packet>(&self) -> Result<P, DecodeError> {
+        if !(0..=0xFF).contains(&self.id) {
+            return Err(DecodeError::PacketIdOutOfRange { id: self.id });
+        }
+
+        P::decode(self.id as u8, &mut Cursor::new(&self.data))
+    }
+}
+
+/// Reads length prefixed frame and returns its body.
+pub(crate) fn read_frame<R: Read>(
+    reader: &mut R,
+    max_length: usize,
+) -> Result<Vec<u8>, DecodeError> {
+    let length = match reader.read_var_i32() {
+        Ok(length) => length as u32 as usize,
+        Err(DecodeError::IOError { io_error }) if io_error.kind() == ErrorKind::UnexpectedEof => {
+            return Err(DecodeError::Incomplete { needed: None });
+        }
+        Err(decode_error) => return Err(decode_error),
+    };
+
+    if length > max_length {
+        return Err(DecodeError::PacketTooLong { length, max_length });
+    }
+
+    let mut buf = Vec::with_capacity(length);
+    reader.take(length as u64).read_to_end(&mut buf)?;
+
+    if buf.len() < length {
+        return Err(DecodeError::Incomplete {
+            needed: Some(length - buf.len()),
+        });
+    }
+
+    Ok(buf)
+}
+
+/// Writes all buffers retrying partial vectored writes.
+pub(crate) fn write_all_vectored<W: Write>(
+    writer: &mut W,
+    mut bufs: &mut [IoSlice<'_>],
+) -> io::Result<()> {
+    IoSlice::advance_slices(&mut bufs, 0);
+
+    while !bufs.is_empty() {
+        match writer.write_vectored(bufs) {
+            Ok(0) => return Err(io::Error::from(ErrorKind::WriteZero)),
+            Ok(written) => IoSlice::advance_slices(&mut bufs, written),
+            Err(io_error) if io_error.kind() == ErrorKind::Interrupted => {}
+            Err(io_error) => return Err(io_error),
+        }
+    }
+
+    Ok(())
+}
+
+/// What to do with packets which have no implementation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OnUnknown {
+    /// Return `Dispatched::Skipped` and keep going.
+    Skip,
+    /// Return `DecodeError::UnknownPacketType` or `DecodeError::PacketIdOutOfRange`.
+    Error,
+    /// Return `Dispatched::Captured` with packet frame.
+    Capture,
+}
+
+/// Result of frame dispatch.
+#[derive(Debug)]
+pub enum Dispatched<P> {
+    Packet(P),
+    Skipped { type_id: i32 },
+    Captured(RawPacket),
+}
+
+/// Bytes of data of unknown packet kept as sample.
+const SAMPLE_LENGTH: usize = 32;
+
+/// Unknown packet id seen by dispatcher.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnknownPacket {
+    pub type_id: i32,
+    /// Number of frames with this id.
+    pub count: u64,
+    /// Hex dump of the beginning of data of the first frame.
+    pub sample: String,
+    /// Data length of the first frame.
+    pub data_length: usize,
+    pub first_seen: SystemTime,
+    pub last_seen: SystemTime,
+}
+
+/// Unknown packet ids from least to most recently seen.
+#[derive(Debug)]
+struct CaptureRing {
+    capacity: usize,
+    packets: VecDeque<UnknownPacket>,
+}
+
+impl CaptureRing {
+    /// Records frame, evicting least recently seen id if ring is full.
+    fn record(&mut self, raw_packet: &RawPacket) {
+        let now = SystemTime::now();
+
+        let position = self
+            .packets
+            .iter()
+            .position(|packet| packet.type_id == raw_packet.id);
+
+        let unknown_packet = match position.and_then(|idx| self.packets.remove(idx)) {
+            Some(mut unknown_packet) => {
+                unknown_packet.count += 1;
+                unknown_packet.last_seen = now;
+
+                unknown_packet
+            }
+            None => {
+                if self.packets.len() == self.capacity {
+                    self.packets.pop_front();
+                }
+
+                UnknownPacket {
+                    type_id: raw_packet.id,
+                    count: 1,
+                    sample: hex_dump(&raw_packet.data[..raw_packet.data.len().min(SAMPLE_LENGTH)]),
+                    data_length: raw_packet.data.len(),
+                    first_seen: now,
+                    last_seen: now,
+                }
+            }
+        };
+
+        self.packets.push_back(unknown_packet);
+    }
+}
+
+fn hex_dump(data: &[u8]) -> String {
+    data.iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Decodes frames into packet enums.
+#[derive(Debug, Clone)]
+pub struct Dispatcher {
+    on_unknown: OnUnknown,
+    capture_ring: Option<Arc<Mutex<CaptureRing>>>,
+}
+
+impl Dispatcher {
+    pub fn new(on_unknown: OnUnknown) -> Self {
+        Dispatcher {
+            on_unknown,
+            capture_ring: None,
+        }
+    }
+
+    /// Records unknown packets whatever the policy is, keeping up to `capacity` most recently
+    /// seen ids. Clones of dispatcher share recorded packets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if capacity is 0.
+    pub fn with_capture_ring(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "Capture ring capacity must be positive");
+
+        let capture_ring = CaptureRing {
+            capacity,
+            packets: VecDeque::with_capacity(capacity),
+        };
+        self.capture_ring = Some(Arc::new(Mutex::new(capture_ring)));
+        self
+    }
+
+    /// Returns recorded unknown packets, most frequent first. Empty without capture ring.
+    pub fn unknown_packets(&self) -> Vec<UnknownPacket> {
+        let mut packets: Vec<UnknownPacket> = match &self.capture_ring {
+            Some(capture_ring) => capture_ring
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .packets
+                .iter()
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+
+        packets.sort_by(|a, b| b.count.cmp(&a.count).then(a.type_id.cmp(&b.type_id)));
+        packets
+    }
+
+    pub fn dispatch<P: Packet>(&self, raw_packet: RawPacket) -> Result<Dispatched<P>, DecodeError> {
+        match raw_packet.decode_packet() {
+            Ok(packet) => Ok(Dispatched::Packet(packet)),
+            Err(
+                decode_error @ DecodeError::UnknownPacketType { .. }
+                | decode_error @ DecodeError::PacketIdOutOfRange { .. },
+            ) => {
+                self.record_unknown(&raw_packet);
+
+                match self.on_unknown {
+                    OnUnknown::Skip => Ok(Dispatched::Skipped {
+                        type_id: raw_packet.id,
+                    }),
+                    OnUnknown::Error => Err(decode_error),
+                    OnUnknown::Capture => Ok(Dispatched::Captured(raw_packet)),
+                }
+            }
+            Err(decode_error) => Err(decode_error),
+        }
+    }
+
+    fn record_unknown(&self, raw_packet: &RawPacket) {
+        if let Some(capture_ring) = &self.capture_ring {
+            capture_ring
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .record(raw_packet);
+        }
+    }
+
+    /// Reads frame and dispatches it. Unknown packet data is consumed, so reader is positioned at
+    /// the next frame whatever the policy is.
+    pub fn read_packet<P: Packet, R: Read>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Dispatched<P>, DecodeError> {
+        let raw_packet = RawPacket::decode(reader)?;
+
+        self.dispatch(raw_packet)
+    }
+}
+
+impl Default for Dispatcher {
+    fn default() -> Self {
+        Dispatcher::new(OnUnknown::Error)
+    }
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::error::DecodeError;
+    use crate::packet::*;
+    use crate::version::v1_14_4::login::{LoginServerBoundPacket, LoginStart};
+
+    #[test]
+    fn test_raw_packet_round_trip() {
+        let raw_packet = RawPacket::new(300, vec![1, 2, 3]);
+
+        let mut vec = Vec::new();
+        raw_packet.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [5, 0b10101100, 0b00000010, 1, 2, 3]);
+        assert_eq!(RawPacket::decode(&mut vec.as_slice()).unwrap(), raw_packet);
+    }
+
+    #[test]
+    fn test_stable_hash() {
+        let packet = LoginStart::new(String::from("Username"));
+        let raw_packet = RawPacket::from_packet(&packet).unwrap();
+
+        // FNV-1a of empty input.
+        assert_eq!(StableHasher::new().finish(), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(packet.stable_hash().unwrap(), raw_packet.stable_hash());
+        assert_eq!(packet.stable_hash().unwrap(), 0x32d1_a7c0_346c_f8e9);
+        assert_ne!(
+            packet.stable_hash().unwrap(),
+            LoginStart::new(String::from("username"))
+                .stable_hash()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encoded_len() {
+        let packet = LoginStart::new(String::from("Username"));
+        let raw_packet = RawPacket::from_packet(&packet).unwrap();
+
+        let mut vec = Vec::new();
+        raw_packet.encode(&mut vec).unwrap();
+
+        assert_eq!(packet.encoded_len().unwrap(), vec.len());
+        assert_eq!(raw_packet.encoded_len(), vec.len());
+    }
+
+    #[test]
+    fn test_raw_packet_too_long() {
+        let vec = vec![0xff, 0xff, 0xff, 0x01];
+        let decode_error = RawPacket::decode(&mut vec.as_slice())
+            .expect_err("Expected error `PacketTooLong` because packet is too long");
+
+        match decode_error {
+            DecodeError::PacketTooLong { length, .. } => assert_eq!(length, 4_194_303),
+            _ => panic!("Expected `PacketTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_raw_packet_incomplete() {
+        let vec = vec![5, 0b10101100, 0b00000010, 1];
+        let decode_error = RawPacket::decode(&mut vec.as_slice())
+            .expect_err("Expected error `Incomplete` because frame is truncated");
+
+        match decode_error {
+            DecodeError::Incomplete { needed } => assert_eq!(needed, Some(2)),
+            _ => panic!("Expected `Incomplete` but got `{:?}`", decode_error),
+        }
+
+        for vec in [vec![], vec![0b10101100]] {
+            let decode_error = RawPacket::decode(&mut vec.as_slice())
+                .expect_err("Expected error `Incomplete` because length is truncated");
+
+            assert!(matches!(
+                decode_error,
+                DecodeError::Incomplete { needed: None }
+            ));
+        }
+    }
+
+    #[test]
+    fn test_packet_name_and_ids() {
+        let packet = LoginStart::new(String::from("Username"));
+
+        assert_eq!(Packet::name(&packet), "LoginStart");
+        assert_eq!(
+            <LoginServerBoundPacket as Packet>::iter_ids().collect::<Vec<_>>(),
+            [0x00, 0x01, 0x02]
+        );
+    }
+
+    #[test]
+    fn test_dispatch_known_packet() {
+        let packet = LoginStart::new(String::from("Username"));
+        let raw_packet = RawPacket::from_packet(&packet).unwrap();
+
+        match Dispatcher::default().dispatch(raw_packet).unwrap() {
+            Dispatched::Packet(LoginServerBoundPacket::LoginStart(login_start)) => {
+                assert_eq!(login_start.name, "Username");
+            }
+            _ => panic!("Expected login start packet"),
+        }
+    }
+
+    #[test]
+    fn test_dispatch_unknown_packet() {
+        let raw_packet = RawPacket::new(0x1FF, vec![1]);
+
+        let dispatched = Dispatcher::new(OnUnknown::Capture)
+            .dispatch::<LoginServerBoundPacket>(raw_packet.clone())
+            .unwrap();
+
+        match dispatched {
+            Dispatched::Captured(captured) => assert_eq!(captured, raw_packet),
+            _ => panic!("Expected captured packet"),
+        }
+
+        let dispatched = Dispatcher::new(OnUnknown::Skip)
+            .dispatch::<LoginServerBoundPacket>(raw_packet.clone())
+            .unwrap();
+
+        assert!(matches!(dispatched, Dispatched::Skipped { type_id: 0x1FF }));
+
+        let decode_error = Dispatcher::new(OnUnknown::Error)
+            .dispatch::<LoginServerBoundPacket>(raw_packet)
+            .expect_err("Expected error `PacketIdOutOfRange` because id doesn't fit into byte");
+
+        assert!(matches!(
+            decode_error,
+            DecodeError::PacketIdOutOfRange { id: 0x1FF }
+        ));
+    }
+
+    #[test]
+    fn test_capture_ring_statistics() {
+        let dispatcher = Dispatcher::new(OnUnknown::Skip).with_capture_ring(2);
+        let shared = dispatcher.clone();
+
+        for raw_packet in [
+            RawPacket::new(0x10, vec![0xAB; 40]),
+            RawPacket::new(0x11, vec![1]),
+            RawPacket::new(0x10, vec![2]),
+        ] {
+            dispatcher
+                .dispatch::<LoginServerBoundPacket>(raw_packet)
+                .unwrap();
+        }
+
+        let known = RawPacket::from_packet(&LoginStart::new(String::from("Username"))).unwrap();
+        shared.dispatch::<LoginServerBoundPacket>(known).unwrap();
+
+        let unknown_packets = shared.unknown_packets();
+
+        assert_eq!(unknown_packets.len(), 2);
+        assert_eq!(unknown_packets[0].type_id, 0x10);
+        assert_eq!(unknown_packets[0].count, 2);
+        assert_eq!(unknown_packets[0].data_length, 40);
+        assert_eq!(unknown_packets[0].sample, vec!["ab"; 32].join(" "));
+        assert!(unknown_packets[0].last_seen >= unknown_packets[0].first_seen);
+        assert_eq!(unknown_packets[1].type_id, 0x11);
+        assert_eq!(unknown_packets[1].sample, "01");
+    }
+
+    #[test]
+    fn test_capture_ring_evicts_least_recent() {
+        let dispatcher = Dispatcher::new(OnUnknown::Skip).with_capture_ring(2);
+
+        for type_id in [0x10, 0x11, 0x10, 0x12] {
+            dispatcher
+                .dispatch::<LoginServerBoundPacket>(RawPacket::new(type_id, Vec::new()))
+                .unwrap();
+        }
+
+        let type_ids: Vec<i32> = dispatcher
+            .unknown_packets()
+            .into_iter()
+            .map(|packet| packet.type_id)
+            .collect();
+
+        assert_eq!(type_ids, [0x10, 0x12]);
+        assert!(Dispatcher::default().unknown_packets().is_empty());
+    }
+}
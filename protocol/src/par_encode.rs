@@ -0,0 +1,170 @@
+//! Parallel encoding of packet batches.
+//!
+//! Encoding many big packets, like chunks sent when player joins, is CPU-bound. `par_encode`
+//! encodes packets into frames on rayon thread pool and returns them in input order, ready to be
+//! written to the stream. Frame buffers are taken from `BufferPool` and should be given back once
+//! they are written, so sending the next batch doesn't allocate.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::par_encode::{par_encode, BufferPool};
+//! use minecraft_protocol::version::v1_14_4::game::ClientBoundKeepAlive;
+//! use std::io::Write;
+//!
+//! let pool = BufferPool::new();
+//! let packets: Vec<_> = (0..64).map(ClientBoundKeepAlive::new).collect();
+//!
+//! let mut stream = Vec::new();
+//!
+//! for frame in par_encode(&packets, None, &pool).unwrap() {
+//!     stream.write_all(&frame).unwrap();
+//!     pool.give(frame);
+//! }
+//!
+//! assert_eq!(stream.len(), 64 * 10);
+//! ```
+use crate::compression::CompressedRawPacket;
+use crate::error::EncodeError;
+use crate::packet::{Packet, RawPacket};
+use rayon::prelude::*;
+use std::sync::Mutex;
+
+/// Pool of reusable byte buffers.
+#[derive(Debug, Default)]
+pub struct BufferPool {
+    buffers: Mutex<Vec<Vec<u8>>>,
+}
+
+impl BufferPool {
+    pub fn new() -> Self {
+        BufferPool::default()
+    }
+
+    /// Returns empty buffer, reusing one from the pool if available.
+    pub fn take(&self) -> Vec<u8> {
+        self.buffers.lock().unwrap().pop().unwrap_or_default()
+    }
+
+    /// Gives buffer back to the pool. Buffer is cleared but keeps its capacity.
+    pub fn give(&self, mut buffer: Vec<u8>) {
+        buffer.clear();
+        self.buffers.lock().unwrap().push(buffer);
+    }
+
+    /// Returns number of buffers in the pool.
+    pub fn len(&self) -> usize {
+        self.buffers.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Encodes packets into frames in parallel. Frames are returned in the order of packets.
+///
+/// Frames are compressed if threshold is set, like `PacketCodec` does after `SetCompression`.
+pub fn par_encode<P: Packet + Sync>(
+    packets: &[P],
+    compression_threshold: Option<usize>,
+    pool: &BufferPool,
+) -> Result<Vec<Vec<u8>>, EncodeError> {
+    packets
+        .par_iter()
+        .map(|packet| encode_frame(packet, compression_threshold, pool))
+        .collect()
+}
+
+fn encode_frame<P: Packet>(
+    packet: &P,
+    compression_threshold: Option<usize>,
+    pool: &BufferPool,
+) -> Result<Vec<u8>, EncodeError> {
+    let mut data = pool.take();
+    packet.encode(&mut data)?;
+
+    let raw_packet = RawPacket::new(packet.get_type_id() as i32, data);
+    let mut frame = pool.take();
+
+    let result = match compression_threshold {
+        Some(threshold) => {
+            let compressed_raw_packet = CompressedRawPacket::from(raw_packet);
+            let result = compressed_raw_packet.encode(&mut frame, threshold);
+            pool.give(compressed_raw_packet.into_inner().data);
+
+            result
+        }
+        None => {
+            let result = raw_packet.encode(&mut frame);
+            pool.give(raw_packet.data);
+
+            result
+        }
+    };
+
+    match result {
+        Ok(()) => Ok(frame),
+        Err(encode_error) => {
+            pool.give(frame);
+
+            Err(encode_error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::PacketCodec;
+    use crate::par_encode::*;
+    use crate::version::v1_14_4::game::{ChunkData, GameClientBoundPacket};
+    use nbt::CompoundTag;
+
+    fn chunk_data(x: i32) -> GameClientBoundPacket {
+        ChunkData::new(
+            x,
+            0,
+            true,
+            0,
+            CompoundTag::new(),
+            vec![x as u8; 512],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_par_encode_matches_codec() {
+        let packets: Vec<_> = (0..32).map(chunk_data).collect();
+
+        for compression_threshold in [None, Some(256)] {
+            let mut codec = PacketCodec::new(Vec::new());
+            codec.set_compression_threshold(compression_threshold);
+
+            for packet in &packets {
+                codec.write_packet(packet).unwrap();
+            }
+
+            let pool = BufferPool::new();
+            let frames = par_encode(&packets, compression_threshold, &pool).unwrap();
+
+            assert_eq!(frames.concat(), codec.into_inner());
+        }
+    }
+
+    #[test]
+    fn test_par_encode_reuses_buffers() {
+        let pool = BufferPool::new();
+        let packets: Vec<_> = (0..4).map(chunk_data).collect();
+
+        for frame in par_encode(&packets, None, &pool).unwrap() {
+            pool.give(frame);
+        }
+
+        let pooled = pool.len();
+        assert!(pooled >= 4);
+
+        let frames = par_encode(&packets, None, &pool).unwrap();
+
+        assert_eq!(pool.len() + frames.len(), pooled);
+    }
+}
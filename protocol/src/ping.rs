@@ -0,0 +1,353 @@
+//! Client side server list ping.
+//!
+//! `ping` sends handshake and status request, then measures latency with ping request like
+//! vanilla client. Servers don't always complete the exchange: some close connection right after
+//! status response, others send garbage. Failures are reported with `PingError`, keeping what
+//! was already known, e.g. latency of a malformed status response, or valid status of a server
+//! which didn't answer ping request.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use minecraft_protocol::ping::{ping_tcp, PingConfig};
+//!
+//! let server_ping = ping_tcp("127.0.0.1", 25565, &PingConfig::default()).unwrap();
+//!
+//! println!("{} players online", server_ping.status.players.online);
+//! ```
+use crate::clock::{Clock, SystemClock};
+use crate::connection::Connection;
+use crate::data::server_status::ServerStatus;
+use crate::driver::Side;
+use crate::error::{DecodeError, PingError};
+use crate::version::v1_14_4::handshake::{Handshake, NextState};
+use crate::version::v1_14_4::status::{
+    PingRequest, StatusClientBoundPacket, StatusServerBoundPacket,
+};
+use crate::version::ProtocolVersion;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// Vanilla client gives up on server list ping after 5 seconds.
+pub const PING_TIMEOUT: Duration = Duration::from_secs(5);
+/// Read timeout of TCP stream, deadline of ping is checked between reads.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PingConfig {
+    /// Protocol version sent in handshake, servers answer with status of matching version.
+    pub protocol_version: i32,
+    /// Time for the whole exchange.
+    pub timeout: Duration,
+}
+
+impl Default for PingConfig {
+    fn default() -> Self {
+        PingConfig {
+            protocol_version: ProtocolVersion::V1_14_4.number(),
+            timeout: PING_TIMEOUT,
+        }
+    }
+}
+
+/// Result of server list ping.
+#[derive(Debug)]
+pub struct ServerPing {
+    pub status: ServerStatus,
+    /// Time between status request and status response.
+    pub status_latency: Duration,
+    /// Time between ping request and ping response, error if server didn't answer ping request.
+    pub latency: Result<Duration, PingError>,
+}
+
+/// Pings server over stream, which must have a read timeout or be non-blocking for timeout to
+/// apply.
+pub fn ping<S: Read + Write>(
+    stream: S,
+    server_addr: &str,
+    server_port: u16,
+    config: &PingConfig,
+) -> Result<ServerPing, PingError> {
+    ping_with_clock(stream, server_addr, server_port, config, &SystemClock)
+}
+
+/// Same as `ping`, measuring latency and timestamping ping request with clock.
+///
+/// Timeout is always checked against system time, because it applies to reads of the stream.
+pub fn ping_with_clock<S: Read + Write, C: Clock>(
+    stream: S,
+    server_addr: &str,
+    server_port: u16,
+    config: &PingConfig,
+    clock: &C,
+) -> Result<ServerPing, PingError> {
+    let deadline = Instant::now() + config.timeout;
+    let mut connection = Connection::new(stream, Side::Client);
+
+    connection.write_packet(&Handshake::new(
+        config.protocol_version,
+        server_addr.to_owned(),
+        server_port,
+        NextState::Status,
+    ))?;
+
+    let sent = clock.now();
+    connection.write_packet(&StatusServerBoundPacket::StatusRequest)?;
+    let raw_packet = connection.read_raw_packet_deadline(deadline)?;
+    let status_latency = clock.elapsed_since(sent);
+
+    let status = match raw_packet.decode_packet() {
+        Ok(StatusClientBoundPacket::StatusResponse(status_response)) => {
+            status_response.server_status
+        }
+        Ok(packet) => return Err(unexpected_packet(&packet, Some(status_latency))),
+        Err(decode_error) => {
+            return Err(PingError::InvalidResponse {
+                decode_error,
+                latency: Some(status_latency),
+            })
+        }
+    };
+
+    let latency = measure_latency(&mut connection, deadline, clock);
+
+    Ok(ServerPing {
+        status,
+        status_latency,
+        latency,
+    })
+}
+
+/// Connects to server and pings it.
+pub fn ping_tcp(
+    server_addr: &str,
+    server_port: u16,
+    config: &PingConfig,
+) -> Result<ServerPing, PingError> {
+    let mut last_error = None;
+
+    for addr in (server_addr, server_port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, config.timeout) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(POLL_INTERVAL))?;
+                stream.set_nodelay(true)?;
+
+                return ping(stream, server_addr, server_port, config);
+            }
+            Err(io_error) => last_error = Some(io_error),
+        }
+    }
+
+    Err(last_error.map_or(PingError::ConnectionClosed, PingError::from))
+}
+
+fn measure_latency<S: Read + Write, C: Clock>(
+    connection: &mut Connection<S>,
+    deadline: Instant,
+    clock: &C,
+) -> Result<Duration, PingError> {
+    let time = clock
+        .system_time()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64);
+
+    let sent = clock.now();
+    connection.write_packet(&PingRequest::new(time))?;
+
+    match connection.read_packet_deadline(deadline)? {
+        StatusClientBoundPacket::PingResponse(_) => Ok(clock.elapsed_since(sent)),
+        packet => Err(unexpected_packet(&packet, None)),
+    }
+}
+
+fn unexpected_packet(packet: &StatusClientBoundPacket, latency: Option<Duration>) -> PingError {
+    PingError::InvalidResponse {
+        decode_error: DecodeError::UnknownPacketType {
+            type_id: packet.get_type_id(),
+        },
+        latency,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::MockClock;
+    use crate::encoder::EncoderWriteExt;
+    use crate::packet::RawPacket;
+    use crate::ping::*;
+    use crate::version::v1_14_4::status::PingResponse;
+    use std::io::{self, Cursor, ErrorKind};
+
+    const STATUS: &str = r#"{"version":{"name":"1.14.4","protocol":498},"players":{"max":20,"online":3},"description":"A Minecraft Server"}"#;
+
+    /// Stream reading prepared frames and discarding written bytes.
+    struct Stream {
+        input: Cursor<Vec<u8>>,
+        stall: bool,
+    }
+
+    impl Stream {
+        fn new(frames: &[RawPacket]) -> Self {
+            let mut input = Vec::new();
+
+            for frame in frames {
+                frame.encode(&mut input).unwrap();
+            }
+
+            Stream {
+                input: Cursor::new(input),
+                stall: false,
+            }
+        }
+
+        /// Stream which waits for data after prepared frames instead of closing.
+        fn stalled(frames: &[RawPacket]) -> Self {
+            Stream {
+                stall: true,
+                ..Stream::new(frames)
+            }
+        }
+    }
+
+    impl Read for Stream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.input.read(buf)? {
+                0 if self.stall => Err(io::Error::from(ErrorKind::WouldBlock)),
+                length => Ok(length),
+            }
+        }
+    }
+
+    impl Write for Stream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn status_response(json: &str) -> RawPacket {
+        let mut data = Vec::new();
+        data.write_string(json, 32767).unwrap();
+
+        RawPacket::new(0x00, data)
+    }
+
+    fn config() -> PingConfig {
+        PingConfig {
+            timeout: Duration::from_millis(20),
+            ..PingConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_ping() {
+        let pong = RawPacket::from_packet(&PingResponse::new(1)).unwrap();
+        let stream = Stream::new(&[status_response(STATUS), pong]);
+
+        let server_ping = ping(stream, "localhost", 25565, &config()).unwrap();
+
+        assert_eq!(server_ping.status.version.protocol, 498);
+        assert_eq!(server_ping.status.players.online, 3);
+        assert!(server_ping.latency.is_ok());
+    }
+
+    #[test]
+    fn test_ping_with_clock() {
+        let pong = RawPacket::from_packet(&PingResponse::new(1)).unwrap();
+        let stream = Stream::new(&[status_response(STATUS), pong]);
+        let clock = MockClock::new();
+
+        let server_ping = ping_with_clock(stream, "localhost", 25565, &config(), &clock).unwrap();
+
+        assert_eq!(server_ping.status_latency, Duration::ZERO);
+        assert_eq!(server_ping.latency.unwrap(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_ping_closed_after_status() {
+        let stream = Stream::new(&[status_response(STATUS)]);
+
+        let server_ping = ping(stream, "localhost", 25565, &config()).unwrap();
+
+        assert_eq!(server_ping.status.players.max, 20);
+        match server_ping.latency {
+            Err(PingError::ConnectionClosed) => {}
+            latency => panic!("Expected `ConnectionClosed` but got `{:?}`", latency),
+        }
+    }
+
+    #[test]
+    fn test_ping_closed_immediately() {
+        let stream = Stream::new(&[]);
+
+        let ping_error = ping(stream, "localhost", 25565, &config())
+            .expect_err("Expected error `ConnectionClosed` because server sent nothing");
+
+        match ping_error {
+            PingError::ConnectionClosed => {}
+            _ => panic!("Expected `ConnectionClosed` but got `{:?}`", ping_error),
+        }
+    }
+
+    #[test]
+    fn test_ping_timeout() {
+        let stream = Stream::stalled(&[]);
+
+        let ping_error = ping(stream, "localhost", 25565, &config())
+            .expect_err("Expected error `Timeout` because server didn't answer");
+
+        match ping_error {
+            PingError::Timeout => {}
+            _ => panic!("Expected `Timeout` but got `{:?}`", ping_error),
+        }
+    }
+
+    #[test]
+    fn test_ping_malformed_status_keeps_latency() {
+        let stream = Stream::new(&[status_response("not a status")]);
+
+        let ping_error = ping(stream, "localhost", 25565, &config())
+            .expect_err("Expected error `InvalidResponse` because status is not JSON");
+
+        match ping_error {
+            PingError::InvalidResponse {
+                decode_error: DecodeError::JsonError { .. },
+                latency: Some(_),
+            } => {}
+            _ => panic!("Expected `InvalidResponse` but got `{:?}`", ping_error),
+        }
+    }
+
+    #[test]
+    fn test_ping_unexpected_packet() {
+        let pong = RawPacket::from_packet(&PingResponse::new(1)).unwrap();
+        let stream = Stream::new(&[pong]);
+
+        let ping_error = ping(stream, "localhost", 25565, &config())
+            .expect_err("Expected error `InvalidResponse` because server answered with pong");
+
+        match ping_error {
+            PingError::InvalidResponse {
+                decode_error: DecodeError::UnknownPacketType { type_id: 0x01 },
+                latency: Some(_),
+            } => {}
+            _ => panic!("Expected `InvalidResponse` but got `{:?}`", ping_error),
+        }
+    }
+
+    #[test]
+    fn test_ping_timeout_after_status() {
+        let stream = Stream::stalled(&[status_response(STATUS)]);
+
+        let server_ping = ping(stream, "localhost", 25565, &config()).unwrap();
+
+        match server_ping.latency {
+            Err(PingError::Timeout) => {}
+            latency => panic!("Expected `Timeout` but got `{:?}`", latency),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Commonly used traits and types.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::prelude::*;
+//!
+//! let packet = LoginStart::new(String::from("Username"));
+//!
+//! let mut vec = Vec::new();
+//! packet.encode(&mut vec).unwrap();
+//! # }
+//! ```
+pub use crate::data::chat::Message;
+pub use crate::decoder::{Decoder, DecoderReadExt};
+pub use crate::encoder::{Encoder, EncoderWriteExt};
+pub use crate::error::{DecodeError, EncodeError, TranslateError};
+pub use crate::packet::{Packet, RawPacket};
+pub use crate::validate::Validate;
+#[cfg(feature = "v1_14_4")]
+pub use crate::version::v1_14_4::game::*;
+#[cfg(feature = "v1_14_4")]
+pub use crate::version::v1_14_4::handshake::*;
+#[cfg(feature = "v1_14_4")]
+pub use crate::version::v1_14_4::login::*;
+#[cfg(feature = "v1_14_4")]
+pub use crate::version::v1_14_4::status::*;
+pub use nbt::CompoundTag;
+pub use uuid::Uuid;
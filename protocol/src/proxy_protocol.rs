@@ -0,0 +1,280 @@
+//! HAProxy PROXY protocol header parsing.
+//!
+//! Load balancers send PROXY protocol header before forwarding connection data, so the server can
+//! see the real client address instead of the proxy one. Both text (v1) and binary (v2) header
+//! versions are supported.
+//!
+//! Header must only be trusted when every connection comes through a proxy, otherwise clients can
+//! send a header themselves and spoof their address.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::proxy_protocol::ProxyHeader;
+//!
+//! let mut reader = &b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 25565\r\n"[..];
+//! let header = ProxyHeader::read(&mut reader).unwrap();
+//!
+//! assert_eq!(
+//!     header,
+//!     ProxyHeader::Proxied {
+//!         source: "192.168.0.1:56324".parse().unwrap(),
+//!         destination: "192.168.0.11:25565".parse().unwrap(),
+//!     }
+//! );
+//! ```
+use byteorder::{BigEndian, ReadBytesExt};
+use std::io::{self, ErrorKind, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+const V1_PREFIX: &[u8] = b"PROXY ";
+
+/// Maximum v1 header length including `\r\n`.
+const V1_MAX_LENGTH: usize = 107;
+
+const V2_SIGNATURE: &[u8] = b"\r\n\r\n\x00\r\nQUIT\n";
+
+const V2_COMMAND_LOCAL: u8 = 0x20;
+const V2_COMMAND_PROXY: u8 = 0x21;
+
+const V2_FAMILY_INET: u8 = 0x1;
+const V2_FAMILY_INET6: u8 = 0x2;
+
+/// Addresses received in PROXY protocol header.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ProxyHeader {
+    /// Connection was proxied from client.
+    Proxied {
+        source: SocketAddr,
+        destination: SocketAddr,
+    },
+    /// Connection was made by proxy itself, for example for health checks, or address family is
+    /// not supported. Connection address should be used.
+    Unknown,
+}
+
+impl ProxyHeader {
+    /// Reads header of any version.
+    ///
+    /// Reader is not read past the header, so it's positioned at the first byte of connection
+    /// data afterwards. Returns `InvalidData` error if header is missing or malformed.
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<ProxyHeader> {
+        let first = reader.read_u8()?;
+
+        match first {
+            b'P' => read_v1(reader),
+            b'\r' => read_v2(reader),
+            _ => Err(invalid_data("Expected PROXY protocol header")),
+        }
+    }
+}
+
+/// Checks whether bytes start with PROXY protocol header.
+///
+/// Returns `None` if more bytes are required to make a decision.
+pub fn detect(bytes: &[u8]) -> Option<bool> {
+    for prefix in [V1_PREFIX, V2_SIGNATURE].iter() {
+        if bytes.starts_with(prefix) {
+            return Some(true);
+        }
+
+        if !bytes.is_empty() && prefix.starts_with(bytes) {
+            return None;
+        }
+    }
+
+    if bytes.is_empty() {
+        None
+    } else {
+        Some(false)
+    }
+}
+
+fn read_v1<R: Read>(reader: &mut R) -> io::Result<ProxyHeader> {
+    let mut line = vec![b'P'];
+
+    while !line.ends_with(b"\r\n") {
+        if line.len() == V1_MAX_LENGTH {
+            return Err(invalid_data("PROXY header is too long"));
+        }
+
+        line.push(reader.read_u8()?);
+    }
+
+    let line = std::str::from_utf8(&line[..line.len() - 2])
+        .map_err(|_| invalid_data("PROXY header is not valid UTF-8"))?;
+    let parts: Vec<&str> = line.split(' ').collect();
+
+    match parts.as_slice() {
+        ["PROXY", "UNKNOWN", ..] => Ok(ProxyHeader::Unknown),
+        ["PROXY", family, source, destination, source_port, destination_port] => {
+            let source = parse_v1_addr(family, source, source_port)?;
+            let destination = parse_v1_addr(family, destination, destination_port)?;
+
+            Ok(ProxyHeader::Proxied {
+                source,
+                destination,
+            })
+        }
+        _ => Err(invalid_data("Malformed PROXY header")),
+    }
+}
+
+fn parse_v1_addr(family: &str, ip: &str, port: &str) -> io::Result<SocketAddr> {
+    let ip = match family {
+        "TCP4" => ip.parse::<Ipv4Addr>().map(IpAddr::V4),
+        "TCP6" => ip.parse::<Ipv6Addr>().map(IpAddr::V6),
+        _ => return Err(invalid_data("Unknown PROXY header address family")),
+    }
+    .map_err(|_| invalid_data("Invalid PROXY header address"))?;
+
+    let port = port
+        .parse()
+        .map_err(|_| invalid_data("Invalid PROXY header port"))?;
+
+    Ok(SocketAddr::new(ip, port))
+}
+
+fn read_v2<R: Read>(reader: &mut R) -> io::Result<ProxyHeader> {
+    let mut signature = [0; 11];
+    reader.read_exact(&mut signature)?;
+
+    if signature != V2_SIGNATURE[1..] {
+        return Err(invalid_data("Invalid PROXY header signature"));
+    }
+
+    let command = reader.read_u8()?;
+    let family = reader.read_u8()? >> 4;
+    let length = reader.read_u16::<BigEndian>()? as usize;
+
+    let mut data = vec![0; length];
+    reader.read_exact(&mut data)?;
+
+    match command {
+        V2_COMMAND_LOCAL => return Ok(ProxyHeader::Unknown),
+        V2_COMMAND_PROXY => {}
+        _ => return Err(invalid_data("Unknown PROXY header command")),
+    }
+
+    let mut data = data.as_slice();
+
+    let (source_ip, destination_ip) = match family {
+        V2_FAMILY_INET if length >= 12 => {
+            let source = Ipv4Addr::from(data.read_u32::<BigEndian>()?);
+            let destination = Ipv4Addr::from(data.read_u32::<BigEndian>()?);
+
+            (IpAddr::V4(source), IpAddr::V4(destination))
+        }
+        V2_FAMILY_INET6 if length >= 36 => {
+            let source = Ipv6Addr::from(data.read_u128::<BigEndian>()?);
+            let destination = Ipv6Addr::from(data.read_u128::<BigEndian>()?);
+
+            (IpAddr::V6(source), IpAddr::V6(destination))
+        }
+        V2_FAMILY_INET | V2_FAMILY_INET6 => {
+            return Err(invalid_data("PROXY header addresses are truncated"))
+        }
+        _ => return Ok(ProxyHeader::Unknown),
+    };
+
+    let source_port = data.read_u16::<BigEndian>()?;
+    let destination_port = data.read_u16::<BigEndian>()?;
+
+    Ok(ProxyHeader::Proxied {
+        source: SocketAddr::new(source_ip, source_port),
+        destination: SocketAddr::new(destination_ip, destination_port),
+    })
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::proxy_protocol::*;
+
+    #[test]
+    fn test_read_v1_tcp6() {
+        let mut reader = &b"PROXY TCP6 ::1 2001:db8::1 56324 25565\r\n\x10\x00"[..];
+        let header = ProxyHeader::read(&mut reader).unwrap();
+
+        assert_eq!(
+            header,
+            ProxyHeader::Proxied {
+                source: "[::1]:56324".parse().unwrap(),
+                destination: "[2001:db8::1]:25565".parse().unwrap(),
+            }
+        );
+        assert_eq!(reader, [0x10, 0x00]);
+    }
+
+    #[test]
+    fn test_read_v1_unknown() {
+        let mut reader = &b"PROXY UNKNOWN\r\n"[..];
+
+        assert_eq!(
+            ProxyHeader::read(&mut reader).unwrap(),
+            ProxyHeader::Unknown
+        );
+    }
+
+    #[test]
+    fn test_read_v1_too_long() {
+        let line = format!("PROXY TCP4 {}\r\n", "1".repeat(V1_MAX_LENGTH));
+        let error = ProxyHeader::read(&mut line.as_bytes())
+            .expect_err("Expected error because header is too long");
+
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_v2_inet() {
+        let mut bytes = V2_SIGNATURE.to_vec();
+        bytes.extend_from_slice(&[0x21, 0x11, 0x00, 0x0F]);
+        bytes.extend_from_slice(&[192, 168, 0, 1, 192, 168, 0, 11]);
+        bytes.extend_from_slice(&[0xDC, 0x04, 0x63, 0xDD]);
+        bytes.extend_from_slice(&[0x04, 0x00, 0x00]);
+        bytes.push(0x10);
+
+        let mut reader = bytes.as_slice();
+        let header = ProxyHeader::read(&mut reader).unwrap();
+
+        assert_eq!(
+            header,
+            ProxyHeader::Proxied {
+                source: "192.168.0.1:56324".parse().unwrap(),
+                destination: "192.168.0.11:25565".parse().unwrap(),
+            }
+        );
+        assert_eq!(reader, [0x10]);
+    }
+
+    #[test]
+    fn test_read_v2_local() {
+        let mut bytes = V2_SIGNATURE.to_vec();
+        bytes.extend_from_slice(&[0x20, 0x00, 0x00, 0x00]);
+
+        assert_eq!(
+            ProxyHeader::read(&mut bytes.as_slice()).unwrap(),
+            ProxyHeader::Unknown
+        );
+    }
+
+    #[test]
+    fn test_read_missing_header() {
+        let error = ProxyHeader::read(&mut &[0x10, 0x00][..])
+            .expect_err("Expected error because header is missing");
+
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(b"PROXY TCP4"), Some(true));
+        assert_eq!(detect(b"PRO"), None);
+        assert_eq!(detect(b"\r\n\r\n"), None);
+        assert_eq!(detect(b"\x10\x00"), Some(false));
+        assert_eq!(detect(b"POST /"), Some(false));
+    }
+}
@@ -0,0 +1,356 @@
+//! Packet codec split into halves for reading and writing on separate threads or tasks.
+//!
+//! Proxies read packets of a connection on one thread and write them on another.
+//! `PacketCodec::into_split` turns codec into `ReadHalf` and `WriteHalf` over handles of the same
+//! stream, e.g. `TcpStream` and its clone. Halves keep negotiated settings in `SharedState`, so
+//! compression enabled by reading half after `SetCompression` is received applies to the next
+//! packet written by the other half. Settings are atomics, halves never block each other.
+//!
+//! Async codec is split the same way with `AsyncPacketCodec::into_split` of `async_io` module.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::codec::PacketCodec;
+//! use minecraft_protocol::version::v1_14_4::login::{LoginClientBoundPacket, LoginStart};
+//! use std::net::TcpStream;
+//! use std::thread;
+//!
+//! let stream = TcpStream::connect("127.0.0.1:25565").unwrap();
+//! let (mut read_half, mut write_half) = PacketCodec::new(stream).into_split().unwrap();
+//!
+//! let reader = thread::spawn(move || {
+//!     let packet: LoginClientBoundPacket = read_half.read_packet().unwrap();
+//!
+//!     if let LoginClientBoundPacket::SetCompression(set_compression) = packet {
+//!         read_half.set_compression_threshold(Some(set_compression.threshold as usize));
+//!     }
+//! });
+//!
+//! write_half
+//!     .write_packet(&LoginStart::new(String::from("Username")))
+//!     .unwrap();
+//! reader.join().unwrap();
+//! # }
+//! ```
+use crate::codec::PacketCodec;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::{Packet, RawPacket};
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Threshold stored when compression is disabled.
+const NO_COMPRESSION: usize = usize::MAX;
+
+/// Halves of codec over stream `S`.
+type Halves<S> = (
+    ReadHalf<<S as SplitStream>::Read>,
+    WriteHalf<<S as SplitStream>::Write>,
+);
+
+/// Stream which can be split into independent read and write handles.
+pub trait SplitStream {
+    type Read: Read;
+    type Write: Write;
+
+    fn split_stream(self) -> io::Result<(Self::Read, Self::Write)>;
+}
+
+impl SplitStream for TcpStream {
+    type Read = TcpStream;
+    type Write = TcpStream;
+
+    fn split_stream(self) -> io::Result<(Self::Read, Self::Write)> {
+        let writer = self.try_clone()?;
+
+        Ok((self, writer))
+    }
+}
+
+/// Settings of connection shared by both halves of split codec.
+#[derive(Debug, Clone)]
+pub struct SharedState {
+    inner: Arc<SharedStateInner>,
+}
+
+#[derive(Debug)]
+struct SharedStateInner {
+    compression_threshold: AtomicUsize,
+    max_packet_length: AtomicUsize,
+}
+
+impl SharedState {
+    pub(crate) fn new(compression_threshold: Option<usize>, max_packet_length: usize) -> Self {
+        let inner = SharedStateInner {
+            compression_threshold: AtomicUsize::new(
+                compression_threshold.unwrap_or(NO_COMPRESSION),
+            ),
+            max_packet_length: AtomicUsize::new(max_packet_length),
+        };
+
+        SharedState {
+            inner: Arc::new(inner),
+        }
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        match self.inner.compression_threshold.load(Ordering::Acquire) {
+            NO_COMPRESSION => None,
+            threshold => Some(threshold),
+        }
+    }
+
+    /// Changes compression of both halves, starting with their next packet.
+    pub fn set_compression_threshold(&self, compression_threshold: Option<usize>) {
+        self.inner.compression_threshold.store(
+            compression_threshold.unwrap_or(NO_COMPRESSION),
+            Ordering::Release,
+        );
+    }
+
+    pub fn max_packet_length(&self) -> usize {
+        self.inner.max_packet_length.load(Ordering::Acquire)
+    }
+
+    pub fn set_max_packet_length(&self, max_packet_length: usize) {
+        self.inner
+            .max_packet_length
+            .store(max_packet_length, Ordering::Release);
+    }
+
+    /// Applies shared settings to codec of half.
+    fn apply<S>(&self, codec: &mut PacketCodec<S>) {
+        codec.set_compression_threshold(self.compression_threshold());
+        codec.set_max_packet_length(self.max_packet_length());
+    }
+}
+
+impl<S: SplitStream> PacketCodec<S> {
+    /// Splits codec into halves over read and write handles of its stream.
+    ///
+    /// Flush policy, encode strategy and compression overrides stay with writing half, decode
+    /// limits are copied to reading half.
+    pub fn into_split(self) -> io::Result<Halves<S>> {
+        let state = SharedState::new(self.compression_threshold(), self.max_packet_length());
+        let (stream, codec) = self.replace_stream(());
+        let (reader, writer) = stream.split_stream()?;
+
+        let mut read_codec = PacketCodec::new(reader);
+        read_codec.set_decode_limits(codec.decode_limits());
+
+        let read_half = ReadHalf {
+            codec: read_codec,
+            state: state.clone(),
+        };
+        let write_half = WriteHalf {
+            codec: codec.replace_stream(writer).1,
+            state,
+        };
+
+        Ok((read_half, write_half))
+    }
+}
+
+/// Reading half of split codec.
+#[derive(Debug)]
+pub struct ReadHalf<R> {
+    codec: PacketCodec<R>,
+    state: SharedState,
+}
+
+impl<R> ReadHalf<R> {
+    pub fn state(&self) -> &SharedState {
+        &self.state
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.state.compression_threshold()
+    }
+
+    /// Changes compression of both halves, see `SharedState::set_compression_threshold`.
+    pub fn set_compression_threshold(&self, compression_threshold: Option<usize>) {
+        self.state.set_compression_threshold(compression_threshold);
+    }
+
+    pub fn get_ref(&self) -> &R {
+        self.codec.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        self.codec.get_mut()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.codec.into_inner()
+    }
+}
+
+impl<R: Read> ReadHalf<R> {
+    /// Returns `DecodeError::PacketTooLong` if frame is longer than max packet length.
+    pub fn read_raw_packet(&mut self) -> Result<RawPacket, DecodeError> {
+        self.state.apply(&mut self.codec);
+        self.codec.read_raw_packet()
+    }
+
+    pub fn read_packet<P: Packet>(&mut self) -> Result<P, DecodeError> {
+        self.state.apply(&mut self.codec);
+        self.codec.read_packet()
+    }
+}
+
+/// Writing half of split codec.
+#[derive(Debug)]
+pub struct WriteHalf<W> {
+    codec: PacketCodec<W>,
+    state: SharedState,
+}
+
+impl<W> WriteHalf<W> {
+    pub fn state(&self) -> &SharedState {
+        &self.state
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.state.compression_threshold()
+    }
+
+    /// Changes compression of both halves, see `SharedState::set_compression_threshold`.
+    pub fn set_compression_threshold(&self, compression_threshold: Option<usize>) {
+        self.state.set_compression_threshold(compression_threshold);
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.codec.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        self.codec.get_mut()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.codec.into_inner()
+    }
+}
+
+impl<W: Write> WriteHalf<W> {
+    /// Returns `EncodeError::PacketTooLong` if frame is longer than max packet length.
+    pub fn write_raw_packet(&mut self, raw_packet: &RawPacket) -> Result<(), EncodeError> {
+        self.state.apply(&mut self.codec);
+        self.codec.write_raw_packet(raw_packet)
+    }
+
+    pub fn write_packet<P: Packet>(&mut self, packet: &P) -> Result<(), EncodeError> {
+        self.state.apply(&mut self.codec);
+        self.codec.write_packet(packet)
+    }
+
+    pub fn write_bundle<P: Packet>(&mut self, packets: &[P]) -> Result<(), EncodeError> {
+        self.state.apply(&mut self.codec);
+        self.codec.write_bundle(packets)
+    }
+
+    pub fn flush(&mut self) -> Result<(), EncodeError> {
+        self.codec.flush()
+    }
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::codec::PacketCodec;
+    use crate::split::*;
+    use crate::version::v1_14_4::game::{ClientBoundKeepAlive, ServerBoundChatMessage};
+    use crate::version::v1_14_4::login::{LoginClientBoundPacket, SetCompression};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread::{self, JoinHandle};
+
+    const THRESHOLD: usize = 64;
+
+    fn connect() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+
+        (client, server)
+    }
+
+    /// Starts server which enables compression, then echoes packet and returns it. Packet can be
+    /// read only if client compresses it too.
+    fn spawn_server() -> (TcpStream, JoinHandle<RawPacket>) {
+        let (client, server) = connect();
+
+        let server = thread::spawn(move || {
+            let mut codec = PacketCodec::new(server);
+
+            codec
+                .write_packet(&SetCompression::new(THRESHOLD as i32))
+                .unwrap();
+            codec.set_compression_threshold(Some(THRESHOLD));
+
+            let raw_packet = codec.read_raw_packet().unwrap();
+            codec.write_raw_packet(&raw_packet).unwrap();
+
+            raw_packet
+        });
+
+        (client, server)
+    }
+
+    fn read_set_compression(read_half: &mut ReadHalf<TcpStream>) {
+        match read_half.read_packet::<LoginClientBoundPacket>().unwrap() {
+            LoginClientBoundPacket::SetCompression(set_compression) => {
+                read_half.set_compression_threshold(Some(set_compression.threshold as usize))
+            }
+            packet => panic!("Expected `SetCompression` but got `{:?}`", packet),
+        }
+    }
+
+    fn chat_message() -> RawPacket {
+        RawPacket::from_packet(&ServerBoundChatMessage::new("a".repeat(200))).unwrap()
+    }
+
+    #[test]
+    fn test_halves_on_threads_share_compression() {
+        let (stream, server) = spawn_server();
+        let (mut read_half, mut write_half) = PacketCodec::new(stream).into_split().unwrap();
+        let (sender, receiver) = mpsc::channel();
+
+        let reader = thread::spawn(move || {
+            read_set_compression(&mut read_half);
+            sender.send(()).unwrap();
+
+            read_half.read_raw_packet().unwrap()
+        });
+
+        receiver.recv().unwrap();
+        assert_eq!(write_half.compression_threshold(), Some(THRESHOLD));
+        write_half.write_raw_packet(&chat_message()).unwrap();
+
+        assert_eq!(reader.join().unwrap(), chat_message());
+        assert_eq!(server.join().unwrap(), chat_message());
+    }
+
+    #[test]
+    fn test_max_packet_length_shared() {
+        let (client, _server) = connect();
+        let (read_half, mut write_half) = PacketCodec::new(client).into_split().unwrap();
+
+        read_half.state().set_max_packet_length(8);
+        let packet = ClientBoundKeepAlive::new(1);
+        let encode_error = write_half
+            .write_packet(&packet)
+            .expect_err("Expected error `PacketTooLong` because max length is shared");
+
+        match encode_error {
+            EncodeError::PacketTooLong { length, max_length } => {
+                assert_eq!(length, 9);
+                assert_eq!(max_length, 8);
+            }
+            _ => panic!("Expected `PacketTooLong` but got `{:?}`", encode_error),
+        }
+    }
+}
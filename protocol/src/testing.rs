@@ -0,0 +1,421 @@
+//! Scripted fake client for integration tests of servers built on this crate.
+//!
+//! `duplex` creates in-memory stream, one end is given to the server under test and the other one
+//! to `FakeClient`, which sends packets and expects answers step by step. Connection state and
+//! compression follow packets like in `Connection`.
+//!
+//! Server should read time from `FakeClock` shared with the client, so `advance_time` can trigger
+//! timeouts without waiting. Packets which client may send in any order can be checked with every
+//! order returned by `permutations`.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::connection::Connection;
+//! use minecraft_protocol::driver::Side;
+//! use minecraft_protocol::testing::{duplex, FakeClient, FakeClock};
+//! use minecraft_protocol::version::v1_14_4::handshake::{Handshake, NextState};
+//! use minecraft_protocol::version::v1_14_4::login::*;
+//! use std::thread;
+//! use uuid::Uuid;
+//!
+//! let (client_stream, server_stream) = duplex();
+//!
+//! let server = thread::spawn(move || {
+//!     let mut connection = Connection::new(server_stream, Side::Server);
+//!     connection.read_raw_packet().unwrap();
+//!     connection.read_raw_packet().unwrap();
+//!
+//!     let login_success = LoginSuccess::new(Uuid::nil(), String::from("Username"));
+//!     connection.write_packet(&login_success).unwrap();
+//! });
+//!
+//! let mut client = FakeClient::new(client_stream, FakeClock::new());
+//! client
+//!     .send(&Handshake::new(498, String::from("localhost"), 25565, NextState::Login))
+//!     .unwrap();
+//! client.send(&LoginStart::new(String::from("Username"))).unwrap();
+//!
+//! let login_success = client.expect_packet::<LoginSuccess, _>().unwrap();
+//! assert_eq!(login_success.username, "Username");
+//!
+//! server.join().unwrap();
+//! ```
+use crate::connection::Connection;
+use crate::driver::{ProtocolState, Side};
+use crate::error::{DecodeError, ScriptError};
+use crate::handler::PacketVariant;
+use crate::packet::Packet;
+use std::any;
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind, Read, Write};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// Clock shared by fake client and server under test.
+pub use crate::clock::MockClock as FakeClock;
+
+/// One direction of the duplex stream.
+#[derive(Debug, Default)]
+struct Pipe {
+    /// Buffered bytes and whether writing end is closed.
+    state: Mutex<(VecDeque<u8>, bool)>,
+    condvar: Condvar,
+}
+
+/// End of in-memory duplex stream. Dropping it closes the stream for the other end.
+///
+/// Reads block until data is written to the other end, `set_read_timeout` limits waiting like
+/// for `TcpStream`.
+#[derive(Debug)]
+pub struct MemoryStream {
+    incoming: Arc<Pipe>,
+    outgoing: Arc<Pipe>,
+    read_timeout: Option<Duration>,
+}
+
+/// Returns connected ends of in-memory stream.
+pub fn duplex() -> (MemoryStream, MemoryStream) {
+    let a = Arc::new(Pipe::default());
+    let b = Arc::new(Pipe::default());
+
+    let client = MemoryStream {
+        incoming: a.clone(),
+        outgoing: b.clone(),
+        read_timeout: None,
+    };
+    let server = MemoryStream {
+        incoming: b,
+        outgoing: a,
+        read_timeout: None,
+    };
+
+    (client, server)
+}
+
+impl MemoryStream {
+    pub fn set_read_timeout(&mut self, read_timeout: Option<Duration>) {
+        self.read_timeout = read_timeout;
+    }
+}
+
+impl Read for MemoryStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.incoming.state.lock().unwrap();
+
+        while state.0.is_empty() && !state.1 {
+            state = match self.read_timeout {
+                Some(read_timeout) => {
+                    let (state, result) = self
+                        .incoming
+                        .condvar
+                        .wait_timeout(state, read_timeout)
+                        .unwrap();
+
+                    if result.timed_out() && state.0.is_empty() && !state.1 {
+                        return Err(ErrorKind::TimedOut.into());
+                    }
+
+                    state
+                }
+                None => self.incoming.condvar.wait(state).unwrap(),
+            };
+        }
+
+        let length = buf.len().min(state.0.len());
+
+        for (dst, src) in buf.iter_mut().zip(state.0.drain(..length)) {
+            *dst = src;
+        }
+
+        Ok(length)
+    }
+}
+
+impl Write for MemoryStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.outgoing.state.lock().unwrap();
+
+        if state.1 {
+            return Err(ErrorKind::BrokenPipe.into());
+        }
+
+        state.0.extend(buf);
+        self.outgoing.condvar.notify_all();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for MemoryStream {
+    fn drop(&mut self) {
+        let mut state = self.outgoing.state.lock().unwrap();
+        state.1 = true;
+        self.outgoing.condvar.notify_all();
+    }
+}
+
+/// Client which sends packets and checks answers in order written by test.
+#[derive(Debug)]
+pub struct FakeClient<S> {
+    connection: Connection<S>,
+    clock: FakeClock,
+    ignored: Vec<&'static str>,
+}
+
+impl<S> FakeClient<S> {
+    pub fn new(stream: S, clock: FakeClock) -> Self {
+        FakeClient {
+            connection: Connection::new(stream, Side::Client),
+            clock,
+            ignored: Vec::new(),
+        }
+    }
+
+    pub fn state(&self) -> ProtocolState {
+        self.connection.state()
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.connection.compression_threshold()
+    }
+
+    pub fn clock(&self) -> &FakeClock {
+        &self.clock
+    }
+
+    /// Moves time of the shared clock forward.
+    pub fn advance_time(&mut self, duration: Duration) {
+        self.clock.advance(duration);
+    }
+
+    /// Skips packets with name, e.g. `ClientBoundKeepAlive`, while expecting other packets.
+    pub fn ignore(&mut self, name: &'static str) -> &mut Self {
+        self.ignored.push(name);
+        self
+    }
+
+    pub fn connection(&mut self) -> &mut Connection<S> {
+        &mut self.connection
+    }
+}
+
+impl<S: Read + Write> FakeClient<S> {
+    pub fn send<P: Packet>(&mut self, packet: &P) -> Result<(), ScriptError> {
+        Ok(self.connection.write_packet(packet)?)
+    }
+
+    /// Returns next packet which is not ignored.
+    pub fn expect_any<P: Packet>(&mut self) -> Result<P, ScriptError> {
+        loop {
+            let packet: P = self.connection.read_packet()?;
+
+            if !self.ignored.contains(&packet.name()) {
+                return Ok(packet);
+            }
+        }
+    }
+
+    /// Returns next packet which is not ignored, failing if it's not `T`.
+    pub fn expect_packet<T, P>(&mut self) -> Result<T, ScriptError>
+    where
+        T: PacketVariant<P>,
+        P: Packet,
+    {
+        let packet = self.expect_any::<P>()?;
+
+        T::try_from_packet(packet).map_err(|packet| ScriptError::UnexpectedPacket {
+            expected: Some(short_type_name::<T>()),
+            name: packet.name(),
+        })
+    }
+}
+
+impl FakeClient<MemoryStream> {
+    /// Fails if packet which is not ignored is received within `wait`.
+    pub fn expect_no_packet<P: Packet>(&mut self, wait: Duration) -> Result<(), ScriptError> {
+        self.connection.get_mut().set_read_timeout(Some(wait));
+        let result = self.expect_any::<P>();
+        self.connection.get_mut().set_read_timeout(None);
+
+        match result {
+            Ok(packet) => Err(ScriptError::UnexpectedPacket {
+                expected: None,
+                name: packet.name(),
+            }),
+            Err(ScriptError::DecodeError {
+                decode_error: DecodeError::IOError { io_error },
+            }) if io_error.kind() == ErrorKind::TimedOut => Ok(()),
+            Err(script_error) => Err(script_error),
+        }
+    }
+}
+
+/// Returns every order of items, e.g. packets which server must accept in any order.
+///
+/// Number of orders grows as factorial, so it's meant for a few items.
+pub fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+
+    let mut result = Vec::new();
+
+    for (index, item) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(index);
+
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, item.clone());
+            result.push(permutation);
+        }
+    }
+
+    result
+}
+
+fn short_type_name<T>() -> &'static str {
+    let name = any::type_name::<T>();
+
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::data::chat::Message;
+    use crate::testing::*;
+    use crate::version::v1_14_4::handshake::{Handshake, NextState};
+    use crate::version::v1_14_4::login::*;
+    use std::thread;
+    use uuid::Uuid;
+
+    fn login(client: &mut FakeClient<MemoryStream>) {
+        client
+            .send(&Handshake::new(
+                498,
+                String::from("localhost"),
+                25565,
+                NextState::Login,
+            ))
+            .unwrap();
+        client
+            .send(&LoginStart::new(String::from("Username")))
+            .unwrap();
+    }
+
+    /// Server answering login with packets or disconnecting if `reject` is set.
+    fn server(stream: MemoryStream, reject: bool) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            let mut connection = Connection::new(stream, Side::Server);
+            connection.read_raw_packet().unwrap();
+            connection.read_raw_packet().unwrap();
+
+            if reject {
+                let reason = Message::from_str("Server is full");
+                connection
+                    .write_packet(&LoginDisconnect::new(reason))
+                    .unwrap();
+                return;
+            }
+
+            connection.write_packet(&SetCompression::new(64)).unwrap();
+            connection
+                .write_packet(&LoginSuccess::new(Uuid::nil(), String::from("Username")))
+                .unwrap();
+        })
+    }
+
+    #[test]
+    fn test_login_script() {
+        let (client_stream, server_stream) = duplex();
+        let server = server(server_stream, false);
+
+        let mut client = FakeClient::new(client_stream, FakeClock::new());
+        login(&mut client);
+
+        let set_compression = client.expect_packet::<SetCompression, _>().unwrap();
+        assert_eq!(set_compression.threshold, 64);
+
+        client.expect_packet::<LoginSuccess, _>().unwrap();
+        assert_eq!(client.state(), ProtocolState::Game);
+        assert_eq!(client.compression_threshold(), Some(64));
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_unexpected_packet() {
+        let (client_stream, server_stream) = duplex();
+        let server = server(server_stream, true);
+
+        let mut client = FakeClient::new(client_stream, FakeClock::new());
+        login(&mut client);
+
+        let script_error = client
+            .expect_packet::<LoginSuccess, _>()
+            .expect_err("Expected error `UnexpectedPacket` because server disconnects");
+
+        match script_error {
+            ScriptError::UnexpectedPacket { expected, name } => {
+                assert_eq!(expected, Some("LoginSuccess"));
+                assert_eq!(name, "LoginDisconnect");
+            }
+            _ => panic!("Expected `UnexpectedPacket` but got `{:?}`", script_error),
+        }
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_expect_no_packet() {
+        let (client_stream, server_stream) = duplex();
+        let server = server(server_stream, false);
+
+        let mut client = FakeClient::new(client_stream, FakeClock::new());
+        client
+            .expect_no_packet::<LoginClientBoundPacket>(Duration::from_millis(10))
+            .unwrap();
+
+        login(&mut client);
+        client.ignore("SetCompression");
+
+        let script_error = client
+            .expect_no_packet::<LoginClientBoundPacket>(Duration::from_secs(5))
+            .expect_err("Expected error `UnexpectedPacket` because server sends login success");
+
+        match script_error {
+            ScriptError::UnexpectedPacket { expected, name } => {
+                assert_eq!(expected, None);
+                assert_eq!(name, "LoginSuccess");
+            }
+            _ => panic!("Expected `UnexpectedPacket` but got `{:?}`", script_error),
+        }
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_advance_time() {
+        let (client_stream, _) = duplex();
+        let clock = FakeClock::new();
+        let start = clock.now();
+
+        let mut client = FakeClient::new(client_stream, clock.clone());
+        client.advance_time(Duration::from_secs(30));
+
+        assert_eq!(clock.now() - start, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_permutations() {
+        let permutations = permutations(&[1, 2, 3]);
+
+        assert_eq!(permutations.len(), 6);
+        assert_eq!(permutations[0], [1, 2, 3]);
+        assert_eq!(permutations[5], [3, 2, 1]);
+    }
+}
@@ -0,0 +1,70 @@
+//! Translation of packets between adjacent supported protocol versions.
+//!
+//! Translations are implemented as `TryFrom` conversions between packets of different versions.
+//! Fields without equivalent in target version are dropped or filled with defaults as long as
+//! packet meaning is preserved, otherwise `TranslateError` is returned.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_16_5")]
+//! # {
+//! use minecraft_protocol::data::chat::{Color, MessageBuilder, Payload};
+//! use minecraft_protocol::version::v1_14_4::game::GameClientBoundPacket;
+//! use minecraft_protocol::version::v1_16_5::game::GameDisconnect;
+//! use std::convert::TryFrom;
+//!
+//! let reason = MessageBuilder::builder(Payload::text("Server closed"))
+//!     .color(Color::Hex(String::from("#ff5050")))
+//!     .build();
+//!
+//! let packet = GameDisconnect::new(reason);
+//!
+//! match GameClientBoundPacket::try_from(packet).unwrap() {
+//!     GameClientBoundPacket::GameDisconnect(game_disconnect) => {
+//!         assert_eq!(game_disconnect.reason.color, Some(Color::Red));
+//!     }
+//!     _ => unreachable!(),
+//! }
+//! # }
+//! ```
+#[cfg(all(feature = "v1_14_4", feature = "v1_16_5"))]
+mod v1_14_4_v1_16_5;
+
+#[cfg(feature = "v1_14_4")]
+use crate::data::chat::{Color, Message, Payload};
+#[cfg(feature = "v1_14_4")]
+use crate::error::TranslateError;
+
+/// Replaces hex colors with the nearest named colors in message and all nested messages.
+#[cfg(feature = "v1_14_4")]
+pub(crate) fn downgrade_message(mut message: Message) -> Result<Message, TranslateError> {
+    if let Some(Color::Hex(hex)) = &message.color {
+        message.color = Some(nearest_named_color(hex)?);
+    }
+
+    if let Payload::Translation { with, .. } = &mut message.payload {
+        *with = with
+            .drain(..)
+            .map(downgrade_message)
+            .collect::<Result<_, _>>()?;
+    }
+
+    message.extra = message
+        .extra
+        .into_iter()
+        .map(downgrade_message)
+        .collect::<Result<_, _>>()?;
+
+    Ok(message)
+}
+
+#[cfg(feature = "v1_14_4")]
+fn nearest_named_color(hex: &str) -> Result<Color, TranslateError> {
+    Color::Hex(hex.to_owned())
+        .to_named()
+        .map_err(|_| TranslateError::UnrepresentableValue {
+            field: "color",
+            value: hex.to_owned(),
+        })
+}
@@ -0,0 +1,445 @@
+//! Translation between 1.14.4 and 1.16.5 packets.
+//!
+//! 1.16 added hex colors to chat messages. They are replaced with the nearest named color when
+//! translating to 1.14.4.
+use crate::error::TranslateError;
+use crate::translate::downgrade_message;
+use crate::version::v1_14_4::game as v1_14_4;
+use crate::version::v1_16_5::game as v1_16_5;
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+impl TryFrom<v1_16_5::GameServerBoundPacket> for v1_14_4::GameServerBoundPacket {
+    type Error = TranslateError;
+
+    fn try_from(packet: v1_16_5::GameServerBoundPacket) -> Result<Self, Self::Error> {
+        match packet {
+            v1_16_5::GameServerBoundPacket::ServerBoundChatMessage(chat_message) => Ok(
+                v1_14_4::GameServerBoundPacket::ServerBoundChatMessage(chat_message.into()),
+            ),
+            v1_16_5::GameServerBoundPacket::ClientStatus(client_status) => Ok(
+                v1_14_4::GameServerBoundPacket::ClientStatus(client_status.into()),
+            ),
+            v1_16_5::GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => Ok(
+                v1_14_4::GameServerBoundPacket::ServerBoundKeepAlive(keep_alive.into()),
+            ),
+        }
+    }
+}
+
+impl TryFrom<v1_14_4::GameServerBoundPacket> for v1_16_5::GameServerBoundPacket {
+    type Error = TranslateError;
+
+    fn try_from(packet: v1_14_4::GameServerBoundPacket) -> Result<Self, Self::Error> {
+        match packet {
+            v1_14_4::GameServerBoundPacket::ServerBoundChatMessage(chat_message) => Ok(
+                v1_16_5::GameServerBoundPacket::ServerBoundChatMessage(chat_message.into()),
+            ),
+            v1_14_4::GameServerBoundPacket::ClientStatus(client_status) => Ok(
+                v1_16_5::GameServerBoundPacket::ClientStatus(client_status.into()),
+            ),
+            v1_14_4::GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => Ok(
+                v1_16_5::GameServerBoundPacket::ServerBoundKeepAlive(keep_alive.into()),
+            ),
+            v1_14_4::GameServerBoundPacket::ServerBoundAbilities(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "ServerBoundAbilities",
+                })
+            }
+            v1_14_4::GameServerBoundPacket::EntityAction(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "EntityAction",
+                })
+            }
+            v1_14_4::GameServerBoundPacket::HandSwing(_) => {
+                Err(TranslateError::UnsupportedPacket { name: "HandSwing" })
+            }
+            v1_14_4::GameServerBoundPacket::UseItem(_) => {
+                Err(TranslateError::UnsupportedPacket { name: "UseItem" })
+            }
+            v1_14_4::GameServerBoundPacket::UpdateSign(_) => {
+                Err(TranslateError::UnsupportedPacket { name: "UpdateSign" })
+            }
+            v1_14_4::GameServerBoundPacket::ServerBoundPluginMessage(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "ServerBoundPluginMessage",
+                })
+            }
+            v1_14_4::GameServerBoundPacket::ServerBoundTabComplete(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "ServerBoundTabComplete",
+                })
+            }
+        }
+    }
+}
+
+impl TryFrom<v1_16_5::GameClientBoundPacket> for v1_14_4::GameClientBoundPacket {
+    type Error = TranslateError;
+
+    fn try_from(packet: v1_16_5::GameClientBoundPacket) -> Result<Self, Self::Error> {
+        match packet {
+            v1_16_5::GameClientBoundPacket::ClientBoundChatMessage(chat_message) => {
+                Ok(v1_14_4::GameClientBoundPacket::ClientBoundChatMessage(
+                    TryFrom::try_from(chat_message)?,
+                ))
+            }
+            v1_16_5::GameClientBoundPacket::ClientBoundKeepAlive(keep_alive) => Ok(
+                v1_14_4::GameClientBoundPacket::ClientBoundKeepAlive(keep_alive.into()),
+            ),
+            v1_16_5::GameClientBoundPacket::GameDisconnect(game_disconnect) => Ok(
+                v1_14_4::GameClientBoundPacket::GameDisconnect(TryFrom::try_from(game_disconnect)?),
+            ),
+            v1_16_5::GameClientBoundPacket::Title(title) => Ok(
+                v1_14_4::GameClientBoundPacket::Title(TryFrom::try_from(title)?),
+            ),
+            v1_16_5::GameClientBoundPacket::JoinGame(_) => {
+                Err(TranslateError::UnsupportedPacket { name: "JoinGame" })
+            }
+        }
+    }
+}
+
+impl TryFrom<v1_14_4::GameClientBoundPacket> for v1_16_5::GameClientBoundPacket {
+    type Error = TranslateError;
+
+    fn try_from(packet: v1_14_4::GameClientBoundPacket) -> Result<Self, Self::Error> {
+        match packet {
+            v1_14_4::GameClientBoundPacket::ClientBoundChatMessage(chat_message) => Ok(
+                v1_16_5::GameClientBoundPacket::ClientBoundChatMessage(chat_message.into()),
+            ),
+            v1_14_4::GameClientBoundPacket::ClientBoundKeepAlive(keep_alive) => Ok(
+                v1_16_5::GameClientBoundPacket::ClientBoundKeepAlive(keep_alive.into()),
+            ),
+            v1_14_4::GameClientBoundPacket::GameDisconnect(game_disconnect) => Ok(
+                v1_16_5::GameClientBoundPacket::GameDisconnect(game_disconnect.into()),
+            ),
+            v1_14_4::GameClientBoundPacket::Title(title) => {
+                Ok(v1_16_5::GameClientBoundPacket::Title(title.into()))
+            }
+            v1_14_4::GameClientBoundPacket::JoinGame(_) => {
+                Err(TranslateError::UnsupportedPacket { name: "JoinGame" })
+            }
+            v1_14_4::GameClientBoundPacket::ChunkData(_) => {
+                Err(TranslateError::UnsupportedPacket { name: "ChunkData" })
+            }
+            v1_14_4::GameClientBoundPacket::BossBar(_) => {
+                Err(TranslateError::UnsupportedPacket { name: "BossBar" })
+            }
+            v1_14_4::GameClientBoundPacket::EntityMetadata(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "EntityMetadata",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::EntityVelocity(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "EntityVelocity",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::CombatEvent(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "CombatEvent",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::EntityAnimation(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "EntityAnimation",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::OpenHorseWindow(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "OpenHorseWindow",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::OpenBook(_) => {
+                Err(TranslateError::UnsupportedPacket { name: "OpenBook" })
+            }
+            v1_14_4::GameClientBoundPacket::OpenSignEditor(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "OpenSignEditor",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::BlockEntityData(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "BlockEntityData",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::SelectAdvancementsTab(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "SelectAdvancementsTab",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::ClientBoundPluginMessage(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "ClientBoundPluginMessage",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::ClientBoundTabComplete(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "ClientBoundTabComplete",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::UnloadChunk(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "UnloadChunk",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::UpdateViewPosition(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "UpdateViewPosition",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::WindowItems(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "WindowItems",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::SetSlot(_) => {
+                Err(TranslateError::UnsupportedPacket { name: "SetSlot" })
+            }
+            v1_14_4::GameClientBoundPacket::SetCooldown(_) => {
+                Err(TranslateError::UnsupportedPacket {
+                    name: "SetCooldown",
+                })
+            }
+            v1_14_4::GameClientBoundPacket::TimeUpdate(_) => {
+                Err(TranslateError::UnsupportedPacket { name: "TimeUpdate" })
+            }
+        }
+    }
+}
+
+impl From<v1_16_5::ServerBoundChatMessage> for v1_14_4::ServerBoundChatMessage {
+    fn from(chat_message: v1_16_5::ServerBoundChatMessage) -> Self {
+        v1_14_4::ServerBoundChatMessage {
+            message: chat_message.message,
+        }
+    }
+}
+
+impl From<v1_14_4::ServerBoundChatMessage> for v1_16_5::ServerBoundChatMessage {
+    fn from(chat_message: v1_14_4::ServerBoundChatMessage) -> Self {
+        v1_16_5::ServerBoundChatMessage {
+            message: chat_message.message,
+        }
+    }
+}
+
+impl From<v1_16_5::ClientStatus> for v1_14_4::ClientStatus {
+    fn from(client_status: v1_16_5::ClientStatus) -> Self {
+        v1_14_4::ClientStatus {
+            action: client_status.action,
+        }
+    }
+}
+
+impl From<v1_14_4::ClientStatus> for v1_16_5::ClientStatus {
+    fn from(client_status: v1_14_4::ClientStatus) -> Self {
+        v1_16_5::ClientStatus {
+            action: client_status.action,
+        }
+    }
+}
+
+impl TryFrom<v1_16_5::ClientBoundChatMessage> for v1_14_4::ClientBoundChatMessage {
+    type Error = TranslateError;
+
+    fn try_from(chat_message: v1_16_5::ClientBoundChatMessage) -> Result<Self, Self::Error> {
+        Ok(v1_14_4::ClientBoundChatMessage {
+            message: downgrade_message(chat_message.message)?,
+            position: chat_message.position,
+        })
+    }
+}
+
+impl From<v1_14_4::ClientBoundChatMessage> for v1_16_5::ClientBoundChatMessage {
+    fn from(chat_message: v1_14_4::ClientBoundChatMessage) -> Self {
+        v1_16_5::ClientBoundChatMessage {
+            message: chat_message.message,
+            position: chat_message.position,
+            sender: Uuid::nil(),
+        }
+    }
+}
+
+impl From<v1_16_5::ServerBoundKeepAlive> for v1_14_4::ServerBoundKeepAlive {
+    fn from(keep_alive: v1_16_5::ServerBoundKeepAlive) -> Self {
+        v1_14_4::ServerBoundKeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<v1_14_4::ServerBoundKeepAlive> for v1_16_5::ServerBoundKeepAlive {
+    fn from(keep_alive: v1_14_4::ServerBoundKeepAlive) -> Self {
+        v1_16_5::ServerBoundKeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<v1_16_5::ClientBoundKeepAlive> for v1_14_4::ClientBoundKeepAlive {
+    fn from(keep_alive: v1_16_5::ClientBoundKeepAlive) -> Self {
+        v1_14_4::ClientBoundKeepAlive { id: keep_alive.id }
+    }
+}
+
+impl From<v1_14_4::ClientBoundKeepAlive> for v1_16_5::ClientBoundKeepAlive {
+    fn from(keep_alive: v1_14_4::ClientBoundKeepAlive) -> Self {
+        v1_16_5::ClientBoundKeepAlive { id: keep_alive.id }
+    }
+}
+
+impl TryFrom<v1_16_5::GameDisconnect> for v1_14_4::GameDisconnect {
+    type Error = TranslateError;
+
+    fn try_from(game_disconnect: v1_16_5::GameDisconnect) -> Result<Self, Self::Error> {
+        Ok(v1_14_4::GameDisconnect {
+            reason: downgrade_message(game_disconnect.reason)?,
+        })
+    }
+}
+
+impl From<v1_14_4::GameDisconnect> for v1_16_5::GameDisconnect {
+    fn from(game_disconnect: v1_14_4::GameDisconnect) -> Self {
+        v1_16_5::GameDisconnect {
+            reason: game_disconnect.reason,
+        }
+    }
+}
+
+impl TryFrom<v1_16_5::Title> for v1_14_4::Title {
+    type Error = TranslateError;
+
+    fn try_from(title: v1_16_5::Title) -> Result<Self, Self::Error> {
+        let action = match title.action {
+            v1_14_4::TitleAction::SetTitle { text } => v1_14_4::TitleAction::SetTitle {
+                text: downgrade_message(text)?,
+            },
+            v1_14_4::TitleAction::SetSubtitle { text } => v1_14_4::TitleAction::SetSubtitle {
+                text: downgrade_message(text)?,
+            },
+            v1_14_4::TitleAction::SetActionBar { text } => v1_14_4::TitleAction::SetActionBar {
+                text: downgrade_message(text)?,
+            },
+            action => action,
+        };
+
+        Ok(v1_14_4::Title { action })
+    }
+}
+
+impl From<v1_14_4::Title> for v1_16_5::Title {
+    fn from(title: v1_14_4::Title) -> Self {
+        v1_16_5::Title {
+            action: title.action,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::chat::{Color, Message, MessageBuilder, Payload};
+    use crate::error::TranslateError;
+    use crate::version::v1_14_4::game as v1_14_4;
+    use crate::version::v1_16_5::game as v1_16_5;
+    use std::convert::TryFrom;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_client_bound_chat_message_downgrade() {
+        let message = MessageBuilder::builder(Payload::text("Hello"))
+            .color(Color::Hex(String::from("#ff4f4f")))
+            .then(Payload::text("world"))
+            .color(Color::Hex(String::from("#5050ff")))
+            .build();
+
+        let packet = v1_16_5::ClientBoundChatMessage::new(
+            message,
+            v1_16_5::MessagePosition::System,
+            Uuid::new_v4(),
+        );
+
+        match v1_14_4::GameClientBoundPacket::try_from(packet).unwrap() {
+            v1_14_4::GameClientBoundPacket::ClientBoundChatMessage(chat_message) => {
+                let expected_message = MessageBuilder::builder(Payload::text("Hello"))
+                    .color(Color::Red)
+                    .then(Payload::text("world"))
+                    .color(Color::Blue)
+                    .build();
+
+                assert_eq!(chat_message.message, expected_message);
+                assert_eq!(chat_message.position, v1_14_4::MessagePosition::System);
+            }
+            _ => panic!("Expected chat message"),
+        }
+    }
+
+    #[test]
+    fn test_client_bound_chat_message_upgrade() {
+        let packet = v1_14_4::ClientBoundChatMessage::new(
+            Message::from_str("Hello"),
+            v1_14_4::MessagePosition::Chat,
+        );
+
+        match v1_16_5::GameClientBoundPacket::try_from(packet).unwrap() {
+            v1_16_5::GameClientBoundPacket::ClientBoundChatMessage(chat_message) => {
+                assert_eq!(chat_message.message, Message::from_str("Hello"));
+                assert_eq!(chat_message.sender, Uuid::nil());
+            }
+            _ => panic!("Expected chat message"),
+        }
+    }
+
+    #[test]
+    fn test_title_downgrade_invalid_color() {
+        let text = MessageBuilder::builder(Payload::text("Title"))
+            .color(Color::Hex(String::from("#fffff")))
+            .build();
+
+        let packet = v1_16_5::Title::new(v1_16_5::TitleAction::SetTitle { text });
+
+        let error = v1_14_4::GameClientBoundPacket::try_from(packet)
+            .expect_err("Expected error because color is invalid");
+
+        assert_eq!(
+            error,
+            TranslateError::UnrepresentableValue {
+                field: "color",
+                value: String::from("#fffff")
+            }
+        );
+    }
+
+    #[test]
+    fn test_keep_alive_round_trip() {
+        let packet = v1_14_4::ServerBoundKeepAlive::new(31122019);
+        let packet = v1_16_5::GameServerBoundPacket::try_from(packet).unwrap();
+
+        assert_eq!(packet.get_type_id(), 0x10);
+
+        match v1_14_4::GameServerBoundPacket::try_from(packet).unwrap() {
+            v1_14_4::GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => {
+                assert_eq!(keep_alive.id, 31122019);
+            }
+            _ => panic!("Expected keep alive"),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_packet_upgrade() {
+        let packet = v1_14_4::JoinGame::new(
+            1,
+            v1_14_4::GameMode::Survival,
+            0,
+            20,
+            String::from("default"),
+            10,
+            false,
+        );
+
+        let error = v1_16_5::GameClientBoundPacket::try_from(packet)
+            .expect_err("Expected error because join game translation is not supported");
+
+        assert_eq!(
+            error,
+            TranslateError::UnsupportedPacket { name: "JoinGame" }
+        );
+    }
+}
@@ -0,0 +1,399 @@
+//! Transports which carry packet frames.
+//!
+//! Over TCP every frame is prefixed with var int length, which `LengthDelimited` reads and
+//! writes. Transports with their own message framing, e.g. QUIC streams or WebTransport between
+//! proxy hops, don't need the prefix, so `RawStream` sends frame bodies as they are, one message
+//! per frame. Other transports can implement `FramedTransport` directly.
+//!
+//! `TransportCodec` reads and writes packets over any `FramedTransport`, following compression
+//! and max packet length like `PacketCodec`. Over `LengthDelimited` it writes the same bytes as
+//! `PacketCodec`.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::transport::{RawStream, TransportCodec};
+//! use minecraft_protocol::version::v1_14_4::game::{ClientBoundKeepAlive, GameClientBoundPacket};
+//! use std::io::Cursor;
+//!
+//! let mut codec = TransportCodec::new(RawStream::new(Cursor::new(Vec::new())));
+//! codec.write_packet(&ClientBoundKeepAlive::new(1)).unwrap();
+//!
+//! // Frame has no length prefix, only packet id and data.
+//! let bytes = codec.into_inner().into_inner().into_inner();
+//! assert_eq!(bytes, [0x20, 0, 0, 0, 0, 0, 0, 0, 1]);
+//!
+//! let mut codec = TransportCodec::new(RawStream::new(Cursor::new(bytes)));
+//! let packet: GameClientBoundPacket = codec.read_packet().unwrap();
+//!
+//! assert!(matches!(packet, GameClientBoundPacket::ClientBoundKeepAlive(_)));
+//! # }
+//! ```
+use crate::codec::FlushPolicy;
+use crate::compression::CompressedRawPacket;
+use crate::encoder::{var_i32_size, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::{read_frame, write_all_vectored, Packet, RawPacket, PACKET_MAX_LENGTH};
+use std::io::{self, ErrorKind, IoSlice, Read, Write};
+
+/// Transport which reads and writes whole frames.
+///
+/// Frame body is packet id and data, or data length and compressed packet when compression is
+/// enabled.
+pub trait FramedTransport {
+    /// Reads body of the next frame, returning `DecodeError::PacketTooLong` if it's longer than
+    /// max length.
+    fn read_frame(&mut self, max_length: usize) -> Result<Vec<u8>, DecodeError>;
+
+    fn write_frame(&mut self, body: &[u8]) -> Result<(), EncodeError>;
+
+    fn flush(&mut self) -> Result<(), EncodeError>;
+}
+
+/// Frames prefixed with var int length on a byte stream, as sent over TCP.
+#[derive(Debug)]
+pub struct LengthDelimited<S> {
+    stream: S,
+}
+
+impl<S> LengthDelimited<S> {
+    pub fn new(stream: S) -> Self {
+        LengthDelimited { stream }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Read + Write> FramedTransport for LengthDelimited<S> {
+    fn read_frame(&mut self, max_length: usize) -> Result<Vec<u8>, DecodeError> {
+        read_frame(&mut self.stream, max_length)
+    }
+
+    fn write_frame(&mut self, body: &[u8]) -> Result<(), EncodeError> {
+        let mut header = Vec::with_capacity(var_i32_size(body.len() as i32));
+        header.write_var_i32(body.len() as i32)?;
+
+        let mut bufs = [IoSlice::new(&header), IoSlice::new(body)];
+
+        Ok(write_all_vectored(&mut self.stream, &mut bufs)?)
+    }
+
+    fn flush(&mut self) -> Result<(), EncodeError> {
+        Ok(self.stream.flush()?)
+    }
+}
+
+/// Frames without length prefix on a stream which keeps message boundaries.
+///
+/// Every read of the stream must return one whole message and every write must send one, as
+/// message oriented adapters of QUIC or WebTransport do. Writes which don't take the whole frame
+/// fail with `ErrorKind::WriteZero`.
+#[derive(Debug)]
+pub struct RawStream<S> {
+    stream: S,
+}
+
+impl<S> RawStream<S> {
+    pub fn new(stream: S) -> Self {
+        RawStream { stream }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Read + Write> FramedTransport for RawStream<S> {
+    fn read_frame(&mut self, max_length: usize) -> Result<Vec<u8>, DecodeError> {
+        // One byte more than allowed tells too long frames apart.
+        let mut buf = vec![0; max_length.saturating_add(1)];
+
+        let length = loop {
+            match self.stream.read(&mut buf) {
+                Ok(0) => return Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+                Ok(length) => break length,
+                Err(io_error) if io_error.kind() == ErrorKind::Interrupted => {}
+                Err(io_error) => return Err(io_error.into()),
+            }
+        };
+
+        if length > max_length {
+            return Err(DecodeError::PacketTooLong { length, max_length });
+        }
+
+        buf.truncate(length);
+
+        Ok(buf)
+    }
+
+    fn write_frame(&mut self, body: &[u8]) -> Result<(), EncodeError> {
+        loop {
+            match self.stream.write(body) {
+                Ok(written) if written == body.len() => return Ok(()),
+                Ok(_) => return Err(io::Error::from(ErrorKind::WriteZero).into()),
+                Err(io_error) if io_error.kind() == ErrorKind::Interrupted => {}
+                Err(io_error) => return Err(io_error.into()),
+            }
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), EncodeError> {
+        Ok(self.stream.flush()?)
+    }
+}
+
+/// Reads and writes packets over framed transport.
+#[derive(Debug)]
+pub struct TransportCodec<T> {
+    transport: T,
+    flush_policy: FlushPolicy,
+    compression_threshold: Option<usize>,
+    max_packet_length: usize,
+}
+
+impl<T> TransportCodec<T> {
+    pub fn new(transport: T) -> Self {
+        TransportCodec {
+            transport,
+            flush_policy: FlushPolicy::default(),
+            compression_threshold: None,
+            max_packet_length: PACKET_MAX_LENGTH,
+        }
+    }
+
+    /// There are no bundles, so `PerBundle` doesn't flush either.
+    pub fn with_flush_policy(mut self, flush_policy: FlushPolicy) -> Self {
+        self.flush_policy = flush_policy;
+        self
+    }
+
+    pub fn with_max_packet_length(mut self, max_packet_length: usize) -> Self {
+        self.max_packet_length = max_packet_length;
+        self
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.compression_threshold
+    }
+
+    pub fn set_compression_threshold(&mut self, compression_threshold: Option<usize>) {
+        self.compression_threshold = compression_threshold;
+    }
+
+    pub fn max_packet_length(&self) -> usize {
+        self.max_packet_length
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+}
+
+impl<T: FramedTransport> TransportCodec<T> {
+    /// Returns `DecodeError::PacketTooLong` if frame is longer than max packet length.
+    pub fn read_raw_packet(&mut self) -> Result<RawPacket, DecodeError> {
+        let body = self.transport.read_frame(self.max_packet_length)?;
+
+        match self.compression_threshold {
+            Some(threshold) => Ok(CompressedRawPacket::from_body(body, threshold)?.into_inner()),
+            None => RawPacket::from_body(body),
+        }
+    }
+
+    pub fn read_packet<P: Packet>(&mut self) -> Result<P, DecodeError> {
+        self.read_raw_packet()?.decode_packet()
+    }
+
+    /// Returns `EncodeError::PacketTooLong` if frame is longer than max packet length.
+    pub fn write_raw_packet(&mut self, raw_packet: &RawPacket) -> Result<(), EncodeError> {
+        let body = match self.compression_threshold {
+            Some(threshold) => {
+                CompressedRawPacket::from(raw_packet.clone()).encode_body(threshold)?
+            }
+            None => {
+                let mut body =
+                    Vec::with_capacity(var_i32_size(raw_packet.id) + raw_packet.data.len());
+                body.write_var_i32(raw_packet.id)?;
+                body.extend_from_slice(&raw_packet.data);
+
+                body
+            }
+        };
+
+        if body.len() > self.max_packet_length {
+            return Err(EncodeError::PacketTooLong {
+                length: body.len(),
+                max_length: self.max_packet_length,
+            });
+        }
+
+        self.transport.write_frame(&body)?;
+
+        if self.flush_policy == FlushPolicy::PerPacket {
+            self.transport.flush()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_packet<P: Packet>(&mut self, packet: &P) -> Result<(), EncodeError> {
+        self.write_raw_packet(&RawPacket::from_packet(packet)?)
+    }
+
+    pub fn flush(&mut self) -> Result<(), EncodeError> {
+        self.transport.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::PacketCodec;
+    use crate::transport::*;
+    use std::collections::VecDeque;
+    use std::io::Cursor;
+
+    /// Stream keeping boundaries of written messages.
+    #[derive(Default)]
+    struct MessageStream {
+        messages: VecDeque<Vec<u8>>,
+    }
+
+    impl Read for MessageStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.messages.pop_front() {
+                Some(message) => {
+                    let length = message.len().min(buf.len());
+                    buf[..length].copy_from_slice(&message[..length]);
+
+                    Ok(length)
+                }
+                None => Ok(0),
+            }
+        }
+    }
+
+    impl Write for MessageStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.messages.push_back(buf.to_vec());
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn packets() -> Vec<RawPacket> {
+        vec![
+            RawPacket::new(0x20, vec![1; 8]),
+            RawPacket::new(0x21, vec![2; 300]),
+            RawPacket::new(0x00, vec![]),
+        ]
+    }
+
+    #[test]
+    fn test_length_delimited_matches_packet_codec() {
+        for threshold in [None, Some(64)] {
+            let mut codec = TransportCodec::new(LengthDelimited::new(Cursor::new(Vec::new())));
+            codec.set_compression_threshold(threshold);
+            let mut packet_codec = PacketCodec::new(Vec::new());
+            packet_codec.set_compression_threshold(threshold);
+
+            for raw_packet in packets() {
+                codec.write_raw_packet(&raw_packet).unwrap();
+                packet_codec.write_raw_packet(&raw_packet).unwrap();
+            }
+
+            let bytes = codec.into_inner().into_inner().into_inner();
+            assert_eq!(bytes, packet_codec.into_inner());
+
+            let mut codec = TransportCodec::new(LengthDelimited::new(Cursor::new(bytes)));
+            codec.set_compression_threshold(threshold);
+
+            for raw_packet in packets() {
+                assert_eq!(codec.read_raw_packet().unwrap(), raw_packet);
+            }
+        }
+    }
+
+    #[test]
+    fn test_raw_stream_round_trip() {
+        for threshold in [None, Some(64)] {
+            let mut codec = TransportCodec::new(RawStream::new(MessageStream::default()));
+            codec.set_compression_threshold(threshold);
+
+            for raw_packet in packets() {
+                codec.write_raw_packet(&raw_packet).unwrap();
+            }
+
+            let frames = &codec.get_ref().get_ref().messages;
+            assert_eq!(frames.len(), 3);
+            assert_eq!(frames[0][0], if threshold.is_some() { 0 } else { 0x20 });
+
+            for raw_packet in packets() {
+                assert_eq!(codec.read_raw_packet().unwrap(), raw_packet);
+            }
+        }
+    }
+
+    #[test]
+    fn test_raw_stream_frame_too_long() {
+        let mut stream = MessageStream::default();
+        stream.messages.push_back(vec![0x20; 17]);
+        let mut codec = TransportCodec::new(RawStream::new(stream)).with_max_packet_length(16);
+
+        let decode_error = codec
+            .read_raw_packet()
+            .expect_err("Expected error `PacketTooLong` because frame is longer than 16 bytes");
+
+        match decode_error {
+            DecodeError::PacketTooLong { length, max_length } => {
+                assert_eq!(length, 17);
+                assert_eq!(max_length, 16);
+            }
+            _ => panic!("Expected `PacketTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_raw_stream_partial_write() {
+        let mut transport = RawStream::new(Cursor::new([0u8; 4]));
+        let encode_error = transport
+            .write_frame(&[1; 8])
+            .expect_err("Expected error `IOError` because stream took only part of frame");
+
+        match encode_error {
+            EncodeError::IOError { io_error } => assert_eq!(io_error.kind(), ErrorKind::WriteZero),
+            _ => panic!("Expected `IOError` but got `{:?}`", encode_error),
+        }
+    }
+}
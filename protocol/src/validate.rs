@@ -0,0 +1,311 @@
+//! Checking packets against protocol invariants before encoding.
+//!
+//! Clients kick themselves or get kicked by vanilla servers for packets with too long strings,
+//! out of range values or too many items, which encoders don't always catch. `Validate` returns
+//! every such violation at once, so servers can check packets they build in development builds.
+//!
+//! `#[derive(Validate)]` checks strings with `#[data_type(max_length = N)]` and fields marked
+//! with `#[validate(...)]`:
+//!
+//! - `max_length = N`: string is at most N UTF-16 code units long, like vanilla counts, and byte
+//!   array is at most N bytes long.
+//! - `min = N`, `max = N`: integer is in range, bounds are inclusive.
+//! - `max_items = N`: collection has at most N items.
+//! - `nested`: field implements `Validate`, its violations are prefixed with field name.
+//!
+//! Fields of tuple variants, as in packet enums, are always nested. Packets of 1.14.4 implement
+//! `Validate`.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_14_4")]
+//! # {
+//! use minecraft_protocol::validate::{Validate, ViolationKind};
+//! use minecraft_protocol::version::v1_14_4::game::{GameMode, JoinGame};
+//!
+//! let join_game = JoinGame::new(0, GameMode::Survival, 0, 20, "default".into(), 64, false);
+//!
+//! if cfg!(debug_assertions) {
+//!     let violations = join_game.validate();
+//!
+//!     assert_eq!(violations.len(), 1);
+//!     assert_eq!(violations[0].field, "view_distance");
+//!     assert_eq!(
+//!         violations[0].kind,
+//!         ViolationKind::OutOfRange { value: 64, min: Some(2), max: Some(32) }
+//!     );
+//! }
+//! # }
+//! ```
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Invariant broken by packet field.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Violation {
+    /// Path of field, e.g. `matches[1].value`.
+    pub field: String,
+    pub kind: ViolationKind,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ViolationKind {
+    /// String is longer than max length in UTF-16 code units or byte array in bytes.
+    TooLong { length: usize, max_length: usize },
+    /// Integer is out of inclusive range.
+    OutOfRange {
+        value: i64,
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    /// Collection has more items than allowed.
+    TooManyItems { length: usize, max_length: usize },
+    /// Identifier contains characters which are not allowed.
+    InvalidIdentifier { value: String },
+}
+
+/// Packet or data type which can check its invariants.
+///
+/// Can be derived with `#[derive(Validate)]`, see module documentation.
+pub trait Validate {
+    /// Appends violations with field paths prefixed with `path`.
+    fn validate_into(&self, path: &str, violations: &mut Vec<Violation>);
+
+    /// Returns all violations, empty if packet can be sent.
+    fn validate(&self) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        self.validate_into("", &mut violations);
+
+        violations
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate_into(&self, path: &str, violations: &mut Vec<Violation>) {
+        if let Some(value) = self {
+            value.validate_into(path, violations)
+        }
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate_into(&self, path: &str, violations: &mut Vec<Violation>) {
+        for (idx, value) in self.iter().enumerate() {
+            value.validate_into(&format!("{}[{}]", path, idx), violations)
+        }
+    }
+}
+
+impl<T: Validate> Validate for Arc<T> {
+    fn validate_into(&self, path: &str, violations: &mut Vec<Violation>) {
+        self.as_ref().validate_into(path, violations)
+    }
+}
+
+impl<K: ToString, V: Validate> Validate for HashMap<K, V> {
+    fn validate_into(&self, path: &str, violations: &mut Vec<Violation>) {
+        for (key, value) in self {
+            value.validate_into(&format!("{}[{}]", path, key.to_string()), violations)
+        }
+    }
+}
+
+impl<K: ToString, V: Validate> Validate for BTreeMap<K, V> {
+    fn validate_into(&self, path: &str, violations: &mut Vec<Violation>) {
+        for (key, value) in self {
+            value.validate_into(&format!("{}[{}]", path, key.to_string()), violations)
+        }
+    }
+}
+
+/// Joins path of parent with field name, empty names keep parent path.
+pub fn field_path(path: &str, field: &str) -> String {
+    match (path.is_empty(), field.is_empty()) {
+        (true, _) => field.to_owned(),
+        (_, true) => path.to_owned(),
+        _ => format!("{}.{}", path, field),
+    }
+}
+
+/// Length checked by `max_length`.
+#[doc(hidden)]
+pub trait ValidateLength {
+    fn validate_length(&self) -> usize;
+}
+
+impl ValidateLength for str {
+    fn validate_length(&self) -> usize {
+        self.encode_utf16().count()
+    }
+}
+
+impl ValidateLength for [u8] {
+    fn validate_length(&self) -> usize {
+        self.len()
+    }
+}
+
+#[doc(hidden)]
+pub fn check_length(
+    violations: &mut Vec<Violation>,
+    path: &str,
+    field: &str,
+    length: usize,
+    max_length: usize,
+) {
+    if length > max_length {
+        violations.push(Violation {
+            field: field_path(path, field),
+            kind: ViolationKind::TooLong { length, max_length },
+        });
+    }
+}
+
+#[doc(hidden)]
+pub fn check_range(
+    violations: &mut Vec<Violation>,
+    path: &str,
+    field: &str,
+    value: i64,
+    min: Option<i64>,
+    max: Option<i64>,
+) {
+    let below = min.is_some_and(|min| value < min);
+    let above = max.is_some_and(|max| value > max);
+
+    if below || above {
+        violations.push(Violation {
+            field: field_path(path, field),
+            kind: ViolationKind::OutOfRange { value, min, max },
+        });
+    }
+}
+
+#[doc(hidden)]
+pub fn check_items(
+    violations: &mut Vec<Violation>,
+    path: &str,
+    field: &str,
+    length: usize,
+    max_length: usize,
+) {
+    if length > max_length {
+        violations.push(Violation {
+            field: field_path(path, field),
+            kind: ViolationKind::TooManyItems { length, max_length },
+        });
+    }
+}
+
+#[cfg(all(test, feature = "v1_14_4"))]
+mod tests {
+    use crate::data::identifier::Identifier;
+    use crate::limits;
+    use crate::validate::*;
+    use crate::version::v1_14_4::game::*;
+    use crate::version::v1_14_4::login::LoginStart;
+
+    #[test]
+    fn test_packet_enum_validates_variant() {
+        let lines = [
+            String::from("Line"),
+            "a".repeat(385),
+            String::new(),
+            String::new(),
+        ];
+        let packet = UpdateSign::new(BlockPosition::new(0, 64, 0), lines);
+
+        assert_eq!(
+            packet.validate(),
+            [Violation {
+                field: String::from("line_2"),
+                kind: ViolationKind::TooLong {
+                    length: 385,
+                    max_length: 384
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn test_valid_packets() {
+        let join_game = JoinGame::new(0, GameMode::Creative, 0, 20, "flat".into(), 10, false);
+        let login_start = LoginStart::new(String::from("Username"));
+
+        assert!(join_game.validate().is_empty());
+        assert!(login_start.validate().is_empty());
+    }
+
+    #[test]
+    fn test_nested_list_paths() {
+        let matches = vec![
+            TabCompleteMatch::new(String::from("help"), None),
+            TabCompleteMatch::new("a".repeat(32768), None),
+        ];
+        let packet = ClientBoundTabComplete::new(1, 0, 1, matches);
+
+        let violations = packet.validate();
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "matches[1].value");
+    }
+
+    #[test]
+    fn test_plugin_message_violations() {
+        let plugin_message = ClientBoundPluginMessage {
+            channel: Identifier::from("Bad Channel"),
+            data: vec![0; limits::CLIENT_BOUND_PAYLOAD_MAX_LENGTH + 1],
+        };
+
+        let kinds: Vec<_> = plugin_message
+            .validate()
+            .into_iter()
+            .map(|v| v.kind)
+            .collect();
+
+        assert_eq!(
+            kinds,
+            [
+                ViolationKind::InvalidIdentifier {
+                    value: String::from("Bad Channel")
+                },
+                ViolationKind::TooLong {
+                    length: limits::CLIENT_BOUND_PAYLOAD_MAX_LENGTH + 1,
+                    max_length: limits::CLIENT_BOUND_PAYLOAD_MAX_LENGTH
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_range_violation() {
+        let mut violations = Vec::new();
+        check_range(
+            &mut violations,
+            "",
+            "primary_mask",
+            -1,
+            Some(0),
+            Some(0xFFFF),
+        );
+        check_range(
+            &mut violations,
+            "",
+            "primary_mask",
+            0xFFFF,
+            Some(0),
+            Some(0xFFFF),
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].kind,
+            ViolationKind::OutOfRange {
+                value: -1,
+                min: Some(0),
+                max: Some(0xFFFF)
+            }
+        );
+    }
+}
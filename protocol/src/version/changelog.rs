@@ -0,0 +1,516 @@
+//! Packet level changes between supported protocol versions.
+//!
+//! Each supported version lists changes of packets modelled by this crate relative to the
+//! previous supported version. Proxies can use it to find packets which need translation and
+//! tools can render support matrices.
+//!
+//! # Example
+//!
+//! ```
+//! use minecraft_protocol::version::changelog::ChangeKind;
+//! use minecraft_protocol::version::ProtocolVersion;
+//!
+//! let moved = ProtocolVersion::V1_16_5
+//!     .changes()
+//!     .iter()
+//!     .filter(|change| matches!(change.kind, ChangeKind::Moved { .. }))
+//!     .count();
+//!
+//! assert_eq!(moved, 3);
+//! ```
+use crate::version::ProtocolVersion;
+use serde::Serialize;
+
+/// Connection state of changed packet.
+#[derive(Serialize, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PacketState {
+    Handshake,
+    Status,
+    Login,
+    Configuration,
+    Game,
+}
+
+/// Direction of changed packet.
+#[derive(Serialize, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Bound {
+    Server,
+    Client,
+}
+
+#[derive(Serialize, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ChangeKind {
+    /// Packet was added.
+    Added { id: u8 },
+    /// Packet was removed or replaced by other packets.
+    Removed { id: u8 },
+    /// Packet layout is the same, but id changed.
+    Moved { old_id: u8, new_id: u8 },
+    /// Packet layout changed, `id` is id in the new version.
+    Changed { id: u8 },
+}
+
+#[derive(Serialize, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PacketChange {
+    pub state: PacketState,
+    pub bound: Bound,
+    /// Name of packet struct, as returned by packet enum `name`.
+    pub name: &'static str,
+    pub kind: ChangeKind,
+}
+
+const fn change(
+    state: PacketState,
+    bound: Bound,
+    name: &'static str,
+    kind: ChangeKind,
+) -> PacketChange {
+    PacketChange {
+        state,
+        bound,
+        name,
+        kind,
+    }
+}
+
+const V1_16_5_CHANGES: &[PacketChange] = &[
+    change(
+        PacketState::Login,
+        Bound::Client,
+        "LoginSuccess",
+        ChangeKind::Changed { id: 0x02 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "ServerBoundKeepAlive",
+        ChangeKind::Moved {
+            old_id: 0x0F,
+            new_id: 0x10,
+        },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "ClientBoundChatMessage",
+        ChangeKind::Changed { id: 0x0E },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "GameDisconnect",
+        ChangeKind::Moved {
+            old_id: 0x1A,
+            new_id: 0x19,
+        },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "ClientBoundKeepAlive",
+        ChangeKind::Moved {
+            old_id: 0x20,
+            new_id: 0x1F,
+        },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "JoinGame",
+        ChangeKind::Changed { id: 0x24 },
+    ),
+];
+
+const V1_19_CHANGES: &[PacketChange] = &[
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "ServerBoundChatMessage",
+        ChangeKind::Removed { id: 0x03 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "ChatCommand",
+        ChangeKind::Added { id: 0x03 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "ChatMessage",
+        ChangeKind::Added { id: 0x04 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "ClientStatus",
+        ChangeKind::Moved {
+            old_id: 0x04,
+            new_id: 0x06,
+        },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "Pong",
+        ChangeKind::Added { id: 0x1F },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "Ping",
+        ChangeKind::Added { id: 0x2D },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "ClientBoundChatMessage",
+        ChangeKind::Removed { id: 0x0E },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "PlayerChatMessage",
+        ChangeKind::Added { id: 0x30 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "SystemChatMessage",
+        ChangeKind::Added { id: 0x5F },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "CombatEvent",
+        ChangeKind::Removed { id: 0x31 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "EndCombatEvent",
+        ChangeKind::Added { id: 0x31 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "EnterCombatEvent",
+        ChangeKind::Added { id: 0x32 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "DeathCombatEvent",
+        ChangeKind::Added { id: 0x33 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "Title",
+        ChangeKind::Removed { id: 0x4F },
+    ),
+];
+
+const V1_20_2_CHANGES: &[PacketChange] = &[
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "ChatCommand",
+        ChangeKind::Changed { id: 0x04 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "ChatMessage",
+        ChangeKind::Changed { id: 0x05 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "PlayerSession",
+        ChangeKind::Added { id: 0x06 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "ChunkBatchReceived",
+        ChangeKind::Added { id: 0x07 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "ChunkBatchFinished",
+        ChangeKind::Added { id: 0x0C },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "ChunkBatchStart",
+        ChangeKind::Added { id: 0x0D },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "ClientStatus",
+        ChangeKind::Moved {
+            old_id: 0x06,
+            new_id: 0x08,
+        },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "Pong",
+        ChangeKind::Moved {
+            old_id: 0x1F,
+            new_id: 0x23,
+        },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "Ping",
+        ChangeKind::Moved {
+            old_id: 0x2D,
+            new_id: 0x33,
+        },
+    ),
+];
+
+const V1_20_5_CHANGES: &[PacketChange] = &[
+    change(
+        PacketState::Login,
+        Bound::Server,
+        "CookieResponse",
+        ChangeKind::Added { id: 0x04 },
+    ),
+    change(
+        PacketState::Login,
+        Bound::Client,
+        "CookieRequest",
+        ChangeKind::Added { id: 0x05 },
+    ),
+    change(
+        PacketState::Configuration,
+        Bound::Server,
+        "CookieResponse",
+        ChangeKind::Added { id: 0x01 },
+    ),
+    change(
+        PacketState::Configuration,
+        Bound::Client,
+        "CookieRequest",
+        ChangeKind::Added { id: 0x00 },
+    ),
+    change(
+        PacketState::Configuration,
+        Bound::Client,
+        "StoreCookie",
+        ChangeKind::Added { id: 0x0A },
+    ),
+    change(
+        PacketState::Configuration,
+        Bound::Client,
+        "Transfer",
+        ChangeKind::Added { id: 0x0B },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "CookieResponse",
+        ChangeKind::Added { id: 0x11 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "CookieRequest",
+        ChangeKind::Added { id: 0x16 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "StoreCookie",
+        ChangeKind::Added { id: 0x6B },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "Transfer",
+        ChangeKind::Added { id: 0x73 },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "ClientStatus",
+        ChangeKind::Moved {
+            old_id: 0x08,
+            new_id: 0x09,
+        },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Server,
+        "Pong",
+        ChangeKind::Moved {
+            old_id: 0x23,
+            new_id: 0x27,
+        },
+    ),
+    change(
+        PacketState::Game,
+        Bound::Client,
+        "Ping",
+        ChangeKind::Moved {
+            old_id: 0x33,
+            new_id: 0x35,
+        },
+    ),
+];
+
+/// Returns packet changes relative to previous supported version, oldest version has none.
+pub fn changes(version: ProtocolVersion) -> &'static [PacketChange] {
+    match version {
+        ProtocolVersion::V1_14_4 => &[],
+        ProtocolVersion::V1_16_5 => V1_16_5_CHANGES,
+        ProtocolVersion::V1_19 => V1_19_CHANGES,
+        ProtocolVersion::V1_20_2 => V1_20_2_CHANGES,
+        ProtocolVersion::V1_20_5 => V1_20_5_CHANGES,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::changelog::*;
+
+    /// Returns ids of packets which exist in version after the change.
+    #[cfg(any(
+        feature = "v1_16_5",
+        feature = "v1_19",
+        feature = "v1_20_2",
+        feature = "v1_20_5"
+    ))]
+    fn current_ids(version: ProtocolVersion, state: PacketState, bound: Bound) -> Vec<u8> {
+        changes(version)
+            .iter()
+            .filter(|change| change.state == state && change.bound == bound)
+            .filter_map(|change| match change.kind {
+                ChangeKind::Added { id } | ChangeKind::Changed { id } => Some(id),
+                ChangeKind::Moved { new_id, .. } => Some(new_id),
+                ChangeKind::Removed { .. } => None,
+            })
+            .collect()
+    }
+
+    #[cfg(any(
+        feature = "v1_16_5",
+        feature = "v1_19",
+        feature = "v1_20_2",
+        feature = "v1_20_5"
+    ))]
+    fn assert_contains<I: Iterator<Item = u8>>(ids: I, expected: Vec<u8>) {
+        let ids: Vec<u8> = ids.collect();
+
+        for id in expected {
+            assert!(ids.contains(&id), "Packet id {:#04X} is missing", id);
+        }
+    }
+
+    #[test]
+    fn test_changes_are_unique() {
+        for version in ProtocolVersion::ALL {
+            let changes = changes(version);
+
+            for (index, change) in changes.iter().enumerate() {
+                assert!(
+                    !changes[index + 1..]
+                        .iter()
+                        .any(|other| other.state == change.state
+                            && other.bound == change.bound
+                            && other.name == change.name),
+                    "Packet {} changed twice in {}",
+                    change.name,
+                    version.name()
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "v1_16_5")]
+    #[test]
+    fn test_v1_16_5_ids() {
+        use crate::version::v1_16_5::game::{GameClientBoundPacket, GameServerBoundPacket};
+        use crate::version::v1_16_5::login::LoginClientBoundPacket;
+
+        let version = ProtocolVersion::V1_16_5;
+
+        assert_contains(
+            LoginClientBoundPacket::iter_ids(),
+            current_ids(version, PacketState::Login, Bound::Client),
+        );
+        assert_contains(
+            GameServerBoundPacket::iter_ids(),
+            current_ids(version, PacketState::Game, Bound::Server),
+        );
+        assert_contains(
+            GameClientBoundPacket::iter_ids(),
+            current_ids(version, PacketState::Game, Bound::Client),
+        );
+    }
+
+    #[cfg(feature = "v1_19")]
+    #[test]
+    fn test_v1_19_ids() {
+        use crate::version::v1_19::game::{GameClientBoundPacket, GameServerBoundPacket};
+
+        assert_contains(
+            GameServerBoundPacket::iter_ids(),
+            current_ids(ProtocolVersion::V1_19, PacketState::Game, Bound::Server),
+        );
+        assert_contains(
+            GameClientBoundPacket::iter_ids(),
+            current_ids(ProtocolVersion::V1_19, PacketState::Game, Bound::Client),
+        );
+    }
+
+    #[cfg(feature = "v1_20_2")]
+    #[test]
+    fn test_v1_20_2_ids() {
+        use crate::version::v1_20_2::game::{GameClientBoundPacket, GameServerBoundPacket};
+
+        let version = ProtocolVersion::V1_20_2;
+
+        assert_contains(
+            GameServerBoundPacket::iter_ids(),
+            current_ids(version, PacketState::Game, Bound::Server),
+        );
+        assert_contains(
+            GameClientBoundPacket::iter_ids(),
+            current_ids(version, PacketState::Game, Bound::Client),
+        );
+    }
+
+    #[cfg(feature = "v1_20_5")]
+    #[test]
+    fn test_v1_20_5_ids() {
+        use crate::version::v1_20_5::{configuration, game, login};
+
+        let version = ProtocolVersion::V1_20_5;
+
+        assert_contains(
+            login::LoginClientBoundPacket::iter_ids(),
+            current_ids(version, PacketState::Login, Bound::Client),
+        );
+        assert_contains(
+            configuration::ConfigurationClientBoundPacket::iter_ids(),
+            current_ids(version, PacketState::Configuration, Bound::Client),
+        );
+        assert_contains(
+            game::GameClientBoundPacket::iter_ids(),
+            current_ids(version, PacketState::Game, Bound::Client),
+        );
+        assert_contains(
+            game::GameServerBoundPacket::iter_ids(),
+            current_ids(version, PacketState::Game, Bound::Server),
+        );
+    }
+}
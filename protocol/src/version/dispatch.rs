@@ -0,0 +1,256 @@
+//! Game packets of protocol version negotiated at runtime.
+//!
+//! Client sends its protocol number in `Handshake`, handshake, status and login packets of all
+//! supported versions are decoded the same way. Game packets differ, so proxies serving several
+//! versions decode them into `VersionedGameServerBoundPacket` and `VersionedGameClientBoundPacket`,
+//! which hold packet enum of the negotiated version. Versions whose cargo feature is disabled are
+//! reported as `DecodeError::UnsupportedVersion`.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "v1_19")]
+//! # {
+//! use minecraft_protocol::packet::RawPacket;
+//! use minecraft_protocol::version::dispatch::VersionedGameServerBoundPacket;
+//! use minecraft_protocol::version::{v1_19, ProtocolVersion};
+//!
+//! let raw_packet = RawPacket::from_packet(&v1_19::game::Pong::new(7)).unwrap();
+//!
+//! let version = ProtocolVersion::from_number(759).unwrap();
+//! let packet = VersionedGameServerBoundPacket::from_raw_packet(version, &raw_packet).unwrap();
+//!
+//! match packet {
+//!     VersionedGameServerBoundPacket::V1_19(v1_19::game::GameServerBoundPacket::Pong(pong)) => {
+//!         assert_eq!(pong.id, 7)
+//!     }
+//!     packet => panic!("Unexpected packet {}", packet.name()),
+//! }
+//! # }
+//! ```
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::RawPacket;
+use crate::version::ProtocolVersion;
+use std::io::{Read, Write};
+
+#[cfg(feature = "v1_14_4")]
+use crate::version::v1_14_4;
+#[cfg(feature = "v1_16_5")]
+use crate::version::v1_16_5;
+#[cfg(feature = "v1_19")]
+use crate::version::v1_19;
+#[cfg(feature = "v1_20_2")]
+use crate::version::v1_20_2;
+#[cfg(feature = "v1_20_5")]
+use crate::version::v1_20_5;
+
+/// Declares enum holding packet enum `$packet` of game module of every enabled version.
+macro_rules! versioned_packet (
+    ($(#[$meta: meta])* $name: ident, $packet: ident) => (
+        $(#[$meta])*
+        #[derive(Debug)]
+        #[non_exhaustive]
+        pub enum $name {
+            #[cfg(feature = "v1_14_4")]
+            V1_14_4(v1_14_4::game::$packet),
+            #[cfg(feature = "v1_16_5")]
+            V1_16_5(v1_16_5::game::$packet),
+            #[cfg(feature = "v1_19")]
+            V1_19(v1_19::game::$packet),
+            #[cfg(feature = "v1_20_2")]
+            V1_20_2(v1_20_2::game::$packet),
+            #[cfg(feature = "v1_20_5")]
+            V1_20_5(v1_20_5::game::$packet),
+        }
+
+        impl $name {
+            pub fn version(&self) -> ProtocolVersion {
+                match self {
+                    #[cfg(feature = "v1_14_4")]
+                    $name::V1_14_4(_) => ProtocolVersion::V1_14_4,
+                    #[cfg(feature = "v1_16_5")]
+                    $name::V1_16_5(_) => ProtocolVersion::V1_16_5,
+                    #[cfg(feature = "v1_19")]
+                    $name::V1_19(_) => ProtocolVersion::V1_19,
+                    #[cfg(feature = "v1_20_2")]
+                    $name::V1_20_2(_) => ProtocolVersion::V1_20_2,
+                    #[cfg(feature = "v1_20_5")]
+                    $name::V1_20_5(_) => ProtocolVersion::V1_20_5,
+                }
+            }
+
+            pub fn get_type_id(&self) -> u8 {
+                match self {
+                    #[cfg(feature = "v1_14_4")]
+                    $name::V1_14_4(packet) => packet.get_type_id(),
+                    #[cfg(feature = "v1_16_5")]
+                    $name::V1_16_5(packet) => packet.get_type_id(),
+                    #[cfg(feature = "v1_19")]
+                    $name::V1_19(packet) => packet.get_type_id(),
+                    #[cfg(feature = "v1_20_2")]
+                    $name::V1_20_2(packet) => packet.get_type_id(),
+                    #[cfg(feature = "v1_20_5")]
+                    $name::V1_20_5(packet) => packet.get_type_id(),
+                }
+            }
+
+            /// Returns name of packet, which is the same in every version it exists in.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #[cfg(feature = "v1_14_4")]
+                    $name::V1_14_4(packet) => packet.name(),
+                    #[cfg(feature = "v1_16_5")]
+                    $name::V1_16_5(packet) => packet.name(),
+                    #[cfg(feature = "v1_19")]
+                    $name::V1_19(packet) => packet.name(),
+                    #[cfg(feature = "v1_20_2")]
+                    $name::V1_20_2(packet) => packet.name(),
+                    #[cfg(feature = "v1_20_5")]
+                    $name::V1_20_5(packet) => packet.name(),
+                }
+            }
+
+            /// Decodes packet data with packets of version.
+            pub fn decode<R: Read>(
+                version: ProtocolVersion,
+                type_id: u8,
+                reader: &mut R,
+            ) -> Result<Self, DecodeError> {
+                match version {
+                    #[cfg(feature = "v1_14_4")]
+                    ProtocolVersion::V1_14_4 => {
+                        v1_14_4::game::$packet::decode(type_id, reader).map($name::V1_14_4)
+                    }
+                    #[cfg(feature = "v1_16_5")]
+                    ProtocolVersion::V1_16_5 => {
+                        v1_16_5::game::$packet::decode(type_id, reader).map($name::V1_16_5)
+                    }
+                    #[cfg(feature = "v1_19")]
+                    ProtocolVersion::V1_19 => {
+                        v1_19::game::$packet::decode(type_id, reader).map($name::V1_19)
+                    }
+                    #[cfg(feature = "v1_20_2")]
+                    ProtocolVersion::V1_20_2 => {
+                        v1_20_2::game::$packet::decode(type_id, reader).map($name::V1_20_2)
+                    }
+                    #[cfg(feature = "v1_20_5")]
+                    ProtocolVersion::V1_20_5 => {
+                        v1_20_5::game::$packet::decode(type_id, reader).map($name::V1_20_5)
+                    }
+                    #[allow(unreachable_patterns)]
+                    _ => Err(DecodeError::UnsupportedVersion {
+                        protocol_version: version.number(),
+                    }),
+                }
+            }
+
+            /// Decodes raw packet with packets of version.
+            pub fn from_raw_packet(
+                version: ProtocolVersion,
+                raw_packet: &RawPacket,
+            ) -> Result<Self, DecodeError> {
+                match version {
+                    #[cfg(feature = "v1_14_4")]
+                    ProtocolVersion::V1_14_4 => raw_packet.decode_packet().map($name::V1_14_4),
+                    #[cfg(feature = "v1_16_5")]
+                    ProtocolVersion::V1_16_5 => raw_packet.decode_packet().map($name::V1_16_5),
+                    #[cfg(feature = "v1_19")]
+                    ProtocolVersion::V1_19 => raw_packet.decode_packet().map($name::V1_19),
+                    #[cfg(feature = "v1_20_2")]
+                    ProtocolVersion::V1_20_2 => raw_packet.decode_packet().map($name::V1_20_2),
+                    #[cfg(feature = "v1_20_5")]
+                    ProtocolVersion::V1_20_5 => raw_packet.decode_packet().map($name::V1_20_5),
+                    #[allow(unreachable_patterns)]
+                    _ => Err(DecodeError::UnsupportedVersion {
+                        protocol_version: version.number(),
+                    }),
+                }
+            }
+
+            /// Encodes packet data without packet id.
+            pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+                match self {
+                    #[cfg(feature = "v1_14_4")]
+                    $name::V1_14_4(packet) => packet.encode(writer),
+                    #[cfg(feature = "v1_16_5")]
+                    $name::V1_16_5(packet) => packet.encode(writer),
+                    #[cfg(feature = "v1_19")]
+                    $name::V1_19(packet) => packet.encode(writer),
+                    #[cfg(feature = "v1_20_2")]
+                    $name::V1_20_2(packet) => packet.encode(writer),
+                    #[cfg(feature = "v1_20_5")]
+                    $name::V1_20_5(packet) => packet.encode(writer),
+                }
+            }
+
+            pub fn to_raw_packet(&self) -> Result<RawPacket, EncodeError> {
+                let mut data = Vec::new();
+                self.encode(&mut data)?;
+
+                Ok(RawPacket::new(self.get_type_id() as i32, data))
+            }
+        }
+    );
+);
+
+versioned_packet!(
+    /// Game packet sent by client in any enabled version.
+    VersionedGameServerBoundPacket,
+    GameServerBoundPacket
+);
+
+versioned_packet!(
+    /// Game packet sent by server in any enabled version.
+    VersionedGameClientBoundPacket,
+    GameClientBoundPacket
+);
+
+#[cfg(all(test, feature = "v1_14_4", feature = "v1_20_2"))]
+mod tests {
+    use crate::version::dispatch::*;
+
+    #[test]
+    fn test_same_id_decoded_by_version() {
+        // Client status is 0x04 in 1.14.4, but 0x04 is chat command in 1.20.2.
+        let raw_packet = RawPacket::from_packet(&v1_14_4::game::ClientStatus::new(
+            v1_14_4::game::ClientStatusAction::RequestStats,
+        ))
+        .unwrap();
+
+        let packet =
+            VersionedGameServerBoundPacket::from_raw_packet(ProtocolVersion::V1_14_4, &raw_packet)
+                .unwrap();
+
+        assert_eq!(packet.version(), ProtocolVersion::V1_14_4);
+        assert_eq!(packet.name(), "ClientStatus");
+        assert_eq!(packet.to_raw_packet().unwrap(), raw_packet);
+
+        let raw_packet = RawPacket::from_packet(&v1_20_2::game::ClientStatus::new(
+            v1_20_2::game::ClientStatusAction::RequestStats,
+        ))
+        .unwrap();
+
+        let packet =
+            VersionedGameServerBoundPacket::from_raw_packet(ProtocolVersion::V1_20_2, &raw_packet)
+                .unwrap();
+
+        assert_eq!(packet.version(), ProtocolVersion::V1_20_2);
+        assert_eq!(packet.get_type_id(), 0x08);
+        assert_eq!(packet.name(), "ClientStatus");
+    }
+
+    #[test]
+    fn test_unknown_id_in_version() {
+        let decode_error = VersionedGameClientBoundPacket::decode(
+            ProtocolVersion::V1_20_2,
+            0x7F,
+            &mut [].as_slice(),
+        )
+        .expect_err("Expected error `UnknownPacketType` because 1.20.2 has no packet 0x7F");
+
+        match decode_error {
+            DecodeError::UnknownPacketType { type_id } => assert_eq!(type_id, 0x7F),
+            _ => panic!("Expected `UnknownPacketType` but got `{:?}`", decode_error),
+        }
+    }
+}
@@ -1 +1,121 @@
+//! Packets of supported protocol versions.
+//!
+//! Each version is compiled only when its cargo feature is enabled. All versions are enabled by
+//! default with `all-versions` feature. Packets of 1.8 and 1.12.2 needed by tools targeting old
+//! servers are enabled with `legacy` feature. 1.16.5 reuses handshake and status packets of
+//! 1.14.4, so `v1_16_5` enables `v1_14_4`.
+//!
+//! `ProtocolVersion` is the version negotiated at runtime, `dispatch` decodes game packets of it.
+pub mod changelog;
+#[cfg(any(
+    feature = "v1_14_4",
+    feature = "v1_16_5",
+    feature = "v1_19",
+    feature = "v1_20_2",
+    feature = "v1_20_5"
+))]
+pub mod dispatch;
+#[cfg(feature = "legacy")]
+pub mod v1_12_2;
+#[cfg(feature = "v1_14_4")]
 pub mod v1_14_4;
+#[cfg(feature = "v1_16_5")]
+pub mod v1_16_5;
+#[cfg(feature = "v1_19")]
+pub mod v1_19;
+#[cfg(feature = "v1_20_2")]
+pub mod v1_20_2;
+#[cfg(feature = "v1_20_5")]
+pub mod v1_20_5;
+#[cfg(feature = "legacy")]
+pub mod v1_8;
+
+/// Supported protocol version, ordered from oldest.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ProtocolVersion {
+    V1_14_4,
+    V1_16_5,
+    V1_19,
+    V1_20_2,
+    V1_20_5,
+}
+
+impl ProtocolVersion {
+    /// All supported versions from oldest.
+    pub const ALL: [ProtocolVersion; 5] = [
+        ProtocolVersion::V1_14_4,
+        ProtocolVersion::V1_16_5,
+        ProtocolVersion::V1_19,
+        ProtocolVersion::V1_20_2,
+        ProtocolVersion::V1_20_5,
+    ];
+
+    /// Returns protocol number sent in handshake.
+    pub fn number(self) -> i32 {
+        match self {
+            ProtocolVersion::V1_14_4 => 498,
+            ProtocolVersion::V1_16_5 => 754,
+            ProtocolVersion::V1_19 => 759,
+            ProtocolVersion::V1_20_2 => 764,
+            ProtocolVersion::V1_20_5 => 766,
+        }
+    }
+
+    pub fn from_number(number: i32) -> Option<Self> {
+        ProtocolVersion::ALL
+            .iter()
+            .copied()
+            .find(|version| version.number() == number)
+    }
+
+    /// Returns game version name, e.g. `1.14.4`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ProtocolVersion::V1_14_4 => "1.14.4",
+            ProtocolVersion::V1_16_5 => "1.16.5",
+            ProtocolVersion::V1_19 => "1.19",
+            ProtocolVersion::V1_20_2 => "1.20.2",
+            ProtocolVersion::V1_20_5 => "1.20.5",
+        }
+    }
+
+    /// Returns previous supported version, `None` for the oldest one.
+    pub fn previous(self) -> Option<Self> {
+        let index = ProtocolVersion::ALL.iter().position(|v| *v == self)?;
+
+        index
+            .checked_sub(1)
+            .map(|index| ProtocolVersion::ALL[index])
+    }
+
+    /// Returns packet changes relative to previous supported version.
+    pub fn changes(self) -> &'static [changelog::PacketChange] {
+        changelog::changes(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::*;
+
+    #[test]
+    fn test_protocol_version_number() {
+        for version in ProtocolVersion::ALL {
+            assert_eq!(
+                ProtocolVersion::from_number(version.number()),
+                Some(version)
+            );
+        }
+
+        assert_eq!(ProtocolVersion::from_number(47), None);
+    }
+
+    #[test]
+    fn test_protocol_version_previous() {
+        assert_eq!(ProtocolVersion::V1_14_4.previous(), None);
+        assert_eq!(
+            ProtocolVersion::V1_19.previous(),
+            Some(ProtocolVersion::V1_16_5)
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Game packets of 1.12.2 which differ in earlier versions.
+//!
+//! Keep-alive id is long since 1.12.2, earlier versions send var int.
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameServerBoundPacket {
+    ServerBoundKeepAlive(ServerBoundKeepAlive),
+    ServerBoundConfirmTransaction(ServerBoundConfirmTransaction),
+}
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameClientBoundPacket {
+    ClientBoundKeepAlive(ClientBoundKeepAlive),
+    ClientBoundConfirmTransaction(ClientBoundConfirmTransaction),
+}
+
+impl GameServerBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameServerBoundPacket::ServerBoundConfirmTransaction(_) => 0x05,
+            GameServerBoundPacket::ServerBoundKeepAlive(_) => 0x0B,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameServerBoundPacket::ServerBoundKeepAlive(_) => "ServerBoundKeepAlive",
+            GameServerBoundPacket::ServerBoundConfirmTransaction(_) => {
+                "ServerBoundConfirmTransaction"
+            }
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x05, 0x0B].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x05 => {
+                let confirm_transaction = ServerBoundConfirmTransaction::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ServerBoundConfirmTransaction(
+                    confirm_transaction,
+                ))
+            }
+            0x0B => {
+                let keep_alive = ServerBoundKeepAlive::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ServerBoundKeepAlive(keep_alive))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => keep_alive.encode(writer),
+            GameServerBoundPacket::ServerBoundConfirmTransaction(confirm_transaction) => {
+                confirm_transaction.encode(writer)
+            }
+        }
+    }
+}
+
+impl_packet!(GameServerBoundPacket);
+
+impl GameClientBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameClientBoundPacket::ClientBoundConfirmTransaction(_) => 0x11,
+            GameClientBoundPacket::ClientBoundKeepAlive(_) => 0x1F,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameClientBoundPacket::ClientBoundKeepAlive(_) => "ClientBoundKeepAlive",
+            GameClientBoundPacket::ClientBoundConfirmTransaction(_) => {
+                "ClientBoundConfirmTransaction"
+            }
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x11, 0x1F].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x11 => {
+                let confirm_transaction = ClientBoundConfirmTransaction::decode(reader)?;
+
+                Ok(GameClientBoundPacket::ClientBoundConfirmTransaction(
+                    confirm_transaction,
+                ))
+            }
+            0x1F => {
+                let keep_alive = ClientBoundKeepAlive::decode(reader)?;
+
+                Ok(GameClientBoundPacket::ClientBoundKeepAlive(keep_alive))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameClientBoundPacket::ClientBoundKeepAlive(keep_alive) => keep_alive.encode(writer),
+            GameClientBoundPacket::ClientBoundConfirmTransaction(confirm_transaction) => {
+                confirm_transaction.encode(writer)
+            }
+        }
+    }
+}
+
+impl_packet!(GameClientBoundPacket);
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ServerBoundKeepAlive {
+    pub id: u64,
+}
+
+impl ServerBoundKeepAlive {
+    pub fn new(id: u64) -> GameServerBoundPacket {
+        let keep_alive = ServerBoundKeepAlive { id };
+
+        GameServerBoundPacket::ServerBoundKeepAlive(keep_alive)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientBoundKeepAlive {
+    pub id: u64,
+}
+
+impl ClientBoundKeepAlive {
+    pub fn new(id: u64) -> GameClientBoundPacket {
+        let keep_alive = ClientBoundKeepAlive { id };
+
+        GameClientBoundPacket::ClientBoundKeepAlive(keep_alive)
+    }
+
+    /// Returns keep-alive which client sends back.
+    pub fn answer(&self) -> GameServerBoundPacket {
+        ServerBoundKeepAlive::new(self.id)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ServerBoundConfirmTransaction {
+    pub window_id: i8,
+    pub action_number: i16,
+    pub accepted: bool,
+}
+
+impl ServerBoundConfirmTransaction {
+    pub fn new(window_id: i8, action_number: i16, accepted: bool) -> GameServerBoundPacket {
+        let confirm_transaction = ServerBoundConfirmTransaction {
+            window_id,
+            action_number,
+            accepted,
+        };
+
+        GameServerBoundPacket::ServerBoundConfirmTransaction(confirm_transaction)
+    }
+}
+
+/// Confirms or rejects window click.
+///
+/// Client answers rejected transactions, so servers also send them to window 0 to measure
+/// latency.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientBoundConfirmTransaction {
+    pub window_id: i8,
+    pub action_number: i16,
+    pub accepted: bool,
+}
+
+impl ClientBoundConfirmTransaction {
+    pub fn new(window_id: i8, action_number: i16, accepted: bool) -> GameClientBoundPacket {
+        let confirm_transaction = ClientBoundConfirmTransaction {
+            window_id,
+            action_number,
+            accepted,
+        };
+
+        GameClientBoundPacket::ClientBoundConfirmTransaction(confirm_transaction)
+    }
+
+    /// Returns confirmation which vanilla client sends back, `None` for accepted transactions.
+    pub fn answer(&self) -> Option<GameServerBoundPacket> {
+        (!self.accepted)
+            .then(|| ServerBoundConfirmTransaction::new(self.window_id, self.action_number, true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::v1_12_2::game::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_keep_alive_long_id() {
+        let keep_alive = ClientBoundKeepAlive { id: 300 };
+
+        let mut vec = Vec::new();
+        keep_alive.encode(&mut vec).unwrap();
+        assert_eq!(vec, [0, 0, 0, 0, 0, 0, 0x01, 0x2C]);
+
+        let mut vec = Vec::new();
+        keep_alive.answer().encode(&mut vec).unwrap();
+
+        match GameServerBoundPacket::decode(0x0B, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => {
+                assert_eq!(keep_alive.id, 300)
+            }
+            packet => panic!("Expected `ServerBoundKeepAlive` but got `{:?}`", packet),
+        }
+    }
+}
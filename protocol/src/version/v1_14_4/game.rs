@@ -1,39 +1,117 @@
+use crate::data::block_entity::BlockEntities;
 use crate::data::chat::Message;
+#[cfg(feature = "serde")]
+use crate::data::compound_tag;
+use crate::data::compound_tag::CompoundTagCache;
+use crate::data::entity_metadata::Metadata;
+use crate::data::game::sign_block_entity;
+use crate::data::identifier::Identifier;
+use crate::data::units::{Ticks, Velocity};
 use crate::decoder::Decoder;
-use crate::decoder::DecoderReadExt;
-use crate::encoder::EncoderWriteExt;
-use crate::error::DecodeError;
-use byteorder::{ReadBytesExt, WriteBytesExt};
-use minecraft_protocol_derive::{Decoder, Encoder};
+use crate::encoder::{Encoder, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use crate::limits;
+use crate::packet::impl_packet;
+use crate::validate::{self, Validate, Violation, ViolationKind};
+use minecraft_protocol_derive::{
+    Builder, Decoder, Encoder, PacketVariants, Validate, VisitMessages,
+};
 use nbt::CompoundTag;
-use std::io::Read;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::sync::Arc;
 use uuid::Uuid;
 
+pub use crate::data::game::{
+    BlockPosition, BossBarFlags, ChunkPosition, ClientStatusAction, InteractionHand,
+    MessagePosition, Slot, TitleAction,
+};
+
+#[derive(VisitMessages, PacketVariants, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum GameServerBoundPacket {
     ServerBoundChatMessage(ServerBoundChatMessage),
+    ClientStatus(ClientStatus),
+    ServerBoundPluginMessage(ServerBoundPluginMessage),
     ServerBoundKeepAlive(ServerBoundKeepAlive),
     ServerBoundAbilities(ServerBoundAbilities),
+    EntityAction(EntityAction),
+    UpdateSign(UpdateSign),
+    HandSwing(HandSwing),
+    UseItem(UseItem),
+    ServerBoundTabComplete(ServerBoundTabComplete),
 }
 
+#[derive(VisitMessages, PacketVariants, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum GameClientBoundPacket {
-    ClientBoundChatMessage(ClientBoundChatMessage),
+    EntityAnimation(EntityAnimation),
+    BlockEntityData(BlockEntityData),
+    ClientBoundChatMessage(#[visit] ClientBoundChatMessage),
     JoinGame(JoinGame),
     ClientBoundKeepAlive(ClientBoundKeepAlive),
     ChunkData(ChunkData),
-    GameDisconnect(GameDisconnect),
-    BossBar(BossBar),
-    EntityAction(EntityAction),
+    GameDisconnect(#[visit] GameDisconnect),
+    ClientBoundPluginMessage(ClientBoundPluginMessage),
+    BossBar(#[visit] BossBar),
+    Title(#[visit] Title),
+    EntityMetadata(#[visit] EntityMetadata),
+    EntityVelocity(EntityVelocity),
+    CombatEvent(#[visit] CombatEvent),
+    OpenHorseWindow(OpenHorseWindow),
+    OpenBook(OpenBook),
+    OpenSignEditor(OpenSignEditor),
+    SelectAdvancementsTab(SelectAdvancementsTab),
+    ClientBoundTabComplete(#[visit] ClientBoundTabComplete),
+    UnloadChunk(UnloadChunk),
+    UpdateViewPosition(UpdateViewPosition),
+    WindowItems(WindowItems),
+    SetSlot(SetSlot),
+    SetCooldown(SetCooldown),
+    TimeUpdate(TimeUpdate),
 }
 
 impl GameServerBoundPacket {
     pub fn get_type_id(&self) -> u8 {
         match self {
             GameServerBoundPacket::ServerBoundChatMessage(_) => 0x03,
+            GameServerBoundPacket::ClientStatus(_) => 0x04,
+            GameServerBoundPacket::ServerBoundPluginMessage(_) => 0x0B,
             GameServerBoundPacket::ServerBoundKeepAlive(_) => 0x0F,
             GameServerBoundPacket::ServerBoundAbilities(_) => 0x19,
+            GameServerBoundPacket::EntityAction(_) => 0x1B,
+            GameServerBoundPacket::HandSwing(_) => 0x2A,
+            GameServerBoundPacket::UpdateSign(_) => 0x29,
+            GameServerBoundPacket::UseItem(_) => 0x2D,
+            GameServerBoundPacket::ServerBoundTabComplete(_) => 0x06,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameServerBoundPacket::ServerBoundChatMessage(_) => "ServerBoundChatMessage",
+            GameServerBoundPacket::ClientStatus(_) => "ClientStatus",
+            GameServerBoundPacket::ServerBoundPluginMessage(_) => "ServerBoundPluginMessage",
+            GameServerBoundPacket::ServerBoundKeepAlive(_) => "ServerBoundKeepAlive",
+            GameServerBoundPacket::ServerBoundAbilities(_) => "ServerBoundAbilities",
+            GameServerBoundPacket::EntityAction(_) => "EntityAction",
+            GameServerBoundPacket::HandSwing(_) => "HandSwing",
+            GameServerBoundPacket::UpdateSign(_) => "UpdateSign",
+            GameServerBoundPacket::UseItem(_) => "UseItem",
+            GameServerBoundPacket::ServerBoundTabComplete(_) => "ServerBoundTabComplete",
         }
     }
 
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x03, 0x04, 0x06, 0x0B, 0x0F, 0x19, 0x1B, 0x29, 0x2A, 0x2D]
+            .iter()
+            .copied()
+    }
+
     pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
         match type_id {
             0x03 => {
@@ -41,41 +119,214 @@ impl GameServerBoundPacket {
 
                 Ok(GameServerBoundPacket::ServerBoundChatMessage(chat_message))
             }
+            0x04 => {
+                let client_status = ClientStatus::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ClientStatus(client_status))
+            }
+            0x06 => {
+                let tab_complete = ServerBoundTabComplete::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ServerBoundTabComplete(tab_complete))
+            }
+            0x0B => {
+                let plugin_message = ServerBoundPluginMessage::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ServerBoundPluginMessage(
+                    plugin_message,
+                ))
+            }
             0x0F => {
                 let keep_alive = ServerBoundKeepAlive::decode(reader)?;
 
                 Ok(GameServerBoundPacket::ServerBoundKeepAlive(keep_alive))
             }
+            0x19 => {
+                let abilities = ServerBoundAbilities::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ServerBoundAbilities(abilities))
+            }
+            0x1B => {
+                let entity_action = EntityAction::decode(reader)?;
+
+                Ok(GameServerBoundPacket::EntityAction(entity_action))
+            }
+            0x29 => {
+                let update_sign = UpdateSign::decode(reader)?;
+
+                Ok(GameServerBoundPacket::UpdateSign(update_sign))
+            }
+            0x2A => {
+                let hand_swing = HandSwing::decode(reader)?;
+
+                Ok(GameServerBoundPacket::HandSwing(hand_swing))
+            }
+            0x2D => {
+                let use_item = UseItem::decode(reader)?;
+
+                Ok(GameServerBoundPacket::UseItem(use_item))
+            }
             _ => Err(DecodeError::UnknownPacketType { type_id }),
         }
     }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameServerBoundPacket::ServerBoundChatMessage(chat_message) => {
+                chat_message.encode(writer)
+            }
+            GameServerBoundPacket::ClientStatus(client_status) => client_status.encode(writer),
+            GameServerBoundPacket::ServerBoundPluginMessage(plugin_message) => {
+                plugin_message.encode(writer)
+            }
+            GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => keep_alive.encode(writer),
+            GameServerBoundPacket::ServerBoundAbilities(abilities) => abilities.encode(writer),
+            GameServerBoundPacket::EntityAction(entity_action) => entity_action.encode(writer),
+            GameServerBoundPacket::HandSwing(hand_swing) => hand_swing.encode(writer),
+            GameServerBoundPacket::UpdateSign(update_sign) => update_sign.encode(writer),
+            GameServerBoundPacket::UseItem(use_item) => use_item.encode(writer),
+            GameServerBoundPacket::ServerBoundTabComplete(tab_complete) => {
+                tab_complete.encode(writer)
+            }
+        }
+    }
 }
 
+impl_packet!(GameServerBoundPacket);
+
 impl GameClientBoundPacket {
     pub fn get_type_id(&self) -> u8 {
         match self {
+            GameClientBoundPacket::EntityAnimation(_) => 0x06,
+            GameClientBoundPacket::BlockEntityData(_) => 0x09,
             GameClientBoundPacket::ClientBoundChatMessage(_) => 0x0E,
+            GameClientBoundPacket::ClientBoundPluginMessage(_) => 0x18,
             GameClientBoundPacket::GameDisconnect(_) => 0x1A,
             GameClientBoundPacket::ClientBoundKeepAlive(_) => 0x20,
             GameClientBoundPacket::ChunkData(_) => 0x21,
             GameClientBoundPacket::JoinGame(_) => 0x25,
-            GameClientBoundPacket::BossBar(_) => 0x0D,
-            GameClientBoundPacket::EntityAction(_) => 0x1B,
+            GameClientBoundPacket::BossBar(_) => 0x0C,
+            GameClientBoundPacket::OpenHorseWindow(_) => 0x1F,
+            GameClientBoundPacket::OpenBook(_) => 0x2D,
+            GameClientBoundPacket::OpenSignEditor(_) => 0x2F,
+            GameClientBoundPacket::CombatEvent(_) => 0x32,
+            GameClientBoundPacket::SelectAdvancementsTab(_) => 0x3C,
+            GameClientBoundPacket::EntityMetadata(_) => 0x43,
+            GameClientBoundPacket::EntityVelocity(_) => 0x45,
+            GameClientBoundPacket::Title(_) => 0x4F,
+            GameClientBoundPacket::ClientBoundTabComplete(_) => 0x10,
+            GameClientBoundPacket::UnloadChunk(_) => 0x1D,
+            GameClientBoundPacket::UpdateViewPosition(_) => 0x40,
+            GameClientBoundPacket::WindowItems(_) => 0x14,
+            GameClientBoundPacket::SetSlot(_) => 0x16,
+            GameClientBoundPacket::SetCooldown(_) => 0x17,
+            GameClientBoundPacket::TimeUpdate(_) => 0x4E,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameClientBoundPacket::EntityAnimation(_) => "EntityAnimation",
+            GameClientBoundPacket::BlockEntityData(_) => "BlockEntityData",
+            GameClientBoundPacket::ClientBoundChatMessage(_) => "ClientBoundChatMessage",
+            GameClientBoundPacket::ClientBoundPluginMessage(_) => "ClientBoundPluginMessage",
+            GameClientBoundPacket::GameDisconnect(_) => "GameDisconnect",
+            GameClientBoundPacket::ClientBoundKeepAlive(_) => "ClientBoundKeepAlive",
+            GameClientBoundPacket::ChunkData(_) => "ChunkData",
+            GameClientBoundPacket::JoinGame(_) => "JoinGame",
+            GameClientBoundPacket::BossBar(_) => "BossBar",
+            GameClientBoundPacket::OpenHorseWindow(_) => "OpenHorseWindow",
+            GameClientBoundPacket::OpenBook(_) => "OpenBook",
+            GameClientBoundPacket::OpenSignEditor(_) => "OpenSignEditor",
+            GameClientBoundPacket::CombatEvent(_) => "CombatEvent",
+            GameClientBoundPacket::SelectAdvancementsTab(_) => "SelectAdvancementsTab",
+            GameClientBoundPacket::EntityMetadata(_) => "EntityMetadata",
+            GameClientBoundPacket::EntityVelocity(_) => "EntityVelocity",
+            GameClientBoundPacket::Title(_) => "Title",
+            GameClientBoundPacket::ClientBoundTabComplete(_) => "ClientBoundTabComplete",
+            GameClientBoundPacket::UnloadChunk(_) => "UnloadChunk",
+            GameClientBoundPacket::UpdateViewPosition(_) => "UpdateViewPosition",
+            GameClientBoundPacket::WindowItems(_) => "WindowItems",
+            GameClientBoundPacket::SetSlot(_) => "SetSlot",
+            GameClientBoundPacket::SetCooldown(_) => "SetCooldown",
+            GameClientBoundPacket::TimeUpdate(_) => "TimeUpdate",
         }
     }
 
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [
+            0x06, 0x09, 0x0C, 0x0E, 0x10, 0x14, 0x16, 0x17, 0x18, 0x1A, 0x1D, 0x1F, 0x20, 0x21,
+            0x25, 0x2D, 0x2F, 0x32, 0x3C, 0x40, 0x43, 0x45, 0x4E, 0x4F,
+        ]
+        .iter()
+        .copied()
+    }
+
     pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
         match type_id {
+            0x06 => {
+                let entity_animation = EntityAnimation::decode(reader)?;
+
+                Ok(GameClientBoundPacket::EntityAnimation(entity_animation))
+            }
+            0x09 => {
+                let block_entity_data = BlockEntityData::decode(reader)?;
+
+                Ok(GameClientBoundPacket::BlockEntityData(block_entity_data))
+            }
+            0x0C => {
+                let boss_bar = BossBar::decode(reader)?;
+
+                Ok(GameClientBoundPacket::BossBar(boss_bar))
+            }
             0x0E => {
                 let chat_message = ClientBoundChatMessage::decode(reader)?;
 
                 Ok(GameClientBoundPacket::ClientBoundChatMessage(chat_message))
             }
+            0x10 => {
+                let tab_complete = ClientBoundTabComplete::decode(reader)?;
+
+                Ok(GameClientBoundPacket::ClientBoundTabComplete(tab_complete))
+            }
+            0x14 => {
+                let window_items = WindowItems::decode(reader)?;
+
+                Ok(GameClientBoundPacket::WindowItems(window_items))
+            }
+            0x16 => {
+                let set_slot = SetSlot::decode(reader)?;
+
+                Ok(GameClientBoundPacket::SetSlot(set_slot))
+            }
+            0x17 => {
+                let set_cooldown = SetCooldown::decode(reader)?;
+
+                Ok(GameClientBoundPacket::SetCooldown(set_cooldown))
+            }
+            0x18 => {
+                let plugin_message = ClientBoundPluginMessage::decode(reader)?;
+
+                Ok(GameClientBoundPacket::ClientBoundPluginMessage(
+                    plugin_message,
+                ))
+            }
             0x1A => {
                 let game_disconnect = GameDisconnect::decode(reader)?;
 
                 Ok(GameClientBoundPacket::GameDisconnect(game_disconnect))
             }
+            0x1D => {
+                let unload_chunk = UnloadChunk::decode(reader)?;
+
+                Ok(GameClientBoundPacket::UnloadChunk(unload_chunk))
+            }
+            0x1F => {
+                let open_horse_window = OpenHorseWindow::decode(reader)?;
+
+                Ok(GameClientBoundPacket::OpenHorseWindow(open_horse_window))
+            }
             0x20 => {
                 let keep_alive = ClientBoundKeepAlive::decode(reader)?;
 
@@ -91,12 +342,117 @@ impl GameClientBoundPacket {
 
                 Ok(GameClientBoundPacket::JoinGame(join_game))
             }
+            0x2D => {
+                let open_book = OpenBook::decode(reader)?;
+
+                Ok(GameClientBoundPacket::OpenBook(open_book))
+            }
+            0x2F => {
+                let open_sign_editor = OpenSignEditor::decode(reader)?;
+
+                Ok(GameClientBoundPacket::OpenSignEditor(open_sign_editor))
+            }
+            0x32 => {
+                let combat_event = CombatEvent::decode(reader)?;
+
+                Ok(GameClientBoundPacket::CombatEvent(combat_event))
+            }
+            0x3C => {
+                let select_advancements_tab = SelectAdvancementsTab::decode(reader)?;
+
+                Ok(GameClientBoundPacket::SelectAdvancementsTab(
+                    select_advancements_tab,
+                ))
+            }
+            0x40 => {
+                let update_view_position = UpdateViewPosition::decode(reader)?;
+
+                Ok(GameClientBoundPacket::UpdateViewPosition(
+                    update_view_position,
+                ))
+            }
+            0x43 => {
+                let entity_metadata = EntityMetadata::decode(reader)?;
+
+                Ok(GameClientBoundPacket::EntityMetadata(entity_metadata))
+            }
+            0x45 => {
+                let entity_velocity = EntityVelocity::decode(reader)?;
+
+                Ok(GameClientBoundPacket::EntityVelocity(entity_velocity))
+            }
+            0x4E => {
+                let time_update = TimeUpdate::decode(reader)?;
+
+                Ok(GameClientBoundPacket::TimeUpdate(time_update))
+            }
+            0x4F => {
+                let title = Title::decode(reader)?;
+
+                Ok(GameClientBoundPacket::Title(title))
+            }
             _ => Err(DecodeError::UnknownPacketType { type_id }),
         }
     }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameClientBoundPacket::ClientBoundChatMessage(chat_message) => {
+                chat_message.encode(writer)
+            }
+            GameClientBoundPacket::JoinGame(join_game) => join_game.encode(writer),
+            GameClientBoundPacket::ClientBoundKeepAlive(keep_alive) => keep_alive.encode(writer),
+            GameClientBoundPacket::ChunkData(chunk_data) => chunk_data.encode(writer),
+            GameClientBoundPacket::GameDisconnect(game_disconnect) => {
+                game_disconnect.encode(writer)
+            }
+            GameClientBoundPacket::ClientBoundPluginMessage(plugin_message) => {
+                plugin_message.encode(writer)
+            }
+            GameClientBoundPacket::BossBar(boss_bar) => boss_bar.encode(writer),
+            GameClientBoundPacket::Title(title) => title.encode(writer),
+            GameClientBoundPacket::EntityVelocity(entity_velocity) => {
+                entity_velocity.encode(writer)
+            }
+            GameClientBoundPacket::CombatEvent(combat_event) => combat_event.encode(writer),
+            GameClientBoundPacket::EntityMetadata(entity_metadata) => {
+                entity_metadata.encode(writer)
+            }
+            GameClientBoundPacket::EntityAnimation(entity_animation) => {
+                entity_animation.encode(writer)
+            }
+            GameClientBoundPacket::OpenHorseWindow(open_horse_window) => {
+                open_horse_window.encode(writer)
+            }
+            GameClientBoundPacket::OpenBook(open_book) => open_book.encode(writer),
+            GameClientBoundPacket::OpenSignEditor(open_sign_editor) => {
+                open_sign_editor.encode(writer)
+            }
+            GameClientBoundPacket::BlockEntityData(block_entity_data) => {
+                block_entity_data.encode(writer)
+            }
+            GameClientBoundPacket::SelectAdvancementsTab(select_advancements_tab) => {
+                select_advancements_tab.encode(writer)
+            }
+            GameClientBoundPacket::ClientBoundTabComplete(tab_complete) => {
+                tab_complete.encode(writer)
+            }
+            GameClientBoundPacket::UnloadChunk(unload_chunk) => unload_chunk.encode(writer),
+            GameClientBoundPacket::UpdateViewPosition(update_view_position) => {
+                update_view_position.encode(writer)
+            }
+            GameClientBoundPacket::WindowItems(window_items) => window_items.encode(writer),
+            GameClientBoundPacket::SetSlot(set_slot) => set_slot.encode(writer),
+            GameClientBoundPacket::SetCooldown(set_cooldown) => set_cooldown.encode(writer),
+            GameClientBoundPacket::TimeUpdate(time_update) => time_update.encode(writer),
+        }
+    }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+impl_packet!(GameClientBoundPacket);
+
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ServerBoundChatMessage {
     #[data_type(max_length = 256)]
     pub message: String,
@@ -110,19 +466,13 @@ impl ServerBoundChatMessage {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, VisitMessages, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ClientBoundChatMessage {
     pub message: Message,
     pub position: MessagePosition,
 }
 
-#[derive(Encoder, Decoder, Debug, Eq, PartialEq)]
-pub enum MessagePosition {
-    Chat,
-    System,
-    HotBar,
-}
-
 impl ClientBoundChatMessage {
     pub fn new(message: Message, position: MessagePosition) -> GameClientBoundPacket {
         let chat_message = ClientBoundChatMessage { message, position };
@@ -131,20 +481,183 @@ impl ClientBoundChatMessage {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+/// Custom payload sent by client on plugin channel, e.g. `minecraft:brand`.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ServerBoundPluginMessage {
+    pub channel: Identifier,
+    /// Takes the rest of the packet, limited to `SERVER_BOUND_PAYLOAD_MAX_LENGTH` bytes.
+    pub data: Vec<u8>,
+}
+
+impl ServerBoundPluginMessage {
+    pub fn new(channel: Identifier, data: Vec<u8>) -> Result<GameServerBoundPacket, EncodeError> {
+        check_plugin_message(&channel, &data, limits::SERVER_BOUND_PAYLOAD_MAX_LENGTH)?;
+        let plugin_message = ServerBoundPluginMessage { channel, data };
+
+        Ok(GameServerBoundPacket::ServerBoundPluginMessage(
+            plugin_message,
+        ))
+    }
+}
+
+impl Encoder for ServerBoundPluginMessage {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        check_plugin_message(
+            &self.channel,
+            &self.data,
+            limits::SERVER_BOUND_PAYLOAD_MAX_LENGTH,
+        )?;
+
+        self.channel.encode(writer)?;
+        writer.write_all(&self.data)?;
+
+        Ok(())
+    }
+}
+
+impl Decoder for ServerBoundPluginMessage {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let channel = Identifier::decode(reader)?;
+        let max_length = limits::current().server_bound_payload_max_length;
+        let data = limits::read_payload(reader, max_length)?;
+
+        Ok(ServerBoundPluginMessage { channel, data })
+    }
+}
+
+/// Custom payload sent by server on plugin channel, e.g. `minecraft:brand`.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientBoundPluginMessage {
+    pub channel: Identifier,
+    /// Takes the rest of the packet, limited to `CLIENT_BOUND_PAYLOAD_MAX_LENGTH` bytes.
+    pub data: Vec<u8>,
+}
+
+impl ClientBoundPluginMessage {
+    pub fn new(channel: Identifier, data: Vec<u8>) -> Result<GameClientBoundPacket, EncodeError> {
+        check_plugin_message(&channel, &data, limits::CLIENT_BOUND_PAYLOAD_MAX_LENGTH)?;
+        let plugin_message = ClientBoundPluginMessage { channel, data };
+
+        Ok(GameClientBoundPacket::ClientBoundPluginMessage(
+            plugin_message,
+        ))
+    }
+}
+
+impl Encoder for ClientBoundPluginMessage {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        check_plugin_message(
+            &self.channel,
+            &self.data,
+            limits::CLIENT_BOUND_PAYLOAD_MAX_LENGTH,
+        )?;
+
+        self.channel.encode(writer)?;
+        writer.write_all(&self.data)?;
+
+        Ok(())
+    }
+}
+
+impl Decoder for ClientBoundPluginMessage {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        let channel = Identifier::decode(reader)?;
+        let max_length = limits::current().client_bound_payload_max_length;
+        let data = limits::read_payload(reader, max_length)?;
+
+        Ok(ClientBoundPluginMessage { channel, data })
+    }
+}
+
+impl Validate for ServerBoundPluginMessage {
+    fn validate_into(&self, path: &str, violations: &mut Vec<Violation>) {
+        validate_plugin_message(
+            &self.channel,
+            &self.data,
+            limits::SERVER_BOUND_PAYLOAD_MAX_LENGTH,
+            path,
+            violations,
+        );
+    }
+}
+
+impl Validate for ClientBoundPluginMessage {
+    fn validate_into(&self, path: &str, violations: &mut Vec<Violation>) {
+        validate_plugin_message(
+            &self.channel,
+            &self.data,
+            limits::CLIENT_BOUND_PAYLOAD_MAX_LENGTH,
+            path,
+            violations,
+        );
+    }
+}
+
+fn validate_plugin_message(
+    channel: &Identifier,
+    data: &[u8],
+    max_length: usize,
+    path: &str,
+    violations: &mut Vec<Violation>,
+) {
+    if !channel.is_valid() {
+        violations.push(Violation {
+            field: validate::field_path(path, "channel"),
+            kind: ViolationKind::InvalidIdentifier {
+                value: channel.as_str().to_owned(),
+            },
+        });
+    }
+
+    validate::check_length(violations, path, "data", data.len(), max_length);
+}
+
+fn check_plugin_message(
+    channel: &Identifier,
+    data: &[u8],
+    max_length: usize,
+) -> Result<(), EncodeError> {
+    if !channel.is_valid() {
+        return Err(EncodeError::InvalidIdentifier {
+            value: channel.as_str().to_owned(),
+        });
+    }
+
+    if data.len() > max_length {
+        return Err(EncodeError::PayloadTooLong {
+            length: data.len(),
+            max_length,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Encoder, Decoder, Builder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct JoinGame {
     pub entity_id: u32,
     pub game_mode: GameMode,
+    #[builder(default)]
     pub dimension: i32,
     pub max_players: u8,
     #[data_type(max_length = 16)]
     pub level_type: String,
     #[data_type(with = "var_int")]
+    #[validate(min = 2, max = 32)]
     pub view_distance: i32,
+    #[builder(default)]
     pub reduced_debug_info: bool,
 }
 
 #[derive(Encoder, Decoder, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum GameMode {
     Survival = 0,
     Creative = 1,
@@ -177,7 +690,8 @@ impl JoinGame {
     }
 }
 
-#[derive(Encoder, Decoder)]
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ServerBoundKeepAlive {
     pub id: u64,
 }
@@ -190,7 +704,8 @@ impl ServerBoundKeepAlive {
     }
 }
 
-#[derive(Encoder, Decoder)]
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ClientBoundKeepAlive {
     pub id: u64,
 }
@@ -203,15 +718,21 @@ impl ClientBoundKeepAlive {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, Builder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ChunkData {
     pub x: i32,
     pub z: i32,
     pub full: bool,
+    /// Bit for every of 16 sections present in data.
     #[data_type(with = "var_int")]
+    #[validate(min = 0, max = 0xFFFF)]
     pub primary_mask: i32,
-    pub heights: CompoundTag,
+    #[cfg_attr(feature = "serde", serde(with = "compound_tag::arc"))]
+    pub heights: Arc<CompoundTag>,
     pub data: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "compound_tag::vec"))]
+    #[builder(default)]
     pub tiles: Vec<CompoundTag>,
 }
 
@@ -221,7 +742,7 @@ impl ChunkData {
         z: i32,
         full: bool,
         primary_mask: i32,
-        heights: CompoundTag,
+        heights: impl Into<Arc<CompoundTag>>,
         data: Vec<u8>,
         tiles: Vec<CompoundTag>,
     ) -> GameClientBoundPacket {
@@ -230,16 +751,43 @@ impl ChunkData {
             z,
             full,
             primary_mask,
-            heights,
+            heights: heights.into(),
             data,
             tiles,
         };
 
         GameClientBoundPacket::ChunkData(chunk_data)
     }
+
+    /// Encodes packet like `encode` but takes heights from cache, so heights shared between
+    /// packets are encoded once.
+    pub fn encode_cached<W: Write>(
+        &self,
+        writer: &mut W,
+        cache: &mut CompoundTagCache,
+    ) -> Result<(), EncodeError> {
+        self.x.encode(writer)?;
+        self.z.encode(writer)?;
+        self.full.encode(writer)?;
+        writer.write_var_i32(self.primary_mask)?;
+        cache.write(writer, &self.heights)?;
+        self.data.encode(writer)?;
+        self.tiles.encode(writer)
+    }
+
+    /// Returns tile entities indexed by position.
+    pub fn block_entities(&self) -> BlockEntities {
+        BlockEntities::from_tags(self.tiles.iter().cloned())
+    }
+
+    /// Replaces tile entities, e.g. after updates were merged.
+    pub fn set_block_entities(&mut self, block_entities: BlockEntities) {
+        self.tiles = block_entities.into_tags();
+    }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, VisitMessages, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GameDisconnect {
     pub reason: Message,
 }
@@ -252,20 +800,23 @@ impl GameDisconnect {
     }
 }
 
-#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[derive(Encoder, Decoder, VisitMessages, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BossBar {
     pub id: Uuid,
+    #[visit]
     pub action: BossBarAction,
 }
 
-#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[derive(Encoder, Decoder, VisitMessages, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BossBarAction {
     Add {
         title: Message,
         health: f32,
         color: BossBarColor,
         division: BossBarDivision,
-        flags: u8,
+        flags: BossBarFlags,
     },
     Remove,
     UpdateHealth {
@@ -279,11 +830,12 @@ pub enum BossBarAction {
         division: BossBarDivision,
     },
     UpdateFlags {
-        flags: u8,
+        flags: BossBarFlags,
     },
 }
 
 #[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BossBarColor {
     Pink,
     Blue,
@@ -295,6 +847,7 @@ pub enum BossBarColor {
 }
 
 #[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BossBarDivision {
     None,
     Notches6,
@@ -311,7 +864,9 @@ impl BossBar {
     }
 }
 
-#[derive(Encoder, Decoder, Debug, PartialEq)]
+/// Client starts or stops sneaking, sprinting, sleeping or jumping with horse.
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EntityAction {
     #[data_type(with = "var_int")]
     pub entity_id: i32,
@@ -321,6 +876,7 @@ pub struct EntityAction {
 }
 
 #[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[data_type(with = "var_int")]
 pub enum EntityActionId {
     StartSneaking,
@@ -334,7 +890,8 @@ pub enum EntityActionId {
     StartFlyingWithElytra,
 }
 
-#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ServerBoundAbilities {
     #[data_type(bitfield)]
     pub invulnerable: bool,
@@ -348,168 +905,570 @@ pub struct ServerBoundAbilities {
     pub walk_speed: f32,
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::data::chat::Payload;
-    use crate::decoder::Decoder;
-    use crate::encoder::Encoder;
-    use crate::encoder::EncoderWriteExt;
-    use crate::error::{DecodeError, EncodeError};
-    use crate::version::v1_14_4::game::*;
-    use crate::STRING_MAX_LENGTH;
-    use nbt::CompoundTag;
-    use std::io::Cursor;
-    use std::str::FromStr;
-
-    #[test]
-    fn test_server_bound_chat_message_encode() {
-        let chat_message = ServerBoundChatMessage {
-            message: String::from("hello server!"),
-        };
+#[derive(Encoder, Decoder, VisitMessages, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Title {
+    #[visit]
+    pub action: TitleAction,
+}
 
-        let mut vec = Vec::new();
-        chat_message.encode(&mut vec).unwrap();
+impl Title {
+    pub fn new(action: TitleAction) -> GameClientBoundPacket {
+        let title = Title { action };
 
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/game/server_bound_chat_message.dat").to_vec()
-        );
+        GameClientBoundPacket::Title(title)
     }
+}
 
-    #[test]
-    fn test_server_bound_chat_message_decode() {
-        let mut cursor = Cursor::new(
-            include_bytes!("../../../test/packet/game/server_bound_chat_message.dat").to_vec(),
-        );
-        let chat_message = ServerBoundChatMessage::decode(&mut cursor).unwrap();
-
-        assert_eq!(chat_message.message, "hello server!");
-    }
+#[derive(Encoder, Decoder, Builder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EntityVelocity {
+    #[data_type(with = "var_int")]
+    #[builder(default)]
+    pub entity_id: i32,
+    #[builder(default)]
+    pub velocity_x: Velocity,
+    #[builder(default)]
+    pub velocity_y: Velocity,
+    #[builder(default)]
+    pub velocity_z: Velocity,
+}
 
-    #[test]
-    fn test_server_bound_chat_message_encode_invalid_length() {
-        let chat_message = ServerBoundChatMessage {
-            message: "abc".repeat(100),
+impl EntityVelocity {
+    pub fn new(
+        entity_id: i32,
+        velocity_x: Velocity,
+        velocity_y: Velocity,
+        velocity_z: Velocity,
+    ) -> GameClientBoundPacket {
+        let entity_velocity = EntityVelocity {
+            entity_id,
+            velocity_x,
+            velocity_y,
+            velocity_z,
         };
 
-        let mut vec = Vec::new();
-
-        let encode_error = chat_message
-            .encode(&mut vec)
-            .err()
-            .expect("Expected error `StringTooLong` because message has invalid length");
-
-        match encode_error {
-            EncodeError::StringTooLong { length, max_length } => {
-                assert_eq!(length, 300);
-                assert_eq!(max_length, 256);
-            }
-            _ => panic!("Expected `StringTooLong` but got `{:?}`", encode_error),
-        }
+        GameClientBoundPacket::EntityVelocity(entity_velocity)
     }
+}
 
-    #[test]
-    fn test_server_bound_chat_message_decode_invalid_length() {
-        let message = "abc".repeat(100);
-
-        let mut vec = Vec::new();
-        vec.write_string(&message, STRING_MAX_LENGTH).unwrap();
+#[derive(Encoder, Decoder, VisitMessages, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CombatEvent {
+    #[visit]
+    pub event: CombatEventType,
+}
 
-        let mut cursor = Cursor::new(vec);
+#[derive(Encoder, Decoder, VisitMessages, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[data_type(with = "var_int")]
+pub enum CombatEventType {
+    EnterCombat,
+    EndCombat {
+        #[data_type(with = "var_int_ticks")]
+        duration: Ticks,
+        /// Id of the last attacker or -1.
+        entity_id: i32,
+    },
+    /// Shows death screen to player.
+    EntityDead {
+        #[data_type(with = "var_int")]
+        player_id: i32,
+        /// Id of the killer or -1.
+        entity_id: i32,
+        message: Message,
+    },
+}
 
-        let decode_error = ServerBoundChatMessage::decode(&mut cursor)
-            .err()
-            .expect("Expected error `StringTooLong` because message has invalid length");
+impl CombatEvent {
+    pub fn new(event: CombatEventType) -> GameClientBoundPacket {
+        let combat_event = CombatEvent { event };
 
-        match decode_error {
-            DecodeError::StringTooLong { length, max_length } => {
-                assert_eq!(length, 300);
-                assert_eq!(max_length, 256);
-            }
-            _ => panic!("Expected `StringTooLong` but got `{:?}`", decode_error),
-        }
+        GameClientBoundPacket::CombatEvent(combat_event)
     }
+}
 
-    #[test]
-    fn test_client_bound_chat_message_encode() {
-        let chat_message = ClientBoundChatMessage {
-            message: Message::new(Payload::text("hello client!")),
-            position: MessagePosition::System,
-        };
+/// Client asks to respawn or for statistics.
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientStatus {
+    pub action: ClientStatusAction,
+}
 
-        let mut vec = Vec::new();
-        chat_message.encode(&mut vec).unwrap();
+impl ClientStatus {
+    pub fn new(action: ClientStatusAction) -> GameServerBoundPacket {
+        let client_status = ClientStatus { action };
 
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/game/client_bound_chat_message.dat").to_vec()
-        );
+        GameServerBoundPacket::ClientStatus(client_status)
     }
+}
 
-    #[test]
-    fn test_client_bound_chat_message_decode() {
-        let mut cursor = Cursor::new(
-            include_bytes!("../../../test/packet/game/client_bound_chat_message.dat").to_vec(),
-        );
-        let chat_message = ClientBoundChatMessage::decode(&mut cursor).unwrap();
+/// Player swings arm. Sent as `Animation` packet.
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HandSwing {
+    pub hand: InteractionHand,
+}
 
-        assert_eq!(
-            chat_message.message,
-            Message::new(Payload::text("hello client!"))
-        );
+impl HandSwing {
+    pub fn new(hand: InteractionHand) -> GameServerBoundPacket {
+        let hand_swing = HandSwing { hand };
 
-        assert_eq!(chat_message.position, MessagePosition::System);
+        GameServerBoundPacket::HandSwing(hand_swing)
     }
+}
 
-    #[test]
-    fn test_server_bound_keep_alive_encode() {
-        let keep_alive = ServerBoundKeepAlive { id: 31122019 };
+/// Player uses item in hand, e.g. eats food or throws projectile.
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UseItem {
+    pub hand: InteractionHand,
+}
 
-        let mut vec = Vec::new();
-        keep_alive.encode(&mut vec).unwrap();
+impl UseItem {
+    pub fn new(hand: InteractionHand) -> GameServerBoundPacket {
+        let use_item = UseItem { hand };
 
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/game/server_bound_keep_alive.dat").to_vec()
-        );
+        GameServerBoundPacket::UseItem(use_item)
     }
+}
 
-    #[test]
-    fn test_server_bound_keep_alive_decode() {
-        let mut cursor = Cursor::new(
-            include_bytes!("../../../test/packet/game/server_bound_keep_alive.dat").to_vec(),
-        );
-        let keep_alive = ServerBoundKeepAlive::decode(&mut cursor).unwrap();
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EntityAnimation {
+    #[data_type(with = "var_int")]
+    pub entity_id: i32,
+    pub animation: Animation,
+}
 
-        assert_eq!(keep_alive.id, 31122019);
-    }
+#[derive(Encoder, Decoder, Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Animation {
+    SwingMainArm,
+    TakeDamage,
+    LeaveBed,
+    SwingOffHand,
+    CriticalEffect,
+    MagicCriticalEffect,
+}
 
-    #[test]
-    fn test_client_bound_keep_alive_encode() {
-        let keep_alive = ClientBoundKeepAlive { id: 240714 };
+impl EntityAnimation {
+    pub fn new(entity_id: i32, animation: Animation) -> GameClientBoundPacket {
+        let entity_animation = EntityAnimation {
+            entity_id,
+            animation,
+        };
 
-        let mut vec = Vec::new();
-        keep_alive.encode(&mut vec).unwrap();
+        GameClientBoundPacket::EntityAnimation(entity_animation)
+    }
+}
 
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/game/client_bound_keep_alive.dat").to_vec()
-        );
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OpenHorseWindow {
+    pub window_id: u8,
+    #[data_type(with = "var_int")]
+    pub slot_count: i32,
+    pub entity_id: i32,
+}
+
+impl OpenHorseWindow {
+    pub fn new(window_id: u8, slot_count: i32, entity_id: i32) -> GameClientBoundPacket {
+        let open_horse_window = OpenHorseWindow {
+            window_id,
+            slot_count,
+            entity_id,
+        };
+
+        GameClientBoundPacket::OpenHorseWindow(open_horse_window)
     }
+}
 
-    #[test]
-    fn test_client_bound_keep_alive_decode() {
-        let mut cursor = Cursor::new(
-            include_bytes!("../../../test/packet/game/client_bound_keep_alive.dat").to_vec(),
-        );
-        let keep_alive = ClientBoundKeepAlive::decode(&mut cursor).unwrap();
+/// Opens written book held in hand.
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OpenBook {
+    pub hand: InteractionHand,
+}
+
+impl OpenBook {
+    pub fn new(hand: InteractionHand) -> GameClientBoundPacket {
+        let open_book = OpenBook { hand };
 
-        assert_eq!(keep_alive.id, 240714);
+        GameClientBoundPacket::OpenBook(open_book)
     }
+}
 
-    #[test]
-    fn test_join_game_encode() {
-        let join_game = JoinGame {
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SelectAdvancementsTab {
+    /// Root advancement of the tab. Client opens the first tab if it's `None` or unknown.
+    pub identifier: Option<Identifier>,
+}
+
+impl SelectAdvancementsTab {
+    pub fn new(identifier: Option<Identifier>) -> GameClientBoundPacket {
+        let select_advancements_tab = SelectAdvancementsTab { identifier };
+
+        GameClientBoundPacket::SelectAdvancementsTab(select_advancements_tab)
+    }
+}
+
+/// Entity metadata update, usually only entries which changed are sent.
+#[derive(Encoder, Decoder, VisitMessages, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EntityMetadata {
+    #[data_type(with = "var_int")]
+    pub entity_id: i32,
+    #[visit]
+    pub metadata: Metadata,
+}
+
+impl EntityMetadata {
+    pub fn new(entity_id: i32, metadata: Metadata) -> GameClientBoundPacket {
+        let entity_metadata = EntityMetadata {
+            entity_id,
+            metadata,
+        };
+
+        GameClientBoundPacket::EntityMetadata(entity_metadata)
+    }
+}
+
+/// Client sends lines entered in sign editor.
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UpdateSign {
+    pub position: BlockPosition,
+    #[data_type(max_length = 384)]
+    pub line_1: String,
+    #[data_type(max_length = 384)]
+    pub line_2: String,
+    #[data_type(max_length = 384)]
+    pub line_3: String,
+    #[data_type(max_length = 384)]
+    pub line_4: String,
+}
+
+impl UpdateSign {
+    pub fn new(position: BlockPosition, lines: [String; 4]) -> GameServerBoundPacket {
+        let [line_1, line_2, line_3, line_4] = lines;
+
+        let update_sign = UpdateSign {
+            position,
+            line_1,
+            line_2,
+            line_3,
+            line_4,
+        };
+
+        GameServerBoundPacket::UpdateSign(update_sign)
+    }
+
+    pub fn lines(&self) -> [&str; 4] {
+        [&self.line_1, &self.line_2, &self.line_3, &self.line_4]
+    }
+}
+
+/// Opens sign editor, sign must be placed at position before.
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OpenSignEditor {
+    pub position: BlockPosition,
+}
+
+impl OpenSignEditor {
+    pub fn new(position: BlockPosition) -> GameClientBoundPacket {
+        let open_sign_editor = OpenSignEditor { position };
+
+        GameClientBoundPacket::OpenSignEditor(open_sign_editor)
+    }
+}
+
+/// Client forgets chunk and its entities.
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UnloadChunk {
+    pub x: i32,
+    pub z: i32,
+}
+
+impl UnloadChunk {
+    pub fn new(position: ChunkPosition) -> GameClientBoundPacket {
+        let unload_chunk = UnloadChunk {
+            x: position.x,
+            z: position.z,
+        };
+
+        GameClientBoundPacket::UnloadChunk(unload_chunk)
+    }
+}
+
+/// Chunk player is in, client drops chunks outside view distance around it.
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UpdateViewPosition {
+    #[data_type(with = "var_int")]
+    pub x: i32,
+    #[data_type(with = "var_int")]
+    pub z: i32,
+}
+
+impl UpdateViewPosition {
+    pub fn new(position: ChunkPosition) -> GameClientBoundPacket {
+        let update_view_position = UpdateViewPosition {
+            x: position.x,
+            z: position.z,
+        };
+
+        GameClientBoundPacket::UpdateViewPosition(update_view_position)
+    }
+}
+
+/// Client asks for completions of chat message or command being typed.
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ServerBoundTabComplete {
+    /// Returned in answer, so client can drop outdated completions.
+    #[data_type(with = "var_int")]
+    pub transaction_id: i32,
+    #[data_type(max_length = 32500)]
+    pub text: String,
+}
+
+impl ServerBoundTabComplete {
+    pub fn new(transaction_id: i32, text: String) -> GameServerBoundPacket {
+        let tab_complete = ServerBoundTabComplete {
+            transaction_id,
+            text,
+        };
+
+        GameServerBoundPacket::ServerBoundTabComplete(tab_complete)
+    }
+}
+
+/// Completion offered to client with optional tooltip.
+#[derive(Encoder, Decoder, VisitMessages, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TabCompleteMatch {
+    #[data_type(max_length = 32767)]
+    pub value: String,
+    pub tooltip: Option<Message>,
+}
+
+impl TabCompleteMatch {
+    pub fn new(value: String, tooltip: Option<Message>) -> Self {
+        TabCompleteMatch { value, tooltip }
+    }
+}
+
+/// Completions replacing `length` characters of text starting at `start`.
+#[derive(Encoder, Decoder, VisitMessages, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientBoundTabComplete {
+    #[data_type(with = "var_int")]
+    pub transaction_id: i32,
+    #[data_type(with = "var_int")]
+    pub start: i32,
+    #[data_type(with = "var_int")]
+    pub length: i32,
+    #[data_type(with = "list")]
+    #[visit]
+    #[validate(nested)]
+    pub matches: Vec<TabCompleteMatch>,
+}
+
+impl ClientBoundTabComplete {
+    pub fn new(
+        transaction_id: i32,
+        start: i32,
+        length: i32,
+        matches: Vec<TabCompleteMatch>,
+    ) -> GameClientBoundPacket {
+        let tab_complete = ClientBoundTabComplete {
+            transaction_id,
+            start,
+            length,
+            matches,
+        };
+
+        GameClientBoundPacket::ClientBoundTabComplete(tab_complete)
+    }
+}
+
+/// Block entity type whose data is updated.
+#[derive(Encoder, Decoder, Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BlockEntityAction {
+    MobSpawner = 1,
+    CommandBlock = 2,
+    Beacon = 3,
+    MobHead = 4,
+    Conduit = 5,
+    Banner = 6,
+    StructureBlock = 7,
+    EndGateway = 8,
+    Sign = 9,
+    Bed = 11,
+    Jigsaw = 12,
+    Campfire = 13,
+}
+
+/// Replaces block entity data, e.g. sign text after `UpdateSign` is accepted.
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockEntityData {
+    pub position: BlockPosition,
+    pub action: BlockEntityAction,
+    #[cfg_attr(feature = "serde", serde(with = "compound_tag"))]
+    pub data: CompoundTag,
+}
+
+impl BlockEntityData {
+    pub fn new(
+        position: BlockPosition,
+        action: BlockEntityAction,
+        data: CompoundTag,
+    ) -> GameClientBoundPacket {
+        let block_entity_data = BlockEntityData {
+            position,
+            action,
+            data,
+        };
+
+        GameClientBoundPacket::BlockEntityData(block_entity_data)
+    }
+
+    /// Applies update to block entities of chunk containing position.
+    pub fn apply_to(&self, block_entities: &mut BlockEntities) {
+        block_entities.update(self.position, self.data.clone());
+    }
+
+    /// Sets sign text at position.
+    pub fn sign(
+        position: BlockPosition,
+        lines: &[Message; 4],
+    ) -> Result<GameClientBoundPacket, serde_json::Error> {
+        let data = sign_block_entity(position, lines)?;
+
+        Ok(BlockEntityData::new(
+            position,
+            BlockEntityAction::Sign,
+            data,
+        ))
+    }
+}
+
+/// Replaces all slots of window, including player inventory when window id is 0.
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WindowItems {
+    pub window_id: u8,
+    /// Slots in window order, `None` is empty slot.
+    #[data_type(with = "short_list")]
+    pub slots: Vec<Option<Slot>>,
+}
+
+impl WindowItems {
+    pub fn new(window_id: u8, slots: Vec<Option<Slot>>) -> GameClientBoundPacket {
+        let window_items = WindowItems { window_id, slots };
+
+        GameClientBoundPacket::WindowItems(window_items)
+    }
+}
+
+/// Replaces single slot of window.
+///
+/// Window id -1 with slot -1 sets item held by cursor.
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SetSlot {
+    pub window_id: i8,
+    pub slot: i16,
+    pub item: Option<Slot>,
+}
+
+impl SetSlot {
+    pub fn new(window_id: i8, slot: i16, item: Option<Slot>) -> GameClientBoundPacket {
+        let set_slot = SetSlot {
+            window_id,
+            slot,
+            item,
+        };
+
+        GameClientBoundPacket::SetSlot(set_slot)
+    }
+}
+
+/// Makes client unable to use item for cooldown, zero cooldown removes it.
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SetCooldown {
+    #[data_type(with = "var_int")]
+    pub item_id: i32,
+    #[data_type(with = "var_int_ticks")]
+    pub cooldown: Ticks,
+}
+
+impl SetCooldown {
+    pub fn new(item_id: i32, cooldown: Ticks) -> GameClientBoundPacket {
+        let set_cooldown = SetCooldown { item_id, cooldown };
+
+        GameClientBoundPacket::SetCooldown(set_cooldown)
+    }
+}
+
+/// Synchronizes world time, vanilla server sends it every second.
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeUpdate {
+    pub world_age: Ticks<i64>,
+    /// Time of day, 0 is sunrise and 6000 is noon. Negative time stops daylight cycle at its
+    /// absolute value.
+    pub time_of_day: Ticks<i64>,
+}
+
+impl TimeUpdate {
+    pub fn new(world_age: Ticks<i64>, time_of_day: Ticks<i64>) -> GameClientBoundPacket {
+        let time_update = TimeUpdate {
+            world_age,
+            time_of_day,
+        };
+
+        GameClientBoundPacket::TimeUpdate(time_update)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::chat::{Payload, VisitMessages};
+    use crate::data::units::Velocity;
+    use crate::decoder::Decoder;
+    use crate::encoder::Encoder;
+    use crate::encoder::EncoderWriteExt;
+    use crate::error::{BuildError, DecodeError, EncodeError};
+    use crate::packet::roundtrip_tests;
+    use crate::version::v1_14_4::game::*;
+    use crate::STRING_MAX_LENGTH;
+    use nbt::CompoundTag;
+    use std::io::Cursor;
+    use std::str::FromStr;
+
+    roundtrip_tests! {
+        test_server_bound_chat_message_roundtrip: ServerBoundChatMessage = ServerBoundChatMessage {
+            message: String::from("hello server!"),
+        } => "game/server_bound_chat_message.dat";
+
+        test_client_bound_chat_message_roundtrip: ClientBoundChatMessage = ClientBoundChatMessage {
+            message: Message::new(Payload::text("hello client!")),
+            position: MessagePosition::System,
+        } => "game/client_bound_chat_message.dat";
+
+        test_server_bound_keep_alive_roundtrip: ServerBoundKeepAlive = ServerBoundKeepAlive { id: 31122019 } => "game/server_bound_keep_alive.dat";
+
+        test_client_bound_keep_alive_roundtrip: ClientBoundKeepAlive = ClientBoundKeepAlive { id: 240714 } => "game/client_bound_keep_alive.dat";
+
+        test_join_game_roundtrip: JoinGame = JoinGame {
             entity_id: 27,
             game_mode: GameMode::Hardcore,
             dimension: 23,
@@ -517,8 +1476,155 @@ mod tests {
             level_type: String::from("default"),
             view_distance: 10,
             reduced_debug_info: true,
+        } => "game/join_game.dat";
+
+        test_chunk_data_roundtrip: ChunkData = ChunkData {
+            x: -2,
+            z: 5,
+            full: true,
+            primary_mask: 65535,
+            heights: Arc::new(CompoundTag::named("HeightMaps")),
+            data: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            tiles: vec![CompoundTag::named("TileEntity")],
+        } => "game/chunk_data.dat";
+
+        test_game_disconnect_roundtrip: GameDisconnect = GameDisconnect {
+            reason: Message::new(Payload::text("Message")),
+        } => "game/game_disconnect.dat";
+
+        test_boss_bar_add_roundtrip: BossBar = create_boss_bar_add_packet() => "game/boss_bar_add.dat";
+
+        test_boss_bar_remove_roundtrip: BossBar = create_boss_bar_remove_packet() => "game/boss_bar_remove.dat";
+
+        test_entity_action_roundtrip: EntityAction = EntityAction {
+            entity_id: 12345,
+            action_id: EntityActionId::StartFlyingWithElytra,
+            jump_boost: i32::MAX,
+        } => "game/entity_action.dat";
+
+        test_title_roundtrip: Title = Title {
+            action: TitleAction::SetTitle {
+                text: Message::from_str("Hello"),
+            },
+        } => "game/title.dat";
+
+        test_entity_velocity_roundtrip: EntityVelocity = EntityVelocity {
+            entity_id: 12,
+            velocity_x: Velocity::from_blocks_per_tick(0.5),
+            velocity_y: Velocity::from_blocks_per_tick(-0.25),
+            velocity_z: Velocity(0),
+        } => "game/entity_velocity.dat";
+
+        test_combat_event_roundtrip: CombatEvent = CombatEvent {
+            event: CombatEventType::EntityDead {
+                player_id: 5,
+                entity_id: 12,
+                message: Message::from_str("You died"),
+            },
+        } => "game/combat_event.dat";
+
+        test_use_item_roundtrip: UseItem = UseItem {
+            hand: InteractionHand::OffHand,
+        } => "game/use_item.dat";
+
+        test_entity_animation_roundtrip: EntityAnimation = EntityAnimation {
+            entity_id: 300,
+            animation: Animation::CriticalEffect,
+        } => "game/entity_animation.dat";
+
+        test_open_horse_window_roundtrip: OpenHorseWindow = OpenHorseWindow {
+            window_id: 2,
+            slot_count: 17,
+            entity_id: 300,
+        } => "game/open_horse_window.dat";
+
+        test_window_items_roundtrip: WindowItems = WindowItems {
+            window_id: 0,
+            slots: vec![Some(Slot::new(1, 64)), None],
+        } => "game/window_items.dat";
+
+        test_set_slot_roundtrip: SetSlot = SetSlot {
+            window_id: 0,
+            slot: 36,
+            item: Some(Slot::new(1, 64)),
+        } => "game/set_slot.dat";
+
+        test_set_cooldown_roundtrip: SetCooldown = SetCooldown {
+            item_id: 598,
+            cooldown: Ticks(20),
+        } => "game/set_cooldown.dat";
+
+        test_time_update_roundtrip: TimeUpdate = TimeUpdate {
+            world_age: Ticks(24_000),
+            time_of_day: Ticks(-6_000),
+        } => "game/time_update.dat";
+
+        test_select_advancements_tab_roundtrip: SelectAdvancementsTab = SelectAdvancementsTab {
+            identifier: Some(Identifier::from("minecraft:story/root")),
+        } => "game/select_advancements_tab.dat";
+
+        test_server_bound_plugin_message_roundtrip: ServerBoundPluginMessage = ServerBoundPluginMessage {
+            channel: Identifier::from("minecraft:brand"),
+            data: b"\x07vanilla".to_vec(),
+        } => "game/server_bound_plugin_message.dat";
+    }
+
+    #[test]
+    fn test_server_bound_chat_message_encode_invalid_length() {
+        let chat_message = ServerBoundChatMessage {
+            message: "abc".repeat(100),
         };
 
+        let mut vec = Vec::new();
+
+        let encode_error = chat_message
+            .encode(&mut vec)
+            .expect_err("Expected error `StringTooLong` because message has invalid length");
+
+        assert_eq!(
+            encode_error,
+            EncodeError::StringTooLong {
+                field: Some("message"),
+                length: 300,
+                max_length: 256,
+            }
+        );
+    }
+
+    #[test]
+    fn test_server_bound_chat_message_decode_invalid_length() {
+        let message = "abc".repeat(100);
+
+        let mut vec = Vec::new();
+        vec.write_string(&message, STRING_MAX_LENGTH).unwrap();
+
+        let mut cursor = Cursor::new(vec);
+
+        let decode_error = ServerBoundChatMessage::decode(&mut cursor)
+            .expect_err("Expected error `StringTooLong` because message has invalid length");
+
+        match decode_error {
+            DecodeError::StringTooLong { length, max_length } => {
+                assert_eq!(length, 300);
+                assert_eq!(max_length, 256);
+            }
+            _ => panic!("Expected `StringTooLong` but got `{:?}`", decode_error),
+        }
+    }
+
+    #[test]
+    fn test_join_game_builder() {
+        let join_game = JoinGame::builder()
+            .entity_id(27)
+            .game_mode(GameMode::Hardcore)
+            .dimension(23)
+            .max_players(100)
+            .level_type(String::from("default"))
+            .view_distance(10)
+            .reduced_debug_info(true)
+            .build()
+            .unwrap();
+
         let mut vec = Vec::new();
         join_game.encode(&mut vec).unwrap();
 
@@ -529,206 +1635,419 @@ mod tests {
     }
 
     #[test]
-    fn test_join_game_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/game/join_game.dat").to_vec());
-        let join_game = JoinGame::decode(&mut cursor).unwrap();
+    fn test_join_game_builder_defaults() {
+        let join_game = JoinGame::builder()
+            .entity_id(27)
+            .game_mode(GameMode::Survival)
+            .max_players(100)
+            .level_type(String::from("default"))
+            .view_distance(10)
+            .build()
+            .unwrap();
+
+        assert_eq!(join_game.dimension, 0);
+        assert!(!join_game.reduced_debug_info);
+    }
+
+    #[test]
+    fn test_join_game_builder_missing_field() {
+        let build_error = JoinGame::builder()
+            .entity_id(27)
+            .game_mode(GameMode::Survival)
+            .build()
+            .expect_err("Expected error `MissingField` because max players is not set");
 
-        assert_eq!(join_game.entity_id, 27);
-        assert_eq!(join_game.game_mode, GameMode::Hardcore);
-        assert_eq!(join_game.dimension, 23);
-        assert_eq!(join_game.max_players, 100);
-        assert_eq!(join_game.level_type, String::from("default"));
-        assert_eq!(join_game.view_distance, 10);
-        assert!(join_game.reduced_debug_info);
+        assert_eq!(
+            build_error,
+            BuildError::MissingField {
+                field: "max_players"
+            }
+        );
     }
 
     #[test]
-    fn test_chunk_data_encode() {
+    fn test_chunk_data_encode_cached() {
+        let heights = Arc::new(CompoundTag::named("HeightMaps"));
         let chunk_data = ChunkData {
             x: -2,
             z: 5,
             full: true,
             primary_mask: 65535,
-            heights: CompoundTag::named("HeightMaps"),
+            heights: heights.clone(),
             data: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
             tiles: vec![CompoundTag::named("TileEntity")],
         };
 
-        let mut vec = Vec::new();
-        chunk_data.encode(&mut vec).unwrap();
+        let mut cache = CompoundTagCache::new();
 
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/game/chunk_data.dat").to_vec()
-        );
+        for _ in 0..2 {
+            let mut vec = Vec::new();
+            chunk_data.encode_cached(&mut vec, &mut cache).unwrap();
+
+            assert_eq!(
+                vec,
+                include_bytes!("../../../test/packet/game/chunk_data.dat").to_vec()
+            );
+        }
+
+        assert_eq!(cache.len(), 1);
     }
 
-    #[test]
-    fn test_chunk_data_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/game/chunk_data.dat").to_vec());
-        let chunk_data = ChunkData::decode(&mut cursor).unwrap();
+    fn create_boss_bar_add_packet() -> BossBar {
+        BossBar {
+            id: Uuid::from_str("afa32ac8-d3bf-47f3-99eb-294d60b3dca2").unwrap(),
+            action: BossBarAction::Add {
+                title: Message::from_str("Boss title"),
+                health: 123.45,
+                color: BossBarColor::Yellow,
+                division: BossBarDivision::Notches10,
+                flags: BossBarFlags::all(),
+            },
+        }
+    }
 
-        assert_eq!(chunk_data.x, -2);
-        assert_eq!(chunk_data.z, 5);
-        assert!(chunk_data.full);
-        assert_eq!(chunk_data.heights.name, Some(String::from("HeightMaps")));
-        assert_eq!(chunk_data.data, vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
-        assert_eq!(chunk_data.primary_mask, 65535);
-        assert_eq!(chunk_data.tiles[0].name, Some(String::from("TileEntity")));
+    fn create_boss_bar_remove_packet() -> BossBar {
+        BossBar {
+            id: Uuid::from_str("afa32ac8-d3bf-47f3-99eb-294d60b3dca2").unwrap(),
+            action: BossBarAction::Remove,
+        }
     }
 
     #[test]
-    fn test_game_disconnect_encode() {
-        let game_disconnect = GameDisconnect {
-            reason: Message::new(Payload::text("Message")),
+    fn test_serverbound_abilities_encode() {
+        let abilities = ServerBoundAbilities {
+            invulnerable: true,
+            flying: true,
+            allow_flying: false,
+            creative_mode: true,
+            fly_speed: 0.0,
+            walk_speed: 0.0,
         };
 
         let mut vec = Vec::new();
-        game_disconnect.encode(&mut vec).unwrap();
+        abilities.encode(&mut vec).unwrap();
 
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/game/game_disconnect.dat").to_vec()
-        );
+        assert_eq!(vec, [13, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_serverbound_abilities_decode() {
+        let vec = [13, 0, 0, 0, 0, 0, 0, 0, 0].to_vec();
+        let mut cursor = Cursor::new(vec);
+
+        let abilities = ServerBoundAbilities::decode(&mut cursor).unwrap();
+        assert!(abilities.invulnerable);
+        assert!(!abilities.allow_flying);
+        assert!(abilities.flying);
+        assert!(abilities.creative_mode);
     }
 
     #[test]
-    fn test_game_disconnect_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/game/game_disconnect.dat").to_vec());
-        let game_disconnect = GameDisconnect::decode(&mut cursor).unwrap();
+    fn test_entity_velocity_default() {
+        let entity_velocity = EntityVelocity::builder()
+            .entity_id(12)
+            .velocity_y(Velocity::from_blocks_per_tick(-0.25))
+            .build()
+            .unwrap();
 
         assert_eq!(
-            game_disconnect.reason,
-            Message::new(Payload::text("Message"))
+            entity_velocity,
+            EntityVelocity {
+                entity_id: 12,
+                velocity_y: Velocity::from_blocks_per_tick(-0.25),
+                ..EntityVelocity::default()
+            }
         );
+        assert_eq!(EntityVelocity::default().velocity_x, Velocity(0));
     }
 
     #[test]
-    fn test_boss_bar_add_encode() {
-        let boss_bar_add = create_boss_bar_add_packet();
+    fn test_client_status_round_trip() {
+        let packet = ClientStatus::new(ClientStatusAction::RequestStats);
 
         let mut vec = Vec::new();
-        boss_bar_add.encode(&mut vec).unwrap();
+        packet.encode(&mut vec).unwrap();
 
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/game/boss_bar_add.dat").to_vec()
-        );
+        assert_eq!(vec, [1]);
+
+        match GameServerBoundPacket::decode(0x04, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::ClientStatus(client_status) => {
+                assert_eq!(client_status.action, ClientStatusAction::RequestStats)
+            }
+            _ => panic!("Expected client status packet"),
+        }
     }
 
     #[test]
-    fn test_boss_bar_add_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/game/boss_bar_add.dat").to_vec());
-        let boss_bar_add = BossBar::decode(&mut cursor).unwrap();
+    fn test_hand_swing_round_trip() {
+        let packet = HandSwing::new(InteractionHand::MainHand);
 
-        assert_eq!(boss_bar_add, create_boss_bar_add_packet());
-    }
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
 
-    fn create_boss_bar_add_packet() -> BossBar {
-        BossBar {
-            id: Uuid::from_str("afa32ac8-d3bf-47f3-99eb-294d60b3dca2").unwrap(),
-            action: BossBarAction::Add {
-                title: Message::from_str("Boss title"),
-                health: 123.45,
-                color: BossBarColor::Yellow,
-                division: BossBarDivision::Notches10,
-                flags: 7,
-            },
+        assert_eq!(vec, [0]);
+
+        match GameServerBoundPacket::decode(0x2A, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::HandSwing(hand_swing) => {
+                assert_eq!(hand_swing.hand, InteractionHand::MainHand)
+            }
+            _ => panic!("Expected hand swing packet"),
         }
     }
 
     #[test]
-    fn test_boss_bar_remove_encode() {
-        let boss_bar_remove = create_boss_bar_remove_packet();
+    fn test_open_book_round_trip() {
+        let packet = OpenBook::new(InteractionHand::OffHand);
 
         let mut vec = Vec::new();
-        boss_bar_remove.encode(&mut vec).unwrap();
+        packet.encode(&mut vec).unwrap();
 
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/game/boss_bar_remove.dat").to_vec()
-        );
+        match GameClientBoundPacket::decode(0x2D, &mut Cursor::new(vec)).unwrap() {
+            GameClientBoundPacket::OpenBook(open_book) => {
+                assert_eq!(open_book.hand, InteractionHand::OffHand)
+            }
+            _ => panic!("Expected open book packet"),
+        }
     }
 
     #[test]
-    fn test_boss_bar_remove_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/game/boss_bar_remove.dat").to_vec());
-        let boss_bar_remove = BossBar::decode(&mut cursor).unwrap();
+    fn test_plugin_message_new_payload_too_long() {
+        let data = vec![0; limits::SERVER_BOUND_PAYLOAD_MAX_LENGTH + 1];
+
+        let encode_error = ServerBoundPluginMessage::new(Identifier::from("mod:channel"), data)
+            .expect_err("Expected error `PayloadTooLong` because payload is too long");
 
-        assert_eq!(boss_bar_remove, create_boss_bar_remove_packet());
+        match encode_error {
+            EncodeError::PayloadTooLong { length, max_length } => {
+                assert_eq!(length, 32768);
+                assert_eq!(max_length, 32767);
+            }
+            _ => panic!("Expected `PayloadTooLong` but got `{:?}`", encode_error),
+        }
+
+        let data = vec![0; limits::SERVER_BOUND_PAYLOAD_MAX_LENGTH + 1];
+        assert!(ClientBoundPluginMessage::new(Identifier::from("mod:channel"), data).is_ok());
     }
 
-    fn create_boss_bar_remove_packet() -> BossBar {
-        BossBar {
-            id: Uuid::from_str("afa32ac8-d3bf-47f3-99eb-294d60b3dca2").unwrap(),
-            action: BossBarAction::Remove,
+    #[test]
+    fn test_plugin_message_new_invalid_channel() {
+        let encode_error = ClientBoundPluginMessage::new(Identifier::from("BungeeCord"), vec![])
+            .expect_err("Expected error `InvalidIdentifier` because of uppercase letters");
+
+        match encode_error {
+            EncodeError::InvalidIdentifier { value } => assert_eq!(value, "BungeeCord"),
+            _ => panic!("Expected `InvalidIdentifier` but got `{:?}`", encode_error),
         }
     }
 
     #[test]
-    fn test_entity_action_encode() {
-        let entity_action = EntityAction {
-            entity_id: 12345,
-            action_id: EntityActionId::StartFlyingWithElytra,
-            jump_boost: i32::MAX,
+    fn test_client_bound_plugin_message_decode_limit() {
+        let plugin_message = ClientBoundPluginMessage {
+            channel: Identifier::from("mod:channel"),
+            data: vec![0; 64],
         };
 
         let mut vec = Vec::new();
-        entity_action.encode(&mut vec).unwrap();
+        plugin_message.encode(&mut vec).unwrap();
 
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/game/entity_action.dat").to_vec()
-        );
+        let decode_limits = limits::DecodeLimits {
+            client_bound_payload_max_length: 32,
+            ..limits::DecodeLimits::default()
+        };
+
+        let decode_error = limits::with_limits(decode_limits, || {
+            ClientBoundPluginMessage::decode(&mut vec.as_slice())
+        })
+        .expect_err("Expected error `PayloadTooLong` because payload exceeds limit");
+
+        match decode_error {
+            DecodeError::PayloadTooLong { length, max_length } => {
+                assert_eq!(length, 64);
+                assert_eq!(max_length, 32);
+            }
+            _ => panic!("Expected `PayloadTooLong` but got `{:?}`", decode_error),
+        }
+
+        let plugin_message = ClientBoundPluginMessage::decode(&mut vec.as_slice()).unwrap();
+        assert_eq!(plugin_message.data.len(), 64);
     }
 
     #[test]
-    fn test_entity_action_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/game/entity_action.dat").to_vec());
-        let entity_action = EntityAction::decode(&mut cursor).unwrap();
+    fn test_select_advancements_tab_none() {
+        let mut vec = Vec::new();
+        SelectAdvancementsTab { identifier: None }
+            .encode(&mut vec)
+            .unwrap();
 
-        assert_eq!(
-            entity_action,
-            EntityAction {
-                entity_id: 12345,
-                action_id: EntityActionId::StartFlyingWithElytra,
-                jump_boost: i32::MAX,
+        assert_eq!(vec, [0]);
+
+        let select_advancements_tab = SelectAdvancementsTab::decode(&mut Cursor::new(vec)).unwrap();
+
+        assert_eq!(select_advancements_tab.identifier, None);
+    }
+
+    #[test]
+    fn test_visit_messages_rewrites_packets() {
+        let mut packets = vec![
+            ClientBoundChatMessage::new(Message::from_str("darn"), MessagePosition::Chat),
+            GameDisconnect::new(Message::from_str("darn")),
+            BossBar::new(
+                Uuid::nil(),
+                BossBarAction::UpdateTitle {
+                    title: Message::from_str("darn"),
+                },
+            ),
+            Title::new(TitleAction::SetSubtitle {
+                text: Message::from_str("darn"),
+            }),
+            CombatEvent::new(CombatEventType::EntityDead {
+                player_id: 1,
+                entity_id: -1,
+                message: Message::from_str("darn"),
+            }),
+            ClientBoundKeepAlive::new(1),
+        ];
+
+        let mut visited = 0;
+
+        for packet in &mut packets {
+            packet.visit_messages(&mut |message| {
+                if message.payload == Payload::text("darn") {
+                    message.payload = Payload::text("****");
+                }
+
+                visited += 1;
+            });
+        }
+
+        assert_eq!(visited, 5);
+
+        match &packets[2] {
+            GameClientBoundPacket::BossBar(BossBar {
+                action: BossBarAction::UpdateTitle { title },
+                ..
+            }) => assert_eq!(*title, Message::from_str("****")),
+            packet => panic!("Expected `BossBar` but got `{:?}`", packet),
+        }
+
+        match &packets[3] {
+            GameClientBoundPacket::Title(Title {
+                action: TitleAction::SetSubtitle { text },
+            }) => assert_eq!(*text, Message::from_str("****")),
+            packet => panic!("Expected `Title` but got `{:?}`", packet),
+        }
+    }
+
+    #[test]
+    fn test_update_sign_round_trip() {
+        let lines = [
+            String::from("Buy"),
+            String::from("diamond"),
+            String::from(""),
+            String::from("64"),
+        ];
+        let packet = UpdateSign::new(BlockPosition::new(10, 70, -3), lines);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        match GameServerBoundPacket::decode(0x29, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::UpdateSign(update_sign) => {
+                assert_eq!(update_sign.position, BlockPosition::new(10, 70, -3));
+                assert_eq!(update_sign.lines(), ["Buy", "diamond", "", "64"]);
             }
-        );
+            _ => panic!("Expected update sign packet"),
+        }
     }
 
     #[test]
-    fn test_serverbound_abilities_encode() {
-        let abilities = ServerBoundAbilities {
-            invulnerable: true,
-            flying: true,
-            allow_flying: false,
-            creative_mode: true,
-            fly_speed: 0.0,
-            walk_speed: 0.0,
-        };
+    fn test_open_sign_editor_encode() {
+        let packet = OpenSignEditor::new(BlockPosition::new(0, 1, 0));
 
         let mut vec = Vec::new();
-        abilities.encode(&mut vec).unwrap();
+        packet.encode(&mut vec).unwrap();
 
-        assert_eq!(vec, [13, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(packet.get_type_id(), 0x2F);
+        assert_eq!(vec, [0, 0, 0, 0, 0, 0, 0, 1]);
     }
 
     #[test]
-    fn test_serverbound_abilities_decode() {
-        let vec = [13, 0, 0, 0, 0, 0, 0, 0, 0].to_vec();
-        let mut cursor = Cursor::new(vec);
+    fn test_server_bound_tab_complete_encode() {
+        let packet = ServerBoundTabComplete::new(7, String::from("/gi"));
 
-        let abilities = ServerBoundAbilities::decode(&mut cursor).unwrap();
-        assert!(abilities.invulnerable);
-        assert!(!abilities.allow_flying);
-        assert!(abilities.flying);
-        assert!(abilities.creative_mode);
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(packet.get_type_id(), 0x06);
+        assert_eq!(vec, [7, 3, b'/', b'g', b'i']);
+    }
+
+    #[test]
+    fn test_client_bound_tab_complete_round_trip() {
+        let matches = vec![
+            TabCompleteMatch::new(String::from("give"), None),
+            TabCompleteMatch::new(
+                String::from("gamemode"),
+                Some(Message::from_str("Changes game mode")),
+            ),
+        ];
+        let mut packet = ClientBoundTabComplete::new(7, 1, 2, matches);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(packet.get_type_id(), 0x10);
+        assert_eq!(&vec[..4], [7, 1, 2, 2]);
+
+        let mut tooltips = 0;
+        packet.visit_messages(&mut |_| tooltips += 1);
+        assert_eq!(tooltips, 1);
+
+        match GameClientBoundPacket::decode(0x10, &mut Cursor::new(vec)).unwrap() {
+            GameClientBoundPacket::ClientBoundTabComplete(tab_complete) => {
+                assert_eq!(tab_complete.transaction_id, 7);
+                assert_eq!(tab_complete.start, 1);
+                assert_eq!(tab_complete.length, 2);
+                assert_eq!(tab_complete.matches.len(), 2);
+                assert_eq!(tab_complete.matches[0].value, "give");
+                assert_eq!(tab_complete.matches[0].tooltip, None);
+                assert_eq!(
+                    tab_complete.matches[1].tooltip,
+                    Some(Message::from_str("Changes game mode"))
+                );
+            }
+            _ => panic!("Expected tab complete packet"),
+        }
+    }
+
+    #[test]
+    fn test_block_entity_data_sign_round_trip() {
+        let lines = [
+            Message::from_str("Shop"),
+            Message::from_str(""),
+            Message::from_str(""),
+            Message::from_str(""),
+        ];
+        let packet = BlockEntityData::sign(BlockPosition::new(10, 70, -3), &lines).unwrap();
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(vec[8], 9);
+
+        match GameClientBoundPacket::decode(0x09, &mut Cursor::new(vec)).unwrap() {
+            GameClientBoundPacket::BlockEntityData(block_entity_data) => {
+                let mut block_entities = BlockEntities::new();
+                block_entity_data.apply_to(&mut block_entities);
+
+                assert_eq!(block_entities.len(), 1);
+                assert_eq!(block_entity_data.action, BlockEntityAction::Sign);
+                assert_eq!(
+                    crate::data::game::sign_lines(&block_entity_data.data),
+                    Some(lines)
+                );
+            }
+            _ => panic!("Expected block entity data packet"),
+        }
     }
 }
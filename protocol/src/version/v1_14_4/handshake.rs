@@ -1,8 +1,15 @@
 use crate::decoder::Decoder;
-use crate::error::DecodeError;
-use minecraft_protocol_derive::{Decoder, Encoder};
-use std::io::Read;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, Validate, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
 
+#[derive(VisitMessages, PacketVariants, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum HandshakeServerBoundPacket {
     Handshake(Handshake),
 }
@@ -14,6 +21,17 @@ impl HandshakeServerBoundPacket {
         }
     }
 
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandshakeServerBoundPacket::Handshake(_) => "Handshake",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x00].iter().copied()
+    }
+
     pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
         match type_id {
             0x00 => {
@@ -23,17 +41,37 @@ impl HandshakeServerBoundPacket {
             _ => Err(DecodeError::UnknownPacketType { type_id }),
         }
     }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            HandshakeServerBoundPacket::Handshake(handshake) => handshake.encode(writer),
+        }
+    }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+impl_packet!(HandshakeServerBoundPacket);
+
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Handshake {
     #[data_type(with = "var_int")]
     pub protocol_version: i32,
     #[data_type(max_length = 255)]
     pub server_addr: String,
+    #[validate(min = 1)]
     pub server_port: u16,
-    #[data_type(with = "var_int")]
-    pub next_state: i32,
+    pub next_state: NextState,
+}
+
+/// State client switches to after handshake.
+#[derive(Encoder, Decoder, Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[data_type(with = "var_int")]
+pub enum NextState {
+    Status = 1,
+    Login = 2,
+    /// Login of client transferred from another server, added in 1.20.5.
+    Transfer = 3,
 }
 
 impl Handshake {
@@ -41,7 +79,7 @@ impl Handshake {
         protocol_version: i32,
         server_addr: String,
         server_port: u16,
-        next_state: i32,
+        next_state: NextState,
     ) -> HandshakeServerBoundPacket {
         let handshake = Handshake {
             protocol_version,
@@ -53,3 +91,38 @@ impl Handshake {
         HandshakeServerBoundPacket::Handshake(handshake)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::version::v1_14_4::handshake::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_handshake_transfer_round_trip() {
+        let packet = Handshake::new(766, String::from("localhost"), 25565, NextState::Transfer);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(vec.last(), Some(&3));
+
+        match HandshakeServerBoundPacket::decode(0x00, &mut Cursor::new(vec)).unwrap() {
+            HandshakeServerBoundPacket::Handshake(handshake) => {
+                assert_eq!(handshake.next_state, NextState::Transfer)
+            }
+        }
+    }
+
+    #[test]
+    fn test_handshake_unknown_next_state() {
+        let vec = vec![0xF2, 0x05, 0, 0x63, 0xDD, 4];
+
+        let decode_error = Handshake::decode(&mut Cursor::new(vec))
+            .expect_err("Expected error `UnknownEnumType` because next state is unknown");
+
+        match decode_error {
+            DecodeError::UnknownEnumType { type_id } => assert_eq!(type_id, 4),
+            _ => panic!("Expected `UnknownEnumType` but got `{:?}`", decode_error),
+        }
+    }
+}
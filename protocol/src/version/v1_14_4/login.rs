@@ -1,19 +1,29 @@
-use std::io::Read;
+use std::io::{Read, Write};
 use uuid::Uuid;
 
 use crate::data::chat::Message;
 use crate::decoder::Decoder;
-use crate::error::DecodeError;
-use minecraft_protocol_derive::{Decoder, Encoder};
-
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, Validate, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(VisitMessages, PacketVariants, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum LoginServerBoundPacket {
     LoginStart(LoginStart),
     EncryptionResponse(EncryptionResponse),
     LoginPluginResponse(LoginPluginResponse),
 }
 
+#[derive(VisitMessages, PacketVariants, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum LoginClientBoundPacket {
-    LoginDisconnect(LoginDisconnect),
+    LoginDisconnect(#[visit] LoginDisconnect),
     EncryptionRequest(EncryptionRequest),
     LoginSuccess(LoginSuccess),
     SetCompression(SetCompression),
@@ -29,6 +39,19 @@ impl LoginServerBoundPacket {
         }
     }
 
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoginServerBoundPacket::LoginStart(_) => "LoginStart",
+            LoginServerBoundPacket::EncryptionResponse(_) => "EncryptionResponse",
+            LoginServerBoundPacket::LoginPluginResponse(_) => "LoginPluginResponse",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x00, 0x01, 0x02].iter().copied()
+    }
+
     pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
         match type_id {
             0x00 => {
@@ -53,8 +76,22 @@ impl LoginServerBoundPacket {
             _ => Err(DecodeError::UnknownPacketType { type_id }),
         }
     }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            LoginServerBoundPacket::LoginStart(login_start) => login_start.encode(writer),
+            LoginServerBoundPacket::EncryptionResponse(encryption_response) => {
+                encryption_response.encode(writer)
+            }
+            LoginServerBoundPacket::LoginPluginResponse(login_plugin_response) => {
+                login_plugin_response.encode(writer)
+            }
+        }
+    }
 }
 
+impl_packet!(LoginServerBoundPacket);
+
 impl LoginClientBoundPacket {
     pub fn get_type_id(&self) -> u8 {
         match self {
@@ -66,6 +103,21 @@ impl LoginClientBoundPacket {
         }
     }
 
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoginClientBoundPacket::LoginDisconnect(_) => "LoginDisconnect",
+            LoginClientBoundPacket::EncryptionRequest(_) => "EncryptionRequest",
+            LoginClientBoundPacket::LoginSuccess(_) => "LoginSuccess",
+            LoginClientBoundPacket::SetCompression(_) => "SetCompression",
+            LoginClientBoundPacket::LoginPluginRequest(_) => "LoginPluginRequest",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x00, 0x01, 0x02, 0x03, 0x04].iter().copied()
+    }
+
     pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
         match type_id {
             0x00 => {
@@ -100,10 +152,32 @@ impl LoginClientBoundPacket {
             _ => Err(DecodeError::UnknownPacketType { type_id }),
         }
     }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            LoginClientBoundPacket::LoginDisconnect(login_disconnect) => {
+                login_disconnect.encode(writer)
+            }
+            LoginClientBoundPacket::EncryptionRequest(encryption_request) => {
+                encryption_request.encode(writer)
+            }
+            LoginClientBoundPacket::LoginSuccess(login_success) => login_success.encode(writer),
+            LoginClientBoundPacket::SetCompression(set_compression) => {
+                set_compression.encode(writer)
+            }
+            LoginClientBoundPacket::LoginPluginRequest(login_plugin_request) => {
+                login_plugin_request.encode(writer)
+            }
+        }
+    }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+impl_packet!(LoginClientBoundPacket);
+
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LoginStart {
+    #[validate(max_length = 16)]
     pub name: String,
 }
 
@@ -115,7 +189,8 @@ impl LoginStart {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EncryptionResponse {
     pub shared_secret: Vec<u8>,
     pub verify_token: Vec<u8>,
@@ -132,7 +207,8 @@ impl EncryptionResponse {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LoginPluginResponse {
     #[data_type(with = "var_int")]
     pub message_id: i32,
@@ -153,7 +229,8 @@ impl LoginPluginResponse {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, VisitMessages, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LoginDisconnect {
     pub reason: Message,
 }
@@ -166,7 +243,8 @@ impl LoginDisconnect {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EncryptionRequest {
     #[data_type(max_length = 20)]
     pub server_id: String,
@@ -190,7 +268,8 @@ impl EncryptionRequest {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LoginSuccess {
     #[data_type(with = "uuid_hyp_str")]
     pub uuid: Uuid,
@@ -206,7 +285,8 @@ impl LoginSuccess {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SetCompression {
     #[data_type(with = "var_int")]
     pub threshold: i32,
@@ -220,7 +300,8 @@ impl SetCompression {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LoginPluginRequest {
     #[data_type(with = "var_int")]
     pub message_id: i32,
@@ -244,239 +325,47 @@ impl LoginPluginRequest {
 #[cfg(test)]
 mod tests {
     use crate::data::chat::Payload;
-    use crate::decoder::Decoder;
-    use crate::encoder::Encoder;
+    use crate::packet::roundtrip_tests;
     use crate::version::v1_14_4::login::*;
-    use std::io::Cursor;
     use uuid::Uuid;
 
-    #[test]
-    fn test_login_start_packet_encode() {
-        let login_start = LoginStart {
+    roundtrip_tests! {
+        test_login_start_packet_roundtrip: LoginStart = LoginStart {
             name: String::from("Username"),
-        };
+        } => "login/login_start.dat";
 
-        let mut vec = Vec::new();
-        login_start.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/login/login_start.dat").to_vec()
-        );
-    }
-
-    #[test]
-    fn test_login_start_packet_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/login/login_start.dat").to_vec());
-        let login_start = LoginStart::decode(&mut cursor).unwrap();
-
-        assert_eq!(login_start.name, String::from("Username"));
-    }
-
-    #[test]
-    fn test_encryption_response_encode() {
-        let encryption_response = EncryptionResponse {
+        test_encryption_response_roundtrip: EncryptionResponse = EncryptionResponse {
             shared_secret: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
             verify_token: vec![1, 2, 3, 4],
-        };
-
-        let mut vec = Vec::new();
-        encryption_response.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/login/encryption_response.dat").to_vec()
-        );
-    }
-
-    #[test]
-    fn test_encryption_response_decode() {
-        let mut cursor = Cursor::new(
-            include_bytes!("../../../test/packet/login/encryption_response.dat").to_vec(),
-        );
-        let encryption_response = EncryptionResponse::decode(&mut cursor).unwrap();
-
-        assert_eq!(
-            encryption_response.shared_secret,
-            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
-        );
-        assert_eq!(encryption_response.verify_token, vec![1, 2, 3, 4]);
-    }
+        } => "login/encryption_response.dat";
 
-    #[test]
-    fn test_login_plugin_response_encode() {
-        let login_plugin_response = LoginPluginResponse {
+        test_login_plugin_response_roundtrip: LoginPluginResponse = LoginPluginResponse {
             message_id: 55,
             successful: true,
             data: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
-        };
-
-        let mut vec = Vec::new();
-        login_plugin_response.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/login/login_plugin_response.dat").to_vec()
-        );
-    }
+        } => "login/login_plugin_response.dat";
 
-    #[test]
-    fn test_login_plugin_response_decode() {
-        let mut cursor = Cursor::new(
-            include_bytes!("../../../test/packet/login/login_plugin_response.dat").to_vec(),
-        );
-        let login_plugin_response = LoginPluginResponse::decode(&mut cursor).unwrap();
-
-        assert_eq!(login_plugin_response.message_id, 55);
-        assert!(login_plugin_response.successful);
-        assert_eq!(
-            login_plugin_response.data,
-            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
-        );
-    }
-
-    #[test]
-    fn test_login_disconnect_encode() {
-        let login_disconnect = LoginDisconnect {
+        test_login_disconnect_roundtrip: LoginDisconnect = LoginDisconnect {
             reason: Message::new(Payload::text("Message")),
-        };
+        } => "login/login_disconnect.dat";
 
-        let mut vec = Vec::new();
-        login_disconnect.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/login/login_disconnect.dat").to_vec()
-        );
-    }
-
-    #[test]
-    fn test_login_disconnect_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/login/login_disconnect.dat").to_vec());
-        let login_disconnect = LoginDisconnect::decode(&mut cursor).unwrap();
-
-        assert_eq!(
-            login_disconnect.reason,
-            Message::new(Payload::text("Message"))
-        );
-    }
-
-    #[test]
-    fn test_encryption_request_encode() {
-        let encryption_request = EncryptionRequest {
+        test_encryption_request_roundtrip: EncryptionRequest = EncryptionRequest {
             server_id: String::from("ServerID"),
             public_key: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
             verify_token: vec![1, 2, 3, 4],
-        };
-
-        let mut vec = Vec::new();
-        encryption_request.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/login/encryption_request.dat").to_vec()
-        );
-    }
-
-    #[test]
-    fn test_encryption_request_decode() {
-        let mut cursor = Cursor::new(
-            include_bytes!("../../../test/packet/login/encryption_request.dat").to_vec(),
-        );
-        let encryption_request = EncryptionRequest::decode(&mut cursor).unwrap();
-
-        assert_eq!(encryption_request.server_id, String::from("ServerID"));
-        assert_eq!(
-            encryption_request.public_key,
-            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
-        );
-        assert_eq!(encryption_request.verify_token, vec![1, 2, 3, 4]);
-    }
+        } => "login/encryption_request.dat";
 
-    #[test]
-    fn test_login_success_encode() {
-        let login_success = LoginSuccess {
+        test_login_success_roundtrip: LoginSuccess = LoginSuccess {
             uuid: Uuid::parse_str("35ee313b-d89a-41b8-b25e-d32e8aff0389").unwrap(),
             username: String::from("Username"),
-        };
-
-        let mut vec = Vec::new();
-        login_success.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/login/login_success.dat").to_vec()
-        );
-    }
-
-    #[test]
-    fn test_login_success_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/login/login_success.dat").to_vec());
-        let login_success = LoginSuccess::decode(&mut cursor).unwrap();
+        } => "login/login_success.dat";
 
-        assert_eq!(login_success.username, String::from("Username"));
+        test_set_compression_roundtrip: SetCompression = SetCompression { threshold: 1 } => "login/login_set_compression.dat";
 
-        assert_eq!(
-            login_success.uuid,
-            Uuid::parse_str("35ee313b-d89a-41b8-b25e-d32e8aff0389").unwrap()
-        );
-    }
-
-    #[test]
-    fn test_set_compression_encode() {
-        let set_compression = SetCompression { threshold: 1 };
-
-        let mut vec = Vec::new();
-        set_compression.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/login/login_set_compression.dat").to_vec()
-        );
-    }
-
-    #[test]
-    fn test_set_compression_decode() {
-        let mut cursor = Cursor::new(
-            include_bytes!("../../../test/packet/login/login_set_compression.dat").to_vec(),
-        );
-        let set_compression = SetCompression::decode(&mut cursor).unwrap();
-
-        assert_eq!(set_compression.threshold, 1);
-    }
-
-    #[test]
-    fn test_login_plugin_request_encode() {
-        let login_plugin_request = LoginPluginRequest {
+        test_login_plugin_request_roundtrip: LoginPluginRequest = LoginPluginRequest {
             message_id: 55,
             channel: String::from("Channel"),
             data: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
-        };
-
-        let mut vec = Vec::new();
-        login_plugin_request.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/login/login_plugin_request.dat").to_vec()
-        );
-    }
-
-    #[test]
-    fn test_login_plugin_request_decode() {
-        let mut cursor = Cursor::new(
-            include_bytes!("../../../test/packet/login/login_plugin_request.dat").to_vec(),
-        );
-        let login_plugin_request = LoginPluginRequest::decode(&mut cursor).unwrap();
-
-        assert_eq!(login_plugin_request.message_id, 55);
-        assert_eq!(login_plugin_request.channel, String::from("Channel"));
-        assert_eq!(
-            login_plugin_request.data,
-            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
-        );
+        } => "login/login_plugin_request.dat";
     }
 }
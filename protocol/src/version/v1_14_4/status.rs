@@ -1,16 +1,26 @@
 use crate::data::server_status::*;
 use crate::decoder::Decoder;
-use crate::error::DecodeError;
-use minecraft_protocol_derive::{Decoder, Encoder};
-use std::io::Read;
-
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, Validate, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+#[derive(VisitMessages, PacketVariants, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum StatusServerBoundPacket {
     StatusRequest,
     PingRequest(PingRequest),
 }
 
+#[derive(VisitMessages, PacketVariants, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum StatusClientBoundPacket {
-    StatusResponse(StatusResponse),
+    StatusResponse(#[visit] StatusResponse),
     PingResponse(PingResponse),
 }
 
@@ -22,6 +32,18 @@ impl StatusServerBoundPacket {
         }
     }
 
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusServerBoundPacket::StatusRequest => "StatusRequest",
+            StatusServerBoundPacket::PingRequest(_) => "PingRequest",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x00, 0x01].iter().copied()
+    }
+
     pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
         match type_id {
             0x00 => Ok(StatusServerBoundPacket::StatusRequest),
@@ -33,8 +55,17 @@ impl StatusServerBoundPacket {
             _ => Err(DecodeError::UnknownPacketType { type_id }),
         }
     }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            StatusServerBoundPacket::StatusRequest => Ok(()),
+            StatusServerBoundPacket::PingRequest(ping_request) => ping_request.encode(writer),
+        }
+    }
 }
 
+impl_packet!(StatusServerBoundPacket);
+
 impl StatusClientBoundPacket {
     pub fn get_type_id(&self) -> u8 {
         match self {
@@ -42,9 +73,49 @@ impl StatusClientBoundPacket {
             StatusClientBoundPacket::PingResponse(_) => 0x01,
         }
     }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusClientBoundPacket::StatusResponse(_) => "StatusResponse",
+            StatusClientBoundPacket::PingResponse(_) => "PingResponse",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x00, 0x01].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x00 => {
+                let status_response = StatusResponse::decode(reader)?;
+
+                Ok(StatusClientBoundPacket::StatusResponse(status_response))
+            }
+            0x01 => {
+                let ping_response = PingResponse::decode(reader)?;
+
+                Ok(StatusClientBoundPacket::PingResponse(ping_response))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            StatusClientBoundPacket::StatusResponse(status_response) => {
+                status_response.encode(writer)
+            }
+            StatusClientBoundPacket::PingResponse(ping_response) => ping_response.encode(writer),
+        }
+    }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+impl_packet!(StatusClientBoundPacket);
+
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PingRequest {
     pub time: u64,
 }
@@ -57,7 +128,8 @@ impl PingRequest {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PingResponse {
     pub time: u64,
 }
@@ -70,8 +142,10 @@ impl PingResponse {
     }
 }
 
-#[derive(Encoder, Decoder, Debug)]
+#[derive(Encoder, Decoder, VisitMessages, Validate, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct StatusResponse {
+    #[visit]
     pub server_status: ServerStatus,
 }
 
@@ -86,115 +160,59 @@ impl StatusResponse {
 #[cfg(test)]
 mod tests {
     use crate::data::chat::{Message, Payload};
-    use crate::decoder::Decoder;
-    use crate::encoder::Encoder;
+    use crate::packet::roundtrip_tests;
     use crate::version::v1_14_4::status::*;
     use std::io::Cursor;
     use uuid::Uuid;
 
-    #[test]
-    fn test_ping_request_encode() {
-        let ping_request = PingRequest {
+    roundtrip_tests! {
+        test_ping_request_roundtrip: PingRequest = PingRequest {
             time: 1577735845610,
-        };
+        } => "status/ping_request.dat";
 
-        let mut vec = Vec::new();
-        ping_request.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/status/ping_request.dat").to_vec()
-        );
-    }
-
-    #[test]
-    fn test_status_ping_request_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/status/ping_request.dat").to_vec());
-        let ping_request = PingRequest::decode(&mut cursor).unwrap();
-
-        assert_eq!(ping_request.time, 1577735845610);
-    }
-
-    #[test]
-    fn test_ping_response_encode() {
-        let ping_response = PingResponse {
+        test_ping_response_roundtrip: PingResponse = PingResponse {
             time: 1577735845610,
-        };
-
-        let mut vec = Vec::new();
-        ping_response.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/status/ping_response.dat").to_vec()
-        );
+        } => "status/ping_response.dat";
+
+        test_status_response_roundtrip: StatusResponse = {
+            let version = ServerVersion {
+                name: String::from("1.15.1"),
+                protocol: 575,
+            };
+
+            let player = OnlinePlayer {
+                id: Uuid::parse_str("2a1e1912-7103-4add-80fc-91ebc346cbce").unwrap(),
+                name: String::from("Username"),
+            };
+
+            let players = OnlinePlayers {
+                online: 10,
+                max: 100,
+                sample: vec![player],
+            };
+
+            let server_status = ServerStatus {
+                version,
+                description: Message::new(Payload::text("Description")),
+                favicon: None,
+                enforces_secure_chat: None,
+                players,
+            };
+
+            StatusResponse { server_status }
+        } => "status/status_response.dat";
     }
 
     #[test]
-    fn test_status_ping_response_decode() {
+    fn test_status_client_bound_packet_decode() {
         let mut cursor =
             Cursor::new(include_bytes!("../../../test/packet/status/ping_response.dat").to_vec());
-        let ping_response = PingResponse::decode(&mut cursor).unwrap();
-
-        assert_eq!(ping_response.time, 1577735845610);
-    }
-
-    #[test]
-    fn test_status_response_encode() {
-        let version = ServerVersion {
-            name: String::from("1.15.1"),
-            protocol: 575,
-        };
-
-        let player = OnlinePlayer {
-            id: Uuid::parse_str("2a1e1912-7103-4add-80fc-91ebc346cbce").unwrap(),
-            name: String::from("Username"),
-        };
-
-        let players = OnlinePlayers {
-            online: 10,
-            max: 100,
-            sample: vec![player],
-        };
-
-        let server_status = ServerStatus {
-            version,
-            description: Message::new(Payload::text("Description")),
-            players,
-        };
-
-        let status_response = StatusResponse { server_status };
 
-        let mut vec = Vec::new();
-        status_response.encode(&mut vec).unwrap();
-
-        assert_eq!(
-            vec,
-            include_bytes!("../../../test/packet/status/status_response.dat").to_vec()
-        );
-    }
-
-    #[test]
-    fn test_status_response_decode() {
-        let mut cursor =
-            Cursor::new(include_bytes!("../../../test/packet/status/status_response.dat").to_vec());
-        let status_response = StatusResponse::decode(&mut cursor).unwrap();
-        let server_status = status_response.server_status;
-
-        let player = OnlinePlayer {
-            id: Uuid::parse_str("2a1e1912-7103-4add-80fc-91ebc346cbce").unwrap(),
-            name: String::from("Username"),
-        };
-
-        assert_eq!(server_status.version.name, String::from("1.15.1"));
-        assert_eq!(server_status.version.protocol, 575);
-        assert_eq!(server_status.players.max, 100);
-        assert_eq!(server_status.players.online, 10);
-        assert_eq!(server_status.players.sample, vec![player]);
-        assert_eq!(
-            server_status.description,
-            Message::new(Payload::text("Description"))
-        );
+        match StatusClientBoundPacket::decode(0x01, &mut cursor).unwrap() {
+            StatusClientBoundPacket::PingResponse(ping_response) => {
+                assert_eq!(ping_response.time, 1577735845610)
+            }
+            packet => panic!("Expected ping response but got {:?}", packet),
+        }
     }
 }
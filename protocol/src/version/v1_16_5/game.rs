@@ -0,0 +1,402 @@
+use crate::data::chat::Message;
+#[cfg(feature = "serde")]
+use crate::data::compound_tag;
+use crate::data::identifier::Identifier;
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Builder, Decoder, Encoder, PacketVariants, VisitMessages};
+use nbt::CompoundTag;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use uuid::Uuid;
+
+pub use crate::data::game::{ClientStatusAction, MessagePosition, TitleAction};
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameServerBoundPacket {
+    ServerBoundChatMessage(ServerBoundChatMessage),
+    ClientStatus(ClientStatus),
+    ServerBoundKeepAlive(ServerBoundKeepAlive),
+}
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameClientBoundPacket {
+    ClientBoundChatMessage(#[visit] ClientBoundChatMessage),
+    ClientBoundKeepAlive(ClientBoundKeepAlive),
+    GameDisconnect(#[visit] GameDisconnect),
+    JoinGame(JoinGame),
+    Title(#[visit] Title),
+}
+
+impl GameServerBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameServerBoundPacket::ServerBoundChatMessage(_) => 0x03,
+            GameServerBoundPacket::ClientStatus(_) => 0x04,
+            GameServerBoundPacket::ServerBoundKeepAlive(_) => 0x10,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameServerBoundPacket::ServerBoundChatMessage(_) => "ServerBoundChatMessage",
+            GameServerBoundPacket::ClientStatus(_) => "ClientStatus",
+            GameServerBoundPacket::ServerBoundKeepAlive(_) => "ServerBoundKeepAlive",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x03, 0x04, 0x10].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x03 => {
+                let chat_message = ServerBoundChatMessage::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ServerBoundChatMessage(chat_message))
+            }
+            0x04 => {
+                let client_status = ClientStatus::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ClientStatus(client_status))
+            }
+            0x10 => {
+                let keep_alive = ServerBoundKeepAlive::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ServerBoundKeepAlive(keep_alive))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameServerBoundPacket::ServerBoundChatMessage(chat_message) => {
+                chat_message.encode(writer)
+            }
+            GameServerBoundPacket::ClientStatus(client_status) => client_status.encode(writer),
+            GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => keep_alive.encode(writer),
+        }
+    }
+}
+
+impl_packet!(GameServerBoundPacket);
+
+impl GameClientBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameClientBoundPacket::ClientBoundChatMessage(_) => 0x0E,
+            GameClientBoundPacket::GameDisconnect(_) => 0x19,
+            GameClientBoundPacket::ClientBoundKeepAlive(_) => 0x1F,
+            GameClientBoundPacket::JoinGame(_) => 0x24,
+            GameClientBoundPacket::Title(_) => 0x4F,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameClientBoundPacket::ClientBoundChatMessage(_) => "ClientBoundChatMessage",
+            GameClientBoundPacket::GameDisconnect(_) => "GameDisconnect",
+            GameClientBoundPacket::ClientBoundKeepAlive(_) => "ClientBoundKeepAlive",
+            GameClientBoundPacket::JoinGame(_) => "JoinGame",
+            GameClientBoundPacket::Title(_) => "Title",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x0E, 0x19, 0x1F, 0x24, 0x4F].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x0E => {
+                let chat_message = ClientBoundChatMessage::decode(reader)?;
+
+                Ok(GameClientBoundPacket::ClientBoundChatMessage(chat_message))
+            }
+            0x19 => {
+                let game_disconnect = GameDisconnect::decode(reader)?;
+
+                Ok(GameClientBoundPacket::GameDisconnect(game_disconnect))
+            }
+            0x1F => {
+                let keep_alive = ClientBoundKeepAlive::decode(reader)?;
+
+                Ok(GameClientBoundPacket::ClientBoundKeepAlive(keep_alive))
+            }
+            0x24 => {
+                let join_game = JoinGame::decode(reader)?;
+
+                Ok(GameClientBoundPacket::JoinGame(join_game))
+            }
+            0x4F => {
+                let title = Title::decode(reader)?;
+
+                Ok(GameClientBoundPacket::Title(title))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameClientBoundPacket::ClientBoundChatMessage(chat_message) => {
+                chat_message.encode(writer)
+            }
+            GameClientBoundPacket::ClientBoundKeepAlive(keep_alive) => keep_alive.encode(writer),
+            GameClientBoundPacket::GameDisconnect(game_disconnect) => {
+                game_disconnect.encode(writer)
+            }
+            GameClientBoundPacket::JoinGame(join_game) => join_game.encode(writer),
+            GameClientBoundPacket::Title(title) => title.encode(writer),
+        }
+    }
+}
+
+impl_packet!(GameClientBoundPacket);
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ServerBoundChatMessage {
+    #[data_type(max_length = 256)]
+    pub message: String,
+}
+
+impl ServerBoundChatMessage {
+    pub fn new(message: String) -> GameServerBoundPacket {
+        let chat_message = ServerBoundChatMessage { message };
+
+        GameServerBoundPacket::ServerBoundChatMessage(chat_message)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientStatus {
+    pub action: ClientStatusAction,
+}
+
+impl ClientStatus {
+    pub fn new(action: ClientStatusAction) -> GameServerBoundPacket {
+        let client_status = ClientStatus { action };
+
+        GameServerBoundPacket::ClientStatus(client_status)
+    }
+}
+
+#[derive(Encoder, Decoder, VisitMessages, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientBoundChatMessage {
+    pub message: Message,
+    pub position: MessagePosition,
+    /// Sender of the message. Nil for messages not sent by a player.
+    pub sender: Uuid,
+}
+
+impl ClientBoundChatMessage {
+    pub fn new(message: Message, position: MessagePosition, sender: Uuid) -> GameClientBoundPacket {
+        let chat_message = ClientBoundChatMessage {
+            message,
+            position,
+            sender,
+        };
+
+        GameClientBoundPacket::ClientBoundChatMessage(chat_message)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ServerBoundKeepAlive {
+    pub id: u64,
+}
+
+impl ServerBoundKeepAlive {
+    pub fn new(id: u64) -> GameServerBoundPacket {
+        let keep_alive = ServerBoundKeepAlive { id };
+
+        GameServerBoundPacket::ServerBoundKeepAlive(keep_alive)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientBoundKeepAlive {
+    pub id: u64,
+}
+
+impl ClientBoundKeepAlive {
+    pub fn new(id: u64) -> GameClientBoundPacket {
+        let keep_alive = ClientBoundKeepAlive { id };
+
+        GameClientBoundPacket::ClientBoundKeepAlive(keep_alive)
+    }
+}
+
+#[derive(Encoder, Decoder, VisitMessages, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameDisconnect {
+    pub reason: Message,
+}
+
+impl GameDisconnect {
+    pub fn new(reason: Message) -> GameClientBoundPacket {
+        let game_disconnect = GameDisconnect { reason };
+
+        GameClientBoundPacket::GameDisconnect(game_disconnect)
+    }
+}
+
+/// Since 1.16 server sends registry of dimension types and biomes as NBT, dimension of player
+/// is a dimension type from that registry.
+#[derive(Encoder, Decoder, Builder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JoinGame {
+    pub entity_id: u32,
+    #[builder(default)]
+    pub hardcore: bool,
+    pub game_mode: GameMode,
+    /// Game mode before the last change, -1 if there was none.
+    pub previous_game_mode: i8,
+    #[data_type(with = "list")]
+    pub world_names: Vec<Identifier>,
+    #[cfg_attr(feature = "serde", serde(with = "compound_tag"))]
+    pub dimension_codec: CompoundTag,
+    #[cfg_attr(feature = "serde", serde(with = "compound_tag"))]
+    pub dimension: CompoundTag,
+    pub world_name: Identifier,
+    /// First 8 bytes of SHA-256 hash of world seed.
+    pub hashed_seed: i64,
+    /// Ignored by client.
+    #[data_type(with = "var_int")]
+    pub max_players: i32,
+    #[data_type(with = "var_int")]
+    pub view_distance: i32,
+    #[builder(default)]
+    pub reduced_debug_info: bool,
+    pub enable_respawn_screen: bool,
+    #[builder(default)]
+    pub debug: bool,
+    #[builder(default)]
+    pub flat: bool,
+}
+
+/// Game mode without hardcore flag, which is a separate field since 1.16.
+#[derive(Encoder, Decoder, Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GameMode {
+    Survival = 0,
+    Creative = 1,
+    Adventure = 2,
+    Spectator = 3,
+}
+
+#[derive(Encoder, Decoder, VisitMessages, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Title {
+    #[visit]
+    pub action: TitleAction,
+}
+
+impl Title {
+    pub fn new(action: TitleAction) -> GameClientBoundPacket {
+        let title = Title { action };
+
+        GameClientBoundPacket::Title(title)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::chat::Message;
+    use crate::data::identifier::Identifier;
+    use crate::decoder::Decoder;
+    use crate::encoder::Encoder;
+    use crate::version::v1_16_5::game::*;
+    use nbt::CompoundTag;
+    use std::io::Cursor;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_client_bound_chat_message_encode() {
+        let chat_message = ClientBoundChatMessage {
+            message: Message::from_str("hello client!"),
+            position: MessagePosition::Chat,
+            sender: Uuid::parse_str("35ee313b-d89a-41b8-b25e-d32e8aff0389").unwrap(),
+        };
+
+        let mut vec = Vec::new();
+        chat_message.encode(&mut vec).unwrap();
+
+        assert_eq!(
+            vec,
+            include_bytes!("../../../test/packet/v1_16_5/game/client_bound_chat_message.dat")
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn test_client_bound_chat_message_decode() {
+        let mut cursor = Cursor::new(
+            include_bytes!("../../../test/packet/v1_16_5/game/client_bound_chat_message.dat")
+                .to_vec(),
+        );
+        let chat_message = ClientBoundChatMessage::decode(&mut cursor).unwrap();
+
+        assert_eq!(chat_message.message, Message::from_str("hello client!"));
+        assert_eq!(chat_message.position, MessagePosition::Chat);
+        assert_eq!(
+            chat_message.sender,
+            Uuid::parse_str("35ee313b-d89a-41b8-b25e-d32e8aff0389").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_join_game_roundtrip() {
+        let mut dimension = CompoundTag::new();
+        dimension.insert_str("effects", "minecraft:overworld");
+
+        let overworld = Identifier::from("overworld");
+        let join_game = JoinGame::builder()
+            .entity_id(27)
+            .game_mode(GameMode::Creative)
+            .previous_game_mode(-1)
+            .world_names(vec![overworld.clone()])
+            .dimension_codec(CompoundTag::new())
+            .dimension(dimension)
+            .world_name(overworld.clone())
+            .hashed_seed(-4)
+            .max_players(100)
+            .view_distance(10)
+            .enable_respawn_screen(true)
+            .build()
+            .unwrap();
+
+        let mut vec = Vec::new();
+        join_game.encode(&mut vec).unwrap();
+
+        let join_game = JoinGame::decode(&mut Cursor::new(vec)).unwrap();
+
+        assert_eq!(join_game.game_mode, GameMode::Creative);
+        assert_eq!(join_game.previous_game_mode, -1);
+        assert_eq!(join_game.world_names, vec![overworld.clone()]);
+        assert_eq!(
+            join_game.dimension.get_str("effects").unwrap(),
+            "minecraft:overworld"
+        );
+        assert_eq!(join_game.world_name, overworld);
+        assert_eq!(join_game.hashed_seed, -4);
+        assert!(join_game.enable_respawn_screen);
+        assert!(!join_game.hardcore && !join_game.debug && !join_game.flat);
+    }
+}
@@ -0,0 +1,336 @@
+use std::io::{Read, Write};
+use uuid::Uuid;
+
+use crate::data::chat::Message;
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum LoginServerBoundPacket {
+    LoginStart(LoginStart),
+    EncryptionResponse(EncryptionResponse),
+    LoginPluginResponse(LoginPluginResponse),
+}
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum LoginClientBoundPacket {
+    LoginDisconnect(#[visit] LoginDisconnect),
+    EncryptionRequest(EncryptionRequest),
+    LoginSuccess(LoginSuccess),
+    SetCompression(SetCompression),
+    LoginPluginRequest(LoginPluginRequest),
+}
+
+impl LoginServerBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            LoginServerBoundPacket::LoginStart(_) => 0x00,
+            LoginServerBoundPacket::EncryptionResponse(_) => 0x01,
+            LoginServerBoundPacket::LoginPluginResponse(_) => 0x02,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoginServerBoundPacket::LoginStart(_) => "LoginStart",
+            LoginServerBoundPacket::EncryptionResponse(_) => "EncryptionResponse",
+            LoginServerBoundPacket::LoginPluginResponse(_) => "LoginPluginResponse",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x00, 0x01, 0x02].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x00 => {
+                let login_start = LoginStart::decode(reader)?;
+
+                Ok(LoginServerBoundPacket::LoginStart(login_start))
+            }
+            0x01 => {
+                let encryption_response = EncryptionResponse::decode(reader)?;
+
+                Ok(LoginServerBoundPacket::EncryptionResponse(
+                    encryption_response,
+                ))
+            }
+            0x02 => {
+                let login_plugin_response = LoginPluginResponse::decode(reader)?;
+
+                Ok(LoginServerBoundPacket::LoginPluginResponse(
+                    login_plugin_response,
+                ))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            LoginServerBoundPacket::LoginStart(login_start) => login_start.encode(writer),
+            LoginServerBoundPacket::EncryptionResponse(encryption_response) => {
+                encryption_response.encode(writer)
+            }
+            LoginServerBoundPacket::LoginPluginResponse(login_plugin_response) => {
+                login_plugin_response.encode(writer)
+            }
+        }
+    }
+}
+
+impl_packet!(LoginServerBoundPacket);
+
+impl LoginClientBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            LoginClientBoundPacket::LoginDisconnect(_) => 0x00,
+            LoginClientBoundPacket::EncryptionRequest(_) => 0x01,
+            LoginClientBoundPacket::LoginSuccess(_) => 0x02,
+            LoginClientBoundPacket::SetCompression(_) => 0x03,
+            LoginClientBoundPacket::LoginPluginRequest(_) => 0x04,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoginClientBoundPacket::LoginDisconnect(_) => "LoginDisconnect",
+            LoginClientBoundPacket::EncryptionRequest(_) => "EncryptionRequest",
+            LoginClientBoundPacket::LoginSuccess(_) => "LoginSuccess",
+            LoginClientBoundPacket::SetCompression(_) => "SetCompression",
+            LoginClientBoundPacket::LoginPluginRequest(_) => "LoginPluginRequest",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x00, 0x01, 0x02, 0x03, 0x04].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x00 => {
+                let login_disconnect = LoginDisconnect::decode(reader)?;
+
+                Ok(LoginClientBoundPacket::LoginDisconnect(login_disconnect))
+            }
+            0x01 => {
+                let encryption_request = EncryptionRequest::decode(reader)?;
+
+                Ok(LoginClientBoundPacket::EncryptionRequest(
+                    encryption_request,
+                ))
+            }
+            0x02 => {
+                let login_success = LoginSuccess::decode(reader)?;
+
+                Ok(LoginClientBoundPacket::LoginSuccess(login_success))
+            }
+            0x03 => {
+                let set_compression = SetCompression::decode(reader)?;
+
+                Ok(LoginClientBoundPacket::SetCompression(set_compression))
+            }
+            0x04 => {
+                let login_plugin_request = LoginPluginRequest::decode(reader)?;
+
+                Ok(LoginClientBoundPacket::LoginPluginRequest(
+                    login_plugin_request,
+                ))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            LoginClientBoundPacket::LoginDisconnect(login_disconnect) => {
+                login_disconnect.encode(writer)
+            }
+            LoginClientBoundPacket::EncryptionRequest(encryption_request) => {
+                encryption_request.encode(writer)
+            }
+            LoginClientBoundPacket::LoginSuccess(login_success) => login_success.encode(writer),
+            LoginClientBoundPacket::SetCompression(set_compression) => {
+                set_compression.encode(writer)
+            }
+            LoginClientBoundPacket::LoginPluginRequest(login_plugin_request) => {
+                login_plugin_request.encode(writer)
+            }
+        }
+    }
+}
+
+impl_packet!(LoginClientBoundPacket);
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LoginStart {
+    pub name: String,
+}
+
+impl LoginStart {
+    pub fn new(name: String) -> LoginServerBoundPacket {
+        let login_start = LoginStart { name };
+
+        LoginServerBoundPacket::LoginStart(login_start)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EncryptionResponse {
+    pub shared_secret: Vec<u8>,
+    pub verify_token: Vec<u8>,
+}
+
+impl EncryptionResponse {
+    pub fn new(shared_secret: Vec<u8>, verify_token: Vec<u8>) -> LoginServerBoundPacket {
+        let encryption_response = EncryptionResponse {
+            shared_secret,
+            verify_token,
+        };
+
+        LoginServerBoundPacket::EncryptionResponse(encryption_response)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LoginPluginResponse {
+    #[data_type(with = "var_int")]
+    pub message_id: i32,
+    pub successful: bool,
+    #[data_type(with = "rest")]
+    pub data: Vec<u8>,
+}
+
+impl LoginPluginResponse {
+    pub fn new(message_id: i32, successful: bool, data: Vec<u8>) -> LoginServerBoundPacket {
+        let login_plugin_response = LoginPluginResponse {
+            message_id,
+            successful,
+            data,
+        };
+
+        LoginServerBoundPacket::LoginPluginResponse(login_plugin_response)
+    }
+}
+
+#[derive(Encoder, Decoder, VisitMessages, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LoginDisconnect {
+    pub reason: Message,
+}
+
+impl LoginDisconnect {
+    pub fn new(reason: Message) -> LoginClientBoundPacket {
+        let login_disconnect = LoginDisconnect { reason };
+
+        LoginClientBoundPacket::LoginDisconnect(login_disconnect)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EncryptionRequest {
+    #[data_type(max_length = 20)]
+    pub server_id: String,
+    pub public_key: Vec<u8>,
+    pub verify_token: Vec<u8>,
+}
+
+impl EncryptionRequest {
+    pub fn new(
+        server_id: String,
+        public_key: Vec<u8>,
+        verify_token: Vec<u8>,
+    ) -> LoginClientBoundPacket {
+        let encryption_request = EncryptionRequest {
+            server_id,
+            public_key,
+            verify_token,
+        };
+
+        LoginClientBoundPacket::EncryptionRequest(encryption_request)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LoginSuccess {
+    /// Encoded as 128-bit integer since 1.16, hyphenated string before.
+    pub uuid: Uuid,
+    #[data_type(max_length = 16)]
+    pub username: String,
+}
+
+impl LoginSuccess {
+    pub fn new(uuid: Uuid, username: String) -> LoginClientBoundPacket {
+        let login_success = LoginSuccess { uuid, username };
+
+        LoginClientBoundPacket::LoginSuccess(login_success)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SetCompression {
+    #[data_type(with = "var_int")]
+    pub threshold: i32,
+}
+
+impl SetCompression {
+    pub fn new(threshold: i32) -> LoginClientBoundPacket {
+        let set_compression = SetCompression { threshold };
+
+        LoginClientBoundPacket::SetCompression(set_compression)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LoginPluginRequest {
+    #[data_type(with = "var_int")]
+    pub message_id: i32,
+    pub channel: String,
+    #[data_type(with = "rest")]
+    pub data: Vec<u8>,
+}
+
+impl LoginPluginRequest {
+    pub fn new(message_id: i32, channel: String, data: Vec<u8>) -> LoginClientBoundPacket {
+        let login_plugin_request = LoginPluginRequest {
+            message_id,
+            channel,
+            data,
+        };
+
+        LoginClientBoundPacket::LoginPluginRequest(login_plugin_request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::packet::roundtrip_tests;
+    use crate::version::v1_16_5::login::*;
+    use uuid::Uuid;
+
+    roundtrip_tests! {
+        test_login_success_roundtrip: LoginSuccess = LoginSuccess {
+            uuid: Uuid::parse_str("35ee313b-d89a-41b8-b25e-d32e8aff0389").unwrap(),
+            username: String::from("Username"),
+        } => "v1_16_5/login/login_success.dat";
+    }
+}
@@ -0,0 +1,157 @@
+//! Packets of 1.16.5.
+//!
+//! Handshake and status packets didn't change since 1.14.4 and are re-exported from it.
+//! `ServerBoundPacket` and `ClientBoundPacket` join packet enums of all connection states, like
+//! in 1.14.4.
+use crate::connection::StatePacket;
+use crate::driver::ProtocolState;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::RawPacket;
+
+pub mod game;
+pub mod login;
+#[cfg(feature = "registries")]
+pub mod registry;
+
+pub use crate::version::v1_14_4::{handshake, status};
+
+use game::{GameClientBoundPacket, GameServerBoundPacket};
+use handshake::HandshakeServerBoundPacket;
+use login::{LoginClientBoundPacket, LoginServerBoundPacket};
+use status::{StatusClientBoundPacket, StatusServerBoundPacket};
+
+/// Packet sent by client in any connection state.
+#[derive(Debug)]
+pub enum ServerBoundPacket {
+    Handshake(HandshakeServerBoundPacket),
+    Status(StatusServerBoundPacket),
+    Login(LoginServerBoundPacket),
+    Game(GameServerBoundPacket),
+}
+
+impl StatePacket for ServerBoundPacket {
+    fn decode_in_state(state: ProtocolState, raw_packet: &RawPacket) -> Result<Self, DecodeError> {
+        match state {
+            ProtocolState::Handshake => {
+                raw_packet.decode_packet().map(ServerBoundPacket::Handshake)
+            }
+            ProtocolState::Status => raw_packet.decode_packet().map(ServerBoundPacket::Status),
+            ProtocolState::Login => raw_packet.decode_packet().map(ServerBoundPacket::Login),
+            ProtocolState::Game => raw_packet.decode_packet().map(ServerBoundPacket::Game),
+        }
+    }
+
+    fn state(&self) -> ProtocolState {
+        match self {
+            ServerBoundPacket::Handshake(_) => ProtocolState::Handshake,
+            ServerBoundPacket::Status(_) => ProtocolState::Status,
+            ServerBoundPacket::Login(_) => ProtocolState::Login,
+            ServerBoundPacket::Game(_) => ProtocolState::Game,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ServerBoundPacket::Handshake(packet) => packet.name(),
+            ServerBoundPacket::Status(packet) => packet.name(),
+            ServerBoundPacket::Login(packet) => packet.name(),
+            ServerBoundPacket::Game(packet) => packet.name(),
+        }
+    }
+
+    fn to_raw_packet(&self) -> Result<RawPacket, EncodeError> {
+        match self {
+            ServerBoundPacket::Handshake(packet) => RawPacket::from_packet(packet),
+            ServerBoundPacket::Status(packet) => RawPacket::from_packet(packet),
+            ServerBoundPacket::Login(packet) => RawPacket::from_packet(packet),
+            ServerBoundPacket::Game(packet) => RawPacket::from_packet(packet),
+        }
+    }
+}
+
+impl From<HandshakeServerBoundPacket> for ServerBoundPacket {
+    fn from(packet: HandshakeServerBoundPacket) -> Self {
+        ServerBoundPacket::Handshake(packet)
+    }
+}
+
+impl From<StatusServerBoundPacket> for ServerBoundPacket {
+    fn from(packet: StatusServerBoundPacket) -> Self {
+        ServerBoundPacket::Status(packet)
+    }
+}
+
+impl From<LoginServerBoundPacket> for ServerBoundPacket {
+    fn from(packet: LoginServerBoundPacket) -> Self {
+        ServerBoundPacket::Login(packet)
+    }
+}
+
+impl From<GameServerBoundPacket> for ServerBoundPacket {
+    fn from(packet: GameServerBoundPacket) -> Self {
+        ServerBoundPacket::Game(packet)
+    }
+}
+
+/// Packet sent by server in any connection state. Server sends nothing in handshake state.
+#[derive(Debug)]
+pub enum ClientBoundPacket {
+    Status(StatusClientBoundPacket),
+    Login(LoginClientBoundPacket),
+    Game(GameClientBoundPacket),
+}
+
+impl StatePacket for ClientBoundPacket {
+    fn decode_in_state(state: ProtocolState, raw_packet: &RawPacket) -> Result<Self, DecodeError> {
+        match state {
+            ProtocolState::Handshake => Err(DecodeError::UnknownPacketType {
+                type_id: raw_packet.id as u8,
+            }),
+            ProtocolState::Status => raw_packet.decode_packet().map(ClientBoundPacket::Status),
+            ProtocolState::Login => raw_packet.decode_packet().map(ClientBoundPacket::Login),
+            ProtocolState::Game => raw_packet.decode_packet().map(ClientBoundPacket::Game),
+        }
+    }
+
+    fn state(&self) -> ProtocolState {
+        match self {
+            ClientBoundPacket::Status(_) => ProtocolState::Status,
+            ClientBoundPacket::Login(_) => ProtocolState::Login,
+            ClientBoundPacket::Game(_) => ProtocolState::Game,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ClientBoundPacket::Status(packet) => packet.name(),
+            ClientBoundPacket::Login(packet) => packet.name(),
+            ClientBoundPacket::Game(packet) => packet.name(),
+        }
+    }
+
+    fn to_raw_packet(&self) -> Result<RawPacket, EncodeError> {
+        match self {
+            ClientBoundPacket::Status(packet) => RawPacket::from_packet(packet),
+            ClientBoundPacket::Login(packet) => RawPacket::from_packet(packet),
+            ClientBoundPacket::Game(packet) => RawPacket::from_packet(packet),
+        }
+    }
+}
+
+impl From<StatusClientBoundPacket> for ClientBoundPacket {
+    fn from(packet: StatusClientBoundPacket) -> Self {
+        ClientBoundPacket::Status(packet)
+    }
+}
+
+impl From<LoginClientBoundPacket> for ClientBoundPacket {
+    fn from(packet: LoginClientBoundPacket) -> Self {
+        ClientBoundPacket::Login(packet)
+    }
+}
+
+impl From<GameClientBoundPacket> for ClientBoundPacket {
+    fn from(packet: GameClientBoundPacket) -> Self {
+        ClientBoundPacket::Game(packet)
+    }
+}
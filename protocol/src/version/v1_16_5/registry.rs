@@ -0,0 +1,189 @@
+//! Registry tables of 1.16.5.
+use crate::data::registry::{Registries, Registry};
+
+pub static REGISTRIES: Registries = Registries {
+    entity_types: Registry::new(&[
+        "area_effect_cloud",
+        "armor_stand",
+        "arrow",
+        "bat",
+        "bee",
+        "blaze",
+        "boat",
+        "cat",
+        "cave_spider",
+        "chicken",
+        "cod",
+        "cow",
+        "creeper",
+        "dolphin",
+        "donkey",
+        "dragon_fireball",
+        "drowned",
+        "elder_guardian",
+        "end_crystal",
+        "ender_dragon",
+        "enderman",
+        "endermite",
+        "evoker",
+        "evoker_fangs",
+        "experience_orb",
+        "eye_of_ender",
+        "falling_block",
+        "firework_rocket",
+        "fox",
+        "ghast",
+        "giant",
+        "guardian",
+        "hoglin",
+        "horse",
+        "husk",
+        "illusioner",
+        "iron_golem",
+        "item",
+        "item_frame",
+        "fireball",
+        "leash_knot",
+        "lightning_bolt",
+        "llama",
+        "llama_spit",
+        "magma_cube",
+        "minecart",
+        "chest_minecart",
+        "command_block_minecart",
+        "furnace_minecart",
+        "hopper_minecart",
+        "spawner_minecart",
+        "tnt_minecart",
+        "mule",
+        "mooshroom",
+        "ocelot",
+        "painting",
+        "panda",
+        "parrot",
+        "phantom",
+        "pig",
+        "piglin",
+        "piglin_brute",
+        "pillager",
+        "polar_bear",
+        "tnt",
+        "pufferfish",
+        "rabbit",
+        "ravager",
+        "salmon",
+        "sheep",
+        "shulker",
+        "shulker_bullet",
+        "silverfish",
+        "skeleton",
+        "skeleton_horse",
+        "slime",
+        "small_fireball",
+        "snow_golem",
+        "snowball",
+        "spectral_arrow",
+        "spider",
+        "squid",
+        "stray",
+        "strider",
+        "egg",
+        "ender_pearl",
+        "experience_bottle",
+        "potion",
+        "trident",
+        "trader_llama",
+        "tropical_fish",
+        "turtle",
+        "vex",
+        "villager",
+        "vindicator",
+        "wandering_trader",
+        "witch",
+        "wither",
+        "wither_skeleton",
+        "wither_skull",
+        "wolf",
+        "zoglin",
+        "zombie",
+        "zombie_horse",
+        "zombie_villager",
+        "zombified_piglin",
+        "player",
+        "fishing_bobber",
+    ]),
+    particles: Registry::new(&[
+        "ambient_entity_effect",
+        "angry_villager",
+        "barrier",
+        "block",
+        "bubble",
+        "cloud",
+        "crit",
+        "damage_indicator",
+        "dragon_breath",
+        "dripping_lava",
+        "falling_lava",
+        "landing_lava",
+        "dripping_water",
+        "falling_water",
+        "dust",
+        "effect",
+        "elder_guardian",
+        "enchanted_hit",
+        "enchant",
+        "end_rod",
+        "entity_effect",
+        "explosion_emitter",
+        "explosion",
+        "falling_dust",
+        "firework",
+        "fishing",
+        "flame",
+        "soul_fire_flame",
+        "soul",
+        "flash",
+        "happy_villager",
+        "composter",
+        "heart",
+        "instant_effect",
+        "item",
+        "item_slime",
+        "item_snowball",
+        "large_smoke",
+        "lava",
+        "mycelium",
+        "note",
+        "poof",
+        "portal",
+        "rain",
+        "smoke",
+        "sneeze",
+        "spit",
+        "squid_ink",
+        "sweep_attack",
+        "totem_of_undying",
+        "underwater",
+        "splash",
+        "witch",
+        "bubble_pop",
+        "current_down",
+        "bubble_column_up",
+        "nautilus",
+        "dolphin",
+        "campfire_cosy_smoke",
+        "campfire_signal_smoke",
+        "dripping_honey",
+        "falling_honey",
+        "landing_honey",
+        "falling_nectar",
+        "ash",
+        "crimson_spore",
+        "warped_spore",
+        "dripping_obsidian_tear",
+        "falling_obsidian_tear",
+        "landing_obsidian_tear",
+        "reverse_portal",
+        "white_ash",
+    ]),
+};
@@ -0,0 +1,584 @@
+use crate::data::chat::Message;
+use crate::data::units::Ticks;
+use crate::decoder::{Decoder, DecoderReadExt};
+use crate::encoder::{Encoder, EncoderWriteExt};
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use uuid::Uuid;
+
+pub use crate::data::game::ClientStatusAction;
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameServerBoundPacket {
+    ChatCommand(ChatCommand),
+    ChatMessage(ChatMessage),
+    ClientStatus(ClientStatus),
+    Pong(Pong),
+}
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameClientBoundPacket {
+    Ping(Ping),
+    PlayerChatMessage(#[visit] PlayerChatMessage),
+    EndCombatEvent(EndCombatEvent),
+    EnterCombatEvent,
+    DeathCombatEvent(#[visit] DeathCombatEvent),
+    SystemChatMessage(#[visit] SystemChatMessage),
+}
+
+impl GameServerBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameServerBoundPacket::ChatCommand(_) => 0x03,
+            GameServerBoundPacket::ChatMessage(_) => 0x04,
+            GameServerBoundPacket::ClientStatus(_) => 0x06,
+            GameServerBoundPacket::Pong(_) => 0x1F,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameServerBoundPacket::ChatCommand(_) => "ChatCommand",
+            GameServerBoundPacket::ChatMessage(_) => "ChatMessage",
+            GameServerBoundPacket::ClientStatus(_) => "ClientStatus",
+            GameServerBoundPacket::Pong(_) => "Pong",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x03, 0x04, 0x06, 0x1F].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x03 => {
+                let chat_command = ChatCommand::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ChatCommand(chat_command))
+            }
+            0x04 => {
+                let chat_message = ChatMessage::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ChatMessage(chat_message))
+            }
+            0x06 => {
+                let client_status = ClientStatus::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ClientStatus(client_status))
+            }
+            0x1F => {
+                let pong = Pong::decode(reader)?;
+
+                Ok(GameServerBoundPacket::Pong(pong))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameServerBoundPacket::ChatCommand(chat_command) => chat_command.encode(writer),
+            GameServerBoundPacket::ChatMessage(chat_message) => chat_message.encode(writer),
+            GameServerBoundPacket::ClientStatus(client_status) => client_status.encode(writer),
+            GameServerBoundPacket::Pong(pong) => pong.encode(writer),
+        }
+    }
+}
+
+impl_packet!(GameServerBoundPacket);
+
+impl GameClientBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameClientBoundPacket::Ping(_) => 0x2D,
+            GameClientBoundPacket::PlayerChatMessage(_) => 0x30,
+            GameClientBoundPacket::EndCombatEvent(_) => 0x31,
+            GameClientBoundPacket::EnterCombatEvent => 0x32,
+            GameClientBoundPacket::DeathCombatEvent(_) => 0x33,
+            GameClientBoundPacket::SystemChatMessage(_) => 0x5F,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameClientBoundPacket::Ping(_) => "Ping",
+            GameClientBoundPacket::PlayerChatMessage(_) => "PlayerChatMessage",
+            GameClientBoundPacket::EndCombatEvent(_) => "EndCombatEvent",
+            GameClientBoundPacket::EnterCombatEvent => "EnterCombatEvent",
+            GameClientBoundPacket::DeathCombatEvent(_) => "DeathCombatEvent",
+            GameClientBoundPacket::SystemChatMessage(_) => "SystemChatMessage",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x2D, 0x30, 0x31, 0x32, 0x33, 0x5F].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x2D => {
+                let ping = Ping::decode(reader)?;
+
+                Ok(GameClientBoundPacket::Ping(ping))
+            }
+            0x30 => {
+                let player_chat_message = PlayerChatMessage::decode(reader)?;
+
+                Ok(GameClientBoundPacket::PlayerChatMessage(
+                    player_chat_message,
+                ))
+            }
+            0x31 => {
+                let end_combat_event = EndCombatEvent::decode(reader)?;
+
+                Ok(GameClientBoundPacket::EndCombatEvent(end_combat_event))
+            }
+            0x32 => Ok(GameClientBoundPacket::EnterCombatEvent),
+            0x33 => {
+                let death_combat_event = DeathCombatEvent::decode(reader)?;
+
+                Ok(GameClientBoundPacket::DeathCombatEvent(death_combat_event))
+            }
+            0x5F => {
+                let system_chat_message = SystemChatMessage::decode(reader)?;
+
+                Ok(GameClientBoundPacket::SystemChatMessage(
+                    system_chat_message,
+                ))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameClientBoundPacket::Ping(ping) => ping.encode(writer),
+            GameClientBoundPacket::PlayerChatMessage(player_chat_message) => {
+                player_chat_message.encode(writer)
+            }
+            GameClientBoundPacket::EndCombatEvent(end_combat_event) => {
+                end_combat_event.encode(writer)
+            }
+            GameClientBoundPacket::EnterCombatEvent => Ok(()),
+            GameClientBoundPacket::DeathCombatEvent(death_combat_event) => {
+                death_combat_event.encode(writer)
+            }
+            GameClientBoundPacket::SystemChatMessage(system_chat_message) => {
+                system_chat_message.encode(writer)
+            }
+        }
+    }
+}
+
+impl_packet!(GameClientBoundPacket);
+
+/// Command typed by player, without leading slash. Replaces commands sent as chat messages.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChatCommand {
+    #[data_type(max_length = 256)]
+    pub command: String,
+    /// Milliseconds since epoch.
+    pub timestamp: i64,
+    pub salt: i64,
+    /// Signatures of arguments which are sent to other players, e.g. message of `/msg`.
+    #[data_type(with = "list")]
+    pub argument_signatures: Vec<ArgumentSignature>,
+    pub signed_preview: bool,
+}
+
+impl ChatCommand {
+    pub fn new(command: String, timestamp: i64, salt: i64) -> GameServerBoundPacket {
+        let chat_command = ChatCommand {
+            command,
+            timestamp,
+            salt,
+            argument_signatures: Vec::new(),
+            signed_preview: false,
+        };
+
+        GameServerBoundPacket::ChatCommand(chat_command)
+    }
+}
+
+/// Signature of command argument. Length of signature is not fixed before 1.19.3.
+#[derive(Encoder, Decoder, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ArgumentSignature {
+    #[data_type(max_length = 16)]
+    pub name: String,
+    pub signature: Vec<u8>,
+}
+
+/// Chat message signed by player. Replaces `ServerBoundChatMessage`.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChatMessage {
+    #[data_type(max_length = 256)]
+    pub message: String,
+    /// Milliseconds since epoch.
+    pub timestamp: i64,
+    pub salt: i64,
+    /// Empty if player has no profile public key.
+    pub signature: Vec<u8>,
+    pub signed_preview: bool,
+}
+
+impl ChatMessage {
+    pub fn new(
+        message: String,
+        timestamp: i64,
+        salt: i64,
+        signature: Vec<u8>,
+    ) -> GameServerBoundPacket {
+        let chat_message = ChatMessage {
+            message,
+            timestamp,
+            salt,
+            signature,
+            signed_preview: false,
+        };
+
+        GameServerBoundPacket::ChatMessage(chat_message)
+    }
+}
+
+/// Client asks to respawn or for statistics.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientStatus {
+    pub action: ClientStatusAction,
+}
+
+impl ClientStatus {
+    pub fn new(action: ClientStatusAction) -> GameServerBoundPacket {
+        let client_status = ClientStatus { action };
+
+        GameServerBoundPacket::ClientStatus(client_status)
+    }
+}
+
+/// Server pings client, which answers with `Pong` with the same id once it has processed all
+/// packets received before. Added in 1.17, used by anti-cheats to measure latency.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ping {
+    pub id: i32,
+}
+
+impl Ping {
+    pub fn new(id: i32) -> GameClientBoundPacket {
+        let ping = Ping { id };
+
+        GameClientBoundPacket::Ping(ping)
+    }
+}
+
+/// Answer to `Ping`.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pong {
+    pub id: i32,
+}
+
+impl Pong {
+    pub fn new(id: i32) -> GameServerBoundPacket {
+        let pong = Pong { id };
+
+        GameServerBoundPacket::Pong(pong)
+    }
+}
+
+/// Id of chat type in the registry sent by server in `JoinGame` packet.
+///
+/// Replaces `MessagePosition` used before 1.19.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct ChatType(pub i32);
+
+impl ChatType {
+    pub const CHAT: ChatType = ChatType(0);
+    pub const SYSTEM: ChatType = ChatType(1);
+    pub const GAME_INFO: ChatType = ChatType(2);
+    pub const SAY_COMMAND: ChatType = ChatType(3);
+    pub const MSG_COMMAND: ChatType = ChatType(4);
+    pub const TEAM_MSG_COMMAND: ChatType = ChatType(5);
+    pub const EMOTE_COMMAND: ChatType = ChatType(6);
+    pub const TELLRAW_COMMAND: ChatType = ChatType(7);
+}
+
+impl Encoder for ChatType {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        writer.write_var_i32(self.0)
+    }
+}
+
+impl Decoder for ChatType {
+    type Output = Self;
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self::Output, DecodeError> {
+        Ok(ChatType(reader.read_var_i32()?))
+    }
+}
+
+/// Chat message signed by player.
+#[derive(Encoder, Decoder, VisitMessages, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlayerChatMessage {
+    pub signed_content: Message,
+    pub unsigned_content: Option<Message>,
+    pub chat_type: ChatType,
+    pub sender: Uuid,
+    pub sender_display_name: Message,
+    pub sender_team_name: Option<Message>,
+    /// Milliseconds since epoch.
+    pub timestamp: i64,
+    pub salt: i64,
+    pub signature: Vec<u8>,
+}
+
+/// Message which is not sent by player, e.g. command feedback.
+#[derive(Encoder, Decoder, VisitMessages, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SystemChatMessage {
+    pub content: Message,
+    pub chat_type: ChatType,
+}
+
+impl SystemChatMessage {
+    pub fn new(content: Message, chat_type: ChatType) -> GameClientBoundPacket {
+        let system_chat_message = SystemChatMessage { content, chat_type };
+
+        GameClientBoundPacket::SystemChatMessage(system_chat_message)
+    }
+}
+
+/// Replaces `EndCombat` event of 1.14.4 `CombatEvent` packet.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EndCombatEvent {
+    #[data_type(with = "var_int_ticks")]
+    pub duration: Ticks,
+    /// Id of the last attacker or -1.
+    pub entity_id: i32,
+}
+
+impl EndCombatEvent {
+    pub fn new(duration: Ticks, entity_id: i32) -> GameClientBoundPacket {
+        let end_combat_event = EndCombatEvent {
+            duration,
+            entity_id,
+        };
+
+        GameClientBoundPacket::EndCombatEvent(end_combat_event)
+    }
+}
+
+/// Replaces `EntityDead` event of 1.14.4 `CombatEvent` packet. Shows death screen to player.
+#[derive(Encoder, Decoder, VisitMessages, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeathCombatEvent {
+    #[data_type(with = "var_int")]
+    pub player_id: i32,
+    /// Id of the killer or -1.
+    pub entity_id: i32,
+    pub message: Message,
+}
+
+impl DeathCombatEvent {
+    pub fn new(player_id: i32, entity_id: i32, message: Message) -> GameClientBoundPacket {
+        let death_combat_event = DeathCombatEvent {
+            player_id,
+            entity_id,
+            message,
+        };
+
+        GameClientBoundPacket::DeathCombatEvent(death_combat_event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::chat::Message;
+    use crate::decoder::Decoder;
+    use crate::encoder::Encoder;
+    use crate::version::v1_19::game::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_system_chat_message_encode() {
+        let system_chat_message = SystemChatMessage {
+            content: Message::from_str("Hello"),
+            chat_type: ChatType::GAME_INFO,
+        };
+
+        let mut vec = Vec::new();
+        system_chat_message.encode(&mut vec).unwrap();
+
+        assert_eq!(
+            vec,
+            include_bytes!("../../../test/packet/v1_19/game/system_chat_message.dat").to_vec()
+        );
+    }
+
+    #[test]
+    fn test_system_chat_message_decode() {
+        let mut cursor = Cursor::new(
+            include_bytes!("../../../test/packet/v1_19/game/system_chat_message.dat").to_vec(),
+        );
+        let system_chat_message = SystemChatMessage::decode(&mut cursor).unwrap();
+
+        assert_eq!(system_chat_message.content, Message::from_str("Hello"));
+        assert_eq!(system_chat_message.chat_type, ChatType::GAME_INFO);
+    }
+
+    #[test]
+    fn test_player_chat_message_round_trip() {
+        let player_chat_message = PlayerChatMessage {
+            signed_content: Message::from_str("Hi"),
+            unsigned_content: None,
+            chat_type: ChatType::CHAT,
+            sender: Uuid::parse_str("35ee313b-d89a-41b8-b25e-d32e8aff0389").unwrap(),
+            sender_display_name: Message::from_str("Username"),
+            sender_team_name: Some(Message::from_str("Team")),
+            timestamp: 1577735845610,
+            salt: 42,
+            signature: vec![1, 2, 3],
+        };
+
+        let mut vec = Vec::new();
+        player_chat_message.encode(&mut vec).unwrap();
+
+        let decoded = PlayerChatMessage::decode(&mut Cursor::new(vec)).unwrap();
+
+        assert_eq!(decoded.signed_content, Message::from_str("Hi"));
+        assert_eq!(decoded.unsigned_content, None);
+        assert_eq!(decoded.sender, player_chat_message.sender);
+        assert_eq!(decoded.sender_team_name, Some(Message::from_str("Team")));
+        assert_eq!(decoded.timestamp, 1577735845610);
+        assert_eq!(decoded.signature, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_death_combat_event_encode() {
+        let death_combat_event = DeathCombatEvent {
+            player_id: 5,
+            entity_id: 12,
+            message: Message::from_str("You died"),
+        };
+
+        let mut vec = Vec::new();
+        death_combat_event.encode(&mut vec).unwrap();
+
+        assert_eq!(
+            vec,
+            include_bytes!("../../../test/packet/v1_19/game/death_combat_event.dat").to_vec()
+        );
+    }
+
+    #[test]
+    fn test_death_combat_event_decode() {
+        let mut cursor = Cursor::new(
+            include_bytes!("../../../test/packet/v1_19/game/death_combat_event.dat").to_vec(),
+        );
+
+        match GameClientBoundPacket::decode(0x33, &mut cursor).unwrap() {
+            GameClientBoundPacket::DeathCombatEvent(death_combat_event) => {
+                assert_eq!(death_combat_event.player_id, 5);
+                assert_eq!(death_combat_event.entity_id, 12);
+                assert_eq!(death_combat_event.message, Message::from_str("You died"));
+            }
+            _ => panic!("Expected death combat event packet"),
+        }
+    }
+
+    #[test]
+    fn test_chat_command_round_trip() {
+        let chat_command = ChatCommand {
+            command: String::from("msg Username hi"),
+            timestamp: 1577735845610,
+            salt: 42,
+            argument_signatures: vec![ArgumentSignature {
+                name: String::from("message"),
+                signature: vec![1, 2, 3],
+            }],
+            signed_preview: false,
+        };
+
+        let mut vec = Vec::new();
+        chat_command.encode(&mut vec).unwrap();
+
+        match GameServerBoundPacket::decode(0x03, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::ChatCommand(decoded) => assert_eq!(decoded, chat_command),
+            _ => panic!("Expected chat command packet"),
+        }
+    }
+
+    #[test]
+    fn test_chat_message_decode() {
+        let packet = ChatMessage::new(String::from("Hi"), 1577735845610, 42, vec![1, 2, 3]);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        match GameServerBoundPacket::decode(0x04, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::ChatMessage(chat_message) => {
+                assert_eq!(chat_message.message, "Hi");
+                assert_eq!(chat_message.salt, 42);
+                assert_eq!(chat_message.signature, [1, 2, 3]);
+                assert!(!chat_message.signed_preview);
+            }
+            _ => panic!("Expected chat message packet"),
+        }
+    }
+
+    #[test]
+    fn test_ping_pong_round_trip() {
+        let packet = Ping::new(-7);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [0xFF, 0xFF, 0xFF, 0xF9]);
+
+        let id = match GameClientBoundPacket::decode(0x2D, &mut Cursor::new(vec)).unwrap() {
+            GameClientBoundPacket::Ping(ping) => ping.id,
+            _ => panic!("Expected ping packet"),
+        };
+
+        let mut vec = Vec::new();
+        Pong::new(id).encode(&mut vec).unwrap();
+
+        match GameServerBoundPacket::decode(0x1F, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::Pong(pong) => assert_eq!(pong.id, -7),
+            _ => panic!("Expected pong packet"),
+        }
+    }
+
+    #[test]
+    fn test_end_combat_event_round_trip() {
+        let packet = EndCombatEvent::new(Ticks(200), -1);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        match GameClientBoundPacket::decode(0x31, &mut Cursor::new(vec)).unwrap() {
+            GameClientBoundPacket::EndCombatEvent(end_combat_event) => {
+                assert_eq!(end_combat_event.duration, Ticks(200));
+                assert_eq!(end_combat_event.entity_id, -1);
+            }
+            _ => panic!("Expected end combat event packet"),
+        }
+    }
+}
@@ -0,0 +1,463 @@
+use crate::data::message_signature::{
+    ArgumentSignature, LastSeenUpdate, MessageSignature, ProfilePublicKeyData,
+};
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use uuid::Uuid;
+
+pub use crate::data::game::ClientStatusAction;
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameServerBoundPacket {
+    ChatCommand(ChatCommand),
+    ChatMessage(ChatMessage),
+    PlayerSession(PlayerSession),
+    ChunkBatchReceived(ChunkBatchReceived),
+    ClientStatus(ClientStatus),
+    Pong(Pong),
+}
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameClientBoundPacket {
+    ChunkBatchFinished(ChunkBatchFinished),
+    ChunkBatchStart,
+    Ping(Ping),
+}
+
+impl GameServerBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameServerBoundPacket::ChatCommand(_) => 0x04,
+            GameServerBoundPacket::ChatMessage(_) => 0x05,
+            GameServerBoundPacket::PlayerSession(_) => 0x06,
+            GameServerBoundPacket::ChunkBatchReceived(_) => 0x07,
+            GameServerBoundPacket::ClientStatus(_) => 0x08,
+            GameServerBoundPacket::Pong(_) => 0x23,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameServerBoundPacket::ChatCommand(_) => "ChatCommand",
+            GameServerBoundPacket::ChatMessage(_) => "ChatMessage",
+            GameServerBoundPacket::PlayerSession(_) => "PlayerSession",
+            GameServerBoundPacket::ChunkBatchReceived(_) => "ChunkBatchReceived",
+            GameServerBoundPacket::ClientStatus(_) => "ClientStatus",
+            GameServerBoundPacket::Pong(_) => "Pong",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x04, 0x05, 0x06, 0x07, 0x08, 0x23].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x04 => {
+                let chat_command = ChatCommand::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ChatCommand(chat_command))
+            }
+            0x05 => {
+                let chat_message = ChatMessage::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ChatMessage(chat_message))
+            }
+            0x06 => {
+                let player_session = PlayerSession::decode(reader)?;
+
+                Ok(GameServerBoundPacket::PlayerSession(player_session))
+            }
+            0x07 => {
+                let chunk_batch_received = ChunkBatchReceived::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ChunkBatchReceived(
+                    chunk_batch_received,
+                ))
+            }
+            0x08 => {
+                let client_status = ClientStatus::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ClientStatus(client_status))
+            }
+            0x23 => {
+                let pong = Pong::decode(reader)?;
+
+                Ok(GameServerBoundPacket::Pong(pong))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameServerBoundPacket::ChatCommand(chat_command) => chat_command.encode(writer),
+            GameServerBoundPacket::ChatMessage(chat_message) => chat_message.encode(writer),
+            GameServerBoundPacket::PlayerSession(player_session) => player_session.encode(writer),
+            GameServerBoundPacket::ChunkBatchReceived(chunk_batch_received) => {
+                chunk_batch_received.encode(writer)
+            }
+            GameServerBoundPacket::ClientStatus(client_status) => client_status.encode(writer),
+            GameServerBoundPacket::Pong(pong) => pong.encode(writer),
+        }
+    }
+}
+
+impl_packet!(GameServerBoundPacket);
+
+impl GameClientBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameClientBoundPacket::ChunkBatchFinished(_) => 0x0C,
+            GameClientBoundPacket::ChunkBatchStart => 0x0D,
+            GameClientBoundPacket::Ping(_) => 0x33,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameClientBoundPacket::ChunkBatchFinished(_) => "ChunkBatchFinished",
+            GameClientBoundPacket::ChunkBatchStart => "ChunkBatchStart",
+            GameClientBoundPacket::Ping(_) => "Ping",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x0C, 0x0D, 0x33].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x0C => {
+                let chunk_batch_finished = ChunkBatchFinished::decode(reader)?;
+
+                Ok(GameClientBoundPacket::ChunkBatchFinished(
+                    chunk_batch_finished,
+                ))
+            }
+            0x0D => Ok(GameClientBoundPacket::ChunkBatchStart),
+            0x33 => {
+                let ping = Ping::decode(reader)?;
+
+                Ok(GameClientBoundPacket::Ping(ping))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameClientBoundPacket::ChunkBatchFinished(chunk_batch_finished) => {
+                chunk_batch_finished.encode(writer)
+            }
+            GameClientBoundPacket::ChunkBatchStart => Ok(()),
+            GameClientBoundPacket::Ping(ping) => ping.encode(writer),
+        }
+    }
+}
+
+impl_packet!(GameClientBoundPacket);
+
+/// Command typed by player, without leading slash.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChatCommand {
+    #[data_type(max_length = 256)]
+    pub command: String,
+    /// Milliseconds since epoch.
+    pub timestamp: i64,
+    pub salt: i64,
+    /// Signatures of arguments which are sent to other players, e.g. message of `/msg`.
+    #[data_type(with = "list")]
+    pub argument_signatures: Vec<ArgumentSignature>,
+    pub last_seen: LastSeenUpdate,
+}
+
+impl ChatCommand {
+    pub fn new(
+        command: String,
+        timestamp: i64,
+        salt: i64,
+        last_seen: LastSeenUpdate,
+    ) -> GameServerBoundPacket {
+        let chat_command = ChatCommand {
+            command,
+            timestamp,
+            salt,
+            argument_signatures: Vec::new(),
+            last_seen,
+        };
+
+        GameServerBoundPacket::ChatCommand(chat_command)
+    }
+}
+
+/// Chat message sent by player.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChatMessage {
+    #[data_type(max_length = 256)]
+    pub message: String,
+    /// Milliseconds since epoch.
+    pub timestamp: i64,
+    pub salt: i64,
+    /// Missing if player has no chat session.
+    pub signature: Option<MessageSignature>,
+    pub last_seen: LastSeenUpdate,
+}
+
+impl ChatMessage {
+    pub fn new(
+        message: String,
+        timestamp: i64,
+        salt: i64,
+        signature: Option<MessageSignature>,
+        last_seen: LastSeenUpdate,
+    ) -> GameServerBoundPacket {
+        let chat_message = ChatMessage {
+            message,
+            timestamp,
+            salt,
+            signature,
+            last_seen,
+        };
+
+        GameServerBoundPacket::ChatMessage(chat_message)
+    }
+}
+
+/// Starts chat session, following messages are signed with the private key of the session.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlayerSession {
+    pub session_id: Uuid,
+    pub public_key: ProfilePublicKeyData,
+}
+
+impl PlayerSession {
+    pub fn new(session_id: Uuid, public_key: ProfilePublicKeyData) -> GameServerBoundPacket {
+        let player_session = PlayerSession {
+            session_id,
+            public_key,
+        };
+
+        GameServerBoundPacket::PlayerSession(player_session)
+    }
+}
+
+/// Client acknowledges chunk batch and reports how many chunks per tick it can process.
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChunkBatchReceived {
+    pub chunks_per_tick: f32,
+}
+
+impl ChunkBatchReceived {
+    pub fn new(chunks_per_tick: f32) -> GameServerBoundPacket {
+        let chunk_batch_received = ChunkBatchReceived { chunks_per_tick };
+
+        GameServerBoundPacket::ChunkBatchReceived(chunk_batch_received)
+    }
+}
+
+/// Ends chunk batch started with `ChunkBatchStart`.
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChunkBatchFinished {
+    /// Number of chunks in the batch.
+    #[data_type(with = "var_int")]
+    pub batch_size: i32,
+}
+
+impl ChunkBatchFinished {
+    pub fn new(batch_size: i32) -> GameClientBoundPacket {
+        let chunk_batch_finished = ChunkBatchFinished { batch_size };
+
+        GameClientBoundPacket::ChunkBatchFinished(chunk_batch_finished)
+    }
+}
+
+/// Client asks to respawn or for statistics.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientStatus {
+    pub action: ClientStatusAction,
+}
+
+impl ClientStatus {
+    pub fn new(action: ClientStatusAction) -> GameServerBoundPacket {
+        let client_status = ClientStatus { action };
+
+        GameServerBoundPacket::ClientStatus(client_status)
+    }
+}
+
+/// Server pings client, which answers with `Pong` with the same id.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ping {
+    pub id: i32,
+}
+
+impl Ping {
+    pub fn new(id: i32) -> GameClientBoundPacket {
+        let ping = Ping { id };
+
+        GameClientBoundPacket::Ping(ping)
+    }
+}
+
+/// Answer to `Ping`.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pong {
+    pub id: i32,
+}
+
+impl Pong {
+    pub fn new(id: i32) -> GameServerBoundPacket {
+        let pong = Pong { id };
+
+        GameServerBoundPacket::Pong(pong)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::message_signature::SIGNATURE_LENGTH;
+    use crate::version::v1_20_2::game::*;
+    use std::io::Cursor;
+
+    fn last_seen() -> LastSeenUpdate {
+        LastSeenUpdate {
+            offset: 1,
+            acknowledged: 0b11,
+        }
+    }
+
+    #[test]
+    fn test_chat_command_round_trip() {
+        let chat_command = ChatCommand {
+            command: String::from("msg Username hi"),
+            timestamp: 1577735845610,
+            salt: 42,
+            argument_signatures: vec![ArgumentSignature {
+                name: String::from("message"),
+                signature: MessageSignature([7; SIGNATURE_LENGTH]),
+            }],
+            last_seen: last_seen(),
+        };
+
+        let mut vec = Vec::new();
+        chat_command.encode(&mut vec).unwrap();
+
+        match GameServerBoundPacket::decode(0x04, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::ChatCommand(decoded) => assert_eq!(decoded, chat_command),
+            _ => panic!("Expected chat command packet"),
+        }
+    }
+
+    #[test]
+    fn test_unsigned_chat_message_encode() {
+        let packet = ChatMessage::new(String::from("Hi"), 1, 2, None, last_seen());
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(
+            vec,
+            [2, b'H', b'i', 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 1, 0b11, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_signed_chat_message_round_trip() {
+        let signature = MessageSignature([7; SIGNATURE_LENGTH]);
+        let packet = ChatMessage::new(String::from("Hi"), 1, 2, Some(signature), last_seen());
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        match GameServerBoundPacket::decode(0x05, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::ChatMessage(chat_message) => {
+                assert_eq!(chat_message.message, "Hi");
+                assert_eq!(
+                    chat_message.signature,
+                    Some(MessageSignature([7; SIGNATURE_LENGTH]))
+                );
+                assert_eq!(chat_message.last_seen, last_seen());
+            }
+            _ => panic!("Expected chat message packet"),
+        }
+    }
+
+    #[test]
+    fn test_player_session_round_trip() {
+        let public_key = ProfilePublicKeyData {
+            expires_at: 1577735845610,
+            key: vec![1, 2, 3],
+            key_signature: vec![4, 5],
+        };
+        let session_id = Uuid::parse_str("35ee313b-d89a-41b8-b25e-d32e8aff0389").unwrap();
+        let packet = PlayerSession::new(session_id, public_key.clone());
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(vec.len(), 16 + 8 + 4 + 3);
+
+        match GameServerBoundPacket::decode(0x06, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::PlayerSession(player_session) => {
+                assert_eq!(player_session.session_id, session_id);
+                assert_eq!(player_session.public_key, public_key);
+            }
+            _ => panic!("Expected player session packet"),
+        }
+    }
+
+    #[test]
+    fn test_chunk_batch_finished_round_trip() {
+        let packet = ChunkBatchFinished::new(300);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [0b10101100, 0b00000010]);
+
+        match GameClientBoundPacket::decode(0x0C, &mut Cursor::new(vec)).unwrap() {
+            GameClientBoundPacket::ChunkBatchFinished(chunk_batch_finished) => {
+                assert_eq!(chunk_batch_finished.batch_size, 300)
+            }
+            _ => panic!("Expected chunk batch finished packet"),
+        }
+    }
+
+    #[test]
+    fn test_client_status_round_trip() {
+        let packet = ClientStatus::new(ClientStatusAction::PerformRespawn);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [0]);
+
+        match GameServerBoundPacket::decode(0x08, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::ClientStatus(client_status) => {
+                assert_eq!(client_status.action, ClientStatusAction::PerformRespawn)
+            }
+            _ => panic!("Expected client status packet"),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::data::cookie::CookiePayload;
+use crate::data::identifier::Identifier;
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum ConfigurationServerBoundPacket {
+    CookieResponse(CookieResponse),
+}
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum ConfigurationClientBoundPacket {
+    CookieRequest(CookieRequest),
+    StoreCookie(StoreCookie),
+    Transfer(Transfer),
+}
+
+impl ConfigurationServerBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            ConfigurationServerBoundPacket::CookieResponse(_) => 0x01,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigurationServerBoundPacket::CookieResponse(_) => "CookieResponse",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x01].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x01 => {
+                let cookie_response = CookieResponse::decode(reader)?;
+
+                Ok(ConfigurationServerBoundPacket::CookieResponse(
+                    cookie_response,
+                ))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            ConfigurationServerBoundPacket::CookieResponse(cookie_response) => {
+                cookie_response.encode(writer)
+            }
+        }
+    }
+}
+
+impl_packet!(ConfigurationServerBoundPacket);
+
+impl ConfigurationClientBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            ConfigurationClientBoundPacket::CookieRequest(_) => 0x00,
+            ConfigurationClientBoundPacket::StoreCookie(_) => 0x0A,
+            ConfigurationClientBoundPacket::Transfer(_) => 0x0B,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigurationClientBoundPacket::CookieRequest(_) => "CookieRequest",
+            ConfigurationClientBoundPacket::StoreCookie(_) => "StoreCookie",
+            ConfigurationClientBoundPacket::Transfer(_) => "Transfer",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x00, 0x0A, 0x0B].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x00 => {
+                let cookie_request = CookieRequest::decode(reader)?;
+
+                Ok(ConfigurationClientBoundPacket::CookieRequest(
+                    cookie_request,
+                ))
+            }
+            0x0A => {
+                let store_cookie = StoreCookie::decode(reader)?;
+
+                Ok(ConfigurationClientBoundPacket::StoreCookie(store_cookie))
+            }
+            0x0B => {
+                let transfer = Transfer::decode(reader)?;
+
+                Ok(ConfigurationClientBoundPacket::Transfer(transfer))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            ConfigurationClientBoundPacket::CookieRequest(cookie_request) => {
+                cookie_request.encode(writer)
+            }
+            ConfigurationClientBoundPacket::StoreCookie(store_cookie) => {
+                store_cookie.encode(writer)
+            }
+            ConfigurationClientBoundPacket::Transfer(transfer) => transfer.encode(writer),
+        }
+    }
+}
+
+impl_packet!(ConfigurationClientBoundPacket);
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CookieRequest {
+    pub key: Identifier,
+}
+
+impl CookieRequest {
+    pub fn new(key: Identifier) -> ConfigurationClientBoundPacket {
+        let cookie_request = CookieRequest { key };
+
+        ConfigurationClientBoundPacket::CookieRequest(cookie_request)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CookieResponse {
+    pub key: Identifier,
+    pub payload: Option<CookiePayload>,
+}
+
+impl CookieResponse {
+    pub fn new(key: Identifier, payload: Option<CookiePayload>) -> ConfigurationServerBoundPacket {
+        let cookie_response = CookieResponse { key, payload };
+
+        ConfigurationServerBoundPacket::CookieResponse(cookie_response)
+    }
+}
+
+/// Server asks client to store cookie, it's kept until client quits the game.
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StoreCookie {
+    pub key: Identifier,
+    pub payload: CookiePayload,
+}
+
+impl StoreCookie {
+    pub fn new(key: Identifier, payload: CookiePayload) -> ConfigurationClientBoundPacket {
+        let store_cookie = StoreCookie { key, payload };
+
+        ConfigurationClientBoundPacket::StoreCookie(store_cookie)
+    }
+}
+
+/// Server tells client to connect to another server, client sends handshake with transfer intent.
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transfer {
+    pub host: String,
+    #[data_type(with = "var_int")]
+    pub port: i32,
+}
+
+impl Transfer {
+    pub fn new(host: String, port: i32) -> ConfigurationClientBoundPacket {
+        let transfer = Transfer { host, port };
+
+        ConfigurationClientBoundPacket::Transfer(transfer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::v1_20_5::configuration::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_transfer_round_trip() {
+        let packet = Transfer::new(String::from("lobby"), 25565);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [5, b'l', b'o', b'b', b'b', b'y', 0xDD, 0xC7, 0x01]);
+
+        match ConfigurationClientBoundPacket::decode(0x0B, &mut Cursor::new(vec)).unwrap() {
+            ConfigurationClientBoundPacket::Transfer(transfer) => {
+                assert_eq!(transfer.host, "lobby");
+                assert_eq!(transfer.port, 25565);
+            }
+            _ => panic!("Expected transfer packet"),
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use crate::data::cookie::CookiePayload;
+use crate::data::identifier::Identifier;
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+pub use crate::data::game::ClientStatusAction;
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameServerBoundPacket {
+    ClientStatus(ClientStatus),
+    CookieResponse(CookieResponse),
+    Pong(Pong),
+}
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameClientBoundPacket {
+    CookieRequest(CookieRequest),
+    Ping(Ping),
+    StoreCookie(StoreCookie),
+    Transfer(Transfer),
+}
+
+impl GameServerBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameServerBoundPacket::ClientStatus(_) => 0x09,
+            GameServerBoundPacket::CookieResponse(_) => 0x11,
+            GameServerBoundPacket::Pong(_) => 0x27,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameServerBoundPacket::ClientStatus(_) => "ClientStatus",
+            GameServerBoundPacket::CookieResponse(_) => "CookieResponse",
+            GameServerBoundPacket::Pong(_) => "Pong",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x09, 0x11, 0x27].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x09 => {
+                let client_status = ClientStatus::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ClientStatus(client_status))
+            }
+            0x11 => {
+                let cookie_response = CookieResponse::decode(reader)?;
+
+                Ok(GameServerBoundPacket::CookieResponse(cookie_response))
+            }
+            0x27 => {
+                let pong = Pong::decode(reader)?;
+
+                Ok(GameServerBoundPacket::Pong(pong))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameServerBoundPacket::ClientStatus(client_status) => client_status.encode(writer),
+            GameServerBoundPacket::CookieResponse(cookie_response) => {
+                cookie_response.encode(writer)
+            }
+            GameServerBoundPacket::Pong(pong) => pong.encode(writer),
+        }
+    }
+}
+
+impl_packet!(GameServerBoundPacket);
+
+impl GameClientBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameClientBoundPacket::CookieRequest(_) => 0x16,
+            GameClientBoundPacket::Ping(_) => 0x35,
+            GameClientBoundPacket::StoreCookie(_) => 0x6B,
+            GameClientBoundPacket::Transfer(_) => 0x73,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameClientBoundPacket::CookieRequest(_) => "CookieRequest",
+            GameClientBoundPacket::Ping(_) => "Ping",
+            GameClientBoundPacket::StoreCookie(_) => "StoreCookie",
+            GameClientBoundPacket::Transfer(_) => "Transfer",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x16, 0x35, 0x6B, 0x73].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x16 => {
+                let cookie_request = CookieRequest::decode(reader)?;
+
+                Ok(GameClientBoundPacket::CookieRequest(cookie_request))
+            }
+            0x35 => {
+                let ping = Ping::decode(reader)?;
+
+                Ok(GameClientBoundPacket::Ping(ping))
+            }
+            0x6B => {
+                let store_cookie = StoreCookie::decode(reader)?;
+
+                Ok(GameClientBoundPacket::StoreCookie(store_cookie))
+            }
+            0x73 => {
+                let transfer = Transfer::decode(reader)?;
+
+                Ok(GameClientBoundPacket::Transfer(transfer))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameClientBoundPacket::CookieRequest(cookie_request) => cookie_request.encode(writer),
+            GameClientBoundPacket::Ping(ping) => ping.encode(writer),
+            GameClientBoundPacket::StoreCookie(store_cookie) => store_cookie.encode(writer),
+            GameClientBoundPacket::Transfer(transfer) => transfer.encode(writer),
+        }
+    }
+}
+
+impl_packet!(GameClientBoundPacket);
+
+/// Client asks to respawn or for statistics.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientStatus {
+    pub action: ClientStatusAction,
+}
+
+impl ClientStatus {
+    pub fn new(action: ClientStatusAction) -> GameServerBoundPacket {
+        let client_status = ClientStatus { action };
+
+        GameServerBoundPacket::ClientStatus(client_status)
+    }
+}
+
+/// Server pings client, which answers with `Pong` with the same id.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ping {
+    pub id: i32,
+}
+
+impl Ping {
+    pub fn new(id: i32) -> GameClientBoundPacket {
+        let ping = Ping { id };
+
+        GameClientBoundPacket::Ping(ping)
+    }
+}
+
+/// Answer to `Ping`.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pong {
+    pub id: i32,
+}
+
+impl Pong {
+    pub fn new(id: i32) -> GameServerBoundPacket {
+        let pong = Pong { id };
+
+        GameServerBoundPacket::Pong(pong)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CookieRequest {
+    pub key: Identifier,
+}
+
+impl CookieRequest {
+    pub fn new(key: Identifier) -> GameClientBoundPacket {
+        let cookie_request = CookieRequest { key };
+
+        GameClientBoundPacket::CookieRequest(cookie_request)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CookieResponse {
+    pub key: Identifier,
+    pub payload: Option<CookiePayload>,
+}
+
+impl CookieResponse {
+    pub fn new(key: Identifier, payload: Option<CookiePayload>) -> GameServerBoundPacket {
+        let cookie_response = CookieResponse { key, payload };
+
+        GameServerBoundPacket::CookieResponse(cookie_response)
+    }
+}
+
+/// Server asks client to store cookie, it's kept until client quits the game.
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StoreCookie {
+    pub key: Identifier,
+    pub payload: CookiePayload,
+}
+
+impl StoreCookie {
+    pub fn new(key: Identifier, payload: CookiePayload) -> GameClientBoundPacket {
+        let store_cookie = StoreCookie { key, payload };
+
+        GameClientBoundPacket::StoreCookie(store_cookie)
+    }
+}
+
+/// Server tells client to connect to another server, client sends handshake with transfer intent.
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transfer {
+    pub host: String,
+    #[data_type(with = "var_int")]
+    pub port: i32,
+}
+
+impl Transfer {
+    pub fn new(host: String, port: i32) -> GameClientBoundPacket {
+        let transfer = Transfer { host, port };
+
+        GameClientBoundPacket::Transfer(transfer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::v1_20_5::game::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_store_cookie_round_trip() {
+        let payload = CookiePayload::new(b"token".to_vec()).unwrap();
+        let packet = StoreCookie::new(Identifier::from("lobby:session"), payload.clone());
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        match GameClientBoundPacket::decode(0x6B, &mut Cursor::new(vec)).unwrap() {
+            GameClientBoundPacket::StoreCookie(store_cookie) => {
+                assert_eq!(store_cookie.key.as_str(), "lobby:session");
+                assert_eq!(store_cookie.payload, payload);
+            }
+            _ => panic!("Expected store cookie packet"),
+        }
+    }
+
+    #[test]
+    fn test_ping_round_trip() {
+        let packet = Ping::new(1024);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(vec, [0, 0, 4, 0]);
+
+        match GameClientBoundPacket::decode(0x35, &mut Cursor::new(vec)).unwrap() {
+            GameClientBoundPacket::Ping(ping) => assert_eq!(ping.id, 1024),
+            _ => panic!("Expected ping packet"),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::data::cookie::CookiePayload;
+use crate::data::identifier::Identifier;
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum LoginServerBoundPacket {
+    CookieResponse(CookieResponse),
+}
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum LoginClientBoundPacket {
+    CookieRequest(CookieRequest),
+}
+
+impl LoginServerBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            LoginServerBoundPacket::CookieResponse(_) => 0x04,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoginServerBoundPacket::CookieResponse(_) => "CookieResponse",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x04].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x04 => {
+                let cookie_response = CookieResponse::decode(reader)?;
+
+                Ok(LoginServerBoundPacket::CookieResponse(cookie_response))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            LoginServerBoundPacket::CookieResponse(cookie_response) => {
+                cookie_response.encode(writer)
+            }
+        }
+    }
+}
+
+impl_packet!(LoginServerBoundPacket);
+
+impl LoginClientBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            LoginClientBoundPacket::CookieRequest(_) => 0x05,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoginClientBoundPacket::CookieRequest(_) => "CookieRequest",
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x05].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x05 => {
+                let cookie_request = CookieRequest::decode(reader)?;
+
+                Ok(LoginClientBoundPacket::CookieRequest(cookie_request))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            LoginClientBoundPacket::CookieRequest(cookie_request) => cookie_request.encode(writer),
+        }
+    }
+}
+
+impl_packet!(LoginClientBoundPacket);
+
+/// Server asks client for cookie it stored before, e.g. on another server before transfer.
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CookieRequest {
+    pub key: Identifier,
+}
+
+impl CookieRequest {
+    pub fn new(key: Identifier) -> LoginClientBoundPacket {
+        let cookie_request = CookieRequest { key };
+
+        LoginClientBoundPacket::CookieRequest(cookie_request)
+    }
+}
+
+/// Client sends requested cookie, payload is empty if client has no such cookie.
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CookieResponse {
+    pub key: Identifier,
+    pub payload: Option<CookiePayload>,
+}
+
+impl CookieResponse {
+    pub fn new(key: Identifier, payload: Option<CookiePayload>) -> LoginServerBoundPacket {
+        let cookie_response = CookieResponse { key, payload };
+
+        LoginServerBoundPacket::CookieResponse(cookie_response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::v1_20_5::login::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_cookie_response_round_trip() {
+        let payload = CookiePayload::new(vec![1, 2]).unwrap();
+        let packet = CookieResponse::new(Identifier::from("lobby:session"), Some(payload.clone()));
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(&vec[vec.len() - 4..], [1, 2, 1, 2]);
+
+        match LoginServerBoundPacket::decode(0x04, &mut Cursor::new(vec)).unwrap() {
+            LoginServerBoundPacket::CookieResponse(cookie_response) => {
+                assert_eq!(cookie_response.key.as_str(), "lobby:session");
+                assert_eq!(cookie_response.payload, Some(payload));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cookie_response_without_payload() {
+        let packet = CookieResponse::new(Identifier::from("lobby:session"), None);
+
+        let mut vec = Vec::new();
+        packet.encode(&mut vec).unwrap();
+
+        assert_eq!(vec.last(), Some(&0));
+
+        match LoginServerBoundPacket::decode(0x04, &mut Cursor::new(vec)).unwrap() {
+            LoginServerBoundPacket::CookieResponse(cookie_response) => {
+                assert_eq!(cookie_response.payload, None)
+            }
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod configuration;
+pub mod game;
+pub mod login;
@@ -0,0 +1,268 @@
+//! Game packets of 1.8 which differ in later versions.
+//!
+//! Keep-alive id is var int until 1.12.1, 1.12.2 changed it to long.
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::error::{DecodeError, EncodeError};
+use crate::packet::impl_packet;
+use minecraft_protocol_derive::{Decoder, Encoder, PacketVariants, VisitMessages};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameServerBoundPacket {
+    ServerBoundKeepAlive(ServerBoundKeepAlive),
+    ServerBoundConfirmTransaction(ServerBoundConfirmTransaction),
+}
+
+#[derive(VisitMessages, PacketVariants, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GameClientBoundPacket {
+    ClientBoundKeepAlive(ClientBoundKeepAlive),
+    ClientBoundConfirmTransaction(ClientBoundConfirmTransaction),
+}
+
+impl GameServerBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameServerBoundPacket::ServerBoundKeepAlive(_) => 0x00,
+            GameServerBoundPacket::ServerBoundConfirmTransaction(_) => 0x0F,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameServerBoundPacket::ServerBoundKeepAlive(_) => "ServerBoundKeepAlive",
+            GameServerBoundPacket::ServerBoundConfirmTransaction(_) => {
+                "ServerBoundConfirmTransaction"
+            }
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x00, 0x0F].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x00 => {
+                let keep_alive = ServerBoundKeepAlive::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ServerBoundKeepAlive(keep_alive))
+            }
+            0x0F => {
+                let confirm_transaction = ServerBoundConfirmTransaction::decode(reader)?;
+
+                Ok(GameServerBoundPacket::ServerBoundConfirmTransaction(
+                    confirm_transaction,
+                ))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => keep_alive.encode(writer),
+            GameServerBoundPacket::ServerBoundConfirmTransaction(confirm_transaction) => {
+                confirm_transaction.encode(writer)
+            }
+        }
+    }
+}
+
+impl_packet!(GameServerBoundPacket);
+
+impl GameClientBoundPacket {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            GameClientBoundPacket::ClientBoundKeepAlive(_) => 0x00,
+            GameClientBoundPacket::ClientBoundConfirmTransaction(_) => 0x32,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameClientBoundPacket::ClientBoundKeepAlive(_) => "ClientBoundKeepAlive",
+            GameClientBoundPacket::ClientBoundConfirmTransaction(_) => {
+                "ClientBoundConfirmTransaction"
+            }
+        }
+    }
+
+    /// Returns type ids of all packets in enum.
+    pub fn iter_ids() -> impl Iterator<Item = u8> {
+        [0x00, 0x32].iter().copied()
+    }
+
+    pub fn decode<R: Read>(type_id: u8, reader: &mut R) -> Result<Self, DecodeError> {
+        match type_id {
+            0x00 => {
+                let keep_alive = ClientBoundKeepAlive::decode(reader)?;
+
+                Ok(GameClientBoundPacket::ClientBoundKeepAlive(keep_alive))
+            }
+            0x32 => {
+                let confirm_transaction = ClientBoundConfirmTransaction::decode(reader)?;
+
+                Ok(GameClientBoundPacket::ClientBoundConfirmTransaction(
+                    confirm_transaction,
+                ))
+            }
+            _ => Err(DecodeError::UnknownPacketType { type_id }),
+        }
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        match self {
+            GameClientBoundPacket::ClientBoundKeepAlive(keep_alive) => keep_alive.encode(writer),
+            GameClientBoundPacket::ClientBoundConfirmTransaction(confirm_transaction) => {
+                confirm_transaction.encode(writer)
+            }
+        }
+    }
+}
+
+impl_packet!(GameClientBoundPacket);
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ServerBoundKeepAlive {
+    #[data_type(with = "var_int")]
+    pub id: i32,
+}
+
+impl ServerBoundKeepAlive {
+    pub fn new(id: i32) -> GameServerBoundPacket {
+        let keep_alive = ServerBoundKeepAlive { id };
+
+        GameServerBoundPacket::ServerBoundKeepAlive(keep_alive)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientBoundKeepAlive {
+    #[data_type(with = "var_int")]
+    pub id: i32,
+}
+
+impl ClientBoundKeepAlive {
+    pub fn new(id: i32) -> GameClientBoundPacket {
+        let keep_alive = ClientBoundKeepAlive { id };
+
+        GameClientBoundPacket::ClientBoundKeepAlive(keep_alive)
+    }
+
+    /// Returns keep-alive which client sends back.
+    pub fn answer(&self) -> GameServerBoundPacket {
+        ServerBoundKeepAlive::new(self.id)
+    }
+}
+
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ServerBoundConfirmTransaction {
+    pub window_id: i8,
+    pub action_number: i16,
+    pub accepted: bool,
+}
+
+impl ServerBoundConfirmTransaction {
+    pub fn new(window_id: i8, action_number: i16, accepted: bool) -> GameServerBoundPacket {
+        let confirm_transaction = ServerBoundConfirmTransaction {
+            window_id,
+            action_number,
+            accepted,
+        };
+
+        GameServerBoundPacket::ServerBoundConfirmTransaction(confirm_transaction)
+    }
+}
+
+/// Confirms or rejects window click.
+///
+/// Client answers rejected transactions, so servers also send them to window 0 to measure
+/// latency.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClientBoundConfirmTransaction {
+    pub window_id: i8,
+    pub action_number: i16,
+    pub accepted: bool,
+}
+
+impl ClientBoundConfirmTransaction {
+    pub fn new(window_id: i8, action_number: i16, accepted: bool) -> GameClientBoundPacket {
+        let confirm_transaction = ClientBoundConfirmTransaction {
+            window_id,
+            action_number,
+            accepted,
+        };
+
+        GameClientBoundPacket::ClientBoundConfirmTransaction(confirm_transaction)
+    }
+
+    /// Returns confirmation which vanilla client sends back, `None` for accepted transactions.
+    pub fn answer(&self) -> Option<GameServerBoundPacket> {
+        (!self.accepted)
+            .then(|| ServerBoundConfirmTransaction::new(self.window_id, self.action_number, true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::v1_8::game::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_keep_alive_var_int_id() {
+        let keep_alive = ClientBoundKeepAlive { id: 300 };
+
+        let mut vec = Vec::new();
+        keep_alive.encode(&mut vec).unwrap();
+        assert_eq!(vec, [0xAC, 0x02]);
+
+        let mut vec = Vec::new();
+        keep_alive.answer().encode(&mut vec).unwrap();
+
+        match GameServerBoundPacket::decode(0x00, &mut Cursor::new(vec)).unwrap() {
+            GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => {
+                assert_eq!(keep_alive.id, 300)
+            }
+            packet => panic!("Expected `ServerBoundKeepAlive` but got `{:?}`", packet),
+        }
+    }
+
+    #[test]
+    fn test_confirm_transaction_answer() {
+        let mut cursor = Cursor::new(vec![0, 0xFF, 0xF6, 0]);
+        let confirm_transaction = ClientBoundConfirmTransaction::decode(&mut cursor).unwrap();
+
+        assert_eq!(confirm_transaction.action_number, -10);
+
+        match confirm_transaction.answer() {
+            Some(GameServerBoundPacket::ServerBoundConfirmTransaction(answer)) => {
+                assert_eq!(answer.window_id, 0);
+                assert_eq!(answer.action_number, -10);
+                assert!(answer.accepted);
+            }
+            packet => panic!(
+                "Expected `ServerBoundConfirmTransaction` but got `{:?}`",
+                packet
+            ),
+        }
+
+        let accepted = ClientBoundConfirmTransaction {
+            accepted: true,
+            ..confirm_transaction
+        };
+
+        assert!(accepted.answer().is_none());
+    }
+}
@@ -0,0 +1,377 @@
+//! Packets declared outside the crate using derive macros re-exported from it.
+//!
+//! Derived code must compile with only `minecraft_protocol` in scope, without helper traits or
+//! `byteorder` imported.
+use minecraft_protocol::data::chat::{Message, VisitMessages};
+use minecraft_protocol::decoder::Decoder;
+use minecraft_protocol::derive::{Builder, Decoder, Encoder, Validate, VisitMessages};
+use minecraft_protocol::encoder::{Encoder, EncoderWriteExt};
+use minecraft_protocol::error::DecodeError;
+use minecraft_protocol::impl_json_encoder_decoder;
+use minecraft_protocol::validate::{Validate, ViolationKind};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::num::{NonZeroU16, NonZeroU32};
+
+#[derive(Encoder, Decoder, Builder, VisitMessages, Validate, Debug, PartialEq)]
+struct Announcement {
+    #[data_type(with = "var_int")]
+    id: i32,
+    #[data_type(max_length = 16)]
+    channel: String,
+    #[data_type(bitfield)]
+    pinned: bool,
+    #[data_type(bitfield)]
+    silent: bool,
+    message: Message,
+    #[builder(default)]
+    priority: Priority,
+    #[builder(default)]
+    #[data_type(with = "list")]
+    #[validate(nested, max_items = 2)]
+    targets: Vec<Target>,
+}
+
+#[derive(Encoder, Decoder, Debug, Default, PartialEq)]
+#[data_type(with = "var_int")]
+enum Priority {
+    #[default]
+    Low,
+    High,
+}
+
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+#[data_type(with = "i32")]
+#[repr(i32)]
+enum Target {
+    Player {
+        #[validate(max_length = 16)]
+        name: String,
+        #[validate(min = 1, max = 10)]
+        level: u8,
+    } = 3,
+    Everyone = 7,
+}
+
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+struct Transfer {
+    id: u128,
+    world: NonZeroU32,
+    slot: Option<NonZeroU16>,
+}
+
+/// Fragment shared by packets with items of different types.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+struct Counted<T> {
+    #[data_type(with = "list")]
+    items: Vec<T>,
+}
+
+/// Borrows data while encoding, decoded view owns it.
+#[derive(Encoder, Decoder, Debug, PartialEq)]
+struct ChatView<'a> {
+    sender: Cow<'a, str>,
+    data: Cow<'a, [u8]>,
+    tags: Counted<i32>,
+}
+
+#[derive(Encoder)]
+struct Borrowed<'a, T> {
+    sender: &'a str,
+    data: &'a [u8],
+    tags: &'a T,
+}
+
+/// All bits of flags byte are used.
+#[derive(Encoder, Decoder, Debug, Default, PartialEq)]
+struct Flags {
+    #[data_type(bitfield)]
+    bit_0: bool,
+    #[data_type(bitfield)]
+    bit_1: bool,
+    #[data_type(bitfield)]
+    bit_2: bool,
+    #[data_type(bitfield)]
+    bit_3: bool,
+    #[data_type(bitfield)]
+    bit_4: bool,
+    #[data_type(bitfield)]
+    bit_5: bool,
+    #[data_type(bitfield)]
+    bit_6: bool,
+    #[data_type(bitfield)]
+    bit_7: bool,
+}
+
+#[derive(Encoder, Decoder, Validate, Debug, PartialEq)]
+struct SignLine {
+    #[data_type(max_length = 16)]
+    text: String,
+}
+
+/// Encoded as JSON string.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Motd {
+    text: String,
+    players: u32,
+}
+
+impl_json_encoder_decoder!(Motd);
+
+#[test]
+fn test_derived_struct_round_trip() {
+    let mut announcement = Announcement::builder()
+        .id(300)
+        .channel(String::from("news"))
+        .pinned(true)
+        .silent(false)
+        .message(Message::from_str("Restart"))
+        .build()
+        .unwrap();
+
+    let mut vec = Vec::new();
+    announcement.encode(&mut vec).unwrap();
+
+    assert_eq!(&vec[..3], [0xAC, 0x02, 4]);
+    assert_eq!(
+        Announcement::decode(&mut Cursor::new(vec)).unwrap(),
+        announcement
+    );
+
+    let mut count = 0;
+    announcement.visit_messages(&mut |_| count += 1);
+
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_derived_enum_round_trip() {
+    let target = Target::Player {
+        name: String::from("Username"),
+        level: 5,
+    };
+
+    let mut vec = Vec::new();
+    target.encode(&mut vec).unwrap();
+
+    assert_eq!(&vec[..4], [0, 0, 0, 3]);
+    assert_eq!(Target::decode(&mut Cursor::new(vec)).unwrap(), target);
+
+    let mut vec = Vec::new();
+    Target::Everyone.encode(&mut vec).unwrap();
+
+    assert_eq!(vec, [0, 0, 0, 7]);
+}
+
+#[test]
+fn test_derived_generic_round_trip() {
+    let numbers = Counted { items: vec![1, -1] };
+    let names = Counted {
+        items: vec![String::from("Steve"), String::from("Alex")],
+    };
+
+    let mut vec = Vec::new();
+    numbers.encode(&mut vec).unwrap();
+
+    assert_eq!(vec, [2, 0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(
+        Counted::<i32>::decode(&mut Cursor::new(vec)).unwrap(),
+        numbers
+    );
+
+    let mut vec = Vec::new();
+    names.encode(&mut vec).unwrap();
+
+    assert_eq!(
+        Counted::<String>::decode(&mut Cursor::new(vec)).unwrap(),
+        names
+    );
+}
+
+#[test]
+fn test_derived_borrowed_view() {
+    let data = [1, 2, 3];
+    let tags = Counted { items: vec![7] };
+
+    let view = ChatView {
+        sender: Cow::Borrowed("Steve"),
+        data: Cow::Borrowed(&data),
+        tags: Counted { items: vec![7] },
+    };
+    let borrowed = Borrowed {
+        sender: "Steve",
+        data: &data,
+        tags: &tags,
+    };
+
+    let mut vec = Vec::new();
+    view.encode(&mut vec).unwrap();
+
+    let mut borrowed_vec = Vec::new();
+    borrowed.encode(&mut borrowed_vec).unwrap();
+
+    assert_eq!(vec, borrowed_vec);
+
+    let decoded = ChatView::decode(&mut Cursor::new(vec)).unwrap();
+
+    assert!(matches!(decoded.sender, Cow::Owned(_)));
+    assert_eq!(decoded, view);
+}
+
+#[test]
+fn test_derived_bitfield_high_bit() {
+    let flags = Flags {
+        bit_0: true,
+        bit_7: true,
+        ..Flags::default()
+    };
+
+    let mut vec = Vec::new();
+    flags.encode(&mut vec).unwrap();
+
+    assert_eq!(vec, [0x81]);
+    assert_eq!(Flags::decode(&mut Cursor::new(vec)).unwrap(), flags);
+}
+
+#[test]
+fn test_derived_struct_non_zero_fields() {
+    let transfer = Transfer {
+        id: u128::MAX - 1,
+        world: NonZeroU32::new(5).unwrap(),
+        slot: NonZeroU16::new(2),
+    };
+
+    let mut vec = Vec::new();
+    transfer.encode(&mut vec).unwrap();
+
+    assert_eq!(vec.len(), 16 + 4 + 1 + 2);
+    assert_eq!(Transfer::decode(&mut Cursor::new(&vec)).unwrap(), transfer);
+
+    vec[16..20].copy_from_slice(&[0; 4]);
+    let decode_error = Transfer::decode(&mut Cursor::new(vec))
+        .expect_err("Expected error `ZeroValue` because world is zero");
+
+    match decode_error {
+        DecodeError::ZeroValue => {}
+        _ => panic!("Expected `ZeroValue` but got `{:?}`", decode_error),
+    }
+}
+
+#[test]
+fn test_derived_string_max_length_matches_vanilla() {
+    // 1 to 4 bytes in UTF-8, the last one is 2 code units in UTF-16.
+    const CHARS: [char; 4] = ['a', '\u{e9}', '\u{20ac}', '\u{1f600}'];
+
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..1000 {
+        let length = rng.gen_range(8..=24);
+        let mut text = String::new();
+
+        while text.encode_utf16().count() < length {
+            text.push(CHARS[rng.gen_range(0..CHARS.len())]);
+        }
+
+        // Vanilla counts UTF-16 code units.
+        let accepted = text.encode_utf16().count() <= 16;
+        let sign_line = SignLine { text };
+
+        let mut vec = Vec::new();
+        let encode_result = sign_line.encode(&mut vec);
+
+        let mut unlimited = Vec::new();
+        unlimited.write_string(&sign_line.text, u16::MAX).unwrap();
+        let decode_result = SignLine::decode(&mut Cursor::new(&unlimited));
+
+        assert_eq!(encode_result.is_ok(), accepted, "Encoded {:?}", sign_line);
+        assert_eq!(decode_result.is_ok(), accepted, "Decoded {:?}", sign_line);
+        assert_eq!(sign_line.validate().is_empty(), accepted);
+
+        if accepted {
+            assert_eq!(vec, unlimited);
+            assert_eq!(decode_result.unwrap(), sign_line);
+        }
+    }
+}
+
+#[test]
+fn test_json_encoder_decoder_round_trip() {
+    let motd = Motd {
+        text: String::from("Hello"),
+        players: 3,
+    };
+
+    let mut vec = Vec::new();
+    motd.encode(&mut vec).unwrap();
+
+    assert_eq!(vec[0] as usize, vec.len() - 1);
+    assert_eq!(Motd::decode(&mut Cursor::new(vec)).unwrap(), motd);
+}
+
+#[test]
+fn test_derived_validate() {
+    let mut announcement = Announcement::builder()
+        .id(1)
+        .channel(String::from("news"))
+        .pinned(false)
+        .silent(false)
+        .message(Message::from_str("Restart"))
+        .build()
+        .unwrap();
+
+    assert!(announcement.validate().is_empty());
+
+    announcement.channel = "a".repeat(17);
+    announcement.targets = vec![
+        Target::Everyone,
+        Target::Player {
+            name: "b".repeat(20),
+            level: 11,
+        },
+        Target::Everyone,
+    ];
+
+    let violations: Vec<_> = announcement
+        .validate()
+        .into_iter()
+        .map(|v| (v.field, v.kind))
+        .collect();
+
+    assert_eq!(
+        violations,
+        [
+            (
+                String::from("channel"),
+                ViolationKind::TooLong {
+                    length: 17,
+                    max_length: 16
+                }
+            ),
+            (
+                String::from("targets"),
+                ViolationKind::TooManyItems {
+                    length: 3,
+                    max_length: 2
+                }
+            ),
+            (
+                String::from("targets[1].name"),
+                ViolationKind::TooLong {
+                    length: 20,
+                    max_length: 16
+                }
+            ),
+            (
+                String::from("targets[1].level"),
+                ViolationKind::OutOfRange {
+                    value: 11,
+                    min: Some(1),
+                    max: Some(10)
+                }
+            ),
+        ]
+    );
+}
@@ -0,0 +1,207 @@
+//! Packet ids of every packet enum checked against `test/packet_ids.csv`.
+//!
+//! Ids are written by hand in `get_type_id` match arms, so arms are read from the source of the
+//! enum and compared with ids of minecraft-data. Ids of arms must also be the ids returned by
+//! `iter_ids`, which decoding follows.
+#![allow(dead_code)]
+
+use minecraft_protocol::packet::Packet;
+use std::any;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// Expected ids by version, state, bound and packet name.
+type ExpectedIds = BTreeMap<(String, String, String, String), u8>;
+
+fn expected_ids() -> ExpectedIds {
+    let csv = include_str!("../test/packet_ids.csv");
+    let mut expected_ids = BTreeMap::new();
+
+    for line in csv.lines().filter(|line| !line.starts_with('#')).skip(1) {
+        let columns: Vec<&str> = line.split(',').collect();
+        assert_eq!(columns.len(), 6, "Malformed line `{}`", line);
+
+        let key = (
+            columns[0].to_owned(),
+            columns[1].to_owned(),
+            columns[2].to_owned(),
+            columns[3].to_owned(),
+        );
+        let id = u8::from_str_radix(columns[5].trim_start_matches("0x"), 16).unwrap();
+
+        assert!(
+            expected_ids.insert(key, id).is_none(),
+            "Duplicate line `{}`",
+            line
+        );
+    }
+
+    expected_ids
+}
+
+/// Returns ids of variants from `get_type_id` of enum in source file.
+fn type_id_arms(source: &str, enum_name: &str) -> BTreeMap<String, u8> {
+    let start = source
+        .find(&format!("impl {} {{", enum_name))
+        .and_then(|index| {
+            source[index..]
+                .find("pub fn get_type_id")
+                .map(|offset| index + offset)
+        })
+        .unwrap_or_else(|| panic!("No `get_type_id` of `{}`", enum_name));
+    let body = &source[start..];
+    let end = body.find("\n    }\n").unwrap();
+    let prefix = format!("{}::", enum_name);
+
+    body[..end]
+        .lines()
+        .filter_map(|line| {
+            let (variant, id) = line.trim().split_once(" => ")?;
+            let variant = variant.strip_prefix(&prefix)?.trim_end_matches("(_)");
+            let id = u8::from_str_radix(id.trim_end_matches(',').trim_start_matches("0x"), 16)
+                .unwrap_or_else(|_| panic!("Invalid id in `{}`", line));
+
+            Some((variant.to_owned(), id))
+        })
+        .collect()
+}
+
+/// Checks ids of packet enum `P` of version module against expected ids.
+fn check<P: Packet>(expected_ids: &ExpectedIds, version: &str, state: &str) {
+    let type_name = any::type_name::<P>();
+    let enum_name = type_name.rsplit("::").next().unwrap();
+    let module = type_name.split("::").nth(2).unwrap();
+    let bound = if enum_name.contains("ServerBound") {
+        "server"
+    } else {
+        "client"
+    };
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/version")
+        .join(module)
+        .join(format!("{}.rs", state));
+    let source = fs::read_to_string(&path).unwrap();
+    let arms = type_id_arms(&source, enum_name);
+
+    assert!(!arms.is_empty(), "No ids of `{}` found", type_name);
+
+    for (variant, id) in &arms {
+        let key = (
+            version.to_owned(),
+            state.to_owned(),
+            bound.to_owned(),
+            variant.clone(),
+        );
+
+        match expected_ids.get(&key) {
+            Some(expected_id) => assert_eq!(
+                id, expected_id,
+                "{} {} has id {:#04X} instead of {:#04X}",
+                version, variant, id, expected_id
+            ),
+            None => panic!(
+                "{} {} {} is missing in packet_ids.csv",
+                version, state, variant
+            ),
+        }
+    }
+
+    let arm_ids: BTreeSet<u8> = arms.values().copied().collect();
+    let iter_ids: BTreeSet<u8> = P::iter_ids().collect();
+    assert_eq!(arm_ids, iter_ids, "`iter_ids` of `{}` differ", type_name);
+
+    let implemented = expected_ids
+        .keys()
+        .filter(|(v, s, b, _)| v == version && s == state && b == bound)
+        .count();
+    assert_eq!(implemented, arms.len(), "Extra ids of `{}`", type_name);
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn test_legacy_ids() {
+    use minecraft_protocol::version::{v1_12_2, v1_8};
+
+    let expected_ids = expected_ids();
+
+    check::<v1_8::game::GameServerBoundPacket>(&expected_ids, "1.8", "game");
+    check::<v1_8::game::GameClientBoundPacket>(&expected_ids, "1.8", "game");
+    check::<v1_12_2::game::GameServerBoundPacket>(&expected_ids, "1.12.2", "game");
+    check::<v1_12_2::game::GameClientBoundPacket>(&expected_ids, "1.12.2", "game");
+}
+
+#[cfg(feature = "v1_14_4")]
+#[test]
+fn test_v1_14_4_ids() {
+    use minecraft_protocol::version::v1_14_4::*;
+
+    let expected_ids = expected_ids();
+
+    check::<handshake::HandshakeServerBoundPacket>(&expected_ids, "1.14.4", "handshake");
+    check::<status::StatusServerBoundPacket>(&expected_ids, "1.14.4", "status");
+    check::<status::StatusClientBoundPacket>(&expected_ids, "1.14.4", "status");
+    check::<login::LoginServerBoundPacket>(&expected_ids, "1.14.4", "login");
+    check::<login::LoginClientBoundPacket>(&expected_ids, "1.14.4", "login");
+    check::<game::GameServerBoundPacket>(&expected_ids, "1.14.4", "game");
+    check::<game::GameClientBoundPacket>(&expected_ids, "1.14.4", "game");
+}
+
+#[cfg(feature = "v1_16_5")]
+#[test]
+fn test_v1_16_5_ids() {
+    use minecraft_protocol::version::v1_16_5::*;
+
+    let expected_ids = expected_ids();
+
+    check::<login::LoginServerBoundPacket>(&expected_ids, "1.16.5", "login");
+    check::<login::LoginClientBoundPacket>(&expected_ids, "1.16.5", "login");
+    check::<game::GameServerBoundPacket>(&expected_ids, "1.16.5", "game");
+    check::<game::GameClientBoundPacket>(&expected_ids, "1.16.5", "game");
+}
+
+#[cfg(feature = "v1_19")]
+#[test]
+fn test_v1_19_ids() {
+    use minecraft_protocol::version::v1_19::*;
+
+    let expected_ids = expected_ids();
+
+    check::<game::GameServerBoundPacket>(&expected_ids, "1.19", "game");
+    check::<game::GameClientBoundPacket>(&expected_ids, "1.19", "game");
+}
+
+#[cfg(feature = "v1_20_2")]
+#[test]
+fn test_v1_20_2_ids() {
+    use minecraft_protocol::version::v1_20_2::*;
+
+    let expected_ids = expected_ids();
+
+    check::<game::GameServerBoundPacket>(&expected_ids, "1.20.2", "game");
+    check::<game::GameClientBoundPacket>(&expected_ids, "1.20.2", "game");
+}
+
+#[cfg(feature = "v1_20_5")]
+#[test]
+fn test_v1_20_5_ids() {
+    use minecraft_protocol::version::v1_20_5::*;
+
+    let expected_ids = expected_ids();
+
+    check::<login::LoginServerBoundPacket>(&expected_ids, "1.20.5", "login");
+    check::<login::LoginClientBoundPacket>(&expected_ids, "1.20.5", "login");
+    check::<configuration::ConfigurationServerBoundPacket>(
+        &expected_ids,
+        "1.20.5",
+        "configuration",
+    );
+    check::<configuration::ConfigurationClientBoundPacket>(
+        &expected_ids,
+        "1.20.5",
+        "configuration",
+    );
+    check::<game::GameServerBoundPacket>(&expected_ids, "1.20.5", "game");
+    check::<game::GameClientBoundPacket>(&expected_ids, "1.20.5", "game");
+}
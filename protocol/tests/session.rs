@@ -0,0 +1,308 @@
+//! Simulated status and login + play sessions between a client and a server connected with an
+//! in-memory duplex stream.
+//!
+//! Both sides only use public crate API, so this test also documents how packets, framing and
+//! dispatch fit together.
+//!
+//! Encrypted login is not covered yet because the crate has no stream encryption support.
+use minecraft_protocol::codec::PacketCodec;
+use minecraft_protocol::data::chat::{Message, Payload};
+use minecraft_protocol::data::server_status::{OnlinePlayers, ServerStatus, ServerVersion};
+use minecraft_protocol::packet::Packet;
+use minecraft_protocol::version::v1_14_4::game::*;
+use minecraft_protocol::version::v1_14_4::handshake::*;
+use minecraft_protocol::version::v1_14_4::login::*;
+use minecraft_protocol::version::v1_14_4::status::*;
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use uuid::Uuid;
+
+const PROTOCOL_VERSION: i32 = 498;
+
+const COMPRESSION_THRESHOLD: usize = 64;
+
+type Connection = PacketCodec<Stream>;
+
+/// One direction of the duplex stream.
+#[derive(Default)]
+struct Pipe {
+    state: Mutex<(VecDeque<u8>, bool)>,
+    condvar: Condvar,
+}
+
+/// End of the duplex stream. Dropping it closes the stream for the other end.
+struct Stream {
+    incoming: Arc<Pipe>,
+    outgoing: Arc<Pipe>,
+}
+
+fn duplex() -> (Stream, Stream) {
+    let a = Arc::new(Pipe::default());
+    let b = Arc::new(Pipe::default());
+
+    let client = Stream {
+        incoming: a.clone(),
+        outgoing: b.clone(),
+    };
+    let server = Stream {
+        incoming: b,
+        outgoing: a,
+    };
+
+    (client, server)
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.incoming.state.lock().unwrap();
+
+        while state.0.is_empty() && !state.1 {
+            state = self.incoming.condvar.wait(state).unwrap();
+        }
+
+        let length = buf.len().min(state.0.len());
+
+        for (dst, src) in buf.iter_mut().zip(state.0.drain(..length)) {
+            *dst = src;
+        }
+
+        Ok(length)
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.outgoing.state.lock().unwrap();
+        state.0.extend(buf);
+        self.outgoing.condvar.notify_all();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        let mut state = self.outgoing.state.lock().unwrap();
+        state.1 = true;
+        self.outgoing.condvar.notify_all();
+    }
+}
+
+fn send<P: Packet>(stream: &mut Connection, packet: P) {
+    stream.write_packet(&packet).unwrap();
+}
+
+fn receive<P: Packet>(stream: &mut Connection) -> P {
+    stream.read_packet().unwrap()
+}
+
+fn server(mut stream: Connection) {
+    let handshake = match receive(&mut stream) {
+        HandshakeServerBoundPacket::Handshake(handshake) => handshake,
+        packet => panic!("Expected handshake but got {}", packet.name()),
+    };
+
+    assert_eq!(handshake.protocol_version, PROTOCOL_VERSION);
+
+    match handshake.next_state {
+        NextState::Status => server_status(stream),
+        NextState::Login => server_login(stream),
+        next_state => panic!("Unexpected next state {:?}", next_state),
+    }
+}
+
+fn server_status(mut stream: Connection) {
+    match receive(&mut stream) {
+        StatusServerBoundPacket::StatusRequest => {}
+        packet => panic!("Expected status request but got {:?}", packet),
+    }
+
+    let server_status = ServerStatus {
+        version: ServerVersion {
+            name: String::from("1.14.4"),
+            protocol: PROTOCOL_VERSION as u32,
+        },
+        players: OnlinePlayers {
+            max: 20,
+            online: 0,
+            sample: vec![],
+        },
+        description: Message::new(Payload::text("A Minecraft Server")),
+        favicon: None,
+        enforces_secure_chat: None,
+    };
+
+    send(&mut stream, StatusResponse::new(server_status));
+
+    match receive(&mut stream) {
+        StatusServerBoundPacket::PingRequest(ping_request) => {
+            send(&mut stream, PingResponse::new(ping_request.time))
+        }
+        packet => panic!("Expected ping request but got {:?}", packet),
+    }
+}
+
+fn server_login(mut stream: Connection) {
+    let username = match receive(&mut stream) {
+        LoginServerBoundPacket::LoginStart(login_start) => login_start.name,
+        packet => panic!("Expected login start but got {:?}", packet),
+    };
+
+    send(
+        &mut stream,
+        SetCompression::new(COMPRESSION_THRESHOLD as i32),
+    );
+    stream.set_compression_threshold(Some(COMPRESSION_THRESHOLD));
+
+    send(
+        &mut stream,
+        LoginSuccess::new(Uuid::new_v4(), username.clone()),
+    );
+    send(
+        &mut stream,
+        JoinGame::new(
+            1,
+            GameMode::Survival,
+            0,
+            20,
+            String::from("default"),
+            10,
+            false,
+        ),
+    );
+    send(&mut stream, ClientBoundKeepAlive::new(42));
+
+    match receive(&mut stream) {
+        GameServerBoundPacket::ServerBoundKeepAlive(keep_alive) => assert_eq!(keep_alive.id, 42),
+        packet => panic!("Expected keep alive but got {:?}", packet),
+    }
+
+    match receive(&mut stream) {
+        GameServerBoundPacket::ServerBoundChatMessage(chat_message) => {
+            let text = format!("<{}> {}", username, chat_message.message);
+
+            send(
+                &mut stream,
+                ClientBoundChatMessage::new(Message::from_str(&text), MessagePosition::Chat),
+            );
+        }
+        packet => panic!("Expected chat message but got {:?}", packet),
+    }
+
+    send(
+        &mut stream,
+        GameDisconnect::new(Message::from_str("Server closed")),
+    );
+}
+
+fn handshake(stream: &mut Connection, next_state: NextState) {
+    send(
+        stream,
+        Handshake::new(
+            PROTOCOL_VERSION,
+            String::from("localhost"),
+            25565,
+            next_state,
+        ),
+    );
+}
+
+#[test]
+fn test_status_session() {
+    let (client, server_stream) = duplex();
+    let server = thread::spawn(move || server(PacketCodec::new(server_stream)));
+    let mut client = PacketCodec::new(client);
+
+    handshake(&mut client, NextState::Status);
+    send(&mut client, StatusServerBoundPacket::StatusRequest);
+
+    match receive(&mut client) {
+        StatusClientBoundPacket::StatusResponse(status_response) => assert_eq!(
+            status_response.server_status.version.protocol,
+            PROTOCOL_VERSION as u32
+        ),
+        packet => panic!("Expected status response but got {:?}", packet),
+    }
+
+    send(&mut client, PingRequest::new(1577735845610));
+
+    match receive(&mut client) {
+        StatusClientBoundPacket::PingResponse(ping_response) => {
+            assert_eq!(ping_response.time, 1577735845610)
+        }
+        packet => panic!("Expected ping response but got {:?}", packet),
+    }
+
+    server.join().unwrap();
+}
+
+#[test]
+fn test_offline_login_and_play_session() {
+    let (client, server_stream) = duplex();
+    let server = thread::spawn(move || server(PacketCodec::new(server_stream)));
+    let mut client = PacketCodec::new(client);
+
+    handshake(&mut client, NextState::Login);
+    send(&mut client, LoginStart::new(String::from("Username")));
+
+    match receive(&mut client) {
+        LoginClientBoundPacket::SetCompression(set_compression) => {
+            let threshold = set_compression.threshold as usize;
+            client.set_compression_threshold(Some(threshold));
+        }
+        packet => panic!("Expected set compression but got {:?}", packet),
+    }
+
+    match receive(&mut client) {
+        LoginClientBoundPacket::LoginSuccess(login_success) => {
+            assert_eq!(login_success.username, "Username")
+        }
+        packet => panic!("Expected login success but got {:?}", packet),
+    }
+
+    match receive(&mut client) {
+        GameClientBoundPacket::JoinGame(join_game) => assert_eq!(join_game.entity_id, 1),
+        packet => panic!("Expected join game but got {:?}", packet),
+    }
+
+    match receive(&mut client) {
+        GameClientBoundPacket::ClientBoundKeepAlive(keep_alive) => {
+            send(&mut client, ServerBoundKeepAlive::new(keep_alive.id))
+        }
+        packet => panic!("Expected keep alive but got {:?}", packet),
+    }
+
+    let text = "hello ".repeat(COMPRESSION_THRESHOLD / 4);
+    send(&mut client, ServerBoundChatMessage::new(text.clone()));
+
+    match receive(&mut client) {
+        GameClientBoundPacket::ClientBoundChatMessage(chat_message) => {
+            assert_eq!(
+                chat_message.message,
+                Message::from_str(&format!("<Username> {}", text))
+            );
+            assert_eq!(chat_message.position, MessagePosition::Chat);
+        }
+        packet => panic!("Expected chat message but got {:?}", packet),
+    }
+
+    match receive(&mut client) {
+        GameClientBoundPacket::GameDisconnect(game_disconnect) => {
+            assert_eq!(game_disconnect.reason, Message::from_str("Server closed"))
+        }
+        packet => panic!("Expected disconnect but got {:?}", packet),
+    }
+
+    server.join().unwrap();
+
+    let mut buf = Vec::new();
+    client.get_mut().read_to_end(&mut buf).unwrap();
+
+    assert!(buf.is_empty());
+}